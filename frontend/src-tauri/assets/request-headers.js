@@ -0,0 +1,58 @@
+// Adds window.__CREWHUB_REQUEST_HEADERS__ to fetch and XMLHttpRequest calls
+// that go to a CrewHub backend (see src/request_headers.rs).
+(function () {
+  if (window.__CREWHUB_HEADERS_WRAPPED__) return;
+  window.__CREWHUB_HEADERS_WRAPPED__ = true;
+
+  function isBackend(url) {
+    var target;
+    try {
+      target = new URL(url, window.location.href);
+    } catch (e) {
+      return false;
+    }
+    var urls = (window.__CREWHUB_BACKENDS__ || []).map(function (b) {
+      return b.url;
+    });
+    urls.push(window.__CREWHUB_BACKEND_URL__);
+    return urls.some(function (base) {
+      try {
+        return !!base && new URL(base).origin === target.origin;
+      } catch (e) {
+        return false;
+      }
+    });
+  }
+
+  function extra() {
+    return window.__CREWHUB_REQUEST_HEADERS__ || {};
+  }
+
+  var originalFetch = window.fetch;
+  window.fetch = function (input, init) {
+    var url = typeof input === 'string' ? input : (input && input.url) || String(input);
+    if (!isBackend(url)) return originalFetch.call(this, input, init);
+    var headers = new Headers(
+      (init && init.headers) || (input instanceof Request ? input.headers : undefined)
+    );
+    var add = extra();
+    Object.keys(add).forEach(function (name) {
+      if (!headers.has(name)) headers.set(name, add[name]);
+    });
+    return originalFetch.call(this, input, Object.assign({}, init, { headers: headers }));
+  };
+
+  var originalOpen = XMLHttpRequest.prototype.open;
+  var originalSend = XMLHttpRequest.prototype.send;
+  XMLHttpRequest.prototype.open = function (method, url) {
+    this.__crewhubBackend = isBackend(url);
+    return originalOpen.apply(this, arguments);
+  };
+  XMLHttpRequest.prototype.send = function () {
+    if (this.__crewhubBackend) {
+      var add = extra();
+      for (var name in add) this.setRequestHeader(name, add[name]);
+    }
+    return originalSend.apply(this, arguments);
+  };
+})();
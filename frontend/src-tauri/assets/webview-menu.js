@@ -0,0 +1,45 @@
+// Replaces the webview's context menu with CrewHub's native one (see
+// src/webview_menu.rs). Editable fields keep the webview's own menu, which
+// carries spelling suggestions and input method entries.
+(function () {
+  if (window.__CREWHUB_WEBVIEW_MENU_WRAPPED__) return;
+  window.__CREWHUB_WEBVIEW_MENU_WRAPPED__ = true;
+
+  function enabled() {
+    var config = window.__CREWHUB_WEBVIEW_MENU__;
+    if (!config) return false;
+    var internals = window.__TAURI_INTERNALS__;
+    var label = internals && internals.metadata && internals.metadata.currentWebview
+      ? internals.metadata.currentWebview.label
+      : '';
+    var override = (config.windows || {})[label];
+    return override && typeof override.enabled === 'boolean' ? override.enabled : config.enabled;
+  }
+
+  function editable(target) {
+    return !!(target && target.closest && target.closest('input, textarea, [contenteditable=""], [contenteditable="true"]'));
+  }
+
+  window.addEventListener('contextmenu', function (event) {
+    var internals = window.__TAURI_INTERNALS__;
+    if (event.defaultPrevented || !internals || !enabled() || editable(event.target)) return;
+    event.preventDefault();
+    var target = event.target && event.target.closest ? event.target : null;
+    var link = target && target.closest('a[href]');
+    var attachment = target && target.closest('[data-crewhub-attachment]');
+    internals.invoke('show_context_menu', {
+      request: {
+        x: event.clientX,
+        y: event.clientY,
+        selection: String(window.getSelection() || ''),
+        link: link ? link.href : null,
+        attachment: attachment
+          ? {
+              url: attachment.getAttribute('data-crewhub-attachment'),
+              name: attachment.getAttribute('data-crewhub-attachment-name') || null,
+            }
+          : null,
+      },
+    });
+  });
+})();
@@ -0,0 +1,142 @@
+//! OS accessibility preferences: high contrast, reduced motion and reduced
+//! transparency.
+//!
+//! Read like the theme (see [`crate::theme`]): at startup, then by a slow
+//! poll, since no window event reports them. Changes are emitted to all
+//! windows as `accessibility-changed`, and new windows get the current values
+//! up front as `window.__CREWHUB_A11Y__` ([`init_script`]) so the first
+//! frame already renders without animations or translucent surfaces.
+//!
+//! Rust-side effects follow automatically: with reduced motion the tray stops
+//! pulsing while agents work and doesn't blink for attention.
+
+use crate::{power, theme};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// How often the OS settings are re-read.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessibilityPrefs {
+    pub high_contrast: bool,
+    pub reduce_motion: bool,
+    pub reduce_transparency: bool,
+}
+
+static PREFS: Mutex<AccessibilityPrefs> = Mutex::new(AccessibilityPrefs {
+    high_contrast: false,
+    reduce_motion: false,
+    reduce_transparency: false,
+});
+
+/// Read the initial preferences and start watching for changes.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(mut prefs) = PREFS.lock() {
+        *prefs = detect();
+    }
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(power::interval(&app, POLL_INTERVAL));
+        set(&app, detect());
+    });
+}
+
+/// The current preferences.
+pub fn current() -> AccessibilityPrefs {
+    PREFS.lock().map(|prefs| *prefs).unwrap_or_default()
+}
+
+/// Whether animations (tray pulse and blink) should be skipped.
+pub fn reduce_motion() -> bool {
+    current().reduce_motion
+}
+
+/// JS fragment exposing the preferences as `window.__CREWHUB_A11Y__`.
+pub fn init_script() -> String {
+    let prefs = serde_json::to_string(&current()).unwrap_or_else(|_| "{}".to_string());
+    format!("window.__CREWHUB_A11Y__ = {};", prefs)
+}
+
+fn set<R: Runtime>(app: &AppHandle<R>, prefs: AccessibilityPrefs) {
+    let Ok(mut current) = PREFS.lock() else {
+        return;
+    };
+    if *current == prefs {
+        return;
+    }
+    *current = prefs;
+    drop(current);
+    // Drop a pulse frame that may be showing when motion gets reduced.
+    crate::refresh_tray_icon(app);
+    let _ = app.emit("accessibility-changed", prefs);
+}
+
+#[cfg(target_os = "macos")]
+fn detect() -> AccessibilityPrefs {
+    let flag = |key: &str| {
+        theme::output("defaults", &["read", "com.apple.universalaccess", key])
+            .is_some_and(|value| value.trim() == "1")
+    };
+    AccessibilityPrefs {
+        high_contrast: flag("increaseContrast"),
+        reduce_motion: flag("reduceMotion"),
+        reduce_transparency: flag("reduceTransparency"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn detect() -> AccessibilityPrefs {
+    // Last field of the `reg query` line for `name`.
+    let value = |key: &str, name: &str| {
+        let output = theme::output("reg", &["query", key, "/v", name])?;
+        let line = output.lines().find(|line| line.contains(name))?;
+        line.split_whitespace().last().map(str::to_string)
+    };
+    // HCF_HIGHCONTRASTON is bit 0 of the flags string.
+    let high_contrast = value(r"HKCU\Control Panel\Accessibility\HighContrast", "Flags")
+        .and_then(|flags| flags.parse::<u32>().ok())
+        .is_some_and(|flags| flags & 1 != 0);
+    // "Animation effects" off in Settings clears MinAnimate.
+    let reduce_motion = value(r"HKCU\Control Panel\Desktop\WindowMetrics", "MinAnimate")
+        .is_some_and(|value| value == "0");
+    let reduce_transparency = value(
+        r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+        "EnableTransparency",
+    )
+    .is_some_and(|value| value == "0x0");
+    AccessibilityPrefs {
+        high_contrast,
+        reduce_motion,
+        reduce_transparency,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn detect() -> AccessibilityPrefs {
+    let get = |schema: &str, key: &str| {
+        theme::output("gsettings", &["get", schema, key]).map(|value| value.trim().to_string())
+    };
+    let high_contrast = get("org.gnome.desktop.a11y.interface", "high-contrast")
+        .is_some_and(|value| value == "true")
+        || get("org.gnome.desktop.interface", "gtk-theme")
+            .is_some_and(|theme| theme.contains("HighContrast"));
+    let reduce_motion = get("org.gnome.desktop.interface", "enable-animations")
+        .is_some_and(|value| value == "false");
+    AccessibilityPrefs {
+        high_contrast,
+        reduce_motion,
+        // GNOME has no such setting; high contrast themes are opaque anyway.
+        reduce_transparency: high_contrast,
+    }
+}
+
+/// Tauri command: the OS accessibility preferences.
+/// Called from the frontend via `invoke('get_accessibility_prefs')`.
+#[tauri::command]
+pub fn get_accessibility_prefs() -> AccessibilityPrefs {
+    current()
+}
@@ -0,0 +1,184 @@
+//! Several accounts signed in at once.
+//!
+//! The frontend registers every account it holds a session for
+//! (`add_account`) — on different backends, or several on one backend. One
+//! account is active: windows get the account list and the active account in
+//! their bootstrap config (`window.__CREWHUB_ACCOUNTS__`,
+//! `window.__CREWHUB_ACCOUNT__`) and follow switches via the
+//! `account-switched` event. Unread counts are reported per account
+//! (`update_tray_badge` with `accountId`); the tray badge shows the sum, and
+//! with more than one account the tray menu gets an account switcher that
+//! lists each account's count. Sessions and tokens stay in the frontend; only
+//! the account metadata is kept here, in `accounts.json`.
+
+use crate::{federation, i18n, store};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Persisted accounts (app data dir).
+const ACCOUNTS_FILE: &str = "accounts.json";
+
+/// Prefix of tray menu item ids that switch accounts.
+pub const MENU_ID_PREFIX: &str = "account:";
+
+static ACCOUNTS: Mutex<Option<Accounts>> = Mutex::new(None);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    pub id: String,
+    /// Backend the account belongs to (see [`crate::federation`]).
+    #[serde(default = "default_backend")]
+    pub backend_id: String,
+    /// Display name ("Work", or the user's name).
+    pub name: String,
+    /// User name or email on the backend.
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+fn default_backend() -> String {
+    federation::DEFAULT_BACKEND_ID.to_string()
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Accounts {
+    pub accounts: Vec<Account>,
+    /// Id of the active account.
+    pub active: Option<String>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let loaded: Accounts = store::load_json(app, ACCOUNTS_FILE);
+    if let Ok(mut accounts) = ACCOUNTS.lock() {
+        *accounts = Some(loaded);
+    }
+}
+
+/// All accounts and the active one.
+pub fn current() -> Accounts {
+    ACCOUNTS
+        .lock()
+        .ok()
+        .and_then(|accounts| accounts.clone())
+        .unwrap_or_default()
+}
+
+fn active_account(accounts: &Accounts) -> Option<&Account> {
+    accounts
+        .active
+        .as_deref()
+        .and_then(|id| accounts.accounts.iter().find(|account| account.id == id))
+}
+
+/// Init script part: the accounts and the active account.
+pub fn init_script() -> String {
+    let accounts = current();
+    let active =
+        serde_json::to_string(&active_account(&accounts)).unwrap_or_else(|_| "null".to_string());
+    let all = serde_json::to_string(&accounts).unwrap_or_else(|_| "null".to_string());
+    format!(
+        "window.__CREWHUB_ACCOUNTS__ = {}; window.__CREWHUB_ACCOUNT__ = {};",
+        all, active
+    )
+}
+
+/// Tray menu label of an account: its name, with the unread count if any.
+pub fn menu_label(account: &Account, unread: u32) -> String {
+    match unread {
+        0 => account.name.clone(),
+        n => i18n::tf(
+            "tray.backendUnread",
+            &[("name", &account.name), ("count", &n.to_string())],
+        ),
+    }
+}
+
+fn save_and_apply<R: Runtime>(app: &AppHandle<R>, accounts: Accounts) -> Result<Accounts, String> {
+    store::save_json(app, ACCOUNTS_FILE, &accounts)?;
+    *ACCOUNTS.lock().map_err(|e| e.to_string())? = Some(accounts.clone());
+    let script = init_script();
+    for window in app.webview_windows().values() {
+        let _ = window.eval(&script);
+    }
+    crate::refresh_tray_menu(app);
+    let _ = app.emit("accounts-changed", &accounts);
+    Ok(accounts)
+}
+
+/// Make `id` the active account.
+pub fn switch<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<(), String> {
+    let mut accounts = current();
+    let account = accounts
+        .accounts
+        .iter()
+        .find(|account| account.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown account: {}", id))?;
+    if accounts.active.as_deref() == Some(id) {
+        return Ok(());
+    }
+    accounts.active = Some(account.id.clone());
+    save_and_apply(app, accounts)?;
+    let _ = app.emit("account-switched", account);
+    Ok(())
+}
+
+/// Tauri command: all accounts and the active one.
+/// Called from the frontend via `invoke('list_accounts')`.
+#[tauri::command]
+pub fn list_accounts() -> Accounts {
+    current()
+}
+
+/// Tauri command: add or update an account (matched by `id`). The first
+/// account becomes the active one.
+/// Called from the frontend after signing in via `invoke('add_account', { account })`.
+#[tauri::command]
+pub fn add_account(account: Account, app: AppHandle) -> Result<Accounts, String> {
+    if account.id.is_empty() || account.name.trim().is_empty() {
+        return Err("Account needs an id and a name".to_string());
+    }
+    let mut accounts = current();
+    match accounts.accounts.iter_mut().find(|a| a.id == account.id) {
+        Some(existing) => *existing = account,
+        None => {
+            if accounts.active.is_none() {
+                accounts.active = Some(account.id.clone());
+            }
+            accounts.accounts.push(account);
+        }
+    }
+    save_and_apply(&app, accounts)
+}
+
+/// Tauri command: forget an account (after signing out) and its unread counts.
+/// If it was active, the first remaining account becomes active.
+/// Called from the frontend via `invoke('remove_account', { id })`.
+#[tauri::command]
+pub fn remove_account(id: String, app: AppHandle) -> Result<Accounts, String> {
+    let mut accounts = current();
+    let Some(index) = accounts.accounts.iter().position(|a| a.id == id) else {
+        return Ok(accounts);
+    };
+    let removed = accounts.accounts.remove(index);
+    let was_active = accounts.active.as_deref() == Some(id.as_str());
+    if was_active {
+        accounts.active = accounts.accounts.first().map(|a| a.id.clone());
+    }
+    crate::clear_account_badge(&app, &removed.backend_id, &removed.id);
+    let accounts = save_and_apply(&app, accounts)?;
+    if was_active {
+        let _ = app.emit("account-switched", active_account(&accounts).cloned());
+    }
+    Ok(accounts)
+}
+
+/// Tauri command: switch the active account.
+/// Called from the frontend via `invoke('switch_account', { id })`.
+#[tauri::command]
+pub fn switch_account(id: String, app: AppHandle) -> Result<(), String> {
+    switch(&app, &id)
+}
@@ -0,0 +1,76 @@
+//! macOS activation policy: a normal app with a Dock icon and a Cmd+Tab entry
+//! (`Regular`), or a menu-bar-only app (`Accessory`).
+//!
+//! The choice is persisted and applied at startup, and can be switched at
+//! runtime from settings. Other platforms keep the setting but ignore it.
+//! Without a tray icon ([`crate::tray_support::chosen`]) the Dock is the only
+//! way back to CrewHub, so `Regular` is used regardless.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "activation-policy.json";
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Mode {
+    /// Dock icon and Cmd+Tab, like a normal app.
+    #[default]
+    Regular,
+    /// Menu bar only: no Dock icon, not in Cmd+Tab.
+    Accessory,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ActivationConfig {
+    mode: Mode,
+}
+
+/// Apply the saved policy (called from setup and when the tray mode changes).
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: ActivationConfig = store::load_json(app, CONFIG_FILE);
+    apply(app, effective(config.mode));
+}
+
+/// `Accessory` needs the tray icon.
+fn effective(mode: Mode) -> Mode {
+    if crate::tray_support::chosen() {
+        Mode::Regular
+    } else {
+        mode
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn apply<R: Runtime>(app: &AppHandle<R>, mode: Mode) {
+    let policy = match mode {
+        Mode::Regular => tauri::ActivationPolicy::Regular,
+        Mode::Accessory => tauri::ActivationPolicy::Accessory,
+    };
+    if let Err(e) = app.set_activation_policy(policy) {
+        eprintln!("[CrewHub] Failed to set activation policy: {}", e);
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn apply<R: Runtime>(_app: &AppHandle<R>, _mode: Mode) {}
+
+/// Tauri command: the saved activation policy.
+/// Called from the settings window via `invoke('get_activation_policy')`.
+#[tauri::command]
+pub fn get_activation_policy(app: AppHandle) -> Mode {
+    store::load_json::<_, ActivationConfig>(&app, CONFIG_FILE).mode
+}
+
+/// Tauri command: switch the activation policy now and remember it.
+/// Called from the settings window via `invoke('set_activation_policy', { mode })`.
+#[tauri::command]
+pub fn set_activation_policy(mode: Mode, app: AppHandle) -> Result<Mode, String> {
+    store::save_json(&app, CONFIG_FILE, &ActivationConfig { mode })?;
+    apply(&app, effective(mode));
+    let _ = app.emit("activation-policy-changed", mode);
+    Ok(mode)
+}
@@ -0,0 +1,97 @@
+//! Pause and resume every agent at once.
+//!
+//! The tray offers "Pause all agents" / "Resume agents"; both go through the
+//! control API of every enabled backend (`POST /api/control/pause` and
+//! `/api/control/resume`). The paused flag is read from the primary backend
+//! at startup (`GET /api/control/state`) so the menu item matches after a
+//! restart, and it is only flipped once a backend has confirmed the change.
+
+use crate::{backend, federation};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Tray menu item id of the pause / resume toggle.
+pub const MENU_ID: &str = "pause-agents";
+
+/// Whether the agents are paused, as last confirmed by a backend.
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Read the paused state from the primary backend in the background.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let state = backend::get_json_from(&crate::backend_url(), "/api/control/state");
+        if let Ok(paused) = state.map(|state| state["paused"].as_bool().unwrap_or(false)) {
+            if PAUSED.swap(paused, Ordering::Relaxed) != paused {
+                crate::refresh_tray_menu(&app);
+            }
+        }
+    });
+}
+
+/// Whether the agents are paused (for the tray menu label).
+pub fn paused() -> bool {
+    PAUSED.load(Ordering::Relaxed)
+}
+
+/// Pause or resume the agents on every enabled backend (blocking). Succeeds
+/// if at least one backend accepted; the others are reported in the error log.
+pub fn set_paused<R: Runtime>(app: &AppHandle<R>, paused: bool) -> Result<bool, String> {
+    let path = if paused {
+        "/api/control/pause"
+    } else {
+        "/api/control/resume"
+    };
+    let mut errors = Vec::new();
+    let mut accepted = 0;
+    for profile in federation::enabled(app) {
+        match backend::post_json_to(&profile.url, path, &Value::Null) {
+            Ok(_) => accepted += 1,
+            Err(e) => errors.push(format!("{}: {}", profile.name, e)),
+        }
+    }
+    for error in &errors {
+        eprintln!(
+            "[CrewHub] Failed to {} agents on {}",
+            if paused { "pause" } else { "resume" },
+            error
+        );
+    }
+    if accepted == 0 {
+        return Err(errors.join("; "));
+    }
+    PAUSED.store(paused, Ordering::Relaxed);
+    crate::refresh_tray_menu(app);
+    let _ = app.emit(
+        "agents-paused-changed",
+        serde_json::json!({ "paused": paused }),
+    );
+    Ok(paused)
+}
+
+/// Flip the paused state in the background (tray item).
+pub fn toggle<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = set_paused(&app, !paused()) {
+            eprintln!("[CrewHub] {}", e);
+        }
+    });
+}
+
+/// Tauri command: whether all agents are paused.
+/// Called from the frontend via `invoke('get_agents_paused')`.
+#[tauri::command]
+pub fn get_agents_paused() -> bool {
+    paused()
+}
+
+/// Tauri command: pause or resume all agents on every enabled backend.
+/// Called from the frontend via `invoke('set_agents_paused', { paused })`.
+#[tauri::command]
+pub async fn set_agents_paused(paused: bool, app: AppHandle) -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(move || set_paused(&app, paused))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,340 @@
+//! Screenshot annotation window.
+//!
+//! After `capture_screenshot` the frontend can hand the PNG to
+//! `annotate_screenshot`, which opens a lightweight `annotate` window for
+//! drawing arrows, boxes and redactions over it. The window only edits a list
+//! of shapes; `flatten_annotation` renders them into the pixels here, writes a
+//! fresh PNG (no layers, no text chunks, nothing from the original file) and
+//! stages it as an attachment for the window that asked, exactly like a
+//! dropped file. Redactions are painted as opaque fills last, so nothing under
+//! them survives in the file that is sent, and the unredacted capture is
+//! deleted once the flattened copy exists.
+
+use crate::attachments;
+use crate::i18n;
+use image::{Rgba, RgbaImage};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, Emitter, Manager, Runtime, WebviewWindow, WebviewWindowBuilder, WindowEvent,
+};
+
+/// Label of the annotation window (one at a time).
+pub const LABEL: &str = "annotate";
+
+/// Fill used for redactions.
+const REDACTION: Rgba<u8> = Rgba([0, 0, 0, 255]);
+/// Stroke colour when a shape doesn't name one.
+const DEFAULT_COLOR: Rgba<u8> = Rgba([230, 57, 70, 255]);
+
+/// Screenshot being annotated and the window its result is attached to.
+struct Pending {
+    source: PathBuf,
+    origin: String,
+}
+
+static PENDING: Mutex<Option<Pending>> = Mutex::new(None);
+
+/// One annotation, in image pixels.
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Shape {
+    #[serde(rename_all = "camelCase")]
+    Arrow {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        color: Option<String>,
+        width: Option<f32>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Box {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        color: Option<String>,
+        stroke: Option<f32>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Redact {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+}
+
+/// Parse `#rrggbb` (or `#rgb`), falling back to [`DEFAULT_COLOR`].
+fn parse_color(color: Option<&str>) -> Rgba<u8> {
+    let Some(hex) = color.and_then(|c| c.strip_prefix('#')) else {
+        return DEFAULT_COLOR;
+    };
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    let rgb = match hex.len() {
+        6 => (
+            channel(&hex[0..2]),
+            channel(&hex[2..4]),
+            channel(&hex[4..6]),
+        ),
+        3 => (
+            channel(&hex[0..1]).map(|v| v * 17),
+            channel(&hex[1..2]).map(|v| v * 17),
+            channel(&hex[2..3]).map(|v| v * 17),
+        ),
+        _ => (None, None, None),
+    };
+    match rgb {
+        (Some(r), Some(g), Some(b)) => Rgba([r, g, b, 255]),
+        _ => DEFAULT_COLOR,
+    }
+}
+
+/// Fill the axis-aligned rectangle, clipped to the image.
+fn fill_rect(image: &mut RgbaImage, x: f32, y: f32, width: f32, height: f32, color: Rgba<u8>) {
+    let (x0, x1) = (x.min(x + width), x.max(x + width));
+    let (y0, y1) = (y.min(y + height), y.max(y + height));
+    let x0 = x0.floor().max(0.0) as u32;
+    let y0 = y0.floor().max(0.0) as u32;
+    let x1 = (x1.ceil().max(0.0) as u32).min(image.width());
+    let y1 = (y1.ceil().max(0.0) as u32).min(image.height());
+    for py in y0..y1 {
+        for px in x0..x1 {
+            image.put_pixel(px, py, color);
+        }
+    }
+}
+
+/// Draw a segment `width` pixels thick with round ends.
+fn draw_line(image: &mut RgbaImage, from: (f32, f32), to: (f32, f32), width: f32, color: Rgba<u8>) {
+    let radius = (width / 2.0).max(0.5);
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length_sq = dx * dx + dy * dy;
+    let x0 = (from.0.min(to.0) - radius).floor().max(0.0) as u32;
+    let y0 = (from.1.min(to.1) - radius).floor().max(0.0) as u32;
+    let x1 = ((from.0.max(to.0) + radius).ceil().max(0.0) as u32).min(image.width());
+    let y1 = ((from.1.max(to.1) + radius).ceil().max(0.0) as u32).min(image.height());
+    for py in y0..y1 {
+        for px in x0..x1 {
+            let (cx, cy) = (px as f32 + 0.5, py as f32 + 0.5);
+            let t = if length_sq > 0.0 {
+                (((cx - from.0) * dx + (cy - from.1) * dy) / length_sq).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let (nx, ny) = (from.0 + t * dx - cx, from.1 + t * dy - cy);
+            if nx * nx + ny * ny <= radius * radius {
+                image.put_pixel(px, py, color);
+            }
+        }
+    }
+}
+
+fn draw_arrow(
+    image: &mut RgbaImage,
+    from: (f32, f32),
+    to: (f32, f32),
+    width: f32,
+    color: Rgba<u8>,
+) {
+    draw_line(image, from, to, width, color);
+    let angle = (to.1 - from.1).atan2(to.0 - from.0);
+    let head = (width * 4.0).max(12.0);
+    for side in [-0.45_f32, 0.45] {
+        let back = angle + std::f32::consts::PI + side;
+        let end = (to.0 + head * back.cos(), to.1 + head * back.sin());
+        draw_line(image, to, end, width, color);
+    }
+}
+
+/// Render the shapes into a copy of the screenshot. Redactions go last.
+pub fn render(source: &Path, shapes: &[Shape]) -> Result<RgbaImage, String> {
+    let mut image = image::open(source)
+        .map_err(|e| format!("Failed to read screenshot: {}", e))?
+        .to_rgba8();
+    for shape in shapes {
+        match shape {
+            Shape::Arrow {
+                x1,
+                y1,
+                x2,
+                y2,
+                color,
+                width,
+            } => draw_arrow(
+                &mut image,
+                (*x1, *y1),
+                (*x2, *y2),
+                width.unwrap_or(4.0),
+                parse_color(color.as_deref()),
+            ),
+            Shape::Box {
+                x,
+                y,
+                width,
+                height,
+                color,
+                stroke,
+            } => {
+                let color = parse_color(color.as_deref());
+                let stroke = stroke.unwrap_or(4.0);
+                let (right, bottom) = (x + width, y + height);
+                for (from, to) in [
+                    ((*x, *y), (right, *y)),
+                    ((right, *y), (right, bottom)),
+                    ((right, bottom), (*x, bottom)),
+                    ((*x, bottom), (*x, *y)),
+                ] {
+                    draw_line(&mut image, from, to, stroke, color);
+                }
+            }
+            Shape::Redact { .. } => {}
+        }
+    }
+    for shape in shapes {
+        if let Shape::Redact {
+            x,
+            y,
+            width,
+            height,
+        } = shape
+        {
+            fill_rect(&mut image, *x, *y, *width, *height, REDACTION);
+        }
+    }
+    Ok(image)
+}
+
+/// Only screenshots taken by CrewHub can be annotated (and later deleted).
+fn screenshot_source<R: Runtime>(app: &AppHandle<R>, path: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("screenshots")
+        .canonicalize()
+        .map_err(|e| e.to_string())?;
+    let path = Path::new(path).canonicalize().map_err(|e| e.to_string())?;
+    if !path.starts_with(&dir) {
+        return Err("Only screenshots captured by CrewHub can be annotated".to_string());
+    }
+    Ok(path)
+}
+
+fn annotate_init_script() -> String {
+    format!(
+        "window.__TAURI_VIEW__ = 'annotate'; {}",
+        crate::base_init(LABEL)
+    )
+}
+
+/// Open the annotation window (960×680) for a screenshot. If it is already
+/// open, it is retargeted via an `annotate-image` event.
+pub fn open<R: Runtime>(app: &AppHandle<R>, source: PathBuf, origin: &str) -> Result<(), String> {
+    *PENDING.lock().map_err(|e| e.to_string())? = Some(Pending {
+        source,
+        origin: origin.to_string(),
+    });
+    if let Some(window) = app.get_webview_window(LABEL) {
+        let _ = window.emit("annotate-image", ());
+        crate::show_and_focus(&window);
+        return Ok(());
+    }
+    let window = WebviewWindowBuilder::new(app, LABEL, crate::view_url("view=annotate"))
+        .title(i18n::t("annotate.title"))
+        .inner_size(960.0, 680.0)
+        .min_inner_size(480.0, 360.0)
+        .resizable(true)
+        .user_agent(&crate::request_headers::user_agent())
+        .on_new_window(crate::external_links::new_window_handler(app))
+        .initialization_script(annotate_init_script())
+        .build()
+        .map_err(|e| format!("Failed to create annotation window: {}", e))?;
+    window.on_window_event(|event| {
+        if let WindowEvent::Destroyed = event {
+            if let Ok(mut pending) = PENDING.lock() {
+                *pending = None;
+            }
+        }
+    });
+    crate::show_and_focus(&window);
+    Ok(())
+}
+
+/// Tauri command: open the annotation window for a screenshot from `capture_screenshot`.
+/// The result is attached to the calling window.
+/// Called from the frontend via `invoke('annotate_screenshot', { path })`.
+#[tauri::command]
+pub fn annotate_screenshot(
+    app: AppHandle,
+    path: String,
+    window: WebviewWindow,
+) -> Result<(), String> {
+    let source = screenshot_source(&app, &path)?;
+    open(&app, source, window.label())
+}
+
+/// Tauri command: the screenshot being annotated, as a PNG data URL.
+/// Called from the annotation window via `invoke('get_annotation_image')`.
+#[tauri::command]
+pub fn get_annotation_image() -> Result<String, String> {
+    use base64::Engine;
+    let source = PENDING
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_ref()
+        .map(|pending| pending.source.clone())
+        .ok_or("No screenshot to annotate")?;
+    let bytes = std::fs::read(source).map_err(|e| e.to_string())?;
+    Ok(format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// Tauri command: flatten the shapes into a new PNG, attach it to the window
+/// that opened the annotation, delete the original capture and close the
+/// annotation window. Returns the staged attachment.
+/// Called from the annotation window via `invoke('flatten_annotation', { shapes })`.
+#[tauri::command]
+pub async fn flatten_annotation(
+    app: AppHandle,
+    shapes: Vec<Shape>,
+) -> Result<attachments::StagedFile, String> {
+    let Pending { source, origin } = PENDING
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .ok_or("No screenshot to annotate")?;
+    let handle = app.clone();
+    let staged = tauri::async_runtime::spawn_blocking(move || {
+        let image = render(&source, &shapes)?;
+        let stem = source
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let target = source.with_file_name(format!("{}-annotated.png", stem));
+        image
+            .save_with_format(&target, image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to write annotated screenshot: {}", e))?;
+        let _ = std::fs::remove_file(&source);
+        let staged = attachments::stage(&handle, std::slice::from_ref(&target));
+        let _ = std::fs::remove_file(&target);
+        staged
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    let file = staged
+        .files
+        .first()
+        .cloned()
+        .ok_or("Failed to stage annotated screenshot")?;
+    attachments::announce(&app, &origin, staged);
+    if let Some(window) = app.get_webview_window(LABEL) {
+        let _ = window.destroy();
+    }
+    Ok(file)
+}
@@ -0,0 +1,239 @@
+//! Screen reader announcements for background events.
+//!
+//! While a CrewHub window has focus, the frontend's live regions speak for
+//! it. Events that happen in the background ("Research Crew failed", a new
+//! approval) would otherwise only reach screen reader users as a visual
+//! notification, so they are also handed to the platform's announcement
+//! channel:
+//! - macOS: `NSAccessibilityAnnouncementRequestedNotification` (VoiceOver);
+//! - Windows: a UI Automation notification event raised on the chat window
+//!   (Narrator, NVDA, JAWS);
+//! - Linux: Speech Dispatcher (`spd-say`), which Orca speaks through, only
+//!   while GNOME's screen reader setting is on so nothing is read aloud to
+//!   users without one.
+//!
+//! Every notification shown by [`crate::notifications`] is announced at the
+//! configured [`Verbosity`], as are increases in pending approvals. Do Not
+//! Disturb silences announcements along with the notifications.
+
+use crate::notifications::{Event, Notice};
+use crate::{i18n, store, BadgeCounts};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "announcements.json";
+
+/// Which background events are announced; each level includes the ones before it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Verbosity {
+    Off,
+    /// Critical notifications only (failures, blocked agents by default).
+    Critical,
+    /// Also crew, schedule and quick action updates and pending approvals.
+    Important,
+    /// Also chat messages.
+    All,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AnnouncementConfig {
+    pub verbosity: Verbosity,
+    /// Also announce while a CrewHub window has focus.
+    pub when_focused: bool,
+}
+
+impl Default for AnnouncementConfig {
+    fn default() -> Self {
+        Self {
+            verbosity: Verbosity::Important,
+            when_focused: false,
+        }
+    }
+}
+
+fn config<R: Runtime>(app: &AppHandle<R>) -> AnnouncementConfig {
+    store::load_json(app, CONFIG_FILE)
+}
+
+/// Announce a notification that is being shown.
+pub fn notice<R: Runtime>(app: &AppHandle<R>, notice: &Notice) {
+    let level = if notice.critical {
+        Verbosity::Critical
+    } else if notice.event == Event::Message {
+        Verbosity::All
+    } else {
+        Verbosity::Important
+    };
+    let text = if notice.body.is_empty() {
+        notice.title.clone()
+    } else {
+        format!("{}. {}", notice.title.trim_end_matches('.'), notice.body)
+    };
+    announce(app, level, &text, notice.critical);
+}
+
+/// Announce newly pending approvals (called when badge counts change).
+pub fn badges_changed<R: Runtime>(app: &AppHandle<R>, before: BadgeCounts, after: BadgeCounts) {
+    if after.approvals > before.approvals && !crate::dnd::quiet(app) {
+        let text = i18n::tf(
+            "announce.approvalsPending",
+            &[("count", &after.approvals.to_string())],
+        );
+        announce(app, Verbosity::Important, &text, false);
+    }
+}
+
+fn announce<R: Runtime>(app: &AppHandle<R>, level: Verbosity, text: &str, urgent: bool) {
+    let config = config(app);
+    if config.verbosity < level || config.verbosity == Verbosity::Off {
+        return;
+    }
+    let focused = app
+        .webview_windows()
+        .values()
+        .any(|window| window.is_focused().unwrap_or(false));
+    if focused && !config.when_focused {
+        return;
+    }
+    platform::announce(app, text, urgent);
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2::runtime::AnyObject;
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::{
+        NSAccessibilityAnnouncementKey, NSAccessibilityAnnouncementRequestedNotification,
+        NSAccessibilityPostNotificationWithUserInfo, NSAccessibilityPriorityKey,
+        NSAccessibilityPriorityLevel, NSApplication,
+    };
+    use objc2_foundation::{NSDictionary, NSNumber, NSString};
+    use tauri::{AppHandle, Runtime};
+
+    pub fn announce<R: Runtime>(app: &AppHandle<R>, text: &str, urgent: bool) {
+        let text = text.to_string();
+        let _ = app.run_on_main_thread(move || {
+            let Some(mtm) = MainThreadMarker::new() else {
+                return;
+            };
+            let priority = if urgent {
+                NSAccessibilityPriorityLevel::High
+            } else {
+                NSAccessibilityPriorityLevel::Medium
+            };
+            let message = NSString::from_str(&text);
+            let priority = NSNumber::new_isize(priority.0);
+            let values: [&AnyObject; 2] = [&message, &priority];
+            unsafe {
+                let info = NSDictionary::from_slices(
+                    &[NSAccessibilityAnnouncementKey, NSAccessibilityPriorityKey],
+                    &values,
+                );
+                let app = NSApplication::sharedApplication(mtm);
+                NSAccessibilityPostNotificationWithUserInfo(
+                    &app,
+                    NSAccessibilityAnnouncementRequestedNotification,
+                    Some(&info),
+                );
+            }
+        });
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use tauri::{AppHandle, Manager, Runtime};
+    use windows::core::BSTR;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Accessibility::{
+        NotificationKind_Other, NotificationProcessing_ImportantMostRecent,
+        NotificationProcessing_MostRecent, UiaHostProviderFromHwnd, UiaRaiseNotificationEvent,
+    };
+
+    pub fn announce<R: Runtime>(app: &AppHandle<R>, text: &str, urgent: bool) {
+        let Some(window) = app.get_webview_window(crate::CHAT_WINDOW_LABEL) else {
+            return;
+        };
+        let Ok(hwnd) = window.hwnd() else {
+            return;
+        };
+        let processing = if urgent {
+            NotificationProcessing_ImportantMostRecent
+        } else {
+            NotificationProcessing_MostRecent
+        };
+        let result = unsafe {
+            UiaHostProviderFromHwnd(HWND(hwnd.0)).and_then(|provider| {
+                UiaRaiseNotificationEvent(
+                    &provider,
+                    NotificationKind_Other,
+                    processing,
+                    &BSTR::from(text),
+                    &BSTR::from("CrewHub.Background"),
+                )
+            })
+        };
+        if let Err(e) = result {
+            eprintln!("[CrewHub] Screen reader announcement failed: {}", e);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use std::process::Command;
+    use tauri::{AppHandle, Runtime};
+
+    /// Whether the desktop's screen reader is turned on.
+    fn screen_reader_enabled() -> bool {
+        Command::new("gsettings")
+            .args([
+                "get",
+                "org.gnome.desktop.a11y.applications",
+                "screen-reader-enabled",
+            ])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "true")
+    }
+
+    pub fn announce<R: Runtime>(_app: &AppHandle<R>, text: &str, urgent: bool) {
+        if !screen_reader_enabled() {
+            return;
+        }
+        let priority = if urgent { "important" } else { "message" };
+        let spawned = Command::new("spd-say")
+            .args([
+                "--application-name",
+                "CrewHub",
+                "--priority",
+                priority,
+                "--",
+            ])
+            .arg(text)
+            .spawn();
+        if let Err(e) = spawned {
+            eprintln!("[CrewHub] Screen reader announcement failed: {}", e);
+        }
+    }
+}
+
+/// Tauri command: which background events are announced to screen readers.
+/// Called from the settings window via `invoke('get_announcement_config')`.
+#[tauri::command]
+pub fn get_announcement_config(app: AppHandle) -> AnnouncementConfig {
+    config(&app)
+}
+
+/// Tauri command: set the announcement verbosity.
+/// Called from the settings window via `invoke('set_announcement_config', { config })`.
+#[tauri::command]
+pub fn set_announcement_config(
+    config: AnnouncementConfig,
+    app: AppHandle,
+) -> Result<AnnouncementConfig, String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    Ok(config)
+}
@@ -0,0 +1,114 @@
+//! macOS application menu bar.
+//!
+//! Shown while a CrewHub window is focused: the standard App, Edit, View,
+//! Window and Help menus (so Cmd+C, Cmd+W, Cmd+, … behave as in any Mac app)
+//! plus entries for CrewHub's windows. The tray's menu handler receives every
+//! menu event, so CrewHub items reuse the tray item ids. Rebuilt when the UI
+//! language changes.
+
+use crate::i18n;
+use tauri::menu::{AboutMetadata, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Runtime};
+
+/// Menu id of the Help menu's "Getting Started" (reopens onboarding).
+pub const GETTING_STARTED_ID: &str = "getting-started";
+
+fn item<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    key: &str,
+    accelerator: Option<&str>,
+) -> tauri::Result<MenuItem<R>> {
+    MenuItem::with_id(app, id, i18n::t(key), true, accelerator)
+}
+
+fn build<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
+    let about = AboutMetadata {
+        name: Some("CrewHub".to_string()),
+        version: Some(app.package_info().version.to_string()),
+        ..Default::default()
+    };
+    let app_menu = Submenu::with_items(
+        app,
+        "CrewHub",
+        true,
+        &[
+            &PredefinedMenuItem::about(app, None, Some(about))?,
+            &PredefinedMenuItem::separator(app)?,
+            &item(app, "settings", "menu.settings", Some("CmdOrCtrl+,"))?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::services(app, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::hide(app, None)?,
+            &PredefinedMenuItem::hide_others(app, None)?,
+            &PredefinedMenuItem::show_all(app, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::quit(app, None)?,
+        ],
+    )?;
+    let edit_menu = Submenu::with_items(
+        app,
+        i18n::t("menu.edit"),
+        true,
+        &[
+            &PredefinedMenuItem::undo(app, None)?,
+            &PredefinedMenuItem::redo(app, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::cut(app, None)?,
+            &PredefinedMenuItem::copy(app, None)?,
+            &PredefinedMenuItem::paste(app, None)?,
+            &PredefinedMenuItem::select_all(app, None)?,
+        ],
+    )?;
+    let view_menu = Submenu::with_items(
+        app,
+        i18n::t("menu.view"),
+        true,
+        &[&PredefinedMenuItem::fullscreen(app, None)?],
+    )?;
+    let window_menu = Submenu::with_items(
+        app,
+        i18n::t("menu.window"),
+        true,
+        &[
+            &PredefinedMenuItem::minimize(app, None)?,
+            &PredefinedMenuItem::maximize(app, None)?,
+            &PredefinedMenuItem::close_window(app, None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &item(app, "chat", "menu.chat", Some("CmdOrCtrl+1"))?,
+            &item(app, "world", "menu.world", Some("CmdOrCtrl+2"))?,
+            &item(app, "zen", "menu.zen", Some("CmdOrCtrl+3"))?,
+            &item(app, "hud", "menu.hud", None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::bring_all_to_front(app, None)?,
+        ],
+    )?;
+    let help_menu = Submenu::with_items(
+        app,
+        i18n::t("menu.help"),
+        true,
+        &[
+            &item(app, GETTING_STARTED_ID, "menu.gettingStarted", None)?,
+            &item(app, crate::whats_new::MENU_ID, "menu.whatsNew", None)?,
+        ],
+    )?;
+    let menu = Menu::with_items(
+        app,
+        &[&app_menu, &edit_menu, &view_menu, &window_menu, &help_menu],
+    )?;
+    window_menu.set_as_windows_menu_for_nsapp()?;
+    help_menu.set_as_help_menu_for_nsapp()?;
+    Ok(menu)
+}
+
+/// Build and install the menu bar.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    refresh(app);
+}
+
+/// Rebuild the menu bar (e.g. after a language change).
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    if let Err(e) = build(app).and_then(|menu| app.set_menu(menu)) {
+        eprintln!("[CrewHub] Failed to build the menu bar: {}", e);
+    }
+}
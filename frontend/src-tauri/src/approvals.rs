@@ -0,0 +1,270 @@
+//! Native approval dialogs for risky agent operations.
+//!
+//! When the backend wants to do something dangerous (delete files, push to
+//! main, exceed a cost cap) it emits `approval-requested` on its event stream.
+//! The bridge hands the request to this module, which shows a native modal
+//! dialog — even when no CrewHub window is open — and posts the verdict back
+//! to `/api/approvals/<id>/verdict`. "Always allow" answers are remembered per
+//! operation kind; rules can be reviewed and revoked from settings. Local
+//! operations (MCP tool calls, see [`crate::mcp_server`]) go through the same
+//! dialog via [`confirm`].
+//!
+//! Rules are keyed by where the request came from (see [`rule_key`]): each
+//! backend has its own, and local rules live in a namespace no backend can
+//! reach, so a backend can't talk the user into a rule that approves local
+//! tools or another backend's operations.
+
+use crate::federation::{self, BackendProfile};
+use crate::{backend, bridge, i18n, store};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime, State};
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+
+/// Backend event that carries an approval request.
+pub const APPROVAL_REQUESTED_EVENT: &str = "approval-requested";
+
+/// Remembered verdicts per rule key (app data dir).
+const RULES_FILE: &str = "approval-rules.json";
+
+/// Rule key prefix of local operations; backend ids are slugs without `:`.
+const LOCAL_PREFIX: &str = "local:";
+
+/// Kind prefix of local MCP tool calls; backends may not use it.
+const MCP_PREFIX: &str = "mcp:";
+
+// Dialog buttons (i18n keys; the label is looked up when the dialog is shown).
+const ALLOW_ONCE: &str = "dialog.allowOnce";
+const ALWAYS_ALLOW: &str = "dialog.alwaysAllow";
+const DENY: &str = "dialog.deny";
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Verdict {
+    Allow,
+    Deny,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRequest {
+    pub id: String,
+    /// Operation kind, e.g. `delete_files`, `git_push_main`, `cost_cap`.
+    pub kind: String,
+    pub title: String,
+    #[serde(default)]
+    pub detail: String,
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Backend the request came from (filled in by the bridge).
+    #[serde(default)]
+    pub backend_id: String,
+}
+
+/// App state: remembered rules, plus a lock so only one dialog is shown at a time.
+pub struct ApprovalState {
+    rules: Mutex<BTreeMap<String, Verdict>>,
+    dialog: Mutex<()>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let mut rules: BTreeMap<String, Verdict> = store::load_json(app, RULES_FILE);
+    // Rules from before namespacing can't tell which backend they were for.
+    let before = rules.len();
+    rules.retain(|key, _| key.starts_with(LOCAL_PREFIX) || key.contains('/'));
+    if rules.len() != before {
+        eprintln!(
+            "[CrewHub] Dropped {} approval rule(s) without a backend",
+            before - rules.len()
+        );
+        if let Err(e) = store::save_json(app, RULES_FILE, &rules) {
+            eprintln!("[CrewHub] {}", e);
+        }
+    }
+    app.manage(ApprovalState {
+        rules: Mutex::new(rules),
+        dialog: Mutex::new(()),
+    });
+}
+
+/// Handle an `approval-requested` event from the backend bridge.
+/// The dialog blocks, so it runs on its own thread.
+pub fn handle_request<R: Runtime>(app: &AppHandle<R>, backend: &BackendProfile, payload: Value) {
+    let mut request: ApprovalRequest = match serde_json::from_value(payload) {
+        Ok(request) => request,
+        Err(e) => {
+            eprintln!("[CrewHub] Ignoring malformed approval request: {}", e);
+            return;
+        }
+    };
+    request.backend_id = backend.id.clone();
+    let app = app.clone();
+    let backend_url = backend.url.clone();
+    std::thread::spawn(move || resolve(&app, &backend_url, request));
+}
+
+/// Rule key of a request: `local:<kind>` for local operations,
+/// `<backend id>/<kind>` for a backend's.
+fn rule_key(request: &ApprovalRequest) -> String {
+    if request.backend_id.is_empty() {
+        format!("{}{}", LOCAL_PREFIX, request.kind)
+    } else {
+        format!("{}/{}", request.backend_id, request.kind)
+    }
+}
+
+/// The remembered verdict for the request's rule key, or the user's answer
+/// (and whether it came from a rule). Blocks while the dialog is open.
+fn decide<R: Runtime>(app: &AppHandle<R>, request: &ApprovalRequest) -> (Verdict, bool) {
+    let Some(state) = app.try_state::<ApprovalState>() else {
+        return (Verdict::Deny, false);
+    };
+    let key = rule_key(request);
+    let remembered = state
+        .rules
+        .lock()
+        .ok()
+        .and_then(|rules| rules.get(&key).copied());
+
+    match remembered {
+        Some(verdict) => (verdict, true),
+        None => {
+            let _one_at_a_time = state.dialog.lock();
+            match ask(app, request) {
+                Some(ALWAYS_ALLOW) => {
+                    remember(app, &state, &key, Verdict::Allow);
+                    (Verdict::Allow, false)
+                }
+                Some(ALLOW_ONCE) => (Verdict::Allow, false),
+                _ => (Verdict::Deny, false),
+            }
+        }
+    }
+}
+
+/// Whether a local operation of `kind` may run: the remembered rule, or the
+/// approval dialog. Blocks; call off the main thread.
+pub fn confirm<R: Runtime>(app: &AppHandle<R>, kind: &str, title: String, detail: String) -> bool {
+    let request = ApprovalRequest {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind: kind.to_string(),
+        title,
+        detail,
+        agent: None,
+        backend_id: String::new(),
+    };
+    let (verdict, remembered) = decide(app, &request);
+    bridge::emit(
+        app,
+        "approval-resolved",
+        json!({ "request": request, "verdict": verdict, "remembered": remembered }),
+    );
+    verdict == Verdict::Allow
+}
+
+fn resolve<R: Runtime>(app: &AppHandle<R>, backend_url: &str, request: ApprovalRequest) {
+    let (verdict, remembered) = if request.kind.starts_with(MCP_PREFIX) {
+        eprintln!(
+            "[CrewHub] Denied approval request for local kind '{}' from backend {}",
+            request.kind, request.backend_id
+        );
+        (Verdict::Deny, false)
+    } else {
+        decide(app, &request)
+    };
+
+    let body = json!({ "approved": verdict == Verdict::Allow, "remembered": remembered });
+    let path = format!("/api/approvals/{}/verdict", backend::urlencode(&request.id));
+    if let Err(e) = backend::post_json_to(backend_url, &path, &body) {
+        eprintln!("[CrewHub] Failed to send approval verdict: {}", e);
+    }
+    bridge::emit(
+        app,
+        "approval-resolved",
+        json!({ "request": request, "verdict": verdict, "remembered": remembered }),
+    );
+}
+
+/// Show the modal dialog and return the label of the pressed button.
+fn ask<R: Runtime>(app: &AppHandle<R>, request: &ApprovalRequest) -> Option<&'static str> {
+    let mut message = request.detail.clone();
+    if let Some(agent) = &request.agent {
+        message = format!(
+            "{}\n\n{}",
+            message,
+            i18n::tf("dialog.requestedBy", &[("agent", agent)])
+        );
+    }
+    let result = app
+        .dialog()
+        .message(message)
+        .title(match federation::label(app, &request.backend_id) {
+            Some(backend) => format!("CrewHub ({}) — {}", backend, request.title),
+            None => format!("CrewHub — {}", request.title),
+        })
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            i18n::t(ALLOW_ONCE),
+            i18n::t(ALWAYS_ALLOW),
+            i18n::t(DENY),
+        ))
+        .blocking_show_with_result();
+    match result {
+        MessageDialogResult::Custom(label) => [ALLOW_ONCE, ALWAYS_ALLOW, DENY]
+            .into_iter()
+            .find(|key| i18n::t(key) == label),
+        MessageDialogResult::Yes | MessageDialogResult::Ok => Some(ALLOW_ONCE),
+        // "Always allow" only counts when its own label comes back; a
+        // dismissed dialog may report `No`.
+        _ => Some(DENY),
+    }
+}
+
+fn remember<R: Runtime>(app: &AppHandle<R>, state: &ApprovalState, key: &str, verdict: Verdict) {
+    if let Ok(mut rules) = state.rules.lock() {
+        rules.insert(key.to_string(), verdict);
+        if let Err(e) = store::save_json(app, RULES_FILE, &*rules) {
+            eprintln!("[CrewHub] {}", e);
+        }
+    }
+}
+
+/// Tauri command: remembered approval rules (`local:<kind>` or `<backend id>/<kind>` → verdict).
+/// Called from the frontend via `invoke('list_approval_rules')`.
+#[tauri::command]
+pub fn list_approval_rules(
+    state: State<ApprovalState>,
+) -> Result<BTreeMap<String, Verdict>, String> {
+    Ok(state.rules.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: set (or with `verdict: null`, forget) the rule for a rule key.
+/// Called from the frontend via `invoke('set_approval_rule', { kind, verdict })`.
+#[tauri::command]
+pub fn set_approval_rule(
+    kind: String,
+    verdict: Option<Verdict>,
+    app: AppHandle,
+    state: State<ApprovalState>,
+) -> Result<BTreeMap<String, Verdict>, String> {
+    let mut rules = state.rules.lock().map_err(|e| e.to_string())?;
+    match verdict {
+        Some(verdict) => rules.insert(kind, verdict),
+        None => rules.remove(&kind),
+    };
+    store::save_json(&app, RULES_FILE, &*rules)?;
+    Ok(rules.clone())
+}
+
+/// Tauri command: forget all remembered approval rules.
+/// Called from the frontend via `invoke('clear_approval_rules')`.
+#[tauri::command]
+pub fn clear_approval_rules(app: AppHandle, state: State<ApprovalState>) -> Result<(), String> {
+    let mut rules = state.rules.lock().map_err(|e| e.to_string())?;
+    rules.clear();
+    store::save_json(&app, RULES_FILE, &*rules)
+}
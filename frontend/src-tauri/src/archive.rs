@@ -0,0 +1,675 @@
+//! Conversation cold storage.
+//!
+//! Transcripts live on the backend; what the desktop keeps hot is the recent
+//! list, the OS search entries and whatever it fetched for them. Archiving a
+//! conversation fetches its whole transcript plus the attachments it links
+//! to on the backend (or that the frontend names), writes them to one
+//! gzip-compressed file under `archives/` in the app data dir, indexes the
+//! text in `desktop.db` (SQLite FTS5, `search_archived_conversations`), and
+//! drops the conversation from the recent list. The backend copy is left
+//! alone.
+//!
+//! Archived conversations stay in the OS search index (see
+//! [`crate::search_index`]) with their last message as the snippet. Opening
+//! one again — the chat view records every conversation it opens — restores
+//! it transparently: the transcript and extracted attachments are sent to
+//! that window as `conversation-rehydrated`, and the archive is removed.
+//!
+//! With `auto` on (`archive.json`), recent conversations not opened for
+//! `afterDays` days and not pinned are archived in the background.
+
+use crate::pins::{self, ConversationRef};
+use crate::print::{self, HistoryMessage};
+use crate::{attachments, db, federation, power, recents, search_index, store};
+use base64::Engine;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "archive.json";
+/// Archive files (app data dir).
+const ARCHIVE_DIR: &str = "archives";
+/// Restored attachments (app cache dir).
+const RESTORE_DIR: &str = "archive-restore";
+
+/// How often old conversations are looked for with `auto` on.
+const AUTO_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+/// Largest attachment taken into an archive.
+const MAX_ATTACHMENT_BYTES: u64 = 25 * 1024 * 1024;
+/// Most attachment bytes per archive.
+const MAX_ARCHIVE_BYTES: u64 = 100 * 1024 * 1024;
+/// Longest snippet kept for the OS search index (characters).
+const MAX_SNIPPET: usize = 200;
+/// Most results per search.
+const MAX_RESULTS: usize = 50;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ArchiveConfig {
+    /// Archive old conversations in the background.
+    pub auto: bool,
+    /// Days since a conversation was last opened before it is archived.
+    pub after_days: u32,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            auto: false,
+            after_days: 90,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchivedAttachment {
+    url: String,
+    name: String,
+    /// Base64 of the file.
+    data: String,
+}
+
+/// Contents of an archive file (gzip-compressed JSON).
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveFile {
+    conversation: ConversationRef,
+    /// Unix seconds.
+    archived_at: i64,
+    messages: Vec<HistoryMessage>,
+    attachments: Vec<ArchivedAttachment>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedConversation {
+    #[serde(flatten)]
+    pub conversation: ConversationRef,
+    /// Unix seconds.
+    pub archived_at: i64,
+    pub messages: usize,
+    pub attachments: usize,
+    /// Size of the archive file.
+    pub bytes: u64,
+    pub snippet: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveMatch {
+    #[serde(flatten)]
+    archived: ArchivedConversation,
+    /// Matching text with the hits in `[` `]`.
+    excerpt: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredAttachment {
+    url: String,
+    name: String,
+    /// Extracted copy in the app cache.
+    path: String,
+}
+
+/// Payload of `conversation-rehydrated`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RehydratedConversation {
+    conversation: ConversationRef,
+    messages: Vec<HistoryMessage>,
+    attachments: Vec<RestoredAttachment>,
+}
+
+/// App state: database connection and config.
+pub struct ArchiveState {
+    conn: Mutex<Connection>,
+    config: Mutex<ArchiveConfig>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let conn = db::open(app)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS archived_conversations (
+            backend_id TEXT NOT NULL,
+            session_key TEXT NOT NULL,
+            title TEXT NOT NULL,
+            archived_at INTEGER NOT NULL,
+            messages INTEGER NOT NULL,
+            attachments INTEGER NOT NULL,
+            bytes INTEGER NOT NULL,
+            snippet TEXT,
+            PRIMARY KEY (backend_id, session_key)
+        );
+        CREATE VIRTUAL TABLE IF NOT EXISTS archived_text USING fts5(
+            backend_id UNINDEXED,
+            session_key UNINDEXED,
+            title,
+            content
+        );",
+    )
+    .map_err(|e| e.to_string())?;
+    app.manage(ArchiveState {
+        conn: Mutex::new(conn),
+        config: Mutex::new(store::load_json(app, CONFIG_FILE)),
+    });
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(power::interval(&app, AUTO_INTERVAL));
+        if !power::is_suspended() {
+            auto_archive(&app);
+        }
+    });
+    Ok(())
+}
+
+fn config<R: Runtime>(app: &AppHandle<R>) -> ArchiveConfig {
+    app.try_state::<ArchiveState>()
+        .and_then(|state| state.config.lock().ok().map(|c| c.clone()))
+        .unwrap_or_default()
+}
+
+/// File name of a conversation's archive.
+fn file_name(conversation: &ConversationRef) -> String {
+    let digest = Sha256::digest(format!(
+        "{}\n{}",
+        conversation.backend_id, conversation.session_key
+    ));
+    format!("{:x}.json.gz", digest)
+}
+
+fn archive_path<R: Runtime>(
+    app: &AppHandle<R>,
+    conversation: &ConversationRef,
+) -> Result<PathBuf, String> {
+    let dir = store::data_path(app, ARCHIVE_DIR)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(file_name(conversation)))
+}
+
+fn shorten(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(MAX_SNIPPET) {
+        Some((end, _)) => Some(format!("{}…", &text[..end])),
+        None if text.is_empty() => None,
+        None => Some(text),
+    }
+}
+
+/// http(s) links in the transcript that point at the conversation's backend.
+fn backend_links(messages: &[HistoryMessage], base: &str) -> Vec<String> {
+    let Ok(base) = url::Url::parse(base) else {
+        return Vec::new();
+    };
+    let mut links: Vec<String> = Vec::new();
+    for message in messages {
+        for word in message
+            .content
+            .split(|c: char| c.is_whitespace() || c == '(')
+        {
+            let Some(start) = word.find("http://").or_else(|| word.find("https://")) else {
+                continue;
+            };
+            let link = word[start..].trim_end_matches([')', ']', '>', '"', '\'', '.', ',']);
+            let same_backend = url::Url::parse(link).is_ok_and(|url| url.origin() == base.origin());
+            if same_backend && !links.iter().any(|known| known == link) {
+                links.push(link.to_string());
+            }
+        }
+    }
+    links
+}
+
+/// Download the attachments into memory, within the size limits.
+fn collect_attachments<R: Runtime>(app: &AppHandle<R>, urls: &[String]) -> Vec<ArchivedAttachment> {
+    let Ok(dir) = app.path().app_cache_dir().map(|dir| dir.join(RESTORE_DIR)) else {
+        return Vec::new();
+    };
+    let _ = std::fs::create_dir_all(&dir);
+    let scratch = dir.join(format!("{}.part", uuid::Uuid::new_v4()));
+    let mut total = 0u64;
+    let mut collected = Vec::new();
+    for url in urls {
+        let result = attachments::fetch(app, url, &scratch).and_then(|()| {
+            let size = std::fs::metadata(&scratch)
+                .map_err(|e| e.to_string())?
+                .len();
+            if size > MAX_ATTACHMENT_BYTES || total + size > MAX_ARCHIVE_BYTES {
+                return Err("too large to archive".to_string());
+            }
+            total += size;
+            std::fs::read(&scratch).map_err(|e| e.to_string())
+        });
+        match result {
+            Ok(bytes) => collected.push(ArchivedAttachment {
+                url: url.clone(),
+                name: url
+                    .rsplit('/')
+                    .next()
+                    .filter(|name| !name.is_empty())
+                    .map(|name| name.split(['?', '#']).next().unwrap_or(name).to_string())
+                    .unwrap_or_else(|| "attachment".to_string()),
+                data: base64::engine::general_purpose::STANDARD.encode(bytes),
+            }),
+            Err(e) => eprintln!("[CrewHub] Attachment {} not archived: {}", url, e),
+        }
+    }
+    let _ = std::fs::remove_file(&scratch);
+    collected
+}
+
+/// Archive a conversation: fetch, compress, index, and drop it from the recent list.
+pub fn archive<R: Runtime>(
+    app: &AppHandle<R>,
+    conversation: &ConversationRef,
+    extra_attachments: &[String],
+) -> Result<ArchivedConversation, String> {
+    let backend = federation::enabled(app)
+        .into_iter()
+        .find(|backend| backend.id == conversation.backend_id)
+        .ok_or_else(|| format!("Unknown backend: {}", conversation.backend_id))?;
+    let messages = print::fetch_history(&backend.url, &conversation.session_key)?;
+    if messages.is_empty() {
+        return Err("The conversation has no messages to archive".to_string());
+    }
+    let mut urls = backend_links(&messages, &backend.url);
+    for url in extra_attachments {
+        if !urls.contains(url) {
+            urls.push(url.clone());
+        }
+    }
+    let file = ArchiveFile {
+        conversation: conversation.clone(),
+        archived_at: chrono::Local::now().timestamp(),
+        attachments: collect_attachments(app, &urls),
+        messages,
+    };
+
+    let path = archive_path(app, conversation)?;
+    let partial = path.with_extension("part");
+    let mut encoder = GzEncoder::new(
+        std::fs::File::create(&partial).map_err(|e| e.to_string())?,
+        Compression::default(),
+    );
+    serde_json::to_writer(&mut encoder, &file).map_err(|e| e.to_string())?;
+    encoder
+        .finish()
+        .and_then(|mut out| out.flush())
+        .map_err(|e| e.to_string())?;
+    std::fs::rename(&partial, &path).map_err(|e| e.to_string())?;
+    let bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+    let archived = ArchivedConversation {
+        conversation: conversation.clone(),
+        archived_at: file.archived_at,
+        messages: file.messages.len(),
+        attachments: file.attachments.len(),
+        bytes,
+        snippet: file.messages.last().and_then(|m| shorten(&m.content)),
+    };
+    let text = file
+        .messages
+        .iter()
+        .map(|message| message.content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let state = app
+        .try_state::<ArchiveState>()
+        .ok_or("The conversation archive is unavailable")?;
+    {
+        let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        remove_rows(&tx, conversation)?;
+        tx.execute(
+            "INSERT INTO archived_conversations
+                (backend_id, session_key, title, archived_at, messages, attachments, bytes, snippet)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                conversation.backend_id,
+                conversation.session_key,
+                conversation.title,
+                archived.archived_at,
+                archived.messages as i64,
+                archived.attachments as i64,
+                archived.bytes as i64,
+                archived.snippet,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        tx.execute(
+            "INSERT INTO archived_text (backend_id, session_key, title, content)
+                VALUES (?1, ?2, ?3, ?4)",
+            params![
+                conversation.backend_id,
+                conversation.session_key,
+                conversation.title,
+                text
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+    }
+    recents::forget(app, conversation);
+    println!(
+        "[CrewHub] Archived {} ({} messages, {} attachments)",
+        conversation.session_key, archived.messages, archived.attachments
+    );
+    Ok(archived)
+}
+
+fn remove_rows(conn: &Connection, conversation: &ConversationRef) -> Result<(), String> {
+    for table in ["archived_conversations", "archived_text"] {
+        conn.execute(
+            &format!(
+                "DELETE FROM {} WHERE backend_id = ?1 AND session_key = ?2",
+                table
+            ),
+            params![conversation.backend_id, conversation.session_key],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn from_row(row: &Row) -> rusqlite::Result<ArchivedConversation> {
+    Ok(ArchivedConversation {
+        conversation: ConversationRef {
+            backend_id: row.get(0)?,
+            session_key: row.get(1)?,
+            title: row.get(2)?,
+        },
+        archived_at: row.get(3)?,
+        messages: row.get::<_, i64>(4)? as usize,
+        attachments: row.get::<_, i64>(5)? as usize,
+        bytes: row.get::<_, i64>(6)? as u64,
+        snippet: row.get(7)?,
+    })
+}
+
+const COLUMNS: &str =
+    "a.backend_id, a.session_key, a.title, a.archived_at, a.messages, a.attachments, a.bytes, a.snippet";
+
+/// Archived conversations, most recently archived first.
+pub fn list<R: Runtime>(app: &AppHandle<R>) -> Vec<ArchivedConversation> {
+    let Some(state) = app.try_state::<ArchiveState>() else {
+        return Vec::new();
+    };
+    let Ok(conn) = state.conn.lock() else {
+        return Vec::new();
+    };
+    let query = format!(
+        "SELECT {} FROM archived_conversations a ORDER BY a.archived_at DESC",
+        COLUMNS
+    );
+    conn.prepare(&query)
+        .and_then(|mut stmt| {
+            stmt.query_map([], from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .unwrap_or_default()
+}
+
+fn find<R: Runtime>(
+    app: &AppHandle<R>,
+    conversation: &ConversationRef,
+) -> Option<ArchivedConversation> {
+    let state = app.try_state::<ArchiveState>()?;
+    let conn = state.conn.lock().ok()?;
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM archived_conversations a WHERE a.backend_id = ?1 AND a.session_key = ?2",
+            COLUMNS
+        ),
+        params![conversation.backend_id, conversation.session_key],
+        from_row,
+    )
+    .optional()
+    .ok()
+    .flatten()
+}
+
+/// Full-text search; every word must match (as a prefix).
+fn search<R: Runtime>(app: &AppHandle<R>, query: &str) -> Result<Vec<ArchiveMatch>, String> {
+    // Quote each word so user input is never FTS5 syntax.
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let state = app
+        .try_state::<ArchiveState>()
+        .ok_or("The conversation archive is unavailable")?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let sql = format!(
+        "SELECT {}, snippet(archived_text, 3, '[', ']', '…', 16)
+            FROM archived_text t
+            JOIN archived_conversations a
+                ON a.backend_id = t.backend_id AND a.session_key = t.session_key
+            WHERE archived_text MATCH ?1
+            ORDER BY rank
+            LIMIT ?2",
+        COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![terms.join(" "), MAX_RESULTS as i64], |row| {
+            Ok(ArchiveMatch {
+                archived: from_row(row)?,
+                excerpt: row.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Snippet of an archived conversation for the OS search index.
+pub fn snippet<R: Runtime>(app: &AppHandle<R>, conversation: &ConversationRef) -> Option<String> {
+    find(app, conversation)?.snippet
+}
+
+/// Read an archive back, extract its attachments, and delete it.
+fn restore<R: Runtime>(
+    app: &AppHandle<R>,
+    conversation: &ConversationRef,
+) -> Result<RehydratedConversation, String> {
+    let path = archive_path(app, conversation)?;
+    let mut json = String::new();
+    GzDecoder::new(std::fs::File::open(&path).map_err(|e| e.to_string())?)
+        .read_to_string(&mut json)
+        .map_err(|e| format!("Unreadable archive: {}", e))?;
+    let file: ArchiveFile =
+        serde_json::from_str(&json).map_err(|e| format!("Unreadable archive: {}", e))?;
+
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join(RESTORE_DIR)
+        .join(file_name(conversation).trim_end_matches(".json.gz"));
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let mut restored = Vec::new();
+    for (i, attachment) in file.attachments.into_iter().enumerate() {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&attachment.data)
+            .map_err(|e| e.to_string())?;
+        let target = dir.join(format!("{}-{}", i, attachment.name));
+        std::fs::write(&target, bytes).map_err(|e| e.to_string())?;
+        restored.push(RestoredAttachment {
+            url: attachment.url,
+            name: attachment.name,
+            path: target.display().to_string(),
+        });
+    }
+
+    let state = app
+        .try_state::<ArchiveState>()
+        .ok_or("The conversation archive is unavailable")?;
+    remove_rows(
+        &*state.conn.lock().map_err(|e| e.to_string())?,
+        conversation,
+    )?;
+    let _ = std::fs::remove_file(&path);
+    println!(
+        "[CrewHub] Restored {} from the archive",
+        conversation.session_key
+    );
+    Ok(RehydratedConversation {
+        conversation: file.conversation,
+        messages: file.messages,
+        attachments: restored,
+    })
+}
+
+/// A conversation was opened: if it is archived, restore it into `window`.
+pub fn rehydrate<R: Runtime>(app: &AppHandle<R>, conversation: &ConversationRef, window: &str) {
+    if find(app, conversation).is_none() {
+        return;
+    }
+    let (app, conversation, window) = (app.clone(), conversation.clone(), window.to_string());
+    std::thread::spawn(move || match restore(&app, &conversation) {
+        Ok(rehydrated) => {
+            let _ = app.emit_to(window.as_str(), "conversation-rehydrated", &rehydrated);
+            search_index::refresh(&app);
+        }
+        Err(e) => eprintln!(
+            "[CrewHub] Failed to restore {}: {}",
+            conversation.session_key, e
+        ),
+    });
+}
+
+/// Archive recent conversations that weren't opened for `afterDays` (not pinned ones).
+fn auto_archive<R: Runtime>(app: &AppHandle<R>) {
+    let config = config(app);
+    if !config.auto {
+        return;
+    }
+    let cutoff = chrono::Local::now().timestamp() - config.after_days as i64 * 24 * 60 * 60;
+    let pinned = pins::pinned(app);
+    let mut archived = 0;
+    for recent in recents::recent(app) {
+        let conversation = recent.conversation;
+        let is_pinned = pinned.iter().any(|pin| {
+            pin.conversation.session_key == conversation.session_key
+                && pin.conversation.backend_id == conversation.backend_id
+        });
+        if recent.opened_at >= cutoff || is_pinned {
+            continue;
+        }
+        match archive(app, &conversation, &[]) {
+            Ok(_) => archived += 1,
+            Err(e) => eprintln!(
+                "[CrewHub] Failed to archive {}: {}",
+                conversation.session_key, e
+            ),
+        }
+    }
+    if archived > 0 {
+        search_index::refresh(app);
+        let _ = app.emit("archive-changed", ());
+    }
+}
+
+/// Tauri command: archive a conversation (with extra attachment URLs the frontend knows of).
+/// Called from the frontend via `invoke('archive_conversation', { sessionKey, title, backendId, attachments })`.
+#[tauri::command]
+pub async fn archive_conversation(
+    session_key: String,
+    title: String,
+    backend_id: Option<String>,
+    attachments: Option<Vec<String>>,
+    app: AppHandle,
+) -> Result<ArchivedConversation, String> {
+    let conversation = ConversationRef {
+        session_key,
+        title,
+        backend_id: backend_id.unwrap_or_else(pins::default_backend),
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let archived = archive(&app, &conversation, &attachments.unwrap_or_default())?;
+        search_index::refresh(&app);
+        let _ = app.emit("archive-changed", ());
+        Ok(archived)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: archived conversations, most recently archived first.
+/// Called from the frontend via `invoke('list_archived_conversations')`.
+#[tauri::command]
+pub fn list_archived_conversations(app: AppHandle) -> Vec<ArchivedConversation> {
+    list(&app)
+}
+
+/// Tauri command: full-text search over archived conversations.
+/// Called from the frontend via `invoke('search_archived_conversations', { query })`.
+#[tauri::command]
+pub async fn search_archived_conversations(
+    query: String,
+    app: AppHandle,
+) -> Result<Vec<ArchiveMatch>, String> {
+    tauri::async_runtime::spawn_blocking(move || search(&app, &query))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: restore an archived conversation and return it (the archive is removed).
+/// Called from the frontend via `invoke('restore_archived_conversation', { sessionKey, backendId })`.
+#[tauri::command]
+pub async fn restore_archived_conversation(
+    session_key: String,
+    backend_id: Option<String>,
+    app: AppHandle,
+) -> Result<RehydratedConversation, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let backend_id = backend_id.unwrap_or_else(pins::default_backend);
+        let conversation = list(&app)
+            .into_iter()
+            .map(|archived| archived.conversation)
+            .find(|c| c.session_key == session_key && c.backend_id == backend_id)
+            .ok_or_else(|| "The conversation isn't archived".to_string())?;
+        let rehydrated = restore(&app, &conversation)?;
+        search_index::refresh(&app);
+        let _ = app.emit("archive-changed", ());
+        Ok(rehydrated)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: automatic archiving config.
+/// Called from the settings window via `invoke('get_archive_config')`.
+#[tauri::command]
+pub fn get_archive_config(state: State<ArchiveState>) -> Result<ArchiveConfig, String> {
+    Ok(state.config.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: replace the automatic archiving config.
+/// Called from the settings window via `invoke('configure_archive', { config })`.
+#[tauri::command]
+pub fn configure_archive(
+    config: ArchiveConfig,
+    app: AppHandle,
+    state: State<ArchiveState>,
+) -> Result<ArchiveConfig, String> {
+    if config.after_days == 0 {
+        return Err("afterDays must be at least 1".to_string());
+    }
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    *state.config.lock().map_err(|e| e.to_string())? = config.clone();
+    Ok(config)
+}
@@ -0,0 +1,275 @@
+//! File attachments dropped onto CrewHub windows.
+//!
+//! Native drag-and-drop is handled here rather than in the webview so folder
+//! drops can be expanded and every file validated before the frontend sees it.
+//! Accepted files are copied into a staging directory in the app cache (so an
+//! attachment doesn't change or vanish while a message is being written) and
+//! announced to the window with an `attach-files` event, together with the
+//! conversation that window last reported via [`set_drop_context`].
+//!
+//! Tray icons don't receive drops on any platform Tauri supports, so windows
+//! are the only drop targets.
+
+use crate::{bandwidth, federation, file_manager, request_headers, task_journal};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, Window};
+
+/// Staging directory under the app cache dir.
+const STAGING_DIR: &str = "attachments";
+
+/// Largest single file that is accepted.
+const MAX_FILE_BYTES: u64 = 25 * 1024 * 1024;
+/// Most files taken from one drop (folders included).
+const MAX_FILES: usize = 200;
+/// Folders never descended into.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "__pycache__"];
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedFile {
+    /// Staged copy in the app cache.
+    pub path: String,
+    /// Where the file was dropped from.
+    pub source: String,
+    pub name: String,
+    /// Path inside the dropped folder, or just the name for single files.
+    pub relative_path: String,
+    pub size: u64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedDrop {
+    pub files: Vec<StagedFile>,
+    pub skipped: Vec<SkippedFile>,
+    /// Names of the folders that were dropped.
+    pub folders: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AttachFilesPayload {
+    window: String,
+    conversation_id: Option<String>,
+    #[serde(flatten)]
+    staged: StagedDrop,
+}
+
+/// App state: the active conversation per window label.
+#[derive(Default)]
+pub struct AttachmentState {
+    contexts: Mutex<HashMap<String, String>>,
+}
+
+/// Register state and clear attachments staged by a previous run.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(AttachmentState::default());
+    // Files of uploads interrupted by a restart stay for them to resume.
+    if let Ok(root) = staging_root(app) {
+        let kept = task_journal::kept_staging_dirs(&root);
+        for entry in std::fs::read_dir(&root).into_iter().flatten().flatten() {
+            if !kept.contains(&entry.path()) {
+                let _ = std::fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+}
+
+fn staging_root<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join(STAGING_DIR))
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with('.'))
+}
+
+/// Expand dropped paths into (source, relative path) pairs, collecting what was skipped.
+fn collect(
+    path: &Path,
+    relative: PathBuf,
+    files: &mut Vec<(PathBuf, PathBuf)>,
+    skipped: &mut Vec<SkippedFile>,
+) {
+    let skip = |skipped: &mut Vec<SkippedFile>, reason: &str| {
+        skipped.push(SkippedFile {
+            path: path.to_string_lossy().to_string(),
+            reason: reason.to_string(),
+        })
+    };
+    let Ok(meta) = std::fs::metadata(path) else {
+        return skip(skipped, "unreadable");
+    };
+    if meta.is_dir() {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return skip(skipped, "unreadable");
+        };
+        let mut entries: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        entries.sort();
+        for entry in entries {
+            let name = entry.file_name().unwrap_or_default();
+            let ignored =
+                is_hidden(&entry) || (entry.is_dir() && SKIPPED_DIRS.iter().any(|d| name == *d));
+            if !ignored {
+                collect(&entry, relative.join(name), files, skipped);
+            }
+        }
+    } else if files.len() >= MAX_FILES {
+        skip(skipped, "too many files");
+    } else if meta.len() > MAX_FILE_BYTES {
+        skip(skipped, "too large");
+    } else {
+        files.push((path.to_path_buf(), relative));
+    }
+}
+
+/// Validate and copy dropped (or shared) paths into a fresh staging directory.
+pub fn stage<R: Runtime>(app: &AppHandle<R>, paths: &[PathBuf]) -> Result<StagedDrop, String> {
+    let mut found = Vec::new();
+    let mut skipped = Vec::new();
+    let mut folders = Vec::new();
+    for path in paths {
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        file_manager::grant(app, path);
+        if path.is_dir() {
+            folders.push(name.to_string_lossy().to_string());
+        }
+        collect(path, PathBuf::from(name), &mut found, &mut skipped);
+    }
+
+    let dir = staging_root(app)?.join(uuid::Uuid::new_v4().to_string());
+    let mut files = Vec::new();
+    for (source, relative) in found {
+        let target = dir.join(&relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        match std::fs::copy(&source, &target) {
+            Ok(size) => files.push(StagedFile {
+                path: target.to_string_lossy().to_string(),
+                source: source.to_string_lossy().to_string(),
+                name: relative
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                relative_path: relative.to_string_lossy().replace('\\', "/"),
+                size,
+            }),
+            Err(e) => skipped.push(SkippedFile {
+                path: source.to_string_lossy().to_string(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+    Ok(StagedDrop {
+        files,
+        skipped,
+        folders,
+    })
+}
+
+/// Handle a native file drop on a window. Staging copies files, so it runs on its own thread.
+pub fn handle_drop<R: Runtime>(window: &Window<R>, paths: Vec<PathBuf>) {
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    std::thread::spawn(move || {
+        let staged = match stage(&app, &paths) {
+            Ok(staged) => staged,
+            Err(e) => {
+                eprintln!("[CrewHub] Failed to stage dropped files: {}", e);
+                return;
+            }
+        };
+        announce(&app, &label, staged);
+    });
+}
+
+/// Send staged files to a window as an `attach-files` event, tagged with the
+/// conversation it last reported.
+pub fn announce<R: Runtime>(app: &AppHandle<R>, label: &str, staged: StagedDrop) {
+    let conversation_id = app
+        .state::<AttachmentState>()
+        .contexts
+        .lock()
+        .ok()
+        .and_then(|contexts| contexts.get(label).cloned());
+    let payload = AttachFilesPayload {
+        window: label.to_string(),
+        conversation_id,
+        staged,
+    };
+    let _ = app.emit_to(label, "attach-files", payload);
+}
+
+/// Tauri command: set the conversation that drops on the calling window belong to.
+/// Called from the frontend via `invoke('set_drop_context', { conversationId })`.
+#[tauri::command]
+pub fn set_drop_context(
+    conversation_id: Option<String>,
+    window: Window,
+    state: State<AttachmentState>,
+) -> Result<(), String> {
+    let mut contexts = state.contexts.lock().map_err(|e| e.to_string())?;
+    match conversation_id {
+        Some(id) => contexts.insert(window.label().to_string(), id),
+        None => contexts.remove(window.label()),
+    };
+    Ok(())
+}
+
+/// Download (http/https) or copy (`file:` URL or local path) an attachment to `target`.
+pub fn fetch<R: Runtime>(app: &AppHandle<R>, source: &str, target: &Path) -> Result<(), String> {
+    match url::Url::parse(source) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {
+            let agent = crate::backend_auth::configure(url.as_str(), ureq::AgentBuilder::new());
+            let mut request = agent
+                .build()
+                .get(url.as_str())
+                .set("User-Agent", &request_headers::user_agent());
+            // Backend credentials only go to backends.
+            let backend = federation::enabled(app).iter().any(|backend| {
+                url::Url::parse(&backend.url).is_ok_and(|base| base.origin() == url.origin())
+            });
+            if backend {
+                for (name, value) in request_headers::effective_headers() {
+                    request = request.set(&name, &value);
+                }
+                request = crate::backend_auth::authorize(url.as_str(), request);
+            }
+            let response = request.call().map_err(|e| e.to_string())?;
+            let mut file = std::fs::File::create(target).map_err(|e| e.to_string())?;
+            let mut reader = bandwidth::counted(url.as_str(), 0, response.into_reader());
+            std::io::copy(&mut reader, &mut file).map_err(|e| e.to_string())?;
+            Ok(())
+        }
+        Ok(url) if url.scheme() == "file" => {
+            let path = url
+                .to_file_path()
+                .map_err(|_| format!("Invalid file URL: {}", source))?;
+            std::fs::copy(path, target)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+        _ => std::fs::copy(source, target)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+    }
+}
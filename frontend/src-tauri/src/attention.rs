@@ -0,0 +1,141 @@
+//! Request the user's attention for direct mentions and blocking questions.
+//!
+//! A `mention` event from the backend bridge, or an agent entering the
+//! `blocked` state (it asked something and waits for an answer), flashes the
+//! taskbar button (Windows, Linux) or bounces the Dock icon (macOS) of the
+//! visible main window — unless a CrewHub window already has focus. With no
+//! main window showing there is nothing to flash, so the tray icon blinks a
+//! few times instead (unless the OS asks for reduced motion). Blocking questions keep flashing until the window is
+//! activated; mentions flash once. Do Not Disturb silences both, and each
+//! can be turned off in settings.
+
+use crate::{accessibility, dnd, presence, store};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime, UserAttentionType};
+
+/// Backend event announcing that someone mentioned the user directly.
+pub const MENTION_EVENT: &str = "mention";
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "attention.json";
+
+/// Main windows, in the order they are preferred for the request.
+const MAIN_WINDOWS: [&str; 3] = [
+    crate::CHAT_WINDOW_LABEL,
+    crate::WORLD_WINDOW_LABEL,
+    crate::ZEN_WINDOW_LABEL,
+];
+
+/// Tray blink: frame length and number of frames.
+const TRAY_FLASH_FRAME: Duration = Duration::from_millis(300);
+const TRAY_FLASH_FRAMES: u32 = 8;
+
+/// Whether a tray blink is in progress (a second request doesn't restart it).
+static FLASHING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AttentionConfig {
+    /// Flash for direct mentions.
+    pub mentions: bool,
+    /// Flash when an agent blocks on a question.
+    pub blocking_questions: bool,
+}
+
+impl Default for AttentionConfig {
+    fn default() -> Self {
+        Self {
+            mentions: true,
+            blocking_questions: true,
+        }
+    }
+}
+
+fn config<R: Runtime>(app: &AppHandle<R>) -> AttentionConfig {
+    store::load_json(app, CONFIG_FILE)
+}
+
+/// Handle a `mention` event from the backend bridge.
+pub fn handle_mention<R: Runtime>(app: &AppHandle<R>, _payload: Value) {
+    if config(app).mentions {
+        request(app, UserAttentionType::Informational);
+    }
+}
+
+/// An agent started waiting on the user (called by [`crate::presence`]).
+pub fn agent_blocked<R: Runtime>(app: &AppHandle<R>) {
+    if config(app).blocking_questions {
+        request(app, UserAttentionType::Critical);
+    }
+}
+
+fn request<R: Runtime>(app: &AppHandle<R>, kind: UserAttentionType) {
+    if dnd::quiet(app) {
+        return;
+    }
+    let windows = app.webview_windows();
+    if windows
+        .values()
+        .any(|window| window.is_focused().unwrap_or(false))
+    {
+        return;
+    }
+    let visible = MAIN_WINDOWS.iter().find_map(|label| {
+        windows
+            .get(*label)
+            .filter(|window| window.is_visible().unwrap_or(false))
+    });
+    match visible {
+        Some(window) => {
+            if let Err(e) = window.request_user_attention(Some(kind)) {
+                eprintln!("[CrewHub] Failed to request attention: {}", e);
+            }
+        }
+        None => flash_tray(app),
+    }
+}
+
+/// Blink the tray icon briefly, then restore it.
+fn flash_tray<R: Runtime>(app: &AppHandle<R>) {
+    if accessibility::reduce_motion() || FLASHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let (Some(tray), Ok(icon)) = (app.tray_by_id(crate::TRAY_ID), crate::tray_icon(&app)) {
+            let dimmed = presence::dimmed(&icon);
+            for frame in 0..TRAY_FLASH_FRAMES {
+                let image = if frame % 2 == 0 {
+                    dimmed.clone()
+                } else {
+                    icon.clone()
+                };
+                let _ = tray.set_icon(Some(image));
+                std::thread::sleep(TRAY_FLASH_FRAME);
+            }
+        }
+        crate::refresh_tray_icon(&app);
+        FLASHING.store(false, Ordering::SeqCst);
+    });
+}
+
+/// Tauri command: when CrewHub asks for attention.
+/// Called from the settings window via `invoke('get_attention_config')`.
+#[tauri::command]
+pub fn get_attention_config(app: AppHandle) -> AttentionConfig {
+    config(&app)
+}
+
+/// Tauri command: turn attention requests for mentions / blocking questions on or off.
+/// Called from the settings window via `invoke('set_attention_config', { config })`.
+#[tauri::command]
+pub fn set_attention_config(
+    config: AttentionConfig,
+    app: AppHandle,
+) -> Result<AttentionConfig, String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    Ok(config)
+}
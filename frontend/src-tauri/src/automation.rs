@@ -0,0 +1,140 @@
+//! Automation mode for CI (`--automation`, or `CREWHUB_AUTOMATION=1` for
+//! drivers that can't pass arguments).
+//!
+//! Makes the full desktop app drivable by tauri-driver (WebDriver) and
+//! scripts in CI pipelines:
+//! - the single-instance plugin is off, so each test session gets a fresh
+//!   app even while another instance is running on the machine;
+//! - ports are fixed whatever the saved settings say: the control surface
+//!   ([`crate::control`]) is on at its default port with the token from
+//!   `CREWHUB_AUTOMATION_TOKEN` (or a random one printed to stdout at
+//!   startup, so no build answers to a well-known token), and the mock
+//!   backend of `--test-harness` ([`crate::test_harness`]) listens on [`HARNESS_PORT`].
+//!   Neither override is saved;
+//! - tray clicks and menu items can be triggered through commands, because
+//!   CI desktops often have no tray and WebDriver can't reach native menus.
+//!
+//! The `automation_*` commands refuse to run outside automation mode.
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use tauri::menu::MenuItemKind;
+use tauri::{AppHandle, Runtime};
+
+/// Command-line flag.
+const FLAG: &str = "--automation";
+
+/// Environment switch (`1` / `true`).
+const ENV: &str = "CREWHUB_AUTOMATION";
+
+/// Environment override for the control surface token.
+const TOKEN_ENV: &str = "CREWHUB_AUTOMATION_TOKEN";
+
+/// Fixed port of the test harness mock backend.
+pub const HARNESS_PORT: u16 = 8097;
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+static TOKEN: OnceLock<String> = OnceLock::new();
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayButton {
+    Left,
+    Middle,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayItem {
+    id: String,
+    text: String,
+    enabled: bool,
+    /// Submenu entries.
+    items: Vec<TrayItem>,
+}
+
+/// Whether the app runs in automation mode.
+pub fn enabled() -> bool {
+    *ENABLED.get_or_init(|| {
+        std::env::args().any(|arg| arg == FLAG)
+            || std::env::var(ENV).is_ok_and(|value| value == "1" || value == "true")
+    })
+}
+
+/// Control surface token in automation mode.
+pub fn token() -> String {
+    TOKEN
+        .get_or_init(|| match std::env::var(TOKEN_ENV) {
+            Ok(token) if !token.is_empty() => token,
+            _ => {
+                let token = crate::local_http::new_token();
+                println!("[CrewHub] Automation control token: {}", token);
+                token
+            }
+        })
+        .clone()
+}
+
+fn require() -> Result<(), String> {
+    if enabled() {
+        Ok(())
+    } else {
+        Err("Automation mode is off".to_string())
+    }
+}
+
+fn describe<R: Runtime>(items: Vec<MenuItemKind<R>>) -> tauri::Result<Vec<TrayItem>> {
+    let mut out = Vec::new();
+    for item in items {
+        let entry = match &item {
+            MenuItemKind::MenuItem(item) => (item.text()?, item.is_enabled()?, Vec::new()),
+            MenuItemKind::Check(item) => (item.text()?, item.is_enabled()?, Vec::new()),
+            MenuItemKind::Icon(item) => (item.text()?, item.is_enabled()?, Vec::new()),
+            MenuItemKind::Submenu(submenu) => (
+                submenu.text()?,
+                submenu.is_enabled()?,
+                describe(submenu.items()?)?,
+            ),
+            MenuItemKind::Predefined(_) => continue,
+        };
+        let (text, enabled, items) = entry;
+        out.push(TrayItem {
+            id: item.id().0.clone(),
+            text,
+            enabled,
+            items,
+        });
+    }
+    Ok(out)
+}
+
+/// Tauri command: act as if the tray icon was clicked.
+/// Called from automation tests via `invoke('automation_tray_click', { button })`.
+#[tauri::command]
+pub fn automation_tray_click(button: TrayButton, app: AppHandle) -> Result<(), String> {
+    require()?;
+    match button {
+        TrayButton::Left => crate::tray_click::handle(&app),
+        TrayButton::Middle => crate::tray_click::handle_middle(&app),
+    }
+    Ok(())
+}
+
+/// Tauri command: act as if a tray menu item was chosen (ids from `automation_tray_items`).
+/// Called from automation tests via `invoke('automation_tray_menu', { id })`.
+#[tauri::command]
+pub fn automation_tray_menu(id: String, app: AppHandle) -> Result<(), String> {
+    require()?;
+    crate::handle_tray_menu(&app, &id);
+    Ok(())
+}
+
+/// Tauri command: the tray menu as it would be shown now (separators omitted).
+/// Called from automation tests via `invoke('automation_tray_items')`.
+#[tauri::command]
+pub fn automation_tray_items(app: AppHandle) -> Result<Vec<TrayItem>, String> {
+    require()?;
+    let menu = crate::build_tray_menu(&app).map_err(|e| e.to_string())?;
+    describe(menu.items().map_err(|e| e.to_string())?).map_err(|e| e.to_string())
+}
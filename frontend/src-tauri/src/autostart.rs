@@ -0,0 +1,415 @@
+//! Start CrewHub at login.
+//!
+//! Each OS has its own mechanism: a LaunchAgent on macOS, an XDG autostart
+//! entry on Linux, and on Windows either the per-user `Run` registry key or a
+//! scheduled task with a logon trigger. Inside Flatpak or Snap the Linux
+//! entry would land in the sandbox's own config dir, so the Background
+//! portal is asked instead. Managed Windows machines often block
+//! `Run` keys through policy while still allowing per-user scheduled tasks,
+//! so the method is selectable there. The task runs with the user's limited
+//! token (never "highest available"), like a `Run` entry would.
+//!
+//! The chosen method is persisted in `autostart.json`; whether autostart is
+//! enabled is read back from the OS, so changes made elsewhere (Task
+//! Manager's Startup tab, System Settings) are reflected. The portal can't be
+//! queried, so for it the last granted state is persisted too.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// Persisted method choice (app data dir).
+const CONFIG_FILE: &str = "autostart.json";
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Method {
+    /// `HKCU\Software\Microsoft\Windows\CurrentVersion\Run` (Windows).
+    RunKey,
+    /// Task Scheduler logon task (Windows).
+    ScheduledTask,
+    /// `~/Library/LaunchAgents` (macOS).
+    LaunchAgent,
+    /// `~/.config/autostart` (Linux).
+    DesktopEntry,
+    /// xdg-desktop-portal Background request (Linux, inside Flatpak/Snap).
+    BackgroundPortal,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AutostartConfig {
+    pub method: Method,
+    /// Last state granted through the Background portal.
+    pub portal_enabled: bool,
+}
+
+impl Default for AutostartConfig {
+    fn default() -> Self {
+        AutostartConfig {
+            method: platform::methods()[0],
+            portal_enabled: false,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutostartStatus {
+    enabled: bool,
+    method: Method,
+    /// Methods available on this OS (the first is the default).
+    methods: &'static [Method],
+}
+
+fn config(app: &AppHandle) -> AutostartConfig {
+    let config: AutostartConfig = store::load_json(app, CONFIG_FILE);
+    if platform::methods().contains(&config.method) {
+        config
+    } else {
+        AutostartConfig::default()
+    }
+}
+
+fn exe() -> Result<String, String> {
+    std::env::current_exe()
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+fn status(app: &AppHandle) -> Result<AutostartStatus, String> {
+    let config = config(app);
+    let method = config.method;
+    let enabled = match method {
+        Method::BackgroundPortal => config.portal_enabled,
+        _ => platform::is_enabled(method)?,
+    };
+    Ok(AutostartStatus {
+        enabled,
+        method,
+        methods: platform::methods(),
+    })
+}
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+mod platform {
+    use super::{exe, Method};
+    use std::path::{Path, PathBuf};
+
+    pub fn methods() -> &'static [Method] {
+        #[cfg(target_os = "linux")]
+        if crate::portal::sandbox().is_some() {
+            return &[Method::BackgroundPortal];
+        }
+        &[Method::DesktopEntry]
+    }
+
+    const DESKTOP_ENTRY: &str = "dev.crewhub.desktop";
+
+    fn entry_path() -> Result<PathBuf, String> {
+        let config = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+            .ok_or("No home directory")?;
+        Ok(config.join("autostart").join(DESKTOP_ENTRY))
+    }
+
+    pub fn is_enabled(_method: Method) -> Result<bool, String> {
+        Ok(entry_path()?.exists())
+    }
+
+    pub fn points_here(_method: Method) -> Result<bool, String> {
+        let entry = std::fs::read_to_string(entry_path()?).map_err(|e| e.to_string())?;
+        Ok(entry.contains(&format!("Exec=\"{}\"", exe()?)))
+    }
+
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    pub fn set(method: Method, enabled: bool) -> Result<(), String> {
+        #[cfg(target_os = "linux")]
+        if method == Method::BackgroundPortal {
+            let granted = crate::portal::request_background(enabled)?;
+            return if enabled && !granted {
+                Err("The desktop did not allow CrewHub to start at login".to_string())
+            } else {
+                Ok(())
+            };
+        }
+        let path = entry_path()?;
+        if !enabled {
+            return match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+                _ => Ok(()),
+            };
+        }
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName=CrewHub\nExec=\"{}\"\n\
+             X-GNOME-Autostart-enabled=true\nNoDisplay=true\n",
+            exe()?
+        );
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, entry).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{exe, Method};
+    use std::os::windows::process::CommandExt;
+    use std::process::{Command, Output};
+
+    pub fn methods() -> &'static [Method] {
+        &[Method::RunKey, Method::ScheduledTask]
+    }
+
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    const VALUE_NAME: &str = "CrewHub";
+    const TASK_NAME: &str = r"CrewHub\Autostart";
+
+    fn run(program: &str, args: &[&str]) -> Result<Output, String> {
+        Command::new(program)
+            .args(args)
+            // CREATE_NO_WINDOW
+            .creation_flags(0x0800_0000)
+            .output()
+            .map_err(|e| format!("{}: {}", program, e))
+    }
+
+    fn check(output: Output) -> Result<(), String> {
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    }
+
+    fn escape_xml(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    /// Task definition: logon trigger for the current user, limited token,
+    /// no run time limit, starts on battery.
+    fn task_xml() -> Result<String, String> {
+        let user = match (std::env::var("USERDOMAIN"), std::env::var("USERNAME")) {
+            (Ok(domain), Ok(name)) => format!(r"{}\{}", domain, name),
+            (_, Ok(name)) => name,
+            _ => return Err("Unknown user".to_string()),
+        };
+        let user = escape_xml(&user);
+        Ok(format!(
+            r#"<?xml version="1.0" encoding="UTF-16"?>
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <RegistrationInfo><Description>Start CrewHub at login</Description></RegistrationInfo>
+  <Triggers><LogonTrigger><Enabled>true</Enabled><UserId>{user}</UserId></LogonTrigger></Triggers>
+  <Principals>
+    <Principal id="Author">
+      <UserId>{user}</UserId>
+      <LogonType>InteractiveToken</LogonType>
+      <RunLevel>LeastPrivilege</RunLevel>
+    </Principal>
+  </Principals>
+  <Settings>
+    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>
+    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
+    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
+    <ExecutionTimeLimit>PT0S</ExecutionTimeLimit>
+  </Settings>
+  <Actions Context="Author"><Exec><Command>{exe}</Command></Exec></Actions>
+</Task>
+"#,
+            user = user,
+            exe = escape_xml(&exe()?)
+        ))
+    }
+
+    fn create_task() -> Result<(), String> {
+        // schtasks reads the definition from a UTF-16 file.
+        let path = std::env::temp_dir().join("crewhub-autostart-task.xml");
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend(
+            task_xml()?
+                .encode_utf16()
+                .flat_map(|unit| unit.to_le_bytes()),
+        );
+        std::fs::write(&path, bytes).map_err(|e| e.to_string())?;
+        let xml = path.to_string_lossy().to_string();
+        let result = run(
+            "schtasks",
+            &["/Create", "/TN", TASK_NAME, "/XML", &xml, "/F"],
+        );
+        let _ = std::fs::remove_file(&path);
+        check(result?)
+    }
+
+    pub fn is_enabled(method: Method) -> Result<bool, String> {
+        let output = match method {
+            Method::ScheduledTask => run("schtasks", &["/Query", "/TN", TASK_NAME])?,
+            _ => run("reg", &["query", RUN_KEY, "/v", VALUE_NAME])?,
+        };
+        Ok(output.status.success())
+    }
+
+    pub fn points_here(method: Method) -> Result<bool, String> {
+        let (output, exe) = match method {
+            Method::ScheduledTask => (
+                run("schtasks", &["/Query", "/TN", TASK_NAME, "/XML"])?,
+                escape_xml(&exe()?),
+            ),
+            _ => (run("reg", &["query", RUN_KEY, "/v", VALUE_NAME])?, exe()?),
+        };
+        // Paths are case-insensitive here.
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .to_lowercase()
+            .contains(&exe.to_lowercase()))
+    }
+
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    pub fn set(method: Method, enabled: bool) -> Result<(), String> {
+        // Only one method at a time, or CrewHub would be started twice.
+        if method != Method::RunKey || !enabled {
+            let _ = run("reg", &["delete", RUN_KEY, "/v", VALUE_NAME, "/f"]);
+        }
+        if method != Method::ScheduledTask || !enabled {
+            let _ = run("schtasks", &["/Delete", "/TN", TASK_NAME, "/F"]);
+        }
+        if !enabled {
+            return Ok(());
+        }
+        match method {
+            Method::ScheduledTask => create_task(),
+            _ => check(run(
+                "reg",
+                &[
+                    "add",
+                    RUN_KEY,
+                    "/v",
+                    VALUE_NAME,
+                    "/t",
+                    "REG_SZ",
+                    "/d",
+                    &format!("\"{}\"", exe()?),
+                    "/f",
+                ],
+            )?),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{exe, Method};
+    use std::path::{Path, PathBuf};
+
+    pub fn methods() -> &'static [Method] {
+        &[Method::LaunchAgent]
+    }
+
+    const LABEL: &str = "dev.crewhub.autostart";
+
+    fn agent_path() -> Result<PathBuf, String> {
+        let home = std::env::var_os("HOME").ok_or("No home directory")?;
+        Ok(Path::new(&home)
+            .join("Library/LaunchAgents")
+            .join(format!("{}.plist", LABEL)))
+    }
+
+    fn escape_xml(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    pub fn is_enabled(_method: Method) -> Result<bool, String> {
+        Ok(agent_path()?.exists())
+    }
+
+    pub fn points_here(_method: Method) -> Result<bool, String> {
+        let plist = std::fs::read_to_string(agent_path()?).map_err(|e| e.to_string())?;
+        Ok(plist.contains(&format!("<string>{}</string>", escape_xml(&exe()?))))
+    }
+
+    pub fn set(_method: Method, enabled: bool) -> Result<(), String> {
+        let path = agent_path()?;
+        if !enabled {
+            return match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+                _ => Ok(()),
+            };
+        }
+        let plist = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>Label</key><string>{}</string>
+  <key>ProgramArguments</key><array><string>{}</string></array>
+  <key>RunAtLoad</key><true/>
+  <key>ProcessType</key><string>Interactive</string>
+</dict>
+</plist>
+"#,
+            LABEL,
+            escape_xml(&exe()?)
+        );
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, plist).map_err(|e| e.to_string())
+    }
+}
+
+/// Tauri command: whether CrewHub starts at login, and how.
+/// Called from the settings window via `invoke('get_autostart_status')`.
+#[tauri::command]
+pub fn get_autostart_status(app: AppHandle) -> Result<AutostartStatus, String> {
+    status(&app)
+}
+
+/// Tauri command: turn autostart on or off, optionally switching the method
+/// (Windows: `runKey` or `scheduledTask`).
+/// Called from the settings window via `invoke('set_autostart', { enabled, method })`.
+#[tauri::command]
+pub async fn set_autostart(
+    enabled: bool,
+    method: Option<Method>,
+    app: AppHandle,
+) -> Result<AutostartStatus, String> {
+    // The portal may wait for the user to answer a dialog.
+    tauri::async_runtime::spawn_blocking(move || set(enabled, method, &app))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Whether autostart is on but its entry starts another executable (CrewHub
+/// was moved or reinstalled elsewhere). The portal has no entry to inspect.
+pub fn is_stale(app: &AppHandle) -> Result<bool, String> {
+    let status = status(app)?;
+    if !status.enabled || status.method == Method::BackgroundPortal {
+        return Ok(false);
+    }
+    Ok(!platform::points_here(status.method)?)
+}
+
+/// Rewrite the autostart entry for this executable, keeping the method.
+pub fn repair(app: &AppHandle) -> Result<(), String> {
+    set(true, None, app).map(|_| ())
+}
+
+fn set(enabled: bool, method: Option<Method>, app: &AppHandle) -> Result<AutostartStatus, String> {
+    let mut config = config(app);
+    if let Some(method) = method {
+        if !platform::methods().contains(&method) {
+            return Err("This autostart method is not available on this OS".to_string());
+        }
+        config.method = method;
+    }
+    platform::set(config.method, enabled)?;
+    if config.method == Method::BackgroundPortal {
+        config.portal_enabled = enabled;
+    }
+    store::save_json(app, CONFIG_FILE, &config)?;
+    status(app)
+}
@@ -0,0 +1,175 @@
+//! Blocking HTTP helpers for talking to the CrewHub backend from Rust.
+//!
+//! The webviews talk to the backend directly; these helpers are for the
+//! background services (scheduler, webhooks, …) that act on the user's
+//! behalf while no window may be open. Call them off the main thread.
+//! Traffic is counted in [`crate::bandwidth`].
+
+use crate::{backend_auth, bandwidth};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read};
+use std::time::Duration;
+
+/// Something that starts agent work on the backend (used by schedules and webhooks).
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum RunTarget {
+    /// Send a prompt to an agent session.
+    Chat {
+        session_key: String,
+        message: String,
+    },
+    /// Start a backend pipeline.
+    Pipeline { pipeline_id: String },
+}
+
+impl RunTarget {
+    /// Execute the run (blocking) and return the backend's reply.
+    pub fn run(&self) -> Result<Value, String> {
+        match self {
+            RunTarget::Chat {
+                session_key,
+                message,
+            } => send_chat_message(session_key, message),
+            RunTarget::Pipeline { pipeline_id } => run_pipeline(pipeline_id),
+        }
+    }
+}
+
+/// Timeout for a single backend request (agent replies can take a while).
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A request to `url` with the backend's auth provider applied (see [`backend_auth`]).
+fn request(method: &str, url: &str) -> ureq::Request {
+    let agent =
+        backend_auth::configure(url, ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT)).build();
+    backend_auth::authorize(url, agent.request(method, url))
+}
+
+fn url(base: &str, path: &str) -> String {
+    format!("{}{}", base.trim_end_matches('/'), path)
+}
+
+fn read_response(
+    url: &str,
+    sent: usize,
+    result: Result<ureq::Response, ureq::Error>,
+) -> Result<Value, String> {
+    match result {
+        Ok(response) => {
+            let body = response.into_string().map_err(|e| e.to_string())?;
+            bandwidth::record(url, sent as u64, body.len() as u64);
+            if body.trim().is_empty() {
+                Ok(Value::Null)
+            } else {
+                serde_json::from_str(&body).map_err(|e| format!("Invalid backend response: {}", e))
+            }
+        }
+        Err(ureq::Error::Status(code, response)) => {
+            let detail = response.into_string().unwrap_or_default();
+            bandwidth::record(url, sent as u64, detail.len() as u64);
+            Err(format!("Backend returned {}: {}", code, detail))
+        }
+        Err(e) => Err(format!("Backend unreachable: {}", e)),
+    }
+}
+
+/// Open a backend's SSE stream (`/api/events`) without a read timeout.
+pub fn open_event_stream(base: &str) -> Result<impl Read + Send, String> {
+    let url = url(base, "/api/events");
+    let agent = ureq::AgentBuilder::new().timeout_connect(Duration::from_secs(5));
+    backend_auth::authorize(&url, backend_auth::configure(&url, agent).build().get(&url))
+        .set("Accept", "text/event-stream")
+        .call()
+        .map(|response| bandwidth::counted(&url, 0, response.into_reader()))
+        .map_err(|e| e.to_string())
+}
+
+/// POST a JSON body to the primary backend and parse the JSON reply.
+pub fn post_json(path: &str, body: &Value) -> Result<Value, String> {
+    post_json_to(&crate::backend_url(), path, body)
+}
+
+/// POST a JSON body to the backend at `base` and parse the JSON reply.
+pub fn post_json_to(base: &str, path: &str, body: &Value) -> Result<Value, String> {
+    let url = url(base, path);
+    let body = body.to_string();
+    read_response(
+        &url,
+        body.len(),
+        request("POST", &url)
+            .set("Content-Type", "application/json")
+            .send_string(&body),
+    )
+}
+
+/// POST a JSON body with the caller's `X-API-Key` (sessions live in the frontend).
+pub fn post_json_as(
+    base: &str,
+    path: &str,
+    body: &Value,
+    api_key: Option<&str>,
+) -> Result<Value, String> {
+    let url = url(base, path);
+    let body = body.to_string();
+    let mut request = request("POST", &url).set("Content-Type", "application/json");
+    if let Some(key) = api_key.filter(|key| !key.is_empty()) {
+        request = request.set("X-API-Key", key);
+    }
+    read_response(&url, body.len(), request.send_string(&body))
+}
+
+/// GET a path from the backend at `base` and parse the JSON reply.
+pub fn get_json_from(base: &str, path: &str) -> Result<Value, String> {
+    let url = url(base, path);
+    read_response(&url, 0, request("GET", &url).call())
+}
+
+/// Send a message to an agent session (non-streaming) and return the reply payload.
+pub fn send_chat_message(session_key: &str, message: &str) -> Result<Value, String> {
+    post_json(
+        &format!("/api/chat/{}/send", urlencode(session_key)),
+        &serde_json::json!({ "message": message }),
+    )
+}
+
+/// Start a pipeline run.
+pub fn run_pipeline(pipeline_id: &str) -> Result<Value, String> {
+    post_json(
+        &format!("/api/pipelines/{}/run", urlencode(pipeline_id)),
+        &Value::Null,
+    )
+}
+
+/// Percent-encode a single path segment (session keys contain `:`).
+pub fn urlencode(segment: &str) -> String {
+    url::form_urlencoded::byte_serialize(segment.as_bytes()).collect()
+}
+
+/// Minimal `text/event-stream` parser: calls `on_event(event, data)` per event.
+pub fn read_sse_stream(reader: impl Read, mut on_event: impl FnMut(&str, &str)) {
+    let mut event = String::new();
+    let mut data = String::new();
+    for line in BufReader::new(reader).lines() {
+        let Ok(line) = line else { break };
+        if line.is_empty() {
+            if !data.is_empty() {
+                on_event(if event.is_empty() { "message" } else { &event }, &data);
+            }
+            event.clear();
+            data.clear();
+        } else if let Some(value) = line.strip_prefix("event:") {
+            event = value.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("data:") {
+            if !data.is_empty() {
+                data.push('\n');
+            }
+            data.push_str(value.strip_prefix(' ').unwrap_or(value));
+        }
+    }
+}
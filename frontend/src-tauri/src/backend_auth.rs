@@ -0,0 +1,722 @@
+//! Authentication providers for backend connections.
+//!
+//! Each backend profile ([`crate::federation`]) uses one provider, chosen in
+//! `backend-auth.json`; enterprises front their backends differently:
+//! - `none` (default): the backend is reachable as is;
+//! - `token`: a static token, sent as `Authorization: Bearer …` or in a
+//!   named header (API key gateways);
+//! - `oidc`: the OAuth 2.0 device authorization grant (RFC 8628) against the
+//!   company's identity provider. Signing in opens the verification page and
+//!   returns the user code; the access token is refreshed with the refresh
+//!   token before it expires;
+//! - `clientCertificate`: mTLS with a PEM certificate chain and private key;
+//! - `headers`: fixed headers an auth gateway expects (service tokens for an
+//!   identity-aware proxy or Cloudflare Access).
+//!
+//! Tokens and header values are kept in the OS keychain, never in the app
+//! data dir. Providers apply to the requests Rust makes to a backend
+//! ([`crate::backend`], the event bridge, attachment downloads), matched by
+//! URL origin. The frontend gets a backend's current headers from
+//! `get_backend_auth_headers`; client certificates can't be handed to the
+//! webviews, so a backend behind mTLS has to let the webviews through
+//! another way (e.g. a session cookie). Changes are emitted as
+//! `backend-auth-changed`.
+
+use crate::{bridge, external_links, store};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Persisted provider config (app data dir).
+const CONFIG_FILE: &str = "backend-auth.json";
+
+/// Keychain service; the account is `backend-auth:<backend id>`.
+const KEYCHAIN_SERVICE: &str = "dev.crewhub.app";
+
+/// Refresh an OIDC access token this long before it expires.
+const REFRESH_MARGIN: i64 = 60;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+static CONFIG: Mutex<Option<BTreeMap<String, AuthProvider>>> = Mutex::new(None);
+
+/// Backend id and URL origin of every profile (see [`set_profiles`]).
+static ORIGINS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Secrets read from the keychain, by backend id.
+static SECRETS: Mutex<Option<HashMap<String, Secrets>>> = Mutex::new(None);
+
+/// TLS configs with client certificates, by backend id.
+static TLS: Mutex<Option<HashMap<String, Arc<rustls::ClientConfig>>>> = Mutex::new(None);
+
+/// Last error per backend (a failed refresh or an unreadable certificate).
+static ERRORS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Serializes token refreshes.
+static REFRESHING: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum AuthProvider {
+    #[default]
+    None,
+    Token {
+        /// Header carrying the raw token; `Authorization: Bearer` when unset.
+        header: Option<String>,
+    },
+    Oidc {
+        /// Issuer URL (its `/.well-known/openid-configuration` is read).
+        issuer: String,
+        client_id: String,
+        #[serde(default)]
+        scope: Option<String>,
+        #[serde(default)]
+        audience: Option<String>,
+    },
+    ClientCertificate {
+        /// PEM file with the certificate (chain).
+        certificate: PathBuf,
+        /// PEM file with the private key (PKCS#8, PKCS#1 or SEC1).
+        key: PathBuf,
+    },
+    Headers {
+        /// Header names; the values are in the keychain.
+        names: Vec<String>,
+    },
+}
+
+impl AuthProvider {
+    fn kind(&self) -> &'static str {
+        match self {
+            AuthProvider::None => "none",
+            AuthProvider::Token { .. } => "token",
+            AuthProvider::Oidc { .. } => "oidc",
+            AuthProvider::ClientCertificate { .. } => "clientCertificate",
+            AuthProvider::Headers { .. } => "headers",
+        }
+    }
+}
+
+/// Keychain entry of one backend.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Secrets {
+    token: Option<String>,
+    headers: BTreeMap<String, String>,
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    /// Unix seconds.
+    expires_at: Option<i64>,
+}
+
+/// Secret part of `set_backend_auth` (only what the provider needs).
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AuthSecrets {
+    token: Option<String>,
+    headers: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendAuthStatus {
+    provider: AuthProvider,
+    /// The provider has what it needs (a token, a signed-in session, readable files).
+    ready: bool,
+    /// When the OIDC access token expires (Unix seconds).
+    expires_at: Option<i64>,
+    error: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceCode {
+    user_code: String,
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    /// Seconds until the code expires.
+    expires_in: u64,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let loaded: BTreeMap<String, AuthProvider> = store::load_json(app, CONFIG_FILE);
+    let configured: Vec<String> = loaded
+        .iter()
+        .filter(|(_, provider)| !matches!(provider, AuthProvider::None))
+        .map(|(id, _)| id.clone())
+        .collect();
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = Some(loaded);
+    }
+    // Read the keychain up front, so the first request doesn't wait on a prompt.
+    std::thread::spawn(move || {
+        for id in configured {
+            secrets(&id);
+        }
+    });
+}
+
+/// Remember which origin belongs to which backend (called when profiles change).
+pub fn set_profiles(profiles: &[crate::federation::BackendProfile]) {
+    let origins = profiles
+        .iter()
+        .filter_map(|profile| Some((profile.id.clone(), origin(&profile.url)?)))
+        .collect();
+    if let Ok(mut current) = ORIGINS.lock() {
+        *current = origins;
+    }
+}
+
+fn origin(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()
+        .map(|url| url.origin().ascii_serialization())
+}
+
+fn backend_for(url: &str) -> Option<String> {
+    let origin = origin(url)?;
+    ORIGINS
+        .lock()
+        .ok()?
+        .iter()
+        .find(|(_, known)| *known == origin)
+        .map(|(id, _)| id.clone())
+}
+
+fn provider(backend_id: &str) -> AuthProvider {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.as_ref()?.get(backend_id).cloned())
+        .unwrap_or_default()
+}
+
+fn set_error(backend_id: &str, error: Option<String>) {
+    if let Ok(mut errors) = ERRORS.lock() {
+        match error {
+            Some(e) => {
+                if errors.get(backend_id) != Some(&e) {
+                    eprintln!("[CrewHub] Backend auth for {}: {}", backend_id, e);
+                }
+                errors.insert(backend_id.to_string(), e);
+            }
+            None => {
+                errors.remove(backend_id);
+            }
+        }
+    }
+}
+
+/// Run a keychain call on its own thread (see [`crate::license`]).
+fn keychain<T: Send + 'static>(
+    backend_id: &str,
+    f: impl FnOnce(keyring::Entry) -> keyring::Result<T> + Send + 'static,
+) -> Result<T, String> {
+    let account = format!("backend-auth:{}", backend_id);
+    std::thread::spawn(move || f(keyring::Entry::new(KEYCHAIN_SERVICE, &account)?))
+        .join()
+        .map_err(|_| "Keychain access failed".to_string())?
+        .map_err(|e| e.to_string())
+}
+
+fn secrets(backend_id: &str) -> Secrets {
+    if let Some(cached) = SECRETS
+        .lock()
+        .ok()
+        .and_then(|secrets| secrets.as_ref()?.get(backend_id).cloned())
+    {
+        return cached;
+    }
+    let loaded: Secrets = match keychain(backend_id, |entry| entry.get_password()) {
+        Ok(raw) => serde_json::from_str(&raw).unwrap_or_default(),
+        Err(e) => {
+            if !e.contains("No matching entry") {
+                eprintln!("[CrewHub] Failed to read backend credentials: {}", e);
+            }
+            Secrets::default()
+        }
+    };
+    if let Ok(mut secrets) = SECRETS.lock() {
+        secrets
+            .get_or_insert_with(HashMap::new)
+            .insert(backend_id.to_string(), loaded.clone());
+    }
+    loaded
+}
+
+fn save_secrets(backend_id: &str, secrets: Secrets) -> Result<(), String> {
+    let raw = serde_json::to_string(&secrets).map_err(|e| e.to_string())?;
+    keychain(backend_id, move |entry| entry.set_password(&raw))?;
+    if let Ok(mut cached) = SECRETS.lock() {
+        cached
+            .get_or_insert_with(HashMap::new)
+            .insert(backend_id.to_string(), secrets);
+    }
+    Ok(())
+}
+
+fn clear_secrets(backend_id: &str) {
+    match keychain(backend_id, |entry| entry.delete_credential()) {
+        Ok(()) => {}
+        Err(e) if e.contains("No matching entry") => {}
+        Err(e) => eprintln!("[CrewHub] Failed to clear backend credentials: {}", e),
+    }
+    if let Ok(mut cached) = SECRETS.lock() {
+        if let Some(cached) = cached.as_mut() {
+            cached.remove(backend_id);
+        }
+    }
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build()
+}
+
+fn json_body(response: ureq::Response) -> Result<Value, String> {
+    let body = response.into_string().map_err(|e| e.to_string())?;
+    serde_json::from_str(&body).map_err(|e| e.to_string())
+}
+
+/// The endpoints of an OIDC issuer.
+fn discover(issuer: &str) -> Result<Value, String> {
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        issuer.trim_end_matches('/')
+    );
+    agent()
+        .get(&url)
+        .call()
+        .map_err(|e| format!("OIDC discovery failed: {}", e))
+        .and_then(|response| {
+            json_body(response).map_err(|e| format!("Invalid OIDC configuration: {}", e))
+        })
+}
+
+fn endpoint(discovery: &Value, name: &str) -> Result<String, String> {
+    discovery[name]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("The identity provider has no {}", name))
+}
+
+/// POST a form to a token endpoint; OAuth errors come back as `Err(code)`.
+fn token_request(url: &str, form: &[(&str, &str)]) -> Result<Value, String> {
+    match agent().post(url).send_form(form) {
+        Ok(response) => json_body(response),
+        Err(ureq::Error::Status(_, response)) => {
+            let body = json_body(response).unwrap_or(Value::Null);
+            Err(body["error"]
+                .as_str()
+                .unwrap_or("token_request_failed")
+                .to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Keep the tokens of a token endpoint reply (a missing refresh token keeps the old one).
+fn store_tokens(backend_id: &str, reply: &Value) -> Result<(), String> {
+    let access_token = reply["access_token"]
+        .as_str()
+        .ok_or("The identity provider sent no access token")?;
+    let mut secrets = secrets(backend_id);
+    secrets.access_token = Some(access_token.to_string());
+    if let Some(refresh) = reply["refresh_token"].as_str() {
+        secrets.refresh_token = Some(refresh.to_string());
+    }
+    secrets.expires_at = reply["expires_in"]
+        .as_i64()
+        .map(|seconds| chrono::Local::now().timestamp() + seconds);
+    save_secrets(backend_id, secrets)
+}
+
+/// A valid OIDC access token, refreshed if it is about to expire.
+fn access_token(backend_id: &str, issuer: &str, client_id: &str) -> Result<String, String> {
+    let _one_at_a_time = REFRESHING.lock();
+    let secrets = secrets(backend_id);
+    let now = chrono::Local::now().timestamp();
+    if let Some(token) = &secrets.access_token {
+        if secrets
+            .expires_at
+            .is_none_or(|expires| expires - REFRESH_MARGIN > now)
+        {
+            return Ok(token.clone());
+        }
+    }
+    let refresh = secrets
+        .refresh_token
+        .ok_or("Not signed in (sign in again)")?;
+    let token_endpoint = endpoint(&discover(issuer)?, "token_endpoint")?;
+    let reply = token_request(
+        &token_endpoint,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &refresh),
+            ("client_id", client_id),
+        ],
+    )
+    .map_err(|e| format!("Token refresh failed ({}); sign in again", e))?;
+    store_tokens(backend_id, &reply)?;
+    reply["access_token"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "The identity provider sent no access token".to_string())
+}
+
+/// Headers the provider of `backend_id` adds to requests.
+fn headers(backend_id: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut headers = BTreeMap::new();
+    match provider(backend_id) {
+        AuthProvider::None | AuthProvider::ClientCertificate { .. } => {}
+        AuthProvider::Token { header } => {
+            let token = secrets(backend_id).token.ok_or("No token configured")?;
+            match header.filter(|header| !header.is_empty()) {
+                Some(header) => headers.insert(header, token),
+                None => headers.insert("Authorization".to_string(), format!("Bearer {}", token)),
+            };
+        }
+        AuthProvider::Oidc {
+            issuer, client_id, ..
+        } => {
+            let token = access_token(backend_id, &issuer, &client_id)?;
+            headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+        }
+        AuthProvider::Headers { names } => {
+            let values = secrets(backend_id).headers;
+            for name in names {
+                if let Some(value) = values.get(&name) {
+                    headers.insert(name, value.clone());
+                }
+            }
+        }
+    }
+    Ok(headers)
+}
+
+/// Add the auth headers of the backend `url` belongs to (none for other URLs).
+pub fn authorize(url: &str, mut request: ureq::Request) -> ureq::Request {
+    let Some(backend_id) = backend_for(url) else {
+        return request;
+    };
+    match headers(&backend_id) {
+        Ok(headers) => {
+            for (name, value) in headers {
+                request = request.set(&name, &value);
+            }
+        }
+        Err(e) => set_error(&backend_id, Some(e)),
+    }
+    request
+}
+
+fn tls_config(certificate: &PathBuf, key: &PathBuf) -> Result<rustls::ClientConfig, String> {
+    let chain = CertificateDer::pem_file_iter(certificate)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Unreadable certificate {}: {}", certificate.display(), e))?;
+    if chain.is_empty() {
+        return Err(format!("No certificate in {}", certificate.display()));
+    }
+    let key = PrivateKeyDer::from_pem_file(key)
+        .map_err(|e| format!("Unreadable private key {}: {}", key.display(), e))?;
+    let roots = rustls::RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .with_root_certificates(roots)
+        .with_client_auth_cert(chain, key)
+        .map_err(|e| format!("Invalid client certificate: {}", e))
+}
+
+/// Add the client certificate of the backend `url` belongs to, if it uses one.
+pub fn configure(url: &str, builder: ureq::AgentBuilder) -> ureq::AgentBuilder {
+    let Some(backend_id) = backend_for(url) else {
+        return builder;
+    };
+    let AuthProvider::ClientCertificate { certificate, key } = provider(&backend_id) else {
+        return builder;
+    };
+    let cached = TLS
+        .lock()
+        .ok()
+        .and_then(|tls| tls.as_ref()?.get(&backend_id).cloned());
+    let config = match cached {
+        Some(config) => config,
+        None => match tls_config(&certificate, &key) {
+            Ok(config) => {
+                let config = Arc::new(config);
+                if let Ok(mut tls) = TLS.lock() {
+                    tls.get_or_insert_with(HashMap::new)
+                        .insert(backend_id.clone(), config.clone());
+                }
+                set_error(&backend_id, None);
+                config
+            }
+            Err(e) => {
+                set_error(&backend_id, Some(e));
+                return builder;
+            }
+        },
+    };
+    builder.tls_config(config)
+}
+
+fn status(backend_id: &str) -> BackendAuthStatus {
+    let provider = provider(backend_id);
+    let secrets = secrets(backend_id);
+    let ready = match &provider {
+        AuthProvider::None => true,
+        AuthProvider::Token { .. } => secrets.token.is_some(),
+        AuthProvider::Oidc { .. } => {
+            secrets.refresh_token.is_some() || secrets.access_token.is_some()
+        }
+        AuthProvider::ClientCertificate { certificate, key } => {
+            certificate.is_file() && key.is_file()
+        }
+        AuthProvider::Headers { names } => names.iter().all(|n| secrets.headers.contains_key(n)),
+    };
+    BackendAuthStatus {
+        expires_at: matches!(provider, AuthProvider::Oidc { .. })
+            .then_some(secrets.expires_at)
+            .flatten(),
+        error: ERRORS
+            .lock()
+            .ok()
+            .and_then(|errors| errors.get(backend_id).cloned()),
+        provider,
+        ready,
+    }
+}
+
+fn changed<R: Runtime>(app: &AppHandle<R>, backend_id: &str) {
+    let _ = app.emit(
+        "backend-auth-changed",
+        json!({ "backendId": backend_id, "status": status(backend_id) }),
+    );
+    // Reconnect the event streams with the new credentials.
+    bridge::resume(app);
+}
+
+/// Poll the token endpoint until the user approves (or the code expires).
+fn poll_device_code<R: Runtime>(
+    app: &AppHandle<R>,
+    backend_id: &str,
+    token_endpoint: &str,
+    client_id: &str,
+    device_code: &str,
+    mut interval: u64,
+    expires_in: u64,
+) {
+    let deadline = std::time::Instant::now() + Duration::from_secs(expires_in);
+    while std::time::Instant::now() < deadline {
+        std::thread::sleep(Duration::from_secs(interval));
+        let reply = token_request(
+            token_endpoint,
+            &[
+                ("grant_type", DEVICE_CODE_GRANT),
+                ("device_code", device_code),
+                ("client_id", client_id),
+            ],
+        );
+        match reply {
+            Ok(reply) => {
+                let result = store_tokens(backend_id, &reply);
+                set_error(backend_id, result.err());
+                println!("[CrewHub] Signed in to backend {}", backend_id);
+                changed(app, backend_id);
+                return;
+            }
+            Err(e) if e == "authorization_pending" => {}
+            // RFC 8628 §3.5: back off by five seconds.
+            Err(e) if e == "slow_down" => interval += 5,
+            Err(e) => {
+                set_error(backend_id, Some(format!("Sign-in failed: {}", e)));
+                changed(app, backend_id);
+                return;
+            }
+        }
+    }
+    set_error(backend_id, Some("Sign-in timed out".to_string()));
+    changed(app, backend_id);
+}
+
+/// Tauri command: provider and state of every backend that has one.
+/// Called from the settings window via `invoke('get_backend_auth')`.
+#[tauri::command]
+pub async fn get_backend_auth() -> Result<BTreeMap<String, BackendAuthStatus>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let ids: Vec<String> = CONFIG
+            .lock()
+            .ok()
+            .and_then(|config| config.as_ref().map(|c| c.keys().cloned().collect()))
+            .unwrap_or_default();
+        ids.into_iter()
+            .map(|id| (id.clone(), status(&id)))
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Tauri command: choose a backend's provider and store its secrets (token or header values).
+/// Called from the settings window via `invoke('set_backend_auth', { backendId, provider, secrets })`.
+#[tauri::command]
+pub async fn set_backend_auth(
+    backend_id: String,
+    provider: AuthProvider,
+    secrets: Option<AuthSecrets>,
+    app: AppHandle,
+) -> Result<BackendAuthStatus, String> {
+    if let AuthProvider::Oidc { issuer, .. } = &provider {
+        url::Url::parse(issuer).map_err(|e| format!("Invalid issuer URL: {}", e))?;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut config = CONFIG
+            .lock()
+            .ok()
+            .and_then(|config| config.clone())
+            .unwrap_or_default();
+        let previous = config.get(&backend_id).map(|p| p.kind()).unwrap_or("none");
+        if previous != provider.kind() {
+            clear_secrets(&backend_id);
+        }
+        if let Some(new) = secrets {
+            let mut stored = self::secrets(&backend_id);
+            if new.token.is_some() {
+                stored.token = new.token;
+            }
+            if !new.headers.is_empty() {
+                stored.headers = new.headers;
+            }
+            save_secrets(&backend_id, stored)?;
+        }
+        match provider {
+            AuthProvider::None => config.remove(&backend_id),
+            provider => config.insert(backend_id.clone(), provider),
+        };
+        store::save_json(&app, CONFIG_FILE, &config)?;
+        if let Ok(mut current) = CONFIG.lock() {
+            *current = Some(config);
+        }
+        if let Ok(mut tls) = TLS.lock() {
+            if let Some(tls) = tls.as_mut() {
+                tls.remove(&backend_id);
+            }
+        }
+        set_error(&backend_id, None);
+        changed(&app, &backend_id);
+        Ok(status(&backend_id))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: start an OIDC device-code sign-in; opens the verification page and returns the code to show.
+/// Called from the settings window via `invoke('start_backend_sign_in', { backendId })`.
+#[tauri::command]
+pub async fn start_backend_sign_in(
+    backend_id: String,
+    app: AppHandle,
+) -> Result<DeviceCode, String> {
+    let AuthProvider::Oidc {
+        issuer,
+        client_id,
+        scope,
+        audience,
+    } = provider(&backend_id)
+    else {
+        return Err("This backend doesn't use OIDC sign-in".to_string());
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let discovery = discover(&issuer)?;
+        let device_endpoint = endpoint(&discovery, "device_authorization_endpoint")?;
+        let token_endpoint = endpoint(&discovery, "token_endpoint")?;
+        let scope = scope.unwrap_or_else(|| "openid offline_access".to_string());
+        let mut form = vec![("client_id", client_id.as_str()), ("scope", scope.as_str())];
+        if let Some(audience) = &audience {
+            form.push(("audience", audience));
+        }
+        let reply = token_request(&device_endpoint, &form)
+            .map_err(|e| format!("Sign-in could not start: {}", e))?;
+        let field = |name: &str| reply[name].as_str().map(str::to_string);
+        let device_code =
+            field("device_code").ok_or("The identity provider sent no device code")?;
+        let code = DeviceCode {
+            user_code: field("user_code").ok_or("The identity provider sent no user code")?,
+            verification_uri: field("verification_uri")
+                .or_else(|| field("verification_url"))
+                .ok_or("The identity provider sent no verification URL")?,
+            verification_uri_complete: field("verification_uri_complete"),
+            expires_in: reply["expires_in"].as_u64().unwrap_or(600),
+        };
+        let page = code
+            .verification_uri_complete
+            .as_deref()
+            .unwrap_or(&code.verification_uri);
+        if let Ok(page) = url::Url::parse(page) {
+            if let Err(e) = external_links::open_in_browser(&page) {
+                eprintln!("[CrewHub] Failed to open the sign-in page: {}", e);
+            }
+        }
+        let interval = reply["interval"].as_u64().unwrap_or(5).max(1);
+        let expires_in = code.expires_in;
+        std::thread::spawn(move || {
+            poll_device_code(
+                &app,
+                &backend_id,
+                &token_endpoint,
+                &client_id,
+                &device_code,
+                interval,
+                expires_in,
+            )
+        });
+        Ok(code)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: forget a backend's OIDC tokens.
+/// Called from the settings window via `invoke('sign_out_backend', { backendId })`.
+#[tauri::command]
+pub async fn sign_out_backend(
+    backend_id: String,
+    app: AppHandle,
+) -> Result<BackendAuthStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut secrets = secrets(&backend_id);
+        secrets.access_token = None;
+        secrets.refresh_token = None;
+        secrets.expires_at = None;
+        save_secrets(&backend_id, secrets)?;
+        changed(&app, &backend_id);
+        Ok(status(&backend_id))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: headers the frontend should send to a backend (refreshing an OIDC token first).
+/// Called from the frontend via `invoke('get_backend_auth_headers', { backendId })`.
+#[tauri::command]
+pub async fn get_backend_auth_headers(
+    backend_id: String,
+) -> Result<BTreeMap<String, String>, String> {
+    tauri::async_runtime::spawn_blocking(move || headers(&backend_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
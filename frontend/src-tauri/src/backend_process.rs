@@ -0,0 +1,444 @@
+//! The CrewHub backend as a supervised sidecar process.
+//!
+//! Packaged builds ship the backend as a Tauri sidecar (`bundle.externalBin`
+//! with `binaries/crewhub-backend`), which the bundler places next to the app
+//! executable; `CREWHUB_BACKEND_BIN` points at another binary during
+//! development. When one is found and the backend URL is on this machine,
+//! the backend is started on launch with `HOST`/`PORT` taken from that URL,
+//! its output going to `backend.log` in the app data dir. Otherwise (a
+//! remote backend, the test harness or demo mode, or no sidecar) nothing is
+//! spawned and only its health is watched.
+//!
+//! A supervisor polls `GET /api/health` and emits `backend-status` to all
+//! windows whenever the [`BackendState`] changes. A sidecar that exits, or
+//! stops answering for [`UNRESPONSIVE_TIMEOUT`], is restarted after a delay
+//! that doubles up to [`MAX_BACKOFF`] and resets once it is healthy again.
+//! On exit (tray "Quit") it is sent `SIGTERM` and killed if it is still
+//! running after [`SHUTDOWN_GRACE`]; on Windows it is killed right away.
+//! A sidecar left running by a crashed run is adopted instead of started
+//! again if it still answers (see [`crate::child_processes`]).
+
+use crate::child_processes::{self, Kind};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// File name of the sidecar (without `.exe`).
+const SIDECAR: &str = "crewhub-backend";
+
+/// Overrides the sidecar binary (development builds).
+const BIN_ENV: &str = "CREWHUB_BACKEND_BIN";
+
+/// Output of the sidecar (app data dir), replaced on every start.
+const LOG_FILE: &str = "backend.log";
+
+/// How often the supervisor checks the process and its health endpoint.
+const TICK: Duration = Duration::from_secs(2);
+
+/// A sidecar that hasn't answered for this long is restarted.
+const UNRESPONSIVE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// First restart delay after a crash; doubles up to [`MAX_BACKOFF`].
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// How long the sidecar may take to exit on its own when CrewHub quits.
+#[cfg(unix)]
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackendState {
+    /// Launched (or first check pending), not answering yet.
+    Starting,
+    /// The health endpoint answers.
+    Ready,
+    /// Was ready (or isn't ours to start) and stopped answering.
+    Unreachable,
+    /// The sidecar exited or hung; a restart is scheduled.
+    Crashed,
+    /// Shut down because CrewHub is quitting.
+    Stopped,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendStatus {
+    state: BackendState,
+    /// Whether CrewHub runs the backend (otherwise it is only watched).
+    managed: bool,
+    url: String,
+    pid: Option<u32>,
+    /// Restarts after crashes since launch.
+    restarts: u32,
+    last_error: Option<String>,
+}
+
+/// App state: the sidecar and what the supervisor knows about it.
+pub struct BackendProcessState {
+    /// Sidecar binary, if the backend is managed.
+    binary: Option<PathBuf>,
+    child: Mutex<Option<Child>>,
+    /// Pid of a sidecar adopted from a crashed run.
+    adopted: Mutex<Option<u32>>,
+    status: Mutex<BackendStatus>,
+    supervision: Mutex<Supervision>,
+    stopping: AtomicBool,
+}
+
+struct Supervision {
+    /// When the state last changed.
+    since: Instant,
+    backoff: Duration,
+    /// When to start the crashed sidecar again.
+    restart_at: Option<Instant>,
+}
+
+fn is_local(url: &str) -> bool {
+    url::Url::parse(url).is_ok_and(|url| {
+        matches!(
+            url.host_str(),
+            Some("localhost" | "127.0.0.1" | "[::1]" | "::1")
+        )
+    })
+}
+
+/// The sidecar binary, if the configured backend is one CrewHub runs.
+pub fn sidecar() -> Option<PathBuf> {
+    sidecar_binary(&crate::backend_url())
+}
+
+fn sidecar_binary(url: &str) -> Option<PathBuf> {
+    if crate::test_harness::url().is_some() || crate::demo::url().is_some() || !is_local(url) {
+        return None;
+    }
+    if let Ok(path) = std::env::var(BIN_ENV) {
+        return Some(PathBuf::from(path));
+    }
+    let name = format!("{}{}", SIDECAR, std::env::consts::EXE_SUFFIX);
+    let path = std::env::current_exe().ok()?.parent()?.join(name);
+    path.is_file().then_some(path)
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let url = crate::backend_url();
+    let binary = sidecar_binary(&url);
+    app.manage(BackendProcessState {
+        status: Mutex::new(BackendStatus {
+            state: BackendState::Starting,
+            managed: binary.is_some(),
+            url,
+            pid: None,
+            restarts: 0,
+            last_error: None,
+        }),
+        binary,
+        child: Mutex::new(None),
+        adopted: Mutex::new(None),
+        supervision: Mutex::new(Supervision {
+            since: Instant::now(),
+            backoff: MIN_BACKOFF,
+            restart_at: None,
+        }),
+        stopping: AtomicBool::new(false),
+    });
+    let state = app.state::<BackendProcessState>();
+    if let Some(pid) = child_processes::adopted(Kind::Backend).filter(|_| state.binary.is_some()) {
+        println!("[CrewHub] Backend already running (pid {})", pid);
+        if let Ok(mut adopted) = state.adopted.lock() {
+            *adopted = Some(pid);
+        }
+        update(app, &state, BackendState::Starting, |status| {
+            status.pid = Some(pid);
+        });
+    } else if state.binary.is_some() {
+        if let Err(e) = spawn(app, &state) {
+            eprintln!("[CrewHub] Failed to start backend: {}", e);
+            crashed(app, &state, e);
+        }
+    }
+    let app = app.clone();
+    std::thread::spawn(move || supervise(&app));
+}
+
+fn spawn<R: Runtime>(app: &AppHandle<R>, state: &BackendProcessState) -> Result<(), String> {
+    let Some(binary) = &state.binary else {
+        return Ok(());
+    };
+    let mut child = state.child.lock().map_err(|e| e.to_string())?;
+    if child.is_some() || state.adopted.lock().is_ok_and(|pid| pid.is_some()) {
+        return Ok(());
+    }
+    let url = url::Url::parse(&current(state).url).map_err(|e| e.to_string())?;
+    let port = url.port_or_known_default().unwrap_or(80);
+    let log = std::fs::File::create(crate::store::data_path(app, LOG_FILE)?)
+        .map_err(|e| e.to_string())?;
+    let errors = log.try_clone().map_err(|e| e.to_string())?;
+    let mut cmd = Command::new(binary);
+    cmd.env("HOST", "127.0.0.1")
+        .env("PORT", port.to_string())
+        .stdin(Stdio::null())
+        .stdout(log)
+        .stderr(errors);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x0800_0000);
+    }
+    let spawned = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", binary.display(), e))?;
+    let pid = spawned.id();
+    child_processes::register(pid, Kind::Backend, "backend", &binary.to_string_lossy());
+    *child = Some(spawned);
+    drop(child);
+    println!("[CrewHub] Backend started (pid {}) on port {}", pid, port);
+    update(app, state, BackendState::Starting, |status| {
+        status.pid = Some(pid);
+    });
+    Ok(())
+}
+
+fn current(state: &BackendProcessState) -> BackendStatus {
+    state
+        .status
+        .lock()
+        .map(|status| status.clone())
+        .unwrap_or_else(|e| e.into_inner().clone())
+}
+
+/// Change the state (emitting `backend-status` if it differs) and apply `edit`.
+fn update<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &BackendProcessState,
+    next: BackendState,
+    edit: impl FnOnce(&mut BackendStatus),
+) {
+    let Ok(mut status) = state.status.lock() else {
+        return;
+    };
+    let changed = status.state != next;
+    status.state = next;
+    edit(&mut status);
+    let snapshot = status.clone();
+    drop(status);
+    if changed {
+        if let Ok(mut supervision) = state.supervision.lock() {
+            supervision.since = Instant::now();
+        }
+        let _ = app.emit("backend-status", snapshot);
+    }
+}
+
+/// Record a crash and schedule the restart.
+fn crashed<R: Runtime>(app: &AppHandle<R>, state: &BackendProcessState, error: String) {
+    if let Ok(mut supervision) = state.supervision.lock() {
+        supervision.restart_at = Some(Instant::now() + supervision.backoff);
+        supervision.backoff = (supervision.backoff * 2).min(MAX_BACKOFF);
+    }
+    update(app, state, BackendState::Crashed, |status| {
+        status.pid = None;
+        status.last_error = Some(error);
+    });
+}
+
+pub fn healthy(url: &str) -> bool {
+    ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(2))
+        .build()
+        .get(&format!("{}/api/health", url.trim_end_matches('/')))
+        .call()
+        .is_ok()
+}
+
+/// Ask the sidecar to exit, then kill it once [`SHUTDOWN_GRACE`] is over.
+fn terminate(mut child: Child) {
+    child_processes::unregister(child.id());
+    #[cfg(unix)]
+    {
+        let _ = Command::new("kill")
+            .args(["-TERM", &child.id().to_string()])
+            .status();
+        let deadline = Instant::now() + SHUTDOWN_GRACE;
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = child.try_wait() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Stop the sidecar, spawned or adopted. Returns whether one was running.
+fn stop_process(state: &BackendProcessState) -> bool {
+    let child = state.child.lock().ok().and_then(|mut child| child.take());
+    let adopted = state.adopted.lock().ok().and_then(|mut pid| pid.take());
+    let running = child.is_some() || adopted.is_some();
+    if let Some(child) = child {
+        terminate(child);
+    }
+    if let Some(pid) = adopted {
+        child_processes::kill(pid);
+    }
+    running
+}
+
+fn supervise<R: Runtime>(app: &AppHandle<R>) {
+    loop {
+        std::thread::sleep(crate::power::interval(app, TICK));
+        let state = app.state::<BackendProcessState>();
+        if state.stopping.load(Ordering::Relaxed) {
+            return;
+        }
+        check(app, &state);
+    }
+}
+
+fn check<R: Runtime>(app: &AppHandle<R>, state: &BackendProcessState) {
+    let managed = state.binary.is_some();
+    if managed {
+        let exited = state.child.lock().ok().and_then(|mut child| {
+            let status = child.as_mut()?.try_wait().ok()??;
+            child_processes::unregister(child.take()?.id());
+            Some(status)
+        });
+        if let Some(status) = exited {
+            eprintln!("[CrewHub] Backend exited: {}", status);
+            crashed(app, state, format!("Backend exited: {}", status));
+        }
+        let adopted_exited = state.adopted.lock().ok().and_then(|mut adopted| {
+            let pid = adopted.take_if(|pid| !child_processes::is_alive(*pid))?;
+            child_processes::unregister(pid);
+            Some(pid)
+        });
+        if adopted_exited.is_some() {
+            eprintln!("[CrewHub] Backend exited");
+            crashed(app, state, "Backend exited".to_string());
+        }
+        let running = state.child.lock().is_ok_and(|child| child.is_some())
+            || state.adopted.lock().is_ok_and(|pid| pid.is_some());
+        if !running {
+            let due = state
+                .supervision
+                .lock()
+                .is_ok_and(|s| s.restart_at.is_some_and(|at| at <= Instant::now()));
+            if !due {
+                return;
+            }
+            if let Ok(mut supervision) = state.supervision.lock() {
+                supervision.restart_at = None;
+            }
+            update(app, state, BackendState::Crashed, |status| {
+                status.restarts += 1
+            });
+            if let Err(e) = spawn(app, state) {
+                eprintln!("[CrewHub] Failed to restart backend: {}", e);
+                crashed(app, state, e);
+            }
+            return;
+        }
+    }
+
+    let status = current(state);
+    if healthy(&status.url) {
+        if status.state != BackendState::Ready {
+            if let Ok(mut supervision) = state.supervision.lock() {
+                supervision.backoff = MIN_BACKOFF;
+                supervision.restart_at = None;
+            }
+            update(app, state, BackendState::Ready, |status| {
+                status.last_error = None;
+            });
+        }
+        return;
+    }
+    if status.state == BackendState::Ready {
+        update(app, state, BackendState::Unreachable, |_| {});
+        return;
+    }
+    if !managed {
+        if status.state == BackendState::Starting {
+            update(app, state, BackendState::Unreachable, |_| {});
+        }
+        return;
+    }
+    let unresponsive = state
+        .supervision
+        .lock()
+        .is_ok_and(|s| s.since.elapsed() > UNRESPONSIVE_TIMEOUT);
+    if unresponsive {
+        eprintln!("[CrewHub] Backend not answering, restarting");
+        stop_process(state);
+        crashed(app, state, "Backend stopped answering".to_string());
+    }
+}
+
+/// Stop the sidecar when the app exits so it doesn't outlive us.
+pub fn shutdown<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<BackendProcessState>() else {
+        return;
+    };
+    state.stopping.store(true, Ordering::Relaxed);
+    if stop_process(&state) {
+        println!("[CrewHub] Backend stopped");
+        update(app, &state, BackendState::Stopped, |status| {
+            status.pid = None
+        });
+    }
+}
+
+/// What is wrong with the sidecar, if CrewHub runs one: a missing binary, or
+/// the error that made it crash.
+pub fn problem<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    let state = app.try_state::<BackendProcessState>()?;
+    let binary = state.binary.as_ref()?;
+    if !binary.is_file() {
+        return Some(format!("Backend sidecar not found at {}", binary.display()));
+    }
+    let status = current(&state);
+    (status.state == BackendState::Crashed).then(|| {
+        status
+            .last_error
+            .unwrap_or_else(|| "The backend sidecar crashed".to_string())
+    })
+}
+
+/// Restart the sidecar now (or re-check a backend CrewHub doesn't run).
+pub fn restart<R: Runtime>(app: &AppHandle<R>) -> Result<BackendStatus, String> {
+    let state = app.state::<BackendProcessState>();
+    if stop_process(&state) {
+        println!("[CrewHub] Restarting backend...");
+    }
+    if let Ok(mut supervision) = state.supervision.lock() {
+        supervision.backoff = MIN_BACKOFF;
+        supervision.restart_at = None;
+    }
+    update(app, &state, BackendState::Starting, |status| {
+        status.pid = None;
+    });
+    spawn(app, &state)?;
+    check(app, &state);
+    Ok(current(&state))
+}
+
+/// Tauri command: whether the backend is up, and whether CrewHub runs it.
+/// Called from the frontend via `invoke('backend_status')`.
+#[tauri::command]
+pub fn backend_status(app: AppHandle) -> BackendStatus {
+    current(&app.state::<BackendProcessState>())
+}
+
+/// Tauri command: restart the sidecar now (or re-check a backend CrewHub doesn't run).
+/// Called from the frontend via `invoke('restart_backend')`.
+#[tauri::command]
+pub async fn restart_backend(app: AppHandle) -> Result<BackendStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || restart(&app))
+        .await
+        .map_err(|e| e.to_string())?
+}
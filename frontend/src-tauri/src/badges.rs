@@ -0,0 +1,298 @@
+//! Badge counts drawn at runtime, and the native badge surfaces.
+//!
+//! The tray icon used to switch between pre-rendered `1`, `2` and `3+`
+//! PNGs. The count is now composited onto the tray icon instead: a pill in
+//! the top-right corner (red for unread, blue for mentions, amber for
+//! approvals, red `!` for failures) with the actual number up to `99+`,
+//! drawn at the tray's pixel size on this platform and display scale. Digits
+//! come from a small built-in bitmap font, drawn supersampled and scaled
+//! down for smooth edges, so no font or image resources are needed.
+//!
+//! The same count goes to the native surfaces, each toggled in
+//! `badges.json`: the Dock badge (macOS) or launcher badge (Linux, Unity
+//! launcher API), and on Windows the taskbar overlay icon of the main
+//! windows plus an optional taskbar flash when the count goes up while no
+//! CrewHub window has focus. They follow the tray: [`apply`] runs on the
+//! tray worker after the counts changed (see [`crate::tray_updates`]).
+
+use crate::store;
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use tauri::image::Image;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "badges.json";
+
+/// Tray icon edge in points (logical pixels) on this platform.
+#[cfg(target_os = "macos")]
+const TRAY_POINTS: f64 = 22.0;
+#[cfg(target_os = "windows")]
+const TRAY_POINTS: f64 = 16.0;
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const TRAY_POINTS: f64 = 24.0;
+
+/// Windows taskbar overlay icons are 16×16 points.
+#[cfg(target_os = "windows")]
+const OVERLAY_POINTS: f64 = 16.0;
+
+/// Largest count shown as a number.
+const MAX_SHOWN: u32 = 99;
+
+/// Edge length of the drawing grid per output pixel.
+const SUPERSAMPLE: u32 = 4;
+
+/// Main windows that get a taskbar overlay or flash.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const MAIN_WINDOWS: [&str; 3] = [
+    crate::CHAT_WINDOW_LABEL,
+    crate::WORLD_WINDOW_LABEL,
+    crate::ZEN_WINDOW_LABEL,
+];
+
+static CONFIG: Mutex<Option<BadgeConfig>> = Mutex::new(None);
+
+/// Count last passed to the native surfaces.
+static SHOWN: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BadgeConfig {
+    /// Dock badge (macOS) / launcher badge (Linux).
+    pub dock: bool,
+    /// Taskbar overlay icon (Windows).
+    pub taskbar: bool,
+    /// Flash the taskbar button when the count goes up (Windows).
+    pub flash: bool,
+}
+
+impl Default for BadgeConfig {
+    fn default() -> Self {
+        Self {
+            dock: true,
+            taskbar: true,
+            flash: false,
+        }
+    }
+}
+
+/// What the badge stands for; sets its color (and `!` for failures).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BadgeStyle {
+    Unread,
+    Mention,
+    Approval,
+    Failure,
+}
+
+impl BadgeStyle {
+    fn color(self) -> Rgba<u8> {
+        match self {
+            BadgeStyle::Unread | BadgeStyle::Failure => Rgba([220, 50, 50, 255]),
+            BadgeStyle::Mention => Rgba([37, 99, 235, 255]),
+            BadgeStyle::Approval => Rgba([217, 119, 6, 255]),
+        }
+    }
+}
+
+fn config() -> BadgeConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let loaded: BadgeConfig = store::load_json(app, CONFIG_FILE);
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = Some(loaded);
+    }
+}
+
+/// Text of the badge: the count, `99+`, or `!` for failures.
+fn label(count: u32, style: BadgeStyle) -> String {
+    match style {
+        BadgeStyle::Failure => "!".to_string(),
+        _ if count > MAX_SHOWN => format!("{}+", MAX_SHOWN),
+        _ => count.to_string(),
+    }
+}
+
+/// 3×5 bitmap glyph, one row per entry (bit 2 is the left column).
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b011, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        '!' => [0b010, 0b010, 0b010, 0b000, 0b010],
+        _ => [0; 5],
+    }
+}
+
+/// A pill `height` pixels tall (at least as wide) with `text` in white; a dot without text.
+fn pill(text: &str, color: Rgba<u8>, height: u32) -> RgbaImage {
+    let height = height.max(1);
+    let h = height * SUPERSAMPLE;
+    // Glyphs take a bit over half the height.
+    let cell = (h * 11 / 20 / 5).max(1);
+    let chars = text.chars().count() as u32;
+    let text_width = (chars * 4 * cell).saturating_sub(cell);
+    let width = (text_width + h / 2).max(h).div_ceil(SUPERSAMPLE);
+    let w = width * SUPERSAMPLE;
+
+    let mut canvas = RgbaImage::new(w, h);
+    let radius = h as f64 / 2.0;
+    let (left, right) = (radius, w as f64 - radius);
+    for (x, y, pixel) in canvas.enumerate_pixels_mut() {
+        let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+        let dx = px - px.clamp(left, right);
+        let dy = py - radius;
+        if dx * dx + dy * dy <= radius * radius {
+            *pixel = color;
+        }
+    }
+    let white = Rgba([255, 255, 255, 255]);
+    let (x0, y0) = ((w - text_width) / 2, (h - 5 * cell) / 2);
+    for (i, c) in text.chars().enumerate() {
+        let gx = x0 + i as u32 * 4 * cell;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                for y in 0..cell {
+                    for x in 0..cell {
+                        canvas.put_pixel(gx + col * cell + x, y0 + row as u32 * cell + y, white);
+                    }
+                }
+            }
+        }
+    }
+    imageops::resize(&canvas, width, height, FilterType::Triangle)
+}
+
+fn pixels<R: Runtime>(app: &AppHandle<R>, points: f64) -> u32 {
+    let scale = app
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map_or(1.0, |monitor| monitor.scale_factor());
+    (points * scale).round().max(1.0) as u32
+}
+
+/// `base` at the tray's pixel size with the badge for `count` in its corner.
+pub fn tray_icon<R: Runtime>(
+    app: &AppHandle<R>,
+    base: &Image<'_>,
+    count: u32,
+    style: BadgeStyle,
+) -> Result<Image<'static>, String> {
+    let size = pixels(app, TRAY_POINTS);
+    let base = RgbaImage::from_raw(base.width(), base.height(), base.rgba().to_vec())
+        .ok_or_else(|| "Invalid tray icon".to_string())?;
+    let mut icon = imageops::resize(&base, size, size, FilterType::Lanczos3);
+    let badge = pill(&label(count, style), style.color(), size * 9 / 16);
+    imageops::overlay(&mut icon, &badge, size as i64 - badge.width() as i64, 0);
+    Ok(Image::new_owned(icon.into_raw(), size, size))
+}
+
+/// `base` at the tray's pixel size with a dot in a project's color in the
+/// bottom-left corner (the count badge keeps the top-right one).
+pub fn project_dot<R: Runtime>(
+    app: &AppHandle<R>,
+    base: &Image<'_>,
+    [r, g, b]: [u8; 3],
+) -> Result<Image<'static>, String> {
+    let size = pixels(app, TRAY_POINTS);
+    let base = RgbaImage::from_raw(base.width(), base.height(), base.rgba().to_vec())
+        .ok_or_else(|| "Invalid tray icon".to_string())?;
+    let mut icon = imageops::resize(&base, size, size, FilterType::Lanczos3);
+    let dot = pill("", Rgba([r, g, b, 255]), size * 7 / 16);
+    imageops::overlay(&mut icon, &dot, 0, (size - dot.height()) as i64);
+    Ok(Image::new_owned(icon.into_raw(), size, size))
+}
+
+/// Square taskbar overlay icon with just the badge.
+#[cfg(target_os = "windows")]
+fn overlay_icon<R: Runtime>(app: &AppHandle<R>, count: u32, style: BadgeStyle) -> Image<'static> {
+    let size = pixels(app, OVERLAY_POINTS);
+    let mut badge = pill(&label(count, style), style.color(), size);
+    if badge.width() > size {
+        let height = (size * size / badge.width()).max(1);
+        badge = imageops::resize(&badge, size, height, FilterType::Triangle);
+    }
+    let mut icon = RgbaImage::new(size, size);
+    imageops::overlay(
+        &mut icon,
+        &badge,
+        (size - badge.width()) as i64 / 2,
+        (size - badge.height()) as i64 / 2,
+    );
+    Image::new_owned(icon.into_raw(), size, size)
+}
+
+/// Show `count` on the enabled native surfaces (`0` clears them).
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+pub fn apply<R: Runtime>(app: &AppHandle<R>, count: u32, style: BadgeStyle) {
+    let config = config();
+    let previous = SHOWN.swap(count, Ordering::SeqCst);
+    // The badge belongs to the app; any window can set it.
+    #[cfg(not(target_os = "windows"))]
+    if let Some(window) = app.webview_windows().into_values().next() {
+        let shown = if config.dock { count } else { 0 };
+        #[cfg(target_os = "macos")]
+        let _ = window.set_badge_label((shown > 0).then(|| label(shown, style)));
+        #[cfg(not(target_os = "macos"))]
+        let _ = window.set_badge_count((shown > 0).then_some(shown as i64));
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let overlay = (config.taskbar && count > 0).then(|| overlay_icon(app, count, style));
+        let focused = app
+            .webview_windows()
+            .values()
+            .any(|window| window.is_focused().unwrap_or(false));
+        for label in MAIN_WINDOWS {
+            let Some(window) = app.get_webview_window(label) else {
+                continue;
+            };
+            let _ = window.set_overlay_icon(overlay.clone());
+            if config.flash && count > previous && !focused && window.is_visible().unwrap_or(false)
+            {
+                let _ =
+                    window.request_user_attention(Some(tauri::UserAttentionType::Informational));
+            }
+        }
+    }
+}
+
+/// Tauri command: which native surfaces show the badge count.
+/// Called from the settings window via `invoke('get_badge_config')`.
+#[tauri::command]
+pub fn get_badge_config() -> BadgeConfig {
+    config()
+}
+
+/// Tauri command: turn the Dock/launcher badge, taskbar overlay or taskbar flash on or off.
+/// Called from the settings window via `invoke('set_badge_config', { config })`.
+#[tauri::command]
+pub fn set_badge_config(config: BadgeConfig, app: AppHandle) -> Result<BadgeConfig, String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config.clone());
+    }
+    crate::refresh_tray_icon(&app);
+    Ok(config)
+}
@@ -0,0 +1,397 @@
+//! Bandwidth accounting and metered-connection mode.
+//!
+//! Requests made from Rust report their body bytes through [`record`]: the
+//! backend helpers in [`crate::backend`] (including the event stream),
+//! attachment downloads and local model downloads. Usage is counted per URL
+//! origin and reported per backend profile ([`crate::federation`]); anything
+//! else is "other". Counters live in `bandwidth-usage.json`, written at most
+//! once a minute. Header bytes and the webviews' own traffic aren't counted.
+//!
+//! Metered mode is `auto`, `on` or `off` (`bandwidth.json`). `auto` follows
+//! the OS where it reports connection cost — NetworkManager's `Metered`
+//! property on Linux, the internet profile's cost on Windows; macOS doesn't
+//! expose it, so `auto` means unmetered there. While metered, large
+//! downloads wait ([`wait_unmetered`]) and windows skip attachment
+//! prefetching (`window.__CREWHUB_METERED__`, `metered-changed`).
+
+use crate::{federation, power, store};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "bandwidth.json";
+/// Persisted counters (app data dir).
+const USAGE_FILE: &str = "bandwidth-usage.json";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+static CONFIG: Mutex<Option<BandwidthConfig>> = Mutex::new(None);
+static USAGE: Mutex<Option<Usage>> = Mutex::new(None);
+/// Counters changed since the last write.
+static DIRTY: AtomicBool = AtomicBool::new(false);
+/// Last OS report (`None`: the OS doesn't say).
+static DETECTED: Mutex<Option<bool>> = Mutex::new(None);
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MeteredMode {
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BandwidthConfig {
+    pub metered: MeteredMode,
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Counter {
+    pub sent: u64,
+    pub received: u64,
+    pub requests: u64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Usage {
+    /// Unix seconds the counters were last reset.
+    since: i64,
+    /// By URL origin (`https://host:port`).
+    origins: BTreeMap<String, Counter>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendUsage {
+    /// `None` for origins that aren't a configured backend.
+    backend_id: Option<String>,
+    name: String,
+    origin: String,
+    #[serde(flatten)]
+    counter: Counter,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeteredStatus {
+    metered: bool,
+    mode: MeteredMode,
+    /// What the OS reports (`null` where it doesn't).
+    detected: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthStatus {
+    since: i64,
+    backends: Vec<BackendUsage>,
+    total: Counter,
+    #[serde(flatten)]
+    metered: MeteredStatus,
+}
+
+fn origin(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    matches!(url.scheme(), "http" | "https").then(|| url.origin().ascii_serialization())
+}
+
+/// Count one request's bytes against the origin of `url`.
+pub fn record(url: &str, sent: u64, received: u64) {
+    add(url, sent, received, 1);
+}
+
+/// Count more bytes of a request already counted (streams, downloads).
+pub fn record_more(url: &str, received: u64) {
+    add(url, 0, received, 0);
+}
+
+fn add(url: &str, sent: u64, received: u64, requests: u64) {
+    let Some(origin) = origin(url) else { return };
+    let Ok(mut usage) = USAGE.lock() else { return };
+    let usage = usage.get_or_insert_with(Usage::default);
+    let counter = usage.origins.entry(origin).or_default();
+    counter.sent += sent;
+    counter.received += received;
+    counter.requests += requests;
+    DIRTY.store(true, Ordering::Relaxed);
+}
+
+/// A reader that counts what is read from it against `url`.
+pub struct Counted<T> {
+    inner: T,
+    url: String,
+}
+
+impl<T: Read> Read for Counted<T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n > 0 {
+            record_more(&self.url, n as u64);
+        }
+        Ok(n)
+    }
+}
+
+/// Count a response body as it is read (the request itself is counted here).
+pub fn counted<T: Read>(url: &str, sent: u64, inner: T) -> Counted<T> {
+    record(url, sent, 0);
+    Counted {
+        inner,
+        url: url.to_string(),
+    }
+}
+
+fn config() -> BandwidthConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+fn metered_status() -> MeteredStatus {
+    let mode = config().metered;
+    let detected = DETECTED.lock().ok().and_then(|detected| *detected);
+    MeteredStatus {
+        metered: is_metered_with(mode, detected),
+        mode,
+        detected,
+    }
+}
+
+/// Whether the connection is treated as metered right now.
+pub fn is_metered() -> bool {
+    metered_status().metered
+}
+
+/// Block until the connection is no longer metered; calls `on_wait` once if it has to wait.
+pub fn wait_unmetered(on_wait: impl FnOnce()) {
+    if !is_metered() {
+        return;
+    }
+    on_wait();
+    while is_metered() {
+        std::thread::sleep(CHECK_INTERVAL);
+    }
+}
+
+/// Register state and start the detector and flush threads.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let loaded: BandwidthConfig = store::load_json(app, CONFIG_FILE);
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = Some(loaded);
+    }
+    let mut usage: Usage = store::load_json(app, USAGE_FILE);
+    if usage.since == 0 {
+        usage.since = chrono::Local::now().timestamp();
+    }
+    if let Ok(mut current) = USAGE.lock() {
+        // Keep whatever was counted before init.
+        if let Some(early) = current.take() {
+            for (origin, counter) in early.origins {
+                let total = usage.origins.entry(origin).or_default();
+                total.sent += counter.sent;
+                total.received += counter.received;
+                total.requests += counter.requests;
+            }
+        }
+        *current = Some(usage);
+    }
+
+    let detector = app.clone();
+    std::thread::spawn(move || loop {
+        if !power::is_suspended() {
+            detect(&detector);
+        }
+        std::thread::sleep(power::interval(&detector, CHECK_INTERVAL));
+    });
+    let flusher = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(FLUSH_INTERVAL);
+        flush(&flusher);
+    });
+}
+
+fn flush<R: Runtime>(app: &AppHandle<R>) {
+    if !DIRTY.swap(false, Ordering::Relaxed) {
+        return;
+    }
+    let Some(usage) = USAGE.lock().ok().and_then(|usage| usage.clone()) else {
+        return;
+    };
+    if let Err(e) = store::save_json(app, USAGE_FILE, &usage) {
+        eprintln!("[CrewHub] Failed to save bandwidth usage: {}", e);
+    }
+}
+
+fn detect<R: Runtime>(app: &AppHandle<R>) {
+    let detected = platform::metered();
+    let changed = match DETECTED.lock() {
+        Ok(mut current) if *current != detected => {
+            let mode = config().metered;
+            let before = is_metered_with(mode, *current);
+            *current = detected;
+            before != is_metered_with(mode, detected)
+        }
+        _ => false,
+    };
+    if changed {
+        announce(app);
+    }
+}
+
+fn is_metered_with(mode: MeteredMode, detected: Option<bool>) -> bool {
+    match mode {
+        MeteredMode::Auto => detected.unwrap_or(false),
+        MeteredMode::On => true,
+        MeteredMode::Off => false,
+    }
+}
+
+fn metered_script(metered: bool) -> String {
+    format!("window.__CREWHUB_METERED__ = {};", metered)
+}
+
+fn announce<R: Runtime>(app: &AppHandle<R>) {
+    let status = metered_status();
+    println!("[CrewHub] Metered connection: {}", status.metered);
+    for window in app.webview_windows().values() {
+        let _ = window.eval(metered_script(status.metered));
+    }
+    let _ = app.emit("metered-changed", &status);
+}
+
+/// Init script part of every window.
+pub fn init_script() -> String {
+    metered_script(is_metered())
+}
+
+fn status<R: Runtime>(app: &AppHandle<R>) -> BandwidthStatus {
+    let usage = USAGE
+        .lock()
+        .ok()
+        .and_then(|usage| usage.clone())
+        .unwrap_or_default();
+    let profiles = federation::enabled(app);
+    let mut total = Counter::default();
+    let backends = usage
+        .origins
+        .into_iter()
+        .map(|(origin, counter)| {
+            total.sent += counter.sent;
+            total.received += counter.received;
+            total.requests += counter.requests;
+            let profile = profiles
+                .iter()
+                .find(|profile| self::origin(&profile.url).as_deref() == Some(origin.as_str()));
+            BackendUsage {
+                backend_id: profile.map(|profile| profile.id.clone()),
+                name: profile.map_or_else(|| origin.clone(), |profile| profile.name.clone()),
+                origin,
+                counter,
+            }
+        })
+        .collect();
+    BandwidthStatus {
+        since: usage.since,
+        backends,
+        total,
+        metered: metered_status(),
+    }
+}
+
+/// Tauri command: bytes sent/received per backend and the metered state.
+/// Called from the settings window via `invoke('get_bandwidth_usage')`.
+#[tauri::command]
+pub fn get_bandwidth_usage(app: AppHandle) -> BandwidthStatus {
+    status(&app)
+}
+
+/// Tauri command: start counting from zero.
+/// Called from the settings window via `invoke('reset_bandwidth_usage')`.
+#[tauri::command]
+pub fn reset_bandwidth_usage(app: AppHandle) -> Result<BandwidthStatus, String> {
+    let usage = Usage {
+        since: chrono::Local::now().timestamp(),
+        origins: BTreeMap::new(),
+    };
+    store::save_json(&app, USAGE_FILE, &usage)?;
+    *USAGE.lock().map_err(|e| e.to_string())? = Some(usage);
+    DIRTY.store(false, Ordering::Relaxed);
+    Ok(status(&app))
+}
+
+/// Tauri command: treat the connection as metered (`on`), unmetered (`off`) or as the OS reports (`auto`).
+/// Called from the settings window via `invoke('set_metered_mode', { mode })`.
+#[tauri::command]
+pub fn set_metered_mode(mode: MeteredMode, app: AppHandle) -> Result<BandwidthStatus, String> {
+    let before = is_metered();
+    let config = BandwidthConfig { metered: mode };
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    *CONFIG.lock().map_err(|e| e.to_string())? = Some(config);
+    if before != is_metered() {
+        announce(&app);
+    }
+    Ok(status(&app))
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use zbus::{Connection, Proxy};
+
+    /// NetworkManager's `NMMetered`: 1 yes, 2 no, 3 guessed yes, 4 guessed no.
+    pub fn metered() -> Option<bool> {
+        let value: u32 = tauri::async_runtime::block_on(async {
+            let connection = Connection::system().await.ok()?;
+            let proxy = Proxy::new(
+                &connection,
+                "org.freedesktop.NetworkManager",
+                "/org/freedesktop/NetworkManager",
+                "org.freedesktop.NetworkManager",
+            )
+            .await
+            .ok()?;
+            proxy.get_property("Metered").await.ok()
+        })?;
+        match value {
+            1 | 3 => Some(true),
+            2 | 4 => Some(false),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::Networking::Connectivity::{NetworkCostType, NetworkInformation};
+
+    pub fn metered() -> Option<bool> {
+        let cost = NetworkInformation::GetInternetConnectionProfile()
+            .ok()?
+            .GetConnectionCost()
+            .ok()?;
+        let over_limit = cost.OverDataLimit().unwrap_or(false) || cost.Roaming().unwrap_or(false);
+        match cost.NetworkCostType().ok()? {
+            NetworkCostType::Fixed | NetworkCostType::Variable => Some(true),
+            NetworkCostType::Unrestricted => Some(over_limit),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+mod platform {
+    pub fn metered() -> Option<bool> {
+        None
+    }
+}
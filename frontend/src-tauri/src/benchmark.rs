@@ -0,0 +1,363 @@
+//! 3D world benchmark and frame-rate telemetry.
+//!
+//! `start_world_benchmark` opens the world window and asks it to fly a
+//! scripted camera path (`benchmark-start`; a window that is still loading
+//! picks the run up with `take_world_benchmark`). While it flies, the page
+//! streams frame durations back in batches (`report_benchmark_frames`) and
+//! Rust samples the app's memory. `finish_world_benchmark` adds the GPU the
+//! page renders on (WebGL's unmasked vendor/renderer) and writes a JSON report
+//! to `benchmarks/` in the app data dir, together with OS, CPU, display and
+//! window details — what is needed to compare "the world is laggy" reports
+//! across hardware. A run the page never finishes is written as incomplete.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Reports directory under the app data dir.
+pub const REPORTS_DIR: &str = "benchmarks";
+/// Camera path flown when none is named.
+const DEFAULT_PATH: &str = "orbit";
+const DEFAULT_DURATION: Duration = Duration::from_secs(30);
+const MAX_DURATION: Duration = Duration::from_secs(300);
+/// Extra time for the window to load and report before a run is abandoned.
+const GRACE: Duration = Duration::from_secs(30);
+const MEMORY_INTERVAL: Duration = Duration::from_millis(500);
+/// Frames slower than this count as a hitch.
+const HITCH_MS: f32 = 50.0;
+/// Frame samples kept per run (about 10 minutes at 144 fps).
+const MAX_FRAMES: usize = 100_000;
+
+static RUN: Mutex<Option<Run>> = Mutex::new(None);
+
+struct Run {
+    request: BenchmarkRequest,
+    started: Instant,
+    frames: Vec<f32>,
+    memory: Vec<u64>,
+}
+
+/// What the world window is asked to do.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkRequest {
+    run_id: String,
+    camera_path: String,
+    duration_ms: u64,
+}
+
+/// GPU details from the page (`WEBGL_debug_renderer_info`).
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GpuInfo {
+    vendor: Option<String>,
+    renderer: Option<String>,
+    /// `webgl2`, `webgl` or `webgpu`.
+    api: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameStats {
+    frames: usize,
+    average_fps: f32,
+    /// Frame times in ms.
+    median_ms: f32,
+    p95_ms: f32,
+    p99_ms: f32,
+    max_ms: f32,
+    /// Average fps of the slowest 1% of frames.
+    low_1_percent_fps: f32,
+    /// Frames slower than 50 ms.
+    hitches: usize,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStats {
+    /// Resident memory of the CrewHub process (webview processes excluded), in bytes.
+    min_bytes: u64,
+    max_bytes: u64,
+    average_bytes: u64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemInfo {
+    os: String,
+    arch: String,
+    cpus: usize,
+    app_version: String,
+    scale_factor: Option<f64>,
+    /// Inner size of the world window in physical pixels.
+    window_size: Option<(u32, u32)>,
+    monitor: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    run_id: String,
+    camera_path: String,
+    started_at: String,
+    duration_ms: u64,
+    /// The page never called `finish_world_benchmark`.
+    incomplete: bool,
+    frames: Option<FrameStats>,
+    memory: Option<MemoryStats>,
+    gpu: GpuInfo,
+    system: SystemInfo,
+    /// Written report.
+    path: String,
+}
+
+/// Resident set size of this process.
+#[cfg(target_os = "linux")]
+fn resident_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(target_os = "windows")]
+fn resident_memory() -> Option<u64> {
+    use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows::Win32::System::Threading::GetCurrentProcess;
+    let mut counters = PROCESS_MEMORY_COUNTERS::default();
+    // SAFETY: the counters struct is sized by `cb`; the pseudo handle needs no closing.
+    unsafe {
+        GetProcessMemoryInfo(
+            GetCurrentProcess(),
+            &mut counters,
+            std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+        )
+    }
+    .ok()?;
+    Some(counters.WorkingSetSize as u64)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn resident_memory() -> Option<u64> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "rss=", "-p", &std::process::id().to_string()])
+        .output()
+        .ok()?;
+    let kb: u64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+fn percentile(sorted: &[f32], p: f32) -> f32 {
+    let index = ((sorted.len() - 1) as f32 * p).round() as usize;
+    sorted[index.min(sorted.len() - 1)]
+}
+
+fn frame_stats(frames: &[f32]) -> Option<FrameStats> {
+    if frames.is_empty() {
+        return None;
+    }
+    let mut sorted = frames.to_vec();
+    sorted.sort_by(f32::total_cmp);
+    let total: f32 = sorted.iter().sum();
+    let slowest = &sorted[sorted.len() - sorted.len().div_ceil(100)..];
+    let slowest_average = slowest.iter().sum::<f32>() / slowest.len() as f32;
+    let fps = |ms: f32| if ms > 0.0 { 1000.0 / ms } else { 0.0 };
+    Some(FrameStats {
+        frames: sorted.len(),
+        average_fps: fps(total / sorted.len() as f32),
+        median_ms: percentile(&sorted, 0.5),
+        p95_ms: percentile(&sorted, 0.95),
+        p99_ms: percentile(&sorted, 0.99),
+        max_ms: sorted[sorted.len() - 1],
+        low_1_percent_fps: fps(slowest_average),
+        hitches: sorted.iter().filter(|ms| **ms > HITCH_MS).count(),
+    })
+}
+
+fn memory_stats(samples: &[u64]) -> Option<MemoryStats> {
+    Some(MemoryStats {
+        min_bytes: *samples.iter().min()?,
+        max_bytes: *samples.iter().max()?,
+        average_bytes: samples.iter().sum::<u64>() / samples.len() as u64,
+    })
+}
+
+fn system_info<R: Runtime>(app: &AppHandle<R>) -> SystemInfo {
+    let window = app.get_webview_window(crate::WORLD_WINDOW_LABEL);
+    SystemInfo {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        app_version: app.package_info().version.to_string(),
+        scale_factor: window.as_ref().and_then(|w| w.scale_factor().ok()),
+        window_size: window
+            .as_ref()
+            .and_then(|w| w.inner_size().ok())
+            .map(|size| (size.width, size.height)),
+        monitor: window
+            .as_ref()
+            .and_then(|w| w.current_monitor().ok().flatten())
+            .map(|monitor| {
+                let size = monitor.size();
+                format!(
+                    "{} ({}×{})",
+                    monitor.name().cloned().unwrap_or_default(),
+                    size.width,
+                    size.height
+                )
+            }),
+    }
+}
+
+/// Write the report for a run that has been taken out of [`RUN`].
+fn write_report<R: Runtime>(
+    app: &AppHandle<R>,
+    run: Run,
+    gpu: GpuInfo,
+    incomplete: bool,
+) -> Result<BenchmarkReport, String> {
+    let started_at = chrono::Local::now()
+        - chrono::Duration::from_std(run.started.elapsed()).unwrap_or_default();
+    let dir = store::data_path(app, REPORTS_DIR)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path: PathBuf = dir.join(format!("world-{}.json", started_at.format("%Y%m%d-%H%M%S")));
+    let report = BenchmarkReport {
+        run_id: run.request.run_id,
+        camera_path: run.request.camera_path,
+        started_at: started_at.to_rfc3339(),
+        duration_ms: run.started.elapsed().as_millis() as u64,
+        incomplete,
+        frames: frame_stats(&run.frames),
+        memory: memory_stats(&run.memory),
+        gpu,
+        system: system_info(app),
+        path: path.to_string_lossy().to_string(),
+    };
+    let json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+    let _ = app.emit("benchmark-finished", report.clone());
+    Ok(report)
+}
+
+/// Sample memory until the run ends; abandon it if the page never finishes.
+fn sample<R: Runtime>(app: AppHandle<R>, run_id: String, limit: Duration) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(MEMORY_INTERVAL);
+        let Ok(mut current) = RUN.lock() else {
+            return;
+        };
+        let expired = match current.as_mut() {
+            Some(run) if run.request.run_id == run_id => {
+                let expired = run.started.elapsed() > limit;
+                if !expired {
+                    run.memory.extend(resident_memory());
+                }
+                expired
+            }
+            _ => return,
+        };
+        if expired {
+            let run = current.take();
+            drop(current);
+            if let Some(run) = run {
+                if let Err(e) = write_report(&app, run, GpuInfo::default(), true) {
+                    eprintln!("[CrewHub] Failed to write benchmark report: {}", e);
+                }
+            }
+            return;
+        }
+    });
+}
+
+/// Tauri command: fly the world window's camera along `cameraPath` for
+/// `durationSecs` (default 30) and record frame timings. Returns the run id;
+/// the report arrives as `benchmark-finished`.
+/// Called from the settings window via `invoke('start_world_benchmark', { cameraPath, durationSecs })`.
+#[tauri::command]
+pub fn start_world_benchmark(
+    app: AppHandle,
+    camera_path: Option<String>,
+    duration_secs: Option<u64>,
+) -> Result<String, String> {
+    let duration = duration_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_DURATION)
+        .clamp(Duration::from_secs(5), MAX_DURATION);
+    let request = BenchmarkRequest {
+        run_id: uuid::Uuid::new_v4().to_string(),
+        camera_path: camera_path.unwrap_or_else(|| DEFAULT_PATH.to_string()),
+        duration_ms: duration.as_millis() as u64,
+    };
+    {
+        let mut current = RUN.lock().map_err(|e| e.to_string())?;
+        if current.is_some() {
+            return Err("A benchmark is already running".to_string());
+        }
+        *current = Some(Run {
+            request: request.clone(),
+            started: Instant::now(),
+            frames: Vec::new(),
+            memory: Vec::new(),
+        });
+    }
+    crate::open_or_focus_world(&app);
+    let _ = app.emit_to(
+        crate::WORLD_WINDOW_LABEL,
+        "benchmark-start",
+        request.clone(),
+    );
+    sample(app, request.run_id.clone(), duration + GRACE);
+    Ok(request.run_id)
+}
+
+/// Tauri command: the benchmark the world window should run, if any.
+/// Called from the world window on load via `invoke('take_world_benchmark')`.
+#[tauri::command]
+pub fn take_world_benchmark() -> Option<BenchmarkRequest> {
+    RUN.lock().ok()?.as_ref().map(|run| run.request.clone())
+}
+
+/// Tauri command: a batch of frame durations (ms) of the running benchmark.
+/// Called from the world window about once a second via `invoke('report_benchmark_frames', { runId, frames })`.
+#[tauri::command]
+pub fn report_benchmark_frames(run_id: String, frames: Vec<f32>) -> Result<(), String> {
+    let mut current = RUN.lock().map_err(|e| e.to_string())?;
+    match current.as_mut() {
+        Some(run) if run.request.run_id == run_id => {
+            let room = MAX_FRAMES.saturating_sub(run.frames.len());
+            run.frames.extend(
+                frames
+                    .into_iter()
+                    .filter(|ms| ms.is_finite() && *ms >= 0.0)
+                    .take(room),
+            );
+            Ok(())
+        }
+        _ => Err("No such benchmark is running".to_string()),
+    }
+}
+
+/// Tauri command: end the benchmark and write its report.
+/// Called from the world window when the camera path is done via `invoke('finish_world_benchmark', { runId, gpu })`.
+#[tauri::command]
+pub fn finish_world_benchmark(
+    app: AppHandle,
+    run_id: String,
+    gpu: Option<GpuInfo>,
+) -> Result<BenchmarkReport, String> {
+    let run = {
+        let mut current = RUN.lock().map_err(|e| e.to_string())?;
+        match current.as_ref() {
+            Some(run) if run.request.run_id == run_id => current.take(),
+            _ => None,
+        }
+    }
+    .ok_or("No such benchmark is running")?;
+    write_report(&app, run, gpu.unwrap_or_default(), false)
+}
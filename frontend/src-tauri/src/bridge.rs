@@ -0,0 +1,290 @@
+//! Backend event bridge.
+//!
+//! Keeps a long-lived subscription to each connected backend's SSE stream
+//! (`/api/events`) from Rust, independent of any open window, and routes the
+//! events that need native handling to the owning subsystem. Reconnects with
+//! exponential backoff when a backend goes away; paused while the system
+//! sleeps (see [`crate::power`]). Which backends are connected is tracked for
+//! the tray tooltip.
+//!
+//! Windows created on demand miss what was emitted before they existed. The
+//! events derived from backend state go through [`emit`], which also keeps
+//! them in a replay buffer: the latest of each state snapshot (agent states,
+//! badge counts, cost, connected backends) plus the last [`REPLAY_LIMIT`]
+//! other events. A window calls `window_ready` once its listeners are in
+//! place and gets them replayed in order, so it doesn't open blank.
+
+use crate::federation::{self, BackendProfile};
+use crate::{approvals, attention, backend, choreography, costs, notifications, pairing, presence};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+
+/// First reconnect delay; doubles up to `MAX_BACKOFF` while the backend is down.
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Events kept for replay besides the state snapshots.
+const REPLAY_LIMIT: usize = 50;
+
+/// Events carrying a full state snapshot; only the latest of each is replayed.
+const SNAPSHOT_EVENTS: &[&str] = &[
+    crate::presence::AGENT_STATES_CHANGED_EVENT,
+    crate::BADGE_COUNTS_CHANGED_EVENT,
+    crate::costs::COST_UPDATED_EVENT,
+    CONNECTIONS_CHANGED_EVENT,
+];
+
+/// Ids of the backends whose event stream is open.
+const CONNECTIONS_CHANGED_EVENT: &str = "backend-connections-changed";
+
+/// App state: one "keep running" flag per active subscription, keyed by backend id and URL.
+#[derive(Default)]
+pub struct BridgeState {
+    streams: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    paused: AtomicBool,
+    /// Ids of backends whose event stream is currently open.
+    connected: Mutex<HashSet<String>>,
+    /// Emitted events for windows created later (see [`emit`]).
+    replay: Mutex<VecDeque<(&'static str, Value)>>,
+}
+
+/// Emit `event` to every window and keep it for windows created later.
+pub fn emit<R: Runtime, S: Serialize>(app: &AppHandle<R>, event: &'static str, payload: S) {
+    let Ok(payload) = serde_json::to_value(payload) else {
+        return;
+    };
+    if let Some(state) = app.try_state::<BridgeState>() {
+        if let Ok(mut replay) = state.replay.lock() {
+            if SNAPSHOT_EVENTS.contains(&event) {
+                replay.retain(|(name, _)| *name != event);
+            } else {
+                let kept = replay
+                    .iter()
+                    .filter(|(name, _)| !SNAPSHOT_EVENTS.contains(name))
+                    .count();
+                if kept >= REPLAY_LIMIT {
+                    if let Some(oldest) = replay
+                        .iter()
+                        .position(|(name, _)| !SNAPSHOT_EVENTS.contains(name))
+                    {
+                        replay.remove(oldest);
+                    }
+                }
+            }
+            replay.push_back((event, payload.clone()));
+        }
+    }
+    let _ = app.emit(event, payload);
+}
+
+/// Register state and subscribe to every enabled backend.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(BridgeState::default());
+    sync(app);
+}
+
+/// Start subscriptions for newly enabled backends and stop removed ones.
+pub fn sync<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<BridgeState>() else {
+        return;
+    };
+    let Ok(mut streams) = state.streams.lock() else {
+        return;
+    };
+    // While paused nothing is wanted, so every stream is stopped.
+    let backends = if state.paused.load(Ordering::Relaxed) {
+        Vec::new()
+    } else {
+        federation::enabled(app)
+    };
+    let wanted: HashMap<String, BackendProfile> = backends
+        .into_iter()
+        .map(|p| (format!("{}|{}", p.id, p.url), p))
+        .collect();
+
+    let mut stopped = Vec::new();
+    streams.retain(|key, alive| {
+        let keep = wanted.contains_key(key);
+        if !keep {
+            alive.store(false, Ordering::Relaxed);
+            stopped.push(key.split('|').next().unwrap_or_default().to_string());
+        }
+        keep
+    });
+    // A stopped stream no longer reports its state (see [`subscribe`]).
+    for id in stopped {
+        if !wanted.values().any(|profile| profile.id == id) {
+            set_connected(app, &id, false);
+        }
+    }
+    for (key, profile) in wanted {
+        if streams.contains_key(&key) {
+            continue;
+        }
+        let alive = Arc::new(AtomicBool::new(true));
+        streams.insert(key, alive.clone());
+        let app = app.clone();
+        std::thread::spawn(move || run(&app, &profile, &alive));
+    }
+}
+
+/// Number of backends whose event stream is open.
+pub fn connected_count<R: Runtime>(app: &AppHandle<R>) -> usize {
+    app.try_state::<BridgeState>()
+        .and_then(|state| state.connected.lock().ok().map(|connected| connected.len()))
+        .unwrap_or(0)
+}
+
+/// Ids of backends whose event stream is open.
+pub fn connected_ids<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    app.try_state::<BridgeState>()
+        .and_then(|state| {
+            state
+                .connected
+                .lock()
+                .ok()
+                .map(|connected| connected.iter().cloned().collect())
+        })
+        .unwrap_or_default()
+}
+
+/// Record whether a backend's stream is open; refreshes the tooltip on changes.
+fn set_connected<R: Runtime>(app: &AppHandle<R>, backend_id: &str, connected: bool) {
+    let Some(state) = app.try_state::<BridgeState>() else {
+        return;
+    };
+    let changed = match state.connected.lock() {
+        Ok(mut ids) if connected => ids.insert(backend_id.to_string()),
+        Ok(mut ids) => ids.remove(backend_id),
+        Err(_) => false,
+    };
+    if changed {
+        crate::refresh_tray_tooltip(app);
+        emit(app, CONNECTIONS_CHANGED_EVENT, connected_ids(app));
+    }
+}
+
+/// Drop all subscriptions until [`resume`] (the system is going to sleep).
+pub fn pause<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(state) = app.try_state::<BridgeState>() {
+        state.paused.store(true, Ordering::Relaxed);
+        sync(app);
+        if let Ok(mut connected) = state.connected.lock() {
+            connected.clear();
+        }
+        crate::refresh_tray_tooltip(app);
+        emit(app, CONNECTIONS_CHANGED_EVENT, Vec::<String>::new());
+    }
+}
+
+/// Reconnect every backend right away, replacing streams that may have died
+/// during sleep without their threads noticing yet.
+pub fn resume<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<BridgeState>() else {
+        return;
+    };
+    state.paused.store(false, Ordering::Relaxed);
+    if let Ok(mut streams) = state.streams.lock() {
+        for alive in streams.values() {
+            alive.store(false, Ordering::Relaxed);
+        }
+        streams.clear();
+    }
+    sync(app);
+}
+
+fn run<R: Runtime>(app: &AppHandle<R>, backend: &BackendProfile, alive: &AtomicBool) {
+    let mut backoff = MIN_BACKOFF;
+    while alive.load(Ordering::Relaxed) {
+        match subscribe(app, backend, alive) {
+            Ok(()) => {
+                // The stream ran and then ended; reconnect promptly.
+                backoff = MIN_BACKOFF;
+            }
+            Err(e) => eprintln!(
+                "[CrewHub] Event stream for {} unavailable: {}",
+                backend.name, e
+            ),
+        }
+        std::thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Connect and pump events until the stream closes. Only a live stream
+/// reports the connection state: after [`resume`] a replaced stream may end
+/// while its successor for the same backend is already connected.
+fn subscribe<R: Runtime>(
+    app: &AppHandle<R>,
+    backend: &BackendProfile,
+    alive: &AtomicBool,
+) -> Result<(), String> {
+    let report = |connected: bool| {
+        if alive.load(Ordering::Relaxed) {
+            set_connected(app, &backend.id, connected);
+        }
+    };
+    let reader = match backend::open_event_stream(&backend.url) {
+        Ok(reader) => reader,
+        Err(e) => {
+            report(false);
+            return Err(e);
+        }
+    };
+    println!("[CrewHub] Connected to event stream of {}", backend.name);
+    report(true);
+    backend::read_sse_stream(reader, |event, data| {
+        // A removed backend's stream may deliver a few more events before it closes.
+        if !alive.load(Ordering::Relaxed) {
+            return;
+        }
+        let payload = serde_json::from_str::<Value>(data).unwrap_or(Value::Null);
+        dispatch(app, backend, event, payload);
+    });
+    report(false);
+    Ok(())
+}
+
+/// Route one backend event to the subsystem that handles it natively (also
+/// used for simulated events, see [`crate::simulate`]).
+pub fn dispatch<R: Runtime>(
+    app: &AppHandle<R>,
+    backend: &BackendProfile,
+    event: &str,
+    payload: Value,
+) {
+    crate::debug::record("backend", event, Some(backend.name.clone()));
+    match event {
+        approvals::APPROVAL_REQUESTED_EVENT => approvals::handle_request(app, backend, payload),
+        costs::USAGE_EVENT => costs::record_usage(app, payload),
+        presence::AGENT_STATUS_EVENT => presence::handle_status(app, &backend.id, payload),
+        pairing::PAIRING_COMPLETED_EVENT => pairing::handle_completed(app, payload),
+        attention::MENTION_EVENT => attention::handle_mention(app, payload),
+        choreography::REQUEST_EVENT => choreography::handle_request(app, backend, payload),
+        notifications::CREW_FAILED_EVENT => {
+            notifications::handle_crew_failed(app, backend, payload)
+        }
+        _ => {}
+    }
+}
+
+/// Tauri command: the calling window's listeners are in place; replay what it missed.
+/// Called from every window via `invoke('window_ready')`; returns how many events were replayed.
+#[tauri::command]
+pub fn window_ready(window: WebviewWindow, state: tauri::State<BridgeState>) -> usize {
+    let events: Vec<(&'static str, Value)> = state
+        .replay
+        .lock()
+        .map(|replay| replay.iter().cloned().collect())
+        .unwrap_or_default();
+    let app = window.app_handle();
+    for (event, payload) in &events {
+        let _ = app.emit_to(window.label(), event, payload);
+    }
+    events.len()
+}
@@ -0,0 +1,495 @@
+//! Browser automation for agents.
+//!
+//! Drives a managed headless Chromium-family browser (Chrome, Chromium, or
+//! Edge) over the Chrome DevTools Protocol, with its own profile in the app
+//! data dir so it never touches the user's browsing data. Exposed to agents
+//! as MCP tools (navigate, extract, screenshot) via [`crate::local_tools`].
+//!
+//! Every domain needs the user's consent before an agent may load it: the
+//! first visit shows a native dialog ("Allow Once" / "Always Allow" /
+//! "Deny"); remembered answers can be reviewed and revoked from settings.
+
+use crate::child_processes::{self, Kind};
+use crate::{i18n, store};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::net::TcpStream;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime, State};
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+/// Remembered per-domain consent (app data dir).
+const CONSENT_FILE: &str = "browser-consent.json";
+
+/// Browser config (app data dir).
+const CONFIG_FILE: &str = "browser.json";
+
+/// Time allowed for the browser to start and for a page to finish loading.
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(15);
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Extracted text is truncated to this many characters.
+const MAX_EXTRACT_CHARS: usize = 100_000;
+
+// Consent dialog buttons (i18n keys).
+const ALLOW_ONCE: &str = "dialog.allowOnce";
+const ALWAYS_ALLOW: &str = "dialog.alwaysAllow";
+const DENY: &str = "dialog.deny";
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BrowserConfig {
+    /// Browser executable; the usual Chrome/Chromium/Edge locations are tried if unset.
+    pub executable: Option<String>,
+}
+
+/// A running headless browser and a CDP connection to its page.
+struct BrowserSession {
+    child: Child,
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    next_id: u64,
+}
+
+impl Drop for BrowserSession {
+    fn drop(&mut self) {
+        child_processes::unregister(self.child.id());
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// App state: browser session (started on first use) and domain consent.
+pub struct BrowserState {
+    session: Mutex<Option<BrowserSession>>,
+    config: Mutex<BrowserConfig>,
+    consent: Mutex<BTreeMap<String, bool>>,
+    /// "Allow Once" grants, valid until the app quits.
+    allowed_once: Mutex<HashSet<String>>,
+    dialog: Mutex<()>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(BrowserState {
+        session: Mutex::new(None),
+        config: Mutex::new(store::load_json(app, CONFIG_FILE)),
+        consent: Mutex::new(store::load_json(app, CONSENT_FILE)),
+        allowed_once: Mutex::new(HashSet::new()),
+        dialog: Mutex::new(()),
+    });
+}
+
+/// Close the browser when the app exits.
+pub fn shutdown<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(state) = app.try_state::<BrowserState>() {
+        if let Ok(mut session) = state.session.lock() {
+            session.take();
+        }
+    }
+}
+
+fn candidates() -> Vec<String> {
+    #[cfg(target_os = "macos")]
+    let list = [
+        "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+        "/Applications/Chromium.app/Contents/MacOS/Chromium",
+        "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+    ];
+    #[cfg(target_os = "windows")]
+    let list = [
+        r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+        r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+        r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+    ];
+    #[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+    let list = [
+        "google-chrome",
+        "chromium",
+        "chromium-browser",
+        "microsoft-edge",
+    ];
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+impl BrowserSession {
+    fn launch<R: Runtime>(app: &AppHandle<R>, executable: Option<&str>) -> Result<Self, String> {
+        let profile = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("browser-profile");
+        std::fs::create_dir_all(&profile).map_err(|e| e.to_string())?;
+        let port_file = profile.join("DevToolsActivePort");
+        let _ = std::fs::remove_file(&port_file);
+
+        let programs = match executable {
+            Some(exe) => vec![exe.to_string()],
+            None => candidates(),
+        };
+        let (program, mut child) = programs
+            .iter()
+            .find_map(|program| {
+                Command::new(program)
+                    .args([
+                        "--headless=new",
+                        "--remote-debugging-port=0",
+                        "--no-first-run",
+                        "--no-default-browser-check",
+                        "--window-size=1280,900",
+                    ])
+                    .arg(format!("--user-data-dir={}", profile.display()))
+                    .arg("about:blank")
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .ok()
+                    .map(|child| (program, child))
+            })
+            .ok_or_else(|| "No Chrome, Chromium or Edge installation found".to_string())?;
+        child_processes::register(child.id(), Kind::Browser, "headless browser", program);
+
+        let socket = match wait_for_page(&port_file).and_then(|url| connect(&url)) {
+            Ok(socket) => socket,
+            Err(e) => {
+                child_processes::unregister(child.id());
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(e);
+            }
+        };
+        let mut session = Self {
+            child,
+            socket,
+            next_id: 0,
+        };
+        session.call("Page.enable", json!({}))?;
+        Ok(session)
+    }
+
+    /// Send a CDP command and wait for its result, skipping unrelated events.
+    fn call(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let message = json!({ "id": id, "method": method, "params": params });
+        self.socket
+            .send(Message::text(message.to_string()))
+            .map_err(|e| e.to_string())?;
+        loop {
+            let reply = match self.socket.read().map_err(|e| e.to_string())? {
+                Message::Text(text) => text,
+                Message::Close(_) => return Err("Browser closed the connection".to_string()),
+                _ => continue,
+            };
+            let reply: Value = serde_json::from_str(&reply).map_err(|e| e.to_string())?;
+            if reply["id"].as_u64() != Some(id) {
+                continue;
+            }
+            if let Some(error) = reply.get("error") {
+                return Err(format!("{} failed: {}", method, error["message"]));
+            }
+            return Ok(reply["result"].clone());
+        }
+    }
+
+    fn evaluate(&mut self, expression: &str) -> Result<Value, String> {
+        let result = self.call(
+            "Runtime.evaluate",
+            json!({ "expression": expression, "returnByValue": true, "awaitPromise": true }),
+        )?;
+        if let Some(exception) = result.get("exceptionDetails") {
+            return Err(format!("Script error: {}", exception["text"]));
+        }
+        Ok(result["result"]["value"].clone())
+    }
+
+    fn current_url(&mut self) -> Result<String, String> {
+        Ok(self
+            .evaluate("location.href")?
+            .as_str()
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    fn wait_for_load(&mut self) -> Result<(), String> {
+        let deadline = Instant::now() + LOAD_TIMEOUT;
+        while Instant::now() < deadline {
+            if self.evaluate("document.readyState")?.as_str() == Some("complete") {
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_millis(250));
+        }
+        Err("Page did not finish loading in time".to_string())
+    }
+}
+
+/// Wait for the browser to write its DevTools port, then find the page target.
+fn wait_for_page(port_file: &Path) -> Result<String, String> {
+    let deadline = Instant::now() + LAUNCH_TIMEOUT;
+    while Instant::now() < deadline {
+        let port = std::fs::read_to_string(port_file).ok().and_then(|raw| {
+            raw.lines()
+                .next()
+                .and_then(|l| l.trim().parse::<u16>().ok())
+        });
+        if let Some(port) = port {
+            let targets: Value = ureq::get(&format!("http://127.0.0.1:{}/json/list", port))
+                .call()
+                .map_err(|e| e.to_string())?
+                .into_string()
+                .map_err(|e| e.to_string())
+                .and_then(|raw| serde_json::from_str(&raw).map_err(|e| e.to_string()))?;
+            if let Some(url) = targets
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|t| t["type"] == "page")
+                .and_then(|t| t["webSocketDebuggerUrl"].as_str())
+            {
+                return Ok(url.to_string());
+            }
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    Err("Browser did not start in time".to_string())
+}
+
+fn connect(ws_url: &str) -> Result<WebSocket<MaybeTlsStream<TcpStream>>, String> {
+    let (socket, _) = tungstenite::connect(ws_url).map_err(|e| e.to_string())?;
+    if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+        let _ = stream.set_read_timeout(Some(LOAD_TIMEOUT));
+    }
+    Ok(socket)
+}
+
+/// Host of a URL, without a leading `www.`.
+fn domain_of(url: &str) -> Result<String, String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Only http(s) URLs can be opened".to_string());
+    }
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| "URL has no host".to_string())?
+        .to_lowercase();
+    Ok(host.strip_prefix("www.").unwrap_or(&host).to_string())
+}
+
+/// Remembered verdict for a domain or any of its parent domains.
+fn remembered(consent: &BTreeMap<String, bool>, domain: &str) -> Option<bool> {
+    let mut candidate = domain;
+    loop {
+        if let Some(verdict) = consent.get(candidate) {
+            return Some(*verdict);
+        }
+        candidate = candidate.split_once('.')?.1;
+    }
+}
+
+/// Ask (or recall) whether agents may load pages from `domain`.
+fn ensure_consent<R: Runtime>(app: &AppHandle<R>, domain: &str) -> Result<(), String> {
+    let state = app.state::<BrowserState>();
+    let _one_at_a_time = state.dialog.lock();
+    let known = state
+        .consent
+        .lock()
+        .ok()
+        .and_then(|consent| remembered(&consent, domain));
+    let once = state
+        .allowed_once
+        .lock()
+        .map(|set| set.contains(domain))
+        .unwrap_or(false);
+    match known {
+        Some(true) => return Ok(()),
+        Some(false) => return Err(format!("Browsing {} was denied by the user", domain)),
+        None if once => return Ok(()),
+        None => {}
+    }
+
+    let result = app
+        .dialog()
+        .message(i18n::tf("dialog.webAccess", &[("domain", domain)]))
+        .title(i18n::t("dialog.webAccessTitle"))
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            i18n::t(ALLOW_ONCE),
+            i18n::t(ALWAYS_ALLOW),
+            i18n::t(DENY),
+        ))
+        .blocking_show_with_result();
+    let answer = match result {
+        MessageDialogResult::Custom(label) => [ALLOW_ONCE, ALWAYS_ALLOW, DENY]
+            .into_iter()
+            .find(|key| i18n::t(key) == label)
+            .unwrap_or(DENY),
+        MessageDialogResult::Yes | MessageDialogResult::Ok => ALLOW_ONCE,
+        // "Always allow" only counts when its own label comes back; a
+        // dismissed dialog may report `No`.
+        _ => DENY,
+    };
+    match answer {
+        ALLOW_ONCE => {
+            if let Ok(mut set) = state.allowed_once.lock() {
+                set.insert(domain.to_string());
+            }
+            Ok(())
+        }
+        ALWAYS_ALLOW => {
+            remember(app, &state, domain, true);
+            Ok(())
+        }
+        _ => Err(format!("Browsing {} was denied by the user", domain)),
+    }
+}
+
+fn remember<R: Runtime>(app: &AppHandle<R>, state: &BrowserState, domain: &str, allowed: bool) {
+    if let Ok(mut consent) = state.consent.lock() {
+        consent.insert(domain.to_string(), allowed);
+        if let Err(e) = store::save_json(app, CONSENT_FILE, &*consent) {
+            eprintln!("[CrewHub] {}", e);
+        }
+    }
+}
+
+/// Run `f` against the browser session, launching the browser on first use.
+fn with_session<R: Runtime, T>(
+    app: &AppHandle<R>,
+    f: impl FnOnce(&mut BrowserSession) -> Result<T, String>,
+) -> Result<T, String> {
+    let state = app.state::<BrowserState>();
+    let mut slot = state.session.lock().map_err(|e| e.to_string())?;
+    if slot.is_none() {
+        let executable = state
+            .config
+            .lock()
+            .map_err(|e| e.to_string())?
+            .executable
+            .clone();
+        *slot = Some(BrowserSession::launch(app, executable.as_deref())?);
+    }
+    let session = slot.as_mut().ok_or("Browser not running")?;
+    let result = f(session);
+    if result.is_err() && session.child.try_wait().ok().flatten().is_some() {
+        // The browser died; start fresh next time.
+        *slot = None;
+    }
+    result
+}
+
+/// Make sure the page currently shown is on a consented domain.
+fn check_current_page<R: Runtime>(
+    app: &AppHandle<R>,
+    session: &mut BrowserSession,
+) -> Result<String, String> {
+    let url = session.current_url()?;
+    if url == "about:blank" {
+        return Err("No page loaded; navigate first".to_string());
+    }
+    ensure_consent(app, &domain_of(&url)?)?;
+    Ok(url)
+}
+
+/// Load `url` and return the final URL, title, and the start of the page text.
+pub fn navigate<R: Runtime>(app: &AppHandle<R>, url: &str) -> Result<Value, String> {
+    ensure_consent(app, &domain_of(url)?)?;
+    with_session(app, |session| {
+        session.call("Page.navigate", json!({ "url": url }))?;
+        session.wait_for_load()?;
+        // Redirects may have left the consented domain.
+        let final_url = session.current_url()?;
+        if let Err(e) = domain_of(&final_url).and_then(|d| ensure_consent(app, &d)) {
+            session.call("Page.navigate", json!({ "url": "about:blank" }))?;
+            return Err(e);
+        }
+        let title = session.evaluate("document.title")?;
+        Ok(json!({ "url": final_url, "title": title }))
+    })
+}
+
+/// Visible text of the page, or of the first element matching `selector`.
+pub fn extract<R: Runtime>(app: &AppHandle<R>, selector: Option<&str>) -> Result<String, String> {
+    with_session(app, |session| {
+        check_current_page(app, session)?;
+        let selector_json = serde_json::to_string(&selector).map_err(|e| e.to_string())?;
+        let text = session.evaluate(&format!(
+            "(() => {{ const s = {}; const el = s ? document.querySelector(s) : document.body; \
+             return el ? el.innerText : null; }})()",
+            selector_json
+        ))?;
+        let text = text
+            .as_str()
+            .ok_or_else(|| "No element matches the selector".to_string())?;
+        Ok(text.chars().take(MAX_EXTRACT_CHARS).collect())
+    })
+}
+
+/// PNG screenshot of the current viewport, base64-encoded.
+pub fn screenshot<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    with_session(app, |session| {
+        check_current_page(app, session)?;
+        let result = session.call("Page.captureScreenshot", json!({ "format": "png" }))?;
+        result["data"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "Browser returned no image".to_string())
+    })
+}
+
+/// Tauri command: remembered per-domain consent (domain → allowed).
+/// Called from the frontend via `invoke('list_browser_consent')`.
+#[tauri::command]
+pub fn list_browser_consent(state: State<BrowserState>) -> Result<BTreeMap<String, bool>, String> {
+    Ok(state.consent.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: set (or with `allowed: null`, forget) consent for a domain.
+/// Called from the frontend via `invoke('set_browser_consent', { domain, allowed })`.
+#[tauri::command]
+pub fn set_browser_consent(
+    domain: String,
+    allowed: Option<bool>,
+    app: AppHandle,
+    state: State<BrowserState>,
+) -> Result<BTreeMap<String, bool>, String> {
+    let domain = domain.trim().to_lowercase();
+    let mut consent = state.consent.lock().map_err(|e| e.to_string())?;
+    match allowed {
+        Some(allowed) => consent.insert(domain.clone(), allowed),
+        None => consent.remove(&domain),
+    };
+    if let Ok(mut set) = state.allowed_once.lock() {
+        set.remove(&domain);
+    }
+    store::save_json(&app, CONSENT_FILE, &*consent)?;
+    Ok(consent.clone())
+}
+
+/// Tauri command: set the browser executable (`null` = auto-detect).
+/// Called from the frontend via `invoke('configure_browser', { config })`.
+#[tauri::command]
+pub fn configure_browser(
+    config: BrowserConfig,
+    app: AppHandle,
+    state: State<BrowserState>,
+) -> Result<(), String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    *state.config.lock().map_err(|e| e.to_string())? = config;
+    // Restart with the new executable on next use.
+    state.session.lock().map_err(|e| e.to_string())?.take();
+    Ok(())
+}
+
+/// Tauri command: quit the automated browser (it restarts on next use).
+/// Called from the frontend via `invoke('close_browser')`.
+#[tauri::command]
+pub fn close_browser(state: State<BrowserState>) -> Result<(), String> {
+    state.session.lock().map_err(|e| e.to_string())?.take();
+    Ok(())
+}
@@ -0,0 +1,288 @@
+//! `.crewhub` bundles: shareable crew/workspace setups.
+//!
+//! A bundle is a JSON file with a manifest (name, description, author), prompt
+//! templates and optionally a window layout profile:
+//!
+//! ```json
+//! { "format": "crewhub-bundle", "version": 1,
+//!   "manifest": { "name": "Release crew", "author": "Ops" },
+//!   "prompts": [{ "name": "Changelog", "body": "…", "tags": ["release"] }],
+//!   "layout": { "id": "…", "name": "Release", "windows": [] } }
+//! ```
+//!
+//! The extension is registered as a file association (`bundle.fileAssociations`
+//! in `tauri.conf.json`), so double-clicking a bundle or "Open With → CrewHub"
+//! starts the app with its path — or, on macOS, delivers it as
+//! `RunEvent::Opened`. Nothing is imported before the user confirms a dialog
+//! that lists what the bundle contains. Imported templates are added to the
+//! library and the layout is added as a new profile (without its hotkey);
+//! nothing existing is replaced.
+
+use crate::i18n;
+use crate::layout_profiles::{self, LayoutProfile};
+use crate::prompts::{self, PromptTemplateInput};
+use crate::task_journal::{self, TaskKind};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+
+/// File extension of bundles.
+pub const EXTENSION: &str = "crewhub";
+
+/// Value of the `format` field.
+const FORMAT: &str = "crewhub-bundle";
+/// Newest format version this build reads.
+const VERSION: u32 = 1;
+/// Larger files are refused before parsing.
+const MAX_BYTES: u64 = 5 * 1024 * 1024;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    format: String,
+    version: u32,
+    manifest: Manifest,
+    #[serde(default)]
+    prompts: Vec<PromptTemplateInput>,
+    #[serde(default)]
+    layout: Option<LayoutProfile>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImported {
+    manifest: Manifest,
+    prompts: usize,
+    layout: Option<String>,
+}
+
+pub fn is_bundle(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(EXTENSION))
+}
+
+/// Read and validate a bundle file.
+pub fn read(path: &Path) -> Result<Bundle, String> {
+    let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_BYTES {
+        return Err(format!("Bundle is too large ({} bytes)", size));
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let bundle: Bundle =
+        serde_json::from_str(&text).map_err(|e| format!("Not a CrewHub bundle: {}", e))?;
+    if bundle.format != FORMAT {
+        return Err("Not a CrewHub bundle".to_string());
+    }
+    if bundle.version > VERSION {
+        return Err(format!(
+            "Bundle version {} needs a newer CrewHub",
+            bundle.version
+        ));
+    }
+    if bundle.manifest.name.trim().is_empty() {
+        return Err("Bundle has no name".to_string());
+    }
+    Ok(bundle)
+}
+
+/// Ask the user whether to import the bundle.
+fn confirm<R: Runtime>(app: &AppHandle<R>, bundle: &Bundle) -> bool {
+    let manifest = &bundle.manifest;
+    let mut details = vec![i18n::tf(
+        "bundle.prompts",
+        &[("count", &bundle.prompts.len().to_string())],
+    )];
+    if let Some(layout) = &bundle.layout {
+        details.push(i18n::tf("bundle.layout", &[("name", &layout.name)]));
+    }
+    if let Some(description) = manifest.description.as_deref().filter(|d| !d.is_empty()) {
+        details.push(String::new());
+        details.push(description.to_string());
+    }
+    let author = manifest
+        .author
+        .clone()
+        .unwrap_or_else(|| i18n::t("bundle.unknownAuthor"));
+    let result = app
+        .dialog()
+        .message(i18n::tf(
+            "dialog.importBundle",
+            &[
+                ("name", &manifest.name),
+                ("author", &author),
+                ("details", &details.join("\n")),
+            ],
+        ))
+        .title(i18n::t("dialog.importBundleTitle"))
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            i18n::t("dialog.import"),
+            i18n::t("dialog.cancel"),
+        ))
+        .blocking_show_with_result();
+    match result {
+        MessageDialogResult::Custom(label) => label == i18n::t("dialog.import"),
+        MessageDialogResult::Ok | MessageDialogResult::Yes => true,
+        _ => false,
+    }
+}
+
+/// Confirm and import a bundle. Returns `None` when the user declined.
+/// Blocks on the dialog, so never call it on the main thread.
+pub fn import<R: Runtime>(
+    app: &AppHandle<R>,
+    path: &Path,
+) -> Result<Option<BundleImported>, String> {
+    let bundle = read(path)?;
+    if !confirm(app, &bundle) {
+        return Ok(None);
+    }
+    prompts::import_templates(app, &bundle.prompts)?;
+    let layout = bundle.layout.as_ref().map(|layout| layout.name.clone());
+    if let Some(profile) = bundle.layout {
+        layout_profiles::import(app, profile)?;
+    }
+    let imported = BundleImported {
+        manifest: bundle.manifest,
+        prompts: bundle.prompts.len(),
+        layout,
+    };
+    let _ = app.emit("bundle-imported", imported.clone());
+    Ok(Some(imported))
+}
+
+/// Import bundles on a background thread (the dialogs block).
+fn import_all<R: Runtime>(app: &AppHandle<R>, paths: Vec<PathBuf>) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        for path in paths {
+            if let Err(e) = import(&app, &path) {
+                eprintln!("[CrewHub] Failed to import {}: {}", path.display(), e);
+                let _ = app
+                    .dialog()
+                    .message(e)
+                    .title(i18n::t("dialog.importBundleTitle"))
+                    .kind(MessageDialogKind::Error)
+                    .blocking_show();
+            }
+        }
+    });
+}
+
+/// Handle bundle paths among launch arguments (double-click or "Open With"
+/// on Windows and Linux). Returns whether there were any.
+pub fn handle_args<R: Runtime>(app: &AppHandle<R>, args: &[String], cwd: &Path) -> bool {
+    let paths: Vec<PathBuf> = args
+        .iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .map(|arg| match url::Url::parse(arg) {
+            Ok(url) if url.scheme() == "file" => url.to_file_path().unwrap_or_default(),
+            _ => cwd.join(arg),
+        })
+        .filter(|path| is_bundle(path) && path.is_file())
+        .collect();
+    if paths.is_empty() {
+        return false;
+    }
+    import_all(app, paths);
+    true
+}
+
+/// Import the bundles among files opened with CrewHub on macOS; returns the other files.
+#[cfg(target_os = "macos")]
+pub fn handle_opened<R: Runtime>(app: &AppHandle<R>, urls: Vec<url::Url>) -> Vec<url::Url> {
+    let (bundles, rest): (Vec<url::Url>, Vec<url::Url>) = urls
+        .into_iter()
+        .partition(|url| url.to_file_path().is_ok_and(|path| is_bundle(&path)));
+    let paths: Vec<PathBuf> = bundles
+        .iter()
+        .filter_map(|url| url.to_file_path().ok())
+        .collect();
+    if !paths.is_empty() {
+        import_all(app, paths);
+    }
+    rest
+}
+
+/// Tauri command: confirm and import a bundle file. Resolves to `null` if the user declined.
+/// Called from the settings window via `invoke('import_bundle', { path })`.
+#[tauri::command]
+pub async fn import_bundle(app: AppHandle, path: String) -> Result<Option<BundleImported>, String> {
+    tauri::async_runtime::spawn_blocking(move || import(&app, Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Write a bundle with the given prompt templates and layout profile to `path`.
+pub fn export<R: Runtime>(
+    app: &AppHandle<R>,
+    path: &str,
+    manifest: Manifest,
+    prompt_ids: Vec<String>,
+    layout_id: Option<String>,
+) -> Result<(), String> {
+    if manifest.name.trim().is_empty() {
+        return Err("Bundle needs a name".to_string());
+    }
+    let mut path = PathBuf::from(path);
+    if !is_bundle(&path) {
+        path.set_extension(EXTENSION);
+    }
+    let layout = match layout_id {
+        Some(id) => Some(
+            layout_profiles::all(app)
+                .into_iter()
+                .find(|profile| profile.id == id)
+                .map(|profile| LayoutProfile {
+                    hotkey: None,
+                    ..profile
+                })
+                .ok_or_else(|| format!("Unknown layout profile: {}", id))?,
+        ),
+        None => None,
+    };
+    let bundle = Bundle {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        manifest,
+        prompts: prompts::export_templates(app, &prompt_ids)?,
+        layout,
+    };
+    let json = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Tauri command: write a bundle with the given prompt templates and layout profile.
+/// Called from the settings window via `invoke('export_bundle', { path, manifest, promptIds, layoutId })`.
+#[tauri::command]
+pub fn export_bundle(
+    app: AppHandle,
+    path: String,
+    manifest: Manifest,
+    prompt_ids: Vec<String>,
+    layout_id: Option<String>,
+) -> Result<(), String> {
+    let _running = task_journal::begin(
+        &app,
+        TaskKind::BundleExport {
+            path: path.clone(),
+            manifest: manifest.clone(),
+            prompt_ids: prompt_ids.clone(),
+            layout_id: layout_id.clone(),
+        },
+    );
+    export(&app, &path, manifest, prompt_ids, layout_id)
+}
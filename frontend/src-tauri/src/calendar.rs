@@ -0,0 +1,612 @@
+//! Do Not Disturb during meetings, from the user's calendar busy status.
+//!
+//! Optional and off by default. Once a minute the current busy/free state is
+//! read from the system calendar (EventKit on macOS, the Windows appointments
+//! store) or from a polled ICS URL (any platform; the only source on Linux).
+//! While a busy event is in progress CrewHub's Do Not Disturb is on, and
+//! `dnd-changed` carries the meeting so the UI can say why notifications are
+//! muted (see [`crate::dnd`]).
+//!
+//! ICS support covers what calendar exports need for busy/free: single
+//! events, daily and weekly recurrences (`INTERVAL`, `BYDAY`, `COUNT`,
+//! `UNTIL`, `EXDATE`), free (`TRANSP:TRANSPARENT`), tentative and cancelled
+//! events. Times with a `TZID` are taken as local wall-clock time, and
+//! all-day events never count as meetings.
+
+use crate::{dnd, power, store};
+use chrono::{
+    Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, TimeZone, Weekday,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "calendar.json";
+
+/// How often the busy state is checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long a downloaded ICS feed is reused before it is fetched again.
+const ICS_REFRESH: Duration = Duration::from_secs(5 * 60);
+
+/// Timeout for downloading an ICS feed.
+const ICS_TIMEOUT: Duration = Duration::from_secs(15);
+
+static CONFIG: Mutex<Option<CalendarConfig>> = Mutex::new(None);
+
+/// Last downloaded ICS feed: URL, fetch time and parsed events.
+static ICS_CACHE: Mutex<Option<(String, Instant, Vec<Event>)>> = Mutex::new(None);
+
+/// Last error reading the calendar, cleared by the next successful check.
+static LAST_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CalendarSource {
+    /// EventKit (macOS) or the Windows appointments store.
+    #[default]
+    System,
+    /// An ICS feed polled over HTTP(S).
+    Ics,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CalendarConfig {
+    /// Turn on Do Not Disturb while a busy event is in progress.
+    pub enabled: bool,
+    pub source: CalendarSource,
+    /// Feed URL for [`CalendarSource::Ics`] (`webcal://` is accepted).
+    pub ics_url: Option<String>,
+    /// Treat tentative events as busy too.
+    pub include_tentative: bool,
+}
+
+/// The meeting currently muting notifications.
+#[derive(Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meeting {
+    pub title: String,
+    /// Unix seconds when the meeting ends.
+    pub ends_at: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarStatus {
+    config: CalendarConfig,
+    meeting: Option<Meeting>,
+    error: Option<String>,
+}
+
+/// Load the config and start polling the calendar.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: CalendarConfig = store::load_json(app, CONFIG_FILE);
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config);
+    }
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        check(&app);
+        std::thread::sleep(power::interval(&app, POLL_INTERVAL));
+    });
+}
+
+fn config() -> CalendarConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+/// Read the busy state now and update Do Not Disturb.
+fn check<R: Runtime>(app: &AppHandle<R>) {
+    let config = config();
+    let result = if config.enabled {
+        current_meeting(&config)
+    } else {
+        Ok(None)
+    };
+    let meeting = match result {
+        Ok(meeting) => {
+            set_error(None);
+            meeting
+        }
+        Err(e) => {
+            set_error(Some(e));
+            None
+        }
+    };
+    dnd::set_calendar(app, meeting);
+}
+
+fn set_error(error: Option<String>) {
+    let Ok(mut last) = LAST_ERROR.lock() else {
+        return;
+    };
+    if let Some(e) = error.as_ref().filter(|e| last.as_ref() != Some(*e)) {
+        eprintln!("[CrewHub] Calendar: {}", e);
+    }
+    *last = error;
+}
+
+fn current_meeting(config: &CalendarConfig) -> Result<Option<Meeting>, String> {
+    match config.source {
+        CalendarSource::System => system_meeting(config.include_tentative),
+        CalendarSource::Ics => {
+            let url = config
+                .ics_url
+                .as_deref()
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+                .ok_or("No ICS URL is configured")?;
+            let events = ics_events(url)?;
+            Ok(busy_at(
+                &events,
+                Local::now().naive_local(),
+                config.include_tentative,
+            ))
+        }
+    }
+}
+
+fn local_timestamp(time: NaiveDateTime) -> i64 {
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .map_or_else(|| time.and_utc().timestamp(), |time| time.timestamp())
+}
+
+// ── ICS feeds ───────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+}
+
+#[derive(Clone)]
+struct Rule {
+    frequency: Frequency,
+    interval: i64,
+    count: Option<i64>,
+    until: Option<NaiveDateTime>,
+    by_day: Vec<Weekday>,
+}
+
+#[derive(Clone, Default)]
+struct Event {
+    title: String,
+    /// Local wall-clock times.
+    start: Option<NaiveDateTime>,
+    end: Option<NaiveDateTime>,
+    duration: Option<ChronoDuration>,
+    all_day: bool,
+    free: bool,
+    tentative: bool,
+    cancelled: bool,
+    rule: Option<Rule>,
+    excluded: Vec<NaiveDateTime>,
+}
+
+/// Download (or reuse) and parse the ICS feed at `url`.
+fn ics_events(url: &str) -> Result<Vec<Event>, String> {
+    let url = match url.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{}", rest),
+        None => url.to_string(),
+    };
+    if let Some((cached, fetched, events)) = ICS_CACHE.lock().ok().and_then(|cache| cache.clone()) {
+        if cached == url && fetched.elapsed() < ICS_REFRESH {
+            return Ok(events);
+        }
+    }
+    let text = ureq::AgentBuilder::new()
+        .timeout(ICS_TIMEOUT)
+        .build()
+        .get(&url)
+        .set("User-Agent", &crate::request_headers::user_agent())
+        .call()
+        .map_err(|e| format!("Failed to fetch the ICS feed: {}", e))?
+        .into_string()
+        .map_err(|e| format!("Failed to read the ICS feed: {}", e))?;
+    if !text.trim_start().starts_with("BEGIN:VCALENDAR") {
+        return Err("The ICS URL did not return a calendar".to_string());
+    }
+    let events = parse_ics(&text);
+    if let Ok(mut cache) = ICS_CACHE.lock() {
+        *cache = Some((url, Instant::now(), events.clone()));
+    }
+    Ok(events)
+}
+
+/// Unfold content lines (continuations start with a space or tab).
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+/// Parse a `DATE` or `DATE-TIME` value into local wall-clock time.
+/// Returns the time and whether it was a whole date.
+fn parse_time(params: &str, value: &str) -> Option<(NaiveDateTime, bool)> {
+    let value = value.trim();
+    if params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME") || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((date.and_hms_opt(0, 0, 0)?, true));
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((time.and_utc().with_timezone(&Local).naive_local(), false));
+    }
+    let time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Some((time, false))
+}
+
+/// Parse an RFC 5545 duration such as `PT1H30M` or `P1D`.
+fn parse_duration(value: &str) -> Option<ChronoDuration> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let mut rest = value.strip_prefix('P')?;
+    let mut seconds = 0i64;
+    let mut in_time = false;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('T') {
+            in_time = true;
+            rest = after;
+            continue;
+        }
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let number: i64 = rest[..digits].parse().ok()?;
+        let unit = match (&rest[digits..digits + 1], in_time) {
+            ("W", false) => 7 * 86_400,
+            ("D", false) => 86_400,
+            ("H", true) => 3_600,
+            ("M", true) => 60,
+            ("S", true) => 1,
+            _ => return None,
+        };
+        seconds += number * unit;
+        rest = &rest[digits + 1..];
+    }
+    Some(ChronoDuration::seconds(if negative {
+        -seconds
+    } else {
+        seconds
+    }))
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    // Ordinals (`1MO`, `-1FR`) only occur with monthly and yearly rules.
+    match value.trim_start_matches(|c: char| c == '-' || c == '+' || c.is_ascii_digit()) {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Parse an `RRULE`; unsupported frequencies yield `None` (first occurrence only).
+fn parse_rule(value: &str) -> Option<Rule> {
+    let mut rule = Rule {
+        frequency: Frequency::Daily,
+        interval: 1,
+        count: None,
+        until: None,
+        by_day: Vec::new(),
+    };
+    let mut frequency = None;
+    for part in value.split(';') {
+        let Some((key, value)) = part.split_once('=') else {
+            continue;
+        };
+        match key {
+            "FREQ" => {
+                frequency = match value {
+                    "DAILY" => Some(Frequency::Daily),
+                    "WEEKLY" => Some(Frequency::Weekly),
+                    _ => None,
+                }
+            }
+            "INTERVAL" => rule.interval = value.parse().unwrap_or(1).max(1),
+            "COUNT" => rule.count = value.parse().ok(),
+            "UNTIL" => rule.until = parse_time("", value).map(|(time, _)| time),
+            "BYDAY" => rule.by_day = value.split(',').filter_map(parse_weekday).collect(),
+            _ => {}
+        }
+    }
+    rule.frequency = frequency?;
+    Some(rule)
+}
+
+fn parse_ics(text: &str) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut current: Option<Event> = None;
+    for line in unfold(text) {
+        if line == "BEGIN:VEVENT" {
+            current = Some(Event::default());
+            continue;
+        }
+        if line == "END:VEVENT" {
+            events.extend(current.take());
+            continue;
+        }
+        let Some(event) = current.as_mut() else {
+            continue;
+        };
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        match name {
+            "SUMMARY" => event.title = unescape(value),
+            "DTSTART" => {
+                if let Some((time, all_day)) = parse_time(params, value) {
+                    event.start = Some(time);
+                    event.all_day = all_day;
+                }
+            }
+            "DTEND" => event.end = parse_time(params, value).map(|(time, _)| time),
+            "DURATION" => event.duration = parse_duration(value),
+            "TRANSP" => event.free = value == "TRANSPARENT",
+            "STATUS" => {
+                event.tentative = value == "TENTATIVE";
+                event.cancelled = value == "CANCELLED";
+            }
+            "RRULE" => event.rule = parse_rule(value),
+            "EXDATE" => event.excluded.extend(
+                value
+                    .split(',')
+                    .filter_map(|value| parse_time(params, value).map(|(time, _)| time)),
+            ),
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Start of the occurrence of `event` in progress at `now`, if any.
+fn occurrence_at(event: &Event, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let start = event.start?;
+    let length = match (event.end, event.duration) {
+        (Some(end), _) => end - start,
+        (None, Some(duration)) => duration,
+        (None, None) => return None,
+    };
+    let covers = |occurrence: NaiveDateTime| {
+        occurrence <= now && now < occurrence + length && !event.excluded.contains(&occurrence)
+    };
+    let Some(rule) = &event.rule else {
+        return covers(start).then_some(start);
+    };
+
+    // Each weekday of a weekly rule is its own series with the rule's period.
+    let (period, bases) = match rule.frequency {
+        Frequency::Daily => (ChronoDuration::days(rule.interval), vec![start]),
+        Frequency::Weekly if rule.by_day.is_empty() => {
+            (ChronoDuration::weeks(rule.interval), vec![start])
+        }
+        Frequency::Weekly => {
+            let weekday = i64::from(start.weekday().num_days_from_monday());
+            let bases = rule
+                .by_day
+                .iter()
+                .map(|day| {
+                    start + ChronoDuration::days(i64::from(day.num_days_from_monday()) - weekday)
+                })
+                .collect();
+            (ChronoDuration::weeks(rule.interval), bases)
+        }
+    };
+    let series = bases.len() as i64;
+    bases.into_iter().find_map(|base| {
+        if now < base {
+            return None;
+        }
+        let index = (now - base).num_seconds() / period.num_seconds();
+        let occurrence = base + period * index as i32;
+        let within_count = rule.count.is_none_or(|count| index * series < count);
+        let within_until = rule.until.is_none_or(|until| occurrence <= until);
+        (occurrence >= start && within_count && within_until && covers(occurrence))
+            .then_some(occurrence)
+    })
+}
+
+/// The busy event in progress at `now` that ends last.
+fn busy_at(events: &[Event], now: NaiveDateTime, include_tentative: bool) -> Option<Meeting> {
+    events
+        .iter()
+        .filter(|event| !event.all_day && !event.free && !event.cancelled)
+        .filter(|event| include_tentative || !event.tentative)
+        .filter_map(|event| {
+            let start = occurrence_at(event, now)?;
+            let length = event.end.zip(event.start).map(|(end, start)| end - start);
+            let end = start + length.or(event.duration)?;
+            Some(Meeting {
+                title: event.title.clone(),
+                ends_at: local_timestamp(end),
+            })
+        })
+        .max_by_key(|meeting| meeting.ends_at)
+}
+
+// ── System calendars ────────────────────────────────────────────────────────
+
+/// Busy events from EventKit; the first check asks for calendar access.
+#[cfg(target_os = "macos")]
+fn system_meeting(include_tentative: bool) -> Result<Option<Meeting>, String> {
+    use block2::RcBlock;
+    use objc2::runtime::{Bool, NSObjectProtocol};
+    use objc2::sel;
+    use objc2_event_kit::{
+        EKAuthorizationStatus, EKEntityType, EKEventAvailability, EKEventStatus, EKEventStore,
+    };
+    use objc2_foundation::{NSDate, NSError};
+
+    unsafe {
+        let status = EKEventStore::authorizationStatusForEntityType(EKEntityType::Event);
+        let store = EKEventStore::new();
+        if status == EKAuthorizationStatus::NotDetermined {
+            // The answer arrives asynchronously; the next check picks it up.
+            let completion = RcBlock::new(|_granted: Bool, _error: *mut NSError| {});
+            if store.respondsToSelector(sel!(requestFullAccessToEventsWithCompletion:)) {
+                store.requestFullAccessToEventsWithCompletion(&*completion as *const _ as *mut _);
+            } else {
+                #[allow(deprecated)]
+                store.requestAccessToEntityType_completion(
+                    EKEntityType::Event,
+                    &*completion as *const _ as *mut _,
+                );
+            }
+            return Ok(None);
+        }
+        if status != EKAuthorizationStatus::FullAccess {
+            return Err(
+                "Calendar access is not allowed (System Settings → Privacy & Security → Calendars)"
+                    .to_string(),
+            );
+        }
+
+        let now = NSDate::now();
+        let soon = NSDate::dateWithTimeIntervalSinceNow(1.0);
+        let predicate = store.predicateForEventsWithStartDate_endDate_calendars(&now, &soon, None);
+        let meeting = store
+            .eventsMatchingPredicate(&predicate)
+            .iter()
+            .filter(|event| !event.isAllDay() && event.status() != EKEventStatus::Canceled)
+            .filter(|event| match event.availability() {
+                EKEventAvailability::Free => false,
+                EKEventAvailability::Tentative => include_tentative,
+                _ => true,
+            })
+            .map(|event| Meeting {
+                title: event.title().to_string(),
+                ends_at: event.endDate().timeIntervalSince1970() as i64,
+            })
+            .max_by_key(|meeting| meeting.ends_at);
+        Ok(meeting)
+    }
+}
+
+/// Busy appointments from the Windows appointments store (all calendars, read-only).
+#[cfg(target_os = "windows")]
+fn system_meeting(include_tentative: bool) -> Result<Option<Meeting>, String> {
+    use windows::ApplicationModel::Appointments::{
+        AppointmentBusyStatus, AppointmentManager, AppointmentStoreAccessType,
+    };
+    use windows::Foundation::{DateTime, TimeSpan};
+
+    /// 100 ns ticks between 1601-01-01 and the Unix epoch.
+    const UNIX_EPOCH_TICKS: i64 = 116_444_736_000_000_000;
+    const TICKS_PER_SECOND: i64 = 10_000_000;
+
+    let err = |e: windows::core::Error| format!("Windows calendar access failed: {}", e.message());
+    let store =
+        AppointmentManager::RequestStoreAsync(AppointmentStoreAccessType::AllCalendarsReadOnly)
+            .and_then(|operation| operation.get())
+            .map_err(err)?;
+    let now = DateTime {
+        UniversalTime: chrono::Utc::now().timestamp() * TICKS_PER_SECOND + UNIX_EPOCH_TICKS,
+    };
+    let appointments = store
+        .FindAppointmentsAsync(
+            now,
+            TimeSpan {
+                Duration: TICKS_PER_SECOND,
+            },
+        )
+        .and_then(|operation| operation.get())
+        .map_err(err)?;
+
+    let mut meeting: Option<Meeting> = None;
+    for appointment in appointments {
+        let busy = match appointment.BusyStatus().map_err(err)? {
+            AppointmentBusyStatus::Free | AppointmentBusyStatus::WorkingElsewhere => false,
+            AppointmentBusyStatus::Tentative => include_tentative,
+            _ => true,
+        };
+        if !busy
+            || appointment.AllDay().map_err(err)?
+            || appointment.IsCanceledMeeting().unwrap_or(false)
+        {
+            continue;
+        }
+        let end = appointment.StartTime().map_err(err)?.UniversalTime
+            + appointment.Duration().map_err(err)?.Duration;
+        let ends_at = (end - UNIX_EPOCH_TICKS) / TICKS_PER_SECOND;
+        if meeting
+            .as_ref()
+            .is_none_or(|meeting| ends_at > meeting.ends_at)
+        {
+            meeting = Some(Meeting {
+                title: appointment.Subject().map_err(err)?.to_string(),
+                ends_at,
+            });
+        }
+    }
+    Ok(meeting)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn system_meeting(_include_tentative: bool) -> Result<Option<Meeting>, String> {
+    Err("There is no system calendar on this platform; use an ICS URL instead".to_string())
+}
+
+fn status() -> CalendarStatus {
+    CalendarStatus {
+        config: config(),
+        meeting: dnd::calendar_meeting(),
+        error: LAST_ERROR.lock().ok().and_then(|error| error.clone()),
+    }
+}
+
+/// Tauri command: calendar Do Not Disturb config, current meeting and last error.
+/// Called from the settings window via `invoke('get_calendar_dnd')`.
+#[tauri::command]
+pub fn get_calendar_dnd() -> CalendarStatus {
+    status()
+}
+
+/// Tauri command: replace the calendar Do Not Disturb config and re-check right away.
+/// Called from the settings window via `invoke('configure_calendar_dnd', { config })`.
+#[tauri::command]
+pub async fn configure_calendar_dnd(
+    config: CalendarConfig,
+    app: AppHandle,
+) -> Result<CalendarStatus, String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config);
+    }
+    if let Ok(mut cache) = ICS_CACHE.lock() {
+        *cache = None;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        check(&app);
+        status()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
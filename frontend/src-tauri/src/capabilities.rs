@@ -0,0 +1,144 @@
+//! Registry of optional capabilities and whether they work on this machine.
+//!
+//! Global hotkeys, the tray icon, portal-backed features and GPU rendering
+//! can each fail depending on the platform, desktop or sandbox. The
+//! subsystems record here whether theirs came up ([`record`]), with an
+//! actionable reason when not, so the frontend can hide buttons that will
+//! never work instead of failing silently (`get_capabilities`, and
+//! `capabilities-changed` on every change). A capability that hasn't been
+//! checked yet has no `available` value. GPU support can only be seen from
+//! a webview, so the world window reports it (`report_capability`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Runtime};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Capability {
+    /// System-wide hotkeys ([`crate::hotkeys`]).
+    GlobalHotkeys,
+    /// Tray icon and menu ([`crate::tray_support`]).
+    Tray,
+    /// Native open/save dialogs (the FileChooser portal in a sandbox).
+    FileDialogs,
+    /// Start at login (the Background portal in a sandbox).
+    Autostart,
+    /// Screen captures for the agents (the Screenshot portal in a sandbox).
+    Screenshots,
+    /// Hardware-accelerated WebGL for the 3D world.
+    Gpu,
+}
+
+impl Capability {
+    const ALL: [Capability; 6] = [
+        Capability::GlobalHotkeys,
+        Capability::Tray,
+        Capability::FileDialogs,
+        Capability::Autostart,
+        Capability::Screenshots,
+        Capability::Gpu,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Capability::GlobalHotkeys => "Global hotkeys",
+            Capability::Tray => "Tray icon",
+            Capability::FileDialogs => "File dialogs",
+            Capability::Autostart => "Start at login",
+            Capability::Screenshots => "Screenshots",
+            Capability::Gpu => "GPU rendering",
+        }
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapabilityStatus {
+    /// `None` until the owning subsystem has checked.
+    available: Option<bool>,
+    /// Why it is unavailable and what would fix it.
+    reason: Option<String>,
+}
+
+static REGISTRY: Mutex<BTreeMap<Capability, CapabilityStatus>> = Mutex::new(BTreeMap::new());
+
+/// Record what is known before any subsystem starts.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    // Outside Linux these go through OS APIs that are always there.
+    #[cfg(not(target_os = "linux"))]
+    for capability in [
+        Capability::FileDialogs,
+        Capability::Autostart,
+        Capability::Screenshots,
+    ] {
+        record(app, capability, Ok(()));
+    }
+    #[cfg(target_os = "linux")]
+    let _ = app;
+}
+
+/// Record whether `capability` works; logs the reason when it newly doesn't.
+pub fn record<R: Runtime>(app: &AppHandle<R>, capability: Capability, result: Result<(), String>) {
+    let status = CapabilityStatus {
+        available: Some(result.is_ok()),
+        reason: result.err(),
+    };
+    {
+        let Ok(mut registry) = REGISTRY.lock() else {
+            return;
+        };
+        if registry.get(&capability) == Some(&status) {
+            return;
+        }
+        if let Some(reason) = &status.reason {
+            eprintln!("[CrewHub] {} unavailable: {}", capability.name(), reason);
+        }
+        registry.insert(capability, status);
+    }
+    let _ = app.emit("capabilities-changed", all());
+}
+
+fn all() -> BTreeMap<Capability, CapabilityStatus> {
+    let registry = REGISTRY.lock().map(|r| r.clone()).unwrap_or_default();
+    Capability::ALL
+        .into_iter()
+        .map(|capability| {
+            let status = registry.get(&capability).cloned().unwrap_or_default();
+            (capability, status)
+        })
+        .collect()
+}
+
+/// Tauri command: every capability and whether it works on this machine.
+/// Called from the frontend via `invoke('get_capabilities')`.
+#[tauri::command]
+pub fn get_capabilities() -> BTreeMap<Capability, CapabilityStatus> {
+    all()
+}
+
+/// Tauri command: record a capability only a webview can detect (GPU rendering).
+/// Called from the world window via `invoke('report_capability', { capability, available, reason })`.
+#[tauri::command]
+pub fn report_capability(
+    capability: Capability,
+    available: bool,
+    reason: Option<String>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if capability != Capability::Gpu {
+        return Err(format!("{} is detected natively", capability.name()));
+    }
+    let result = if available {
+        Ok(())
+    } else {
+        Err(reason.unwrap_or_else(|| {
+            "WebGL is unavailable; update the graphics driver or turn on hardware \
+             acceleration"
+                .to_string()
+        }))
+    };
+    record(&app, capability, result);
+    Ok(())
+}
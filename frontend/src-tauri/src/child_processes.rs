@@ -0,0 +1,392 @@
+//! Registry of the processes CrewHub spawns, and cleanup after a crash.
+//!
+//! The backend sidecar, the local model runner, stdio MCP servers, the
+//! automation browser and the OS event watchers are recorded in
+//! `children.json` (app data dir) with who spawned them and when, and removed
+//! once they exit or are stopped. After a clean quit the file is empty; after
+//! a crash it lists what may still be running. At the next launch each
+//! leftover that is still alive — and still the same process, as pids get
+//! reused: same program name and a start time matching `startedAt` — is
+//! killed, except a backend sidecar that still answers, which is
+//! adopted: the supervisor keeps it instead of starting a second one (see
+//! [`crate::backend_process`]).
+//!
+//! `list_child_processes` shows the registry and what startup did with the
+//! leftovers in the debug window.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Runtime};
+
+/// Registry file (app data dir).
+const REGISTRY_FILE: &str = "children.json";
+
+/// Slack between spawning a process and registering it, plus rounding of
+/// the OS start times.
+const START_TOLERANCE_SECS: i64 = 5;
+
+/// Registry path and records of this run; `None` until [`init`].
+static REGISTRY: Mutex<Option<(PathBuf, Vec<ChildProcess>)>> = Mutex::new(None);
+/// What startup did with the last run's leftovers.
+static LEFTOVERS: Mutex<Vec<Leftover>> = Mutex::new(Vec::new());
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Kind {
+    Backend,
+    LocalModel,
+    McpServer,
+    Browser,
+    /// Long-running OS event listeners (`dbus-monitor`, PowerShell).
+    Watcher,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildProcess {
+    pub pid: u32,
+    pub kind: Kind,
+    /// What it is for: MCP server name, model, …
+    pub name: String,
+    /// Program as it was started (path or command name).
+    pub program: String,
+    /// Unix seconds.
+    pub started_at: i64,
+    /// CrewHub process that spawned (or adopted) it.
+    pub owner_pid: u32,
+    /// Left over from a previous run and kept.
+    #[serde(default)]
+    pub adopted: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LeftoverAction {
+    Killed,
+    Adopted,
+    /// Already gone, or the pid belongs to another program now.
+    Gone,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Leftover {
+    process: ChildProcess,
+    action: LeftoverAction,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildProcesses {
+    processes: Vec<ChildProcess>,
+    leftovers: Vec<Leftover>,
+}
+
+fn save(path: &Path, records: &[ChildProcess]) {
+    let written = serde_json::to_string_pretty(records)
+        .map_err(|e| e.to_string())
+        .and_then(|json| store::write_atomic(path, json.as_bytes()).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        eprintln!("[CrewHub] Failed to save {}: {}", REGISTRY_FILE, e);
+    }
+}
+
+fn edit(change: impl FnOnce(&mut Vec<ChildProcess>)) {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        if let Some((path, records)) = registry.as_mut() {
+            change(records);
+            save(path, records);
+        }
+    }
+}
+
+fn file_stem(program: &str) -> String {
+    Path::new(program)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Whether `pid` is alive and still the process of `record`: the file name
+/// matches (macOS may truncate it) and it started when the record says. An
+/// unreadable start time counts as a different process.
+fn runs(record: &ChildProcess) -> bool {
+    platform::executable(record.pid)
+        .is_some_and(|exe| file_stem(&exe.to_string_lossy()) == file_stem(&record.program))
+        && platform::started_at(record.pid)
+            .is_some_and(|started| (started - record.started_at).abs() <= START_TOLERANCE_SECS)
+}
+
+/// Whether `pid` is still alive.
+pub fn is_alive(pid: u32) -> bool {
+    platform::executable(pid).is_some()
+}
+
+/// Kill a process that isn't a `Child` of this run (adopted or left over).
+pub fn kill(pid: u32) {
+    platform::kill(pid);
+    unregister(pid);
+}
+
+/// Record a process this run spawned.
+pub fn register(pid: u32, kind: Kind, name: &str, program: &str) {
+    let record = ChildProcess {
+        pid,
+        kind,
+        name: name.to_string(),
+        program: program.to_string(),
+        started_at: chrono::Utc::now().timestamp(),
+        owner_pid: std::process::id(),
+        adopted: false,
+    };
+    edit(|records| {
+        records.retain(|r| r.pid != pid);
+        records.push(record);
+    });
+}
+
+/// Forget a process that exited or was stopped.
+pub fn unregister(pid: u32) {
+    edit(|records| records.retain(|r| r.pid != pid));
+}
+
+/// Pid of an adopted leftover of `kind`.
+pub fn adopted(kind: Kind) -> Option<u32> {
+    let registry = REGISTRY.lock().ok()?;
+    let (_, records) = registry.as_ref()?;
+    records
+        .iter()
+        .find(|r| r.kind == kind && r.adopted)
+        .map(|r| r.pid)
+}
+
+/// Deal with the last run's leftovers and start this run's registry.
+/// Runs before anything is spawned.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let Ok(path) = store::data_path(app, REGISTRY_FILE) else {
+        return;
+    };
+    let previous: Vec<ChildProcess> = store::load_json(app, REGISTRY_FILE);
+    let sidecar = crate::backend_process::sidecar();
+    let mut kept = Vec::new();
+    let mut leftovers = Vec::new();
+    for mut process in previous {
+        let action = if !runs(&process) {
+            LeftoverAction::Gone
+        } else if process.kind == Kind::Backend
+            && sidecar
+                .as_deref()
+                .is_some_and(|bin| file_stem(&bin.to_string_lossy()) == file_stem(&process.program))
+            && !kept.iter().any(|p: &ChildProcess| p.kind == Kind::Backend)
+            && crate::backend_process::healthy(&crate::backend_url())
+        {
+            process.adopted = true;
+            process.owner_pid = std::process::id();
+            kept.push(process.clone());
+            LeftoverAction::Adopted
+        } else {
+            platform::kill(process.pid);
+            LeftoverAction::Killed
+        };
+        if action != LeftoverAction::Gone {
+            println!(
+                "[CrewHub] {} leftover {} process {} (pid {})",
+                if action == LeftoverAction::Adopted {
+                    "Adopted"
+                } else {
+                    "Killed"
+                },
+                serde_json::to_string(&process.kind).unwrap_or_default(),
+                process.name,
+                process.pid
+            );
+        }
+        leftovers.push(Leftover { process, action });
+    }
+    save(&path, &kept);
+    if let Ok(mut registry) = REGISTRY.lock() {
+        *registry = Some((path, kept));
+    }
+    if let Ok(mut last) = LEFTOVERS.lock() {
+        *last = leftovers;
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    /// Clock ticks per second of `/proc` times (`USER_HZ`, 100 on every
+    /// architecture Linux exposes to user space).
+    const TICKS_PER_SEC: i64 = 100;
+
+    pub fn executable(pid: u32) -> Option<PathBuf> {
+        std::fs::read_link(format!("/proc/{}/exe", pid)).ok()
+    }
+
+    /// Unix seconds: boot time plus the `starttime` field of `/proc/<pid>/stat`.
+    pub fn started_at(pid: u32) -> Option<i64> {
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        // Fields after the parenthesized command name start at field 3.
+        let (_, fields) = stat.rsplit_once(')')?;
+        let ticks: i64 = fields.split_whitespace().nth(19)?.parse().ok()?;
+        let boot: i64 = std::fs::read_to_string("/proc/stat")
+            .ok()?
+            .lines()
+            .find_map(|line| line.strip_prefix("btime "))?
+            .trim()
+            .parse()
+            .ok()?;
+        Some(boot + ticks / TICKS_PER_SEC)
+    }
+
+    pub fn kill(pid: u32) {
+        let _ = Command::new("kill")
+            .args(["-KILL", &pid.to_string()])
+            .status();
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    pub fn executable(pid: u32) -> Option<PathBuf> {
+        let output = Command::new("ps")
+            .args(["-p", &pid.to_string(), "-o", "comm="])
+            .output()
+            .ok()?;
+        let comm = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !comm.is_empty()).then(|| PathBuf::from(comm))
+    }
+
+    /// Unix seconds, from the elapsed time `[[dd-]hh:]mm:ss` that `ps` reports.
+    pub fn started_at(pid: u32) -> Option<i64> {
+        let output = Command::new("ps")
+            .args(["-p", &pid.to_string(), "-o", "etime="])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let etime = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let (days, clock) = match etime.split_once('-') {
+            Some((days, clock)) => (days.parse::<i64>().ok()?, clock),
+            None => (0, etime.as_str()),
+        };
+        let mut elapsed = days * 86_400;
+        let mut unit = 1;
+        for part in clock.rsplit(':') {
+            elapsed += part.parse::<i64>().ok()? * unit;
+            unit *= 60;
+        }
+        Some(chrono::Utc::now().timestamp() - elapsed)
+    }
+
+    pub fn kill(pid: u32) {
+        let _ = Command::new("kill")
+            .args(["-KILL", &pid.to_string()])
+            .status();
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::path::PathBuf;
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::{CloseHandle, FILETIME, STILL_ACTIVE};
+    use windows::Win32::System::Threading::{
+        GetExitCodeProcess, GetProcessTimes, OpenProcess, QueryFullProcessImageNameW,
+        TerminateProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE,
+    };
+
+    /// Seconds between 1601-01-01 (`FILETIME` epoch) and 1970-01-01.
+    const FILETIME_UNIX_OFFSET_SECS: i64 = 11_644_473_600;
+
+    pub fn executable(pid: u32) -> Option<PathBuf> {
+        // SAFETY: the handle is closed below; the buffer is sized by `len`.
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut code = 0u32;
+            let running =
+                GetExitCodeProcess(process, &mut code).is_ok() && code == STILL_ACTIVE.0 as u32;
+            let mut buffer = [0u16; 1024];
+            let mut len = buffer.len() as u32;
+            let named = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(buffer.as_mut_ptr()),
+                &mut len,
+            )
+            .is_ok();
+            let _ = CloseHandle(process);
+            (running && named)
+                .then(|| PathBuf::from(String::from_utf16_lossy(&buffer[..len as usize])))
+        }
+    }
+
+    /// Unix seconds, from the creation time of `GetProcessTimes`.
+    pub fn started_at(pid: u32) -> Option<i64> {
+        // SAFETY: the handle is closed right after use.
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut created = FILETIME::default();
+            let (mut exited, mut kernel, mut user) = (created, created, created);
+            let read =
+                GetProcessTimes(process, &mut created, &mut exited, &mut kernel, &mut user).is_ok();
+            let _ = CloseHandle(process);
+            let ticks =
+                (u64::from(created.dwHighDateTime) << 32) | u64::from(created.dwLowDateTime);
+            read.then(|| (ticks / 10_000_000) as i64 - FILETIME_UNIX_OFFSET_SECS)
+        }
+    }
+
+    pub fn kill(pid: u32) {
+        // SAFETY: the handle is closed right after use.
+        unsafe {
+            if let Ok(process) = OpenProcess(PROCESS_TERMINATE, false, pid) {
+                let _ = TerminateProcess(process, 1);
+                let _ = CloseHandle(process);
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+mod platform {
+    use std::path::PathBuf;
+
+    pub fn executable(_pid: u32) -> Option<PathBuf> {
+        None
+    }
+
+    pub fn started_at(_pid: u32) -> Option<i64> {
+        None
+    }
+
+    pub fn kill(_pid: u32) {}
+}
+
+/// Tauri command: processes CrewHub runs, and what startup did with the last run's leftovers.
+/// Called from the debug window via `invoke('list_child_processes')`.
+#[tauri::command]
+pub async fn list_child_processes() -> Result<ChildProcesses, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        // Children that died on their own are dropped here.
+        let mut processes = Vec::new();
+        edit(|records| {
+            records.retain(|r| is_alive(r.pid));
+            processes = records.clone();
+        });
+        ChildProcesses {
+            processes,
+            leftovers: LEFTOVERS.lock().map(|l| l.clone()).unwrap_or_default(),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
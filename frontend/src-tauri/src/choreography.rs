@@ -0,0 +1,337 @@
+//! Window choreography requested by agents.
+//!
+//! A crew can guide the user's attention with a `ui-choreography` event on
+//! its backend's stream: `{command, args, agent}`. Only a fixed set of
+//! commands exists, and only their known arguments are passed on:
+//!
+//! | Command       | Args         | Effect                                       |
+//! |---------------|--------------|----------------------------------------------|
+//! | `focus-agent` | `agentId`    | opens the 3D world and focuses the agent     |
+//! | `open-diff`   | `worktreeId` | opens the review window for a task worktree  |
+//! | `flash-hud`   | `message`    | shows the HUD for a few seconds with a note  |
+//!
+//! Each command has a consent level: performed, asked about first (a native
+//! dialog; "Always allow" raises the level) or refused. Nothing happens while
+//! CrewHub is quiet ([`crate::dnd`]), while another request is being asked
+//! about, or within a few seconds of the last performed request. Windows get
+//! a `choreography` event (`{command, args}`); a window the request had to
+//! create fetches it with `take_pending_choreography` once loaded.
+
+use crate::federation::BackendProfile;
+use crate::{diff_review, dnd, federation, hud, i18n, store};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, WebviewWindow};
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+
+/// Backend event that carries a choreography request.
+pub const REQUEST_EVENT: &str = "ui-choreography";
+
+/// Persisted consent levels (app data dir).
+const CONFIG_FILE: &str = "choreography.json";
+
+/// Requests within this long of the last performed one are dropped.
+const MIN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Longest HUD message passed on.
+const MAX_MESSAGE_CHARS: usize = 200;
+
+// Dialog buttons (i18n keys; the label is looked up when the dialog is shown).
+const ALLOW_ONCE: &str = "dialog.allowOnce";
+const ALWAYS_ALLOW: &str = "dialog.alwaysAllow";
+const DENY: &str = "dialog.deny";
+
+/// Events waiting for the window they open to load (label → event).
+static PENDING: Mutex<Option<HashMap<String, Value>>> = Mutex::new(None);
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Command {
+    FocusAgent,
+    OpenDiff,
+    FlashHud,
+}
+
+impl Command {
+    fn describe_key(self) -> &'static str {
+        match self {
+            Command::FocusAgent => "choreography.focusAgent",
+            Command::OpenDiff => "choreography.openDiff",
+            Command::FlashHud => "choreography.flashHud",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Consent {
+    Allow,
+    Ask,
+    Deny,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChoreographyConfig {
+    /// Consent per command.
+    pub consent: BTreeMap<Command, Consent>,
+}
+
+impl Default for ChoreographyConfig {
+    fn default() -> Self {
+        Self {
+            consent: [
+                (Command::FocusAgent, Consent::Ask),
+                (Command::OpenDiff, Consent::Ask),
+                (Command::FlashHud, Consent::Allow),
+            ]
+            .into(),
+        }
+    }
+}
+
+impl ChoreographyConfig {
+    fn consent(&self, command: Command) -> Consent {
+        self.consent.get(&command).copied().unwrap_or(Consent::Ask)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Request {
+    command: Command,
+    #[serde(default)]
+    args: Map<String, Value>,
+    /// Agent or crew asking, for the dialog.
+    #[serde(default)]
+    agent: Option<String>,
+}
+
+/// App state: consent levels, the dialog lock and when a request was last performed.
+pub struct ChoreographyState {
+    config: Mutex<ChoreographyConfig>,
+    dialog: Mutex<()>,
+    last_performed: Mutex<Option<Instant>>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(ChoreographyState {
+        config: Mutex::new(store::load_json(app, CONFIG_FILE)),
+        dialog: Mutex::new(()),
+        last_performed: Mutex::new(None),
+    });
+}
+
+/// The known arguments of `command`, or why the request is invalid.
+fn sanitize(command: Command, args: &Map<String, Value>) -> Result<Value, String> {
+    let text = |key: &str| {
+        args.get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    match command {
+        Command::FocusAgent => text("agentId")
+            .map(|id| json!({ "agentId": id }))
+            .ok_or_else(|| "Missing 'agentId'".to_string()),
+        Command::OpenDiff => text("worktreeId")
+            .map(|id| json!({ "worktreeId": id }))
+            .ok_or_else(|| "Missing 'worktreeId'".to_string()),
+        Command::FlashHud => {
+            let message: Option<String> =
+                text("message").map(|message| message.chars().take(MAX_MESSAGE_CHARS).collect());
+            Ok(json!({ "message": message }))
+        }
+    }
+}
+
+/// Handle a `ui-choreography` event from the backend bridge.
+/// Asking blocks, so it runs on its own thread.
+pub fn handle_request<R: Runtime>(app: &AppHandle<R>, backend: &BackendProfile, payload: Value) {
+    let request: Request = match serde_json::from_value(payload) {
+        Ok(request) => request,
+        Err(e) => {
+            eprintln!("[CrewHub] Ignoring malformed choreography request: {}", e);
+            return;
+        }
+    };
+    let args = match sanitize(request.command, &request.args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("[CrewHub] Ignoring choreography request: {}", e);
+            return;
+        }
+    };
+    let app = app.clone();
+    let backend_id = backend.id.clone();
+    std::thread::spawn(move || resolve(&app, &backend_id, &request, args));
+}
+
+fn recently_performed(state: &ChoreographyState) -> bool {
+    state
+        .last_performed
+        .lock()
+        .ok()
+        .and_then(|last| *last)
+        .is_some_and(|at| at.elapsed() < MIN_INTERVAL)
+}
+
+fn resolve<R: Runtime>(app: &AppHandle<R>, backend_id: &str, request: &Request, args: Value) {
+    let state = app.state::<ChoreographyState>();
+    if dnd::quiet(app) || recently_performed(&state) {
+        return;
+    }
+    let consent = state
+        .config
+        .lock()
+        .map(|config| config.consent(request.command))
+        .unwrap_or(Consent::Deny);
+    let allowed = match consent {
+        Consent::Allow => true,
+        Consent::Deny => false,
+        Consent::Ask => {
+            // A request arriving while another is asked about is dropped, not queued.
+            let Ok(_one_at_a_time) = state.dialog.try_lock() else {
+                return;
+            };
+            match ask(app, backend_id, request) {
+                Some(ALWAYS_ALLOW) => {
+                    if let Ok(mut config) = state.config.lock() {
+                        config.consent.insert(request.command, Consent::Allow);
+                        if let Err(e) = store::save_json(app, CONFIG_FILE, &*config) {
+                            eprintln!("[CrewHub] {}", e);
+                        }
+                    }
+                    true
+                }
+                Some(ALLOW_ONCE) => true,
+                _ => false,
+            }
+        }
+    };
+    if !allowed {
+        return;
+    }
+    if let Ok(mut last) = state.last_performed.lock() {
+        *last = Some(Instant::now());
+    }
+    if let Err(e) = perform(app, request.command, args) {
+        eprintln!("[CrewHub] Choreography request failed: {}", e);
+    }
+}
+
+/// Show the consent dialog and return the i18n key of the pressed button.
+fn ask<R: Runtime>(
+    app: &AppHandle<R>,
+    backend_id: &str,
+    request: &Request,
+) -> Option<&'static str> {
+    let agent = request
+        .agent
+        .clone()
+        .unwrap_or_else(|| i18n::t("choreography.anAgent"));
+    let result = app
+        .dialog()
+        .message(i18n::tf(
+            "dialog.choreography",
+            &[
+                ("agent", &agent),
+                ("action", &i18n::t(request.command.describe_key())),
+            ],
+        ))
+        .title(match federation::label(app, backend_id) {
+            Some(backend) => format!(
+                "CrewHub ({}) — {}",
+                backend,
+                i18n::t("dialog.choreographyTitle")
+            ),
+            None => format!("CrewHub — {}", i18n::t("dialog.choreographyTitle")),
+        })
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            i18n::t(ALLOW_ONCE),
+            i18n::t(ALWAYS_ALLOW),
+            i18n::t(DENY),
+        ))
+        .blocking_show_with_result();
+    match result {
+        MessageDialogResult::Custom(label) => [ALLOW_ONCE, ALWAYS_ALLOW, DENY]
+            .into_iter()
+            .find(|key| i18n::t(key) == label),
+        MessageDialogResult::Yes | MessageDialogResult::Ok => Some(ALLOW_ONCE),
+        // "Always allow" only counts when its own label comes back; a
+        // dismissed dialog may report `No`.
+        _ => Some(DENY),
+    }
+}
+
+fn perform<R: Runtime>(app: &AppHandle<R>, command: Command, args: Value) -> Result<(), String> {
+    let window = match command {
+        Command::FocusAgent => {
+            let label = crate::WORLD_WINDOW_LABEL;
+            let already_open = app.get_webview_window(label).is_some();
+            crate::open_or_focus_world(app);
+            (label, already_open)
+        }
+        Command::OpenDiff => {
+            let worktree_id = args["worktreeId"].as_str().unwrap_or_default();
+            return diff_review::open(app, worktree_id);
+        }
+        Command::FlashHud => {
+            let label = hud::HUD_WINDOW_LABEL;
+            let already_open = app.get_webview_window(label).is_some();
+            hud::flash(app);
+            (label, already_open)
+        }
+    };
+    let event = json!({ "command": command, "args": args });
+    match window {
+        (label, true) => {
+            let _ = app.emit_to(label, "choreography", event);
+        }
+        (label, false) => {
+            if let Ok(mut pending) = PENDING.lock() {
+                pending
+                    .get_or_insert_with(HashMap::new)
+                    .insert(label.to_string(), event);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Tauri command: the choreography request that opened this window, if any.
+/// Called from the world and HUD windows via `invoke('take_pending_choreography')`.
+#[tauri::command]
+pub fn take_pending_choreography(window: WebviewWindow) -> Option<Value> {
+    PENDING.lock().ok()?.as_mut()?.remove(window.label())
+}
+
+/// Tauri command: consent levels of agent choreography commands.
+/// Called from the settings window via `invoke('get_choreography_config')`.
+#[tauri::command]
+pub fn get_choreography_config(
+    state: State<ChoreographyState>,
+) -> Result<ChoreographyConfig, String> {
+    Ok(state.config.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: set the consent level of one choreography command.
+/// Called from the settings window via `invoke('set_choreography_consent', { command, consent })`.
+#[tauri::command]
+pub fn set_choreography_consent(
+    command: Command,
+    consent: Consent,
+    app: AppHandle,
+    state: State<ChoreographyState>,
+) -> Result<ChoreographyConfig, String> {
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.consent.insert(command, consent);
+    store::save_json(&app, CONFIG_FILE, &*config)?;
+    Ok(config.clone())
+}
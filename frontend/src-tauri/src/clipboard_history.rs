@@ -0,0 +1,231 @@
+//! Opt-in clipboard history for the quick prompt and command palette.
+//!
+//! While enabled, a background thread polls the clipboard for text and keeps
+//! the most recent entries in memory, so "paste the thing I copied ten
+//! minutes ago" can be found by search. History is never written to disk (the
+//! clipboard often holds passwords and tokens) and is capped both in entries
+//! and in size per entry. Under memory pressure the entry cap drops to
+//! [`PRESSURE_MAX_ITEMS`] (see [`crate::resources`]).
+
+use crate::{power, resources, store};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "clipboard-history.json";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Entries longer than this are skipped rather than truncated.
+const MAX_ENTRY_BYTES: usize = 32 * 1024;
+
+/// Most entries kept while system memory is under pressure.
+const PRESSURE_MAX_ITEMS: usize = 10;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ClipboardHistoryConfig {
+    pub enabled: bool,
+    pub max_items: usize,
+}
+
+impl Default for ClipboardHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_items: 50,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardEntry {
+    id: u64,
+    text: String,
+    /// Unix seconds when the text was copied.
+    copied_at: i64,
+}
+
+/// App state: config, entries (newest first) and the last text seen.
+pub struct ClipboardHistoryState {
+    config: Mutex<ClipboardHistoryConfig>,
+    entries: Mutex<VecDeque<ClipboardEntry>>,
+    last_seen: Mutex<Option<String>>,
+    next_id: Mutex<u64>,
+}
+
+/// Load the config, register state, and start the poll thread.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: ClipboardHistoryConfig = store::load_json(app, CONFIG_FILE);
+    app.manage(ClipboardHistoryState {
+        config: Mutex::new(config),
+        entries: Mutex::new(VecDeque::new()),
+        last_seen: Mutex::new(None),
+        next_id: Mutex::new(1),
+    });
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(power::interval(&app, POLL_INTERVAL));
+        poll(&app);
+    });
+}
+
+/// Entries to keep: the configured cap, lowered under memory pressure.
+fn capacity(config: &ClipboardHistoryConfig) -> usize {
+    if resources::memory_pressure() {
+        config.max_items.min(PRESSURE_MAX_ITEMS)
+    } else {
+        config.max_items
+    }
+}
+
+/// Drop entries past the current capacity.
+pub fn trim<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<ClipboardHistoryState>() else {
+        return;
+    };
+    let Some(max_items) = state.config.lock().ok().map(|config| capacity(&config)) else {
+        return;
+    };
+    if let Ok(mut entries) = state.entries.lock() {
+        entries.truncate(max_items);
+    };
+}
+
+fn poll<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<ClipboardHistoryState>();
+    let max_items = match state.config.lock() {
+        Ok(config) if config.enabled => capacity(&config),
+        _ => return,
+    };
+    let Ok(text) = app.clipboard().read_text() else {
+        return;
+    };
+    {
+        let Ok(mut last_seen) = state.last_seen.lock() else {
+            return;
+        };
+        if last_seen.as_deref() == Some(text.as_str()) {
+            return;
+        }
+        *last_seen = Some(text.clone());
+    }
+    if text.trim().is_empty() || text.len() > MAX_ENTRY_BYTES {
+        return;
+    }
+    let id = match state.next_id.lock() {
+        Ok(mut next) => {
+            *next += 1;
+            *next - 1
+        }
+        Err(_) => return,
+    };
+    if let Ok(mut entries) = state.entries.lock() {
+        // Copying the same text again moves it to the top.
+        entries.retain(|entry| entry.text != text);
+        entries.push_front(ClipboardEntry {
+            id,
+            text,
+            copied_at: chrono::Local::now().timestamp(),
+        });
+        entries.truncate(max_items);
+    }
+    let _ = app.emit("clipboard-history-changed", ());
+}
+
+/// Tauri command: clipboard history config.
+/// Called from the frontend via `invoke('get_clipboard_history_config')`.
+#[tauri::command]
+pub fn get_clipboard_history_config(
+    state: State<ClipboardHistoryState>,
+) -> Result<ClipboardHistoryConfig, String> {
+    Ok(state.config.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: enable/disable the history and/or change how many entries it keeps.
+/// Disabling also clears it. Called from the frontend via
+/// `invoke('configure_clipboard_history', { enabled, maxItems })`.
+#[tauri::command]
+pub fn configure_clipboard_history(
+    enabled: bool,
+    max_items: Option<usize>,
+    app: AppHandle,
+    state: State<ClipboardHistoryState>,
+) -> Result<ClipboardHistoryConfig, String> {
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.enabled = enabled;
+    if let Some(max_items) = max_items {
+        config.max_items = max_items.clamp(1, 500);
+    }
+    store::save_json(&app, CONFIG_FILE, &*config)?;
+    let mut entries = state.entries.lock().map_err(|e| e.to_string())?;
+    if enabled {
+        entries.truncate(capacity(&config));
+    } else {
+        entries.clear();
+    }
+    Ok(config.clone())
+}
+
+/// Tauri command: history entries, newest first, optionally filtered by a
+/// case-insensitive substring. Called from the quick prompt and command palette via
+/// `invoke('search_clipboard_history', { query, limit })`.
+#[tauri::command]
+pub fn search_clipboard_history(
+    query: Option<String>,
+    limit: Option<usize>,
+    state: State<ClipboardHistoryState>,
+) -> Result<Vec<ClipboardEntry>, String> {
+    let query = query.unwrap_or_default().to_lowercase();
+    let entries = state.entries.lock().map_err(|e| e.to_string())?;
+    Ok(entries
+        .iter()
+        .filter(|entry| query.is_empty() || entry.text.to_lowercase().contains(&query))
+        .take(limit.unwrap_or(usize::MAX))
+        .cloned()
+        .collect())
+}
+
+/// Tauri command: put a history entry back on the clipboard (ready to paste).
+/// Called from the frontend via `invoke('copy_clipboard_entry', { id })`.
+#[tauri::command]
+pub fn copy_clipboard_entry(
+    id: u64,
+    app: AppHandle,
+    state: State<ClipboardHistoryState>,
+) -> Result<(), String> {
+    let text = state
+        .entries
+        .lock()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .find(|entry| entry.id == id)
+        .map(|entry| entry.text.clone())
+        .ok_or_else(|| format!("Clipboard entry not found: {}", id))?;
+    app.clipboard().write_text(text).map_err(|e| e.to_string())
+}
+
+/// Tauri command: remove one entry from the history.
+/// Called from the frontend via `invoke('delete_clipboard_entry', { id })`.
+#[tauri::command]
+pub fn delete_clipboard_entry(id: u64, state: State<ClipboardHistoryState>) -> Result<(), String> {
+    state
+        .entries
+        .lock()
+        .map_err(|e| e.to_string())?
+        .retain(|entry| entry.id != id);
+    Ok(())
+}
+
+/// Tauri command: forget the whole history.
+/// Called from the frontend via `invoke('clear_clipboard_history')`.
+#[tauri::command]
+pub fn clear_clipboard_history(state: State<ClipboardHistoryState>) -> Result<(), String> {
+    state.entries.lock().map_err(|e| e.to_string())?.clear();
+    Ok(())
+}
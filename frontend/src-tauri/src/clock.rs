@@ -0,0 +1,160 @@
+//! Clock and time-zone change awareness.
+//!
+//! Every few seconds the wall clock is compared against the monotonic clock
+//! and the local UTC offset against the last one seen; on Linux timedated's
+//! `PropertiesChanged` signal triggers the same check right away. Three kinds
+//! of change are told apart:
+//!
+//! - the clock was set back: schedules that had already been checked past the
+//!   new time are rewound so nothing is silently skipped, and a running focus
+//!   timer keeps its real remaining time;
+//! - a daylight-saving shift: cron times are local, so only the next run
+//!   times change;
+//! - a move to another time zone: pending timed reminders keep their local
+//!   wall-clock time ("at 4pm" stays 4pm).
+//!
+//! Each is followed by a `schedules-adjusted` event carrying the schedules'
+//! recomputed next runs. Clocks jumping forward look the same as waking from
+//! sleep and are left to [`crate::power`] and the catch-up policies.
+
+use crate::{focus_timer, reminders, scheduler};
+use chrono::{DateTime, Local, Offset, TimeZone, Utc};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// How often the clocks are compared.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Wall-clock drift (seconds) beyond which the clock counts as set back.
+const SET_BACK_TOLERANCE: i64 = 30;
+
+/// Last reading of both clocks and the UTC offset.
+struct Baseline {
+    wall: SystemTime,
+    monotonic: Instant,
+    offset: i32,
+}
+
+static BASELINE: Mutex<Option<Baseline>> = Mutex::new(None);
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum Reason {
+    ClockSetBack,
+    DaylightSaving,
+    TimeZoneChanged,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Adjustment {
+    reason: Reason,
+    /// How far the clock jumped or the UTC offset moved, in seconds.
+    seconds: i64,
+    schedules: Vec<scheduler::ScheduleView>,
+}
+
+/// Start watching for clock and time-zone changes.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    check(app);
+    {
+        let app = app.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(CHECK_INTERVAL);
+            check(&app);
+        });
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let app = app.clone();
+        std::thread::spawn(move || {
+            let result = crate::power::follow(
+                "dbus-monitor",
+                &[
+                    "--system",
+                    "type='signal',interface='org.freedesktop.DBus.Properties',\
+                     member='PropertiesChanged',path='/org/freedesktop/timedate1'",
+                ],
+                |line| {
+                    if line.contains("member=PropertiesChanged") {
+                        check(&app);
+                    }
+                },
+            );
+            if let Err(e) = result {
+                eprintln!("[CrewHub] Time zone change signals unavailable: {}", e);
+            }
+        });
+    }
+}
+
+fn offset_at(at: DateTime<Utc>) -> i32 {
+    Local
+        .from_utc_datetime(&at.naive_utc())
+        .offset()
+        .fix()
+        .local_minus_utc()
+}
+
+/// Compare the clocks against the last reading and adjust for any change.
+fn check<R: Runtime>(app: &AppHandle<R>) {
+    let wall = SystemTime::now();
+    let monotonic = Instant::now();
+    let offset = offset_at(Utc::now());
+    let previous = {
+        let Ok(mut baseline) = BASELINE.lock() else {
+            return;
+        };
+        baseline.replace(Baseline {
+            wall,
+            monotonic,
+            offset,
+        })
+    };
+    let Some(previous) = previous else {
+        return;
+    };
+
+    let expected = previous.wall + monotonic.duration_since(previous.monotonic);
+    if let Err(behind) = wall.duration_since(expected) {
+        let seconds = -(behind.duration().as_secs() as i64);
+        if seconds < -SET_BACK_TOLERANCE {
+            println!("[CrewHub] Clock was set back by {}s", -seconds);
+            scheduler::rewind(app);
+            focus_timer::shift(app, seconds);
+            adjusted(app, Reason::ClockSetBack, seconds);
+        }
+    }
+
+    if offset != previous.offset {
+        let seconds = i64::from(offset - previous.offset);
+        // Same rules, different offset: the zone's daylight-saving transition.
+        // Otherwise the previous moment reads differently now: a new zone.
+        let previous_utc = DateTime::<Utc>::from(previous.wall);
+        if offset_at(previous_utc) == previous.offset {
+            println!(
+                "[CrewHub] Daylight saving time shifted the clock by {}s",
+                seconds
+            );
+            adjusted(app, Reason::DaylightSaving, seconds);
+        } else {
+            println!(
+                "[CrewHub] Time zone changed (UTC offset moved by {}s)",
+                seconds
+            );
+            reminders::keep_local_times(app, seconds);
+            adjusted(app, Reason::TimeZoneChanged, seconds);
+        }
+    }
+}
+
+fn adjusted<R: Runtime>(app: &AppHandle<R>, reason: Reason, seconds: i64) {
+    let adjustment = Adjustment {
+        reason,
+        seconds,
+        schedules: scheduler::views(app),
+    };
+    let _ = app.emit("schedules-adjusted", adjustment);
+}
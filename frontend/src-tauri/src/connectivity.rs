@@ -0,0 +1,182 @@
+//! Network connectivity monitoring (online / offline / captive portal).
+//!
+//! Every few seconds the detector checks whether the machine has a route to
+//! the internet at all (a UDP `connect`, which sends no packets) and, if so,
+//! fetches a "generate_204" URL: anything other than an empty 204 means a
+//! captive portal is intercepting traffic. Transitions are emitted as
+//! `connectivity-changed` so the UI can show "offline" at once, and coming
+//! back online makes the backend bridge reconnect immediately instead of
+//! waiting out its backoff.
+
+use crate::{bridge, power, store};
+use serde::{Deserialize, Serialize};
+use std::net::UdpSocket;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "connectivity.json";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ConnectivityConfig {
+    /// Probe for captive portals (one small HTTP request per check).
+    pub captive_portal_check: bool,
+    /// URL that answers an empty `204 No Content` when not intercepted.
+    pub check_url: String,
+}
+
+impl Default for ConnectivityConfig {
+    fn default() -> Self {
+        Self {
+            captive_portal_check: true,
+            check_url: "http://connectivitycheck.gstatic.com/generate_204".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Connectivity {
+    Online,
+    Offline,
+    CaptivePortal,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityStatus {
+    state: Connectivity,
+    /// Unix seconds of the last state change.
+    since: i64,
+    #[serde(flatten)]
+    config: ConnectivityConfig,
+}
+
+/// App state: config and the last detected state.
+pub struct ConnectivityState {
+    config: Mutex<ConnectivityConfig>,
+    current: Mutex<(Connectivity, i64)>,
+}
+
+/// Register state and start the detector thread.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: ConnectivityConfig = store::load_json(app, CONFIG_FILE);
+    // Assume online until the first check (which runs right away) says otherwise.
+    app.manage(ConnectivityState {
+        config: Mutex::new(config),
+        current: Mutex::new((Connectivity::Online, chrono::Local::now().timestamp())),
+    });
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        if !power::is_suspended() {
+            check(&app);
+        }
+        std::thread::sleep(power::interval(&app, CHECK_INTERVAL));
+    });
+}
+
+/// Whether there is a route to the internet (no packets are sent).
+fn has_route() -> bool {
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| socket.connect("1.1.1.1:80"))
+        .is_ok()
+}
+
+fn detect(config: &ConnectivityConfig) -> Connectivity {
+    if !has_route() {
+        return Connectivity::Offline;
+    }
+    if !config.captive_portal_check {
+        return Connectivity::Online;
+    }
+    let result = ureq::AgentBuilder::new()
+        .timeout(PROBE_TIMEOUT)
+        .redirects(0)
+        .build()
+        .get(&config.check_url)
+        .call();
+    match result {
+        Ok(response) if response.status() == 204 => Connectivity::Online,
+        // A redirect or a login page instead of the empty 204.
+        Ok(_) | Err(ureq::Error::Status(..)) => Connectivity::CaptivePortal,
+        Err(_) => Connectivity::Offline,
+    }
+}
+
+fn check<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<ConnectivityState>();
+    let Ok(config) = state.config.lock().map(|c| c.clone()) else {
+        return;
+    };
+    let detected = detect(&config);
+    let previous = {
+        let Ok(mut current) = state.current.lock() else {
+            return;
+        };
+        if current.0 == detected {
+            return;
+        }
+        let previous = current.0;
+        *current = (detected, chrono::Local::now().timestamp());
+        previous
+    };
+    println!("[CrewHub] Connectivity changed: {:?}", detected);
+    if detected == Connectivity::Online && previous != Connectivity::Online {
+        bridge::resume(app);
+    }
+    crate::refresh_tray_tooltip(app);
+    let _ = app.emit("connectivity-changed", &status(&state));
+}
+
+/// The last detected state (online until the first check says otherwise).
+pub fn current<R: Runtime>(app: &AppHandle<R>) -> Connectivity {
+    app.try_state::<ConnectivityState>()
+        .and_then(|state| state.current.lock().ok().map(|current| current.0))
+        .unwrap_or(Connectivity::Online)
+}
+
+fn status(state: &ConnectivityState) -> ConnectivityStatus {
+    let (current, since) = state
+        .current
+        .lock()
+        .map(|c| *c)
+        .unwrap_or((Connectivity::Online, 0));
+    ConnectivityStatus {
+        state: current,
+        since,
+        config: state.config.lock().map(|c| c.clone()).unwrap_or_default(),
+    }
+}
+
+/// Tauri command: current connectivity state and detector config.
+/// Called from the frontend via `invoke('get_connectivity')`.
+#[tauri::command]
+pub fn get_connectivity(state: State<ConnectivityState>) -> ConnectivityStatus {
+    status(&state)
+}
+
+/// Tauri command: configure captive-portal probing and/or the probe URL.
+/// Called from the settings window via `invoke('configure_connectivity', { captivePortalCheck, checkUrl })`.
+#[tauri::command]
+pub fn configure_connectivity(
+    captive_portal_check: bool,
+    check_url: Option<String>,
+    app: AppHandle,
+    state: State<ConnectivityState>,
+) -> Result<ConnectivityStatus, String> {
+    {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.captive_portal_check = captive_portal_check;
+        if let Some(url) = check_url {
+            url::Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
+            config.check_url = url;
+        }
+        store::save_json(&app, CONFIG_FILE, &*config)?;
+    }
+    Ok(status(&state))
+}
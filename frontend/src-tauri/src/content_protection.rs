@@ -0,0 +1,91 @@
+//! Content protection for sensitive conversations.
+//!
+//! A protected window is left out of screen shares, recordings and
+//! screenshots: `SetWindowDisplayAffinity(WDA_EXCLUDEFROMCAPTURE)` on
+//! Windows (it shows up black before Windows 10 2004), `NSWindow.sharingType
+//! = none` on macOS. X11 and Wayland have no way for a client to opt out of
+//! capture, so there the setting is stored but has no effect
+//! (`get_content_protection` reports `supported: false`). Protection is per
+//! window label, persisted in `content-protection.json` and applied as soon
+//! as the window is created ([`plugin`]).
+
+use crate::store;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Labels of protected windows (app data dir).
+const CONFIG_FILE: &str = "content-protection.json";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentProtection {
+    /// The platform can exclude windows from capture.
+    supported: bool,
+    /// Labels of protected windows.
+    windows: BTreeSet<String>,
+}
+
+fn protected<R: Runtime>(app: &AppHandle<R>) -> BTreeSet<String> {
+    store::load_json(app, CONFIG_FILE)
+}
+
+/// Whether a window is kept out of captures (also CrewHub's own thumbnails).
+pub fn is_protected<R: Runtime>(app: &AppHandle<R>, label: &str) -> bool {
+    protected(app).contains(label)
+}
+
+fn supported() -> bool {
+    cfg!(any(target_os = "windows", target_os = "macos"))
+}
+
+/// Plugin that protects every new window that was protected before.
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("content-protection")
+        .on_window_ready(|window| {
+            if protected(window.app_handle()).contains(window.label()) {
+                if let Err(e) = window.set_content_protected(true) {
+                    eprintln!("[CrewHub] Failed to protect '{}': {}", window.label(), e);
+                }
+            }
+        })
+        .build()
+}
+
+/// Tauri command: keep a window out of screen shares and screenshots (or stop).
+/// Called from the frontend via `invoke('set_content_protected', { label, enabled })`.
+#[tauri::command]
+pub fn set_content_protected(
+    label: String,
+    enabled: bool,
+    app: AppHandle,
+) -> Result<ContentProtection, String> {
+    let mut windows = protected(&app);
+    if enabled {
+        windows.insert(label.clone());
+        crate::thumbnails::forget(&label);
+    } else {
+        windows.remove(&label);
+    }
+    store::save_json(&app, CONFIG_FILE, &windows)?;
+    if let Some(window) = app.get_webview_window(&label) {
+        window
+            .set_content_protected(enabled)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(ContentProtection {
+        supported: supported(),
+        windows,
+    })
+}
+
+/// Tauri command: protected windows and whether protection works here.
+/// Called from the frontend via `invoke('get_content_protection')`.
+#[tauri::command]
+pub fn get_content_protection(app: AppHandle) -> ContentProtection {
+    ContentProtection {
+        supported: supported(),
+        windows: protected(&app),
+    }
+}
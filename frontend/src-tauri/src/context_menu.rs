@@ -0,0 +1,293 @@
+//! "Send to CrewHub" in file manager context menus.
+//!
+//! Installs a per-user entry that starts CrewHub with `--share <paths>`; the
+//! single-instance plugin hands those to the running app, which attaches the
+//! files in the quick-prompt window (see [`crate::share`]):
+//! - macOS: a Finder Quick Action (`~/Library/Services/Send to CrewHub.workflow`);
+//! - Windows: a shell verb for files and folders under `HKCU\Software\Classes`;
+//! - Linux: a Nautilus script (`~/.local/share/nautilus/scripts`).
+
+use serde::Serialize;
+#[cfg(not(target_os = "windows"))]
+use std::path::PathBuf;
+
+/// Menu entry title.
+const ENTRY_NAME: &str = "Send to CrewHub";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextMenuStatus {
+    pub installed: bool,
+    /// Where the entry lives (file path or registry key).
+    pub location: String,
+}
+
+#[cfg(not(target_os = "windows"))]
+fn home() -> Result<PathBuf, String> {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .ok_or_else(|| "No home directory".to_string())
+}
+
+fn exe() -> Result<String, String> {
+    std::env::current_exe()
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Quote for a POSIX shell.
+#[cfg(not(target_os = "windows"))]
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+fn location() -> Result<PathBuf, String> {
+    let data = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => home()?.join(".local/share"),
+    };
+    Ok(data.join("nautilus/scripts").join(ENTRY_NAME))
+}
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+fn install() -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    let path = location()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    // Nautilus passes the selected paths as arguments.
+    let script = format!("#!/bin/sh\nexec {} --share \"$@\"\n", shell_quote(&exe()?));
+    std::fs::write(&path, script).map_err(|e| e.to_string())?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+fn uninstall() -> Result<(), String> {
+    let path = location()?;
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+fn status() -> Result<ContextMenuStatus, String> {
+    let path = location()?;
+    Ok(ContextMenuStatus {
+        installed: path.exists(),
+        location: path.to_string_lossy().to_string(),
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn location() -> Result<PathBuf, String> {
+    Ok(home()?
+        .join("Library/Services")
+        .join(format!("{}.workflow", ENTRY_NAME)))
+}
+
+#[cfg(target_os = "macos")]
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Service definition: shows the entry for any Finder selection.
+#[cfg(target_os = "macos")]
+const WORKFLOW_INFO_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>NSServices</key>
+    <array>
+        <dict>
+            <key>NSMenuItem</key>
+            <dict><key>default</key><string>Send to CrewHub</string></dict>
+            <key>NSMessage</key>
+            <string>runWorkflowAsService</string>
+            <key>NSRequiredContext</key>
+            <dict><key>NSApplicationIdentifier</key><string>com.apple.finder</string></dict>
+            <key>NSSendFileTypes</key>
+            <array><string>public.item</string></array>
+        </dict>
+    </array>
+</dict>
+</plist>
+"#;
+
+/// A single "Run Shell Script" action receiving the selection as arguments.
+#[cfg(target_os = "macos")]
+const WORKFLOW_DOCUMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>AMApplicationBuild</key><string>523</string>
+    <key>AMApplicationVersion</key><string>2.10</string>
+    <key>AMDocumentVersion</key><string>2</string>
+    <key>actions</key>
+    <array>
+        <dict>
+            <key>action</key>
+            <dict>
+                <key>AMAccepts</key>
+                <dict>
+                    <key>Container</key><string>List</string>
+                    <key>Optional</key><true/>
+                    <key>Types</key><array><string>com.apple.cocoa.path</string></array>
+                </dict>
+                <key>AMActionVersion</key><string>2.0.3</string>
+                <key>AMProvides</key>
+                <dict>
+                    <key>Container</key><string>List</string>
+                    <key>Types</key><array><string>com.apple.cocoa.string</string></array>
+                </dict>
+                <key>ActionBundlePath</key><string>/System/Library/Automator/Run Shell Script.action</string>
+                <key>ActionName</key><string>Run Shell Script</string>
+                <key>ActionParameters</key>
+                <dict>
+                    <key>COMMAND_STRING</key><string>{command}</string>
+                    <key>CheckedForUserDefaultShell</key><true/>
+                    <key>inputMethod</key><integer>1</integer>
+                    <key>shell</key><string>/bin/sh</string>
+                    <key>source</key><string></string>
+                </dict>
+                <key>BundleIdentifier</key><string>com.apple.RunShellScript</string>
+                <key>CFBundleVersion</key><string>2.0.3</string>
+                <key>Class Name</key><string>RunShellScriptAction</string>
+                <key>InputUUID</key><string>{input}</string>
+                <key>OutputUUID</key><string>{output}</string>
+                <key>UUID</key><string>{uuid}</string>
+            </dict>
+        </dict>
+    </array>
+    <key>connectors</key><dict/>
+    <key>workflowMetaData</key>
+    <dict>
+        <key>serviceInputTypeIdentifier</key><string>com.apple.Automator.fileSystemObject</string>
+        <key>serviceOutputTypeIdentifier</key><string>com.apple.Automator.nothing</string>
+        <key>serviceProcessesInput</key><integer>0</integer>
+        <key>workflowTypeIdentifier</key><string>com.apple.Automator.servicesMenu</string>
+    </dict>
+</dict>
+</plist>
+"#;
+
+#[cfg(target_os = "macos")]
+fn install() -> Result<(), String> {
+    let contents = location()?.join("Contents");
+    std::fs::create_dir_all(&contents).map_err(|e| e.to_string())?;
+    let command = format!("{} --share \"$@\"", shell_quote(&exe()?));
+    let uuid = || uuid::Uuid::new_v4().to_string().to_uppercase();
+    let document = WORKFLOW_DOCUMENT
+        .replace("{command}", &xml_escape(&command))
+        .replace("{input}", &uuid())
+        .replace("{output}", &uuid())
+        .replace("{uuid}", &uuid());
+    std::fs::write(contents.join("Info.plist"), WORKFLOW_INFO_PLIST).map_err(|e| e.to_string())?;
+    std::fs::write(contents.join("document.wflow"), document).map_err(|e| e.to_string())?;
+    refresh_services();
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn uninstall() -> Result<(), String> {
+    let path = location()?;
+    if path.exists() {
+        std::fs::remove_dir_all(&path).map_err(|e| e.to_string())?;
+    }
+    refresh_services();
+    Ok(())
+}
+
+/// Ask the pasteboard server to re-read the Services menu; best effort.
+#[cfg(target_os = "macos")]
+fn refresh_services() {
+    let _ = std::process::Command::new("/System/Library/CoreServices/pbs")
+        .arg("-update")
+        .status();
+}
+
+#[cfg(target_os = "macos")]
+fn status() -> Result<ContextMenuStatus, String> {
+    let path = location()?;
+    Ok(ContextMenuStatus {
+        installed: path.join("Contents/document.wflow").exists(),
+        location: path.to_string_lossy().to_string(),
+    })
+}
+
+/// Shell verb keys: one for files (`*`), one for folders.
+#[cfg(target_os = "windows")]
+const VERB_KEYS: [&str; 2] = [
+    r"HKCU\Software\Classes\*\shell\CrewHub",
+    r"HKCU\Software\Classes\Directory\shell\CrewHub",
+];
+
+#[cfg(target_os = "windows")]
+fn reg(args: &[&str]) -> Result<bool, String> {
+    use std::os::windows::process::CommandExt;
+    std::process::Command::new("reg")
+        .args(args)
+        // CREATE_NO_WINDOW
+        .creation_flags(0x0800_0000)
+        .output()
+        .map(|output| output.status.success())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn install() -> Result<(), String> {
+    let exe = exe()?;
+    let command = format!("\"{}\" --share \"%1\"", exe);
+    for key in VERB_KEYS {
+        let command_key = format!(r"{}\command", key);
+        let ok = reg(&["add", key, "/ve", "/d", ENTRY_NAME, "/f"])?
+            && reg(&["add", key, "/v", "Icon", "/d", &exe, "/f"])?
+            && reg(&["add", &command_key, "/ve", "/d", &command, "/f"])?;
+        if !ok {
+            return Err(format!("Failed to write {}", key));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn uninstall() -> Result<(), String> {
+    for key in VERB_KEYS {
+        // Fails when the key is already gone, which is fine.
+        reg(&["delete", key, "/f"])?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn status() -> Result<ContextMenuStatus, String> {
+    Ok(ContextMenuStatus {
+        installed: reg(&["query", &format!(r"{}\command", VERB_KEYS[0])])?,
+        location: VERB_KEYS[0].to_string(),
+    })
+}
+
+/// Tauri command: whether the "Send to CrewHub" context menu entry is installed.
+/// Called from the settings window via `invoke('get_context_menu_status')`.
+#[tauri::command]
+pub fn get_context_menu_status() -> Result<ContextMenuStatus, String> {
+    status()
+}
+
+/// Tauri command: install (or with `enabled: false`, remove) the file manager context menu entry.
+/// Called from the settings window via `invoke('set_context_menu_entry', { enabled })`.
+#[tauri::command]
+pub fn set_context_menu_entry(enabled: bool) -> Result<ContextMenuStatus, String> {
+    if enabled {
+        install()?;
+    } else {
+        uninstall()?;
+    }
+    status()
+}
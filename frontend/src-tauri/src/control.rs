@@ -0,0 +1,627 @@
+//! Local control surface for external controllers (Stream Deck plugins,
+//! scripts, launchers).
+//!
+//! Endpoints (all on 127.0.0.1, bearer-token protected, opt-in):
+//! - `GET  /control/actions`        — available actions
+//! - `POST /control/actions/<name>` — perform an action; optional JSON args
+//! - `GET  /control/state`          — unread count, agent activity, window visibility
+//! - `GET  /control/query?q=…`      — search recent/pinned conversations and agents
+//!   (for Raycast/Alfred); each item carries a `crewhub://` link and the action that opens it
+//! - `GET  /control/events`         — SSE stream of `state` events (for button feedback);
+//!   also accepts `?token=` because `EventSource` cannot set headers. Streams
+//!   end when the server stops or the token changes; at most [`MAX_STREAMS`]
+//!   run at once
+//!
+//! The same actions, state and query are also offered over a local socket
+//! or named pipe with `socket` enabled (see [`crate::control_socket`]).
+
+use crate::pins::{self, ConversationRef};
+use crate::{
+    automation, backend, control_socket, dnd, hud, layout_profiles, local_http, power, presence,
+    quick_actions, quick_prompt, recents, store, switcher,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "control.json";
+
+const DEFAULT_PORT: u16 = 8096;
+
+/// How often an event stream checks for state changes.
+const STATE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// An idle event stream sends a comment this often, so a stream whose
+/// client went away fails its write and ends.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Event streams served at once.
+const MAX_STREAMS: usize = 8;
+
+/// Actions and what they do, as listed by `GET /control/actions`.
+pub const ACTIONS: &[(&str, &str)] = &[
+    ("toggle-chat", "Show or hide the chat window"),
+    ("open-chat", "Open the chat window"),
+    ("open-world", "Open the 3D world"),
+    ("open-settings", "Open settings"),
+    ("start-focus", "Start focus (Zen) mode"),
+    ("toggle-focus", "Start or leave focus (Zen) mode"),
+    ("open-quick-prompt", "Open the quick-prompt window"),
+    ("open-switcher", "Open the keyboard window switcher"),
+    (
+        "toggle-window",
+        "Hide a window if it is in front, show it otherwise; args: {\"id\": \"chat\" | \"zen\" | \"world\" | \"settings\"}",
+    ),
+    ("toggle-hud", "Show or hide the HUD overlay"),
+    ("toggle-dnd", "Turn CrewHub's Do Not Disturb on or off"),
+    ("mark-all-read", "Clear the unread count everywhere"),
+    ("quick-action", "Run a quick action; args: {\"id\": \"…\"}"),
+    (
+        "apply-layout",
+        "Switch to a window layout profile; args: {\"id\": \"…\"}",
+    ),
+    (
+        "send-prompt",
+        "Send a prompt to an agent session and wait for the reply; args: {\"sessionKey\": \"…\", \"message\": \"…\"}",
+    ),
+    ("get-state", "Unread count, agent activity, window visibility"),
+    (
+        "open-pinned",
+        "Open a pinned conversation; args: {\"index\": 0}",
+    ),
+    (
+        "open-conversation",
+        "Open a conversation; args: {\"sessionKey\": \"…\", \"backendId\": \"…\"}",
+    ),
+    (
+        "open-agent",
+        "Open the chat with an agent; args: {\"agentId\": \"…\"}",
+    ),
+    (
+        "open-room",
+        "Open the 3D world in a room; args: {\"roomId\": \"…\"}",
+    ),
+];
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ControlConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+    /// Also listen on the local socket / named pipe.
+    pub socket: bool,
+}
+
+impl Default for ControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: local_http::new_token(),
+            socket: false,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlStatus {
+    #[serde(flatten)]
+    config: ControlConfig,
+    running: bool,
+    url: String,
+    /// Socket path or pipe name while the socket is listening.
+    socket_path: Option<String>,
+}
+
+/// App state: control server config plus the running listener (if any).
+pub struct ControlState {
+    config: Mutex<ControlConfig>,
+    server: Mutex<Option<local_http::Listener>>,
+    /// Bumped when the server stops or the token changes; event streams
+    /// opened under an older generation end.
+    generation: AtomicU64,
+    streams: AtomicUsize,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let mut config: ControlConfig = store::load_json(app, CONFIG_FILE);
+    if automation::enabled() {
+        // A fixed endpoint for test drivers; not saved.
+        config.enabled = true;
+        config.port = DEFAULT_PORT;
+        config.token = automation::token();
+    } else {
+        // Persist a token generated for a missing one.
+        if let Err(e) = store::save_json(app, CONFIG_FILE, &config) {
+            eprintln!("[CrewHub] {}", e);
+        }
+    }
+    let enabled = config.enabled;
+    let port = config.port;
+    let socket = config.socket;
+    app.manage(ControlState {
+        config: Mutex::new(config),
+        server: Mutex::new(None),
+        generation: AtomicU64::new(0),
+        streams: AtomicUsize::new(0),
+    });
+    if enabled {
+        if let Err(e) = start(app, port) {
+            eprintln!("[CrewHub] Failed to start control server: {}", e);
+        }
+    }
+    if socket {
+        if let Err(e) = control_socket::start(app) {
+            eprintln!("[CrewHub] Failed to start control socket: {}", e);
+        }
+    }
+}
+
+/// Current control token (checked by [`control_socket`] too).
+pub fn token<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    let state = app.try_state::<ControlState>()?;
+    let token = state.config.lock().ok()?.token.clone();
+    Some(token)
+}
+
+/// Listen on `port`; a listener already running on another port is
+/// replaced once the new one is bound, one on the same port is kept.
+fn start<R: Runtime>(app: &AppHandle<R>, port: u16) -> Result<(), String> {
+    let state = app.state::<ControlState>();
+    let mut slot = state.server.lock().map_err(|e| e.to_string())?;
+    if slot.as_ref().is_some_and(|listener| listener.port == port) {
+        return Ok(());
+    }
+    let handler = app.clone();
+    let listener =
+        local_http::Listener::start(port, move |request| handle_request(&handler, request))?;
+    if let Some(previous) = slot.replace(listener) {
+        previous.stop();
+    }
+    println!(
+        "[CrewHub] Control server on http://127.0.0.1:{}/control",
+        port
+    );
+    Ok(())
+}
+
+fn stop(state: &ControlState) -> Result<(), String> {
+    state.generation.fetch_add(1, Ordering::SeqCst);
+    let listener = state.server.lock().map_err(|e| e.to_string())?.take();
+    if let Some(listener) = listener {
+        listener.stop();
+    }
+    Ok(())
+}
+
+/// Perform a named action. Shared by the HTTP surface and other local integrations.
+pub fn perform<R: Runtime>(
+    app: &AppHandle<R>,
+    action: &str,
+    args: &Value,
+) -> Result<Value, String> {
+    match action {
+        "toggle-chat" => {
+            match app.get_webview_window(crate::CHAT_WINDOW_LABEL) {
+                Some(window) if window.is_visible().unwrap_or(false) => {
+                    window.hide().map_err(|e| e.to_string())?
+                }
+                _ => crate::open_or_focus_chat(app),
+            }
+            Ok(snapshot(app))
+        }
+        "open-chat" => {
+            crate::open_or_focus_chat(app);
+            Ok(snapshot(app))
+        }
+        "open-world" => {
+            crate::open_or_focus_world(app);
+            Ok(snapshot(app))
+        }
+        "open-settings" => {
+            crate::open_or_focus_settings(app);
+            Ok(snapshot(app))
+        }
+        "start-focus" => {
+            crate::open_or_focus_zen(app);
+            Ok(snapshot(app))
+        }
+        "toggle-focus" => {
+            match app.get_webview_window(crate::ZEN_WINDOW_LABEL) {
+                Some(window) if window.is_visible().unwrap_or(false) => {
+                    window.hide().map_err(|e| e.to_string())?
+                }
+                _ => crate::open_or_focus_zen(app),
+            }
+            Ok(snapshot(app))
+        }
+        "open-quick-prompt" => {
+            quick_prompt::open(app);
+            Ok(snapshot(app))
+        }
+        "open-switcher" => {
+            switcher::open(app);
+            Ok(snapshot(app))
+        }
+        "toggle-window" => {
+            let id = args
+                .get("id")
+                .and_then(Value::as_str)
+                .ok_or_else(|| "Missing 'id'".to_string())?;
+            crate::window_shortcuts::toggle(app, id)?;
+            Ok(snapshot(app))
+        }
+        "toggle-hud" => {
+            hud::toggle(app)?;
+            Ok(snapshot(app))
+        }
+        "toggle-dnd" => {
+            dnd::set_manual(app, !dnd::manual());
+            Ok(snapshot(app))
+        }
+        "mark-all-read" => {
+            crate::mark_all_read(app);
+            Ok(snapshot(app))
+        }
+        "quick-action" => {
+            let id = args
+                .get("id")
+                .and_then(Value::as_str)
+                .ok_or_else(|| "Missing 'id'".to_string())?;
+            quick_actions::trigger(app, id)?;
+            Ok(json!({ "started": id }))
+        }
+        "apply-layout" => {
+            let id = args
+                .get("id")
+                .and_then(Value::as_str)
+                .ok_or_else(|| "Missing 'id'".to_string())?;
+            layout_profiles::apply(app, id)?;
+            Ok(snapshot(app))
+        }
+        "send-prompt" => {
+            let text = |key: &str| {
+                args.get(key)
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| format!("Missing '{}'", key))
+            };
+            let reply = backend::send_chat_message(text("sessionKey")?, text("message")?)?;
+            Ok(json!({ "reply": reply }))
+        }
+        "get-state" => Ok(snapshot(app)),
+        "open-pinned" => {
+            let index = args
+                .get("index")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| "Missing 'index'".to_string())?;
+            pins::open(app, index as usize)?;
+            Ok(snapshot(app))
+        }
+        "open-conversation" => {
+            let session_key = args
+                .get("sessionKey")
+                .and_then(Value::as_str)
+                .ok_or_else(|| "Missing 'sessionKey'".to_string())?;
+            let text = |key: &str| args.get(key).and_then(Value::as_str).map(str::to_string);
+            pins::open_conversation(
+                app,
+                ConversationRef {
+                    session_key: session_key.to_string(),
+                    title: text("title").unwrap_or_default(),
+                    backend_id: text("backendId").unwrap_or_else(pins::default_backend),
+                },
+            );
+            Ok(snapshot(app))
+        }
+        "open-agent" => {
+            let agent_id = args
+                .get("agentId")
+                .and_then(Value::as_str)
+                .ok_or_else(|| "Missing 'agentId'".to_string())?;
+            crate::open_or_focus_chat(app);
+            let _ = app.emit_to(
+                crate::CHAT_WINDOW_LABEL,
+                "open-agent",
+                json!({ "agentId": agent_id }),
+            );
+            Ok(snapshot(app))
+        }
+        "open-room" => {
+            let room_id = args
+                .get("roomId")
+                .and_then(Value::as_str)
+                .ok_or_else(|| "Missing 'roomId'".to_string())?;
+            crate::open_or_focus_world(app);
+            let _ = app.emit_to(
+                crate::WORLD_WINDOW_LABEL,
+                "open-room",
+                json!({ "roomId": room_id }),
+            );
+            Ok(snapshot(app))
+        }
+        other => Err(format!("Unknown action: {}", other)),
+    }
+}
+
+fn window_visible<R: Runtime>(app: &AppHandle<R>, label: &str) -> bool {
+    app.get_webview_window(label)
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(false)
+}
+
+/// State a controller needs to render its buttons.
+pub fn snapshot<R: Runtime>(app: &AppHandle<R>) -> Value {
+    json!({
+        "unread": crate::unread_total(app),
+        "badges": crate::badge_totals(app),
+        "agentsWorking": presence::working_count(app),
+        "agentsBlocked": presence::blocked_count(app),
+        "chatVisible": window_visible(app, crate::CHAT_WINDOW_LABEL),
+        "focusActive": window_visible(app, crate::ZEN_WINDOW_LABEL),
+        "doNotDisturb": dnd::quiet(app),
+    })
+}
+
+/// Conversations (pinned first, then recent) and agents matching `q`, as launcher items.
+pub fn search_items<R: Runtime>(app: &AppHandle<R>, q: &str, limit: usize) -> Vec<Value> {
+    let needle = q.trim().to_lowercase();
+    let matches = |text: &str| needle.is_empty() || text.to_lowercase().contains(&needle);
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+
+    let conversations = pins::pinned(app)
+        .into_iter()
+        .map(|p| (p.conversation, true))
+        .chain(
+            recents::recent(app)
+                .into_iter()
+                .map(|r| (r.conversation, false)),
+        );
+    for (conversation, pinned) in conversations {
+        let key = (
+            conversation.backend_id.clone(),
+            conversation.session_key.clone(),
+        );
+        if !matches(&conversation.title) || !seen.insert(key) {
+            continue;
+        }
+        items.push(json!({
+            "kind": "conversation",
+            "id": conversation.session_key,
+            "title": conversation.title,
+            "subtitle": if pinned { "Pinned conversation" } else { "Recent conversation" },
+            "url": format!("crewhub://chat/{}", backend::urlencode(&conversation.session_key)),
+            "action": { "name": "open-conversation", "args": {
+                "sessionKey": conversation.session_key,
+                "backendId": conversation.backend_id,
+                "title": conversation.title,
+            } },
+        }));
+    }
+    for agent in presence::agents(app) {
+        if !matches(&agent.name) && !matches(&agent.agent_id) {
+            continue;
+        }
+        let state = serde_json::to_value(agent.state).unwrap_or_default();
+        items.push(json!({
+            "kind": "agent",
+            "id": agent.agent_id,
+            "title": agent.name,
+            "subtitle": agent.detail.as_ref().map(|d| json!(d)).unwrap_or(state),
+            "url": format!("crewhub://agent/{}", backend::urlencode(&agent.agent_id)),
+            "action": { "name": "open-agent", "args": { "agentId": agent.agent_id } },
+        }));
+    }
+    items.truncate(limit);
+    items
+}
+
+fn handle_request<R: Runtime>(app: &AppHandle<R>, mut request: tiny_http::Request) {
+    let token = match app.state::<ControlState>().config.lock() {
+        Ok(config) => config.token.clone(),
+        Err(_) => return local_http::respond_empty(request, 500),
+    };
+    let (path, query) = request
+        .url()
+        .split_once('?')
+        .map(|(p, q)| (p.to_string(), q.to_string()))
+        .unwrap_or_else(|| (request.url().to_string(), String::new()));
+    let authorized = local_http::is_authorized(&request, &token)
+        || (path == "/control/events"
+            && local_http::query_token(&query)
+                .is_some_and(|given| local_http::token_matches(&given, &token)));
+    if !authorized {
+        return local_http::respond_empty(request, 401);
+    }
+
+    match (request.method().clone(), path.as_str()) {
+        (tiny_http::Method::Get, "/control/actions") => {
+            let actions: Vec<Value> = ACTIONS
+                .iter()
+                .map(|(name, description)| json!({ "name": name, "description": description }))
+                .collect();
+            local_http::respond_json(request, 200, &json!({ "actions": actions }))
+        }
+        (tiny_http::Method::Get, "/control/state") => {
+            local_http::respond_json(request, 200, &snapshot(app))
+        }
+        (tiny_http::Method::Get, "/control/query") => {
+            let params: HashMap<String, String> = url::form_urlencoded::parse(query.as_bytes())
+                .into_owned()
+                .collect();
+            let limit = params
+                .get("limit")
+                .and_then(|l| l.parse().ok())
+                .unwrap_or(20);
+            let q = params.get("q").map(String::as_str).unwrap_or_default();
+            local_http::respond_json(
+                request,
+                200,
+                &json!({ "items": search_items(app, q, limit) }),
+            )
+        }
+        (tiny_http::Method::Get, "/control/events") => {
+            let state = app.state::<ControlState>();
+            if state.streams.fetch_add(1, Ordering::SeqCst) >= MAX_STREAMS {
+                state.streams.fetch_sub(1, Ordering::SeqCst);
+                return local_http::respond_empty(request, 503);
+            }
+            let generation = state.generation.load(Ordering::SeqCst);
+            // Long-lived response: serve it off the accept loop.
+            let app = app.clone();
+            std::thread::spawn(move || stream_state(app, generation, request));
+        }
+        (tiny_http::Method::Post, p) if p.starts_with("/control/actions/") => {
+            let name = p.trim_start_matches("/control/actions/").to_string();
+            // Some actions wait on the backend (send-prompt): serve them off the accept loop.
+            let app = app.clone();
+            std::thread::spawn(move || {
+                let result = local_http::read_json_body(&mut request)
+                    .and_then(|args| perform(&app, &name, &args));
+                match result {
+                    Ok(body) => local_http::respond_json(request, 200, &body),
+                    Err(e) => local_http::respond_json(request, 400, &json!({ "error": e })),
+                }
+            });
+        }
+        _ => local_http::respond_empty(request, 404),
+    }
+}
+
+/// SSE body that emits a `state` event whenever the snapshot changes, and
+/// ends once the server generation moves on.
+struct StateStream<R: Runtime> {
+    app: AppHandle<R>,
+    generation: u64,
+    last: Option<Value>,
+    idle: Duration,
+    buffer: Vec<u8>,
+}
+
+impl<R: Runtime> Read for StateStream<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.buffer.is_empty() {
+            let state = self.app.state::<ControlState>();
+            if state.generation.load(Ordering::SeqCst) != self.generation {
+                return Ok(0);
+            }
+            let current = snapshot(&self.app);
+            if self.last.as_ref() != Some(&current) {
+                self.buffer = format!("event: state\ndata: {}\n\n", current).into_bytes();
+                self.last = Some(current);
+                self.idle = Duration::ZERO;
+            } else if self.idle >= KEEPALIVE_INTERVAL {
+                self.buffer = b": keep-alive\n\n".to_vec();
+                self.idle = Duration::ZERO;
+            } else {
+                let pause = power::interval(&self.app, STATE_POLL_INTERVAL);
+                std::thread::sleep(pause);
+                self.idle += pause;
+            }
+        }
+        let n = buf.len().min(self.buffer.len());
+        buf[..n].copy_from_slice(&self.buffer[..n]);
+        self.buffer.drain(..n);
+        Ok(n)
+    }
+}
+
+fn stream_state<R: Runtime>(app: AppHandle<R>, generation: u64, request: tiny_http::Request) {
+    let header = tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/event-stream"[..])
+        .expect("static header is valid");
+    let body = StateStream {
+        app: app.clone(),
+        generation,
+        last: None,
+        idle: Duration::ZERO,
+        buffer: Vec::new(),
+    };
+    let response = tiny_http::Response::new(200.into(), vec![header], body, None, None);
+    // Ends with an error once the client disconnects.
+    let _ = request.respond(response);
+    app.state::<ControlState>()
+        .streams
+        .fetch_sub(1, Ordering::SeqCst);
+}
+
+fn status(state: &ControlState) -> Result<ControlStatus, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let running = state.server.lock().map_err(|e| e.to_string())?.is_some();
+    Ok(ControlStatus {
+        url: format!("http://127.0.0.1:{}/control", config.port),
+        config,
+        running,
+        socket_path: control_socket::endpoint(),
+    })
+}
+
+/// Tauri command: control server config, URL, and running state.
+/// Called from the frontend via `invoke('get_control_status')`.
+#[tauri::command]
+pub fn get_control_status(state: State<ControlState>) -> Result<ControlStatus, String> {
+    status(&state)
+}
+
+/// Tauri command: enable/disable the control server and/or change its port,
+/// and optionally turn the local socket on or off.
+/// Called from the frontend via `invoke('configure_control', { enabled, port, socket })`.
+#[tauri::command]
+pub fn configure_control(
+    enabled: bool,
+    port: Option<u16>,
+    socket: Option<bool>,
+    app: AppHandle,
+    state: State<ControlState>,
+) -> Result<ControlStatus, String> {
+    let mut config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    config.enabled = enabled;
+    if let Some(port) = port {
+        config.port = port;
+    }
+    if let Some(socket) = socket {
+        config.socket = socket;
+    }
+    // Only saved once the listeners run as configured, so settings never
+    // claim a server that failed to start.
+    if enabled {
+        start(&app, config.port)?;
+    } else {
+        stop(&state)?;
+    }
+    if config.socket {
+        control_socket::start(&app)?;
+    } else {
+        control_socket::stop();
+    }
+    {
+        let mut current = state.config.lock().map_err(|e| e.to_string())?;
+        *current = config;
+        store::save_json(&app, CONFIG_FILE, &*current)?;
+    }
+    status(&state)
+}
+
+/// Tauri command: issue a new token, invalidating the old one immediately.
+/// Called from the frontend via `invoke('regenerate_control_token')`.
+#[tauri::command]
+pub fn regenerate_control_token(
+    app: AppHandle,
+    state: State<ControlState>,
+) -> Result<ControlStatus, String> {
+    {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.token = local_http::new_token();
+        store::save_json(&app, CONFIG_FILE, &*config)?;
+    }
+    // End event streams opened with the old token.
+    state.generation.fetch_add(1, Ordering::SeqCst);
+    status(&state)
+}
@@ -0,0 +1,295 @@
+//! The control API over a local socket instead of HTTP.
+//!
+//! Scripts and local tools that shouldn't need a TCP port (or can't reach
+//! one, e.g. from a sandbox with only a shared directory) talk to the same
+//! actions as [`crate::control`] through a Unix domain socket
+//! (`control.sock` in the app data dir, mode 0600) or, on Windows, the named
+//! pipe `\\.\pipe\crewhub-control-<user>` (remote clients rejected). Only
+//! this machine can connect, and every request must still carry the
+//! control token.
+//!
+//! The protocol is one JSON object per line in each direction:
+//!
+//! ```text
+//! → {"id": 1, "token": "…", "method": "perform", "params": {"action": "open-world"}}
+//! ← {"id": 1, "result": {…}}
+//! ```
+//!
+//! Methods: `actions`, `state`, `query` (`{"q": "…", "limit": 20}`) and
+//! `perform` (`{"action": "…", "args": {…}}`); failures answer with
+//! `{"id": …, "error": "…"}`. A connection may send any number of requests;
+//! each is answered in order.
+
+use crate::{control, local_http};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Runtime};
+
+/// Longest request line accepted.
+const MAX_REQUEST_BYTES: u64 = 1024 * 1024;
+
+/// The running listener: its stop flag and endpoint.
+static LISTENER: Mutex<Option<(Arc<AtomicBool>, String)>> = Mutex::new(None);
+
+#[derive(Deserialize)]
+struct SocketRequest {
+    #[serde(default)]
+    id: Value,
+    #[serde(default)]
+    token: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Start listening (no-op if already running). Returns the endpoint.
+pub fn start<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    let mut listener = LISTENER.lock().map_err(|e| e.to_string())?;
+    if let Some((_, endpoint)) = listener.as_ref() {
+        return Ok(endpoint.clone());
+    }
+    let stop = Arc::new(AtomicBool::new(false));
+    let endpoint = platform::listen(app, stop.clone())?;
+    println!("[CrewHub] Control socket on {}", endpoint);
+    *listener = Some((stop, endpoint.clone()));
+    Ok(endpoint)
+}
+
+pub fn stop() {
+    let Some((stop, endpoint)) = LISTENER.lock().ok().and_then(|mut l| l.take()) else {
+        return;
+    };
+    stop.store(true, Ordering::SeqCst);
+    platform::wake(&endpoint);
+}
+
+/// Endpoint of the running listener.
+pub fn endpoint() -> Option<String> {
+    LISTENER
+        .lock()
+        .ok()?
+        .as_ref()
+        .map(|(_, endpoint)| endpoint.clone())
+}
+
+fn dispatch<R: Runtime>(app: &AppHandle<R>, request: &SocketRequest) -> Result<Value, String> {
+    if control::token(app).is_none_or(|token| !local_http::token_matches(&request.token, &token)) {
+        return Err("Unauthorized".to_string());
+    }
+    let text = |key: &str| request.params.get(key).and_then(Value::as_str);
+    match request.method.as_str() {
+        "actions" => Ok(json!({
+            "actions": control::ACTIONS
+                .iter()
+                .map(|(name, description)| json!({ "name": name, "description": description }))
+                .collect::<Vec<_>>()
+        })),
+        "state" => Ok(control::snapshot(app)),
+        "query" => {
+            let limit = request
+                .params
+                .get("limit")
+                .and_then(Value::as_u64)
+                .unwrap_or(20) as usize;
+            Ok(json!({
+                "items": control::search_items(app, text("q").unwrap_or_default(), limit)
+            }))
+        }
+        "perform" => {
+            let action = text("action").ok_or_else(|| "Missing 'action'".to_string())?;
+            let args = request.params.get("args").cloned().unwrap_or(Value::Null);
+            control::perform(app, action, &args)
+        }
+        other => Err(format!("Unknown method: {}", other)),
+    }
+}
+
+/// Answer requests on one connection until the client hangs up.
+fn serve<R: Runtime>(app: &AppHandle<R>, reader: impl Read, mut writer: impl Write) {
+    let mut reader = BufReader::new(reader);
+    loop {
+        let mut line = String::new();
+        match reader
+            .by_ref()
+            .take(MAX_REQUEST_BYTES + 1)
+            .read_line(&mut line)
+        {
+            Ok(0) | Err(_) => return,
+            Ok(n) if n as u64 > MAX_REQUEST_BYTES => {
+                let _ = writeln!(writer, "{}", json!({ "error": "Request too large" }));
+                return;
+            }
+            Ok(_) => {}
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<SocketRequest>(&line) {
+            Ok(request) => match dispatch(app, &request) {
+                Ok(result) => json!({ "id": request.id, "result": result }),
+                Err(e) => json!({ "id": request.id, "error": e }),
+            },
+            Err(e) => json!({ "id": null, "error": format!("Invalid request: {}", e) }),
+        };
+        if writeln!(writer, "{}", response)
+            .and_then(|_| writer.flush())
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use super::serve;
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tauri::{AppHandle, Runtime};
+
+    const SOCKET_FILE: &str = "control.sock";
+
+    pub fn listen<R: Runtime>(app: &AppHandle<R>, stop: Arc<AtomicBool>) -> Result<String, String> {
+        let path = crate::store::data_path(app, SOCKET_FILE)?;
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                return Err(format!("{} is in use by another process", path.display()));
+            }
+            // Left behind by a crash.
+            std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+        }
+        let listener = UnixListener::bind(&path).map_err(|e| e.to_string())?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| e.to_string())?;
+        let endpoint = path.to_string_lossy().to_string();
+        let app = app.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let app = app.clone();
+                std::thread::spawn(move || {
+                    if let Ok(reader) = stream.try_clone() {
+                        serve(&app, reader, stream);
+                    }
+                });
+            }
+        });
+        Ok(endpoint)
+    }
+
+    /// Unblock the accept loop so it sees the stop flag, and remove the socket.
+    pub fn wake(endpoint: &str) {
+        let _ = UnixStream::connect(endpoint);
+        let _ = std::fs::remove_file(endpoint);
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::serve;
+    use std::fs::File;
+    use std::os::windows::io::FromRawHandle;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tauri::{AppHandle, Runtime};
+    use windows::core::HSTRING;
+    use windows::Win32::Foundation::{CloseHandle, ERROR_PIPE_CONNECTED, HANDLE};
+    use windows::Win32::Storage::FileSystem::{
+        FILE_FLAGS_AND_ATTRIBUTES, FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX,
+    };
+    use windows::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+        PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+
+    const BUFFER_SIZE: u32 = 64 * 1024;
+
+    fn pipe_name() -> String {
+        let user: String = std::env::var("USERNAME")
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            .collect();
+        format!(r"\\.\pipe\crewhub-control-{}", user)
+    }
+
+    fn create(name: &str, flags: FILE_FLAGS_AND_ATTRIBUTES) -> Result<HANDLE, String> {
+        let handle = unsafe {
+            CreateNamedPipeW(
+                &HSTRING::from(name),
+                PIPE_ACCESS_DUPLEX | flags,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                BUFFER_SIZE,
+                BUFFER_SIZE,
+                0,
+                None,
+            )
+        };
+        if handle.is_invalid() {
+            Err(format!("{}: {}", name, windows::core::Error::from_win32()))
+        } else {
+            Ok(handle)
+        }
+    }
+
+    pub fn listen<R: Runtime>(app: &AppHandle<R>, stop: Arc<AtomicBool>) -> Result<String, String> {
+        let name = pipe_name();
+        // Fails if another process already owns the name.
+        let first = create(&name, FILE_FLAG_FIRST_PIPE_INSTANCE)?;
+        let app = app.clone();
+        let endpoint = name.clone();
+        // HANDLE isn't Send; pass the raw value.
+        let first = first.0 as isize;
+        std::thread::spawn(move || {
+            let mut handle = HANDLE(first as *mut _);
+            loop {
+                let connected = match unsafe { ConnectNamedPipe(handle, None) } {
+                    Ok(()) => true,
+                    Err(e) => e.code() == ERROR_PIPE_CONNECTED.to_hresult(),
+                };
+                if stop.load(Ordering::SeqCst) {
+                    let _ = unsafe { CloseHandle(handle) };
+                    break;
+                }
+                if connected {
+                    let pipe = unsafe { File::from_raw_handle(handle.0) };
+                    let app = app.clone();
+                    std::thread::spawn(move || {
+                        if let Ok(reader) = pipe.try_clone() {
+                            serve(&app, reader, pipe);
+                        }
+                    });
+                } else {
+                    let _ = unsafe { CloseHandle(handle) };
+                }
+                handle = match create(&name, FILE_FLAGS_AND_ATTRIBUTES(0)) {
+                    Ok(handle) => handle,
+                    Err(e) => {
+                        eprintln!("[CrewHub] Control pipe stopped: {}", e);
+                        break;
+                    }
+                };
+            }
+        });
+        Ok(endpoint)
+    }
+
+    /// Unblock the accept loop so it sees the stop flag.
+    pub fn wake(endpoint: &str) {
+        let _ = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(endpoint);
+    }
+}
@@ -0,0 +1,262 @@
+//! Token and cost tracking.
+//!
+//! Consumes `usage` events from the backend bridge, stores them in SQLite and
+//! aggregates per day, project, and agent on query. Today's running total is
+//! shown in the tray tooltip, and a notification fires once per day when the
+//! configured daily budget is exceeded.
+
+use crate::notifications::{self, Event};
+use crate::{bridge, db, i18n, store};
+use chrono::{Duration, Local};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime, State};
+
+/// Backend event carrying token usage for one agent turn.
+pub const USAGE_EVENT: &str = "usage";
+
+/// Emitted with the day's running total (replayed, see [`crate::bridge::emit`]).
+pub const COST_UPDATED_EVENT: &str = "cost-updated";
+
+/// Budget config (app data dir).
+const CONFIG_FILE: &str = "costs.json";
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CostConfig {
+    /// Daily budget in USD; `None` disables the warning.
+    pub daily_budget_usd: Option<f64>,
+    /// Local date (YYYY-MM-DD) the budget warning was last shown.
+    pub warned_on: Option<String>,
+}
+
+/// A usage event as sent by the backend.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageEvent {
+    #[serde(default)]
+    agent_id: Option<String>,
+    #[serde(default)]
+    project_id: Option<String>,
+    #[serde(default)]
+    session_key: Option<String>,
+    #[serde(default)]
+    input_tokens: i64,
+    #[serde(default)]
+    output_tokens: i64,
+    #[serde(default)]
+    cost_usd: f64,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupBy {
+    Day,
+    Project,
+    Agent,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostRow {
+    key: String,
+    input_tokens: i64,
+    output_tokens: i64,
+    cost_usd: f64,
+}
+
+/// App state: database connection and budget config.
+pub struct CostState {
+    conn: Mutex<Connection>,
+    config: Mutex<CostConfig>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let conn = db::open(app)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS usage_events (
+            id INTEGER PRIMARY KEY,
+            ts INTEGER NOT NULL,
+            day TEXT NOT NULL,
+            project TEXT NOT NULL,
+            agent TEXT NOT NULL,
+            session_key TEXT,
+            input_tokens INTEGER NOT NULL,
+            output_tokens INTEGER NOT NULL,
+            cost_usd REAL NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS usage_events_day ON usage_events(day);",
+    )
+    .map_err(|e| e.to_string())?;
+    app.manage(CostState {
+        conn: Mutex::new(conn),
+        config: Mutex::new(store::load_json(app, CONFIG_FILE)),
+    });
+    Ok(())
+}
+
+fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn day_total(conn: &Connection, day: &str) -> Result<f64, String> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(cost_usd), 0) FROM usage_events WHERE day = ?1",
+        params![day],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Today's spend in USD, if cost tracking is initialized.
+pub fn today_total<R: Runtime>(app: &AppHandle<R>) -> Option<f64> {
+    let state = app.try_state::<CostState>()?;
+    let conn = state.conn.lock().ok()?;
+    day_total(&conn, &today()).ok()
+}
+
+/// Handle a `usage` event from the backend bridge.
+pub fn record_usage<R: Runtime>(app: &AppHandle<R>, payload: Value) {
+    let event: UsageEvent = match serde_json::from_value(payload) {
+        Ok(event) => event,
+        Err(e) => {
+            eprintln!("[CrewHub] Ignoring malformed usage event: {}", e);
+            return;
+        }
+    };
+    let Some(state) = app.try_state::<CostState>() else {
+        return;
+    };
+
+    let day = today();
+    let total = {
+        let Ok(conn) = state.conn.lock() else { return };
+        let inserted = conn.execute(
+            "INSERT INTO usage_events
+                (ts, day, project, agent, session_key, input_tokens, output_tokens, cost_usd)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                Local::now().timestamp(),
+                day,
+                event.project_id.unwrap_or_default(),
+                event.agent_id.unwrap_or_default(),
+                event.session_key,
+                event.input_tokens,
+                event.output_tokens,
+                event.cost_usd,
+            ],
+        );
+        if let Err(e) = inserted {
+            eprintln!("[CrewHub] Failed to record usage: {}", e);
+            return;
+        }
+        day_total(&conn, &day).unwrap_or_default()
+    };
+
+    crate::refresh_tray_tooltip(app);
+    bridge::emit(
+        app,
+        COST_UPDATED_EVENT,
+        serde_json::json!({ "day": day, "totalUsd": total }),
+    );
+    check_budget(app, &state, &day, total);
+}
+
+/// Warn once per day when the running total crosses the budget.
+fn check_budget<R: Runtime>(app: &AppHandle<R>, state: &CostState, day: &str, total: f64) {
+    let Ok(mut config) = state.config.lock() else {
+        return;
+    };
+    let Some(budget) = config.daily_budget_usd else {
+        return;
+    };
+    if total <= budget || config.warned_on.as_deref() == Some(day) {
+        return;
+    }
+    config.warned_on = Some(day.to_string());
+    if let Err(e) = store::save_json(app, CONFIG_FILE, &*config) {
+        eprintln!("[CrewHub] {}", e);
+    }
+    let body = i18n::tf(
+        "notify.budgetBody",
+        &[
+            ("total", &format!("{:.2}", total)),
+            ("budget", &format!("{:.2}", budget)),
+        ],
+    );
+    notifications::notify(
+        app,
+        Event::BudgetExceeded,
+        i18n::t("notify.budgetTitle"),
+        body,
+    );
+}
+
+/// Tauri command: aggregated usage for the last `days` days (including today).
+/// Called from the frontend via `invoke('get_cost_summary', { days, groupBy })`.
+#[tauri::command]
+pub fn get_cost_summary(
+    days: u32,
+    group_by: GroupBy,
+    state: State<CostState>,
+) -> Result<Vec<CostRow>, String> {
+    let since = (Local::now() - Duration::days(days.saturating_sub(1) as i64))
+        .format("%Y-%m-%d")
+        .to_string();
+    let column = match group_by {
+        GroupBy::Day => "day",
+        GroupBy::Project => "project",
+        GroupBy::Agent => "agent",
+    };
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {column}, SUM(input_tokens), SUM(output_tokens), SUM(cost_usd)
+             FROM usage_events WHERE day >= ?1 GROUP BY {column} ORDER BY {column}"
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![since], |row| {
+            Ok(CostRow {
+                key: row.get(0)?,
+                input_tokens: row.get(1)?,
+                output_tokens: row.get(2)?,
+                cost_usd: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+/// Tauri command: today's total spend in USD.
+/// Called from the frontend via `invoke('get_today_cost')`.
+#[tauri::command]
+pub fn get_today_cost(state: State<CostState>) -> Result<f64, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    day_total(&conn, &today())
+}
+
+/// Tauri command: budget configuration.
+/// Called from the frontend via `invoke('get_cost_config')`.
+#[tauri::command]
+pub fn get_cost_config(state: State<CostState>) -> Result<CostConfig, String> {
+    Ok(state.config.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: set (or with `null`, clear) the daily budget in USD.
+/// Called from the frontend via `invoke('set_daily_budget', { budgetUsd })`.
+#[tauri::command]
+pub fn set_daily_budget(
+    budget_usd: Option<f64>,
+    app: AppHandle,
+    state: State<CostState>,
+) -> Result<CostConfig, String> {
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.daily_budget_usd = budget_usd;
+    // Re-arm the warning for today under the new budget.
+    config.warned_on = None;
+    store::save_json(&app, CONFIG_FILE, &*config)?;
+    Ok(config.clone())
+}
@@ -0,0 +1,211 @@
+//! Five-field cron expressions (`minute hour day-of-month month day-of-week`),
+//! evaluated in local time.
+//!
+//! Supports `*`, lists (`1,15`), ranges (`1-5`), steps (`*/10`, `8-18/2`) and
+//! the usual rule that when both day fields are restricted, either may match.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+
+#[derive(Clone, Debug)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("Expected 5 cron fields, got {}", fields.len()));
+        }
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)? as u32,
+            days_of_month: parse_field(fields[2], 1, 31)? as u32,
+            months: parse_field(fields[3], 1, 12)? as u16,
+            // 7 is an alias for Sunday.
+            days_of_week: {
+                let bits = parse_field(fields[4], 0, 7)?;
+                ((bits | (bits >> 7)) & 0x7f) as u8
+            },
+            dom_restricted: fields[2] != "*",
+            dow_restricted: fields[4] != "*",
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+
+    /// The first matching local time strictly after `after`, or `None` if
+    /// nothing matches within the next few years (e.g. `0 0 31 2 *`).
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut t: NaiveDateTime =
+            after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = t + Duration::days(366 * 4);
+
+        while t < limit {
+            if self.months & (1 << t.month()) == 0 {
+                let (y, m) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(y, m, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << t.minute()) == 0 {
+                t += Duration::minutes(1);
+                continue;
+            }
+            // Local times skipped by a DST jump don't exist; such an
+            // occurrence runs at the first minute after the gap instead.
+            let mut run = t;
+            let local = loop {
+                match Local.from_local_datetime(&run).earliest() {
+                    Some(local) => break local,
+                    None if run - t < Duration::hours(3) => run += Duration::minutes(1),
+                    None => return None,
+                }
+            };
+            if local > after {
+                return Some(local);
+            }
+            t += Duration::minutes(1);
+        }
+        None
+    }
+}
+
+/// Parse one field into a bitmask where bit `n` means value `n` matches.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("Invalid step in '{}'", part))?,
+            ),
+            None => (part, 1),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (parse_value(lo, min, max)?, parse_value(hi, min, max)?)
+        } else {
+            let value = parse_value(range, min, max)?;
+            // `5/15` means "from 5 every 15".
+            (value, if step > 1 { max } else { value })
+        };
+        if lo > hi {
+            return Err(format!("Invalid range '{}'", range));
+        }
+        for value in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(raw: &str, min: u32, max: u32) -> Result<u32, String> {
+    raw.parse::<u32>()
+        .ok()
+        .filter(|v| (min..=max).contains(v))
+        .ok_or_else(|| format!("'{}' is out of range {}-{}", raw, min, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_lists_ranges_and_steps() {
+        assert_eq!(
+            parse_field("*/15", 0, 59),
+            Ok(1 | 1 << 15 | 1 << 30 | 1 << 45)
+        );
+        assert_eq!(parse_field("1,15", 0, 59), Ok(1 << 1 | 1 << 15));
+        assert_eq!(parse_field("1-3", 0, 59), Ok(1 << 1 | 1 << 2 | 1 << 3));
+        assert_eq!(parse_field("8-18/5", 0, 23), Ok(1 << 8 | 1 << 13 | 1 << 18));
+        assert_eq!(parse_field("50/5", 0, 59), Ok(1 << 50 | 1 << 55));
+    }
+
+    #[test]
+    fn rejects_invalid_fields() {
+        assert!(CronExpr::parse("* * * *").is_err());
+        assert!(CronExpr::parse("60 * * * *").is_err());
+        assert!(CronExpr::parse("* 24 * * *").is_err());
+        assert!(CronExpr::parse("* * 0 * *").is_err());
+        assert!(CronExpr::parse("*/0 * * * *").is_err());
+        assert!(CronExpr::parse("5-1 * * * *").is_err());
+        assert!(CronExpr::parse("a * * * *").is_err());
+    }
+
+    #[test]
+    fn seven_is_sunday() {
+        let expr = CronExpr::parse("0 0 * * 7").unwrap();
+        assert_eq!(expr.days_of_week, 1);
+    }
+
+    #[test]
+    fn next_weekday_occurrence() {
+        let expr = CronExpr::parse("30 9 * * 1-5").unwrap();
+        // Friday 2026-01-09 after the run, so the next one is on Monday.
+        assert_eq!(
+            expr.next_after(local(2026, 1, 9, 10, 0)),
+            Some(local(2026, 1, 12, 9, 30))
+        );
+        // Strictly after: an exact match moves on to the next day.
+        assert_eq!(
+            expr.next_after(local(2026, 1, 12, 9, 30)),
+            Some(local(2026, 1, 13, 9, 30))
+        );
+    }
+
+    #[test]
+    fn either_restricted_day_field_matches() {
+        // The 13th or any Friday; Friday 2026-01-09 comes first.
+        let expr = CronExpr::parse("0 0 13 * 5").unwrap();
+        assert_eq!(
+            expr.next_after(local(2026, 1, 5, 0, 0)),
+            Some(local(2026, 1, 9, 0, 0))
+        );
+        assert_eq!(
+            expr.next_after(local(2026, 1, 9, 0, 0)),
+            Some(local(2026, 1, 13, 0, 0))
+        );
+    }
+
+    #[test]
+    fn impossible_date_never_fires() {
+        let expr = CronExpr::parse("0 0 31 2 *").unwrap();
+        assert_eq!(expr.next_after(local(2026, 1, 1, 0, 0)), None);
+    }
+}
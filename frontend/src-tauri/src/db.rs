@@ -0,0 +1,21 @@
+//! Shared SQLite database for Rust-side structured data.
+//!
+//! Subsystems that need queries rather than a single JSON document (usage
+//! aggregation, libraries, reminders) keep their tables in one `desktop.db`
+//! in the app data dir. Each subsystem opens its own connection and creates
+//! its own tables on init.
+
+use rusqlite::Connection;
+use tauri::{AppHandle, Runtime};
+
+const DB_FILE: &str = "desktop.db";
+
+/// Open a connection to the shared database (WAL mode, so subsystems don't block each other).
+pub fn open<R: Runtime>(app: &AppHandle<R>) -> Result<Connection, String> {
+    let path = crate::store::data_path(app, DB_FILE)?;
+    let conn = Connection::open(path).map_err(|e| format!("Failed to open database: {}", e))?;
+    conn.pragma_update(None, "journal_mode", "WAL")
+        .and_then(|_| conn.busy_timeout(std::time::Duration::from_secs(5)))
+        .map_err(|e| e.to_string())?;
+    Ok(conn)
+}
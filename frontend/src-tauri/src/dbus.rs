@@ -0,0 +1,162 @@
+//! `org.crewhub.Desktop` on the session bus (Linux).
+//!
+//! Lets GNOME Shell extensions, scripts and tiling-WM keybindings drive
+//! CrewHub without the tray, e.g.
+//! `busctl --user call org.crewhub.Desktop /org/crewhub/Desktop org.crewhub.Desktop OpenWindow s chat`.
+//!
+//! Methods are thin wrappers over [`control::perform`], the same actions the
+//! local control API and launch arguments use; `PerformAction` reaches all of them
+//! (`ListActions` names them). The `UnreadCount` and `DoNotDisturb`
+//! properties emit `PropertiesChanged`, so a panel indicator doesn't need to
+//! poll. Unlike the control API this needs no opt-in or token: the session
+//! bus is only reachable by the same user.
+
+use crate::{control, dnd};
+use serde_json::{json, Map, Value};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Runtime};
+use zbus::{connection, fdo, interface, Connection};
+
+const BUS_NAME: &str = "org.crewhub.Desktop";
+const OBJECT_PATH: &str = "/org/crewhub/Desktop";
+
+static CONNECTION: OnceLock<Connection> = OnceLock::new();
+
+/// Last (unread, DND) values announced with `PropertiesChanged`.
+static PUBLISHED: Mutex<Option<(u32, bool)>> = Mutex::new(None);
+
+struct Desktop {
+    app: AppHandle,
+}
+
+impl Desktop {
+    /// Run a control action off the async runtime (some wait on a backend).
+    async fn perform(&self, action: &str, args: Value) -> fdo::Result<Value> {
+        let app = self.app.clone();
+        let action = action.to_string();
+        tauri::async_runtime::spawn_blocking(move || control::perform(&app, &action, &args))
+            .await
+            .map_err(|e| fdo::Error::Failed(e.to_string()))?
+            .map_err(fdo::Error::Failed)
+    }
+}
+
+#[interface(name = "org.crewhub.Desktop")]
+impl Desktop {
+    /// Open and focus a window: `chat`, `world`, `settings`, `focus` or `quick-prompt`.
+    async fn open_window(&self, name: &str) -> fdo::Result<()> {
+        let action = match name {
+            "chat" => "open-chat",
+            "world" => "open-world",
+            "settings" => "open-settings",
+            "focus" | "zen" => "start-focus",
+            "quick-prompt" => "open-quick-prompt",
+            other => {
+                return Err(fdo::Error::InvalidArgs(format!(
+                    "Unknown window: {}",
+                    other
+                )));
+            }
+        };
+        self.perform(action, json!({})).await.map(|_| ())
+    }
+
+    /// Send a prompt to an agent session and return its reply.
+    async fn send_prompt(&self, session_key: &str, message: &str) -> fdo::Result<String> {
+        let args = json!({ "sessionKey": session_key, "message": message });
+        let result = self.perform("send-prompt", args).await?;
+        Ok(result["reply"].as_str().unwrap_or_default().to_string())
+    }
+
+    fn get_unread_count(&self) -> u32 {
+        crate::unread_total(&self.app)
+    }
+
+    /// Turn CrewHub's Do Not Disturb on or off; returns the new state.
+    fn toggle_dnd(&self) -> bool {
+        dnd::set_manual(&self.app, !dnd::manual());
+        dnd::quiet(&self.app)
+    }
+
+    /// Perform any control action; `args` is a JSON object (or empty). Returns JSON.
+    async fn perform_action(&self, action: &str, args: &str) -> fdo::Result<String> {
+        let args = if args.trim().is_empty() {
+            Value::Object(Map::new())
+        } else {
+            serde_json::from_str(args).map_err(|e| fdo::Error::InvalidArgs(e.to_string()))?
+        };
+        Ok(self.perform(action, args).await?.to_string())
+    }
+
+    /// (name, description) of every action `PerformAction` accepts.
+    fn list_actions(&self) -> Vec<(String, String)> {
+        control::ACTIONS
+            .iter()
+            .map(|(name, description)| (name.to_string(), description.to_string()))
+            .collect()
+    }
+
+    #[zbus(property)]
+    fn unread_count(&self) -> u32 {
+        crate::unread_total(&self.app)
+    }
+
+    #[zbus(property)]
+    fn do_not_disturb(&self) -> bool {
+        dnd::quiet(&self.app)
+    }
+}
+
+/// Claim the bus name and serve the interface.
+pub fn init(app: &AppHandle) {
+    let desktop = Desktop { app: app.clone() };
+    tauri::async_runtime::spawn(async move {
+        let connection = async {
+            connection::Builder::session()?
+                .name(BUS_NAME)?
+                .serve_at(OBJECT_PATH, desktop)?
+                .build()
+                .await
+        };
+        match connection.await {
+            Ok(connection) => {
+                let _ = CONNECTION.set(connection);
+            }
+            Err(e) => eprintln!("[CrewHub] D-Bus service unavailable: {}", e),
+        }
+    });
+}
+
+/// Announce changed properties (called after tray icon updates, which follow
+/// every unread and DND change).
+pub fn state_changed<R: Runtime>(app: &AppHandle<R>) {
+    let Some(connection) = CONNECTION.get() else {
+        return;
+    };
+    let state = (crate::unread_total(app), dnd::quiet(app));
+    let previous = match PUBLISHED.lock() {
+        Ok(mut published) => published.replace(state),
+        Err(_) => return,
+    };
+    if previous == Some(state) {
+        return;
+    }
+    let connection = connection.clone();
+    tauri::async_runtime::spawn(async move {
+        let Ok(iface) = connection
+            .object_server()
+            .interface::<_, Desktop>(OBJECT_PATH)
+            .await
+        else {
+            return;
+        };
+        let desktop = iface.get().await;
+        let emitter = iface.signal_emitter();
+        if previous.map(|p| p.0) != Some(state.0) {
+            let _ = desktop.unread_count_changed(emitter).await;
+        }
+        if previous.map(|p| p.1) != Some(state.1) {
+            let _ = desktop.do_not_disturb_changed(emitter).await;
+        }
+    });
+}
@@ -0,0 +1,234 @@
+//! Diagnostics for issues in the field: devtools and the debug window.
+//!
+//! `toggle_devtools(label)` opens or closes a window's web inspector. Debug
+//! builds always allow it; release builds only once the user turns on
+//! `devtools` in `debug.json` (settings), since the inspector can read and
+//! change everything the frontend can.
+//!
+//! The debug window shows live Rust state — badge counts, backends and
+//! connectivity, the window registry, recent backend events and panics. It
+//! is a self-contained page (no frontend bundle, no IPC) that Rust pushes a
+//! fresh snapshot into every second, so it keeps working when the frontend
+//! or the backend is what's broken. Panics are also written to
+//! `last-panic.txt` (app data dir) and shown on the next start.
+
+use crate::{bridge, connectivity, federation, presence, store};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindowBuilder};
+
+/// Label for the debug window.
+pub const DEBUG_WINDOW_LABEL: &str = "debug";
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "debug.json";
+
+/// Last panic message (app data dir), kept across restarts.
+const PANIC_FILE: &str = "last-panic.txt";
+
+/// Events kept for the debug window.
+const MAX_EVENTS: usize = 200;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+const PAGE: &str = include_str!("../assets/debug.html");
+
+static EVENTS: Mutex<VecDeque<DebugEvent>> = Mutex::new(VecDeque::new());
+
+static STARTED: OnceLock<Instant> = OnceLock::new();
+
+static PANIC_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DebugConfig {
+    /// Allow `toggle_devtools` in release builds.
+    pub devtools: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DebugEvent {
+    /// Unix milliseconds.
+    at: i64,
+    source: &'static str,
+    name: String,
+    detail: Option<String>,
+}
+
+/// Record a panic hook (chained before the default one) and the previous run's panic.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let _ = STARTED.set(Instant::now());
+    if let Ok(path) = store::data_path(app, PANIC_FILE) {
+        if let Ok(previous) = std::fs::read_to_string(&path) {
+            record("panic", "Previous run panicked", Some(previous));
+        }
+        let _ = PANIC_PATH.set(path);
+    }
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info.to_string();
+        if let Some(path) = PANIC_PATH.get() {
+            let _ = std::fs::write(path, &message);
+        }
+        record("panic", "Panic", Some(message));
+        default_hook(info);
+    }));
+}
+
+/// Remember an event for the debug window (oldest dropped past [`MAX_EVENTS`]).
+pub fn record(source: &'static str, name: &str, detail: Option<String>) {
+    let Ok(mut events) = EVENTS.lock() else {
+        return;
+    };
+    if events.len() == MAX_EVENTS {
+        events.pop_front();
+    }
+    events.push_back(DebugEvent {
+        at: chrono::Local::now().timestamp_millis(),
+        source,
+        name: name.to_string(),
+        detail,
+    });
+}
+
+fn config<R: Runtime>(app: &AppHandle<R>) -> DebugConfig {
+    store::load_json(app, CONFIG_FILE)
+}
+
+fn devtools_allowed<R: Runtime>(app: &AppHandle<R>) -> bool {
+    cfg!(debug_assertions) || config(app).devtools
+}
+
+/// Everything the debug window shows.
+fn snapshot<R: Runtime>(app: &AppHandle<R>) -> Value {
+    let badge = app
+        .try_state::<crate::BadgeCount>()
+        .and_then(|badge| badge.0.lock().ok().map(|counts| counts.clone()))
+        .unwrap_or_default();
+    let connected = bridge::connected_ids(app);
+    let backends: Vec<Value> = federation::enabled(app)
+        .into_iter()
+        .map(|backend| {
+            json!({
+                "id": backend.id,
+                "name": backend.name,
+                "url": backend.url,
+                "connected": connected.contains(&backend.id),
+            })
+        })
+        .collect();
+    let mut windows: Vec<Value> = app
+        .webview_windows()
+        .into_iter()
+        .map(|(label, window)| {
+            json!({
+                "label": label,
+                "title": window.title().ok(),
+                "url": window.url().ok().map(|url| url.to_string()),
+                "visible": window.is_visible().unwrap_or(false),
+                "focused": window.is_focused().unwrap_or(false),
+                "minimized": window.is_minimized().unwrap_or(false),
+            })
+        })
+        .collect();
+    windows.sort_by(|a, b| a["label"].as_str().cmp(&b["label"].as_str()));
+    let events: Vec<DebugEvent> = EVENTS
+        .lock()
+        .map(|events| events.iter().rev().cloned().collect())
+        .unwrap_or_default();
+    json!({
+        "version": app.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "uptimeSecs": STARTED.get().map_or(0, |started| started.elapsed().as_secs()),
+        "backendUrl": crate::backend_url(),
+        "connectivity": connectivity::current(app),
+        "badge": badge,
+        "backends": backends,
+        "agents": presence::agents(app),
+        "windows": windows,
+        "events": events,
+    })
+}
+
+/// Push snapshots into the debug window until it is closed.
+fn refresh<R: Runtime>(app: AppHandle<R>) {
+    while let Some(window) = app.get_webview_window(DEBUG_WINDOW_LABEL) {
+        let script = format!("window.render && window.render({});", snapshot(&app));
+        let _ = window.eval(&script);
+        std::thread::sleep(REFRESH_INTERVAL);
+    }
+}
+
+/// Open or focus the debug window (720×640).
+pub fn open<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(DEBUG_WINDOW_LABEL) {
+        crate::show_and_focus(&window);
+        return Ok(());
+    }
+    let page = percent_encoding::utf8_percent_encode(PAGE, percent_encoding::NON_ALPHANUMERIC);
+    let url = tauri::Url::parse(&format!("data:text/html;charset=utf-8,{}", page))
+        .map_err(|e| e.to_string())?;
+    let window = WebviewWindowBuilder::new(app, DEBUG_WINDOW_LABEL, WebviewUrl::External(url))
+        .title("CrewHub Debug")
+        .inner_size(720.0, 640.0)
+        .resizable(true)
+        .on_new_window(crate::external_links::new_window_handler(app))
+        .build()
+        .map_err(|e| format!("Failed to create debug window: {}", e))?;
+    crate::show_and_focus(&window);
+    let app = app.clone();
+    std::thread::spawn(move || refresh(app));
+    Ok(())
+}
+
+/// Tauri command: open or close a window's web inspector; returns whether it is open.
+/// Called from the frontend via `invoke('toggle_devtools', { label })`.
+#[tauri::command]
+pub fn toggle_devtools(label: String, app: AppHandle) -> Result<bool, String> {
+    if !devtools_allowed(&app) {
+        return Err("Developer tools are turned off in settings".to_string());
+    }
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("No window '{}'", label))?;
+    if window.is_devtools_open() {
+        window.close_devtools();
+        Ok(false)
+    } else {
+        window.open_devtools();
+        Ok(true)
+    }
+}
+
+/// Tauri command: open the debug window.
+/// Called from the settings window via `invoke('open_debug_window')`.
+#[tauri::command]
+pub fn open_debug_window(app: AppHandle) -> Result<(), String> {
+    open(&app)
+}
+
+/// Tauri command: the debug window's snapshot (for bug reports).
+/// Called from the settings window via `invoke('get_debug_state')`.
+#[tauri::command]
+pub fn get_debug_state(app: AppHandle) -> Value {
+    snapshot(&app)
+}
+
+/// Tauri command: debug settings.
+/// Called from the settings window via `invoke('get_debug_config')`.
+#[tauri::command]
+pub fn get_debug_config(app: AppHandle) -> DebugConfig {
+    config(&app)
+}
+
+/// Tauri command: allow or forbid devtools in release builds.
+/// Called from the settings window via `invoke('set_debug_config', { config })`.
+#[tauri::command]
+pub fn set_debug_config(config: DebugConfig, app: AppHandle) -> Result<(), String> {
+    store::save_json(&app, CONFIG_FILE, &config)
+}
@@ -0,0 +1,98 @@
+//! Demo mode with synthetic data (`--demo`, or Help → Demo Mode in the tray).
+//!
+//! For showing CrewHub at meetups without exposing real projects. The mock
+//! backend of [`crate::test_harness`] serves a canned crew from the bundled
+//! `fixtures/demo.json` (agents, conversations, rooms) in place of the real
+//! backend, and replays its `demo` script of agent events in a loop so the
+//! world and the tray stay lively. Settings, history and the database live
+//! in a separate `demo` folder of the app data dir (see
+//! [`crate::store::data_path`]), so pins, recents and backends of the real
+//! setup never show up and nothing from the demo leaks back. Windows see
+//! `window.__CREWHUB_DEMO__`.
+//!
+//! The backend URL is fixed for a whole run, so switching from the tray
+//! relaunches the app with `CREWHUB_DEMO` set (which also overrides the flag
+//! for that launch). Demo mode ends when CrewHub quits.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+
+/// Command-line flag.
+const FLAG: &str = "--demo";
+
+/// Environment switch (`1` / `true` on, anything else off).
+const ENV: &str = "CREWHUB_DEMO";
+
+const FIXTURE: &str = include_str!("../fixtures/demo.json");
+
+/// Fixture script replayed while demo mode runs.
+const SCRIPT: &str = "demo";
+
+/// Pause between replays of [`SCRIPT`].
+const REPLAY_PAUSE: Duration = Duration::from_secs(30);
+
+/// Tray menu item id.
+pub const MENU_ID: &str = "demo-mode";
+
+/// Subfolder of the app data dir used while demo mode is on.
+pub const DATA_DIR: &str = "demo";
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Demo backend URL once started.
+static URL: OnceLock<String> = OnceLock::new();
+
+/// Whether this run is in demo mode.
+pub fn enabled() -> bool {
+    *ENABLED.get_or_init(|| match std::env::var(ENV) {
+        Ok(value) => value == "1" || value == "true",
+        Err(_) => std::env::args().any(|arg| arg == FLAG),
+    })
+}
+
+/// Start the demo backend if demo mode is on. Call before anything reads the
+/// backend URL; a running test harness takes precedence.
+pub fn start_if_enabled() {
+    if !enabled() || crate::test_harness::url().is_some() {
+        return;
+    }
+    let url = match crate::test_harness::serve(FIXTURE, 0) {
+        Ok(url) => url,
+        Err(e) => {
+            eprintln!("[CrewHub] Demo backend not started: {}", e);
+            return;
+        }
+    };
+    println!("[CrewHub] Demo mode: synthetic backend at {}", url);
+    let _ = URL.set(url);
+    std::thread::spawn(|| loop {
+        std::thread::sleep(REPLAY_PAUSE);
+        if let Err(e) = crate::test_harness::run_script(SCRIPT) {
+            eprintln!("[CrewHub] Demo script failed: {}", e);
+            return;
+        }
+    });
+}
+
+/// Demo backend URL, when running in demo mode.
+pub fn url() -> Option<&'static str> {
+    URL.get().map(String::as_str)
+}
+
+/// JS fragment exposing the mode as `window.__CREWHUB_DEMO__`.
+pub fn init_script() -> String {
+    format!("window.__CREWHUB_DEMO__ = {};", enabled())
+}
+
+/// Relaunch CrewHub with demo mode switched.
+pub fn toggle<R: Runtime>(app: &AppHandle<R>) {
+    let next = !enabled();
+    println!(
+        "[CrewHub] Relaunching with demo mode {}",
+        if next { "on" } else { "off" }
+    );
+    // Inherited by the relaunched process.
+    std::env::set_var(ENV, if next { "1" } else { "0" });
+    app.request_restart();
+}
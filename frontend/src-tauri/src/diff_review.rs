@@ -0,0 +1,323 @@
+//! "Review changes" window for agent-proposed edits.
+//!
+//! An agent's proposal is the content of its task worktree (see
+//! [`crate::worktrees`]). Diffs are computed in Rust against the user's own
+//! working tree, so what the window shows is exactly what accepting a file
+//! would change on disk. Accepting copies the agent's version into the user's
+//! checkout; rejecting discards the change inside the agent worktree.
+
+use crate::worktrees::{self, AgentWorktree};
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindowBuilder};
+
+/// Label for the review window.
+pub const REVIEW_WINDOW_LABEL: &str = "review";
+
+/// Lines of unchanged context around each hunk.
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FileChange {
+    Added,
+    Modified,
+    Deleted,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    /// `equal`, `insert` or `delete`.
+    tag: &'static str,
+    old_line: Option<usize>,
+    new_line: Option<usize>,
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    header: String,
+    lines: Vec<DiffLine>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiff {
+    path: String,
+    change: FileChange,
+    binary: bool,
+    hunks: Vec<DiffHunk>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewDiff {
+    worktree: AgentWorktree,
+    files: Vec<FileDiff>,
+}
+
+fn review_init_script(worktree_id: &str) -> String {
+    let id_json = serde_json::to_string(worktree_id).unwrap_or_else(|_| "\"\"".to_string());
+    format!(
+        "window.__TAURI_VIEW__ = 'review'; window.__CREWHUB_REVIEW_WORKTREE__ = {}; {}",
+        id_json,
+        crate::base_init(REVIEW_WINDOW_LABEL)
+    )
+}
+
+/// Open or focus the review window (960×720) for the given worktree.
+/// If it is already open, it is retargeted via a `review-target` event.
+fn open_or_focus_review<R: Runtime>(app: &AppHandle<R>, worktree_id: &str) {
+    if let Some(window) = app.get_webview_window(REVIEW_WINDOW_LABEL) {
+        let _ = window.emit("review-target", worktree_id);
+        crate::show_and_focus(&window);
+        return;
+    }
+
+    let result =
+        WebviewWindowBuilder::new(app, REVIEW_WINDOW_LABEL, crate::view_url("view=review"))
+            .title("Review Changes")
+            .inner_size(960.0, 720.0)
+            .min_inner_size(640.0, 420.0)
+            .resizable(true)
+            .fullscreen(false)
+            .decorations(true)
+            .always_on_top(false)
+            .user_agent(&crate::request_headers::user_agent())
+            .on_new_window(crate::external_links::new_window_handler(app))
+            .initialization_script(review_init_script(worktree_id))
+            .build();
+
+    match result {
+        Ok(window) => crate::show_and_focus(&window),
+        Err(e) => eprintln!("[CrewHub] Failed to create review window: {}", e),
+    }
+}
+
+/// Reject absolute paths and `..` so file commands stay inside the repo.
+fn relative_path(path: &str) -> Result<PathBuf, String> {
+    let rel = PathBuf::from(path);
+    if rel
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        Ok(rel)
+    } else {
+        Err(format!("Invalid path: {}", path))
+    }
+}
+
+/// `rel` inside `repo` once its parent directory exists, refusing a path
+/// that leaves the repository through a symlinked directory or is a symlink
+/// itself.
+fn confined_target(repo: &Path, rel: &Path) -> Result<PathBuf, String> {
+    let target = repo.join(rel);
+    let repo = repo.canonicalize().map_err(|e| e.to_string())?;
+    let parent = target.parent().ok_or("Invalid target path")?;
+    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    let parent = parent.canonicalize().map_err(|e| e.to_string())?;
+    if !parent.starts_with(&repo) {
+        return Err(format!(
+            "Refusing to write outside the repository: {}",
+            target.display()
+        ));
+    }
+    let target = parent.join(target.file_name().ok_or("Invalid target path")?);
+    if std::fs::symlink_metadata(&target).is_ok_and(|meta| meta.file_type().is_symlink()) {
+        return Err(format!(
+            "Refusing to overwrite symbolic link {}",
+            target.display()
+        ));
+    }
+    Ok(target)
+}
+
+/// Files the agent touched relative to its base (committed, staged, unstaged, untracked).
+fn changed_paths(worktree: &AgentWorktree) -> Result<Vec<String>, String> {
+    let dir = Path::new(&worktree.path);
+    let tracked = worktrees::git(dir, &["diff", "--name-only", &worktree.base_ref])?;
+    let untracked = worktrees::git(dir, &["ls-files", "--others", "--exclude-standard"])?;
+    let mut paths: Vec<String> = tracked
+        .lines()
+        .chain(untracked.lines())
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect();
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+fn file_diff(path: &str, current: Option<Vec<u8>>, proposed: Option<Vec<u8>>) -> Option<FileDiff> {
+    let change = match (&current, &proposed) {
+        (None, Some(_)) => FileChange::Added,
+        (Some(_), None) => FileChange::Deleted,
+        (Some(a), Some(b)) if a != b => FileChange::Modified,
+        _ => return None,
+    };
+    let (old, new) = match (
+        String::from_utf8(current.unwrap_or_default()),
+        String::from_utf8(proposed.unwrap_or_default()),
+    ) {
+        (Ok(old), Ok(new)) => (old, new),
+        _ => {
+            return Some(FileDiff {
+                path: path.to_string(),
+                change,
+                binary: true,
+                hunks: Vec::new(),
+            })
+        }
+    };
+
+    let diff = TextDiff::from_lines(&old, &new);
+    let hunks = diff
+        .grouped_ops(CONTEXT_LINES)
+        .iter()
+        .map(|group| {
+            let (first, last) = (&group[0], &group[group.len() - 1]);
+            let old_range = first.old_range().start..last.old_range().end;
+            let new_range = first.new_range().start..last.new_range().end;
+            let lines = group
+                .iter()
+                .flat_map(|op| diff.iter_changes(op))
+                .map(|change| DiffLine {
+                    tag: match change.tag() {
+                        ChangeTag::Equal => "equal",
+                        ChangeTag::Insert => "insert",
+                        ChangeTag::Delete => "delete",
+                    },
+                    old_line: change.old_index().map(|i| i + 1),
+                    new_line: change.new_index().map(|i| i + 1),
+                    text: change.to_string_lossy().trim_end_matches('\n').to_string(),
+                })
+                .collect();
+            DiffHunk {
+                header: format!(
+                    "@@ -{},{} +{},{} @@",
+                    old_range.start + 1,
+                    old_range.len(),
+                    new_range.start + 1,
+                    new_range.len()
+                ),
+                lines,
+            }
+        })
+        .collect();
+
+    Some(FileDiff {
+        path: path.to_string(),
+        change,
+        binary: false,
+        hunks,
+    })
+}
+
+fn compute_review(worktree: AgentWorktree) -> Result<ReviewDiff, String> {
+    let files = changed_paths(&worktree)?
+        .into_iter()
+        .filter_map(|path| {
+            let rel = relative_path(&path).ok()?;
+            let current = std::fs::read(Path::new(&worktree.repo_path).join(&rel)).ok();
+            let proposed = std::fs::read(Path::new(&worktree.path).join(&rel)).ok();
+            file_diff(&path, current, proposed)
+        })
+        .collect();
+    Ok(ReviewDiff { worktree, files })
+}
+
+fn lookup(app: &AppHandle, worktree_id: &str) -> Result<AgentWorktree, String> {
+    worktrees::find(app, worktree_id).ok_or_else(|| format!("Worktree not found: {}", worktree_id))
+}
+
+/// Open the "Review changes" window for an agent worktree.
+pub fn open<R: Runtime>(app: &AppHandle<R>, worktree_id: &str) -> Result<(), String> {
+    worktrees::find(app, worktree_id)
+        .ok_or_else(|| format!("Worktree not found: {}", worktree_id))?;
+    open_or_focus_review(app, worktree_id);
+    Ok(())
+}
+
+/// Tauri command: open the "Review changes" window for an agent worktree.
+/// Called from the frontend via `invoke('open_review_window', { worktreeId })`.
+#[tauri::command]
+pub fn open_review_window(worktree_id: String, app: AppHandle) -> Result<(), String> {
+    open(&app, &worktree_id)
+}
+
+/// Tauri command: per-file diffs between the user's working tree and the agent's proposal.
+/// Called from the frontend via `invoke('get_review_diff', { worktreeId })`.
+#[tauri::command]
+pub async fn get_review_diff(worktree_id: String, app: AppHandle) -> Result<ReviewDiff, String> {
+    let worktree = lookup(&app, &worktree_id)?;
+    tauri::async_runtime::spawn_blocking(move || compute_review(worktree))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: apply the agent's version of one file to the user's working tree.
+/// Called from the frontend via `invoke('accept_review_file', { worktreeId, path })`.
+#[tauri::command]
+pub fn accept_review_file(worktree_id: String, path: String, app: AppHandle) -> Result<(), String> {
+    let worktree = lookup(&app, &worktree_id)?;
+    let rel = relative_path(&path)?;
+    let source = Path::new(&worktree.path).join(&rel);
+    // `symlink_metadata`, not `exists`: a link the agent committed must not
+    // be followed (it could point at any file of the user's).
+    let proposed = std::fs::symlink_metadata(&source).ok();
+    if let Some(meta) = &proposed {
+        if meta.file_type().is_symlink() {
+            return Err(format!("Refusing to apply {}: it is a symbolic link", path));
+        }
+        if !meta.is_file() {
+            return Err(format!("Refusing to apply {}: not a regular file", path));
+        }
+    }
+    let target = confined_target(Path::new(&worktree.repo_path), &rel)?;
+
+    if proposed.is_some() {
+        std::fs::copy(&source, &target).map_err(|e| format!("Failed to apply {}: {}", path, e))?;
+    } else if target.exists() {
+        std::fs::remove_file(&target).map_err(|e| format!("Failed to delete {}: {}", path, e))?;
+    }
+
+    let _ = app.emit(
+        "review-updated",
+        serde_json::json!({ "worktreeId": worktree_id, "path": path, "accepted": true }),
+    );
+    Ok(())
+}
+
+/// Tauri command: discard the agent's change to one file inside its worktree.
+/// Called from the frontend via `invoke('reject_review_file', { worktreeId, path })`.
+#[tauri::command]
+pub fn reject_review_file(worktree_id: String, path: String, app: AppHandle) -> Result<(), String> {
+    let worktree = lookup(&app, &worktree_id)?;
+    let rel = relative_path(&path)?;
+    let dir = Path::new(&worktree.path);
+    let in_base = worktrees::git(
+        dir,
+        &["cat-file", "-e", &format!("{}:{}", worktree.base_ref, path)],
+    )
+    .is_ok();
+
+    if in_base {
+        worktrees::git(dir, &["checkout", &worktree.base_ref, "--", &path])?;
+    } else {
+        // Added by the agent: unstage (if staged) and delete.
+        let _ = worktrees::git(dir, &["rm", "--cached", "--quiet", "--", &path]);
+        let file = dir.join(&rel);
+        if file.exists() {
+            std::fs::remove_file(&file).map_err(|e| format!("Failed to delete {}: {}", path, e))?;
+        }
+    }
+
+    let _ = app.emit(
+        "review-updated",
+        serde_json::json!({ "worktreeId": worktree_id, "path": path, "accepted": false }),
+    );
+    Ok(())
+}
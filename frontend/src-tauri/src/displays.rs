@@ -0,0 +1,345 @@
+//! Display hot-plug rules.
+//!
+//! The connected monitors are polled every few seconds (slower on battery).
+//! When a monitor appears, the first rule matching its name opens a window
+//! on it, full screen if asked ("when the TV appears, put the world window
+//! there"). When a monitor disappears, CrewHub windows left off-screen, or
+//! full screen on it, are pulled back to the primary monitor (`pullBack`).
+//! The frontend gets `displays-changed` with the new monitor list.
+//!
+//! Independently of the rules, after any change in the monitor setup
+//! (including a resolution or scale change) every CrewHub window whose title
+//! bar is no longer on a monitor is moved onto the nearest one and shrunk to
+//! fit; the frontend gets `windows-relocated` with their labels to re-layout.
+
+use crate::{layout_profiles, power, store};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{
+    AppHandle, Emitter, Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow,
+};
+
+/// Persisted rules (app data dir).
+const CONFIG_FILE: &str = "displays.json";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How much of a window's top edge must be on a monitor to count as
+/// reachable (height and width of the grab strip, logical pixels).
+pub const REACHABLE: f64 = 48.0;
+
+/// Main windows that are pulled back from a disconnected monitor.
+const PULLED_WINDOWS: [&str; 4] = [
+    crate::CHAT_WINDOW_LABEL,
+    crate::WORLD_WINDOW_LABEL,
+    crate::ZEN_WINDOW_LABEL,
+    crate::SETTINGS_WINDOW_LABEL,
+];
+
+static CONFIG: Mutex<Option<DisplayConfig>> = Mutex::new(None);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayRule {
+    /// Monitor name as listed by `list_displays`, or `*` for any monitor
+    /// other than the primary one.
+    pub monitor: String,
+    /// Window to open there.
+    #[serde(default = "default_window")]
+    pub window: String,
+    #[serde(default)]
+    pub fullscreen: bool,
+}
+
+fn default_window() -> String {
+    crate::WORLD_WINDOW_LABEL.to_string()
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DisplayConfig {
+    pub rules: Vec<DisplayRule>,
+    /// Bring windows back to the primary monitor when theirs disconnects.
+    pub pull_back: bool,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            pull_back: true,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Display {
+    /// Identifies the monitor in rules (falls back to its position).
+    name: String,
+    primary: bool,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale_factor: f64,
+}
+
+fn config() -> DisplayConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+fn name(monitor: &Monitor) -> String {
+    monitor.name().cloned().unwrap_or_else(|| {
+        let position = monitor.position();
+        format!("{},{}", position.x, position.y)
+    })
+}
+
+fn displays<R: Runtime>(app: &AppHandle<R>) -> Vec<Display> {
+    let primary = app.primary_monitor().ok().flatten().map(|m| name(&m));
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| Display {
+            name: name(monitor),
+            primary: primary.as_deref() == Some(name(monitor).as_str()),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale_factor: monitor.scale_factor(),
+        })
+        .collect()
+}
+
+/// Load the rules and start watching for monitors coming and going.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let loaded: DisplayConfig = store::load_json(app, CONFIG_FILE);
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = Some(loaded);
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut known = displays(&app);
+        loop {
+            std::thread::sleep(power::interval(&app, POLL_INTERVAL));
+            if power::is_suspended() {
+                continue;
+            }
+            let current = displays(&app);
+            // An empty list is a transient answer while the display server reconfigures.
+            if current.is_empty() || current == known {
+                continue;
+            }
+            changed(&app, &known, &current);
+            known = current;
+        }
+    });
+}
+
+fn changed<R: Runtime>(app: &AppHandle<R>, before: &[Display], after: &[Display]) {
+    let names = |list: &[Display]| list.iter().map(|d| d.name.clone()).collect::<HashSet<_>>();
+    let (old, new) = (names(before), names(after));
+    let config = config();
+
+    if config.pull_back && old.difference(&new).next().is_some() {
+        for label in PULLED_WINDOWS {
+            if let Some(window) = app.get_webview_window(label) {
+                if let Err(e) = pull_back(app, &window, &old.difference(&new).cloned().collect()) {
+                    eprintln!("[CrewHub] Failed to pull back '{}': {}", label, e);
+                }
+            }
+        }
+    }
+
+    for display in after.iter().filter(|d| !old.contains(&d.name)) {
+        let rule = config
+            .rules
+            .iter()
+            .find(|rule| rule.monitor == display.name || (rule.monitor == "*" && !display.primary));
+        if let Some(rule) = rule {
+            if let Err(e) = apply(app, rule, display) {
+                eprintln!(
+                    "[CrewHub] Display rule for '{}' failed: {}",
+                    display.name, e
+                );
+            }
+        }
+    }
+    let relocated = rescue(app);
+    let _ = app.emit("displays-changed", after);
+    if !relocated.is_empty() {
+        let _ = app.emit("windows-relocated", relocated);
+    }
+}
+
+/// Open the rule's window on `display` (full screen if the rule says so).
+fn apply<R: Runtime>(
+    app: &AppHandle<R>,
+    rule: &DisplayRule,
+    display: &Display,
+) -> Result<(), String> {
+    layout_profiles::open(app, &rule.window)?;
+    let window = app
+        .get_webview_window(&rule.window)
+        .ok_or_else(|| format!("No window '{}'", rule.window))?;
+    window.set_fullscreen(false).map_err(|e| e.to_string())?;
+    window.unmaximize().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let x = display.x + (display.width.saturating_sub(size.width) / 2) as i32;
+    let y = display.y + (display.height.saturating_sub(size.height) / 2) as i32;
+    window
+        .set_position(PhysicalPosition::new(x, y))
+        .map_err(|e| e.to_string())?;
+    if rule.fullscreen {
+        window.set_fullscreen(true).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Move a window onto the primary monitor if it was full screen on a
+/// monitor that went away, or now sits outside every monitor.
+fn pull_back<R: Runtime>(
+    app: &AppHandle<R>,
+    window: &WebviewWindow<R>,
+    gone: &HashSet<String>,
+) -> Result<(), String> {
+    if !window.is_visible().unwrap_or(false) {
+        return Ok(());
+    }
+    let monitor = window.current_monitor().map_err(|e| e.to_string())?;
+    let stranded = match &monitor {
+        Some(monitor) => gone.contains(&name(monitor)),
+        None => true,
+    };
+    if !stranded {
+        return Ok(());
+    }
+    let primary = app
+        .primary_monitor()
+        .map_err(|e| e.to_string())?
+        .ok_or("No primary monitor")?;
+    window.set_fullscreen(false).map_err(|e| e.to_string())?;
+    move_onto(window, &primary, true)
+}
+
+/// Fit a window into `monitor`'s work area: centered, or else moved by as
+/// little as possible.
+fn move_onto<R: Runtime>(
+    window: &WebviewWindow<R>,
+    monitor: &Monitor,
+    center: bool,
+) -> Result<(), String> {
+    let area = monitor.work_area();
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let fitted = PhysicalSize::new(
+        size.width.min(area.size.width),
+        size.height.min(area.size.height),
+    );
+    if fitted != size {
+        window.set_size(fitted).map_err(|e| e.to_string())?;
+    }
+    let (min_x, min_y) = (area.position.x, area.position.y);
+    let max_x = min_x + (area.size.width - fitted.width) as i32;
+    let max_y = min_y + (area.size.height - fitted.height) as i32;
+    let position = if center {
+        PhysicalPosition::new((min_x + max_x) / 2, (min_y + max_y) / 2)
+    } else {
+        let current = window.outer_position().map_err(|e| e.to_string())?;
+        PhysicalPosition::new(current.x.clamp(min_x, max_x), current.y.clamp(min_y, max_y))
+    };
+    window.set_position(position).map_err(|e| e.to_string())
+}
+
+/// Whether enough of the window's top strip lies on `monitor` to grab it.
+fn reachable_on(position: PhysicalPosition<i32>, width: u32, monitor: &Monitor) -> bool {
+    let area = monitor.work_area();
+    let strip = (REACHABLE * monitor.scale_factor()) as i32;
+    let overlap_x = (position.x + width as i32).min(area.position.x + area.size.width as i32)
+        - position.x.max(area.position.x);
+    let overlap_y = (position.y + strip).min(area.position.y + area.size.height as i32)
+        - position.y.max(area.position.y);
+    overlap_x >= strip && overlap_y > 0
+}
+
+/// Squared distance from a point to a monitor's bounds.
+fn distance(x: i32, y: i32, monitor: &Monitor) -> i64 {
+    let (left, top) = (monitor.position().x, monitor.position().y);
+    let right = left + monitor.size().width as i32;
+    let bottom = top + monitor.size().height as i32;
+    let dx = (left - x).max(x - right).max(0) as i64;
+    let dy = (top - y).max(y - bottom).max(0) as i64;
+    dx * dx + dy * dy
+}
+
+/// Move every shown window that can't be reached onto the nearest monitor.
+/// Returns the labels of the windows that moved.
+fn rescue<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let monitors = app.available_monitors().unwrap_or_default();
+    if monitors.is_empty() {
+        return Vec::new();
+    }
+    let mut relocated = Vec::new();
+    for (label, window) in app.webview_windows() {
+        if !window.is_visible().unwrap_or(false)
+            || window.is_minimized().unwrap_or(false)
+            || window.is_maximized().unwrap_or(false)
+            || window.is_fullscreen().unwrap_or(false)
+        {
+            continue;
+        }
+        let (Ok(position), Ok(size)) = (window.outer_position(), window.outer_size()) else {
+            continue;
+        };
+        if monitors
+            .iter()
+            .any(|monitor| reachable_on(position, size.width, monitor))
+        {
+            continue;
+        }
+        let (cx, cy) = (
+            position.x + size.width as i32 / 2,
+            position.y + size.height as i32 / 2,
+        );
+        let Some(nearest) = monitors.iter().min_by_key(|m| distance(cx, cy, m)) else {
+            continue;
+        };
+        match move_onto(&window, nearest, false) {
+            Ok(()) => relocated.push(label),
+            Err(e) => eprintln!("[CrewHub] Failed to relocate '{}': {}", label, e),
+        }
+    }
+    relocated
+}
+
+/// Tauri command: connected monitors (names to use in rules).
+/// Called from the settings window via `invoke('list_displays')`.
+#[tauri::command]
+pub fn list_displays(app: AppHandle) -> Vec<Display> {
+    displays(&app)
+}
+
+/// Tauri command: display hot-plug rules.
+/// Called from the settings window via `invoke('get_display_rules')`.
+#[tauri::command]
+pub fn get_display_rules() -> DisplayConfig {
+    config()
+}
+
+/// Tauri command: replace the display hot-plug rules.
+/// Called from the settings window via `invoke('set_display_rules', { config })`.
+#[tauri::command]
+pub fn set_display_rules(config: DisplayConfig, app: AppHandle) -> Result<(), String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config);
+    }
+    Ok(())
+}
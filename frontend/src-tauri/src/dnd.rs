@@ -0,0 +1,269 @@
+//! OS Do Not Disturb / Focus detection.
+//!
+//! The platform's focus state is polled (macOS Focus assertions, the Windows
+//! toast setting that Do Not Disturb / Focus Assist flips, GNOME's
+//! `show-banners`). While it is on, CrewHub notifications are dropped and the
+//! tray stops pulsing. Critical alerts (a blocked agent, a blown budget) can be
+//! let through with [`DndConfig::critical_override`].
+//! CrewHub's own Do Not Disturb (toggled from the taskbar thumbnail, the
+//! control API or the frontend) has the same effect; it lasts until turned
+//! off or CrewHub restarts. Meetings from the user's calendar turn it on for
+//! their duration too (see [`crate::calendar`]), and so do scheduled quiet
+//! hours (see [`crate::quiet_hours`]), which hold notifications instead.
+
+use crate::calendar::Meeting;
+use crate::{power, store};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "dnd.json";
+
+/// How often the OS focus state is checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// CrewHub's own Do Not Disturb, independent of the OS.
+static MANUAL: AtomicBool = AtomicBool::new(false);
+
+/// Scheduled quiet hours are on.
+static SCHEDULED: AtomicBool = AtomicBool::new(false);
+
+/// The calendar meeting in progress, if calendar sync is on.
+static CALENDAR: Mutex<Option<Meeting>> = Mutex::new(None);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DndConfig {
+    /// Follow the OS Do Not Disturb state at all.
+    pub respect_os: bool,
+    /// Still show critical alerts while Do Not Disturb is on.
+    pub critical_override: bool,
+}
+
+impl Default for DndConfig {
+    fn default() -> Self {
+        Self {
+            respect_os: true,
+            critical_override: false,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DndStatus {
+    /// The OS reports Do Not Disturb / Focus as on.
+    pub active: bool,
+    /// CrewHub's own Do Not Disturb is on.
+    pub manual: bool,
+    /// The calendar meeting that turned Do Not Disturb on.
+    pub calendar: Option<Meeting>,
+    /// CrewHub is currently holding back notifications.
+    pub quiet: bool,
+    /// Why notifications are held back: `manual`, `calendar`, `schedule` or `os`.
+    pub reason: Option<&'static str>,
+    pub config: DndConfig,
+}
+
+/// App state: DND config.
+pub struct DndState {
+    config: Mutex<DndConfig>,
+}
+
+/// Register state and start polling the OS focus state.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(DndState {
+        config: Mutex::new(store::load_json(app, CONFIG_FILE)),
+    });
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        if let Some(active) = detect() {
+            if ACTIVE.swap(active, Ordering::Relaxed) != active {
+                let _ = app.emit("dnd-changed", status(&app));
+            }
+        }
+        std::thread::sleep(power::interval(&app, POLL_INTERVAL));
+    });
+}
+
+fn config<R: Runtime>(app: &AppHandle<R>) -> DndConfig {
+    app.try_state::<DndState>()
+        .and_then(|state| state.config.lock().ok().map(|c| c.clone()))
+        .unwrap_or_default()
+}
+
+fn reason<R: Runtime>(app: &AppHandle<R>) -> Option<&'static str> {
+    if MANUAL.load(Ordering::Relaxed) {
+        Some("manual")
+    } else if calendar_meeting().is_some() {
+        Some("calendar")
+    } else if scheduled() {
+        Some("schedule")
+    } else if ACTIVE.load(Ordering::Relaxed) && config(app).respect_os {
+        Some("os")
+    } else {
+        None
+    }
+}
+
+/// Whether CrewHub should stay quiet (no notifications, steady tray icon).
+pub fn quiet<R: Runtime>(app: &AppHandle<R>) -> bool {
+    reason(app).is_some()
+}
+
+/// Whether CrewHub's own Do Not Disturb is on.
+pub fn manual() -> bool {
+    MANUAL.load(Ordering::Relaxed)
+}
+
+/// Turn CrewHub's own Do Not Disturb on or off.
+pub fn set_manual<R: Runtime>(app: &AppHandle<R>, on: bool) {
+    if MANUAL.swap(on, Ordering::Relaxed) != on {
+        let _ = app.emit("dnd-changed", status(app));
+        #[cfg(target_os = "windows")]
+        crate::thumb_bar::refresh(app);
+    }
+}
+
+/// The calendar meeting holding Do Not Disturb on, if any.
+pub fn calendar_meeting() -> Option<Meeting> {
+    CALENDAR.lock().ok().and_then(|meeting| meeting.clone())
+}
+
+/// Record the calendar meeting in progress (`None` once it is over).
+pub fn set_calendar<R: Runtime>(app: &AppHandle<R>, meeting: Option<Meeting>) {
+    let changed = match CALENDAR.lock() {
+        Ok(mut current) if *current != meeting => {
+            *current = meeting;
+            true
+        }
+        _ => false,
+    };
+    if changed {
+        let _ = app.emit("dnd-changed", status(app));
+    }
+}
+
+/// Whether scheduled quiet hours are on.
+pub fn scheduled() -> bool {
+    SCHEDULED.load(Ordering::Relaxed)
+}
+
+/// Record the start or end of scheduled quiet hours.
+pub fn set_schedule<R: Runtime>(app: &AppHandle<R>, on: bool) {
+    if SCHEDULED.swap(on, Ordering::Relaxed) != on {
+        let _ = app.emit("dnd-changed", status(app));
+    }
+}
+
+/// Whether a notification should be dropped right now.
+pub fn suppresses<R: Runtime>(app: &AppHandle<R>, critical: bool) -> bool {
+    quiet(app) && !(critical && config(app).critical_override)
+}
+
+fn status<R: Runtime>(app: &AppHandle<R>) -> DndStatus {
+    DndStatus {
+        active: ACTIVE.load(Ordering::Relaxed),
+        manual: manual(),
+        calendar: calendar_meeting(),
+        quiet: quiet(app),
+        reason: reason(app),
+        config: config(app),
+    }
+}
+
+/// GNOME turns off notification banners for Do Not Disturb.
+#[cfg(target_os = "linux")]
+fn detect() -> Option<bool> {
+    let output = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim() == "false")
+}
+
+/// A Focus mode is on when the DoNotDisturb database holds an assertion.
+#[cfg(target_os = "macos")]
+fn detect() -> Option<bool> {
+    let home = std::env::var_os("HOME")?;
+    let path = std::path::Path::new(&home).join("Library/DoNotDisturb/DB/Assertions.json");
+    let text = std::fs::read_to_string(path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&text).ok()?;
+    let active = value["data"].as_array().is_some_and(|data| {
+        data.iter().any(|entry| {
+            entry["storeAssertionRecords"]
+                .as_array()
+                .is_some_and(|records| !records.is_empty())
+        })
+    });
+    Some(active)
+}
+
+/// Do Not Disturb / Focus Assist clears the global toast setting.
+#[cfg(target_os = "windows")]
+fn detect() -> Option<bool> {
+    use std::os::windows::process::CommandExt;
+    let output = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKCU\Software\Microsoft\Windows\CurrentVersion\Notifications\Settings",
+            "/v",
+            "NOC_GLOBAL_SETTING_TOASTS_ENABLED",
+        ])
+        // CREATE_NO_WINDOW
+        .creation_flags(0x0800_0000)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        // The value only exists once notifications have been turned off.
+        return Some(false);
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    Some(text.trim_end().ends_with("0x0"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn detect() -> Option<bool> {
+    None
+}
+
+/// Tauri command: OS Do Not Disturb state and config.
+/// Called from the frontend via `invoke('get_dnd_status')`.
+#[tauri::command]
+pub fn get_dnd_status(app: AppHandle) -> DndStatus {
+    status(&app)
+}
+
+/// Tauri command: turn CrewHub's own Do Not Disturb on or off.
+/// Called from the frontend via `invoke('set_manual_dnd', { enabled })`.
+#[tauri::command]
+pub fn set_manual_dnd(enabled: bool, app: AppHandle) -> DndStatus {
+    set_manual(&app, enabled);
+    status(&app)
+}
+
+/// Tauri command: replace the Do Not Disturb config.
+/// Called from the settings window via `invoke('configure_dnd', { config })`.
+#[tauri::command]
+pub fn configure_dnd(
+    config: DndConfig,
+    app: AppHandle,
+    state: State<DndState>,
+) -> Result<DndStatus, String> {
+    {
+        let mut current = state.config.lock().map_err(|e| e.to_string())?;
+        *current = config;
+        store::save_json(&app, CONFIG_FILE, &*current)?;
+    }
+    let status = status(&app);
+    let _ = app.emit("dnd-changed", status.clone());
+    Ok(status)
+}
@@ -0,0 +1,189 @@
+//! macOS Dock menu.
+//!
+//! Right-clicking the Dock icon lists the same entries as the tray (Chat,
+//! World, Zen, Settings) plus recent conversations. Tauri has no Dock menu
+//! API, so `applicationDockMenu:` is added to the app delegate's class at
+//! startup and returns an `NSMenu` kept here. Items target a small
+//! Objective-C object whose action performs the matching
+//! [`crate::control`] action. The menu is rebuilt whenever recents or the UI
+//! language change; it is only visible while CrewHub shows a Dock icon.
+
+use crate::{control, i18n, recents};
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, Imp, NSObject, Sel};
+use objc2::{define_class, msg_send, sel, MainThreadMarker, MainThreadOnly};
+use objc2_app_kit::{NSApplication, NSMenu, NSMenuItem, NSUserInterfaceLayoutDirection};
+use objc2_foundation::NSString;
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Runtime};
+
+/// Recent conversations listed below the window entries.
+const MAX_RECENTS: usize = 5;
+
+/// Performs a control action; set once in [`init`].
+type Handler = Box<dyn Fn(&str, &Value) + Send + Sync>;
+static HANDLER: OnceLock<Handler> = OnceLock::new();
+
+struct Entry {
+    action: &'static str,
+    args: Value,
+}
+
+thread_local! {
+    // AppKit objects live on the main thread only.
+    static MENU: RefCell<Option<Retained<NSMenu>>> = const { RefCell::new(None) };
+    static TARGET: RefCell<Option<Retained<DockMenuTarget>>> = const { RefCell::new(None) };
+    /// Indexed by the menu item's tag.
+    static ENTRIES: RefCell<Vec<Entry>> = const { RefCell::new(Vec::new()) };
+}
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "CrewHubDockMenuTarget"]
+    struct DockMenuTarget;
+
+    impl DockMenuTarget {
+        #[unsafe(method(performEntry:))]
+        fn perform_entry(&self, sender: &NSMenuItem) {
+            let Ok(index) = usize::try_from(sender.tag()) else {
+                return;
+            };
+            ENTRIES.with(|entries| {
+                if let (Some(entry), Some(handler)) = (entries.borrow().get(index), HANDLER.get()) {
+                    handler(entry.action, &entry.args);
+                }
+            });
+        }
+    }
+);
+
+/// `-[NSApplicationDelegate applicationDockMenu:]`, added to the delegate class.
+extern "C-unwind" fn application_dock_menu(
+    _this: &AnyObject,
+    _cmd: Sel,
+    _sender: &NSApplication,
+) -> *mut NSMenu {
+    MENU.with(|menu| {
+        menu.borrow().as_ref().map_or(std::ptr::null_mut(), |menu| {
+            Retained::as_ptr(menu).cast_mut()
+        })
+    })
+}
+
+/// Hook the Dock menu into the app delegate and build it. Must run on the
+/// main thread, after the app has finished launching (i.e. from setup).
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        eprintln!("[CrewHub] Dock menu must be installed on the main thread");
+        return;
+    };
+    let handle = app.clone();
+    let _ = HANDLER.set(Box::new(move |action, args| {
+        if let Err(e) = control::perform(&handle, action, args) {
+            eprintln!("[CrewHub] Dock menu: {}", e);
+        }
+    }));
+
+    let Some(delegate) = NSApplication::sharedApplication(mtm).delegate() else {
+        eprintln!("[CrewHub] No app delegate; Dock menu not installed");
+        return;
+    };
+    let class: *const AnyClass = AsRef::<AnyObject>::as_ref(&*delegate).class();
+    let imp: Imp = unsafe {
+        std::mem::transmute::<
+            extern "C-unwind" fn(&AnyObject, Sel, &NSApplication) -> *mut NSMenu,
+            Imp,
+        >(application_dock_menu)
+    };
+    // Returns NO if the delegate already implements it; keep whatever is there.
+    let added = unsafe {
+        objc2::ffi::class_addMethod(
+            class.cast_mut(),
+            sel!(applicationDockMenu:),
+            imp,
+            c"@@:@".as_ptr(),
+        )
+    };
+    if !added.as_bool() {
+        eprintln!("[CrewHub] App delegate already provides a Dock menu");
+    }
+    TARGET.with(|target| {
+        let object: Retained<DockMenuTarget> =
+            unsafe { msg_send![DockMenuTarget::alloc(mtm), init] };
+        *target.borrow_mut() = Some(object);
+    });
+    refresh(app);
+}
+
+/// Rebuild the Dock menu from the current recents and language.
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let mut items: Vec<(String, Entry)> = [
+        ("chat", "open-chat"),
+        ("world", "open-world"),
+        ("zen", "start-focus"),
+        ("settings", "open-settings"),
+    ]
+    .into_iter()
+    .map(|(id, action)| {
+        let entry = Entry {
+            action,
+            args: Value::Null,
+        };
+        (i18n::t(&format!("tray.{}", id)), entry)
+    })
+    .collect();
+    let fixed = items.len();
+    items.extend(
+        recents::recent(app)
+            .into_iter()
+            .take(MAX_RECENTS)
+            .map(|recent| {
+                let conversation = recent.conversation;
+                let entry = Entry {
+                    action: "open-conversation",
+                    args: json!({
+                        "sessionKey": conversation.session_key,
+                        "backendId": conversation.backend_id,
+                        "title": conversation.title,
+                    }),
+                };
+                (conversation.title, entry)
+            }),
+    );
+
+    let _ = app.run_on_main_thread(move || {
+        let Some(mtm) = MainThreadMarker::new() else {
+            return;
+        };
+        let menu = NSMenu::new(mtm);
+        if crate::i18n::rtl() {
+            menu.setUserInterfaceLayoutDirection(NSUserInterfaceLayoutDirection::RightToLeft);
+        }
+        let target = TARGET.with(|target| target.borrow().clone());
+        let mut entries = Vec::with_capacity(items.len());
+        for (index, (title, entry)) in items.into_iter().enumerate() {
+            if index == fixed {
+                menu.addItem(&NSMenuItem::separatorItem(mtm));
+            }
+            let item = unsafe {
+                NSMenuItem::initWithTitle_action_keyEquivalent(
+                    NSMenuItem::alloc(mtm),
+                    &NSString::from_str(&title),
+                    Some(sel!(performEntry:)),
+                    &NSString::from_str(""),
+                )
+            };
+            item.setTag(index as isize);
+            if let Some(target) = &target {
+                unsafe { item.setTarget(Some(target)) };
+            }
+            menu.addItem(&item);
+            entries.push(entry);
+        }
+        ENTRIES.with(|cell| *cell.borrow_mut() = entries);
+        MENU.with(|cell| *cell.borrow_mut() = Some(menu));
+    });
+}
@@ -0,0 +1,154 @@
+//! Dragging attachments and exports out of CrewHub into other apps.
+//!
+//! Webviews can only drag data within the page, so the frontend calls
+//! `start_drag_out` from its `dragstart` handler (and cancels the HTML drag).
+//! Each file is materialized first — local files as they are, anything
+//! remote downloaded into a per-drag directory in the app cache under its
+//! display name — and then an OS file drag is started from the window, so
+//! Finder, Explorer, file managers and chat apps receive real files.
+
+use crate::attachments;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+
+/// Materialized files under the app cache dir (cleared at startup).
+const DRAG_DIR: &str = "drag-out";
+
+/// Drag preview.
+const PREVIEW_ICON: &[u8] = include_bytes!("../icons/64x64.png");
+
+/// A file to drag, as shown in the frontend.
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DragFile {
+    /// http(s) or `file:` URL, or a local path.
+    url: String,
+    /// File name to give it; defaults to the last URL segment.
+    name: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DragOutResult {
+    dropped: bool,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(dir) = drag_root(app) {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
+
+fn drag_root<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join(DRAG_DIR))
+}
+
+/// File name without directories, so it can't escape the drag directory.
+fn file_name(file: &DragFile) -> String {
+    let name = file.name.clone().unwrap_or_else(|| {
+        file.url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    });
+    name.rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.is_empty() && *name != "." && *name != "..")
+        .unwrap_or("attachment")
+        .to_string()
+}
+
+/// Local path of an existing file the URL points at, if any.
+fn local_path(url: &str) -> Option<PathBuf> {
+    let path = match url::Url::parse(url) {
+        Ok(url) if url.scheme() == "file" => url.to_file_path().ok()?,
+        Ok(url) if matches!(url.scheme(), "http" | "https") => return None,
+        _ => PathBuf::from(url),
+    };
+    path.is_file().then_some(path)
+}
+
+/// Make every file exist on disk under its display name.
+fn materialize<R: Runtime>(app: &AppHandle<R>, files: &[DragFile]) -> Result<Vec<PathBuf>, String> {
+    let dir = drag_root(app)?.join(uuid::Uuid::new_v4().to_string());
+    let mut paths = Vec::new();
+    for file in files {
+        let name = file_name(file);
+        if let Some(path) = local_path(&file.url) {
+            if path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy() == name)
+            {
+                paths.push(path);
+                continue;
+            }
+        }
+        // Same names in one drag go to separate directories.
+        let target = dir.join(paths.len().to_string()).join(&name);
+        std::fs::create_dir_all(target.parent().unwrap_or(Path::new(&dir)))
+            .map_err(|e| e.to_string())?;
+        attachments::fetch(app, &file.url, &target)?;
+        paths.push(target);
+    }
+    Ok(paths)
+}
+
+fn start<R: Runtime>(window: &WebviewWindow<R>, paths: Vec<PathBuf>) -> Result<(), String> {
+    let label = window.label().to_string();
+    let app = window.app_handle().clone();
+    let on_drop = move |result: drag::DragResult, _position: drag::CursorPosition| {
+        let dropped = matches!(result, drag::DragResult::Dropped);
+        let _ = app.emit_to(
+            label.as_str(),
+            "drag-out-finished",
+            DragOutResult { dropped },
+        );
+    };
+    #[cfg(target_os = "linux")]
+    let handle = window.gtk_window().map_err(|e| e.to_string())?;
+    #[cfg(target_os = "linux")]
+    let handle = &handle;
+    #[cfg(not(target_os = "linux"))]
+    let handle = window;
+    drag::start_drag(
+        handle,
+        drag::DragItem::Files(paths),
+        drag::Image::Raw(PREVIEW_ICON.to_vec()),
+        on_drop,
+        drag::Options::default(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Tauri command: materialize files and start an OS drag with them from the
+/// calling window. Emits `drag-out-finished` (`{ dropped }`) to the window
+/// when the drag ends.
+/// Called from the chat window via `invoke('start_drag_out', { files })`.
+#[tauri::command]
+pub async fn start_drag_out(
+    app: AppHandle,
+    files: Vec<DragFile>,
+    window: WebviewWindow,
+) -> Result<(), String> {
+    if files.is_empty() {
+        return Err("Nothing to drag".to_string());
+    }
+    let handle = app.clone();
+    let paths = tauri::async_runtime::spawn_blocking(move || materialize(&handle, &files))
+        .await
+        .map_err(|e| e.to_string())??;
+    let (tx, rx) = std::sync::mpsc::channel();
+    app.run_on_main_thread(move || {
+        let _ = tx.send(start(&window, paths));
+    })
+    .map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || rx.recv().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())??
+}
@@ -0,0 +1,348 @@
+//! Local embedding cache for retrieval over project files.
+//!
+//! Files are split into line-aligned chunks, embedded through an
+//! OpenAI-compatible `/embeddings` endpoint (the local model runner by
+//! default), and stored in SQLite keyed by project, path, and chunk. Chunks
+//! whose content hash hasn't changed are never re-embedded. Search is a
+//! brute-force cosine scan over the project's vectors, which is fast enough
+//! for per-project corpora and needs no SQLite extension.
+//!
+//! Exposed to the frontend as commands and to agents as MCP tools (see
+//! [`crate::local_tools`]), so the backend can offload retrieval to the
+//! machine where the files live.
+
+use crate::{db, local_model, store};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime, State};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "embeddings.json";
+
+/// Target chunk size in bytes; chunks break on line boundaries.
+const CHUNK_BYTES: usize = 1500;
+
+/// Texts sent per embeddings request.
+const BATCH_SIZE: usize = 32;
+
+/// Files larger than this are skipped (generated or binary blobs).
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EmbeddingConfig {
+    /// OpenAI-compatible base URL (`…/v1`); defaults to the local model runner.
+    pub endpoint: Option<String>,
+    pub model: Option<String>,
+    pub api_key: Option<String>,
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbedReport {
+    files: usize,
+    chunks: usize,
+    embedded: usize,
+    skipped_files: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    path: String,
+    chunk: i64,
+    text: String,
+    score: f32,
+}
+
+/// App state: database connection and embedding endpoint config.
+pub struct EmbeddingState {
+    conn: Mutex<Connection>,
+    config: Mutex<EmbeddingConfig>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let conn = db::open(app)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS embeddings (
+            project TEXT NOT NULL,
+            path TEXT NOT NULL,
+            chunk INTEGER NOT NULL,
+            hash TEXT NOT NULL,
+            text TEXT NOT NULL,
+            vector BLOB NOT NULL,
+            PRIMARY KEY (project, path, chunk)
+        );",
+    )
+    .map_err(|e| e.to_string())?;
+    app.manage(EmbeddingState {
+        conn: Mutex::new(conn),
+        config: Mutex::new(store::load_json(app, CONFIG_FILE)),
+    });
+    Ok(())
+}
+
+/// Split text into chunks of roughly `CHUNK_BYTES`, breaking between lines.
+fn chunk_text(text: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in text.split_inclusive('\n') {
+        if !current.is_empty() && current.len() + line.len() > CHUNK_BYTES {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// FNV-1a; stable across Rust versions, unlike `DefaultHasher`.
+fn content_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325u64, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+fn to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut na, mut nb) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        na += x * x;
+        nb += y * y;
+    }
+    if na == 0.0 || nb == 0.0 {
+        0.0
+    } else {
+        dot / (na.sqrt() * nb.sqrt())
+    }
+}
+
+/// Embed a batch of texts via the configured OpenAI-compatible endpoint.
+fn embed<R: Runtime>(app: &AppHandle<R>, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let config = app
+        .state::<EmbeddingState>()
+        .config
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
+    let endpoint = config
+        .endpoint
+        .or_else(|| local_model::running_endpoint(app))
+        .ok_or_else(|| {
+            "No embedding endpoint: start the local model runner or configure one".to_string()
+        })?;
+    let mut request = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(120))
+        .build()
+        .post(&format!("{}/embeddings", endpoint.trim_end_matches('/')))
+        .set("Content-Type", "application/json");
+    if let Some(key) = &config.api_key {
+        request = request.set("Authorization", &format!("Bearer {}", key));
+    }
+    let body = json!({ "model": config.model.unwrap_or_default(), "input": texts });
+    let raw = request
+        .send_string(&body.to_string())
+        .map_err(|e| format!("Embedding request failed: {}", e))?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+    let reply: Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+
+    let vectors: Vec<Vec<f32>> = reply["data"]
+        .as_array()
+        .ok_or_else(|| "Invalid embeddings response".to_string())?
+        .iter()
+        .map(|item| {
+            item["embedding"]
+                .as_array()
+                .map(|v| {
+                    v.iter()
+                        .filter_map(Value::as_f64)
+                        .map(|f| f as f32)
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+        .collect();
+    if vectors.len() != texts.len() {
+        return Err("Embedding count does not match input".to_string());
+    }
+    Ok(vectors)
+}
+
+/// Chunk, embed (changed chunks only), and store the given files for a project.
+pub fn embed_and_store_files<R: Runtime>(
+    app: &AppHandle<R>,
+    project: &str,
+    paths: &[String],
+) -> Result<EmbedReport, String> {
+    let state = app.state::<EmbeddingState>();
+    let mut report = EmbedReport::default();
+
+    for path in paths {
+        let text = match std::fs::metadata(path) {
+            Ok(meta) if meta.len() <= MAX_FILE_BYTES => std::fs::read_to_string(path).ok(),
+            _ => None,
+        };
+        let Some(text) = text else {
+            report.skipped_files.push(path.clone());
+            continue;
+        };
+        report.files += 1;
+
+        let chunks = chunk_text(&text);
+        report.chunks += chunks.len();
+        let stale: Vec<(usize, String)> = {
+            let conn = state.conn.lock().map_err(|e| e.to_string())?;
+            chunks
+                .iter()
+                .enumerate()
+                .filter(|(i, chunk)| {
+                    let stored: Option<String> = conn
+                        .query_row(
+                            "SELECT hash FROM embeddings WHERE project = ?1 AND path = ?2 AND chunk = ?3",
+                            params![project, path, *i as i64],
+                            |row| row.get(0),
+                        )
+                        .ok();
+                    stored.as_deref() != Some(content_hash(chunk).as_str())
+                })
+                .map(|(i, chunk)| (i, chunk.clone()))
+                .collect()
+        };
+
+        for batch in stale.chunks(BATCH_SIZE) {
+            let texts: Vec<String> = batch.iter().map(|(_, t)| t.clone()).collect();
+            let vectors = embed(app, &texts)?;
+            let conn = state.conn.lock().map_err(|e| e.to_string())?;
+            for ((i, chunk), vector) in batch.iter().zip(vectors) {
+                conn.execute(
+                    "INSERT OR REPLACE INTO embeddings (project, path, chunk, hash, text, vector)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        project,
+                        path,
+                        *i as i64,
+                        content_hash(chunk),
+                        chunk,
+                        to_blob(&vector)
+                    ],
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            report.embedded += batch.len();
+        }
+
+        // The file may have shrunk: drop chunks past its new end.
+        let conn = state.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "DELETE FROM embeddings WHERE project = ?1 AND path = ?2 AND chunk >= ?3",
+            params![project, path, chunks.len() as i64],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(report)
+}
+
+/// Most similar stored chunks for `query` within a project.
+pub fn search<R: Runtime>(
+    app: &AppHandle<R>,
+    project: &str,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SearchHit>, String> {
+    let query_vector = embed(app, &[query.to_string()])?.pop().unwrap_or_default();
+    let state = app.state::<EmbeddingState>();
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT path, chunk, text, vector FROM embeddings WHERE project = ?1")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![project], |row| {
+            let blob: Vec<u8> = row.get(3)?;
+            Ok(SearchHit {
+                path: row.get(0)?,
+                chunk: row.get(1)?,
+                text: row.get(2)?,
+                score: cosine(&query_vector, &from_blob(&blob)),
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut hits: Vec<SearchHit> = rows.filter_map(Result::ok).collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(limit);
+    Ok(hits)
+}
+
+/// Tauri command: embed files into the project's cache (unchanged chunks are skipped).
+/// Called from the frontend via `invoke('embed_and_store', { project, paths })`.
+#[tauri::command]
+pub async fn embed_and_store(
+    project: String,
+    paths: Vec<String>,
+    app: AppHandle,
+) -> Result<EmbedReport, String> {
+    tauri::async_runtime::spawn_blocking(move || embed_and_store_files(&app, &project, &paths))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: nearest chunks to a natural-language query.
+/// Called from the frontend via `invoke('similarity_search', { project, query, limit })`.
+#[tauri::command]
+pub async fn similarity_search(
+    project: String,
+    query: String,
+    limit: Option<usize>,
+    app: AppHandle,
+) -> Result<Vec<SearchHit>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        search(&app, &project, &query, limit.unwrap_or(10))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: drop all cached embeddings of a project.
+/// Called from the frontend via `invoke('clear_embeddings', { project })`.
+#[tauri::command]
+pub fn clear_embeddings(project: String, state: State<EmbeddingState>) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM embeddings WHERE project = ?1",
+        params![project],
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// Tauri command: set the embedding endpoint (unset fields fall back to the local runner).
+/// Called from the frontend via `invoke('configure_embeddings', { config })`.
+#[tauri::command]
+pub fn configure_embeddings(
+    config: EmbeddingConfig,
+    app: AppHandle,
+    state: State<EmbeddingState>,
+) -> Result<(), String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    *state.config.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
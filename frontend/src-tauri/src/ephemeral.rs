@@ -0,0 +1,88 @@
+//! Ephemeral ("incognito") chat windows.
+//!
+//! `open_ephemeral_chat` opens a separate chat window whose webview keeps
+//! nothing on disk (incognito data store: no cookies, local storage or
+//! cache survive it) and is titled as not saved. The frontend in that window
+//! sees `window.__CREWHUB_EPHEMERAL__` and claims each conversation it opens
+//! with `claim_ephemeral_session`; until the window closes, those
+//! conversations are kept out of recents, pins (and so the OS search index),
+//! and its notifications are never held for replay after the screen unlocks.
+
+use crate::i18n;
+use std::sync::Mutex;
+use tauri::{AppHandle, Runtime, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+
+/// Prefix of ephemeral chat window labels (one window per id).
+pub const LABEL_PREFIX: &str = "ephemeral-";
+
+/// Session keys claimed by open ephemeral windows: (window label, session key).
+static SESSIONS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+pub fn is_ephemeral_window(label: &str) -> bool {
+    label.starts_with(LABEL_PREFIX)
+}
+
+/// Whether an open ephemeral window holds this conversation.
+pub fn is_ephemeral(session_key: &str) -> bool {
+    SESSIONS
+        .lock()
+        .is_ok_and(|sessions| sessions.iter().any(|(_, key)| key == session_key))
+}
+
+fn ephemeral_init_script(label: &str) -> String {
+    format!(
+        "window.__TAURI_VIEW__ = 'mobile'; window.__CREWHUB_EPHEMERAL__ = true; {}",
+        crate::base_init(label)
+    )
+}
+
+/// Open a new ephemeral chat window (390×700, like the chat window).
+pub fn open<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    let label = format!("{}{}", LABEL_PREFIX, uuid::Uuid::new_v4().simple());
+    let window = WebviewWindowBuilder::new(app, &label, crate::view_url("ephemeral=1"))
+        .title(i18n::t("ephemeral.title"))
+        .inner_size(390.0, 700.0)
+        .min_inner_size(320.0, 500.0)
+        .resizable(true)
+        .incognito(true)
+        .user_agent(&crate::request_headers::user_agent())
+        .on_new_window(crate::external_links::new_window_handler(app))
+        .initialization_script(ephemeral_init_script(&label))
+        .build()
+        .map_err(|e| format!("Failed to create ephemeral chat window: {}", e))?;
+
+    let closed = label.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            if let Ok(mut sessions) = SESSIONS.lock() {
+                sessions.retain(|(label, _)| *label != closed);
+            }
+        }
+    });
+    crate::show_and_focus(&window);
+    Ok(label)
+}
+
+/// Tauri command: open an ephemeral chat window; returns its label.
+/// Called from the frontend via `invoke('open_ephemeral_chat')`.
+#[tauri::command]
+pub fn open_ephemeral_chat(app: AppHandle) -> Result<String, String> {
+    open(&app)
+}
+
+/// Tauri command: mark a conversation as ephemeral while this window is open.
+/// Called from an ephemeral chat window via `invoke('claim_ephemeral_session', { sessionKey })`.
+#[tauri::command]
+pub fn claim_ephemeral_session(session_key: String, window: WebviewWindow) -> Result<(), String> {
+    if !is_ephemeral_window(window.label()) {
+        return Err("Only ephemeral chat windows can claim conversations".to_string());
+    }
+    let mut sessions = SESSIONS.lock().map_err(|e| e.to_string())?;
+    let claimed = sessions
+        .iter()
+        .any(|(label, key)| label == window.label() && *key == session_key);
+    if !claimed {
+        sessions.push((window.label().to_string(), session_key));
+    }
+    Ok(())
+}
@@ -0,0 +1,256 @@
+//! Confirm-before-open for external links.
+//!
+//! Links in agent output can point anywhere, so no webview navigates away
+//! from the app or hands a URL to the system browser unchecked: navigations
+//! to `http(s):` / `mailto:` URLs are cancelled in every webview (see
+//! [`plugin`]), new-window requests (`window.open`, `target="_blank"`) are
+//! denied in every window built with [`new_window_handler`], and those URLs,
+//! like links the frontend opens via [`open_external_url`], go through a
+//! native confirmation that shows the destination domain before they open in
+//! the default browser. Any other scheme (`file:`, `smb:`, `ms-settings:`,
+//! other apps' URL schemes) is never opened; webviews only navigate within
+//! the app's own origins (see [`is_own_page`]).
+//! "Always allow" is remembered per domain (covering its subdomains) and can
+//! be revoked from settings.
+
+use crate::{i18n, store};
+use std::collections::BTreeSet;
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::plugin::TauriPlugin;
+use tauri::webview::{NewWindowFeatures, NewWindowResponse};
+use tauri::{AppHandle, Manager, Runtime, State};
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+use url::Url;
+
+/// Domains the user always allows (app data dir).
+const TRUSTED_FILE: &str = "link-domains.json";
+
+/// Domain key used for `mailto:` links.
+const MAILTO: &str = "mailto";
+
+// Dialog buttons (i18n keys).
+const OPEN: &str = "dialog.open";
+const ALWAYS_ALLOW: &str = "dialog.alwaysAllow";
+const CANCEL: &str = "dialog.cancel";
+
+/// App state: trusted domains, plus a lock so only one dialog is shown at a time.
+pub struct LinkState {
+    trusted: Mutex<BTreeSet<String>>,
+    dialog: Mutex<()>,
+}
+
+/// Plugin that intercepts external navigations in all webviews.
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("external-links")
+        .setup(|app, _api| {
+            app.manage(LinkState {
+                trusted: Mutex::new(store::load_json(app, TRUSTED_FILE)),
+                dialog: Mutex::new(()),
+            });
+            Ok(())
+        })
+        .on_navigation(|webview, url| {
+            if is_own_page(url) {
+                return true;
+            }
+            if domain(url).is_some() {
+                open_in_background(webview.app_handle(), url.clone());
+            } else {
+                eprintln!("[CrewHub] Blocked navigation to a {}: URL", url.scheme());
+            }
+            false
+        })
+        .build()
+}
+
+/// Handler for a window's new-window requests: external URLs are offered to
+/// the default browser, and no webview window is ever created for them (the
+/// frontend opens its own windows through commands).
+pub fn new_window_handler<R: Runtime>(
+    app: &AppHandle<R>,
+) -> impl Fn(Url, NewWindowFeatures) -> NewWindowResponse<R> + Send + 'static {
+    let app = app.clone();
+    move |url, _features| {
+        if domain(&url).is_some() {
+            open_in_background(&app, url);
+        }
+        NewWindowResponse::Deny
+    }
+}
+
+/// [`confirm_and_open`] without blocking the caller (webview callbacks).
+fn open_in_background<R: Runtime>(app: &AppHandle<R>, url: Url) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = confirm_and_open(&app, &url) {
+            eprintln!("[CrewHub] {}", e);
+        }
+    });
+}
+
+/// Hosts of the app's own pages: the dev server, bundled assets and IPC on
+/// Windows, and local backends.
+const OWN_HOSTS: &[&str] = &[
+    "localhost",
+    "127.0.0.1",
+    "[::1]",
+    "tauri.localhost",
+    "asset.localhost",
+    "ipc.localhost",
+];
+
+/// Whether a webview may navigate to `url` itself: the app's own origins
+/// (`tauri:`, `asset:` and `ipc:` on macOS and Linux, [`OWN_HOSTS`] over
+/// http(s)) and blank pages.
+fn is_own_page(url: &Url) -> bool {
+    match url.scheme() {
+        "tauri" | "asset" | "ipc" => true,
+        "about" => url.path() == "blank",
+        "http" | "https" => url.host_str().is_some_and(|host| {
+            OWN_HOSTS.contains(&host.trim_end_matches('.').to_lowercase().as_str())
+        }),
+        _ => false,
+    }
+}
+
+/// The domain to confirm for an external URL; `None` for the app's own pages
+/// and for schemes that are never opened.
+fn domain(url: &Url) -> Option<String> {
+    match url.scheme() {
+        "mailto" => Some(MAILTO.to_string()),
+        "http" | "https" => {
+            let host = url.host_str()?.trim_end_matches('.').to_lowercase();
+            (!OWN_HOSTS.contains(&host.as_str())).then_some(host)
+        }
+        _ => None,
+    }
+}
+
+fn trusted(trusted: &BTreeSet<String>, domain: &str) -> bool {
+    let mut candidate = domain;
+    loop {
+        if trusted.contains(candidate) {
+            return true;
+        }
+        match candidate.split_once('.') {
+            Some((_, parent)) => candidate = parent,
+            None => return false,
+        }
+    }
+}
+
+/// Ask (unless the domain is trusted) and open `url` in the default browser. Blocks.
+fn confirm_and_open<R: Runtime>(app: &AppHandle<R>, url: &Url) -> Result<bool, String> {
+    let Some(domain) = domain(url) else {
+        return Err(format!("Not an external link: {}", url));
+    };
+    let state = app.state::<LinkState>();
+    let _one_at_a_time = state.dialog.lock();
+    let known = state
+        .trusted
+        .lock()
+        .map(|set| trusted(&set, &domain))
+        .unwrap_or(false);
+    if !known {
+        let message = if domain == MAILTO {
+            i18n::tf("dialog.openMail", &[("address", url.path())])
+        } else {
+            i18n::tf(
+                "dialog.openLink",
+                &[("domain", &domain), ("url", url.as_str())],
+            )
+        };
+        let result = app
+            .dialog()
+            .message(message)
+            .title(i18n::t("dialog.openLinkTitle"))
+            .kind(MessageDialogKind::Info)
+            .buttons(MessageDialogButtons::YesNoCancelCustom(
+                i18n::t(OPEN),
+                i18n::t(ALWAYS_ALLOW),
+                i18n::t(CANCEL),
+            ))
+            .blocking_show_with_result();
+        let answer = match result {
+            MessageDialogResult::Custom(label) => [OPEN, ALWAYS_ALLOW, CANCEL]
+                .into_iter()
+                .find(|key| i18n::t(key) == label)
+                .unwrap_or(CANCEL),
+            MessageDialogResult::Yes | MessageDialogResult::Ok => OPEN,
+            // "Always allow" only counts when its own label comes back; a
+            // dismissed dialog may report `No`.
+            _ => CANCEL,
+        };
+        match answer {
+            OPEN => {}
+            ALWAYS_ALLOW => {
+                if let Ok(mut set) = state.trusted.lock() {
+                    set.insert(domain);
+                    if let Err(e) = store::save_json(app, TRUSTED_FILE, &*set) {
+                        eprintln!("[CrewHub] {}", e);
+                    }
+                }
+            }
+            _ => return Ok(false),
+        }
+    }
+    open_in_browser(url)?;
+    Ok(true)
+}
+
+/// Open `url` in the default browser without asking.
+pub fn open_in_browser(url: &Url) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        use std::os::windows::process::CommandExt;
+        // Unlike `cmd /c start`, doesn't re-parse `&` in the URL.
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command.creation_flags(0x0800_0000); // CREATE_NO_WINDOW
+        command
+    };
+    #[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+    command
+        .arg(url.as_str())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open {}: {}", url, e))
+}
+
+/// Tauri command: confirm and open an external link in the default browser.
+/// Resolves to whether it was opened.
+/// Called from the frontend via `invoke('open_external_url', { url })`.
+#[tauri::command]
+pub async fn open_external_url(url: String, app: AppHandle) -> Result<bool, String> {
+    let url = Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
+    tauri::async_runtime::spawn_blocking(move || confirm_and_open(&app, &url))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: domains whose links open without asking.
+/// Called from the frontend via `invoke('list_trusted_link_domains')`.
+#[tauri::command]
+pub fn list_trusted_link_domains(state: State<LinkState>) -> Result<BTreeSet<String>, String> {
+    Ok(state.trusted.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: stop trusting a domain.
+/// Called from the settings window via `invoke('forget_link_domain', { domain })`.
+#[tauri::command]
+pub fn forget_link_domain(
+    domain: String,
+    app: AppHandle,
+    state: State<LinkState>,
+) -> Result<BTreeSet<String>, String> {
+    let mut trusted = state.trusted.lock().map_err(|e| e.to_string())?;
+    trusted.remove(&domain.trim().to_lowercase());
+    store::save_json(&app, TRUSTED_FILE, &*trusted)?;
+    Ok(trusted.clone())
+}
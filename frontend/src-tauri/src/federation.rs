@@ -0,0 +1,214 @@
+//! Multi-backend federation.
+//!
+//! One app instance can be connected to several CrewHub backends at once
+//! (e.g. work and personal). Each backend gets its own event bridge
+//! subscription; conversations, badges, and notifications are namespaced by
+//! backend id, and the tray shows the aggregate. With a single backend
+//! configured everything behaves exactly as before.
+
+use crate::{license, store};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Registry file (app data dir).
+const BACKENDS_FILE: &str = "backends.json";
+
+/// Id of the backend from `VITE_API_URL`, used when nothing is configured.
+pub const DEFAULT_BACKEND_ID: &str = "default";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendProfile {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+fn default_profiles() -> Vec<BackendProfile> {
+    vec![BackendProfile {
+        id: DEFAULT_BACKEND_ID.to_string(),
+        name: "CrewHub".to_string(),
+        url: crate::backend_url(),
+        enabled: true,
+    }]
+}
+
+/// App state: configured backends.
+pub struct FederationState {
+    profiles: Mutex<Vec<BackendProfile>>,
+}
+
+/// JSON of the enabled backends for window init scripts, which are built
+/// without an `AppHandle` (see `crate::base_init`).
+static INIT_SNAPSHOT: Mutex<String> = Mutex::new(String::new());
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let mut profiles: Vec<BackendProfile> = store::load_json(app, BACKENDS_FILE);
+    if profiles.is_empty() {
+        profiles = default_profiles();
+    }
+    update_snapshot(&profiles);
+    app.manage(FederationState {
+        profiles: Mutex::new(profiles),
+    });
+}
+
+fn update_snapshot(profiles: &[BackendProfile]) {
+    let enabled: Vec<&BackendProfile> = profiles.iter().filter(|p| p.enabled).collect();
+    if let Ok(mut snapshot) = INIT_SNAPSHOT.lock() {
+        *snapshot = serde_json::to_string(&enabled).unwrap_or_else(|_| "[]".to_string());
+    }
+    crate::backend_auth::set_profiles(profiles);
+}
+
+/// JS fragment exposing the enabled backends as `window.__CREWHUB_BACKENDS__`.
+pub fn init_script() -> String {
+    let backends = INIT_SNAPSHOT.lock().map(|s| s.clone()).unwrap_or_default();
+    if backends.is_empty() {
+        return String::new();
+    }
+    format!("window.__CREWHUB_BACKENDS__ = {};", backends)
+}
+
+/// Backends with a live connection.
+pub fn enabled<R: Runtime>(app: &AppHandle<R>) -> Vec<BackendProfile> {
+    app.try_state::<FederationState>()
+        .and_then(|state| state.profiles.lock().ok().map(|p| p.clone()))
+        .unwrap_or_else(default_profiles)
+        .into_iter()
+        .filter(|p| p.enabled)
+        .collect()
+}
+
+/// Display name to prefix notifications with, or `None` when only one backend
+/// is connected (so single-backend setups see no namespacing at all).
+pub fn label<R: Runtime>(app: &AppHandle<R>, backend_id: &str) -> Option<String> {
+    let enabled = enabled(app);
+    if enabled.len() < 2 {
+        return None;
+    }
+    enabled
+        .into_iter()
+        .find(|p| p.id == backend_id)
+        .map(|p| p.name)
+}
+
+/// Namespaced key for per-backend ids (agents, sessions); unchanged for the default backend.
+pub fn namespaced(backend_id: &str, id: &str) -> String {
+    if backend_id == DEFAULT_BACKEND_ID {
+        id.to_string()
+    } else {
+        format!("{}/{}", backend_id, id)
+    }
+}
+
+fn save_and_apply(
+    app: &AppHandle,
+    profiles: &[BackendProfile],
+) -> Result<Vec<BackendProfile>, String> {
+    store::save_json(app, BACKENDS_FILE, &profiles)?;
+    update_snapshot(profiles);
+    crate::bridge::sync(app);
+    crate::refresh_tray_menu(app);
+    let _ = app.emit("backends-changed", profiles);
+    Ok(profiles.to_vec())
+}
+
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    slug.trim_matches('-').to_string()
+}
+
+/// Tauri command: all configured backends.
+/// Called from the frontend via `invoke('list_backends')`.
+#[tauri::command]
+pub fn list_backends(state: State<FederationState>) -> Result<Vec<BackendProfile>, String> {
+    Ok(state.profiles.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: connect an additional backend.
+/// Called from the frontend via `invoke('add_backend', { name, url })`.
+#[tauri::command]
+pub fn add_backend(
+    name: String,
+    url: String,
+    app: AppHandle,
+    state: State<FederationState>,
+) -> Result<Vec<BackendProfile>, String> {
+    let parsed = url::Url::parse(&url).map_err(|e| format!("Invalid backend URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Backend URL must be http(s)".to_string());
+    }
+    license::require(license::MULTIPLE_BACKENDS)?;
+    let mut profiles = state.profiles.lock().map_err(|e| e.to_string())?;
+    let base = match slug(&name) {
+        s if s.is_empty() => "backend".to_string(),
+        s => s,
+    };
+    let mut id = base.clone();
+    let mut n = 2;
+    while profiles.iter().any(|p| p.id == id) {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    profiles.push(BackendProfile {
+        id,
+        name,
+        url: url.trim_end_matches('/').to_string(),
+        enabled: true,
+    });
+    save_and_apply(&app, &profiles)
+}
+
+/// Tauri command: enable or disable a backend without forgetting it.
+/// Called from the frontend via `invoke('set_backend_enabled', { id, enabled })`.
+#[tauri::command]
+pub fn set_backend_enabled(
+    id: String,
+    enabled: bool,
+    app: AppHandle,
+    state: State<FederationState>,
+) -> Result<Vec<BackendProfile>, String> {
+    let mut profiles = state.profiles.lock().map_err(|e| e.to_string())?;
+    let profile = profiles
+        .iter_mut()
+        .find(|p| p.id == id)
+        .ok_or_else(|| format!("Backend not found: {}", id))?;
+    profile.enabled = enabled;
+    if !profiles.iter().any(|p| p.enabled) {
+        return Err("At least one backend must stay enabled".to_string());
+    }
+    if enabled && profiles.iter().filter(|p| p.enabled).count() > 1 {
+        license::require(license::MULTIPLE_BACKENDS)?;
+    }
+    save_and_apply(&app, &profiles)
+}
+
+/// Tauri command: forget a backend.
+/// Called from the frontend via `invoke('remove_backend', { id })`.
+#[tauri::command]
+pub fn remove_backend(
+    id: String,
+    app: AppHandle,
+    state: State<FederationState>,
+) -> Result<Vec<BackendProfile>, String> {
+    let mut profiles = state.profiles.lock().map_err(|e| e.to_string())?;
+    let remaining: Vec<BackendProfile> = profiles.iter().filter(|p| p.id != id).cloned().collect();
+    if !remaining.iter().any(|p| p.enabled) {
+        return Err("At least one backend must stay enabled".to_string());
+    }
+    *profiles = remaining;
+    crate::clear_badge(&app, &id);
+    save_and_apply(&app, &profiles)
+}
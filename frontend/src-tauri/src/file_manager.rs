@@ -0,0 +1,204 @@
+//! Revealing and opening files from the chat UI.
+//!
+//! The frontend can only point at paths inside a scope: the app's own cache
+//! and data directories (staged attachments, printed and exported documents),
+//! the user's Downloads folder, and anything the user handed to CrewHub
+//! (dropped or shared files, see [`grant`]). Paths are canonicalized before
+//! the check, so `..` and symlinks can't escape it.
+//!
+//! Attachments can also be previewed natively ([`preview_attachment`]: Quick
+//! Look on macOS, the default viewer elsewhere) rather than downloaded into
+//! the webview; only files in the app cache (where attachments are staged and
+//! materialized) or handed over by the user qualify.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// App state: paths granted by the user during this run.
+#[derive(Default)]
+pub struct FileScope {
+    granted: Mutex<Vec<PathBuf>>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(FileScope::default());
+}
+
+/// Allow the frontend to reveal/open `path` (and, for a folder, its contents).
+pub fn grant<R: Runtime>(app: &AppHandle<R>, path: &Path) {
+    let Ok(path) = path.canonicalize() else {
+        return;
+    };
+    if let Some(scope) = app.try_state::<FileScope>() {
+        if let Ok(mut granted) = scope.granted.lock() {
+            if !granted.contains(&path) {
+                granted.push(path);
+            }
+        }
+    }
+}
+
+fn roots<R: Runtime>(app: &AppHandle<R>) -> Vec<PathBuf> {
+    let paths = app.path();
+    scoped_roots(
+        app,
+        [
+            paths.app_cache_dir(),
+            paths.app_data_dir(),
+            paths.download_dir(),
+        ],
+    )
+}
+
+/// `dirs` that exist, plus the paths granted during this run.
+fn scoped_roots<R: Runtime>(
+    app: &AppHandle<R>,
+    dirs: impl IntoIterator<Item = tauri::Result<PathBuf>>,
+) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = dirs
+        .into_iter()
+        .flatten()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .collect();
+    if let Some(scope) = app.try_state::<FileScope>() {
+        if let Ok(granted) = scope.granted.lock() {
+            roots.extend(granted.iter().cloned());
+        }
+    }
+    roots
+}
+
+/// Resolve `path` and check that it exists and lies inside the scope.
+fn validate<R: Runtime>(app: &AppHandle<R>, path: &str) -> Result<PathBuf, String> {
+    validate_in(&roots(app), path)
+}
+
+fn validate_in(roots: &[PathBuf], path: &str) -> Result<PathBuf, String> {
+    let resolved = Path::new(path)
+        .canonicalize()
+        .map_err(|e| format!("{}: {}", path, e))?;
+    if roots.iter().any(|root| resolved.starts_with(root)) {
+        // canonicalize() yields `\\?\` paths on Windows, which Explorer rejects.
+        #[cfg(target_os = "windows")]
+        if let Some(plain) = resolved.to_str().and_then(|s| s.strip_prefix(r"\\?\")) {
+            return Ok(PathBuf::from(plain));
+        }
+        Ok(resolved)
+    } else {
+        Err(format!("Path is outside the allowed locations: {}", path))
+    }
+}
+
+fn spawn(command: &mut Command) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x0800_0000); // CREATE_NO_WINDOW
+    }
+    command.spawn().map(|_| ()).map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn reveal(path: &Path) -> Result<(), String> {
+    spawn(Command::new("open").arg("-R").arg(path))
+}
+
+#[cfg(target_os = "windows")]
+fn reveal(path: &Path) -> Result<(), String> {
+    // explorer wants `/select,` and the path as a single argument.
+    let mut select = std::ffi::OsString::from("/select,");
+    select.push(path);
+    spawn(Command::new("explorer").arg(select))
+}
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+fn reveal(path: &Path) -> Result<(), String> {
+    // FileManager1 selects the item (Nautilus, Dolphin, Nemo, …); otherwise
+    // just open the containing folder.
+    let uri = url::Url::from_file_path(path).map_err(|_| "Invalid path".to_string())?;
+    let shown = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{}", uri))
+        .arg("string:")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if shown {
+        return Ok(());
+    }
+    let folder = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+    spawn(Command::new("xdg-open").arg(folder))
+}
+
+fn open(path: &Path, with: Option<&str>) -> Result<(), String> {
+    match with {
+        #[cfg(target_os = "macos")]
+        Some(app) => spawn(Command::new("open").arg("-a").arg(app).arg(path)),
+        #[cfg(not(target_os = "macos"))]
+        Some(app) => spawn(Command::new(app).arg(path)),
+        #[cfg(target_os = "macos")]
+        None => spawn(Command::new("open").arg(path)),
+        #[cfg(target_os = "windows")]
+        None => spawn(Command::new("explorer").arg(path)),
+        #[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+        None => spawn(Command::new("xdg-open").arg(path)),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn preview(path: &Path) -> Result<(), String> {
+    spawn(
+        Command::new("qlmanage")
+            .arg("-p")
+            .arg(path)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null()),
+    )
+}
+
+#[cfg(not(target_os = "macos"))]
+fn preview(path: &Path) -> Result<(), String> {
+    open(path, None)
+}
+
+/// Tauri command: show a file (selected) or folder in the platform file manager.
+/// Called from the frontend via `invoke('reveal_in_file_manager', { path })`.
+#[tauri::command]
+pub fn reveal_in_file_manager(path: String, app: AppHandle) -> Result<(), String> {
+    reveal(&validate(&app, &path)?)
+}
+
+/// Tauri command: open a file with its default application, or with `with`
+/// (an application name on macOS, an executable elsewhere).
+/// Called from the frontend via `invoke('open_path', { path, with })`.
+#[tauri::command]
+pub fn open_path(path: String, with: Option<String>, app: AppHandle) -> Result<(), String> {
+    open(&validate(&app, &path)?, with.as_deref())
+}
+
+/// Tauri command: preview an attachment natively (Quick Look on macOS, the
+/// default viewer elsewhere). Only staged attachments and other files in the
+/// app cache, or files the user handed to CrewHub, can be previewed.
+/// Called from the chat window via `invoke('preview_attachment', { path })`.
+#[tauri::command]
+pub fn preview_attachment(path: String, app: AppHandle) -> Result<(), String> {
+    let roots = scoped_roots(&app, [app.path().app_cache_dir()]);
+    let path = validate_in(&roots, &path)?;
+    if !path.is_file() {
+        return Err("Only files can be previewed".to_string());
+    }
+    preview(&path)
+}
@@ -0,0 +1,115 @@
+//! Focus (Zen) timer state for the tray tooltip.
+//!
+//! The timer itself runs in the Zen window; it reports start, pause and stop
+//! through `update_focus_timer`. While a session is running the tooltip's
+//! "minutes left" is refreshed every half minute, and the session is dropped
+//! once its end time passes even if the window never reports it. When the
+//! clock is set back, the end time moves with it so the session keeps its
+//! real remaining time.
+
+use crate::i18n;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// How often the remaining time in the tooltip is updated.
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The current session, if one is running or paused.
+static TIMER: Mutex<Option<FocusTimer>> = Mutex::new(None);
+
+/// Whether the tooltip ticker thread is running.
+static TICKING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusTimer {
+    /// Unix seconds when the session ends (ignored while paused).
+    pub ends_at: i64,
+    /// Seconds left when the session was paused; `None` while running.
+    #[serde(default)]
+    pub paused_remaining: Option<i64>,
+}
+
+impl FocusTimer {
+    fn remaining(&self) -> i64 {
+        self.paused_remaining
+            .unwrap_or_else(|| self.ends_at - chrono::Local::now().timestamp())
+    }
+}
+
+/// The current session, dropping it if it has run out.
+fn current() -> Option<FocusTimer> {
+    let mut timer = TIMER.lock().ok()?;
+    if timer.as_ref().is_some_and(|t| t.remaining() <= 0) {
+        *timer = None;
+    }
+    timer.clone()
+}
+
+/// Tooltip line for the running session (`None` when no session is on).
+pub fn summary() -> Option<String> {
+    let timer = current()?;
+    // Round up so the last minute reads "1 min", not "0 min".
+    let minutes = ((timer.remaining() + 59) / 60).to_string();
+    let key = if timer.paused_remaining.is_some() {
+        "tooltip.focusPaused"
+    } else {
+        "tooltip.focus"
+    };
+    Some(i18n::tf(key, &[("minutes", &minutes)]))
+}
+
+/// Keep the tooltip's remaining time current while a session runs.
+fn start_ticker<R: Runtime>(app: &AppHandle<R>) {
+    if TICKING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(TICK_INTERVAL);
+            let running = current();
+            crate::refresh_tray_tooltip(&app);
+            if running.is_none() {
+                break;
+            }
+        }
+        TICKING.store(false, Ordering::SeqCst);
+    });
+}
+
+/// Move a running session's end time by `seconds` after the wall clock jumped.
+pub fn shift<R: Runtime>(app: &AppHandle<R>, seconds: i64) {
+    let timer = {
+        let Ok(mut timer) = TIMER.lock() else {
+            return;
+        };
+        match timer.as_mut() {
+            Some(timer) if timer.paused_remaining.is_none() => {
+                timer.ends_at += seconds;
+                timer.clone()
+            }
+            _ => return,
+        }
+    };
+    crate::refresh_tray_tooltip(app);
+    let _ = app.emit("focus-timer-changed", Some(timer));
+}
+
+/// Tauri command: report the focus timer (`null` when the session ends or is cancelled).
+/// Called from the Zen window via `invoke('update_focus_timer', { timer })`.
+#[tauri::command]
+pub fn update_focus_timer(timer: Option<FocusTimer>, app: AppHandle) {
+    let running = timer.is_some();
+    if let Ok(mut current) = TIMER.lock() {
+        *current = timer.clone();
+    }
+    crate::refresh_tray_tooltip(&app);
+    if running {
+        start_ticker(&app);
+    }
+    let _ = app.emit("focus-timer-changed", timer);
+}
@@ -0,0 +1,134 @@
+//! Handoff and universal links (macOS).
+//!
+//! The conversation opened last is published as the current `NSUserActivity`
+//! so it can be continued on another Mac or iPhone signed in to the same
+//! Apple ID. Its web page URL is the conversation's universal link, which
+//! opens the website on devices without CrewHub.
+//!
+//! Universal links (`https://go.crewhub.app/…`, claimed through the Associated
+//! Domains entitlement) and Handoff activities both arrive in
+//! `application:continueUserActivity:restorationHandler:` and are routed like
+//! `crewhub://` links (see [`crate::url_scheme`]); paths the app doesn't handle
+//! fall back to the website in the browser.
+
+use crate::pins::ConversationRef;
+use crate::{external_links, url_scheme};
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, Bool, Imp, Sel};
+use objc2::{sel, AllocAnyThread, MainThreadMarker};
+use objc2_app_kit::NSApplication;
+use objc2_foundation::{NSString, NSUserActivity, NSUserActivityTypeBrowsingWeb, NSURL};
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Runtime};
+use url::Url;
+
+/// Activity type of conversations; listed under `NSUserActivityTypes` in `Info.plist`.
+const ACTIVITY_TYPE: &str = "dev.crewhub.conversation";
+
+thread_local! {
+    /// The published activity; it stays current only while retained.
+    static CURRENT: RefCell<Option<Retained<NSUserActivity>>> = const { RefCell::new(None) };
+}
+
+type ContinueUserActivity =
+    extern "C-unwind" fn(&AnyObject, Sel, &AnyObject, &NSUserActivity, *mut c_void) -> Bool;
+
+/// Opens a universal link; set once in [`init`].
+type Handler = Box<dyn Fn(Url) + Send + Sync>;
+static HANDLER: OnceLock<Handler> = OnceLock::new();
+
+/// The implementation this one replaced (Spotlight, see [`crate::search_index`]).
+static ORIGINAL: OnceLock<Imp> = OnceLock::new();
+
+/// The universal link an activity carries, if it is ours.
+fn universal_link(activity: &NSUserActivity) -> Option<Url> {
+    let url = activity.webpageURL()?.absoluteString()?.to_string();
+    let url = Url::parse(&url).ok()?;
+    let activity_type = activity.activityType();
+    let handoff = activity_type.isEqualToString(&NSString::from_str(ACTIVITY_TYPE));
+    let browsing = activity_type.isEqualToString(unsafe { NSUserActivityTypeBrowsingWeb });
+    (handoff || (browsing && url.host_str() == Some(url_scheme::UNIVERSAL_LINK_HOST)))
+        .then_some(url)
+}
+
+/// `-[NSApplicationDelegate application:continueUserActivity:restorationHandler:]`:
+/// Handoff activities and universal links are handled here, everything else
+/// goes to the original.
+extern "C-unwind" fn continue_user_activity(
+    this: &AnyObject,
+    cmd: Sel,
+    application: &AnyObject,
+    activity: &NSUserActivity,
+    restoration_handler: *mut c_void,
+) -> Bool {
+    if let (Some(url), Some(handler)) = (universal_link(activity), HANDLER.get()) {
+        handler(url);
+        return Bool::YES;
+    }
+    match ORIGINAL.get() {
+        Some(original) => {
+            let original: ContinueUserActivity = unsafe { std::mem::transmute(*original) };
+            original(this, cmd, application, activity, restoration_handler)
+        }
+        None => Bool::NO,
+    }
+}
+
+/// Route Handoff activities and universal links into the app. Runs on the
+/// main thread (setup), after [`crate::search_index::init`].
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        return;
+    };
+    let handle = app.clone();
+    let _ = HANDLER.set(Box::new(move |url| {
+        if !url_scheme::open_universal(&handle, &url) {
+            if let Err(e) = external_links::open_in_browser(&url) {
+                eprintln!("[CrewHub] {}", e);
+            }
+        }
+    }));
+
+    let Some(delegate) = NSApplication::sharedApplication(mtm).delegate() else {
+        eprintln!("[CrewHub] No app delegate; Handoff and universal links won't open");
+        return;
+    };
+    let class: *const AnyClass = AsRef::<AnyObject>::as_ref(&*delegate).class();
+    let selector = sel!(application:continueUserActivity:restorationHandler:);
+    let imp: Imp =
+        unsafe { std::mem::transmute::<ContinueUserActivity, Imp>(continue_user_activity) };
+    unsafe {
+        let method = objc2::ffi::class_getInstanceMethod(class, selector);
+        if method.is_null() {
+            objc2::ffi::class_addMethod(class.cast_mut(), selector, imp, c"c@:@@@?".as_ptr());
+        } else if let Some(original) = objc2::ffi::method_setImplementation(method.cast_mut(), imp)
+        {
+            let _ = ORIGINAL.set(original);
+        }
+    }
+}
+
+/// Publish `conversation` as the activity to continue on other devices.
+pub fn set_current<R: Runtime>(app: &AppHandle<R>, conversation: &ConversationRef) {
+    let title = conversation.title.clone();
+    let link = url_scheme::universal_link(conversation);
+    let _ = app.run_on_main_thread(move || {
+        let activity = NSUserActivity::initWithActivityType(
+            NSUserActivity::alloc(),
+            &NSString::from_str(ACTIVITY_TYPE),
+        );
+        activity.setTitle(Some(&NSString::from_str(&title)));
+        if let Some(url) = NSURL::URLWithString(&NSString::from_str(&link)) {
+            activity.setWebpageURL(Some(&url));
+        }
+        activity.setEligibleForHandoff(true);
+        activity.becomeCurrent();
+        CURRENT.with(|current| {
+            if let Some(previous) = current.replace(Some(activity)) {
+                previous.invalidate();
+            }
+        });
+    });
+}
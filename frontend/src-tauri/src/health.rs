@@ -0,0 +1,208 @@
+//! Startup self-check of what CrewHub needs outside its own binary.
+//!
+//! Many "CrewHub is broken" reports come down to a missing tray badge icon,
+//! a backend sidecar that was not installed, `crewhub://` links opening an
+//! older copy, or an autostart entry still pointing at a moved executable.
+//! Shortly after launch these are checked once and any problems logged and
+//! emitted as `health-report`; the settings window fetches a fresh report
+//! with `get_health_report` and fixes what it can with `repair_health_issue`.
+//! Missing resources and an unwritable data directory can't be repaired from
+//! inside the app and only ask for a reinstall or a look at permissions.
+
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Give the sidecar and the scheme registration a moment before checking.
+const STARTUP_DELAY: Duration = Duration::from_secs(10);
+
+/// Resources loaded from `icons/` in the resource dir (`bundle.resources`).
+const ICONS: &[&str] = &["tray-icon.png"];
+
+const ICONS_ISSUE: &str = "icons";
+const DATA_DIR_ISSUE: &str = "dataDir";
+const BACKEND_ISSUE: &str = "backend";
+const LINK_HANDLER_ISSUE: &str = "linkHandler";
+const AUTOSTART_ISSUE: &str = "autostart";
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// CrewHub works, but something around it doesn't (links, autostart).
+    Warning,
+    /// Part of CrewHub itself doesn't work.
+    Error,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthIssue {
+    id: &'static str,
+    severity: Severity,
+    message: String,
+    /// Whether `repair_health_issue` can fix it.
+    repairable: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    /// Unix time of the check, in milliseconds.
+    checked_at: i64,
+    issues: Vec<HealthIssue>,
+}
+
+fn issue(id: &'static str, severity: Severity, message: String, repairable: bool) -> HealthIssue {
+    HealthIssue {
+        id,
+        severity,
+        message,
+        repairable,
+    }
+}
+
+fn check_icons(app: &AppHandle) -> Option<HealthIssue> {
+    let dir = match app.path().resource_dir() {
+        Ok(dir) => dir.join("icons"),
+        Err(e) => return Some(issue(ICONS_ISSUE, Severity::Error, e.to_string(), false)),
+    };
+    let broken: Vec<&str> = ICONS
+        .iter()
+        .copied()
+        .filter(|name| crate::tray_updates::load_icon(&dir.join(name)).is_err())
+        .collect();
+    (!broken.is_empty()).then(|| {
+        issue(
+            ICONS_ISSUE,
+            Severity::Error,
+            format!(
+                "Missing or unreadable icons in {}: {}. Reinstall CrewHub to restore them.",
+                dir.display(),
+                broken.join(", ")
+            ),
+            false,
+        )
+    })
+}
+
+fn check_data_dir(app: &AppHandle) -> Option<HealthIssue> {
+    let result = crate::store::data_path(app, ".health-check").and_then(|path| {
+        std::fs::write(&path, b"ok").map_err(|e| format!("{}: {}", path.display(), e))?;
+        let _ = std::fs::remove_file(&path);
+        Ok(())
+    });
+    result.err().map(|e| {
+        issue(
+            DATA_DIR_ISSUE,
+            Severity::Error,
+            format!("Settings can't be saved: {}", e),
+            false,
+        )
+    })
+}
+
+fn check_backend(app: &AppHandle) -> Option<HealthIssue> {
+    crate::backend_process::problem(app)
+        .map(|problem| issue(BACKEND_ISSUE, Severity::Error, problem, true))
+}
+
+fn check_link_handler(app: &AppHandle) -> Option<HealthIssue> {
+    match crate::url_scheme::is_registered(app) {
+        Ok(true) => None,
+        Ok(false) => Some(issue(
+            LINK_HANDLER_ISSUE,
+            Severity::Warning,
+            "crewhub:// links don't open this copy of CrewHub".to_string(),
+            true,
+        )),
+        Err(e) => Some(issue(
+            LINK_HANDLER_ISSUE,
+            Severity::Warning,
+            format!("Couldn't check the crewhub:// link handler: {}", e),
+            true,
+        )),
+    }
+}
+
+fn check_autostart(app: &AppHandle) -> Option<HealthIssue> {
+    match crate::autostart::is_stale(app) {
+        Ok(false) => None,
+        Ok(true) => Some(issue(
+            AUTOSTART_ISSUE,
+            Severity::Warning,
+            "The login item starts a different CrewHub executable".to_string(),
+            true,
+        )),
+        Err(e) => Some(issue(
+            AUTOSTART_ISSUE,
+            Severity::Warning,
+            format!("Couldn't check the login item: {}", e),
+            true,
+        )),
+    }
+}
+
+/// Run every check (blocking: some ask the OS through helper processes).
+pub fn report(app: &AppHandle) -> HealthReport {
+    let checks: [fn(&AppHandle) -> Option<HealthIssue>; 5] = [
+        check_icons,
+        check_data_dir,
+        check_backend,
+        check_link_handler,
+        check_autostart,
+    ];
+    HealthReport {
+        checked_at: chrono::Utc::now().timestamp_millis(),
+        issues: checks.iter().filter_map(|check| check(app)).collect(),
+    }
+}
+
+/// Check once, shortly after launch, and log and emit what is wrong.
+pub fn init(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(STARTUP_DELAY);
+        let report = report(&app);
+        if report.issues.is_empty() {
+            return;
+        }
+        for issue in &report.issues {
+            eprintln!("[CrewHub] Self-check: {}: {}", issue.id, issue.message);
+        }
+        let _ = app.emit("health-report", &report);
+    });
+}
+
+fn repair(app: &AppHandle, id: &str) -> Result<(), String> {
+    match id {
+        BACKEND_ISSUE => crate::backend_process::restart(app).map(|_| ()),
+        LINK_HANDLER_ISSUE => crate::url_scheme::repair(app).map(|_| ()),
+        AUTOSTART_ISSUE => crate::autostart::repair(app),
+        ICONS_ISSUE | DATA_DIR_ISSUE => {
+            Err("This can't be repaired from within CrewHub".to_string())
+        }
+        _ => Err(format!("Unknown health issue: {}", id)),
+    }
+}
+
+/// Tauri command: check resources, the backend sidecar, link handler and autostart entry.
+/// Called from the settings window via `invoke('get_health_report')`.
+#[tauri::command]
+pub async fn get_health_report(app: AppHandle) -> Result<HealthReport, String> {
+    tauri::async_runtime::spawn_blocking(move || report(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command: fix one issue from the report, then check again.
+/// Called from the settings window via `invoke('repair_health_issue', { id })`.
+#[tauri::command]
+pub async fn repair_health_issue(id: String, app: AppHandle) -> Result<HealthReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        repair(&app, &id)?;
+        println!("[CrewHub] Repaired {}", id);
+        Ok(report(&app))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,178 @@
+//! Hot-corner activation.
+//!
+//! Opt-in: parking the mouse in the chosen screen corner (of any monitor)
+//! for a moment opens the chat window or shows the HUD. A background thread
+//! polls the global cursor position; it sleeps longer on battery
+//! ([`power::interval`]), stops while the system suspends, and only re-arms
+//! once the cursor has left the corner. Wayland doesn't report the pointer
+//! outside our own windows, so the hot corner is unavailable there.
+
+use crate::hud::{self, Corner};
+use crate::{power, store, wayland};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Monitor, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "hot-corner.json";
+
+/// Cursor poll interval (stretched on battery).
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Poll interval while the hot corner is off.
+const IDLE_INTERVAL: Duration = Duration::from_secs(1);
+/// How often the monitor layout is re-read.
+const MONITOR_REFRESH: Duration = Duration::from_secs(5);
+/// Size of the corner square, in logical pixels.
+const CORNER_SIZE: f64 = 4.0;
+
+static CONFIG: Mutex<Option<HotCornerConfig>> = Mutex::new(None);
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotCornerAction {
+    Chat,
+    Hud,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HotCornerConfig {
+    pub enabled: bool,
+    pub corner: Corner,
+    /// How long the cursor has to rest in the corner, in milliseconds.
+    pub delay_ms: u64,
+    pub action: HotCornerAction,
+}
+
+impl Default for HotCornerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            corner: Corner::BottomRight,
+            delay_ms: 300,
+            action: HotCornerAction::Chat,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotCornerStatus {
+    config: HotCornerConfig,
+    /// The cursor can be tracked in this session (not on Wayland).
+    available: bool,
+}
+
+fn config() -> HotCornerConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+/// Load the config and start the cursor poller (unless on Wayland).
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let loaded: HotCornerConfig = store::load_json(app, CONFIG_FILE);
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = Some(loaded);
+    }
+    if wayland::is_wayland() {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || watch(&app));
+}
+
+/// Whether `(x, y)` (physical pixels) lies in `corner` of `monitor`.
+fn in_corner(monitor: &Monitor, corner: Corner, x: f64, y: f64) -> bool {
+    let position = monitor.position();
+    let size = monitor.size();
+    let reach = CORNER_SIZE * monitor.scale_factor();
+    let (left, top) = (position.x as f64, position.y as f64);
+    let (right, bottom) = (left + size.width as f64, top + size.height as f64);
+    if x < left || x >= right || y < top || y >= bottom {
+        return false;
+    }
+    let near_left = x < left + reach;
+    let near_right = x >= right - reach;
+    let near_top = y < top + reach;
+    let near_bottom = y >= bottom - reach;
+    match corner {
+        Corner::TopLeft => near_top && near_left,
+        Corner::TopRight => near_top && near_right,
+        Corner::BottomLeft => near_bottom && near_left,
+        Corner::BottomRight => near_bottom && near_right,
+    }
+}
+
+fn watch<R: Runtime>(app: &AppHandle<R>) {
+    let mut monitors: Vec<Monitor> = Vec::new();
+    let mut monitors_read: Option<Instant> = None;
+    // When the cursor entered the corner; `None` once fired until it leaves.
+    let mut entered: Option<Option<Instant>> = None;
+    loop {
+        let config = config();
+        if !config.enabled || power::is_suspended() {
+            entered = None;
+            std::thread::sleep(IDLE_INTERVAL);
+            continue;
+        }
+        std::thread::sleep(power::interval(app, POLL_INTERVAL));
+
+        if monitors_read.is_none_or(|read| read.elapsed() >= MONITOR_REFRESH) {
+            monitors = app.available_monitors().unwrap_or_default();
+            monitors_read = Some(Instant::now());
+        }
+        let Ok(cursor) = app.cursor_position() else {
+            continue;
+        };
+        let inside = monitors
+            .iter()
+            .any(|monitor| in_corner(monitor, config.corner, cursor.x, cursor.y));
+        entered = match (inside, entered) {
+            (false, _) => None,
+            (true, None) => Some(Some(Instant::now())),
+            (true, Some(Some(since)))
+                if since.elapsed() >= Duration::from_millis(config.delay_ms) =>
+            {
+                activate(app, config.action);
+                Some(None)
+            }
+            (true, waiting) => waiting,
+        };
+    }
+}
+
+fn activate<R: Runtime>(app: &AppHandle<R>, action: HotCornerAction) {
+    match action {
+        HotCornerAction::Chat => crate::open_or_focus_chat(app),
+        HotCornerAction::Hud => {
+            if let Err(e) = hud::set_visible(app, true) {
+                eprintln!("[CrewHub] {}", e);
+            }
+        }
+    }
+}
+
+/// Tauri command: hot-corner config and whether it works in this session.
+/// Called from the settings window via `invoke('get_hot_corner')`.
+#[tauri::command]
+pub fn get_hot_corner() -> HotCornerStatus {
+    HotCornerStatus {
+        config: config(),
+        available: !wayland::is_wayland(),
+    }
+}
+
+/// Tauri command: change the hot-corner config (takes effect on the next poll).
+/// Called from the settings window via `invoke('set_hot_corner', { config })`.
+#[tauri::command]
+pub fn set_hot_corner(config: HotCornerConfig, app: AppHandle) -> Result<HotCornerStatus, String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config);
+    }
+    Ok(get_hot_corner())
+}
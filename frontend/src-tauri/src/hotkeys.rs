@@ -0,0 +1,598 @@
+//! Global hotkeys for quick actions, the window switcher and window toggles.
+//!
+//! How a hotkey reaches CrewHub depends on the desktop, and the settings
+//! window shows which [`Mechanism`] is in use (`get_hotkey_status`):
+//! - Windows and macOS register through the global-shortcut plugin
+//!   (`RegisterHotKey` / Carbon hot keys).
+//! - X11 grabs the keys on the root window with `XGrabKey`. A grab that
+//!   another client already holds fails with `BadAccess`, which is reported
+//!   as a conflict for that binding instead of silently never firing.
+//! - Wayland compositors don't allow clients to grab keys (XWayland grabs
+//!   only fire while an X11 window is focused). Where the desktop has the
+//!   GlobalShortcuts portal (also used inside Flatpak/Snap, see
+//!   [`crate::portal`]) the bindings go through it; the desktop may ask the
+//!   user to confirm or choose another trigger. Otherwise nothing is
+//!   registered and each binding carries a command line to add as a custom
+//!   shortcut in the desktop's keyboard settings (see [`crate::launch`]).
+//!
+//! Bindings come from [`QuickAction::hotkey`], the switcher config
+//! ([`crate::switcher`]), window shortcuts ([`crate::window_shortcuts`]) and
+//! layout profiles ([`crate::layout_profiles`]);
+//! [`sync`] re-registers them whenever any of them changes
+//! and emits `hotkeys-changed`.
+
+use crate::capabilities::{self, Capability};
+use crate::quick_actions::{self, QuickAction};
+use crate::{i18n, layout_profiles, switcher, window_shortcuts};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Serializes [`sync`] runs (each replaces all registrations).
+static SYNC: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Mechanism {
+    /// OS hotkey API through the global-shortcut plugin.
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    Native,
+    /// Key grabs on the X11 root window.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    X11Grab,
+    /// xdg-desktop-portal GlobalShortcuts (Wayland, sandboxes).
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    Portal,
+    /// Custom shortcuts configured in the desktop environment.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    DesktopShortcuts,
+    /// No display connection (see `detail`).
+    #[cfg_attr(any(target_os = "windows", target_os = "macos"), allow(dead_code))]
+    Unavailable,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Binding {
+    pub action_id: String,
+    pub name: String,
+    pub accelerator: String,
+    pub registered: bool,
+    /// Why the binding is not active (taken by another app, used twice, unparsable).
+    pub conflict: Option<String>,
+    /// Command line for a desktop custom shortcut ([`Mechanism::DesktopShortcuts`]).
+    pub command: Option<String>,
+    /// Trigger the desktop actually assigned ([`Mechanism::Portal`]).
+    pub trigger: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyStatus {
+    pub mechanism: Mechanism,
+    pub detail: Option<String>,
+    pub bindings: Vec<Binding>,
+}
+
+static STATUS: Mutex<Option<HotkeyStatus>> = Mutex::new(None);
+
+/// Register the hotkey backend and bind the current quick actions.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    platform::init(app);
+    sync(app, &quick_actions::all(app));
+}
+
+/// Re-register all bindings (call after quick actions change). Runs in the
+/// background: a portal may wait for the user to confirm the shortcuts.
+pub fn sync<R: Runtime>(app: &AppHandle<R>, actions: &[QuickAction]) {
+    let app = app.clone();
+    let actions = actions.to_vec();
+    std::thread::spawn(move || {
+        let _running = SYNC.lock().unwrap_or_else(|e| e.into_inner());
+        apply(&app, &actions);
+    });
+}
+
+fn apply<R: Runtime>(app: &AppHandle<R>, actions: &[QuickAction]) {
+    let switcher = (
+        switcher::HOTKEY_ID.to_string(),
+        i18n::t("switcher.hotkeyName"),
+        switcher::config(app).hotkey,
+    );
+    let profiles = layout_profiles::all(app).into_iter().map(|p| {
+        let id = format!("{}{}", layout_profiles::MENU_ID_PREFIX, p.id);
+        (id, p.name, p.hotkey)
+    });
+    let wanted = std::iter::once(switcher)
+        .chain(window_shortcuts::bindings(app))
+        .chain(
+            actions
+                .iter()
+                .map(|a| (a.id.clone(), a.name.clone(), a.hotkey.clone())),
+        )
+        .chain(profiles)
+        .filter_map(|(id, name, hotkey)| {
+            let accelerator = hotkey?.trim().to_string();
+            (!accelerator.is_empty()).then_some((id, name, accelerator))
+        });
+
+    let mut seen: HashMap<String, String> = HashMap::new();
+    let mut bindings = Vec::new();
+    for (action_id, name, accelerator) in wanted {
+        let key = accelerator.to_lowercase().replace(' ', "");
+        let conflict = seen
+            .get(&key)
+            .map(|other| format!("Also assigned to \"{}\"", other));
+        seen.entry(key).or_insert_with(|| name.clone());
+        bindings.push(Binding {
+            action_id,
+            name,
+            accelerator,
+            registered: false,
+            conflict,
+            command: None,
+            trigger: None,
+        });
+    }
+
+    let wanted: Vec<&Binding> = bindings.iter().filter(|b| b.conflict.is_none()).collect();
+    let (mechanism, detail, results) = platform::register(app, &wanted);
+    for binding in bindings.iter_mut().filter(|b| b.conflict.is_none()) {
+        match results.get(&binding.action_id) {
+            Some(Ok(trigger)) => {
+                binding.registered = true;
+                binding.trigger = trigger.clone();
+            }
+            Some(Err(e)) => binding.conflict = Some(e.clone()),
+            None => {}
+        }
+        if matches!(mechanism, Mechanism::DesktopShortcuts) {
+            binding.command = Some(desktop_command(&binding.action_id));
+        }
+    }
+    for binding in bindings.iter().filter(|b| b.conflict.is_some()) {
+        eprintln!(
+            "[CrewHub] Hotkey {} for '{}' not bound: {}",
+            binding.accelerator,
+            binding.name,
+            binding.conflict.as_deref().unwrap_or_default()
+        );
+    }
+
+    let available = match mechanism {
+        Mechanism::Unavailable => Err(detail
+            .clone()
+            .unwrap_or_else(|| "No global hotkey mechanism on this desktop".to_string())),
+        Mechanism::DesktopShortcuts => Err(detail.clone().unwrap_or_default()),
+        _ => Ok(()),
+    };
+    capabilities::record(app, Capability::GlobalHotkeys, available);
+
+    let status = HotkeyStatus {
+        mechanism,
+        detail,
+        bindings,
+    };
+    if let Ok(mut current) = STATUS.lock() {
+        *current = Some(status.clone());
+    }
+    let _ = app.emit("hotkeys-changed", &status);
+}
+
+/// A bound hotkey fired.
+fn fire<R: Runtime>(app: &AppHandle<R>, action_id: &str) {
+    let result = if action_id == switcher::HOTKEY_ID {
+        switcher::toggle(app);
+        Ok(())
+    } else if let Some(window) = action_id.strip_prefix(window_shortcuts::HOTKEY_PREFIX) {
+        window_shortcuts::toggle(app, window)
+    } else if let Some(id) = action_id.strip_prefix(layout_profiles::MENU_ID_PREFIX) {
+        layout_profiles::apply(app, id)
+    } else {
+        quick_actions::trigger(app, action_id)
+    };
+    if let Err(e) = result {
+        eprintln!("[CrewHub] Hotkey action failed: {}", e);
+    }
+}
+
+/// Shell command that runs a quick action (or opens the switcher, toggles a
+/// window or applies a layout profile) in the running instance.
+fn desktop_command(action_id: &str) -> String {
+    let exe = std::env::current_exe()
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "crewhub".to_string());
+    // The path inside a sandbox means nothing to the host's shortcut settings.
+    let launcher = match (std::env::var("FLATPAK_ID"), std::env::var("SNAP_NAME")) {
+        (Ok(id), _) => vec!["flatpak".to_string(), "run".to_string(), id],
+        (_, Ok(name)) => vec!["snap".to_string(), "run".to_string(), name],
+        _ => vec![exe],
+    };
+    launcher
+        .into_iter()
+        .chain(if action_id == switcher::HOTKEY_ID {
+            crate::launch::action_args("open-switcher", &[])
+        } else if let Some(window) = action_id.strip_prefix(window_shortcuts::HOTKEY_PREFIX) {
+            crate::launch::action_args("toggle-window", &[("id", window)])
+        } else if let Some(id) = action_id.strip_prefix(layout_profiles::MENU_ID_PREFIX) {
+            crate::launch::action_args("apply-layout", &[("id", id)])
+        } else {
+            crate::launch::action_args("quick-action", &[("id", action_id)])
+        })
+        .map(|arg| shell_quote(&arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn shell_quote(arg: &str) -> String {
+    if arg
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_./=:".contains(c))
+    {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Per action id: the trigger the desktop assigned (if it reports one), or why binding failed.
+type Results = HashMap<String, Result<Option<String>, String>>;
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+mod platform {
+    use super::{Binding, Mechanism, Results};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use tauri::{AppHandle, Runtime};
+    use tauri_plugin_global_shortcut::{Builder, GlobalShortcutExt, Shortcut, ShortcutState};
+
+    /// Registered shortcut id → quick action id.
+    static BOUND: Mutex<Option<HashMap<u32, String>>> = Mutex::new(None);
+
+    pub fn init<R: Runtime>(app: &AppHandle<R>) {
+        let plugin = Builder::new()
+            .with_handler(|app, shortcut, event| {
+                if event.state != ShortcutState::Pressed {
+                    return;
+                }
+                let action = BOUND
+                    .lock()
+                    .ok()
+                    .and_then(|b| b.as_ref()?.get(&shortcut.id()).cloned());
+                if let Some(action) = action {
+                    super::fire(app, &action);
+                }
+            })
+            .build();
+        if let Err(e) = app.plugin(plugin) {
+            eprintln!("[CrewHub] Global shortcut plugin failed: {}", e);
+        }
+    }
+
+    pub fn register<R: Runtime>(
+        app: &AppHandle<R>,
+        wanted: &[&Binding],
+    ) -> (Mechanism, Option<String>, Results) {
+        let shortcuts = app.global_shortcut();
+        let _ = shortcuts.unregister_all();
+        let mut bound = HashMap::new();
+        let mut results = HashMap::new();
+        for binding in wanted {
+            let result = binding
+                .accelerator
+                .parse::<Shortcut>()
+                .map_err(|e| format!("Invalid hotkey: {}", e))
+                .and_then(|shortcut| {
+                    shortcuts
+                        .register(shortcut)
+                        .map(|()| {
+                            bound.insert(shortcut.id(), binding.action_id.clone());
+                            None
+                        })
+                        .map_err(|e| format!("In use by another application ({})", e))
+                });
+            results.insert(binding.action_id.clone(), result);
+        }
+        if let Ok(mut current) = BOUND.lock() {
+            *current = Some(bound);
+        }
+        (Mechanism::Native, None, results)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{Binding, Mechanism, Results};
+    use crate::portal;
+    use std::collections::HashMap;
+    use std::sync::{Mutex, OnceLock};
+    use tauri::{AppHandle, Runtime};
+    use x11rb::connection::Connection;
+    use x11rb::errors::ReplyError;
+    use x11rb::protocol::xproto::{ConnectionExt, GrabMode, ModMask, Window};
+    use x11rb::protocol::{ErrorKind, Event};
+    use x11rb::rust_connection::RustConnection;
+
+    /// Lock modifiers that must not affect matching (Caps Lock, Num Lock).
+    const IGNORED: [u16; 4] = [0, 2, 16, 18];
+
+    struct X11 {
+        conn: RustConnection,
+        root: Window,
+        /// (keycode, modifiers) → quick action id.
+        bound: Mutex<HashMap<(u8, u16), String>>,
+    }
+
+    static X11: OnceLock<Result<X11, String>> = OnceLock::new();
+
+    pub fn init<R: Runtime>(app: &AppHandle<R>) {
+        if crate::wayland::is_wayland() {
+            return;
+        }
+        let x11 = X11.get_or_init(|| {
+            let (conn, screen) = RustConnection::connect(None).map_err(|e| e.to_string())?;
+            let root = conn.setup().roots[screen].root;
+            Ok(X11 {
+                conn,
+                root,
+                bound: Mutex::new(HashMap::new()),
+            })
+        });
+        match x11 {
+            Ok(x11) => {
+                let app = app.clone();
+                std::thread::spawn(move || listen(&app, x11));
+            }
+            Err(e) => eprintln!("[CrewHub] X11 hotkeys unavailable: {}", e),
+        }
+    }
+
+    fn listen<R: Runtime>(app: &AppHandle<R>, x11: &X11) {
+        while let Ok(event) = x11.conn.wait_for_event() {
+            let Event::KeyPress(press) = event else {
+                continue;
+            };
+            let state = u16::from(press.state) & !(2 | 16) & 0xff;
+            let action = x11
+                .bound
+                .lock()
+                .ok()
+                .and_then(|b| b.get(&(press.detail, state)).cloned());
+            if let Some(action) = action {
+                super::fire(app, &action);
+            }
+        }
+    }
+
+    pub fn register<R: Runtime>(
+        app: &AppHandle<R>,
+        wanted: &[&Binding],
+    ) -> (Mechanism, Option<String>, Results) {
+        let wayland = crate::wayland::is_wayland();
+        if (wayland || portal::sandbox().is_some()) && portal::status().global_shortcuts {
+            return register_portal(app, wanted);
+        }
+        if wayland {
+            let detail = "Wayland doesn't let apps grab global keys. Add the commands below \
+                          as custom shortcuts in your desktop's keyboard settings.";
+            return (
+                Mechanism::DesktopShortcuts,
+                Some(detail.to_string()),
+                HashMap::new(),
+            );
+        }
+        let x11 = match X11.get() {
+            Some(Ok(x11)) => x11,
+            Some(Err(e)) => return (Mechanism::Unavailable, Some(e.clone()), HashMap::new()),
+            None => {
+                let detail = "No X11 display".to_string();
+                return (Mechanism::Unavailable, Some(detail), HashMap::new());
+            }
+        };
+
+        let mut bound = x11.bound.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = x11
+            .conn
+            .ungrab_key(0u8, x11.root, ModMask::ANY)
+            .map(|c| c.ignore_error());
+        bound.clear();
+
+        let mut results = HashMap::new();
+        for binding in wanted {
+            let result = parse(&binding.accelerator).and_then(|(mods, keysym, _)| {
+                let keycode = keycode_for(&x11.conn, keysym).ok_or_else(|| {
+                    format!("No key for {} on this keyboard layout", binding.accelerator)
+                })?;
+                grab(x11, keycode, mods)?;
+                bound.insert((keycode, mods), binding.action_id.clone());
+                Ok(None)
+            });
+            results.insert(binding.action_id.clone(), result);
+        }
+        let _ = x11.conn.flush();
+        (Mechanism::X11Grab, None, results)
+    }
+
+    fn register_portal<R: Runtime>(
+        app: &AppHandle<R>,
+        wanted: &[&Binding],
+    ) -> (Mechanism, Option<String>, Results) {
+        let mut results = HashMap::new();
+        let mut shortcuts = Vec::new();
+        for binding in wanted {
+            match parse(&binding.accelerator) {
+                Ok((_, _, trigger)) => shortcuts.push(portal::Shortcut {
+                    id: binding.action_id.clone(),
+                    description: binding.name.clone(),
+                    preferred_trigger: trigger,
+                }),
+                Err(e) => {
+                    results.insert(binding.action_id.clone(), Err(e));
+                }
+            }
+        }
+        let ids: Vec<String> = shortcuts.iter().map(|s| s.id.clone()).collect();
+        let app = app.clone();
+        match portal::bind_shortcuts(shortcuts, move |id| super::fire(&app, id)) {
+            Ok(mut bound) => {
+                for id in ids {
+                    let result = bound
+                        .remove(&id)
+                        .map(|trigger| Some(trigger).filter(|t| !t.is_empty()))
+                        .ok_or_else(|| "Not assigned by the desktop".to_string());
+                    results.insert(id, result);
+                }
+                (Mechanism::Portal, None, results)
+            }
+            Err(e) => {
+                for id in ids {
+                    results.insert(id, Err(e.clone()));
+                }
+                (Mechanism::Portal, Some(e), results)
+            }
+        }
+    }
+
+    /// Grab a key combination in every lock state; undo partial grabs on conflict.
+    fn grab(x11: &X11, keycode: u8, mods: u16) -> Result<(), String> {
+        for (i, lock) in IGNORED.iter().enumerate() {
+            let mask = ModMask::from(mods | lock);
+            let checked = x11
+                .conn
+                .grab_key(
+                    false,
+                    x11.root,
+                    mask,
+                    keycode,
+                    GrabMode::ASYNC,
+                    GrabMode::ASYNC,
+                )
+                .map_err(|e| e.to_string())?
+                .check();
+            if let Err(e) = checked {
+                for lock in &IGNORED[..i] {
+                    let mask = ModMask::from(mods | lock);
+                    let _ = x11
+                        .conn
+                        .ungrab_key(keycode, x11.root, mask)
+                        .map(|c| c.ignore_error());
+                }
+                return Err(match e {
+                    ReplyError::X11Error(err) if err.error_kind == ErrorKind::Access => {
+                        "In use by another application".to_string()
+                    }
+                    other => other.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Named keys: accelerator names, keysym, keysym name (portal triggers).
+    const NAMED_KEYS: &[(&[&str], u32, &str)] = &[
+        (&["space"], 0x20, "space"),
+        (&["enter", "return"], 0xff0d, "Return"),
+        (&["tab"], 0xff09, "Tab"),
+        (&["escape", "esc"], 0xff1b, "Escape"),
+        (&["backspace"], 0xff08, "BackSpace"),
+        (&["delete"], 0xffff, "Delete"),
+        (&["insert"], 0xff63, "Insert"),
+        (&["home"], 0xff50, "Home"),
+        (&["end"], 0xff57, "End"),
+        (&["pageup"], 0xff55, "Page_Up"),
+        (&["pagedown"], 0xff56, "Page_Down"),
+        (&["left", "arrowleft"], 0xff51, "Left"),
+        (&["up", "arrowup"], 0xff52, "Up"),
+        (&["right", "arrowright"], 0xff53, "Right"),
+        (&["down", "arrowdown"], 0xff54, "Down"),
+    ];
+
+    /// Accelerator (`CmdOrCtrl+Shift+K`) → (X11 modifier mask, keysym,
+    /// portal trigger such as `CTRL+SHIFT+k`).
+    fn parse(accelerator: &str) -> Result<(u16, u32, String), String> {
+        let invalid = || format!("Invalid hotkey: {}", accelerator);
+        let mut mods = 0u16;
+        let mut trigger = Vec::new();
+        let mut key = None;
+        for part in accelerator.split('+').map(str::trim) {
+            let (mask, name) = match part.to_lowercase().as_str() {
+                "shift" => (ModMask::SHIFT, "SHIFT"),
+                "ctrl" | "control" | "cmdorctrl" | "commandorcontrol" | "cmdorcontrol" => {
+                    (ModMask::CONTROL, "CTRL")
+                }
+                "alt" | "option" => (ModMask::M1, "ALT"),
+                "super" | "meta" | "cmd" | "command" => (ModMask::M4, "LOGO"),
+                other => {
+                    key = Some(key_for(other).ok_or_else(invalid)?);
+                    continue;
+                }
+            };
+            mods |= u16::from(mask);
+            trigger.push(name.to_string());
+        }
+        let (keysym, name) = key.ok_or_else(invalid)?;
+        trigger.push(name);
+        Ok((mods, keysym, trigger.join("+")))
+    }
+
+    /// Lowercase key name → (keysym, keysym name).
+    fn key_for(key: &str) -> Option<(u32, String)> {
+        let key = key
+            .strip_prefix("key")
+            .or_else(|| key.strip_prefix("digit"))
+            .filter(|rest| rest.len() == 1)
+            .unwrap_or(key);
+        let mut chars = key.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return c.is_ascii_graphic().then(|| (c as u32, c.to_string()));
+        }
+        if let Some(n) = key.strip_prefix('f').and_then(|n| n.parse::<u32>().ok()) {
+            return (1..=24)
+                .contains(&n)
+                .then(|| (0xffbe + n - 1, format!("F{}", n)));
+        }
+        NAMED_KEYS
+            .iter()
+            .find(|(names, _, _)| names.contains(&key))
+            .map(|(_, keysym, name)| (*keysym, name.to_string()))
+    }
+
+    fn keycode_for(conn: &RustConnection, keysym: u32) -> Option<u8> {
+        let setup = conn.setup();
+        let (min, max) = (setup.min_keycode, setup.max_keycode);
+        let mapping = conn
+            .get_keyboard_mapping(min, max - min + 1)
+            .ok()?
+            .reply()
+            .ok()?;
+        let per = usize::from(mapping.keysyms_per_keycode).max(1);
+        mapping
+            .keysyms
+            .chunks(per)
+            .position(|syms| syms.contains(&keysym))
+            .map(|i| min + i as u8)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod platform {
+    use super::{Binding, Mechanism, Results};
+    use tauri::{AppHandle, Runtime};
+
+    pub fn init<R: Runtime>(_app: &AppHandle<R>) {}
+
+    pub fn register<R: Runtime>(
+        _app: &AppHandle<R>,
+        _wanted: &[&Binding],
+    ) -> (Mechanism, Option<String>, Results) {
+        (Mechanism::Unavailable, None, Results::new())
+    }
+}
+
+/// Tauri command: which hotkey mechanism is in use and the state of each binding.
+/// Called from the settings window via `invoke('get_hotkey_status')`.
+#[tauri::command]
+pub fn get_hotkey_status() -> Option<HotkeyStatus> {
+    STATUS.lock().ok().and_then(|s| s.clone())
+}
@@ -0,0 +1,288 @@
+//! Always-on-top HUD overlay with live agent activity.
+//!
+//! A small frameless window pinned to a screen corner that shows a compact
+//! ticker of agent states and current tasks. The window renders the presence
+//! snapshot ([`crate::presence`], `agent-states-changed` events); this module
+//! only owns the window itself: where it sits, whether clicks pass through it,
+//! and whether it is shown. Toggle it from the tray, the control surface
+//! (`toggle-hud`) or `invoke('toggle_hud')`. On Wayland the corner is applied
+//! through layer-shell where available; otherwise the compositor places the
+//! window (see [`crate::wayland`]).
+
+use crate::presence::{self, AgentPresence, AgentState};
+use crate::store;
+use crate::wayland::{self, Placement};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, Runtime, State, WebviewWindow, WebviewWindowBuilder,
+};
+
+/// Label for the HUD window.
+pub const HUD_WINDOW_LABEL: &str = "hud";
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "hud.json";
+
+/// Distance from the screen edges, in logical pixels.
+const MARGIN: f64 = 16.0;
+
+/// How long a flash shows a hidden HUD.
+const FLASH_DURATION: Duration = Duration::from_secs(6);
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Default for Corner {
+    /// Top right, mirrored to top left in right-to-left locales.
+    fn default() -> Self {
+        if crate::i18n::rtl() {
+            Corner::TopLeft
+        } else {
+            Corner::TopRight
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HudConfig {
+    pub corner: Corner,
+    /// Let mouse clicks pass through to the windows underneath.
+    pub click_through: bool,
+    /// Shown at startup (remembers the last toggle).
+    pub visible: bool,
+}
+
+impl Default for HudConfig {
+    fn default() -> Self {
+        Self {
+            corner: Corner::default(),
+            click_through: true,
+            visible: false,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HudSnapshot {
+    config: HudConfig,
+    /// How the corner is applied; with `compositor` the HUD should be draggable.
+    placement: Placement,
+    /// Agents that are not idle, blocked ones first.
+    agents: Vec<AgentPresence>,
+}
+
+/// App state: HUD placement and behaviour.
+pub struct HudState {
+    config: Mutex<HudConfig>,
+}
+
+/// Load the config and reopen the HUD if it was showing when the app quit.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: HudConfig = store::load_json(app, CONFIG_FILE);
+    let visible = config.visible;
+    app.manage(HudState {
+        config: Mutex::new(config),
+    });
+    if visible {
+        show(app);
+    }
+}
+
+fn hud_init_script() -> String {
+    format!(
+        "window.__TAURI_VIEW__ = 'hud'; {}",
+        crate::base_init(HUD_WINDOW_LABEL)
+    )
+}
+
+fn config<R: Runtime>(app: &AppHandle<R>) -> HudConfig {
+    app.try_state::<HudState>()
+        .and_then(|state| state.config.lock().ok().map(|c| c.clone()))
+        .unwrap_or_default()
+}
+
+/// Move the window into the configured corner of its monitor's work area.
+fn place<R: Runtime>(window: &WebviewWindow<R>, corner: Corner) -> Result<(), String> {
+    match wayland::placement() {
+        Placement::Absolute => {}
+        Placement::LayerShell => {
+            let top = matches!(corner, Corner::TopLeft | Corner::TopRight);
+            let left = matches!(corner, Corner::TopLeft | Corner::BottomLeft);
+            let target = window.clone();
+            return window
+                .run_on_main_thread(move || {
+                    if let Err(e) = wayland::anchor(&target, top, left, MARGIN as i32) {
+                        eprintln!("[CrewHub] Failed to anchor HUD: {}", e);
+                    }
+                })
+                .map_err(|e| e.to_string());
+        }
+        // Wayland ignores client positioning; the compositor decides.
+        Placement::Compositor => return Ok(()),
+    }
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .or_else(|| window.primary_monitor().ok().flatten())
+        .ok_or("No monitor found")?;
+    let area = monitor.work_area();
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let margin = (MARGIN * monitor.scale_factor()) as i32;
+    let left = area.position.x + margin;
+    let right = area.position.x + area.size.width as i32 - size.width as i32 - margin;
+    let top = area.position.y + margin;
+    let bottom = area.position.y + area.size.height as i32 - size.height as i32 - margin;
+    let (x, y) = match corner {
+        Corner::TopLeft => (left, top),
+        Corner::TopRight => (right, top),
+        Corner::BottomLeft => (left, bottom),
+        Corner::BottomRight => (right, bottom),
+    };
+    window
+        .set_position(PhysicalPosition::new(x, y))
+        .map_err(|e| e.to_string())
+}
+
+/// Apply placement and click-through to an existing HUD window.
+fn apply<R: Runtime>(window: &WebviewWindow<R>, config: &HudConfig) {
+    if let Err(e) = place(window, config.corner) {
+        eprintln!("[CrewHub] Failed to position HUD: {}", e);
+    }
+    let _ = window.set_ignore_cursor_events(config.click_through);
+}
+
+/// Show the HUD (320×140, frameless, always on top) without stealing focus.
+fn show<R: Runtime>(app: &AppHandle<R>) {
+    let config = config(app);
+    let window = match app.get_webview_window(HUD_WINDOW_LABEL) {
+        Some(window) => window,
+        None => {
+            let result =
+                WebviewWindowBuilder::new(app, HUD_WINDOW_LABEL, crate::view_url("view=hud"))
+                    .title("CrewHub HUD")
+                    .inner_size(320.0, 140.0)
+                    .resizable(false)
+                    .fullscreen(false)
+                    .decorations(false)
+                    .shadow(false)
+                    .always_on_top(true)
+                    .visible_on_all_workspaces(true)
+                    .skip_taskbar(true)
+                    .focused(false)
+                    .visible(false)
+                    .user_agent(&crate::request_headers::user_agent())
+                    .on_new_window(crate::external_links::new_window_handler(app))
+                    .initialization_script(hud_init_script())
+                    .build();
+            match result {
+                Ok(window) => window,
+                Err(e) => {
+                    eprintln!("[CrewHub] Failed to create HUD window: {}", e);
+                    return;
+                }
+            }
+        }
+    };
+    apply(&window, &config);
+    let _ = window.show();
+}
+
+fn is_visible<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.get_webview_window(HUD_WINDOW_LABEL)
+        .and_then(|window| window.is_visible().ok())
+        .unwrap_or(false)
+}
+
+pub fn set_visible<R: Runtime>(app: &AppHandle<R>, visible: bool) -> Result<(), String> {
+    if visible {
+        show(app);
+    } else if let Some(window) = app.get_webview_window(HUD_WINDOW_LABEL) {
+        window.hide().map_err(|e| e.to_string())?;
+    }
+    let state = app.state::<HudState>();
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.visible = visible;
+    store::save_json(app, CONFIG_FILE, &*config)
+}
+
+/// Show the HUD for a while (see [`crate::choreography`]); a hidden HUD hides
+/// again unless it was turned on in the meantime.
+pub fn flash<R: Runtime>(app: &AppHandle<R>) {
+    if is_visible(app) {
+        return;
+    }
+    show(app);
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(FLASH_DURATION);
+        if !config(&app).visible {
+            if let Some(window) = app.get_webview_window(HUD_WINDOW_LABEL) {
+                let _ = window.hide();
+            }
+        }
+    });
+}
+
+/// Show the HUD if hidden, hide it otherwise. Returns whether it is now shown.
+pub fn toggle<R: Runtime>(app: &AppHandle<R>) -> Result<bool, String> {
+    let visible = !is_visible(app);
+    set_visible(app, visible)?;
+    Ok(visible)
+}
+
+/// Tauri command: show or hide the HUD overlay; returns whether it is now shown.
+/// Called from the frontend via `invoke('toggle_hud')`.
+#[tauri::command]
+pub fn toggle_hud(app: AppHandle) -> Result<bool, String> {
+    toggle(&app)
+}
+
+/// Tauri command: HUD config plus the agents it should list.
+/// Called from the HUD window via `invoke('get_hud_snapshot')`.
+#[tauri::command]
+pub fn get_hud_snapshot(app: AppHandle) -> HudSnapshot {
+    let mut agents: Vec<AgentPresence> = presence::agents(&app)
+        .into_iter()
+        .filter(|agent| agent.state != AgentState::Idle)
+        .collect();
+    agents.sort_by_key(|agent| agent.state != AgentState::Blocked);
+    HudSnapshot {
+        config: config(&app),
+        placement: wayland::placement(),
+        agents,
+    }
+}
+
+/// Tauri command: change the HUD corner and/or click-through behaviour.
+/// Called from the frontend via `invoke('configure_hud', { corner, clickThrough })`.
+#[tauri::command]
+pub fn configure_hud(
+    corner: Option<Corner>,
+    click_through: Option<bool>,
+    app: AppHandle,
+    state: State<HudState>,
+) -> Result<HudConfig, String> {
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    if let Some(corner) = corner {
+        config.corner = corner;
+    }
+    if let Some(click_through) = click_through {
+        config.click_through = click_through;
+    }
+    store::save_json(&app, CONFIG_FILE, &*config)?;
+    if let Some(window) = app.get_webview_window(HUD_WINDOW_LABEL) {
+        apply(&window, &config);
+    }
+    Ok(config.clone())
+}
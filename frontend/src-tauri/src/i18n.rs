@@ -0,0 +1,302 @@
+//! Localized strings for native UI (tray menu, tooltip, dialogs, notifications).
+//!
+//! Catalogs are flat `key → string` JSON files in `locales/`, compiled into
+//! the binary. The language follows the OS locale unless the user picks one
+//! with `set_language` (persisted in `language.json`); missing keys fall back
+//! to English. Placeholders are written `{name}` and filled by [`tf`].
+//!
+//! The text direction follows the chosen language, or the OS locale when
+//! none is chosen, so Arabic, Hebrew and other right-to-left locales get
+//! mirrored menus ([`mirror_menu`]) and window defaults even while their
+//! strings fall back to English. Windows read it as `window.__CREWHUB_DIR__`.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{OnceLock, RwLock};
+use tauri::menu::{Menu, MenuItemKind};
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Persisted language choice (app data dir).
+const CONFIG_FILE: &str = "language.json";
+
+const FALLBACK: &str = "en";
+
+const CATALOG_SOURCES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.json")),
+    ("de", include_str!("../locales/de.json")),
+    ("es", include_str!("../locales/es.json")),
+    ("fr", include_str!("../locales/fr.json")),
+    ("nl", include_str!("../locales/nl.json")),
+];
+
+/// Primary language subtags written right to left.
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ps", "sd", "ug", "ur", "yi",
+];
+
+/// Right-to-left mark, prefixed to menu labels in RTL locales.
+const RLM: char = '\u{200F}';
+
+static CATALOGS: OnceLock<HashMap<&'static str, HashMap<String, String>>> = OnceLock::new();
+
+/// Active language code (one of the catalog keys).
+static LANGUAGE: RwLock<String> = RwLock::new(String::new());
+
+/// Whether the active locale is written right to left.
+static RTL: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Ltr,
+    Rtl,
+}
+
+impl Direction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Direction::Ltr => "ltr",
+            Direction::Rtl => "rtl",
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LanguageConfig {
+    /// Chosen language; `None` follows the OS locale.
+    pub language: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageStatus {
+    /// Active language.
+    language: String,
+    /// The user's explicit choice, if any.
+    chosen: Option<String>,
+    system_locale: Option<String>,
+    available: Vec<&'static str>,
+    /// Text direction of the chosen language or OS locale.
+    direction: Direction,
+}
+
+fn catalogs() -> &'static HashMap<&'static str, HashMap<String, String>> {
+    CATALOGS.get_or_init(|| {
+        CATALOG_SOURCES
+            .iter()
+            .map(|(code, source)| {
+                let catalog = serde_json::from_str(source).unwrap_or_else(|e| {
+                    eprintln!("[CrewHub] Invalid locale file {}.json: {}", code, e);
+                    HashMap::new()
+                });
+                (*code, catalog)
+            })
+            .collect()
+    })
+}
+
+/// Best catalog for a locale tag like `de-AT` or `pt_BR.UTF-8`.
+fn resolve(locale: &str) -> Option<&'static str> {
+    let lang = locale.split(['-', '_', '.']).next()?.to_ascii_lowercase();
+    CATALOG_SOURCES
+        .iter()
+        .map(|(code, _)| *code)
+        .find(|code| *code == lang)
+}
+
+fn is_rtl(locale: &str) -> bool {
+    locale
+        .split(['-', '_', '.'])
+        .next()
+        .is_some_and(|lang| RTL_LANGUAGES.contains(&lang.to_ascii_lowercase().as_str()))
+}
+
+fn apply(config: &LanguageConfig) {
+    let language = config
+        .language
+        .as_deref()
+        .and_then(resolve)
+        .or_else(|| sys_locale::get_locale().as_deref().and_then(resolve))
+        .unwrap_or(FALLBACK);
+    if let Ok(mut current) = LANGUAGE.write() {
+        *current = language.to_string();
+    }
+    let rtl = match config.language.as_deref() {
+        Some(chosen) => is_rtl(chosen),
+        None => sys_locale::get_locale().is_some_and(|locale| is_rtl(&locale)),
+    };
+    RTL.store(rtl, Ordering::Relaxed);
+}
+
+/// Pick the language before any menu or dialog is built.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: LanguageConfig = store::load_json(app, CONFIG_FILE);
+    apply(&config);
+}
+
+/// Active language code.
+pub fn language() -> String {
+    LANGUAGE
+        .read()
+        .ok()
+        .map(|l| l.clone())
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| FALLBACK.to_string())
+}
+
+/// Text direction of the active locale.
+pub fn direction() -> Direction {
+    if RTL.load(Ordering::Relaxed) {
+        Direction::Rtl
+    } else {
+        Direction::Ltr
+    }
+}
+
+/// Whether native UI should be laid out right to left.
+pub fn rtl() -> bool {
+    direction() == Direction::Rtl
+}
+
+/// JS fragment exposing the text direction as `window.__CREWHUB_DIR__`.
+pub fn init_script() -> String {
+    format!("window.__CREWHUB_DIR__ = '{}';", direction().as_str())
+}
+
+/// Mirror a freshly built menu for RTL locales: labels get a leading
+/// right-to-left mark so mixed text (emoji, names, counts) is ordered from
+/// the right, and on Windows items are right-aligned with submenus opening
+/// to the left. No-op for left-to-right locales.
+pub fn mirror_menu<R: Runtime>(menu: &Menu<R>) -> tauri::Result<()> {
+    if !rtl() {
+        return Ok(());
+    }
+    mark_items(&menu.items()?)?;
+    #[cfg(target_os = "windows")]
+    {
+        use tauri::menu::ContextMenu;
+        platform::mirror(menu.hpopupmenu()?);
+    }
+    Ok(())
+}
+
+fn mark(text: String) -> String {
+    if text.starts_with(RLM) {
+        text
+    } else {
+        format!("{}{}", RLM, text)
+    }
+}
+
+fn mark_items<R: Runtime>(items: &[MenuItemKind<R>]) -> tauri::Result<()> {
+    for item in items {
+        match item {
+            MenuItemKind::MenuItem(item) => item.set_text(mark(item.text()?))?,
+            MenuItemKind::Check(item) => item.set_text(mark(item.text()?))?,
+            MenuItemKind::Icon(item) => item.set_text(mark(item.text()?))?,
+            MenuItemKind::Submenu(submenu) => {
+                submenu.set_text(mark(submenu.text()?))?;
+                mark_items(&submenu.items()?)?;
+            }
+            MenuItemKind::Predefined(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Translate `key` (falls back to English, then to the key itself).
+pub fn t(key: &str) -> String {
+    let catalogs = catalogs();
+    catalogs
+        .get(language().as_str())
+        .and_then(|catalog| catalog.get(key))
+        .or_else(|| catalogs.get(FALLBACK).and_then(|catalog| catalog.get(key)))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Translate `key` and fill its `{name}` placeholders.
+pub fn tf(key: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(t(key), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// Tauri command: active language, the user's choice, and available languages.
+/// Called from the frontend via `invoke('get_language')`.
+#[tauri::command]
+pub fn get_language(app: AppHandle) -> LanguageStatus {
+    let config: LanguageConfig = store::load_json(&app, CONFIG_FILE);
+    LanguageStatus {
+        language: language(),
+        chosen: config.language,
+        system_locale: sys_locale::get_locale(),
+        available: CATALOG_SOURCES.iter().map(|(code, _)| *code).collect(),
+        direction: direction(),
+    }
+}
+
+/// Tauri command: switch the language (`null` follows the OS) and rebuild the tray live.
+/// Called from the frontend via `invoke('set_language', { language })`.
+#[tauri::command]
+pub fn set_language(language: Option<String>, app: AppHandle) -> Result<LanguageStatus, String> {
+    if let Some(code) = language.as_deref() {
+        if resolve(code).is_none() {
+            return Err(format!("Unsupported language: {}", code));
+        }
+    }
+    let config = LanguageConfig { language };
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    apply(&config);
+    crate::refresh_tray_menu(&app);
+    crate::refresh_tray_tooltip(&app);
+    #[cfg(target_os = "windows")]
+    {
+        crate::jump_list::refresh(&app);
+        crate::thumb_bar::refresh(&app);
+    }
+    #[cfg(target_os = "macos")]
+    {
+        crate::dock_menu::refresh(&app);
+        crate::app_menu::refresh(&app);
+    }
+    let _ = app.emit("language-changed", self::language());
+    Ok(get_language(app))
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetMenuItemCount, GetMenuItemInfoW, SetMenuItemInfoW, HMENU, MENUITEMINFOW,
+        MFT_RIGHTJUSTIFY, MFT_RIGHTORDER, MIIM_FTYPE, MIIM_SUBMENU,
+    };
+
+    /// Right-align every item of `hmenu` (an `HMENU`) and its submenus, and
+    /// open submenus to the left.
+    pub fn mirror(hmenu: isize) {
+        mirror_menu(HMENU(hmenu as *mut _));
+    }
+
+    fn mirror_menu(hmenu: HMENU) {
+        let count = unsafe { GetMenuItemCount(Some(hmenu)) };
+        for index in 0..count.max(0) as u32 {
+            let mut info = MENUITEMINFOW {
+                cbSize: std::mem::size_of::<MENUITEMINFOW>() as u32,
+                fMask: MIIM_FTYPE | MIIM_SUBMENU,
+                ..Default::default()
+            };
+            if unsafe { GetMenuItemInfoW(hmenu, index, true, &mut info) }.is_err() {
+                continue;
+            }
+            info.fMask = MIIM_FTYPE;
+            info.fType |= MFT_RIGHTORDER | MFT_RIGHTJUSTIFY;
+            let _ = unsafe { SetMenuItemInfoW(hmenu, index, true, &info) };
+            if !info.hSubMenu.is_invalid() {
+                mirror_menu(info.hSubMenu);
+            }
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! Deployment-specific additions to the init scripts of CrewHub windows.
+//!
+//! Self-hosted deployments want feature toggles, A/B switches or their
+//! analytics site id in the frontend without forking `base_init()`.
+//! `init-extensions.json` maps window labels to what is injected there:
+//!
+//! ```json
+//! { "windows": {
+//!     "*": { "flags": { "newComposer": true } },
+//!     "chat": { "snippets": [
+//!         { "template": "experiment", "params": { "name": "onboarding", "variant": "b" } }
+//!     ] },
+//!     "widget-*": { "flags": { "compact": true } } } }
+//! ```
+//!
+//! A key is a window label, a label prefix ending in `*`, or `*` for every
+//! window; more specific keys are applied later and win. `flags` end up
+//! merged in `window.__CREWHUB_FLAGS__`. `snippets` can't carry arbitrary
+//! code: each names one of the [`TEMPLATES`], whose parameters are validated
+//! by kind and inserted as JSON string literals. A file that doesn't
+//! validate is ignored as a whole. Flag changes reach open windows at once;
+//! snippets apply to windows created afterwards.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "init-extensions.json";
+
+/// Longest accepted `text` parameter.
+const MAX_TEXT: usize = 1024;
+
+static CONFIG: Mutex<Option<InitExtensionsConfig>> = Mutex::new(None);
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct InitExtensionsConfig {
+    /// Window label, `prefix*` or `*` → what to inject there.
+    pub windows: BTreeMap<String, WindowExtensions>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WindowExtensions {
+    /// Merged into `window.__CREWHUB_FLAGS__`.
+    pub flags: Map<String, Value>,
+    pub snippets: Vec<Snippet>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    /// Name of one of the [`TEMPLATES`].
+    pub template: String,
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ParamKind {
+    /// `https:` URL, or `http:` on this machine.
+    Url,
+    /// Letters, digits and `.`, `_`, `-`, `:` (up to 128).
+    Id,
+    /// Any text up to [`MAX_TEXT`] characters.
+    Text,
+}
+
+#[derive(Serialize)]
+pub struct Param {
+    name: &'static str,
+    kind: ParamKind,
+}
+
+#[derive(Serialize)]
+pub struct Template {
+    name: &'static str,
+    params: &'static [Param],
+    /// JavaScript with `{{param}}` placeholders.
+    #[serde(skip)]
+    script: &'static str,
+}
+
+/// Snippets a config may use. All parameters are required.
+const TEMPLATES: &[Template] = &[
+    Template {
+        name: "analytics",
+        params: &[
+            Param { name: "endpoint", kind: ParamKind::Url },
+            Param { name: "siteId", kind: ParamKind::Id },
+        ],
+        script: "window.__CREWHUB_ANALYTICS__ = { endpoint: {{endpoint}}, siteId: {{siteId}} };",
+    },
+    Template {
+        name: "experiment",
+        params: &[
+            Param { name: "name", kind: ParamKind::Id },
+            Param { name: "variant", kind: ParamKind::Id },
+        ],
+        script: "(window.__CREWHUB_EXPERIMENTS__ = window.__CREWHUB_EXPERIMENTS__ || {})[{{name}}] = {{variant}};",
+    },
+    Template {
+        name: "localStorage",
+        params: &[
+            Param { name: "key", kind: ParamKind::Id },
+            Param { name: "value", kind: ParamKind::Text },
+        ],
+        script: "try { localStorage.setItem({{key}}, {{value}}); } catch (e) {}",
+    },
+];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitExtensionsStatus {
+    config: InitExtensionsConfig,
+    templates: &'static [Template],
+}
+
+fn config() -> InitExtensionsConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let mut loaded: InitExtensionsConfig = store::load_json(app, CONFIG_FILE);
+    if let Err(e) = validate(&loaded) {
+        eprintln!("[CrewHub] Ignoring {}: {}", CONFIG_FILE, e);
+        loaded = InitExtensionsConfig::default();
+    }
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = Some(loaded);
+    }
+}
+
+fn is_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 128
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"._-:".contains(&b))
+}
+
+fn valid_param(kind: ParamKind, value: &str) -> bool {
+    match kind {
+        ParamKind::Url => url::Url::parse(value).is_ok_and(|url| {
+            url.scheme() == "https"
+                || (url.scheme() == "http"
+                    && matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]")))
+        }),
+        ParamKind::Id => is_id(value),
+        ParamKind::Text => value.chars().count() <= MAX_TEXT,
+    }
+}
+
+fn template(name: &str) -> Result<&'static Template, String> {
+    TEMPLATES
+        .iter()
+        .find(|template| template.name == name)
+        .ok_or_else(|| format!("Unknown template: {}", name))
+}
+
+fn validate_snippet(snippet: &Snippet) -> Result<(), String> {
+    let template = template(&snippet.template)?;
+    if let Some(name) = snippet.params.keys().find(|name| {
+        !template
+            .params
+            .iter()
+            .any(|param| param.name == name.as_str())
+    }) {
+        return Err(format!("{}: unknown parameter {}", template.name, name));
+    }
+    for param in template.params {
+        match snippet.params.get(param.name) {
+            None => {
+                return Err(format!(
+                    "{}: missing parameter {}",
+                    template.name, param.name
+                ))
+            }
+            Some(value) if !valid_param(param.kind, value) => {
+                return Err(format!("{}: invalid {}", template.name, param.name))
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+fn validate(config: &InitExtensionsConfig) -> Result<(), String> {
+    for (pattern, extensions) in &config.windows {
+        let label = pattern.strip_suffix('*').unwrap_or(pattern);
+        if pattern.is_empty() || label.contains('*') {
+            return Err(format!("Invalid window pattern: {}", pattern));
+        }
+        if let Some(name) = extensions.flags.keys().find(|name| !is_id(name)) {
+            return Err(format!("Invalid flag name: {}", name));
+        }
+        for snippet in &extensions.snippets {
+            validate_snippet(snippet).map_err(|e| format!("{}: {}", pattern, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// How closely `pattern` matches `label` (higher is more specific), if at all.
+fn specificity(pattern: &str, label: &str) -> Option<usize> {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => label.starts_with(prefix).then_some(prefix.len()),
+        None => (pattern == label).then_some(usize::MAX),
+    }
+}
+
+fn matching<'a>(config: &'a InitExtensionsConfig, label: &str) -> Vec<&'a WindowExtensions> {
+    let mut sections: Vec<(usize, &WindowExtensions)> = config
+        .windows
+        .iter()
+        .filter_map(|(pattern, extensions)| Some((specificity(pattern, label)?, extensions)))
+        .collect();
+    sections.sort_by_key(|(specificity, _)| *specificity);
+    sections
+        .into_iter()
+        .map(|(_, extensions)| extensions)
+        .collect()
+}
+
+fn flags_script(config: &InitExtensionsConfig, label: &str) -> String {
+    let mut flags = Map::new();
+    for extensions in matching(config, label) {
+        flags.extend(extensions.flags.clone());
+    }
+    format!("window.__CREWHUB_FLAGS__ = {};", Value::Object(flags))
+}
+
+fn render(snippet: &Snippet) -> Option<String> {
+    let template = template(&snippet.template).ok()?;
+    let mut script = template.script.to_string();
+    for param in template.params {
+        let value = serde_json::to_string(snippet.params.get(param.name)?).ok()?;
+        script = script.replace(&format!("{{{{{}}}}}", param.name), &value);
+    }
+    Some(script)
+}
+
+/// Init script part for the window `label`: its flags and snippets.
+pub fn init_script(label: &str) -> String {
+    let config = config();
+    let snippets: Vec<String> = matching(&config, label)
+        .into_iter()
+        .flat_map(|extensions| extensions.snippets.iter().filter_map(render))
+        .collect();
+    format!("{} {}", flags_script(&config, label), snippets.join(" "))
+}
+
+fn status() -> InitExtensionsStatus {
+    InitExtensionsStatus {
+        config: config(),
+        templates: TEMPLATES,
+    }
+}
+
+/// Tauri command: configured init-script extensions and the available templates.
+/// Called from the settings window via `invoke('get_init_extensions')`.
+#[tauri::command]
+pub fn get_init_extensions() -> InitExtensionsStatus {
+    status()
+}
+
+/// Tauri command: replace the init-script extensions; flags apply to open
+/// windows right away, snippets to windows opened afterwards.
+/// Called from the settings window via `invoke('set_init_extensions', { config })`.
+#[tauri::command]
+pub fn set_init_extensions(
+    config: InitExtensionsConfig,
+    app: AppHandle,
+) -> Result<InitExtensionsStatus, String> {
+    validate(&config)?;
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    for (label, window) in app.webview_windows() {
+        let _ = window.eval(flags_script(&config, &label));
+    }
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config);
+    }
+    Ok(status())
+}
@@ -0,0 +1,136 @@
+//! Windows taskbar jump list.
+//!
+//! Tasks (new quick prompt, Zen Mode, 3D world) plus "Pinned" and "Recent"
+//! categories mirrored from [`crate::pins`] and [`crate::recents`]. Every
+//! entry is a shell link back to this executable with `--action` arguments,
+//! which the running instance performs (see [`crate::launch`]). The list is
+//! rebuilt whenever pins, recents or the UI language change.
+
+use crate::{i18n, launch, pins, recents};
+use std::sync::Mutex;
+use tauri::{AppHandle, Runtime};
+use windows::core::{Interface, Result, HSTRING};
+use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+use windows::Win32::System::Com::StructuredStorage::PROPVARIANT;
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+};
+use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+use windows::Win32::UI::Shell::{
+    DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW, ShellLink,
+};
+
+/// Conversations shown per category.
+const MAX_CONVERSATIONS: usize = 5;
+
+/// Rebuilds run on their own threads; one at a time.
+static BUILDING: Mutex<()> = Mutex::new(());
+
+struct Entry {
+    title: String,
+    args: Vec<String>,
+}
+
+fn conversation_entry(conversation: &pins::ConversationRef) -> Entry {
+    Entry {
+        title: conversation.title.clone(),
+        args: launch::action_args(
+            "open-conversation",
+            &[
+                ("sessionKey", &conversation.session_key),
+                ("backendId", &conversation.backend_id),
+                ("title", &conversation.title),
+            ],
+        ),
+    }
+}
+
+/// Rebuild the jump list from the current pins and recents.
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let tasks = vec![
+        Entry {
+            title: i18n::t("jumpList.quickPrompt"),
+            args: launch::action_args("open-quick-prompt", &[]),
+        },
+        Entry {
+            title: i18n::t("jumpList.zen"),
+            args: launch::action_args("start-focus", &[]),
+        },
+        Entry {
+            title: i18n::t("jumpList.world"),
+            args: launch::action_args("open-world", &[]),
+        },
+    ];
+    let pinned: Vec<Entry> = pins::pinned(app)
+        .iter()
+        .take(MAX_CONVERSATIONS)
+        .map(|pin| conversation_entry(&pin.conversation))
+        .collect();
+    // Pinned conversations already have their own category.
+    let recent: Vec<Entry> = recents::recent(app)
+        .iter()
+        .filter(|recent| {
+            !pinned
+                .iter()
+                .any(|pin| pin.args == conversation_entry(&recent.conversation).args)
+        })
+        .take(MAX_CONVERSATIONS)
+        .map(|recent| conversation_entry(&recent.conversation))
+        .collect();
+    let categories = vec![
+        (i18n::t("jumpList.pinned"), pinned),
+        (i18n::t("jumpList.recent"), recent),
+    ];
+    std::thread::spawn(move || {
+        let _one_at_a_time = BUILDING.lock();
+        if let Err(e) = unsafe { build(&tasks, &categories) } {
+            eprintln!("[CrewHub] Failed to update jump list: {}", e);
+        }
+    });
+}
+
+/// Quote one argument for the Windows command line.
+pub fn quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('"', "\\\""))
+}
+
+unsafe fn collection(entries: &[Entry]) -> Result<IObjectArray> {
+    let exe = HSTRING::from(std::env::current_exe().unwrap_or_default().as_os_str());
+    let collection: IObjectCollection =
+        CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+    for entry in entries {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        let args: Vec<String> = entry.args.iter().map(|arg| quote(arg)).collect();
+        link.SetPath(&exe)?;
+        link.SetArguments(&HSTRING::from(args.join(" ")))?;
+        link.SetIconLocation(&exe, 0)?;
+        link.SetDescription(&HSTRING::from(entry.title.as_str()))?;
+        let store: IPropertyStore = link.cast()?;
+        store.SetValue(&PKEY_Title, &PROPVARIANT::from(entry.title.as_str()))?;
+        store.Commit()?;
+        collection.AddObject(&link)?;
+    }
+    collection.cast()
+}
+
+unsafe fn build(tasks: &[Entry], categories: &[(String, Vec<Entry>)]) -> Result<()> {
+    // Fails harmlessly if COM is already initialised on this thread.
+    let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+    let list: ICustomDestinationList =
+        CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+    let mut min_slots = 0u32;
+    let _removed: IObjectArray = list.BeginList(&mut min_slots)?;
+    for (name, entries) in categories {
+        if entries.is_empty() {
+            continue;
+        }
+        // Rejected when it holds an item the user removed from the list;
+        // the category then stays out until that item is gone.
+        if let Err(e) = list.AppendCategory(&HSTRING::from(name.as_str()), &collection(entries)?) {
+            eprintln!("[CrewHub] Jump list category '{}' skipped: {}", name, e);
+        }
+    }
+    list.AddUserTasks(&collection(tasks)?)?;
+    list.CommitList()
+}
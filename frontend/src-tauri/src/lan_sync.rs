@@ -0,0 +1,719 @@
+//! Opt-in LAN sync of app state between the user's own desktops.
+//!
+//! Desktops that share a sync secret find each other over mDNS
+//! (`_crewhub-sync._tcp`) and exchange full snapshots of the synced state:
+//! the prompt library ([`crate::prompts`]), read markers and saved window
+//! layouts. Every exchange is encrypted and authenticated with a key derived
+//! from the secret (ChaCha20-Poly1305), so a device without the secret can
+//! neither read nor inject state.
+//!
+//! Secrets are generated, never typed: `<profile>-<key>`, a random 64-bit
+//! profile id and a random 256-bit key in hex ([`generate_sync_secret`]),
+//! copied or scanned onto the other desktops. The TXT record carries only
+//! the profile id, which lets peers skip desktops from other profiles and
+//! says nothing about the key.
+//!
+//! Merging is last-writer-wins per item; read markers keep the latest time.
+//! A sync runs every minute while enabled, and on demand via `sync_now`.
+
+use crate::{license, local_http, prompts, store};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "sync.json";
+/// Conversation key → last read time (ms since epoch).
+const READ_STATE_FILE: &str = "read-state.json";
+/// Layout name → saved layout.
+const LAYOUTS_FILE: &str = "window-layouts.json";
+
+const SERVICE_TYPE: &str = "_crewhub-sync._tcp.local.";
+
+/// How often peers are synced while enabled.
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+/// Messages older (or newer) than this are rejected as replays.
+const MAX_CLOCK_SKEW_MS: i64 = 5 * 60 * 1000;
+/// Largest encrypted snapshot accepted from a peer.
+const MAX_BODY_BYTES: u64 = 16 * 1024 * 1024;
+const NONCE_LEN: usize = 12;
+/// Random bytes of the profile id and the key in a sync secret.
+const PROFILE_BYTES: usize = 8;
+const KEY_BYTES: usize = 32;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SyncConfig {
+    pub enabled: bool,
+    /// Shared secret (`<profile>-<key>`, see the module docs); every desktop
+    /// of the profile must use the same one.
+    pub secret: String,
+    pub device_id: String,
+    pub device_name: String,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            secret: String::new(),
+            device_id: local_http::new_token(),
+            device_name: std::env::var("COMPUTERNAME")
+                .or_else(|_| std::env::var("HOSTNAME"))
+                .unwrap_or_else(|_| "CrewHub desktop".to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowLayout {
+    /// Frontend-defined layout (window sizes, panel arrangement, …).
+    pub layout: Value,
+    pub updated_at: i64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncPeer {
+    device_id: String,
+    name: String,
+    address: String,
+    last_synced_at: Option<i64>,
+    last_error: Option<String>,
+    #[serde(skip)]
+    fullname: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    enabled: bool,
+    has_secret: bool,
+    device_id: String,
+    device_name: String,
+    running: bool,
+    peers: Vec<SyncPeer>,
+}
+
+/// What one desktop sends another.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Snapshot {
+    device_id: String,
+    sent_at: i64,
+    prompts: prompts::SyncedLibrary,
+    read_markers: BTreeMap<String, i64>,
+    layouts: BTreeMap<String, WindowLayout>,
+}
+
+/// Listener, mDNS registration and worker threads while sync is running.
+struct Service {
+    server: Arc<tiny_http::Server>,
+    daemon: ServiceDaemon,
+    stop: Arc<AtomicBool>,
+}
+
+/// App state: sync config, the synced stores, discovered peers and the running service.
+pub struct SyncState {
+    config: Mutex<SyncConfig>,
+    read_markers: Mutex<BTreeMap<String, i64>>,
+    layouts: Mutex<BTreeMap<String, WindowLayout>>,
+    peers: Mutex<HashMap<String, SyncPeer>>,
+    service: Mutex<Option<Service>>,
+}
+
+/// Load config and synced stores, register state, and start syncing if the user opted in.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: SyncConfig = store::load_json(app, CONFIG_FILE);
+    // Persist the generated device id so peers see a stable identity.
+    if let Err(e) = store::save_json(app, CONFIG_FILE, &config) {
+        eprintln!("[CrewHub] {}", e);
+    }
+    if config.enabled && split_secret(&config.secret).is_none() {
+        eprintln!(
+            "[CrewHub] LAN sync secret is not a generated one; generate a new secret to sync"
+        );
+    }
+    let enabled = config.enabled
+        && split_secret(&config.secret).is_some()
+        && license::entitled(license::LAN_SYNC);
+    app.manage(SyncState {
+        config: Mutex::new(config),
+        read_markers: Mutex::new(store::load_json(app, READ_STATE_FILE)),
+        layouts: Mutex::new(store::load_json(app, LAYOUTS_FILE)),
+        peers: Mutex::new(HashMap::new()),
+        service: Mutex::new(None),
+    });
+    if enabled {
+        if let Err(e) = start(app) {
+            eprintln!("[CrewHub] Failed to start LAN sync: {}", e);
+        }
+    }
+}
+
+fn now_ms() -> i64 {
+    chrono::Local::now().timestamp_millis()
+}
+
+fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// A new sync secret: random profile id and key.
+fn new_secret() -> String {
+    format!("{}-{}", random_hex(PROFILE_BYTES), random_hex(KEY_BYTES))
+}
+
+/// Profile id and key of a generated secret; `None` for anything else
+/// (typed passphrases and secrets of older versions are too weak).
+fn split_secret(secret: &str) -> Option<(&str, &str)> {
+    let (profile, key) = secret.split_once('-')?;
+    let hex = |part: &str, bytes: usize| {
+        part.len() == bytes * 2 && part.bytes().all(|b| b.is_ascii_hexdigit())
+    };
+    (hex(profile, PROFILE_BYTES) && hex(key, KEY_BYTES)).then_some((profile, key))
+}
+
+/// Public identifier of a profile: peers with the same secret share it.
+fn profile_id(secret: &str) -> String {
+    split_secret(secret)
+        .map(|(profile, _)| profile.to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+fn cipher(secret: &str) -> ChaCha20Poly1305 {
+    // The key part is 256 random bits, so a plain hash is enough to turn it
+    // into a key; no stretching needed.
+    let mut hasher = Sha256::new();
+    hasher.update(b"crewhub-sync-key:");
+    hasher.update(
+        split_secret(secret)
+            .map_or("", |(_, key)| key)
+            .to_ascii_lowercase(),
+    );
+    let key: [u8; 32] = hasher.finalize().into();
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+/// Encrypt a snapshot as `nonce || ciphertext`.
+fn seal(secret: &str, snapshot: &Snapshot) -> Result<Vec<u8>, String> {
+    let plain = serde_json::to_vec(snapshot).map_err(|e| e.to_string())?;
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let mut sealed = nonce.to_vec();
+    sealed.extend(
+        cipher(secret)
+            .encrypt(&nonce, plain.as_slice())
+            .map_err(|_| "Encryption failed".to_string())?,
+    );
+    Ok(sealed)
+}
+
+/// Decrypt and authenticate a peer's snapshot, rejecting stale ones and our own.
+fn open(secret: &str, own_device_id: &str, sealed: &[u8]) -> Result<Snapshot, String> {
+    if sealed.len() < NONCE_LEN {
+        return Err("Message too short".to_string());
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let plain = cipher(secret)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Message not authenticated (different sync secret?)".to_string())?;
+    let snapshot: Snapshot = serde_json::from_slice(&plain).map_err(|e| e.to_string())?;
+    if (now_ms() - snapshot.sent_at).abs() > MAX_CLOCK_SKEW_MS {
+        return Err("Message expired (check the clocks of both desktops)".to_string());
+    }
+    if snapshot.device_id == own_device_id {
+        return Err("Message from this desktop".to_string());
+    }
+    Ok(snapshot)
+}
+
+fn snapshot<R: Runtime>(app: &AppHandle<R>) -> Result<Snapshot, String> {
+    let state = app.state::<SyncState>();
+    let device_id = state
+        .config
+        .lock()
+        .map_err(|e| e.to_string())?
+        .device_id
+        .clone();
+    let read_markers = state
+        .read_markers
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
+    let layouts = state.layouts.lock().map_err(|e| e.to_string())?.clone();
+    Ok(Snapshot {
+        device_id,
+        sent_at: now_ms(),
+        prompts: prompts::export_library(app)?,
+        read_markers,
+        layouts,
+    })
+}
+
+/// Merge a peer's snapshot into local state and tell the frontend what changed.
+fn merge<R: Runtime>(app: &AppHandle<R>, peer: Snapshot) -> Result<(), String> {
+    let state = app.state::<SyncState>();
+
+    if prompts::merge_library(app, peer.prompts)? {
+        let _ = app.emit("prompt-templates-changed", ());
+    }
+
+    let markers_changed = {
+        let mut markers = state.read_markers.lock().map_err(|e| e.to_string())?;
+        let mut changed = false;
+        for (key, read_at) in peer.read_markers {
+            let entry = markers.entry(key).or_insert(0);
+            if read_at > *entry {
+                *entry = read_at;
+                changed = true;
+            }
+        }
+        if changed {
+            store::save_json(app, READ_STATE_FILE, &*markers)?;
+        }
+        changed.then(|| markers.clone())
+    };
+    if let Some(markers) = markers_changed {
+        let _ = app.emit("read-state-changed", markers);
+    }
+
+    let layouts_changed = {
+        let mut layouts = state.layouts.lock().map_err(|e| e.to_string())?;
+        let mut changed = false;
+        for (name, layout) in peer.layouts {
+            if layouts
+                .get(&name)
+                .is_none_or(|ours| layout.updated_at > ours.updated_at)
+            {
+                layouts.insert(name, layout);
+                changed = true;
+            }
+        }
+        if changed {
+            store::save_json(app, LAYOUTS_FILE, &*layouts)?;
+        }
+        changed.then(|| layouts.clone())
+    };
+    if let Some(layouts) = layouts_changed {
+        let _ = app.emit("window-layouts-changed", layouts);
+    }
+    Ok(())
+}
+
+fn start<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<SyncState>();
+    let mut slot = state.service.lock().map_err(|e| e.to_string())?;
+    if slot.is_some() {
+        return Ok(());
+    }
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    if split_secret(&config.secret).is_none() {
+        return Err("Generate a sync secret first".to_string());
+    }
+
+    // Reachable from the LAN on purpose; every request must decrypt with the secret.
+    let server = Arc::new(
+        tiny_http::Server::http(("0.0.0.0", 0)).map_err(|e| format!("Cannot bind: {}", e))?,
+    );
+    let port = server
+        .server_addr()
+        .to_ip()
+        .map(|addr| addr.port())
+        .ok_or("Listener has no IP address")?;
+
+    let daemon = ServiceDaemon::new().map_err(|e| e.to_string())?;
+    let profile = profile_id(&config.secret);
+    let properties = [
+        ("profile", profile.as_str()),
+        ("device", config.device_id.as_str()),
+        ("name", config.device_name.as_str()),
+    ];
+    let info = ServiceInfo::new(
+        SERVICE_TYPE,
+        &config.device_id,
+        &format!("{}.local.", config.device_id),
+        "",
+        port,
+        &properties[..],
+    )
+    .map_err(|e| e.to_string())?
+    .enable_addr_auto();
+    daemon.register(info).map_err(|e| e.to_string())?;
+    let events = daemon.browse(SERVICE_TYPE).map_err(|e| e.to_string())?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    {
+        let (app, server) = (app.clone(), server.clone());
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                handle_request(&app, request);
+            }
+        });
+    }
+    {
+        let (app, stop) = (app.clone(), stop.clone());
+        std::thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                match events.recv_timeout(Duration::from_secs(1)) {
+                    Ok(event) => on_discovery(&app, &profile, event),
+                    Err(_) if events.is_disconnected() => break,
+                    Err(_) => {}
+                }
+            }
+        });
+    }
+    {
+        let (app, stop) = (app.clone(), stop.clone());
+        std::thread::spawn(move || loop {
+            for _ in 0..SYNC_INTERVAL.as_secs() {
+                std::thread::sleep(Duration::from_secs(1));
+                if stop.load(Ordering::Relaxed) {
+                    return;
+                }
+            }
+            sync_all(&app);
+        });
+    }
+
+    *slot = Some(Service {
+        server,
+        daemon,
+        stop,
+    });
+    println!("[CrewHub] LAN sync listening on port {}", port);
+    Ok(())
+}
+
+fn stop<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<SyncState>();
+    if let Some(service) = state.service.lock().map_err(|e| e.to_string())?.take() {
+        service.stop.store(true, Ordering::Relaxed);
+        service.server.unblock();
+        let _ = service.daemon.shutdown();
+    }
+    state.peers.lock().map_err(|e| e.to_string())?.clear();
+    Ok(())
+}
+
+/// Withdraw the mDNS announcement on app exit.
+pub fn shutdown<R: Runtime>(app: &AppHandle<R>) {
+    if app.try_state::<SyncState>().is_some() {
+        let _ = stop(app);
+    }
+}
+
+fn on_discovery<R: Runtime>(app: &AppHandle<R>, profile: &str, event: ServiceEvent) {
+    let state = app.state::<SyncState>();
+    let own_id = match state.config.lock() {
+        Ok(config) => config.device_id.clone(),
+        Err(_) => return,
+    };
+    let Ok(mut peers) = state.peers.lock() else {
+        return;
+    };
+    match event {
+        ServiceEvent::ServiceResolved(info) => {
+            let device_id = info.get_property_val_str("device").unwrap_or_default();
+            if info.get_property_val_str("profile") != Some(profile) || device_id == own_id {
+                return;
+            }
+            let Some(ip) = info.get_addresses_v4().into_iter().next().copied() else {
+                return;
+            };
+            let previous = peers.remove(device_id);
+            peers.insert(
+                device_id.to_string(),
+                SyncPeer {
+                    device_id: device_id.to_string(),
+                    name: info
+                        .get_property_val_str("name")
+                        .unwrap_or(device_id)
+                        .to_string(),
+                    address: format!("{}:{}", ip, info.get_port()),
+                    last_synced_at: previous.as_ref().and_then(|p| p.last_synced_at),
+                    last_error: previous.and_then(|p| p.last_error),
+                    fullname: info.get_fullname().to_string(),
+                },
+            );
+            let _ = app.emit("sync-peers-changed", ());
+            // Sync right away with a desktop that just appeared.
+            let (app, device_id) = (app.clone(), device_id.to_string());
+            std::thread::spawn(move || sync_peer(&app, &device_id));
+        }
+        ServiceEvent::ServiceRemoved(_, fullname) => {
+            peers.retain(|_, peer| peer.fullname != fullname);
+            let _ = app.emit("sync-peers-changed", ());
+        }
+        _ => {}
+    }
+}
+
+/// Incoming sync: decrypt the peer's snapshot, merge it, and answer with ours.
+fn handle_request<R: Runtime>(app: &AppHandle<R>, mut request: tiny_http::Request) {
+    if request.method() != &tiny_http::Method::Post || request.url() != "/sync" {
+        return local_http::respond_empty(request, 404);
+    }
+    let (secret, device_id) = match app.state::<SyncState>().config.lock() {
+        Ok(config) => (config.secret.clone(), config.device_id.clone()),
+        Err(_) => return local_http::respond_empty(request, 500),
+    };
+    let mut body = Vec::new();
+    if request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_end(&mut body)
+        .is_err()
+    {
+        return local_http::respond_empty(request, 400);
+    }
+    let peer = match open(&secret, &device_id, &body) {
+        Ok(peer) => peer,
+        Err(e) => {
+            eprintln!("[CrewHub] Rejected LAN sync request: {}", e);
+            return local_http::respond_empty(request, 401);
+        }
+    };
+    if let Err(e) = merge(app, peer) {
+        eprintln!("[CrewHub] LAN sync merge failed: {}", e);
+        return local_http::respond_empty(request, 500);
+    }
+    match snapshot(app).and_then(|ours| seal(&secret, &ours)) {
+        Ok(sealed) => {
+            if let Err(e) = request.respond(tiny_http::Response::from_data(sealed)) {
+                eprintln!("[CrewHub] Failed to send LAN sync response: {}", e);
+            }
+        }
+        Err(e) => {
+            eprintln!("[CrewHub] LAN sync snapshot failed: {}", e);
+            local_http::respond_empty(request, 500);
+        }
+    }
+}
+
+/// Outgoing sync with one peer: send our snapshot, merge the one it answers with.
+fn exchange<R: Runtime>(app: &AppHandle<R>, address: &str) -> Result<(), String> {
+    let (secret, device_id) = {
+        let config = app
+            .state::<SyncState>()
+            .config
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone();
+        (config.secret, config.device_id)
+    };
+    let sealed = seal(&secret, &snapshot(app)?)?;
+    let response = ureq::post(&format!("http://{}/sync", address))
+        .timeout(Duration::from_secs(15))
+        .set("Content-Type", "application/octet-stream")
+        .send_bytes(&sealed)
+        .map_err(|e| e.to_string())?;
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_end(&mut body)
+        .map_err(|e| e.to_string())?;
+    merge(app, open(&secret, &device_id, &body)?)
+}
+
+fn sync_peer<R: Runtime>(app: &AppHandle<R>, device_id: &str) {
+    let state = app.state::<SyncState>();
+    let address = match state.peers.lock() {
+        Ok(peers) => match peers.get(device_id) {
+            Some(peer) => peer.address.clone(),
+            None => return,
+        },
+        Err(_) => return,
+    };
+    let result = exchange(app, &address);
+    if let Err(e) = &result {
+        eprintln!("[CrewHub] LAN sync with {} failed: {}", address, e);
+    }
+    if let Ok(mut peers) = state.peers.lock() {
+        if let Some(peer) = peers.get_mut(device_id) {
+            match result {
+                Ok(()) => {
+                    peer.last_synced_at = Some(now_ms());
+                    peer.last_error = None;
+                }
+                Err(e) => peer.last_error = Some(e),
+            }
+        }
+    }
+    let _ = app.emit("sync-peers-changed", ());
+}
+
+fn sync_all<R: Runtime>(app: &AppHandle<R>) {
+    let device_ids: Vec<String> = match app.state::<SyncState>().peers.lock() {
+        Ok(peers) => peers.keys().cloned().collect(),
+        Err(_) => return,
+    };
+    for device_id in device_ids {
+        sync_peer(app, &device_id);
+    }
+}
+
+fn status(state: &SyncState) -> Result<SyncStatus, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let running = state.service.lock().map_err(|e| e.to_string())?.is_some();
+    let mut peers: Vec<SyncPeer> = state
+        .peers
+        .lock()
+        .map_err(|e| e.to_string())?
+        .values()
+        .cloned()
+        .collect();
+    peers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(SyncStatus {
+        enabled: config.enabled,
+        has_secret: split_secret(&config.secret).is_some(),
+        device_id: config.device_id,
+        device_name: config.device_name,
+        running,
+        peers,
+    })
+}
+
+/// Tauri command: sync config (without the secret), running state and discovered peers.
+/// Called from the frontend via `invoke('get_sync_status')`.
+#[tauri::command]
+pub fn get_sync_status(state: State<SyncState>) -> Result<SyncStatus, String> {
+    status(&state)
+}
+
+/// Tauri command: enable/disable LAN sync and/or set the shared secret and device name.
+/// Called from the frontend via `invoke('configure_sync', { enabled, secret, deviceName })`.
+#[tauri::command]
+pub fn configure_sync(
+    enabled: bool,
+    secret: Option<String>,
+    device_name: Option<String>,
+    app: AppHandle,
+    state: State<SyncState>,
+) -> Result<SyncStatus, String> {
+    if enabled {
+        license::require(license::LAN_SYNC)?;
+    }
+    {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.enabled = enabled;
+        if let Some(secret) = secret {
+            let secret = secret.trim();
+            if split_secret(secret).is_none() {
+                return Err(
+                    "Not a sync secret; generate one, or copy it from a desktop that already syncs"
+                        .to_string(),
+                );
+            }
+            config.secret = secret.to_string();
+        }
+        if let Some(name) = device_name.filter(|n| !n.trim().is_empty()) {
+            config.device_name = name.trim().to_string();
+        }
+        store::save_json(&app, CONFIG_FILE, &*config)?;
+    }
+    // Restart so a new secret or name is advertised.
+    stop(&app)?;
+    if enabled {
+        start(&app)?;
+    }
+    status(&state)
+}
+
+/// Tauri command: a new random sync secret (not saved until passed to `configure_sync`).
+/// Called from the frontend via `invoke('generate_sync_secret')`.
+#[tauri::command]
+pub fn generate_sync_secret() -> String {
+    new_secret()
+}
+
+/// Tauri command: the current sync secret, to copy or show as a QR code for another desktop.
+/// Called from the frontend via `invoke('get_sync_secret')`.
+#[tauri::command]
+pub fn get_sync_secret(state: State<SyncState>) -> Result<Option<String>, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?;
+    Ok(split_secret(&config.secret).map(|_| config.secret.clone()))
+}
+
+/// Tauri command: sync with every discovered peer now.
+/// Called from the frontend via `invoke('sync_now')`.
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<SyncStatus, String> {
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || sync_all(&handle))
+        .await
+        .map_err(|e| e.to_string())?;
+    status(&app.state::<SyncState>())
+}
+
+/// Tauri command: mark a conversation read up to `readAt` (ms; defaults to now).
+/// Called from the frontend via `invoke('set_read_marker', { key, readAt })`.
+#[tauri::command]
+pub fn set_read_marker(
+    key: String,
+    read_at: Option<i64>,
+    app: AppHandle,
+    state: State<SyncState>,
+) -> Result<(), String> {
+    let mut markers = state.read_markers.lock().map_err(|e| e.to_string())?;
+    let read_at = read_at.unwrap_or_else(now_ms);
+    let entry = markers.entry(key).or_insert(0);
+    if read_at > *entry {
+        *entry = read_at;
+        store::save_json(&app, READ_STATE_FILE, &*markers)?;
+    }
+    Ok(())
+}
+
+/// Tauri command: read markers (conversation key → last read time in ms).
+/// Called from the frontend via `invoke('get_read_markers')`.
+#[tauri::command]
+pub fn get_read_markers(state: State<SyncState>) -> Result<BTreeMap<String, i64>, String> {
+    Ok(state
+        .read_markers
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone())
+}
+
+/// Tauri command: save a named window layout (synced to other desktops).
+/// Called from the frontend via `invoke('save_window_layout', { name, layout })`.
+#[tauri::command]
+pub fn save_window_layout(
+    name: String,
+    layout: Value,
+    app: AppHandle,
+    state: State<SyncState>,
+) -> Result<(), String> {
+    let mut layouts = state.layouts.lock().map_err(|e| e.to_string())?;
+    layouts.insert(
+        name,
+        WindowLayout {
+            layout,
+            updated_at: now_ms(),
+        },
+    );
+    store::save_json(&app, LAYOUTS_FILE, &*layouts)
+}
+
+/// Tauri command: saved window layouts by name.
+/// Called from the frontend via `invoke('list_window_layouts')`.
+#[tauri::command]
+pub fn list_window_layouts(
+    state: State<SyncState>,
+) -> Result<BTreeMap<String, WindowLayout>, String> {
+    Ok(state.layouts.lock().map_err(|e| e.to_string())?.clone())
+}
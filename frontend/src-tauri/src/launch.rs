@@ -0,0 +1,61 @@
+//! Requests passed on the command line.
+//!
+//! Shell integrations (jump list tasks, context menu entries, the share
+//! target) start the executable with arguments. They are handled here at
+//! startup and — through the single-instance plugin — when a second launch
+//! hands its arguments to the running app:
+//! - `crewhub://…` links (see [`crate::url_scheme`]);
+//! - `--share <file|url>…` / `--share-text <text>` (see [`crate::share`]);
+//! - `.crewhub` bundle paths (see [`crate::bundle`]);
+//! - `--action <name> [--arg key=value]…` performs a control action
+//!   (see [`crate::control::perform`]), e.g.
+//!   `--action open-conversation --arg sessionKey=abc`.
+
+use crate::{bundle, control, share, url_scheme};
+use serde_json::{Map, Value};
+use std::path::Path;
+use tauri::{AppHandle, Runtime};
+
+const ACTION_FLAG: &str = "--action";
+const ARG_FLAG: &str = "--arg";
+
+/// Handle launch arguments (without the program name). Returns whether they asked for anything.
+pub fn handle<R: Runtime>(app: &AppHandle<R>, args: &[String], cwd: &Path) -> bool {
+    if url_scheme::handle_args(app, args)
+        || share::handle_args(app, args, cwd)
+        || bundle::handle_args(app, args, cwd)
+    {
+        return true;
+    }
+    let mut action = None;
+    let mut action_args = Map::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            ACTION_FLAG => action = iter.next().cloned(),
+            ARG_FLAG => {
+                if let Some((key, value)) = iter.next().and_then(|pair| pair.split_once('=')) {
+                    action_args.insert(key.to_string(), Value::String(value.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+    let Some(action) = action else {
+        return false;
+    };
+    if let Err(e) = control::perform(app, &action, &Value::Object(action_args)) {
+        eprintln!("[CrewHub] Launch action '{}' failed: {}", action, e);
+    }
+    true
+}
+
+/// Arguments for a launch that performs `action` (for shell links and desktop shortcuts).
+pub fn action_args(action: &str, args: &[(&str, &str)]) -> Vec<String> {
+    let mut out = vec![ACTION_FLAG.to_string(), action.to_string()];
+    for (key, value) in args {
+        out.push(ARG_FLAG.to_string());
+        out.push(format!("{}={}", key, value));
+    }
+    out
+}
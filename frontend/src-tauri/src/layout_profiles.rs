@@ -0,0 +1,280 @@
+//! Named window layout profiles ("Monitoring", "Pairing", "Demo").
+//!
+//! A profile records which of CrewHub's windows are open, where they are
+//! and whether they stay on top. Profiles are captured from the current
+//! windows by the settings window and switched from the tray's Layouts
+//! submenu, a per-profile global hotkey (bound by [`crate::hotkeys`]) or the
+//! `apply-layout` control action. Switching opens the profile's windows,
+//! restores their geometry and hides the other main windows; the HUD only
+//! follows the profile's visibility and keeps its own corner.
+//!
+//! These are native window placements, separate from the frontend's panel
+//! layouts synced by [`crate::lan_sync`].
+
+use crate::{hud, store};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, Emitter, LogicalPosition, LogicalSize, Manager, Runtime, State, WebviewWindow,
+};
+
+/// Persisted profiles (app data dir).
+const PROFILES_FILE: &str = "layout-profiles.json";
+
+/// Prefix of tray menu item ids and hotkey binding ids that apply a profile.
+pub const MENU_ID_PREFIX: &str = "layout-profile:";
+
+/// Windows a profile opens, places and hides, in the order they are restored.
+const MANAGED_WINDOWS: [&str; 5] = [
+    crate::SETTINGS_WINDOW_LABEL,
+    crate::ZEN_WINDOW_LABEL,
+    crate::WORLD_WINDOW_LABEL,
+    crate::CHAT_WINDOW_LABEL,
+    hud::HUD_WINDOW_LABEL,
+];
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowPlacement {
+    pub label: String,
+    /// Outer position and inner size in logical pixels.
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default)]
+    pub always_on_top: bool,
+    /// Had focus when captured (restored last so it ends up in front).
+    #[serde(default)]
+    pub focused: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutProfile {
+    pub id: String,
+    pub name: String,
+    /// Accelerator such as `CmdOrCtrl+Alt+1`, bound by [`crate::hotkeys`].
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    /// Windows open in this profile; managed windows not listed are hidden.
+    #[serde(default)]
+    pub windows: Vec<WindowPlacement>,
+}
+
+/// App state: profiles in display order.
+pub struct LayoutProfileState {
+    profiles: Mutex<Vec<LayoutProfile>>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let profiles: Vec<LayoutProfile> = store::load_json(app, PROFILES_FILE);
+    app.manage(LayoutProfileState {
+        profiles: Mutex::new(profiles),
+    });
+}
+
+/// All profiles in display order.
+pub fn all<R: Runtime>(app: &AppHandle<R>) -> Vec<LayoutProfile> {
+    app.try_state::<LayoutProfileState>()
+        .and_then(|state| state.profiles.lock().ok().map(|p| p.clone()))
+        .unwrap_or_default()
+}
+
+/// Where a window is and how it is shown.
+pub fn placement<R: Runtime>(window: &WebviewWindow<R>) -> Result<WindowPlacement, String> {
+    let scale = window.scale_factor().map_err(|e| e.to_string())?;
+    let position = window
+        .outer_position()
+        .map_err(|e| e.to_string())?
+        .to_logical::<f64>(scale);
+    let size = window
+        .inner_size()
+        .map_err(|e| e.to_string())?
+        .to_logical::<f64>(scale);
+    Ok(WindowPlacement {
+        label: window.label().to_string(),
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: window.is_maximized().unwrap_or(false),
+        always_on_top: window.is_always_on_top().unwrap_or(false),
+        focused: window.is_focused().unwrap_or(false),
+    })
+}
+
+/// The managed windows that are open right now.
+fn capture<R: Runtime>(app: &AppHandle<R>) -> Vec<WindowPlacement> {
+    MANAGED_WINDOWS
+        .iter()
+        .filter_map(|label| app.get_webview_window(label))
+        .filter(|window| window.is_visible().unwrap_or(false))
+        .filter_map(|window| placement(&window).ok())
+        .collect()
+}
+
+/// Whether a profile opens, places and hides this window.
+pub fn is_managed(label: &str) -> bool {
+    MANAGED_WINDOWS.contains(&label)
+}
+
+/// Open (or show) one of the [`MANAGED_WINDOWS`].
+pub fn open<R: Runtime>(app: &AppHandle<R>, label: &str) -> Result<(), String> {
+    match label {
+        crate::CHAT_WINDOW_LABEL => crate::open_or_focus_chat(app),
+        crate::WORLD_WINDOW_LABEL => crate::open_or_focus_world(app),
+        crate::ZEN_WINDOW_LABEL => crate::open_or_focus_zen(app),
+        crate::SETTINGS_WINDOW_LABEL => crate::open_or_focus_settings(app),
+        hud::HUD_WINDOW_LABEL => hud::set_visible(app, true)?,
+        _ => return Err(format!("Not a layout window: {}", label)),
+    }
+    Ok(())
+}
+
+/// Open a window and put it back where `placement` says.
+pub fn restore<R: Runtime>(app: &AppHandle<R>, placement: &WindowPlacement) -> Result<(), String> {
+    open(app, &placement.label)?;
+    if placement.label == hud::HUD_WINDOW_LABEL {
+        return Ok(());
+    }
+    let window = app
+        .get_webview_window(&placement.label)
+        .ok_or_else(|| format!("No window '{}'", placement.label))?;
+    window.unmaximize().map_err(|e| e.to_string())?;
+    window
+        .set_size(LogicalSize::new(placement.width, placement.height))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_position(LogicalPosition::new(placement.x, placement.y))
+        .map_err(|e| e.to_string())?;
+    if placement.maximized {
+        window.maximize().map_err(|e| e.to_string())?;
+    }
+    window
+        .set_always_on_top(placement.always_on_top)
+        .map_err(|e| e.to_string())
+}
+
+/// Switch to a profile: hide the managed windows it doesn't list, then open
+/// and place the ones it does (the focused one last).
+pub fn apply<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<(), String> {
+    let profile = all(app)
+        .into_iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| format!("Layout profile not found: {}", id))?;
+
+    for label in MANAGED_WINDOWS {
+        if profile.windows.iter().any(|w| w.label == label) {
+            continue;
+        }
+        if label == hud::HUD_WINDOW_LABEL {
+            hud::set_visible(app, false)?;
+        } else if let Some(window) = app.get_webview_window(label) {
+            window.hide().map_err(|e| e.to_string())?;
+        }
+    }
+
+    let mut windows = profile.windows.clone();
+    windows.sort_by_key(|w| w.focused);
+    for placement in &windows {
+        if let Err(e) = restore(app, placement) {
+            eprintln!("[CrewHub] Failed to restore {}: {}", placement.label, e);
+        }
+    }
+    let _ = app.emit("layout-profile-applied", &profile.id);
+    Ok(())
+}
+
+fn save_and_apply<R: Runtime>(
+    app: &AppHandle<R>,
+    profiles: &[LayoutProfile],
+) -> Result<Vec<LayoutProfile>, String> {
+    store::save_json(app, PROFILES_FILE, &profiles)?;
+    crate::refresh_tray_menu(app);
+    crate::hotkeys::sync(app, &crate::quick_actions::all(app));
+    let _ = app.emit("layout-profiles-changed", profiles);
+    Ok(profiles.to_vec())
+}
+
+/// Add a profile under a new id, without a hotkey (for [`crate::bundle`] imports).
+pub fn import<R: Runtime>(app: &AppHandle<R>, mut profile: LayoutProfile) -> Result<(), String> {
+    let state = app.state::<LayoutProfileState>();
+    let mut profiles = state.profiles.lock().map_err(|e| e.to_string())?;
+    profile.id = uuid::Uuid::new_v4().to_string();
+    profile.hotkey = None;
+    profiles.push(profile);
+    save_and_apply(app, &profiles).map(|_| ())
+}
+
+/// Tauri command: all layout profiles.
+/// Called from the settings window via `invoke('list_layout_profiles')`.
+#[tauri::command]
+pub fn list_layout_profiles(
+    state: State<LayoutProfileState>,
+) -> Result<Vec<LayoutProfile>, String> {
+    Ok(state.profiles.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: create or update a profile (matched by `id`; a new id is
+/// assigned if empty). New profiles, and existing ones with `capture`, record
+/// the windows as they are now.
+/// Called from the settings window via `invoke('save_layout_profile', { id, name, hotkey, capture })`.
+#[tauri::command]
+pub fn save_layout_profile(
+    id: Option<String>,
+    name: String,
+    hotkey: Option<String>,
+    capture: bool,
+    app: AppHandle,
+    state: State<LayoutProfileState>,
+) -> Result<Vec<LayoutProfile>, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Layout profile needs a name".to_string());
+    }
+    let mut profiles = state.profiles.lock().map_err(|e| e.to_string())?;
+    match profiles
+        .iter_mut()
+        .find(|p| id.as_deref() == Some(p.id.as_str()))
+    {
+        Some(profile) => {
+            profile.name = name;
+            profile.hotkey = hotkey;
+            if capture {
+                profile.windows = self::capture(&app);
+            }
+        }
+        None => profiles.push(LayoutProfile {
+            id: id
+                .filter(|id| !id.is_empty())
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            name,
+            hotkey,
+            windows: self::capture(&app),
+        }),
+    }
+    save_and_apply(&app, &profiles)
+}
+
+/// Tauri command: delete a profile.
+/// Called from the settings window via `invoke('delete_layout_profile', { id })`.
+#[tauri::command]
+pub fn delete_layout_profile(
+    id: String,
+    app: AppHandle,
+    state: State<LayoutProfileState>,
+) -> Result<Vec<LayoutProfile>, String> {
+    let mut profiles = state.profiles.lock().map_err(|e| e.to_string())?;
+    profiles.retain(|p| p.id != id);
+    save_and_apply(&app, &profiles)
+}
+
+/// Tauri command: switch to a profile.
+/// Called from the frontend via `invoke('apply_layout_profile', { id })`.
+#[tauri::command]
+pub fn apply_layout_profile(id: String, app: AppHandle) -> Result<(), String> {
+    apply(&app, &id)
+}
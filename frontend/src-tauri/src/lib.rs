@@ -1,10 +1,147 @@
+mod accessibility;
+mod accounts;
+mod activation_policy;
+mod agent_pause;
+mod annotate;
+mod announcements;
+#[cfg(target_os = "macos")]
+mod app_menu;
+mod approvals;
+mod archive;
+mod attachments;
+mod attention;
+mod automation;
+mod autostart;
+mod backend;
+mod backend_auth;
+mod backend_process;
+mod badges;
+mod bandwidth;
+mod benchmark;
+mod bridge;
+mod browser;
+mod bundle;
+mod calendar;
+mod capabilities;
+mod child_processes;
+mod choreography;
+mod clipboard_history;
+mod clock;
+mod config_template;
+mod connectivity;
+mod content_protection;
+mod context_menu;
+mod control;
+mod control_socket;
+mod costs;
+mod cron;
+mod db;
+#[cfg(target_os = "linux")]
+mod dbus;
+mod debug;
+mod demo;
+mod diff_review;
+mod displays;
+mod dnd;
+#[cfg(target_os = "macos")]
+mod dock_menu;
+mod drag_out;
+mod embeddings;
+mod ephemeral;
+mod external_links;
+mod federation;
+mod file_manager;
+mod focus_timer;
+#[cfg(target_os = "macos")]
+mod handoff;
+mod health;
+mod hot_corner;
+mod hotkeys;
+mod hud;
+mod i18n;
+mod init_extensions;
+#[cfg(target_os = "windows")]
+mod jump_list;
+mod lan_sync;
+mod launch;
+mod layout_profiles;
+mod license;
+mod local_http;
+mod local_model;
+mod local_tools;
+mod magnetism;
+mod mcp_client;
+mod mcp_server;
+mod mute_rules;
+mod native_messaging;
+mod notifications;
+mod ocr;
+mod onboarding;
+mod pairing;
+mod pins;
+#[cfg(target_os = "linux")]
+mod portal;
+mod power;
+mod presence;
+mod print;
+mod project_windows;
+mod prompts;
+mod quick_actions;
+mod quick_prompt;
+mod quiet_hours;
+mod recents;
+mod reminders;
+mod request_headers;
+mod resources;
+mod scheduler;
+mod screen_share;
+mod search_index;
+#[cfg(target_os = "macos")]
+mod services;
+mod session_events;
+mod session_handover;
+mod share;
+#[cfg(any(debug_assertions, feature = "simulation"))]
+mod simulate;
+mod snap;
+mod spellcheck;
+mod store;
+mod switcher;
+mod task_journal;
+mod test_harness;
+mod theme;
+#[cfg(target_os = "windows")]
+mod thumb_bar;
+mod thumbnails;
+#[cfg(target_os = "windows")]
+mod toast_activator;
+mod tray_click;
+mod tray_support;
+mod tray_updates;
+mod ui_scale;
+mod url_scheme;
+mod usage_stats;
+mod wayland;
+mod webhook;
+mod webview_data;
+mod webview_menu;
+mod whats_new;
+mod widgets;
+#[cfg(target_os = "macos")]
+mod window_restoration;
+mod window_shortcuts;
+mod window_state;
+mod worktrees;
+mod world_quality;
+mod x_callback;
+
+use std::collections::HashMap;
 use std::sync::Mutex;
 use tauri::{
-    App, AppHandle, Manager, Runtime, State,
     image::Image,
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    WebviewUrl, WebviewWindowBuilder,
+    AppHandle, Emitter, Manager, Runtime, State, WebviewUrl, WebviewWindowBuilder,
 };
 
 /// Label for the chat window (compact, mobile view)
@@ -19,40 +156,102 @@ const SETTINGS_WINDOW_LABEL: &str = "settings";
 /// Label for the standalone Zen Mode window
 const ZEN_WINDOW_LABEL: &str = "zen-mode";
 
+/// Emitted with the badge totals after a change (replayed, see [`bridge::emit`])
+const BADGE_COUNTS_CHANGED_EVENT: &str = "badge-counts-changed";
+
 /// ID for the system tray icon (used for badge updates)
 const TRAY_ID: &str = "main-tray";
 
-/// App state: current badge count (used to debounce icon updates)
-struct BadgeCount(Mutex<u32>);
+/// Attention counts of one backend by category. The categories don't overlap:
+/// a mention is not also counted as unread.
+#[derive(Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct BadgeCounts {
+    /// Unread messages that don't mention the user.
+    unread: u32,
+    /// Unread messages that mention the user.
+    mentions: u32,
+    /// Approvals waiting for the user.
+    approvals: u32,
+    /// Failed runs the user hasn't looked at.
+    failures: u32,
+}
 
-/// Returns the backend URL from env var or default.
+impl BadgeCounts {
+    fn total(&self) -> u32 {
+        self.unread + self.mentions + self.approvals + self.failures
+    }
+
+    fn add(self, other: BadgeCounts) -> BadgeCounts {
+        BadgeCounts {
+            unread: self.unread + other.unread,
+            mentions: self.mentions + other.mentions,
+            approvals: self.approvals + other.approvals,
+            failures: self.failures + other.failures,
+        }
+    }
+}
+
+/// App state: current badge counts per backend and account (used to debounce
+/// icon updates). Counts reported without an account are kept under `""`.
+#[derive(Default)]
+struct BadgeCount(Mutex<HashMap<String, HashMap<String, BadgeCounts>>>);
+
+/// Returns the backend URL: the test harness or demo mock, the env var, or the default.
 fn backend_url() -> String {
+    if let Some(url) = test_harness::url().or_else(demo::url) {
+        return url.to_string();
+    }
     std::env::var("VITE_API_URL").unwrap_or_else(|_| "http://localhost:8091".to_string())
 }
 
-/// Base init: sets backend URL and skips onboarding (backend handles OpenClaw connection).
-fn base_init() -> String {
+/// Base init for the window `label`: sets backend URL, syncs the onboarding
+/// flag (see [`onboarding`]) and adds the configured [`init_extensions`].
+fn base_init(label: &str) -> String {
     // Use JSON string escaping so any backend URL is safe to inject into JS.
     let url_json = serde_json::to_string(&backend_url()).unwrap_or_else(|_| "\"\"".to_string());
     format!(
-        "window.__CREWHUB_BACKEND_URL__ = {}; localStorage.setItem('crewhub-onboarded', 'true');",
-        url_json
+        "window.__CREWHUB_BACKEND_URL__ = {}; {} {} {} {} {} {} {} {} {} {} {} {} {}",
+        url_json,
+        federation::init_script(),
+        theme::init_script(),
+        accessibility::init_script(),
+        i18n::init_script(),
+        spellcheck::init_script(),
+        test_harness::init_script(),
+        demo::init_script(),
+        onboarding::init_script(),
+        request_headers::init_script(),
+        webview_menu::init_script(),
+        accounts::init_script(),
+        bandwidth::init_script(),
+        init_extensions::init_script(label)
     )
 }
 
 /// JavaScript injected into the chat window before page load.
 fn chat_init_script() -> String {
-    format!("window.__TAURI_VIEW__ = 'mobile'; {}", base_init())
+    format!(
+        "window.__TAURI_VIEW__ = 'mobile'; {}",
+        base_init(CHAT_WINDOW_LABEL)
+    )
 }
 
 /// JavaScript injected into the world window before page load.
 fn world_init_script() -> String {
-    format!("window.__TAURI_VIEW__ = 'desktop'; {}", base_init())
+    format!(
+        "window.__TAURI_VIEW__ = 'desktop'; {} {}",
+        base_init(WORLD_WINDOW_LABEL),
+        world_quality::init_script()
+    )
 }
 
 /// JavaScript injected into the settings window before page load.
 fn settings_init_script() -> String {
-    format!("window.__TAURI_VIEW__ = 'settings'; {}", base_init())
+    format!(
+        "window.__TAURI_VIEW__ = 'settings'; {}",
+        base_init(SETTINGS_WINDOW_LABEL)
+    )
 }
 
 /// Show an existing window and explicitly focus it.
@@ -67,7 +266,7 @@ fn show_and_focus<R: Runtime>(window: &tauri::WebviewWindow<R>) {
 /// Open or focus the chat window (390×700, compact mobile chat).
 /// - If already open: bring to front.
 /// - If hidden: show + focus.
-/// - If not yet created: create, then show + focus.
+/// - If not yet created: create where it was last (see [`window_state`]), then show + focus.
 fn open_or_focus_chat<R: Runtime>(app: &AppHandle<R>) {
     if let Some(window) = app.get_webview_window(CHAT_WINDOW_LABEL) {
         show_and_focus(&window);
@@ -75,7 +274,7 @@ fn open_or_focus_chat<R: Runtime>(app: &AppHandle<R>) {
     }
 
     // Create the chat window with initialization script injected before page load
-    let result = WebviewWindowBuilder::new(app, CHAT_WINDOW_LABEL, chat_url())
+    let builder = WebviewWindowBuilder::new(app, CHAT_WINDOW_LABEL, chat_url())
         .title("CrewHub Chat")
         .inner_size(390.0, 700.0)
         .min_inner_size(320.0, 500.0)
@@ -84,11 +283,17 @@ fn open_or_focus_chat<R: Runtime>(app: &AppHandle<R>) {
         .decorations(true)
         .always_on_top(false)
         .skip_taskbar(false)
-        .initialization_script(&chat_init_script())
-        .build();
+        .user_agent(&request_headers::user_agent())
+        .on_new_window(external_links::new_window_handler(app))
+        .initialization_script(chat_init_script());
+    let result = window_state::apply(app, CHAT_WINDOW_LABEL, builder).build();
 
     match result {
-        Ok(window) => show_and_focus(&window),
+        Ok(window) => {
+            show_and_focus(&window);
+            #[cfg(target_os = "windows")]
+            thumb_bar::attach(&window);
+        }
         Err(e) => eprintln!("[CrewHub] Failed to create chat window: {}", e),
     }
 }
@@ -96,7 +301,7 @@ fn open_or_focus_chat<R: Runtime>(app: &AppHandle<R>) {
 /// Open or focus the 3D world window (1280×900, resizable, fullscreen capable).
 /// - If already open: bring to front.
 /// - If hidden: show + focus.
-/// - If not yet created: create, then show + focus.
+/// - If not yet created: create where it was last (see [`window_state`]), then show + focus.
 fn open_or_focus_world<R: Runtime>(app: &AppHandle<R>) {
     if let Some(window) = app.get_webview_window(WORLD_WINDOW_LABEL) {
         show_and_focus(&window);
@@ -104,7 +309,7 @@ fn open_or_focus_world<R: Runtime>(app: &AppHandle<R>) {
     }
 
     // Create the world window with initialization script injected before page load
-    let result = WebviewWindowBuilder::new(app, WORLD_WINDOW_LABEL, world_url())
+    let builder = WebviewWindowBuilder::new(app, WORLD_WINDOW_LABEL, world_url())
         .title("CrewHub 3D World")
         .inner_size(1280.0, 900.0)
         .min_inner_size(900.0, 600.0)
@@ -112,8 +317,10 @@ fn open_or_focus_world<R: Runtime>(app: &AppHandle<R>) {
         .fullscreen(false)
         .decorations(true)
         .always_on_top(false)
-        .initialization_script(&world_init_script())
-        .build();
+        .user_agent(&request_headers::user_agent())
+        .on_new_window(external_links::new_window_handler(app))
+        .initialization_script(world_init_script());
+    let result = window_state::apply(app, WORLD_WINDOW_LABEL, builder).build();
 
     match result {
         Ok(window) => show_and_focus(&window),
@@ -159,6 +366,18 @@ fn settings_url() -> WebviewUrl {
     }
 }
 
+/// Build the WebviewUrl for a secondary view selected by query string (e.g. `view=review`).
+fn view_url(query: &str) -> WebviewUrl {
+    #[cfg(debug_assertions)]
+    {
+        WebviewUrl::External(format!("http://localhost:5180/?{}", query).parse().unwrap())
+    }
+    #[cfg(not(debug_assertions))]
+    {
+        WebviewUrl::App(format!("index.html?{}", query).into())
+    }
+}
+
 /// Build the WebviewUrl for the standalone Zen Mode window.
 fn zen_url() -> WebviewUrl {
     #[cfg(debug_assertions)]
@@ -175,21 +394,21 @@ fn zen_url() -> WebviewUrl {
 fn zen_init_script() -> String {
     format!(
         "window.__TAURI_VIEW__ = 'zen'; {}",
-        base_init()
+        base_init(ZEN_WINDOW_LABEL)
     )
 }
 
 /// Open or focus the standalone Zen Mode window (800×900, resizable, no decorations).
 /// - If already open: bring to front.
 /// - If hidden: show + focus.
-/// - If not yet created: create, then show + focus.
+/// - If not yet created: create where it was last (see [`window_state`]), then show + focus.
 fn open_or_focus_zen<R: Runtime>(app: &AppHandle<R>) {
     if let Some(window) = app.get_webview_window(ZEN_WINDOW_LABEL) {
         show_and_focus(&window);
         return;
     }
 
-    let result = WebviewWindowBuilder::new(app, ZEN_WINDOW_LABEL, zen_url())
+    let builder = WebviewWindowBuilder::new(app, ZEN_WINDOW_LABEL, zen_url())
         .title("Zen Mode")
         .inner_size(820.0, 920.0)
         .min_inner_size(600.0, 500.0)
@@ -198,8 +417,10 @@ fn open_or_focus_zen<R: Runtime>(app: &AppHandle<R>) {
         .decorations(true)
         .always_on_top(false)
         .skip_taskbar(false)
-        .initialization_script(&zen_init_script())
-        .build();
+        .user_agent(&request_headers::user_agent())
+        .on_new_window(external_links::new_window_handler(app))
+        .initialization_script(zen_init_script());
+    let result = window_state::apply(app, ZEN_WINDOW_LABEL, builder).build();
 
     match result {
         Ok(window) => show_and_focus(&window),
@@ -217,23 +438,25 @@ fn open_zen_window(app: AppHandle) {
 /// Open or focus the settings window (420×280, not resizable).
 /// - If already open: bring to front.
 /// - If hidden: show + focus.
-/// - If not yet created: create, then show + focus.
+/// - If not yet created: create where it was last (see [`window_state`]), then show + focus.
 fn open_or_focus_settings<R: Runtime>(app: &AppHandle<R>) {
     if let Some(window) = app.get_webview_window(SETTINGS_WINDOW_LABEL) {
         show_and_focus(&window);
         return;
     }
 
-    let result = WebviewWindowBuilder::new(app, SETTINGS_WINDOW_LABEL, settings_url())
+    let builder = WebviewWindowBuilder::new(app, SETTINGS_WINDOW_LABEL, settings_url())
         .title("CrewHub Settings")
         .inner_size(420.0, 280.0)
         .resizable(false)
         .fullscreen(false)
         .decorations(true)
         .always_on_top(true)
-        .skip_taskbar(true)
-        .initialization_script(&settings_init_script())
-        .build();
+        .skip_taskbar(!tray_support::trayless())
+        .user_agent(&request_headers::user_agent())
+        .on_new_window(external_links::new_window_handler(app))
+        .initialization_script(settings_init_script());
+    let result = window_state::apply(app, SETTINGS_WINDOW_LABEL, builder).build();
 
     match result {
         Ok(window) => show_and_focus(&window),
@@ -241,36 +464,232 @@ fn open_or_focus_settings<R: Runtime>(app: &AppHandle<R>) {
     }
 }
 
-/// Set up the system tray with the CrewHub menu.
-fn setup_tray(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
-    let handle = app.handle();
+/// Build the tray menu. With several backends connected, a per-backend
+/// unread summary is listed above Quit.
+fn build_tray_menu<R: Runtime>(handle: &AppHandle<R>) -> tauri::Result<Menu<R>> {
+    let menu = Menu::new(handle)?;
+    for id in ["chat", "world", "zen", "hud", "settings"] {
+        let label = i18n::t(&format!("tray.{}", id));
+        menu.append(&MenuItem::with_id(handle, id, label, true, None::<&str>)?)?;
+    }
+    menu.append(&PredefinedMenuItem::separator(handle)?)?;
+
+    let pinned = pins::pinned(handle);
+    if !pinned.is_empty() {
+        let submenu = Submenu::new(handle, i18n::t("tray.pinned"), true)?;
+        for (index, pin) in pinned.into_iter().enumerate() {
+            let id = format!("{}{}", pins::MENU_ID_PREFIX, index);
+            submenu.append(&MenuItem::with_id(
+                handle,
+                id,
+                pin.conversation.title,
+                true,
+                None::<&str>,
+            )?)?;
+        }
+        menu.append(&submenu)?;
+    }
+
+    let actions = quick_actions::tray_actions(handle);
+    if !actions.is_empty() {
+        let submenu = Submenu::new(handle, i18n::t("tray.quickActions"), true)?;
+        for action in actions {
+            let id = format!("{}{}", quick_actions::MENU_ID_PREFIX, action.id);
+            submenu.append(&MenuItem::with_id(
+                handle,
+                id,
+                action.name,
+                true,
+                None::<&str>,
+            )?)?;
+        }
+        menu.append(&submenu)?;
+        menu.append(&PredefinedMenuItem::separator(handle)?)?;
+    }
+
+    let profiles = layout_profiles::all(handle);
+    if !profiles.is_empty() {
+        let submenu = Submenu::new(handle, i18n::t("tray.layouts"), true)?;
+        for profile in profiles {
+            let id = format!("{}{}", layout_profiles::MENU_ID_PREFIX, profile.id);
+            submenu.append(&MenuItem::with_id(
+                handle,
+                id,
+                profile.name,
+                true,
+                None::<&str>,
+            )?)?;
+        }
+        menu.append(&submenu)?;
+        menu.append(&PredefinedMenuItem::separator(handle)?)?;
+    }
+
+    let pause_label = if agent_pause::paused() {
+        i18n::t("tray.resumeAgents")
+    } else {
+        i18n::t("tray.pauseAgents")
+    };
+    menu.append(&MenuItem::with_id(
+        handle,
+        agent_pause::MENU_ID,
+        pause_label,
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&PredefinedMenuItem::separator(handle)?)?;
+
+    let counts = handle
+        .try_state::<BadgeCount>()
+        .and_then(|badge| badge.0.lock().ok().map(|counts| counts.clone()))
+        .unwrap_or_default();
+    let accounts = accounts::current();
+    if accounts.accounts.len() > 1 {
+        let submenu = Submenu::new(handle, i18n::t("tray.accounts"), true)?;
+        for account in &accounts.accounts {
+            let total = counts
+                .get(&account.backend_id)
+                .and_then(|backend| backend.get(&account.id))
+                .map_or(0, BadgeCounts::total);
+            let id = format!("{}{}", accounts::MENU_ID_PREFIX, account.id);
+            let active = accounts.active.as_deref() == Some(account.id.as_str());
+            let label = accounts::menu_label(account, total);
+            submenu.append(&CheckMenuItem::with_id(
+                handle,
+                id,
+                label,
+                true,
+                active,
+                None::<&str>,
+            )?)?;
+        }
+        menu.append(&submenu)?;
+        menu.append(&PredefinedMenuItem::separator(handle)?)?;
+    }
+
+    let backends = federation::enabled(handle);
+    if backends.len() > 1 {
+        for backend in backends {
+            let total = counts.get(&backend.id).map_or(0, |accounts| {
+                accounts.values().map(BadgeCounts::total).sum()
+            });
+            let label = match total {
+                0 => backend.name,
+                n => i18n::tf(
+                    "tray.backendUnread",
+                    &[("name", &backend.name), ("count", &n.to_string())],
+                ),
+            };
+            let id = format!("backend:{}", backend.id);
+            menu.append(&MenuItem::with_id(handle, id, label, false, None::<&str>)?)?;
+        }
+        menu.append(&PredefinedMenuItem::separator(handle)?)?;
+    }
+
+    let help = Submenu::new(handle, i18n::t("tray.help"), true)?;
+    help.append(&MenuItem::with_id(
+        handle,
+        whats_new::MENU_ID,
+        i18n::t("menu.whatsNew"),
+        true,
+        None::<&str>,
+    )?)?;
+    help.append(&CheckMenuItem::with_id(
+        handle,
+        demo::MENU_ID,
+        i18n::t("menu.demoMode"),
+        true,
+        demo::enabled(),
+        None::<&str>,
+    )?)?;
+    menu.append(&help)?;
+    menu.append(&MenuItem::with_id(
+        handle,
+        "quit",
+        i18n::t("tray.quit"),
+        true,
+        None::<&str>,
+    )?)?;
+    i18n::mirror_menu(&menu)?;
+    Ok(menu)
+}
+
+/// Rebuild the tray menu after the backend list or per-backend counts changed
+/// (coalesced on the tray worker, see [`tray_updates`]).
+fn refresh_tray_menu<R: Runtime>(app: &AppHandle<R>) {
+    tray_updates::menu(app);
+}
+
+/// Rebuild the tray menu now (tray worker).
+fn apply_tray_menu<R: Runtime>(app: &AppHandle<R>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_tray_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => eprintln!("[CrewHub] Failed to rebuild tray menu: {}", e),
+    }
+}
 
-    let chat_item = MenuItem::with_id(handle, "chat", "Chat", true, None::<&str>)?;
-    let world_item = MenuItem::with_id(handle, "world", "3D World", true, None::<&str>)?;
-    let zen_item = MenuItem::with_id(handle, "zen", "🧘 Zen Mode", true, None::<&str>)?;
-    let settings_item = MenuItem::with_id(handle, "settings", "⚙️ Settings", true, None::<&str>)?;
-    let separator = PredefinedMenuItem::separator(handle)?;
-    let quit_item = MenuItem::with_id(handle, "quit", "Quit CrewHub", true, None::<&str>)?;
+/// Run the tray menu item `id` (also used for synthetic clicks, see [`automation`]).
+fn handle_tray_menu<R: Runtime>(app: &AppHandle<R>, id: &str) {
+    match id {
+        "chat" => open_or_focus_chat(app),
+        "world" => open_or_focus_world(app),
+        "zen" => open_or_focus_zen(app),
+        "hud" => {
+            if let Err(e) = hud::toggle(app) {
+                eprintln!("[CrewHub] {}", e);
+            }
+        }
+        "settings" => open_or_focus_settings(app),
+        agent_pause::MENU_ID => agent_pause::toggle(app),
+        whats_new::MENU_ID => whats_new::open_or_focus(app, None),
+        demo::MENU_ID => demo::toggle(app),
+        #[cfg(target_os = "macos")]
+        app_menu::GETTING_STARTED_ID => onboarding::open_or_focus_onboarding(app),
+        "quit" => {
+            println!("[CrewHub] Quitting...");
+            app.exit(0);
+        }
+        other => {
+            let result = if let Some(id) = other.strip_prefix(quick_actions::MENU_ID_PREFIX) {
+                quick_actions::trigger(app, id)
+            } else if let Some(id) = other.strip_prefix(webview_menu::MENU_ID_PREFIX) {
+                webview_menu::handle(app, id)
+            } else if let Some(id) = other.strip_prefix(accounts::MENU_ID_PREFIX) {
+                accounts::switch(app, id)
+            } else if let Some(id) = other.strip_prefix(layout_profiles::MENU_ID_PREFIX) {
+                layout_profiles::apply(app, id)
+            } else if let Some(index) = other.strip_prefix(pins::MENU_ID_PREFIX) {
+                index
+                    .parse()
+                    .map_err(|_| format!("Invalid pin index: {}", index))
+                    .and_then(|index| pins::open(app, index))
+            } else {
+                Err(format!("Unknown menu event: {}", other))
+            };
+            if let Err(e) = result {
+                eprintln!("[CrewHub] {}", e);
+            }
+        }
+    }
+}
 
-    let menu = Menu::with_items(handle, &[&chat_item, &world_item, &zen_item, &settings_item, &separator, &quit_item])?;
+/// Set up the system tray with the CrewHub menu (also when trayless operation
+/// is switched off, see [`tray_support`]).
+fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    let menu = build_tray_menu(app)?;
 
     let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
         .icon(app.default_window_icon().unwrap().clone())
         .tooltip("CrewHub")
+        .show_menu_on_left_click(tray_click::shows_menu())
         // On menu item click
-        .on_menu_event(|app, event| match event.id.as_ref() {
-            "chat" => open_or_focus_chat(app),
-            "world" => open_or_focus_world(app),
-            "zen" => open_or_focus_zen(app),
-            "settings" => open_or_focus_settings(app),
-            "quit" => {
-                println!("[CrewHub] Quitting...");
-                app.exit(0);
-            }
-            other => eprintln!("[CrewHub] Unknown menu event: {}", other),
-        })
-        // On direct tray icon left-click: open/focus chat (useful on Windows/Linux)
+        .on_menu_event(|app, event| handle_tray_menu(app, event.id.as_ref()))
+        // On direct tray icon left/middle click: the configured actions
         .on_tray_icon_event(|tray, event| {
             if let TrayIconEvent::Click {
                 button: MouseButton::Left,
@@ -278,7 +697,15 @@ fn setup_tray(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
                 ..
             } = event
             {
-                open_or_focus_chat(tray.app_handle());
+                tray_click::handle(tray.app_handle());
+            }
+            if let TrayIconEvent::Click {
+                button: MouseButton::Middle,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                tray_click::handle_middle(tray.app_handle());
             }
         })
         .build(app)?;
@@ -288,79 +715,811 @@ fn setup_tray(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
 
 /// Tauri command: update the tray icon badge.
 ///
-/// - count = 0 → normal tray icon
-/// - count = 1 → tray-badge-1.png
-/// - count = 2 → tray-badge-2.png
-/// - count ≥ 3 → tray-badge-3plus.png
+/// - nothing pending → normal tray icon
+/// - any failure → red `!` badge
+/// - otherwise the total count (up to `99+`), amber when an approval is
+///   pending, else blue when someone mentioned the user, else red
 ///
-/// Called from the frontend via `invoke('update_tray_badge', { count })`.
+/// The count also goes to the Dock / taskbar badge (see [`badges`]).
+///
+/// `badges` carries the counts by category (`{ unread, mentions, approvals,
+/// failures }`); a plain `count` is taken as unread messages. Counts are per
+/// backend (`backendId` defaults to the primary backend) and account
+/// (`accountId`, see [`accounts`]); the tray shows the sum across all of them.
+/// With per-conversation `conversations`, unread and mention counts are
+/// summed from them instead, leaving out muted conversations ([`mute_rules`]).
+///
+/// Called from the frontend via `invoke('update_tray_badge', { count, badges, backendId, accountId, conversations })`.
 #[tauri::command]
 fn update_tray_badge(
-    count: u32,
+    count: Option<u32>,
+    badges: Option<BadgeCounts>,
+    backend_id: Option<String>,
+    account_id: Option<String>,
+    conversations: Option<Vec<mute_rules::ConversationBadge>>,
     app: AppHandle,
     badge_state: State<BadgeCount>,
 ) -> Result<(), String> {
-    // Debounce: skip if count hasn't changed
+    let backend_id = backend_id.unwrap_or_else(|| federation::DEFAULT_BACKEND_ID.to_string());
+    let mut badges = badges.unwrap_or(BadgeCounts {
+        unread: count.unwrap_or(0),
+        ..BadgeCounts::default()
+    });
+    if let Some(conversations) = conversations {
+        (badges.unread, badges.mentions) = mute_rules::badge_totals(&app, &conversations);
+    }
+    let before = badge_totals(&app);
+    // Debounce: skip if the counts haven't changed
     {
-        let mut current = badge_state.0.lock().map_err(|e| e.to_string())?;
-        if *current == count {
+        let mut counts = badge_state.0.lock().map_err(|e| e.to_string())?;
+        let accounts = counts.entry(backend_id).or_default();
+        let account_id = account_id.unwrap_or_default();
+        if accounts.get(&account_id).copied().unwrap_or_default() == badges {
             return Ok(());
         }
-        *current = count;
+        accounts.insert(account_id, badges);
     }
 
-    let tray = app
-        .tray_by_id(TRAY_ID)
-        .ok_or_else(|| "Tray icon not found".to_string())?;
+    refresh_tray_icon(&app);
+    refresh_tray_menu(&app);
+    let after = badge_totals(&app);
+    announcements::badges_changed(&app, before, after);
+    bridge::emit(&app, BADGE_COUNTS_CHANGED_EVENT, after);
+    Ok(())
+}
 
-    let icon = if count == 0 {
-        // Restore default icon
-        app.default_window_icon()
+/// Clear every backend's unread count and tell the windows to do the same.
+fn mark_all_read<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(badge) = app.try_state::<BadgeCount>() {
+        if let Ok(mut counts) = badge.0.lock() {
+            counts.clear();
+        }
+    }
+    refresh_tray_icon(app);
+    refresh_tray_menu(app);
+    bridge::emit(app, BADGE_COUNTS_CHANGED_EVENT, BadgeCounts::default());
+    let _ = app.emit("mark-all-read", ());
+}
+
+/// Forget the unread count of a backend that was removed.
+fn clear_badge<R: Runtime>(app: &AppHandle<R>, backend_id: &str) {
+    if let Some(badge) = app.try_state::<BadgeCount>() {
+        if let Ok(mut counts) = badge.0.lock() {
+            counts.remove(backend_id);
+        }
+    }
+    refresh_tray_icon(app);
+}
+
+/// Forget the unread count of an account that signed out.
+fn clear_account_badge<R: Runtime>(app: &AppHandle<R>, backend_id: &str, account_id: &str) {
+    if let Some(badge) = app.try_state::<BadgeCount>() {
+        if let Ok(mut counts) = badge.0.lock() {
+            if let Some(accounts) = counts.get_mut(backend_id) {
+                accounts.remove(account_id);
+            }
+        }
+    }
+    refresh_tray_icon(app);
+}
+
+/// Badge counts summed across all backends and accounts.
+fn badge_totals<R: Runtime>(app: &AppHandle<R>) -> BadgeCounts {
+    app.try_state::<BadgeCount>()
+        .and_then(|badge| {
+            badge.0.lock().ok().map(|counts| {
+                counts
+                    .values()
+                    .flat_map(HashMap::values)
+                    .fold(BadgeCounts::default(), |sum, counts| sum.add(*counts))
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Unread messages (mentions included) across all backends.
+fn unread_total<R: Runtime>(app: &AppHandle<R>) -> u32 {
+    let totals = badge_totals(app);
+    totals.unread + totals.mentions
+}
+
+/// Badge to show: the live one, or the one held during quiet hours (see [`quiet_hours`]).
+fn badge<R: Runtime>(app: &AppHandle<R>) -> (u32, badges::BadgeStyle) {
+    quiet_hours::frozen_badge().unwrap_or_else(|| live_badge(app))
+}
+
+/// Current badge: the attention count (badge categories plus blocked agents,
+/// which count as pending approvals) and the style of its most urgent category.
+fn live_badge<R: Runtime>(app: &AppHandle<R>) -> (u32, badges::BadgeStyle) {
+    let mut totals = badge_totals(app);
+    totals.approvals += presence::blocked_count(app);
+    let style = if totals.failures > 0 {
+        badges::BadgeStyle::Failure
+    } else if totals.approvals > 0 {
+        badges::BadgeStyle::Approval
+    } else if totals.mentions > 0 {
+        badges::BadgeStyle::Mention
+    } else {
+        badges::BadgeStyle::Unread
+    };
+    (totals.total(), style)
+}
+
+/// Tray icon for the current attention counts, with the count drawn on
+/// `tray-icon.png` (see [`badges`]). Themed variants (e.g.
+/// `tray-icon-dark.png`) are preferred when bundled.
+fn tray_icon<R: Runtime>(app: &AppHandle<R>) -> Result<Image<'static>, String> {
+    let variant = theme::icon_variant(app, "tray-icon.png");
+    let (count, style) = badge(app);
+    if count == 0 {
+        if let Some(path) = variant {
+            return tray_updates::load_icon(&path);
+        }
+        // Default icon
+        return Ok(app
+            .default_window_icon()
             .ok_or_else(|| "No default icon".to_string())?
             .clone()
-    } else {
-        // Pick the appropriate badge icon
-        let icon_name = match count {
-            1 => "tray-badge-1.png",
-            2 => "tray-badge-2.png",
-            _ => "tray-badge-3plus.png",
-        };
-        Image::from_path(
-            app.path()
-                .resource_dir()
-                .map_err(|e| e.to_string())?
-                .join("icons")
-                .join(icon_name),
-        )
-        .map_err(|e| format!("Failed to load badge icon '{}': {}", icon_name, e))?
+            .to_owned());
+    }
+    let path = match variant {
+        Some(path) => path,
+        None => app
+            .path()
+            .resource_dir()
+            .map_err(|e| e.to_string())?
+            .join("icons")
+            .join("tray-icon.png"),
     };
+    let base =
+        tray_updates::load_icon(&path).map_err(|e| format!("Failed to load tray icon: {}", e))?;
+    badges::tray_icon(app, &base, count, style)
+}
 
-    tray.set_icon(Some(icon)).map_err(|e| e.to_string())?;
-    tray.set_tooltip(Some(if count == 0 {
-        "CrewHub".to_string()
-    } else {
-        format!("CrewHub — {} unread", count)
-    }))
-    .map_err(|e| e.to_string())?;
+/// Re-apply the tray icon and tooltip after any input to them changed
+/// (coalesced on the tray worker, see [`tray_updates`]).
+fn refresh_tray_icon<R: Runtime>(app: &AppHandle<R>) {
+    tray_updates::icon(app);
+}
 
-    Ok(())
+/// Apply the tray icon, tooltip and native badges now (tray worker).
+fn apply_tray_icon<R: Runtime>(app: &AppHandle<R>) {
+    let (count, style) = badge(app);
+    badges::apply(app, count, style);
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match tray_icon(app).and_then(|icon| project_windows::tray_variant(app, icon)) {
+        Ok(icon) => {
+            let _ = tray.set_icon(Some(icon));
+        }
+        Err(e) => eprintln!("[CrewHub] {}", e),
+    }
+    refresh_tray_tooltip(app);
+}
+
+/// Rebuild the tray tooltip from the connection status, badge counts, agent
+/// activity, focus timer and today's agent spend. Each of those refreshes it
+/// when it changes.
+fn refresh_tray_tooltip<R: Runtime>(app: &AppHandle<R>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let mut parts = vec!["CrewHub".to_string()];
+    match connectivity::current(app) {
+        connectivity::Connectivity::Offline => parts.push(i18n::t("tooltip.offline")),
+        connectivity::Connectivity::CaptivePortal => parts.push(i18n::t("tooltip.captivePortal")),
+        connectivity::Connectivity::Online => {
+            let total = federation::enabled(app).len();
+            let connected = bridge::connected_count(app).min(total);
+            if total > 1 {
+                parts.push(i18n::tf(
+                    "tooltip.backendsConnected",
+                    &[
+                        ("connected", &connected.to_string()),
+                        ("total", &total.to_string()),
+                    ],
+                ));
+            } else if total == 1 && connected == 0 {
+                parts.push(i18n::t("tooltip.backendOffline"));
+            }
+        }
+    }
+    let totals = badge_totals(app);
+    let count = totals.unread + totals.mentions;
+    if count > 0 {
+        parts.push(i18n::tf("tooltip.unread", &[("count", &count.to_string())]));
+    }
+    if totals.mentions > 0 {
+        let mentions = totals.mentions.to_string();
+        parts.push(i18n::tf("tooltip.mentions", &[("count", &mentions)]));
+    }
+    if totals.approvals > 0 {
+        let approvals = totals.approvals.to_string();
+        parts.push(i18n::tf("tooltip.approvals", &[("count", &approvals)]));
+    }
+    if totals.failures > 0 {
+        let failures = totals.failures.to_string();
+        parts.push(i18n::tf("tooltip.failures", &[("count", &failures)]));
+    }
+    let working = presence::working_count(app);
+    if working > 0 {
+        parts.push(i18n::tf(
+            "tooltip.working",
+            &[("count", &working.to_string())],
+        ));
+    }
+    let blocked = presence::blocked_count(app);
+    if blocked > 0 {
+        parts.push(i18n::tf(
+            "tooltip.blocked",
+            &[("count", &blocked.to_string())],
+        ));
+    }
+    if let Some(focus) = focus_timer::summary() {
+        parts.push(focus);
+    }
+    if let Some(cost) = costs::today_total(app).filter(|cost| *cost > 0.0) {
+        parts.push(i18n::tf(
+            "tooltip.cost",
+            &[("cost", &format!("{:.2}", cost))],
+        ));
+    }
+    let _ = tray.set_tooltip(Some(parts.join(" — ")));
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    // Started by a browser as the extension's native messaging host: no UI.
+    if native_messaging::is_host_invocation() {
+        native_messaging::run_host();
+        return;
+    }
+
+    #[cfg(target_os = "linux")]
+    portal::prepare();
+
+    // E2E tests: serve a mock backend and point everything at it.
+    test_harness::start_if_requested();
+    // Demo mode: the same mock, serving synthetic data.
+    demo::start_if_enabled();
+
+    let mut builder = tauri::Builder::default();
+    // Must be registered first: a second launch hands its arguments over and exits.
+    // Off in automation mode, where a test driver starts its own instance.
+    if !automation::enabled() {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            let args = args.get(1..).unwrap_or_default();
+            if !launch::handle(app, args, std::path::Path::new(&cwd)) {
+                open_or_focus_chat(app);
+            }
+        }));
+    }
+    builder
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_notification::init())
-        .manage(BadgeCount(Mutex::new(0)))
-        .invoke_handler(tauri::generate_handler![update_tray_badge, open_zen_window])
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(external_links::plugin())
+        .plugin(theme::plugin())
+        .plugin(ui_scale::plugin())
+        .plugin(spellcheck::plugin())
+        .plugin(magnetism::plugin())
+        .plugin(window_state::plugin())
+        .plugin(content_protection::plugin())
+        .manage(BadgeCount::default())
+        .invoke_handler(tauri::generate_handler![
+            update_tray_badge,
+            open_zen_window,
+            local_tools::capture_screenshot,
+            mcp_server::get_mcp_server_status,
+            mcp_server::set_mcp_server_enabled,
+            mcp_server::set_mcp_tool_enabled,
+            mcp_server::set_mcp_roots,
+            mcp_client::list_mcp_servers,
+            mcp_client::add_mcp_server,
+            mcp_client::remove_mcp_server,
+            mcp_client::test_mcp_server,
+            scheduler::list_schedules,
+            scheduler::create_schedule,
+            scheduler::update_schedule,
+            scheduler::delete_schedule,
+            scheduler::run_schedule_now,
+            webhook::get_webhook_status,
+            webhook::configure_webhook,
+            webhook::regenerate_webhook_token,
+            worktrees::list_agent_worktrees,
+            worktrees::create_agent_worktree,
+            worktrees::remove_agent_worktree,
+            worktrees::cleanup_agent_worktrees,
+            diff_review::open_review_window,
+            diff_review::get_review_diff,
+            diff_review::accept_review_file,
+            diff_review::reject_review_file,
+            approvals::list_approval_rules,
+            approvals::set_approval_rule,
+            approvals::clear_approval_rules,
+            choreography::take_pending_choreography,
+            choreography::get_choreography_config,
+            choreography::set_choreography_consent,
+            costs::get_cost_summary,
+            costs::get_today_cost,
+            costs::get_cost_config,
+            costs::set_daily_budget,
+            presence::get_agent_states,
+            federation::list_backends,
+            federation::add_backend,
+            federation::set_backend_enabled,
+            federation::remove_backend,
+            backend_auth::get_backend_auth,
+            backend_auth::set_backend_auth,
+            backend_auth::start_backend_sign_in,
+            backend_auth::sign_out_backend,
+            backend_auth::get_backend_auth_headers,
+            local_model::get_local_model_status,
+            local_model::configure_local_model,
+            local_model::list_local_models,
+            local_model::download_local_model,
+            local_model::start_local_model,
+            local_model::stop_local_model,
+            embeddings::embed_and_store,
+            embeddings::similarity_search,
+            embeddings::clear_embeddings,
+            embeddings::configure_embeddings,
+            prompts::list_prompt_templates,
+            prompts::create_prompt_template,
+            prompts::update_prompt_template,
+            prompts::tag_prompt_template,
+            prompts::delete_prompt_template,
+            prompts::render_prompt_template,
+            quick_actions::list_quick_actions,
+            quick_actions::save_quick_action,
+            quick_actions::delete_quick_action,
+            quick_actions::run_quick_action,
+            pins::list_pinned_conversations,
+            pins::pin_conversation,
+            pins::unpin_conversation,
+            pins::reorder_pinned_conversations,
+            pins::open_pinned,
+            pins::take_pending_conversation,
+            browser::list_browser_consent,
+            browser::set_browser_consent,
+            browser::configure_browser,
+            browser::close_browser,
+            control::get_control_status,
+            control::configure_control,
+            control::regenerate_control_token,
+            recents::list_recent_conversations,
+            recents::record_recent_conversation,
+            archive::archive_conversation,
+            archive::list_archived_conversations,
+            archive::search_archived_conversations,
+            archive::restore_archived_conversation,
+            archive::get_archive_config,
+            archive::configure_archive,
+            native_messaging::install_native_messaging_host,
+            pairing::start_pairing,
+            pairing::get_pairing_session,
+            pairing::cancel_pairing,
+            lan_sync::get_sync_status,
+            lan_sync::configure_sync,
+            lan_sync::generate_sync_secret,
+            lan_sync::get_sync_secret,
+            lan_sync::sync_now,
+            lan_sync::set_read_marker,
+            lan_sync::get_read_markers,
+            lan_sync::save_window_layout,
+            lan_sync::list_window_layouts,
+            hud::toggle_hud,
+            hud::get_hud_snapshot,
+            hud::configure_hud,
+            widgets::list_widgets,
+            widgets::open_widget,
+            widgets::close_widget,
+            clipboard_history::get_clipboard_history_config,
+            clipboard_history::configure_clipboard_history,
+            clipboard_history::search_clipboard_history,
+            clipboard_history::copy_clipboard_entry,
+            clipboard_history::delete_clipboard_entry,
+            clipboard_history::clear_clipboard_history,
+            usage_stats::record_usage_event,
+            usage_stats::get_usage_stats,
+            onboarding::get_onboarding_status,
+            onboarding::detect_backends,
+            onboarding::test_backend_connection,
+            onboarding::complete_onboarding,
+            onboarding::reset_onboarding,
+            theme::get_system_theme,
+            theme::get_accent_color,
+            project_windows::get_project_themes,
+            project_windows::set_project_theme,
+            project_windows::open_project_window,
+            i18n::get_language,
+            i18n::set_language,
+            power::get_power_status,
+            power::configure_battery_policy,
+            connectivity::get_connectivity,
+            connectivity::configure_connectivity,
+            bandwidth::get_bandwidth_usage,
+            bandwidth::reset_bandwidth_usage,
+            bandwidth::set_metered_mode,
+            session_events::get_session_config,
+            session_events::configure_session,
+            dnd::get_dnd_status,
+            dnd::configure_dnd,
+            dnd::set_manual_dnd,
+            quiet_hours::get_quiet_hours,
+            quiet_hours::configure_quiet_hours,
+            notifications::send_notification,
+            notifications::get_notification_config,
+            notifications::configure_notifications,
+            attachments::set_drop_context,
+            quick_prompt::open_quick_prompt,
+            share::take_pending_share,
+            share::get_share_target_status,
+            share::set_share_target,
+            context_menu::get_context_menu_status,
+            context_menu::set_context_menu_entry,
+            activation_policy::get_activation_policy,
+            activation_policy::set_activation_policy,
+            print::print_conversation,
+            file_manager::reveal_in_file_manager,
+            file_manager::open_path,
+            file_manager::preview_attachment,
+            search_index::get_search_index_status,
+            search_index::configure_search_index,
+            reminders::list_reminders,
+            reminders::create_reminder,
+            reminders::snooze_reminder,
+            reminders::delete_reminder,
+            external_links::open_external_url,
+            external_links::list_trusted_link_domains,
+            external_links::forget_link_domain,
+            url_scheme::get_link_handler_status,
+            url_scheme::repair_link_handler,
+            url_scheme::take_pending_deep_link,
+            session_handover::continue_in_browser,
+            snap::snap_window,
+            autostart::get_autostart_status,
+            autostart::set_autostart,
+            tray_click::get_tray_left_click,
+            tray_click::set_tray_left_click,
+            tray_click::get_tray_click_config,
+            tray_click::set_tray_middle_click,
+            tray_click::set_tray_scroll,
+            focus_timer::update_focus_timer,
+            agent_pause::get_agents_paused,
+            agent_pause::set_agents_paused,
+            attention::get_attention_config,
+            attention::set_attention_config,
+            badges::get_badge_config,
+            badges::set_badge_config,
+            tray_support::get_tray_support,
+            capabilities::get_capabilities,
+            bridge::window_ready,
+            capabilities::report_capability,
+            tray_support::set_trayless,
+            tray_support::quit_app,
+            accessibility::get_accessibility_prefs,
+            announcements::get_announcement_config,
+            announcements::set_announcement_config,
+            ui_scale::set_ui_scale,
+            ui_scale::get_ui_scales,
+            switcher::get_switcher_entries,
+            switcher::switch_to,
+            switcher::get_switcher_config,
+            switcher::set_switcher_config,
+            spellcheck::get_spellcheck_config,
+            spellcheck::set_spellcheck_config,
+            test_harness::get_test_harness,
+            test_harness::harness_emit,
+            test_harness::harness_run_script,
+            test_harness::harness_requests,
+            test_harness::harness_reset,
+            debug::toggle_devtools,
+            debug::open_debug_window,
+            debug::get_debug_state,
+            debug::get_debug_config,
+            debug::set_debug_config,
+            child_processes::list_child_processes,
+            automation::automation_tray_click,
+            automation::automation_tray_menu,
+            automation::automation_tray_items,
+            #[cfg(any(debug_assertions, feature = "simulation"))]
+            simulate::simulate_backend_event,
+            #[cfg(any(debug_assertions, feature = "simulation"))]
+            simulate::simulate_notification,
+            #[cfg(any(debug_assertions, feature = "simulation"))]
+            simulate::simulate_tray_click,
+            layout_profiles::list_layout_profiles,
+            layout_profiles::save_layout_profile,
+            layout_profiles::delete_layout_profile,
+            layout_profiles::apply_layout_profile,
+            hot_corner::get_hot_corner,
+            hot_corner::set_hot_corner,
+            magnetism::get_magnetism_config,
+            magnetism::set_magnetism_config,
+            displays::list_displays,
+            displays::get_display_rules,
+            displays::set_display_rules,
+            content_protection::set_content_protected,
+            content_protection::get_content_protection,
+            ephemeral::open_ephemeral_chat,
+            ephemeral::claim_ephemeral_session,
+            webview_data::clear_webview_cache,
+            webview_data::clear_webview_storage,
+            request_headers::get_request_headers,
+            request_headers::set_request_headers,
+            init_extensions::get_init_extensions,
+            init_extensions::set_init_extensions,
+            annotate::annotate_screenshot,
+            annotate::get_annotation_image,
+            annotate::flatten_annotation,
+            ocr::recognize_text,
+            webview_menu::show_context_menu,
+            webview_menu::get_webview_menu_config,
+            webview_menu::set_webview_menu_config,
+            drag_out::start_drag_out,
+            bundle::import_bundle,
+            bundle::export_bundle,
+            accounts::list_accounts,
+            accounts::add_account,
+            accounts::remove_account,
+            accounts::switch_account,
+            benchmark::start_world_benchmark,
+            benchmark::take_world_benchmark,
+            benchmark::report_benchmark_frames,
+            benchmark::finish_world_benchmark,
+            task_journal::track_upload,
+            task_journal::finish_upload,
+            task_journal::take_recovered_uploads,
+            task_journal::get_task_recovery,
+            world_quality::get_world_quality,
+            world_quality::set_world_quality_override,
+            whats_new::open_whats_new,
+            calendar::get_calendar_dnd,
+            calendar::configure_calendar_dnd,
+            mute_rules::list_mute_rules,
+            mute_rules::save_mute_rule,
+            mute_rules::delete_mute_rule,
+            resources::get_resource_status,
+            resources::configure_resource_monitor,
+            config_template::export_config_template,
+            config_template::apply_config_template,
+            backend_process::backend_status,
+            backend_process::restart_backend,
+            thumbnails::capture_window_thumbnail,
+            window_shortcuts::get_shortcuts,
+            window_shortcuts::set_shortcut,
+            window_state::reset_window_layout,
+            health::get_health_report,
+            health::repair_health_issue,
+            license::get_license,
+            license::has_entitlement,
+            license::activate_license,
+            license::deactivate_license,
+            hotkeys::get_hotkey_status,
+            #[cfg(target_os = "linux")]
+            portal::get_portal_status,
+        ])
         .setup(|app| {
-            // ── macOS: Regular activation policy ────────────────────────────
-            // Regular = Dock icon + Cmd+Tab app switcher, like a normal app.
+            // ── Diagnostics first, so early panics are kept for the debug window
+            debug::init(app.handle());
+            capabilities::init(app.handle());
+
+            // ── macOS: activation policy (Dock icon + Cmd+Tab, or menu bar only)
+            tray_support::load(app.handle());
+            activation_policy::init(app.handle());
+
+            // ── Windows: toast clicks after CrewHub quit (COM activator) ─────
+            #[cfg(target_os = "windows")]
+            toast_activator::init(app.handle());
+
+            // ── macOS: window roles for Spaces / Stage Manager (before any window)
             #[cfg(target_os = "macos")]
-            app.set_activation_policy(tauri::ActivationPolicy::Regular);
+            app.handle().plugin(window_restoration::plugin())?;
+
+            // ── OS theme and language (before any window or the tray is created)
+            theme::init(app.handle());
+            request_headers::init(app.handle());
+            init_extensions::init(app.handle());
+            world_quality::init(app.handle());
+            project_windows::init(app.handle());
+            accounts::init(app.handle());
+            webview_menu::init(app.handle());
+            accessibility::init(app.handle());
+            i18n::init(app.handle());
+
+            // ── Backend sidecar (started before anything connects to it) ───
+            child_processes::init(app.handle());
+            backend_process::init(app.handle());
+
+            // ── License (entitlements gate the team features set up below) ──
+            license::init(app.handle());
+
+            // ── Backends, pins, quick actions (listed in the tray) ───────────
+            backend_auth::init(app.handle());
+            federation::init(app.handle());
+            quick_actions::init(app.handle());
+            layout_profiles::init(app.handle());
+            pins::init(app.handle());
+            recents::init(app.handle());
+            agent_pause::init(app.handle());
+            hotkeys::init(app.handle());
+            #[cfg(target_os = "linux")]
+            dbus::init(app.handle());
+            #[cfg(target_os = "linux")]
+            portal::init(app.handle());
 
             // ── Set up system tray ───────────────────────────────────────────
-            setup_tray(app)?;
+            badges::init(app.handle());
+            tray_click::init(app.handle());
+            if tray_support::chosen() {
+                open_or_focus_chat(app.handle());
+            } else {
+                match setup_tray(app.handle()) {
+                    Ok(()) => tray_support::created(app.handle()),
+                    Err(e) => tray_support::fallback(
+                        app.handle(),
+                        format!("The tray icon could not be created: {}", e),
+                    ),
+                }
+            }
+            tray_updates::init(app.handle());
+            tray_support::init(app.handle());
+
+            // ── First-run onboarding window ──────────────────────────────────
+            onboarding::init(app.handle());
+            whats_new::init(app.handle());
+
+            // ── Embedded MCP server (local tools for agents) ─────────────────
+            mcp_client::init(app.handle());
+            mcp_server::init(app.handle());
+
+            // ── Scheduled agent runs ─────────────────────────────────────────
+            task_journal::init(app.handle());
+            scheduler::init(app.handle());
+
+            // ── Local webhook listener (opt-in) ──────────────────────────────
+            webhook::init(app.handle());
+
+            // ── Agent task worktrees ─────────────────────────────────────────
+            worktrees::init(app.handle());
+
+            // ── Backend event bridge (native handling of backend events) ─────
+            approvals::init(app.handle());
+            choreography::init(app.handle());
+            pairing::init(app.handle());
+            presence::init(app.handle());
+            if let Err(e) = costs::init(app.handle()) {
+                eprintln!("[CrewHub] Cost tracking unavailable: {}", e);
+            }
+            bridge::init(app.handle());
+
+            // ── Sleep/wake handling (pauses and reconnects the bridge) ───────
+            power::init(app.handle());
+
+            // ── File drops / attachment staging ──────────────────────────────
+            file_manager::init(app.handle());
+            attachments::init(app.handle());
+            drag_out::init(app.handle());
+
+            // ── Share target (content shared from other apps) ────────────────
+            share::init(app.handle());
+
+            // ── crewhub:// links ─────────────────────────────────────────────
+            url_scheme::init(app.handle());
+            x_callback::init(app.handle());
+
+            // ── Self-check (resources, sidecar, link handler, autostart) ─────
+            health::init(app.handle());
+
+            // ── Notification rate limiting / digests ─────────────────────────
+            mute_rules::init(app.handle());
+            notifications::init(app.handle());
+
+            // ── OS Do Not Disturb / Focus ────────────────────────────────────
+            dnd::init(app.handle());
+            calendar::init(app.handle());
+            quiet_hours::init(app.handle());
+
+            // ── Screen lock (holds notifications, reports away) ──────────────
+            session_events::init(app.handle());
+
+            // ── Network connectivity (online/offline/captive portal) ─────────
+            connectivity::init(app.handle());
+
+            // ── Bandwidth usage and metered-connection mode ──────────────────
+            bandwidth::init(app.handle());
+
+            // ── Low disk space / memory pressure ─────────────────────────────
+            resources::init(app.handle());
+
+            // ── Local model runner (optional llama.cpp sidecar) ──────────────
+            local_model::init(app.handle());
+
+            // ── Embedding cache for retrieval over project files ────────────
+            if let Err(e) = embeddings::init(app.handle()) {
+                eprintln!("[CrewHub] Embedding cache unavailable: {}", e);
+            }
+
+            // ── Prompt template library ──────────────────────────────────────
+            if let Err(e) = prompts::init(app.handle()) {
+                eprintln!("[CrewHub] Prompt library unavailable: {}", e);
+            }
+
+            // ── Automated browser for agent web research ─────────────────────
+            browser::init(app.handle());
+
+            // ── Local control surface (Stream Deck, scripts; opt-in) ─────────
+            control::init(app.handle());
+
+            // ── HUD overlay with live agent activity ─────────────────────────
+            hud::init(app.handle());
+            hot_corner::init(app.handle());
+
+            // ── Monitor hot-plug rules (world window on the external screen) ─
+            displays::init(app.handle());
+
+            // ── Desktop widgets (restored from last session) ─────────────────
+            widgets::init(app.handle());
+
+            // ── Reminders (timed or when an agent finishes) ──────────────────
+            if let Err(e) = reminders::init(app.handle()) {
+                eprintln!("[CrewHub] Reminders unavailable: {}", e);
+            }
+
+            // ── Clock / time zone changes (schedules, reminders, focus) ──────
+            clock::init(app.handle());
+
+            // ── Local usage statistics (weekly review) ───────────────────────
+            if let Err(e) = usage_stats::init(app.handle()) {
+                eprintln!("[CrewHub] Usage statistics unavailable: {}", e);
+            }
+
+            // ── Clipboard history for the quick prompt (opt-in) ──────────────
+            clipboard_history::init(app.handle());
+
+            // ── LAN sync between the user's desktops (opt-in) ────────────────
+            lan_sync::init(app.handle());
+
+            // ── Taskbar jump list (tasks, pinned and recent conversations) ───
+            #[cfg(target_os = "windows")]
+            jump_list::refresh(app.handle());
+
+            // ── Conversation archive (cold storage, before the search index) ─
+            if let Err(e) = archive::init(app.handle()) {
+                eprintln!("[CrewHub] Conversation archive unavailable: {}", e);
+            }
+
+            // ── Conversations in Spotlight / Windows Search (opt-out) ────────
+            search_index::init(app.handle());
+
+            // ── Handoff and universal links ──────────────────────────────────
+            // (after Spotlight: both hook the app delegate)
+            #[cfg(target_os = "macos")]
+            handoff::init(app.handle());
+
+            // ── Dock menu (same entries as the tray, plus recents) ───────────
+            #[cfg(target_os = "macos")]
+            dock_menu::init(app.handle());
+
+            // ── Menu bar (App/Edit/View/Window/Help) ─────────────────────────
+            #[cfg(target_os = "macos")]
+            app_menu::init(app.handle());
+
+            // ── Services menu ("CrewHub: Ask about selection") ───────────────
+            #[cfg(target_os = "macos")]
+            services::init(app.handle());
+
+            // ── macOS: reopen the windows that were open at the last quit ────
+            #[cfg(target_os = "macos")]
+            window_restoration::restore(app.handle());
+
+            // ── Launch arguments (shell integrations, share target) ──────────
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            let cwd = std::env::current_dir().unwrap_or_default();
+            launch::handle(app.handle(), &args, &cwd);
 
             Ok(())
         })
@@ -368,6 +1527,16 @@ pub fn run() {
         // Prevents expensive Three.js re-initialization on reopen (500ms+).
         // The app stays alive via the tray icon even when all windows are hidden.
         .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Focused(true) = event {
+                tray_click::track_focus(window.label());
+                project_windows::track_focus(window.app_handle(), window.label());
+            }
+            if let tauri::WindowEvent::ThemeChanged(native) = event {
+                theme::set(window.app_handle(), (*native).into());
+            }
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                attachments::handle_drop(window, paths.clone());
+            }
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 let label = window.label();
                 if label == CHAT_WINDOW_LABEL
@@ -376,10 +1545,48 @@ pub fn run() {
                     || label == ZEN_WINDOW_LABEL
                 {
                     api.prevent_close();
-                    let _ = window.hide();
+                    // Without a tray a hidden chat window could not be brought back.
+                    if label == CHAT_WINDOW_LABEL
+                        && tray_support::trayless()
+                        && !tray_support::chosen()
+                    {
+                        let _ = window.minimize();
+                    } else {
+                        let _ = window.hide();
+                    }
                 }
+                tray_support::window_closed(window.app_handle(), label);
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running CrewHub application");
+        .build(tauri::generate_context!())
+        .expect("error while building CrewHub application")
+        .run(|app, event| {
+            // ── Links and files opened with CrewHub (macOS) ─────────────────
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = &event {
+                let files = bundle::handle_opened(app, url_scheme::handle_opened(app, urls));
+                if !files.is_empty() {
+                    share::handle_opened(app, &files);
+                }
+            }
+            // ── Dock icon clicked without a tray → chat window ───────────────
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Reopen {
+                has_visible_windows: false,
+                ..
+            } = &event
+            {
+                if tray_support::trayless() {
+                    open_or_focus_chat(app);
+                }
+            }
+            // ── App exit → stop child processes ─────────────────────────────
+            if let tauri::RunEvent::Exit = event {
+                local_model::shutdown(app);
+                backend_process::shutdown(app);
+                control_socket::stop();
+                browser::shutdown(app);
+                lan_sync::shutdown(app);
+            }
+        });
 }
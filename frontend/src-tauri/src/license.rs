@@ -0,0 +1,382 @@
+//! License and team activation.
+//!
+//! The user activates CrewHub with a license key or a token issued by their
+//! team's SSO. Either is validated against the license server
+//! (`CREWHUB_LICENSE_SERVER` overrides the default), which answers with the
+//! plan, team and entitlements. The credential and the last successful
+//! answer are kept in the OS keychain (Keychain, Credential Manager, Secret
+//! Service), never in the app data dir.
+//!
+//! The license is re-validated at startup and twice a day. While the server
+//! can't be reached the last answer stays in force for [`GRACE_PERIOD`]
+//! (state `grace`); after that, or once the license expires, entitlements
+//! are gone until the next successful validation. Team-only features ask
+//! [`require`] before they turn on; changes are emitted as `license-changed`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Entitlement: connecting more than the primary backend ([`crate::federation`]).
+pub const MULTIPLE_BACKENDS: &str = "multiple-backends";
+
+/// Entitlement: syncing read state and layouts between desktops ([`crate::lan_sync`]).
+pub const LAN_SYNC: &str = "lan-sync";
+
+const DEFAULT_SERVER: &str = "https://license.crewhub.dev";
+
+/// Environment override for the license server (staging, self-hosted).
+const SERVER_ENV: &str = "CREWHUB_LICENSE_SERVER";
+
+/// Keychain service and account of the stored license.
+const KEYCHAIN_SERVICE: &str = "dev.crewhub.app";
+const KEYCHAIN_ACCOUNT: &str = "license";
+
+/// How long the last validation holds while the server is unreachable.
+pub const GRACE_PERIOD: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+const REVALIDATE_INTERVAL: Duration = Duration::from_secs(12 * 60 * 60);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Stored credential and last server answer (mirrors the keychain entry).
+static STORED: Mutex<Option<Stored>> = Mutex::new(None);
+
+/// The last validation attempt could not reach the server.
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum Credential {
+    /// License key bought for a user or team.
+    Key(String),
+    /// Token issued by the team's SSO.
+    Sso(String),
+}
+
+impl Credential {
+    fn kind(&self) -> &'static str {
+        match self {
+            Credential::Key(_) => "key",
+            Credential::Sso(_) => "sso",
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct License {
+    plan: String,
+    team: Option<String>,
+    seats: Option<u32>,
+    entitlements: Vec<String>,
+    /// Unix seconds.
+    expires_at: Option<i64>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stored {
+    credential: Credential,
+    /// Last accepted answer; `None` when the server rejected the credential.
+    license: Option<License>,
+    /// Unix seconds of the last answer from the server.
+    validated_at: i64,
+    /// Why the server rejected the credential.
+    message: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LicenseState {
+    None,
+    Active,
+    /// Server unreachable; the last validation still holds.
+    Grace,
+    Expired,
+    Invalid,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseStatus {
+    state: LicenseState,
+    /// `key` or `sso`.
+    kind: Option<&'static str>,
+    plan: Option<String>,
+    team: Option<String>,
+    seats: Option<u32>,
+    /// Entitlements in force right now.
+    entitlements: Vec<String>,
+    expires_at: Option<i64>,
+    validated_at: Option<i64>,
+    /// End of the offline grace period (Unix seconds).
+    grace_until: Option<i64>,
+    message: Option<String>,
+}
+
+/// Server answer to a validation request.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Validation {
+    valid: bool,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(flatten)]
+    license: License,
+}
+
+fn now() -> i64 {
+    chrono::Local::now().timestamp()
+}
+
+fn server() -> String {
+    std::env::var(SERVER_ENV).unwrap_or_else(|_| DEFAULT_SERVER.to_string())
+}
+
+/// Run a keychain call on its own thread: it may wait on D-Bus or an OS
+/// prompt, and the Secret Service client must not run inside the async runtime.
+fn keychain<T: Send + 'static>(
+    f: impl FnOnce(keyring::Entry) -> keyring::Result<T> + Send + 'static,
+) -> Result<T, String> {
+    std::thread::spawn(move || {
+        let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)?;
+        f(entry)
+    })
+    .join()
+    .map_err(|_| "Keychain access failed".to_string())?
+    .map_err(|e| e.to_string())
+}
+
+fn load() -> Option<Stored> {
+    match keychain(|entry| entry.get_password()) {
+        Ok(raw) => serde_json::from_str(&raw).ok(),
+        Err(e) => {
+            if !e.contains("No matching entry") {
+                eprintln!("[CrewHub] Failed to read license from keychain: {}", e);
+            }
+            None
+        }
+    }
+}
+
+fn save(stored: &Stored) -> Result<(), String> {
+    let raw = serde_json::to_string(stored).map_err(|e| e.to_string())?;
+    keychain(move |entry| entry.set_password(&raw))
+}
+
+fn current() -> Option<Stored> {
+    STORED.lock().ok().and_then(|stored| stored.clone())
+}
+
+fn state_of(stored: &Stored) -> LicenseState {
+    let Some(license) = &stored.license else {
+        return LicenseState::Invalid;
+    };
+    let now = now();
+    if license.expires_at.is_some_and(|expires| expires <= now)
+        || now > stored.validated_at + GRACE_PERIOD.as_secs() as i64
+    {
+        LicenseState::Expired
+    } else if OFFLINE.load(Ordering::Relaxed) {
+        LicenseState::Grace
+    } else {
+        LicenseState::Active
+    }
+}
+
+fn status() -> LicenseStatus {
+    let Some(stored) = current() else {
+        return LicenseStatus {
+            state: LicenseState::None,
+            kind: None,
+            plan: None,
+            team: None,
+            seats: None,
+            entitlements: Vec::new(),
+            expires_at: None,
+            validated_at: None,
+            grace_until: None,
+            message: None,
+        };
+    };
+    let state = state_of(&stored);
+    let license = stored.license.clone().unwrap_or_default();
+    let in_force = matches!(state, LicenseState::Active | LicenseState::Grace);
+    LicenseStatus {
+        state,
+        kind: Some(stored.credential.kind()),
+        plan: stored.license.is_some().then(|| license.plan.clone()),
+        team: license.team.clone(),
+        seats: license.seats,
+        entitlements: if in_force {
+            license.entitlements.clone()
+        } else {
+            Vec::new()
+        },
+        expires_at: license.expires_at,
+        validated_at: Some(stored.validated_at),
+        grace_until: (state == LicenseState::Grace)
+            .then(|| stored.validated_at + GRACE_PERIOD.as_secs() as i64),
+        message: stored.message.clone(),
+    }
+}
+
+/// Whether the license in force grants `entitlement`.
+pub fn entitled(entitlement: &str) -> bool {
+    status().entitlements.iter().any(|e| e == entitlement)
+}
+
+/// Err with a user-facing message unless the license grants `entitlement`.
+pub fn require(entitlement: &str) -> Result<(), String> {
+    if entitled(entitlement) {
+        Ok(())
+    } else {
+        Err("This feature needs a CrewHub Team license".to_string())
+    }
+}
+
+/// Ask the server about a credential (blocking). `Err` means unreachable.
+fn validate(credential: &Credential) -> Result<Validation, String> {
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+    let url = format!("{}/v1/licenses/validate", server().trim_end_matches('/'));
+    let request = agent.post(&url).set("Content-Type", "application/json");
+    let body = json!({ "app": "crewhub-desktop", "os": std::env::consts::OS });
+    let result = match credential {
+        Credential::Key(key) => {
+            let mut body = body;
+            body["key"] = Value::String(key.clone());
+            request.send_string(&body.to_string())
+        }
+        Credential::Sso(token) => request
+            .set("Authorization", &format!("Bearer {}", token))
+            .send_string(&body.to_string()),
+    };
+    let response = match result {
+        Ok(response) => response,
+        // Rejections come back as 4xx with the usual body.
+        Err(ureq::Error::Status(code, response)) if (400..500).contains(&code) => response,
+        Err(e) => return Err(format!("License server unreachable: {}", e)),
+    };
+    serde_json::from_reader(response.into_reader())
+        .map_err(|e| format!("Invalid license server response: {}", e))
+}
+
+/// Validate `credential` and store the outcome (blocking).
+fn refresh<R: Runtime>(app: &AppHandle<R>, credential: Credential) -> Result<(), String> {
+    let validation = validate(&credential);
+    OFFLINE.store(validation.is_err(), Ordering::Relaxed);
+    let stored = match validation {
+        Ok(validation) => Stored {
+            credential,
+            message: (!validation.valid).then(|| {
+                validation
+                    .message
+                    .unwrap_or_else(|| "License not accepted".to_string())
+            }),
+            license: validation.valid.then_some(validation.license),
+            validated_at: now(),
+        },
+        // Keep the last answer; the grace period runs from it.
+        Err(e) => {
+            eprintln!("[CrewHub] {}", e);
+            match current() {
+                Some(stored) => stored,
+                None => return Err(e),
+            }
+        }
+    };
+    save(&stored)?;
+    if let Ok(mut current) = STORED.lock() {
+        *current = Some(stored);
+    }
+    let _ = app.emit("license-changed", status());
+    Ok(())
+}
+
+/// Load the stored license and keep it validated in the background.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let stored = load();
+    if let Ok(mut current) = STORED.lock() {
+        *current = stored;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        if let Some(stored) = current() {
+            if let Err(e) = refresh(&app, stored.credential) {
+                eprintln!("[CrewHub] License check failed: {}", e);
+            }
+        }
+        std::thread::sleep(REVALIDATE_INTERVAL);
+    });
+}
+
+/// Tauri command: the license in force and its entitlements.
+/// Called from the frontend via `invoke('get_license')`.
+#[tauri::command]
+pub fn get_license() -> LicenseStatus {
+    status()
+}
+
+/// Tauri command: whether the license grants a feature.
+/// Called from the frontend via `invoke('has_entitlement', { entitlement })`.
+#[tauri::command]
+pub fn has_entitlement(entitlement: String) -> bool {
+    entitled(&entitlement)
+}
+
+/// Tauri command: activate with a license key or SSO token; fails if the
+/// server can't be reached or rejects it (a rejected credential is not kept).
+/// Called from the frontend via `invoke('activate_license', { credential })`
+/// with `{ kind: 'key' | 'sso', value }`.
+#[tauri::command]
+pub async fn activate_license(
+    credential: Credential,
+    app: AppHandle,
+) -> Result<LicenseStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let validation = validate(&credential)?;
+        OFFLINE.store(false, Ordering::Relaxed);
+        if !validation.valid {
+            return Err(validation
+                .message
+                .unwrap_or_else(|| "License not accepted".to_string()));
+        }
+        let stored = Stored {
+            credential,
+            license: Some(validation.license),
+            validated_at: now(),
+            message: None,
+        };
+        save(&stored)?;
+        if let Ok(mut current) = STORED.lock() {
+            *current = Some(stored);
+        }
+        let status = status();
+        let _ = app.emit("license-changed", &status);
+        Ok(status)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: remove the license from this computer.
+/// Called from the frontend via `invoke('deactivate_license')`.
+#[tauri::command]
+pub fn deactivate_license(app: AppHandle) -> Result<LicenseStatus, String> {
+    if let Err(e) = keychain(|entry| entry.delete_credential()) {
+        if !e.contains("No matching entry") {
+            return Err(e);
+        }
+    }
+    if let Ok(mut current) = STORED.lock() {
+        *current = None;
+    }
+    OFFLINE.store(false, Ordering::Relaxed);
+    let status = status();
+    let _ = app.emit("license-changed", &status);
+    Ok(status)
+}
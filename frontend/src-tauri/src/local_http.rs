@@ -0,0 +1,127 @@
+//! Small helpers shared by the localhost HTTP endpoints the app hosts.
+//!
+//! All of them bind to 127.0.0.1 only and require a bearer token, because any
+//! local process (including a browser tab) can reach a loopback port.
+
+use serde_json::Value;
+use std::io::Read;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tiny_http::{Header, Request, Response};
+
+/// Largest request body accepted by local endpoints.
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// Generate a random bearer token for a local endpoint.
+pub fn new_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Check the `Authorization: Bearer <token>` header (or `X-CrewHub-Token`).
+pub fn is_authorized(request: &Request, token: &str) -> bool {
+    request.headers().iter().any(|h| {
+        let value = h.value.as_str();
+        if h.field.equiv("Authorization") {
+            value
+                .strip_prefix("Bearer ")
+                .is_some_and(|v| token_matches(v.trim(), token))
+        } else if h.field.equiv("X-CrewHub-Token") {
+            token_matches(value.trim(), token)
+        } else {
+            false
+        }
+    })
+}
+
+/// Compare a presented token with the expected one in constant time, so the
+/// response time doesn't reveal how much of a guess was right.
+pub fn token_matches(given: &str, expected: &str) -> bool {
+    if given.len() != expected.len() || expected.is_empty() {
+        return false;
+    }
+    given
+        .bytes()
+        .zip(expected.bytes())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+/// Value of a `token` query parameter, for clients that cannot set headers
+/// (`EventSource`). Only use it for endpoints that need it.
+pub fn query_token(query: &str) -> Option<String> {
+    url::form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == "token")
+        .map(|(_, value)| value.into_owned())
+}
+
+/// Read and parse the request body as JSON (an empty body parses as `null`).
+pub fn read_json_body(request: &mut Request) -> Result<Value, String> {
+    let mut raw = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES as u64 + 1)
+        .read_to_string(&mut raw)
+        .map_err(|e| e.to_string())?;
+    if raw.len() > MAX_BODY_BYTES {
+        return Err("Request body too large".to_string());
+    }
+    if raw.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(&raw).map_err(|e| format!("Invalid JSON: {}", e))
+}
+
+/// Respond with a JSON body and status code, logging (not propagating) IO errors.
+pub fn respond_json(request: Request, status: u16, body: &Value) {
+    let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header is valid");
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header);
+    if let Err(e) = request.respond(response) {
+        eprintln!("[CrewHub] Failed to send local HTTP response: {}", e);
+    }
+}
+
+/// Respond with an empty body and status code.
+pub fn respond_empty(request: Request, status: u16) {
+    if let Err(e) = request.respond(Response::empty(status)) {
+        eprintln!("[CrewHub] Failed to send local HTTP response: {}", e);
+    }
+}
+
+/// A running 127.0.0.1 listener and the thread accepting its requests.
+pub struct Listener {
+    server: Arc<tiny_http::Server>,
+    worker: JoinHandle<()>,
+    pub port: u16,
+}
+
+impl Listener {
+    /// Bind 127.0.0.1:`port` and pass each request to `handle` on the
+    /// accept thread (long-running work should spawn its own).
+    pub fn start(port: u16, handle: impl Fn(Request) + Send + 'static) -> Result<Self, String> {
+        let server = Arc::new(
+            tiny_http::Server::http(("127.0.0.1", port))
+                .map_err(|e| format!("Cannot bind port {}: {}", port, e))?,
+        );
+        let accepting = server.clone();
+        let worker = std::thread::spawn(move || {
+            for request in accepting.incoming_requests() {
+                handle(request);
+            }
+        });
+        Ok(Self {
+            server,
+            worker,
+            port,
+        })
+    }
+
+    /// Stop accepting and wait for the accept thread, so the last reference
+    /// to the server (and with it the socket) is gone when this returns.
+    pub fn stop(self) {
+        self.server.unblock();
+        let _ = self.worker.join();
+    }
+}
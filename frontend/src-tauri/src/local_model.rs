@@ -0,0 +1,397 @@
+//! Optional local model runner (llama.cpp `llama-server` sidecar).
+//!
+//! Downloads GGUF models into the app data dir, starts/stops a
+//! `llama-server` process on localhost, monitors its health, and advertises
+//! the OpenAI-compatible endpoint to every connected backend via
+//! `POST /api/local-models/endpoint`, so crews can run fully offline.
+//! Off by default; nothing is spawned until the user starts it. On a
+//! metered connection downloads wait unless the user allows them anyway.
+
+use crate::child_processes::{self, Kind};
+use crate::{backend, bandwidth, federation, store};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "local-model.json";
+
+const DEFAULT_PORT: u16 = 8095;
+
+/// How often the monitor checks that the sidecar is still alive.
+const HEALTH_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LocalModelConfig {
+    /// Start the runner automatically on launch.
+    pub autostart: bool,
+    /// Path to `llama-server` (or anything with the same CLI); looked up on PATH if unset.
+    pub binary_path: Option<String>,
+    /// Model file name inside the models directory.
+    pub model: Option<String>,
+    pub port: u16,
+    pub context_size: u32,
+    /// Layers to offload to the GPU (`-ngl`); `None` lets the runner decide.
+    pub gpu_layers: Option<u32>,
+}
+
+impl Default for LocalModelConfig {
+    fn default() -> Self {
+        Self {
+            autostart: false,
+            binary_path: None,
+            model: None,
+            port: DEFAULT_PORT,
+            context_size: 4096,
+            gpu_layers: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModelStatus {
+    #[serde(flatten)]
+    config: LocalModelConfig,
+    running: bool,
+    healthy: bool,
+    /// OpenAI-compatible base URL while running.
+    endpoint: Option<String>,
+    models_dir: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModelFile {
+    file_name: String,
+    size_bytes: u64,
+}
+
+/// App state: runner config and the sidecar process (if running).
+pub struct LocalModelState {
+    config: Mutex<LocalModelConfig>,
+    child: Mutex<Option<Child>>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: LocalModelConfig = store::load_json(app, CONFIG_FILE);
+    let autostart = config.autostart;
+    app.manage(LocalModelState {
+        config: Mutex::new(config),
+        child: Mutex::new(None),
+    });
+    if autostart {
+        if let Err(e) = start(app) {
+            eprintln!("[CrewHub] Failed to start local model runner: {}", e);
+        }
+    }
+    let app = app.clone();
+    std::thread::spawn(move || monitor(&app));
+}
+
+/// Stop the sidecar when the app exits so it doesn't outlive us.
+pub fn shutdown<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(state) = app.try_state::<LocalModelState>() {
+        let _ = stop(app, &state);
+    }
+}
+
+fn models_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("models");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// Only plain file names are accepted for models (no paths).
+fn model_path<R: Runtime>(app: &AppHandle<R>, file_name: &str) -> Result<PathBuf, String> {
+    if file_name.is_empty() || file_name.contains(['/', '\\']) || file_name.starts_with('.') {
+        return Err(format!("Invalid model file name: {}", file_name));
+    }
+    Ok(models_dir(app)?.join(file_name))
+}
+
+fn endpoint(port: u16) -> String {
+    format!("http://127.0.0.1:{}/v1", port)
+}
+
+/// OpenAI-compatible base URL of the runner, if it is running.
+pub fn running_endpoint<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    let state = app.try_state::<LocalModelState>()?;
+    if state.child.lock().ok()?.is_none() {
+        return None;
+    }
+    let port = state.config.lock().ok()?.port;
+    Some(endpoint(port))
+}
+
+fn start<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<LocalModelState>();
+    let mut child = state.child.lock().map_err(|e| e.to_string())?;
+    if child.is_some() {
+        return Ok(());
+    }
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let model = config
+        .model
+        .as_deref()
+        .ok_or_else(|| "No model selected".to_string())?;
+    let model_path = model_path(app, model)?;
+    if !model_path.exists() {
+        return Err(format!("Model not downloaded: {}", model));
+    }
+
+    let mut cmd = Command::new(config.binary_path.as_deref().unwrap_or("llama-server"));
+    cmd.arg("--model")
+        .arg(&model_path)
+        .args(["--host", "127.0.0.1"])
+        .args(["--port", &config.port.to_string()])
+        .args(["--ctx-size", &config.context_size.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(layers) = config.gpu_layers {
+        cmd.args(["--n-gpu-layers", &layers.to_string()]);
+    }
+    let spawned = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start llama-server: {}", e))?;
+    child_processes::register(
+        spawned.id(),
+        Kind::LocalModel,
+        model,
+        config.binary_path.as_deref().unwrap_or("llama-server"),
+    );
+    *child = Some(spawned);
+    println!(
+        "[CrewHub] Local model runner started on {}",
+        endpoint(config.port)
+    );
+    drop(child);
+
+    advertise(app, Some((&config, model)));
+    Ok(())
+}
+
+fn stop<R: Runtime>(app: &AppHandle<R>, state: &LocalModelState) -> Result<(), String> {
+    let Some(mut child) = state.child.lock().map_err(|e| e.to_string())?.take() else {
+        return Ok(());
+    };
+    child_processes::unregister(child.id());
+    let _ = child.kill();
+    let _ = child.wait();
+    advertise(app, None);
+    Ok(())
+}
+
+/// Tell every connected backend where the local model lives (or that it's gone).
+fn advertise<R: Runtime>(app: &AppHandle<R>, running: Option<(&LocalModelConfig, &str)>) {
+    let body = match running {
+        Some((config, model)) => json!({
+            "running": true,
+            "baseUrl": endpoint(config.port),
+            "model": model,
+            "contextSize": config.context_size,
+        }),
+        None => json!({ "running": false }),
+    };
+    let _ = app.emit("local-model-changed", &body);
+    let backends = federation::enabled(app);
+    std::thread::spawn(move || {
+        for backend in backends {
+            if let Err(e) = backend::post_json_to(&backend.url, "/api/local-models/endpoint", &body)
+            {
+                eprintln!(
+                    "[CrewHub] Failed to advertise local model to {}: {}",
+                    backend.name, e
+                );
+            }
+        }
+    });
+}
+
+fn healthy(port: u16) -> bool {
+    ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(2))
+        .build()
+        .get(&format!("http://127.0.0.1:{}/health", port))
+        .call()
+        .is_ok()
+}
+
+/// Detect a sidecar that exited on its own (crash, OOM) and report it.
+fn monitor<R: Runtime>(app: &AppHandle<R>) {
+    loop {
+        std::thread::sleep(HEALTH_INTERVAL);
+        let state = app.state::<LocalModelState>();
+        let exited = match state.child.lock() {
+            Ok(mut child) => match child.as_mut().map(|c| c.try_wait()) {
+                Some(Ok(Some(status))) => {
+                    eprintln!("[CrewHub] Local model runner exited: {}", status);
+                    if let Some(exited) = child.take() {
+                        child_processes::unregister(exited.id());
+                    }
+                    true
+                }
+                _ => false,
+            },
+            Err(_) => false,
+        };
+        if exited {
+            advertise(app, None);
+        }
+    }
+}
+
+fn status<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &LocalModelState,
+) -> Result<LocalModelStatus, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let running = state.child.lock().map_err(|e| e.to_string())?.is_some();
+    Ok(LocalModelStatus {
+        healthy: running && healthy(config.port),
+        endpoint: running.then(|| endpoint(config.port)),
+        models_dir: models_dir(app)?.display().to_string(),
+        config,
+        running,
+    })
+}
+
+/// Tauri command: runner config, process state, and endpoint.
+/// Called from the frontend via `invoke('get_local_model_status')`.
+#[tauri::command]
+pub async fn get_local_model_status(app: AppHandle) -> Result<LocalModelStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || status(&app, &app.state::<LocalModelState>()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: replace the runner config (takes effect on next start).
+/// Called from the frontend via `invoke('configure_local_model', { config })`.
+#[tauri::command]
+pub fn configure_local_model(
+    config: LocalModelConfig,
+    app: AppHandle,
+    state: State<LocalModelState>,
+) -> Result<(), String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    *state.config.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
+
+/// Tauri command: downloaded model files.
+/// Called from the frontend via `invoke('list_local_models')`.
+#[tauri::command]
+pub fn list_local_models(app: AppHandle) -> Result<Vec<LocalModelFile>, String> {
+    let mut models = Vec::new();
+    for entry in std::fs::read_dir(models_dir(&app)?).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name.ends_with(".gguf") {
+            models.push(LocalModelFile {
+                file_name,
+                size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            });
+        }
+    }
+    models.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    Ok(models)
+}
+
+/// Tauri command: download a GGUF model, emitting `local-model-download` progress events.
+/// On a metered connection it waits (a `deferred` event) unless `allowMetered` is set.
+/// Called from the frontend via `invoke('download_local_model', { url, fileName, allowMetered })`.
+#[tauri::command]
+pub async fn download_local_model(
+    url: String,
+    file_name: String,
+    allow_metered: Option<bool>,
+    app: AppHandle,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        download(&app, &url, &file_name, allow_metered.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn download(
+    app: &AppHandle,
+    url: &str,
+    file_name: &str,
+    allow_metered: bool,
+) -> Result<(), String> {
+    let target = model_path(app, file_name)?;
+    if !allow_metered {
+        bandwidth::wait_unmetered(|| {
+            let _ = app.emit(
+                "local-model-download",
+                json!({ "fileName": file_name, "deferred": true }),
+            );
+        });
+    }
+    // Download next to the target and rename at the end, so a partial file is never loaded.
+    let partial = target.with_extension("part");
+    let response = ureq::get(url)
+        .call()
+        .map_err(|e| format!("Download failed: {}", e))?;
+    let total: Option<u64> = response
+        .header("Content-Length")
+        .and_then(|v| v.parse().ok());
+    let mut reader = bandwidth::counted(url, 0, response.into_reader());
+    let mut file = std::fs::File::create(&partial).map_err(|e| e.to_string())?;
+
+    let mut buf = vec![0u8; 1024 * 1024];
+    let mut downloaded: u64 = 0;
+    loop {
+        let n = reader.read(&mut buf).map_err(|e| {
+            let _ = std::fs::remove_file(&partial);
+            format!("Download interrupted: {}", e)
+        })?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buf[..n]).map_err(|e| e.to_string())?;
+        downloaded += n as u64;
+        let _ = app.emit(
+            "local-model-download",
+            json!({ "fileName": file_name, "downloaded": downloaded, "total": total }),
+        );
+    }
+    file.flush().map_err(|e| e.to_string())?;
+    std::fs::rename(&partial, &target).map_err(|e| e.to_string())
+}
+
+/// Tauri command: start the runner with the configured model.
+/// Called from the frontend via `invoke('start_local_model')`.
+#[tauri::command]
+pub async fn start_local_model(app: AppHandle) -> Result<LocalModelStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        start(&app)?;
+        status(&app, &app.state::<LocalModelState>())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: stop the runner.
+/// Called from the frontend via `invoke('stop_local_model')`.
+#[tauri::command]
+pub async fn stop_local_model(app: AppHandle) -> Result<LocalModelStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<LocalModelState>();
+        stop(&app, &state)?;
+        status(&app, &state)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
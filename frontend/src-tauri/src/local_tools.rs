@@ -0,0 +1,465 @@
+//! Local desktop capabilities (files, shell, screenshots, OCR, clipboard).
+//!
+//! These are the things only the desktop app can do on the user's machine.
+//! They are exposed to agents through the embedded MCP server and, where it
+//! makes sense, directly to the frontend as Tauri commands. Paths given to
+//! the tools must lie inside one of the project folders the user shared.
+//! The check runs after symlinks are resolved.
+
+use crate::{browser, embeddings, ocr};
+use base64::Engine;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Files larger than this are refused by `read_file` (agents should not pull binaries).
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+
+/// Description of a local tool as advertised to MCP clients.
+pub struct ToolSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Dangerous tools (shell, writes) start disabled until the user opts in.
+    pub default_enabled: bool,
+    pub input_schema: fn() -> Value,
+}
+
+/// All local tools, in the order they are listed to clients.
+pub fn tool_specs() -> Vec<ToolSpec> {
+    vec![
+        ToolSpec {
+            name: "read_file",
+            description: "Read a UTF-8 text file from the user's machine.",
+            default_enabled: true,
+            input_schema: || path_schema("Absolute path of the file to read"),
+        },
+        ToolSpec {
+            name: "list_directory",
+            description: "List the entries of a directory on the user's machine.",
+            default_enabled: true,
+            input_schema: || path_schema("Absolute path of the directory to list"),
+        },
+        ToolSpec {
+            name: "write_file",
+            description:
+                "Write a UTF-8 text file on the user's machine, replacing any existing content.",
+            default_enabled: false,
+            input_schema: || {
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string", "description": "Absolute path of the file to write" },
+                        "content": { "type": "string" }
+                    },
+                    "required": ["path", "content"]
+                })
+            },
+        },
+        ToolSpec {
+            name: "run_shell_command",
+            description: "Run a shell command and return its exit code, stdout and stderr.",
+            default_enabled: false,
+            input_schema: || {
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "command": { "type": "string" },
+                        "cwd": { "type": "string", "description": "Working directory (optional)" }
+                    },
+                    "required": ["command"]
+                })
+            },
+        },
+        ToolSpec {
+            name: "capture_screenshot",
+            description: "Capture a screenshot of the user's screen as a PNG image, optionally with the text recognized in it.",
+            default_enabled: true,
+            input_schema: || {
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "ocr": { "type": "boolean", "description": "Also return the recognized text" },
+                        "textOnly": { "type": "boolean", "description": "Return only the recognized text, not the image" }
+                    }
+                })
+            },
+        },
+        ToolSpec {
+            name: "recognize_text",
+            description: "Recognize the text in an image file on the user's machine (OCR).",
+            default_enabled: true,
+            input_schema: || path_schema("Absolute path of the image"),
+        },
+        ToolSpec {
+            name: "read_clipboard",
+            description: "Read the current text content of the clipboard.",
+            default_enabled: true,
+            input_schema: || json!({ "type": "object", "properties": {} }),
+        },
+        ToolSpec {
+            name: "write_clipboard",
+            description: "Replace the clipboard content with the given text.",
+            default_enabled: false,
+            input_schema: || {
+                json!({
+                    "type": "object",
+                    "properties": { "text": { "type": "string" } },
+                    "required": ["text"]
+                })
+            },
+        },
+        ToolSpec {
+            name: "embed_files",
+            description: "Index text files into the desktop's embedding cache for a project (unchanged content is not re-embedded).",
+            default_enabled: true,
+            input_schema: || {
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "project": { "type": "string" },
+                        "paths": { "type": "array", "items": { "type": "string" }, "description": "Absolute file paths" }
+                    },
+                    "required": ["project", "paths"]
+                })
+            },
+        },
+        ToolSpec {
+            name: "similarity_search",
+            description: "Find the file chunks most relevant to a query in a project's embedding cache.",
+            default_enabled: true,
+            input_schema: || {
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "project": { "type": "string" },
+                        "query": { "type": "string" },
+                        "limit": { "type": "integer", "description": "Maximum results (default 10)" }
+                    },
+                    "required": ["project", "query"]
+                })
+            },
+        },
+        ToolSpec {
+            name: "browser_navigate",
+            description: "Open a web page in CrewHub's automated browser (the user must consent per domain). Returns the final URL and title.",
+            default_enabled: false,
+            input_schema: || {
+                json!({
+                    "type": "object",
+                    "properties": { "url": { "type": "string", "description": "http(s) URL" } },
+                    "required": ["url"]
+                })
+            },
+        },
+        ToolSpec {
+            name: "browser_extract",
+            description: "Get the visible text of the page open in the automated browser, or of the first element matching a CSS selector.",
+            default_enabled: false,
+            input_schema: || {
+                json!({
+                    "type": "object",
+                    "properties": { "selector": { "type": "string", "description": "CSS selector (optional)" } }
+                })
+            },
+        },
+        ToolSpec {
+            name: "browser_screenshot",
+            description: "Capture the viewport of the page open in the automated browser as a PNG image.",
+            default_enabled: false,
+            input_schema: || json!({ "type": "object", "properties": {} }),
+        },
+    ]
+}
+
+fn path_schema(description: &str) -> Value {
+    json!({
+        "type": "object",
+        "properties": { "path": { "type": "string", "description": description } },
+        "required": ["path"]
+    })
+}
+
+/// Execute a local tool and return MCP content items (`{ type: "text" | "image", ... }`).
+/// File paths are confined to `roots`.
+pub fn call_tool<R: Runtime>(
+    app: &AppHandle<R>,
+    name: &str,
+    args: &Value,
+    roots: &[PathBuf],
+) -> Result<Vec<Value>, String> {
+    match name {
+        "read_file" => {
+            let path = confine(roots, str_arg(args, "path")?)?;
+            let size = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+            if size > MAX_READ_BYTES {
+                return Err(format!(
+                    "File is too large ({} bytes, limit {})",
+                    size, MAX_READ_BYTES
+                ));
+            }
+            let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            Ok(vec![text_content(content)])
+        }
+        "list_directory" => {
+            let mut lines = Vec::new();
+            let dir = confine(roots, str_arg(args, "path")?)?;
+            for entry in std::fs::read_dir(dir).map_err(|e| e.to_string())? {
+                let entry = entry.map_err(|e| e.to_string())?;
+                let suffix = if entry.path().is_dir() { "/" } else { "" };
+                lines.push(format!("{}{}", entry.file_name().to_string_lossy(), suffix));
+            }
+            lines.sort();
+            Ok(vec![text_content(lines.join("\n"))])
+        }
+        "write_file" => {
+            let path = confine(roots, str_arg(args, "path")?)?;
+            std::fs::write(&path, str_arg(args, "content")?).map_err(|e| e.to_string())?;
+            Ok(vec![text_content(format!("Wrote {}", path.display()))])
+        }
+        "run_shell_command" => {
+            let output = shell_command(
+                str_arg(args, "command")?,
+                args.get("cwd").and_then(Value::as_str),
+            )?;
+            Ok(vec![text_content(format!(
+                "exit code: {}\n--- stdout ---\n{}\n--- stderr ---\n{}",
+                output.status.code().unwrap_or(-1),
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ))])
+        }
+        "capture_screenshot" => {
+            let path = take_screenshot(app)?;
+            let flag = |name| args.get(name).and_then(Value::as_bool).unwrap_or(false);
+            let mut content = Vec::new();
+            if flag("ocr") || flag("textOnly") {
+                content.push(text_content(ocr::recognize(&path)?));
+            }
+            if !flag("textOnly") {
+                let bytes = std::fs::read(&path).map_err(|e| e.to_string())?;
+                content.push(json!({
+                    "type": "image",
+                    "mimeType": "image/png",
+                    "data": base64::engine::general_purpose::STANDARD.encode(bytes),
+                }));
+            }
+            Ok(content)
+        }
+        "recognize_text" => {
+            let text = ocr::recognize(&confine(roots, str_arg(args, "path")?)?)?;
+            Ok(vec![text_content(text)])
+        }
+        "read_clipboard" => {
+            let text = app.clipboard().read_text().map_err(|e| e.to_string())?;
+            Ok(vec![text_content(text)])
+        }
+        "write_clipboard" => {
+            app.clipboard()
+                .write_text(str_arg(args, "text")?.to_string())
+                .map_err(|e| e.to_string())?;
+            Ok(vec![text_content("Clipboard updated".to_string())])
+        }
+        "embed_files" => {
+            let paths: Vec<String> = args
+                .get("paths")
+                .and_then(Value::as_array)
+                .map(|a| {
+                    a.iter()
+                        .filter_map(Value::as_str)
+                        .map(|path| Ok(confine(roots, path)?.to_string_lossy().into_owned()))
+                        .collect::<Result<_, String>>()
+                })
+                .transpose()?
+                .unwrap_or_default();
+            let report = embeddings::embed_and_store_files(app, str_arg(args, "project")?, &paths)?;
+            Ok(vec![text_content(
+                serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?,
+            )])
+        }
+        "similarity_search" => {
+            let limit = args.get("limit").and_then(Value::as_u64).unwrap_or(10) as usize;
+            let hits = embeddings::search(
+                app,
+                str_arg(args, "project")?,
+                str_arg(args, "query")?,
+                limit,
+            )?;
+            Ok(vec![text_content(
+                serde_json::to_string_pretty(&hits).map_err(|e| e.to_string())?,
+            )])
+        }
+        "browser_navigate" => {
+            let page = browser::navigate(app, str_arg(args, "url")?)?;
+            Ok(vec![text_content(page.to_string())])
+        }
+        "browser_extract" => {
+            let selector = args.get("selector").and_then(Value::as_str);
+            Ok(vec![text_content(browser::extract(app, selector)?)])
+        }
+        "browser_screenshot" => Ok(vec![json!({
+            "type": "image",
+            "mimeType": "image/png",
+            "data": browser::screenshot(app)?,
+        })]),
+        other => Err(format!("Unknown tool: {}", other)),
+    }
+}
+
+/// `path` resolved, if it lies inside one of `roots`. A file that doesn't
+/// exist yet (for `write_file`) is checked by its parent folder.
+fn confine(roots: &[PathBuf], path: &str) -> Result<PathBuf, String> {
+    if roots.is_empty() {
+        return Err("No project folders are shared with agents; add one in settings".to_string());
+    }
+    let path = Path::new(path);
+    let resolved = match path.canonicalize() {
+        Ok(resolved) => resolved,
+        Err(_) => {
+            let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+                return Err(format!("Invalid path: {}", path.display()));
+            };
+            parent.canonicalize().map_err(|e| e.to_string())?.join(name)
+        }
+    };
+    let inside = roots.iter().any(|root| {
+        root.canonicalize()
+            .is_ok_and(|root| resolved.starts_with(root))
+    });
+    if inside {
+        Ok(resolved)
+    } else {
+        Err(format!(
+            "{} is outside the project folders shared with agents",
+            path.display()
+        ))
+    }
+}
+
+fn text_content(text: String) -> Value {
+    json!({ "type": "text", "text": text })
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Result<&'a str, String> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("Missing string argument '{}'", key))
+}
+
+fn shell_command(command: &str, cwd: Option<&str>) -> Result<std::process::Output, String> {
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    if let Some(dir) = cwd {
+        cmd.current_dir(dir);
+    }
+    cmd.output()
+        .map_err(|e| format!("Failed to run command: {}", e))
+}
+
+/// Capture the whole screen into a PNG in the app cache dir and return its path.
+///
+/// Uses the platform's own screenshot tooling rather than a capture library,
+/// so OS-level screen recording permissions apply exactly as for the user.
+pub fn take_screenshot<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("screenshots");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let path = dir.join(format!("screenshot-{}.png", millis));
+
+    run_screenshot_tool(&path)?;
+    if !path.exists() {
+        return Err("Screenshot tool did not produce an image".to_string());
+    }
+    Ok(path)
+}
+
+#[cfg(target_os = "macos")]
+fn run_screenshot_tool(path: &Path) -> Result<(), String> {
+    let status = Command::new("screencapture")
+        .arg("-x")
+        .arg(path)
+        .status()
+        .map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("screencapture failed (is Screen Recording permission granted?)".to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn run_screenshot_tool(path: &Path) -> Result<(), String> {
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms,System.Drawing; \
+         $b = [System.Windows.Forms.SystemInformation]::VirtualScreen; \
+         $bmp = New-Object System.Drawing.Bitmap $b.Width, $b.Height; \
+         $g = [System.Drawing.Graphics]::FromImage($bmp); \
+         $g.CopyFromScreen($b.Left, $b.Top, 0, 0, $bmp.Size); \
+         $bmp.Save('{}', [System.Drawing.Imaging.ImageFormat]::Png)",
+        path.display().to_string().replace('\'', "''")
+    );
+    let status = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .status()
+        .map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("PowerShell screen capture failed".to_string())
+    }
+}
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+fn run_screenshot_tool(path: &Path) -> Result<(), String> {
+    // Wayland compositors need grim; X11 sessions usually have one of the others.
+    let candidates: [(&str, &[&str]); 3] = [
+        ("grim", &[]),
+        ("gnome-screenshot", &["-f"]),
+        ("import", &["-window", "root"]),
+    ];
+    #[cfg(target_os = "linux")]
+    if crate::portal::sandbox().is_some() {
+        // The host's tools aren't reachable from the sandbox.
+        return crate::portal::screenshot(path);
+    }
+    for (program, args) in candidates {
+        if let Ok(status) = Command::new(program).args(args).arg(path).status() {
+            if status.success() {
+                return Ok(());
+            }
+        }
+    }
+    #[cfg(target_os = "linux")]
+    if crate::portal::status().screenshot {
+        return crate::portal::screenshot(path);
+    }
+    Err("No screenshot tool found (install grim, gnome-screenshot or ImageMagick)".to_string())
+}
+
+/// Tauri command: capture a screenshot and return the PNG path.
+/// Called from the frontend via `invoke('capture_screenshot')`.
+#[tauri::command]
+pub async fn capture_screenshot(app: AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || take_screenshot(&app))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|path| path.display().to_string())
+}
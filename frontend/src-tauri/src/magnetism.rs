@@ -0,0 +1,238 @@
+//! Window magnetism between the chat and world windows.
+//!
+//! While one of the two is dragged, an edge that comes within a few pixels
+//! of the other window's opposite edge snaps onto it (and, once they sit
+//! side by side, the top or bottom edges line up as well). With
+//! `moveTogether`, dragging the world window takes a chat window docked to
+//! it along; dragging the chat window away undocks it. Edges are compared on
+//! the visible frame ([`snap::invisible_borders`] on Windows). Wayland
+//! doesn't report or accept window positions, so nothing happens there.
+
+use crate::{snap, store, wayland};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, PhysicalPosition, Runtime, Window, WindowEvent};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "magnetism.json";
+
+/// Dragging this window moves a docked [`FOLLOWER`] along.
+const LEADER: &str = crate::WORLD_WINDOW_LABEL;
+const FOLLOWER: &str = crate::CHAT_WINDOW_LABEL;
+
+static CONFIG: Mutex<Option<MagnetismConfig>> = Mutex::new(None);
+
+/// Last known position per window, to turn move events into deltas.
+static POSITIONS: Mutex<Option<HashMap<String, PhysicalPosition<i32>>>> = Mutex::new(None);
+
+/// Positions we set ourselves; their move events are not treated as drags.
+static PLACED: Mutex<Option<HashMap<String, PhysicalPosition<i32>>>> = Mutex::new(None);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MagnetismConfig {
+    pub enabled: bool,
+    /// Snap distance in logical pixels.
+    pub distance: f64,
+    /// Move a docked chat window along with the world window.
+    pub move_together: bool,
+}
+
+impl Default for MagnetismConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            distance: 16.0,
+            move_together: true,
+        }
+    }
+}
+
+/// Visible frame in physical pixels.
+#[derive(Clone, Copy)]
+struct Rect {
+    left: i32,
+    top: i32,
+    right: i32,
+    bottom: i32,
+}
+
+impl Rect {
+    fn shifted(self, dx: i32, dy: i32) -> Rect {
+        Rect {
+            left: self.left + dx,
+            top: self.top + dy,
+            right: self.right + dx,
+            bottom: self.bottom + dy,
+        }
+    }
+
+    fn overlaps_vertically(&self, other: &Rect) -> bool {
+        self.top < other.bottom && other.top < self.bottom
+    }
+
+    fn overlaps_horizontally(&self, other: &Rect) -> bool {
+        self.left < other.right && other.left < self.right
+    }
+
+    /// Edges touch along a shared stretch.
+    fn docked_to(&self, other: &Rect) -> bool {
+        ((self.left == other.right || self.right == other.left) && self.overlaps_vertically(other))
+            || ((self.top == other.bottom || self.bottom == other.top)
+                && self.overlaps_horizontally(other))
+    }
+
+    /// Offset that snaps this frame onto `other` when edges are within `reach`.
+    fn snap_to(&self, other: &Rect, reach: i32) -> (i32, i32) {
+        let pick = |candidates: &[i32]| {
+            candidates
+                .iter()
+                .copied()
+                .filter(|c| c.abs() <= reach)
+                .min_by_key(|c| c.abs())
+        };
+        let side = self
+            .overlaps_vertically(other)
+            .then(|| pick(&[other.right - self.left, other.left - self.right]))
+            .flatten();
+        let stacked = self
+            .overlaps_horizontally(other)
+            .then(|| pick(&[other.bottom - self.top, other.top - self.bottom]))
+            .flatten();
+        let dx =
+            side.or_else(|| stacked.and(pick(&[other.left - self.left, other.right - self.right])));
+        let dy =
+            stacked.or_else(|| side.and(pick(&[other.top - self.top, other.bottom - self.bottom])));
+        (dx.unwrap_or(0), dy.unwrap_or(0))
+    }
+}
+
+fn config() -> MagnetismConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+/// Plugin that watches the chat and world windows move.
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("magnetism")
+        .setup(|app, _api| {
+            let loaded: MagnetismConfig = store::load_json(app, CONFIG_FILE);
+            if let Ok(mut config) = CONFIG.lock() {
+                *config = Some(loaded);
+            }
+            Ok(())
+        })
+        .on_window_ready(|window| {
+            if wayland::is_wayland() || ![LEADER, FOLLOWER].contains(&window.label()) {
+                return;
+            }
+            let target = window.clone();
+            window.on_window_event(move |event| {
+                if let WindowEvent::Moved(position) = event {
+                    moved(&target, *position);
+                }
+            });
+        })
+        .build()
+}
+
+/// Frame of a window that is shown normally (not minimized, maximized or full screen).
+fn frame<R: Runtime>(window: &Window<R>) -> Option<Rect> {
+    if !window.is_visible().unwrap_or(false)
+        || window.is_minimized().unwrap_or(false)
+        || window.is_maximized().unwrap_or(false)
+        || window.is_fullscreen().unwrap_or(false)
+    {
+        return None;
+    }
+    let position = window.outer_position().ok()?;
+    let size = window.outer_size().ok()?;
+    let (left, top, right, bottom) = snap::invisible_borders(window);
+    Some(Rect {
+        left: position.x + left,
+        top: position.y + top,
+        right: position.x + size.width as i32 - right,
+        bottom: position.y + size.height as i32 - bottom,
+    })
+}
+
+fn place<R: Runtime>(window: &Window<R>, position: PhysicalPosition<i32>) {
+    if let Ok(mut placed) = PLACED.lock() {
+        placed
+            .get_or_insert_with(HashMap::new)
+            .insert(window.label().to_string(), position);
+    }
+    if let Err(e) = window.set_position(position) {
+        eprintln!("[CrewHub] Failed to move '{}': {}", window.label(), e);
+    }
+}
+
+fn moved<R: Runtime>(window: &Window<R>, position: PhysicalPosition<i32>) {
+    let label = window.label().to_string();
+    let ours = PLACED.lock().ok().is_some_and(|mut placed| {
+        let placed = placed.get_or_insert_with(HashMap::new);
+        placed.get(&label) == Some(&position) && placed.remove(&label).is_some()
+    });
+    let previous = POSITIONS.lock().ok().and_then(|mut positions| {
+        positions
+            .get_or_insert_with(HashMap::new)
+            .insert(label.clone(), position)
+    });
+    let config = config();
+    if ours || !config.enabled {
+        return;
+    }
+
+    let other_label = if label == LEADER { FOLLOWER } else { LEADER };
+    let Some(other) = window.app_handle().get_webview_window(other_label) else {
+        return;
+    };
+    let other = other.as_ref().window();
+    let (Some(this), Some(that)) = (frame(window), frame(&other)) else {
+        return;
+    };
+
+    if label == LEADER && config.move_together {
+        if let Some(previous) = previous {
+            let (dx, dy) = (position.x - previous.x, position.y - previous.y);
+            if this.shifted(-dx, -dy).docked_to(&that) {
+                if let Ok(at) = other.outer_position() {
+                    place(&other, PhysicalPosition::new(at.x + dx, at.y + dy));
+                }
+                return;
+            }
+        }
+    }
+
+    let reach = (config.distance * window.scale_factor().unwrap_or(1.0)).round() as i32;
+    let (dx, dy) = this.snap_to(&that, reach);
+    if (dx, dy) != (0, 0) {
+        place(
+            window,
+            PhysicalPosition::new(position.x + dx, position.y + dy),
+        );
+    }
+}
+
+/// Tauri command: window magnetism settings.
+/// Called from the settings window via `invoke('get_magnetism_config')`.
+#[tauri::command]
+pub fn get_magnetism_config() -> MagnetismConfig {
+    config()
+}
+
+/// Tauri command: change window magnetism settings.
+/// Called from the settings window via `invoke('set_magnetism_config', { config })`.
+#[tauri::command]
+pub fn set_magnetism_config(config: MagnetismConfig, app: AppHandle) -> Result<(), String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config);
+    }
+    Ok(())
+}
@@ -0,0 +1,570 @@
+//! MCP client manager for user-configured external MCP servers.
+//!
+//! Connects to external servers over stdio (spawned child process) or the
+//! HTTP+SSE transport, keeps the connections alive, and re-exports their tools
+//! through the embedded MCP server as `<server-id>__<tool>`. The backend
+//! therefore only ever talks to one MCP endpoint — the desktop app.
+
+use crate::child_processes::{self, Kind};
+use crate::store;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime, State};
+
+/// Persisted server list (app data dir).
+const CONFIG_FILE: &str = "mcp-clients.json";
+
+/// Separator between server id and tool name in proxied tool names.
+pub const TOOL_SEPARATOR: &str = "__";
+
+/// How long to wait for a JSON-RPC response from an external server.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often the supervisor pings live servers and reconnects dead ones.
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum McpTransport {
+    Stdio {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        env: BTreeMap<String, String>,
+    },
+    Sse {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalMcpServer {
+    /// Short identifier used as the tool-name prefix (letters, digits, `-`).
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub transport: McpTransport,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct McpClientConfig {
+    servers: Vec<ExternalMcpServer>,
+}
+
+/// A tool advertised by an external server.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteTool {
+    pub name: String,
+    pub description: String,
+    pub input_schema: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerConnectionStatus {
+    #[serde(flatten)]
+    server: ExternalMcpServer,
+    connected: bool,
+    tools: Vec<String>,
+    last_error: Option<String>,
+}
+
+/// App state: configured servers plus live sessions keyed by server id.
+pub struct McpClientState {
+    config: Mutex<McpClientConfig>,
+    sessions: Mutex<HashMap<String, Arc<McpSession>>>,
+    errors: Mutex<HashMap<String, String>>,
+}
+
+// ── JSON-RPC plumbing shared by both transports ─────────────────────────────
+
+/// Outstanding requests waiting for their response, keyed by JSON-RPC id.
+#[derive(Default)]
+struct RpcRouter {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, mpsc::Sender<Value>>>,
+    alive: AtomicBool,
+}
+
+impl RpcRouter {
+    fn register(&self) -> (u64, mpsc::Receiver<Value>) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(id, tx);
+        }
+        (id, rx)
+    }
+
+    /// Route an incoming message to whoever is waiting for its id.
+    fn dispatch(&self, message: Value) {
+        let Some(id) = message.get("id").and_then(Value::as_u64) else {
+            return; // server notifications/requests are ignored
+        };
+        if let Some(tx) = self.pending.lock().ok().and_then(|mut p| p.remove(&id)) {
+            let _ = tx.send(message);
+        }
+    }
+
+    fn wait(&self, id: u64, rx: mpsc::Receiver<Value>) -> Result<Value, String> {
+        let reply = rx.recv_timeout(REQUEST_TIMEOUT);
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&id);
+        }
+        let reply = reply.map_err(|_| "Timed out waiting for MCP server".to_string())?;
+        if let Some(error) = reply.get("error") {
+            return Err(error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("MCP error")
+                .to_string());
+        }
+        Ok(reply.get("result").cloned().unwrap_or(Value::Null))
+    }
+}
+
+enum Connection {
+    Stdio {
+        child: Mutex<Child>,
+        stdin: Mutex<ChildStdin>,
+    },
+    Sse {
+        endpoint: String,
+        headers: BTreeMap<String, String>,
+    },
+}
+
+/// A live, initialized connection to one external server.
+pub struct McpSession {
+    connection: Connection,
+    router: Arc<RpcRouter>,
+    tools: Mutex<Vec<RemoteTool>>,
+}
+
+impl McpSession {
+    fn connect(transport: &McpTransport) -> Result<Arc<Self>, String> {
+        let router = Arc::new(RpcRouter {
+            alive: AtomicBool::new(true),
+            ..Default::default()
+        });
+        let connection = match transport {
+            McpTransport::Stdio { command, args, env } => {
+                connect_stdio(command, args, env, router.clone())?
+            }
+            McpTransport::Sse { url, headers } => connect_sse(url, headers, router.clone())?,
+        };
+        let session = Arc::new(Self {
+            connection,
+            router,
+            tools: Mutex::new(Vec::new()),
+        });
+
+        session.request(
+            "initialize",
+            json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": { "name": "crewhub-desktop", "version": env!("CARGO_PKG_VERSION") },
+            }),
+        )?;
+        session.notify("notifications/initialized", json!({}))?;
+        session.refresh_tools()?;
+        Ok(session)
+    }
+
+    fn is_alive(&self) -> bool {
+        self.router.alive.load(Ordering::SeqCst)
+    }
+
+    fn send(&self, message: &Value) -> Result<(), String> {
+        match &self.connection {
+            Connection::Stdio { stdin, .. } => {
+                let mut stdin = stdin.lock().map_err(|e| e.to_string())?;
+                writeln!(stdin, "{}", message)
+                    .and_then(|_| stdin.flush())
+                    .map_err(|e| {
+                        self.router.alive.store(false, Ordering::SeqCst);
+                        e.to_string()
+                    })
+            }
+            Connection::Sse { endpoint, headers } => {
+                let mut req = ureq::post(endpoint).set("Content-Type", "application/json");
+                for (k, v) in headers {
+                    req = req.set(k, v);
+                }
+                req.send_string(&message.to_string())
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        let (id, rx) = self.router.register();
+        self.send(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))?;
+        self.router.wait(id, rx)
+    }
+
+    fn notify(&self, method: &str, params: Value) -> Result<(), String> {
+        self.send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    fn refresh_tools(&self) -> Result<(), String> {
+        let result = self.request("tools/list", json!({}))?;
+        let tools = result
+            .get("tools")
+            .and_then(Value::as_array)
+            .map(|tools| {
+                tools
+                    .iter()
+                    .filter_map(|t| {
+                        Some(RemoteTool {
+                            name: t.get("name")?.as_str()?.to_string(),
+                            description: t
+                                .get("description")
+                                .and_then(Value::as_str)
+                                .unwrap_or_default()
+                                .to_string(),
+                            input_schema: t
+                                .get("inputSchema")
+                                .cloned()
+                                .unwrap_or_else(|| json!({ "type": "object" })),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        *self.tools.lock().map_err(|e| e.to_string())? = tools;
+        Ok(())
+    }
+
+    fn close(&self) {
+        self.router.alive.store(false, Ordering::SeqCst);
+        if let Connection::Stdio { child, .. } = &self.connection {
+            if let Ok(mut child) = child.lock() {
+                child_processes::unregister(child.id());
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+        }
+    }
+}
+
+fn connect_stdio(
+    command: &str,
+    args: &[String],
+    env: &BTreeMap<String, String>,
+    router: Arc<RpcRouter>,
+) -> Result<Connection, String> {
+    let mut child = Command::new(command)
+        .args(args)
+        .envs(env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start '{}': {}", command, e))?;
+    child_processes::register(child.id(), Kind::McpServer, command, command);
+    let stdin = child.stdin.take().ok_or("No stdin on MCP server process")?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or("No stdout on MCP server process")?;
+
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            if let Ok(message) = serde_json::from_str::<Value>(&line) {
+                router.dispatch(message);
+            }
+        }
+        router.alive.store(false, Ordering::SeqCst);
+    });
+
+    Ok(Connection::Stdio {
+        child: Mutex::new(child),
+        stdin: Mutex::new(stdin),
+    })
+}
+
+/// Open the SSE stream and wait for the server's `endpoint` event.
+fn connect_sse(
+    url: &str,
+    headers: &BTreeMap<String, String>,
+    router: Arc<RpcRouter>,
+) -> Result<Connection, String> {
+    let base = url::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let mut req = ureq::get(url).set("Accept", "text/event-stream");
+    for (k, v) in headers {
+        req = req.set(k, v);
+    }
+    let reader = req.call().map_err(|e| e.to_string())?.into_reader();
+
+    let (endpoint_tx, endpoint_rx) = mpsc::channel::<String>();
+    std::thread::spawn(move || {
+        crate::backend::read_sse_stream(reader, |event, data| match event {
+            "endpoint" => {
+                let _ = endpoint_tx.send(data.to_string());
+            }
+            _ => {
+                if let Ok(message) = serde_json::from_str::<Value>(data) {
+                    router.dispatch(message);
+                }
+            }
+        });
+        router.alive.store(false, Ordering::SeqCst);
+    });
+
+    let endpoint = endpoint_rx
+        .recv_timeout(REQUEST_TIMEOUT)
+        .map_err(|_| "MCP server did not announce a message endpoint".to_string())?;
+    let endpoint = base.join(&endpoint).map_err(|e| e.to_string())?.to_string();
+    Ok(Connection::Sse {
+        endpoint,
+        headers: headers.clone(),
+    })
+}
+
+// ── Manager ─────────────────────────────────────────────────────────────────
+
+/// Load configured servers, register state, and start the connection supervisor.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: McpClientConfig = store::load_json(app, CONFIG_FILE);
+    app.manage(McpClientState {
+        config: Mutex::new(config),
+        sessions: Mutex::new(HashMap::new()),
+        errors: Mutex::new(HashMap::new()),
+    });
+
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        supervise(&app.state::<McpClientState>());
+        std::thread::sleep(SUPERVISE_INTERVAL);
+    });
+}
+
+/// Reconnect dead or missing sessions and ping live ones.
+fn supervise(state: &McpClientState) {
+    let servers = match state.config.lock() {
+        Ok(config) => config.servers.clone(),
+        Err(_) => return,
+    };
+    for server in servers.iter().filter(|s| s.enabled) {
+        let existing = state
+            .sessions
+            .lock()
+            .ok()
+            .and_then(|s| s.get(&server.id).cloned());
+        if let Some(session) = existing {
+            if session.is_alive() && session.request("ping", json!({})).is_ok() {
+                continue;
+            }
+            session.close();
+        }
+        connect_server(state, server);
+    }
+}
+
+fn connect_server(state: &McpClientState, server: &ExternalMcpServer) {
+    let result = McpSession::connect(&server.transport);
+    let (Ok(mut sessions), Ok(mut errors)) = (state.sessions.lock(), state.errors.lock()) else {
+        return;
+    };
+    match result {
+        Ok(session) => {
+            sessions.insert(server.id.clone(), session);
+            errors.remove(&server.id);
+        }
+        Err(e) => {
+            eprintln!("[CrewHub] MCP server '{}' unavailable: {}", server.name, e);
+            sessions.remove(&server.id);
+            errors.insert(server.id.clone(), e);
+        }
+    }
+}
+
+fn disconnect_server(state: &McpClientState, id: &str) {
+    if let Some(session) = state.sessions.lock().ok().and_then(|mut s| s.remove(id)) {
+        session.close();
+    }
+}
+
+/// Tools of all connected external servers, with prefixed names.
+pub fn proxied_tools<R: Runtime>(app: &AppHandle<R>) -> Vec<RemoteTool> {
+    let Some(state) = app.try_state::<McpClientState>() else {
+        return Vec::new();
+    };
+    let Ok(sessions) = state.sessions.lock() else {
+        return Vec::new();
+    };
+    let mut tools = Vec::new();
+    for (id, session) in sessions.iter().filter(|(_, s)| s.is_alive()) {
+        if let Ok(list) = session.tools.lock() {
+            tools.extend(list.iter().map(|tool| RemoteTool {
+                name: format!("{}{}{}", id, TOOL_SEPARATOR, tool.name),
+                ..tool.clone()
+            }));
+        }
+    }
+    tools.sort_by(|a, b| a.name.cmp(&b.name));
+    tools
+}
+
+/// Forward a prefixed tool call to its server and return the raw MCP result.
+pub fn call_proxied_tool<R: Runtime>(
+    app: &AppHandle<R>,
+    name: &str,
+    args: &Value,
+) -> Result<Value, String> {
+    let (server_id, tool) = name
+        .split_once(TOOL_SEPARATOR)
+        .ok_or_else(|| format!("Unknown tool: {}", name))?;
+    let state = app
+        .try_state::<McpClientState>()
+        .ok_or("MCP client not initialized")?;
+    let session = state
+        .sessions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(server_id)
+        .cloned()
+        .ok_or_else(|| format!("MCP server '{}' is not connected", server_id))?;
+    session.request("tools/call", json!({ "name": tool, "arguments": args }))
+}
+
+fn validate(server: &ExternalMcpServer) -> Result<(), String> {
+    let id_ok = !server.id.is_empty()
+        && !server.id.contains(TOOL_SEPARATOR)
+        && server
+            .id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !id_ok {
+        return Err(
+            "Server id must be non-empty and only contain letters, digits and '-'".to_string(),
+        );
+    }
+    match &server.transport {
+        McpTransport::Stdio { command, .. } if command.trim().is_empty() => {
+            Err("Command is required".to_string())
+        }
+        McpTransport::Sse { url, .. } if url::Url::parse(url).is_err() => {
+            Err("Invalid SSE URL".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn statuses(state: &McpClientState) -> Result<Vec<McpServerConnectionStatus>, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?;
+    let sessions = state.sessions.lock().map_err(|e| e.to_string())?;
+    let errors = state.errors.lock().map_err(|e| e.to_string())?;
+    Ok(config
+        .servers
+        .iter()
+        .map(|server| {
+            let session = sessions.get(&server.id).filter(|s| s.is_alive());
+            McpServerConnectionStatus {
+                server: server.clone(),
+                connected: session.is_some(),
+                tools: session
+                    .and_then(|s| {
+                        s.tools
+                            .lock()
+                            .ok()
+                            .map(|t| t.iter().map(|t| t.name.clone()).collect())
+                    })
+                    .unwrap_or_default(),
+                last_error: errors.get(&server.id).cloned(),
+            }
+        })
+        .collect())
+}
+
+/// Tauri command: list configured external MCP servers with connection status.
+/// Called from the frontend via `invoke('list_mcp_servers')`.
+#[tauri::command]
+pub fn list_mcp_servers(
+    state: State<McpClientState>,
+) -> Result<Vec<McpServerConnectionStatus>, String> {
+    statuses(&state)
+}
+
+/// Tauri command: add (or replace, by id) an external MCP server and connect to it.
+/// Called from the frontend via `invoke('add_mcp_server', { server })`.
+#[tauri::command]
+pub async fn add_mcp_server(
+    server: ExternalMcpServer,
+    app: AppHandle,
+) -> Result<Vec<McpServerConnectionStatus>, String> {
+    validate(&server)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<McpClientState>();
+        {
+            let mut config = state.config.lock().map_err(|e| e.to_string())?;
+            config.servers.retain(|s| s.id != server.id);
+            config.servers.push(server.clone());
+            store::save_json(&app, CONFIG_FILE, &*config)?;
+        }
+        disconnect_server(&state, &server.id);
+        if server.enabled {
+            connect_server(&state, &server);
+        }
+        statuses(&state)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: remove an external MCP server and close its connection.
+/// Called from the frontend via `invoke('remove_mcp_server', { id })`.
+#[tauri::command]
+pub fn remove_mcp_server(
+    id: String,
+    app: AppHandle,
+    state: State<McpClientState>,
+) -> Result<Vec<McpServerConnectionStatus>, String> {
+    {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.servers.retain(|s| s.id != id);
+        store::save_json(&app, CONFIG_FILE, &*config)?;
+    }
+    disconnect_server(&state, &id);
+    if let Ok(mut errors) = state.errors.lock() {
+        errors.remove(&id);
+    }
+    statuses(&state)
+}
+
+/// Tauri command: connect to a server definition without saving it and list its tools.
+/// Called from the frontend via `invoke('test_mcp_server', { server })`.
+#[tauri::command]
+pub async fn test_mcp_server(server: ExternalMcpServer) -> Result<Vec<RemoteTool>, String> {
+    validate(&server)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let session = McpSession::connect(&server.transport)?;
+        let tools = session.tools.lock().map_err(|e| e.to_string())?.clone();
+        session.close();
+        Ok(tools)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
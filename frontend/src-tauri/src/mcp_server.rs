@@ -0,0 +1,386 @@
+//! Embedded MCP (Model Context Protocol) server.
+//!
+//! Hosts the tools from [`crate::local_tools`] on `http://127.0.0.1:<port>/mcp`
+//! using MCP's streamable-HTTP transport (JSON-RPC 2.0 over POST, plain JSON
+//! responses), so the backend's agents can act on the user's machine.
+//! Each tool can be enabled or disabled from the settings window. Tools of
+//! external servers managed by [`crate::mcp_client`] are listed alongside.
+//!
+//! The server is off until the user turns it on. File tools only reach the
+//! project folders listed in `roots`, and every call needs the user's
+//! approval ([`crate::approvals`], rememberable per tool as `mcp:<tool>`).
+
+use crate::{approvals, i18n, local_http, local_tools, mcp_client, store};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, Runtime, State};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "mcp-server.json";
+
+/// Default port; sits next to the backend's 8091.
+const DEFAULT_PORT: u16 = 8093;
+
+/// Longest argument preview shown in the approval dialog.
+const MAX_DETAIL_CHARS: usize = 600;
+
+/// MCP protocol revision we implement.
+const PROTOCOL_VERSION: &str = "2025-03-26";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct McpServerConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+    /// Per-tool overrides; tools not listed use their `default_enabled`.
+    pub tools: BTreeMap<String, bool>,
+    /// Project folders the file tools may access (none: file tools refuse).
+    pub roots: Vec<PathBuf>,
+}
+
+impl Default for McpServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: local_http::new_token(),
+            tools: BTreeMap::new(),
+            roots: Vec::new(),
+        }
+    }
+}
+
+impl McpServerConfig {
+    fn is_tool_enabled(&self, spec: &local_tools::ToolSpec) -> bool {
+        self.tools
+            .get(spec.name)
+            .copied()
+            .unwrap_or(spec.default_enabled)
+    }
+}
+
+/// App state: MCP server config plus the running listener (if any).
+pub struct McpServerState {
+    config: Mutex<McpServerConfig>,
+    server: Mutex<Option<Arc<tiny_http::Server>>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolStatus {
+    name: &'static str,
+    description: &'static str,
+    enabled: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerStatus {
+    enabled: bool,
+    running: bool,
+    url: String,
+    token: String,
+    tools: Vec<McpToolStatus>,
+    roots: Vec<PathBuf>,
+}
+
+/// Load the config, register state, and start the server if enabled.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: McpServerConfig = store::load_json(app, CONFIG_FILE);
+    // Persist immediately so a freshly generated token stays stable across launches.
+    if let Err(e) = store::save_json(app, CONFIG_FILE, &config) {
+        eprintln!("[CrewHub] {}", e);
+    }
+    let enabled = config.enabled;
+    app.manage(McpServerState {
+        config: Mutex::new(config),
+        server: Mutex::new(None),
+    });
+    if enabled {
+        if let Err(e) = start(app) {
+            eprintln!("[CrewHub] Failed to start MCP server: {}", e);
+        }
+    }
+}
+
+fn start<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<McpServerState>();
+    let mut slot = state.server.lock().map_err(|e| e.to_string())?;
+    if slot.is_some() {
+        return Ok(());
+    }
+    let port = state.config.lock().map_err(|e| e.to_string())?.port;
+
+    let server = Arc::new(
+        tiny_http::Server::http(("127.0.0.1", port))
+            .map_err(|e| format!("Cannot bind port {}: {}", port, e))?,
+    );
+    *slot = Some(server.clone());
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            handle_request(&app, request);
+        }
+    });
+    println!(
+        "[CrewHub] MCP server listening on http://127.0.0.1:{}/mcp",
+        port
+    );
+    Ok(())
+}
+
+fn stop(state: &McpServerState) -> Result<(), String> {
+    if let Some(server) = state.server.lock().map_err(|e| e.to_string())?.take() {
+        server.unblock();
+    }
+    Ok(())
+}
+
+fn handle_request<R: Runtime>(app: &AppHandle<R>, mut request: tiny_http::Request) {
+    let state = app.state::<McpServerState>();
+    let token = match state.config.lock() {
+        Ok(config) => config.token.clone(),
+        Err(_) => return local_http::respond_empty(request, 500),
+    };
+
+    if request.url() != "/mcp" {
+        return local_http::respond_empty(request, 404);
+    }
+    if !local_http::is_authorized(&request, &token) {
+        return local_http::respond_empty(request, 401);
+    }
+    if *request.method() != tiny_http::Method::Post {
+        // We don't offer a server-initiated SSE stream.
+        return local_http::respond_empty(request, 405);
+    }
+
+    let message = match local_http::read_json_body(&mut request) {
+        Ok(message) => message,
+        Err(e) => {
+            return local_http::respond_json(request, 400, &rpc_error(Value::Null, -32700, &e))
+        }
+    };
+
+    // Batches are answered element by element; notifications produce no reply.
+    let replies: Vec<Value> = match message {
+        Value::Array(batch) => batch
+            .iter()
+            .filter_map(|m| handle_rpc(app, &state, m))
+            .collect(),
+        single => handle_rpc(app, &state, &single).into_iter().collect(),
+    };
+    match replies.len() {
+        0 => local_http::respond_empty(request, 202),
+        1 => local_http::respond_json(request, 200, &replies[0]),
+        _ => local_http::respond_json(request, 200, &Value::Array(replies)),
+    }
+}
+
+/// Handle one JSON-RPC message. Returns `None` for notifications.
+fn handle_rpc<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &McpServerState,
+    message: &Value,
+) -> Option<Value> {
+    let id = message.get("id").cloned()?;
+    let method = message
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": { "listChanged": false } },
+            "serverInfo": { "name": "crewhub-desktop", "version": env!("CARGO_PKG_VERSION") },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(list_tools(app, state)),
+        "tools/call" => Ok(call_tool(app, state, &params)),
+        other => Err(format!("Method not found: {}", other)),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(message) => rpc_error(id, -32601, &message),
+    })
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn list_tools<R: Runtime>(app: &AppHandle<R>, state: &McpServerState) -> Value {
+    let config = match state.config.lock() {
+        Ok(config) => config.clone(),
+        Err(_) => return json!({ "tools": [] }),
+    };
+    let mut tools: Vec<Value> = local_tools::tool_specs()
+        .into_iter()
+        .filter(|spec| config.is_tool_enabled(spec))
+        .map(|spec| {
+            json!({
+                "name": spec.name,
+                "description": spec.description,
+                "inputSchema": (spec.input_schema)(),
+            })
+        })
+        .collect();
+    tools.extend(mcp_client::proxied_tools(app).into_iter().map(|tool| {
+        json!({
+            "name": tool.name,
+            "description": tool.description,
+            "inputSchema": tool.input_schema,
+        })
+    }));
+    json!({ "tools": tools })
+}
+
+/// Run a tool. Tool failures are reported as `isError` results (per MCP), not RPC errors.
+fn call_tool<R: Runtime>(app: &AppHandle<R>, state: &McpServerState, params: &Value) -> Value {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let args = params
+        .get("arguments")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+
+    let proxied = name.contains(mcp_client::TOOL_SEPARATOR);
+    let Ok(config) = state.config.lock().map(|config| config.clone()) else {
+        return tool_error("The MCP server is unavailable".to_string());
+    };
+    let enabled = local_tools::tool_specs()
+        .iter()
+        .any(|spec| spec.name == name && config.is_tool_enabled(spec));
+    if !proxied && !enabled {
+        return tool_error(format!("Tool '{}' is not available", name));
+    }
+    if !approved(app, name, &args) {
+        return tool_error(format!("The user did not allow '{}'", name));
+    }
+
+    if proxied {
+        return mcp_client::call_proxied_tool(app, name, &args).unwrap_or_else(tool_error);
+    }
+    match local_tools::call_tool(app, name, &args, &config.roots) {
+        Ok(content) => json!({ "content": content, "isError": false }),
+        Err(e) => tool_error(e),
+    }
+}
+
+/// Ask the user (or apply their remembered rule) before running a tool.
+fn approved<R: Runtime>(app: &AppHandle<R>, name: &str, args: &Value) -> bool {
+    let mut details = serde_json::to_string_pretty(args).unwrap_or_default();
+    if details.chars().count() > MAX_DETAIL_CHARS {
+        details = details.chars().take(MAX_DETAIL_CHARS).collect::<String>() + "…";
+    }
+    approvals::confirm(
+        app,
+        &format!("mcp:{}", name),
+        i18n::tf("dialog.toolCallTitle", &[("tool", name)]),
+        i18n::tf("dialog.toolCall", &[("tool", name), ("details", &details)]),
+    )
+}
+
+fn tool_error(message: String) -> Value {
+    json!({ "content": [{ "type": "text", "text": message }], "isError": true })
+}
+
+fn status(state: &McpServerState) -> Result<McpServerStatus, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let running = state.server.lock().map_err(|e| e.to_string())?.is_some();
+    let tools = local_tools::tool_specs()
+        .into_iter()
+        .map(|spec| McpToolStatus {
+            enabled: config.is_tool_enabled(&spec),
+            name: spec.name,
+            description: spec.description,
+        })
+        .collect();
+    Ok(McpServerStatus {
+        enabled: config.enabled,
+        running,
+        url: format!("http://127.0.0.1:{}/mcp", config.port),
+        token: config.token,
+        tools,
+        roots: config.roots,
+    })
+}
+
+/// Tauri command: MCP server status, endpoint, token, and per-tool enablement.
+/// Called from the frontend via `invoke('get_mcp_server_status')`.
+#[tauri::command]
+pub fn get_mcp_server_status(state: State<McpServerState>) -> Result<McpServerStatus, String> {
+    status(&state)
+}
+
+/// Tauri command: start or stop the MCP server and persist the choice.
+/// Called from the frontend via `invoke('set_mcp_server_enabled', { enabled })`.
+#[tauri::command]
+pub fn set_mcp_server_enabled(
+    enabled: bool,
+    app: AppHandle,
+    state: State<McpServerState>,
+) -> Result<McpServerStatus, String> {
+    {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.enabled = enabled;
+        store::save_json(&app, CONFIG_FILE, &*config)?;
+    }
+    if enabled {
+        start(&app)?;
+    } else {
+        stop(&state)?;
+    }
+    status(&state)
+}
+
+/// Tauri command: enable or disable a single MCP tool.
+/// Called from the frontend via `invoke('set_mcp_tool_enabled', { name, enabled })`.
+#[tauri::command]
+pub fn set_mcp_tool_enabled(
+    name: String,
+    enabled: bool,
+    app: AppHandle,
+    state: State<McpServerState>,
+) -> Result<McpServerStatus, String> {
+    if !local_tools::tool_specs()
+        .iter()
+        .any(|spec| spec.name == name)
+    {
+        return Err(format!("Unknown tool: {}", name));
+    }
+    {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.tools.insert(name, enabled);
+        store::save_json(&app, CONFIG_FILE, &*config)?;
+    }
+    status(&state)
+}
+
+/// Tauri command: set the project folders the file tools may access.
+/// Called from the frontend via `invoke('set_mcp_roots', { roots })`.
+#[tauri::command]
+pub fn set_mcp_roots(
+    roots: Vec<PathBuf>,
+    app: AppHandle,
+    state: State<McpServerState>,
+) -> Result<McpServerStatus, String> {
+    if let Some(root) = roots.iter().find(|root| !root.is_dir()) {
+        return Err(format!("Not a folder: {}", root.display()));
+    }
+    {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.roots = roots;
+        store::save_json(&app, CONFIG_FILE, &*config)?;
+    }
+    status(&state)
+}
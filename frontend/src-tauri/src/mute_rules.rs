@@ -0,0 +1,255 @@
+//! Per-conversation notification mute rules.
+//!
+//! A rule mutes one conversation or every conversation of a project, either
+//! entirely or for everything but mentions of the user, optionally until a
+//! given time. The notification service drops muted notices before rate
+//! limiting ([`crate::notifications`]), and `update_tray_badge` leaves muted
+//! conversations out of the tray badge when the frontend sends per-conversation
+//! counts. Expired rules are pruned by a background sweep, which emits
+//! `mute-rules-changed` so windows can resend their badges.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Persisted rules (app data dir).
+const RULES_FILE: &str = "mute-rules.json";
+
+/// Event sent to every window after the rules changed.
+const RULES_CHANGED_EVENT: &str = "mute-rules-changed";
+
+/// How often expired rules are pruned.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What a rule applies to.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum MuteTarget {
+    #[serde(rename_all = "camelCase")]
+    Conversation { session_key: String },
+    #[serde(rename_all = "camelCase")]
+    Project { project_id: String },
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MuteMode {
+    /// Only notices and unread counts that mention the user get through.
+    OnlyMentions,
+    /// Nothing gets through.
+    #[default]
+    All,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MuteRule {
+    #[serde(default)]
+    pub id: String,
+    pub target: MuteTarget,
+    #[serde(default)]
+    pub mode: MuteMode,
+    /// Unix seconds when the rule stops applying; forever when unset.
+    #[serde(default)]
+    pub until: Option<i64>,
+}
+
+impl MuteRule {
+    fn expired(&self, now: i64) -> bool {
+        self.until.is_some_and(|until| until <= now)
+    }
+
+    fn matches(&self, session_key: Option<&str>, project_id: Option<&str>) -> bool {
+        match &self.target {
+            MuteTarget::Conversation { session_key: key } => session_key == Some(key.as_str()),
+            MuteTarget::Project { project_id: id } => project_id == Some(id.as_str()),
+        }
+    }
+}
+
+/// Where a notice or unread count comes from, for matching against rules.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MuteContext {
+    pub session_key: Option<String>,
+    pub project_id: Option<String>,
+    /// The notice mentions the user.
+    pub mention: bool,
+}
+
+/// Unread counts of one conversation, sent with `update_tray_badge`.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ConversationBadge {
+    pub session_key: String,
+    pub project_id: Option<String>,
+    pub unread: u32,
+    pub mentions: u32,
+}
+
+/// App state: rules in creation order.
+pub struct MuteRuleState {
+    rules: Mutex<Vec<MuteRule>>,
+}
+
+/// Load the rules and start sweeping expired ones.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let rules: Vec<MuteRule> = store::load_json(app, RULES_FILE);
+    app.manage(MuteRuleState {
+        rules: Mutex::new(rules),
+    });
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SWEEP_INTERVAL);
+        sweep(&app);
+    });
+}
+
+fn now() -> i64 {
+    chrono::Local::now().timestamp()
+}
+
+fn sweep<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<MuteRuleState>() else {
+        return;
+    };
+    let Ok(mut rules) = state.rules.lock() else {
+        return;
+    };
+    let now = now();
+    if rules.iter().any(|rule| rule.expired(now)) {
+        rules.retain(|rule| !rule.expired(now));
+        if let Err(e) = save_and_apply(app, &rules) {
+            eprintln!("[CrewHub] {}", e);
+        }
+    }
+}
+
+/// The strictest active rule for a conversation.
+fn mode<R: Runtime>(
+    app: &AppHandle<R>,
+    session_key: Option<&str>,
+    project_id: Option<&str>,
+) -> Option<MuteMode> {
+    if session_key.is_none() && project_id.is_none() {
+        return None;
+    }
+    let state = app.try_state::<MuteRuleState>()?;
+    let rules = state.rules.lock().ok()?;
+    let now = now();
+    rules
+        .iter()
+        .filter(|rule| !rule.expired(now) && rule.matches(session_key, project_id))
+        .map(|rule| rule.mode)
+        .max()
+}
+
+/// Whether a notice from `context` may be shown.
+pub fn allows<R: Runtime>(app: &AppHandle<R>, context: &MuteContext) -> bool {
+    match mode(
+        app,
+        context.session_key.as_deref(),
+        context.project_id.as_deref(),
+    ) {
+        None => true,
+        Some(MuteMode::OnlyMentions) => context.mention,
+        Some(MuteMode::All) => false,
+    }
+}
+
+/// Unread and mention totals of `conversations`, leaving out what is muted.
+pub fn badge_totals<R: Runtime>(
+    app: &AppHandle<R>,
+    conversations: &[ConversationBadge],
+) -> (u32, u32) {
+    conversations
+        .iter()
+        .fold((0, 0), |(unread, mentions), conversation| {
+            match mode(
+                app,
+                Some(&conversation.session_key),
+                conversation.project_id.as_deref(),
+            ) {
+                None => (
+                    unread + conversation.unread,
+                    mentions + conversation.mentions,
+                ),
+                Some(MuteMode::OnlyMentions) => (unread, mentions + conversation.mentions),
+                Some(MuteMode::All) => (unread, mentions),
+            }
+        })
+}
+
+fn save_and_apply<R: Runtime>(
+    app: &AppHandle<R>,
+    rules: &[MuteRule],
+) -> Result<Vec<MuteRule>, String> {
+    store::save_json(app, RULES_FILE, &rules)?;
+    let _ = app.emit(RULES_CHANGED_EVENT, rules);
+    Ok(rules.to_vec())
+}
+
+/// Tauri command: all active mute rules.
+/// Called from the settings window via `invoke('list_mute_rules')`.
+#[tauri::command]
+pub fn list_mute_rules(state: State<MuteRuleState>) -> Result<Vec<MuteRule>, String> {
+    let now = now();
+    Ok(state
+        .rules
+        .lock()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .filter(|rule| !rule.expired(now))
+        .cloned()
+        .collect())
+}
+
+/// Tauri command: create or replace a rule (matched by `id`, then by target;
+/// a new id is assigned if empty).
+/// Called from the frontend via `invoke('save_mute_rule', { rule })`.
+#[tauri::command]
+pub fn save_mute_rule(
+    mut rule: MuteRule,
+    app: AppHandle,
+    state: State<MuteRuleState>,
+) -> Result<Vec<MuteRule>, String> {
+    match &rule.target {
+        MuteTarget::Conversation { session_key: id } | MuteTarget::Project { project_id: id }
+            if id.trim().is_empty() =>
+        {
+            return Err("Mute rule needs a conversation or project".to_string());
+        }
+        _ => {}
+    }
+    if rule.expired(now()) {
+        return Err("Mute rule would already have expired".to_string());
+    }
+    let mut rules = state.rules.lock().map_err(|e| e.to_string())?;
+    if rule.id.is_empty() {
+        rule.id = rules
+            .iter()
+            .find(|existing| existing.target == rule.target)
+            .map(|existing| existing.id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    }
+    match rules.iter_mut().find(|existing| existing.id == rule.id) {
+        Some(existing) => *existing = rule,
+        None => rules.push(rule),
+    }
+    save_and_apply(&app, &rules)
+}
+
+/// Tauri command: delete a rule (unmute).
+/// Called from the frontend via `invoke('delete_mute_rule', { id })`.
+#[tauri::command]
+pub fn delete_mute_rule(
+    id: String,
+    app: AppHandle,
+    state: State<MuteRuleState>,
+) -> Result<Vec<MuteRule>, String> {
+    let mut rules = state.rules.lock().map_err(|e| e.to_string())?;
+    rules.retain(|rule| rule.id != id);
+    save_and_apply(&app, &rules)
+}
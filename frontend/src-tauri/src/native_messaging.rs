@@ -0,0 +1,297 @@
+//! Native messaging host for the CrewHub browser extension.
+//!
+//! When a browser starts this executable as a native messaging host, `run()`
+//! switches to host mode instead of opening the app: it speaks the
+//! length-prefixed JSON protocol on stdin/stdout and forwards the page or
+//! selection the extension sends to a backend conversation, replying with the
+//! agent's answer.
+//!
+//! Messages from the extension (every reply echoes `id`):
+//! - `{"type":"ping"}` → `{"type":"pong","version":"…"}`
+//! - `{"type":"send","sessionKey":"…","message":"…","page":{"url":"…","title":"…"},"selection":"…"}`
+//!   → `{"type":"reply","reply":{…}}`
+//!
+//! `install_native_messaging_host` writes the host manifest for the
+//! installed browsers so the extension can find us.
+
+use crate::backend;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+/// Host name the extension connects to (`chrome.runtime.connectNative`).
+const HOST_NAME: &str = "dev.crewhub.native";
+
+/// Extension id of the Firefox add-on (fixed by its manifest).
+const FIREFOX_EXTENSION_ID: &str = "browser-extension@crewhub.dev";
+
+/// Largest message accepted from the browser.
+const MAX_INCOMING_BYTES: u32 = 8 * 1024 * 1024;
+
+/// Browsers refuse messages from the host larger than this.
+const MAX_OUTGOING_BYTES: usize = 1024 * 1024;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    title: String,
+}
+
+#[derive(Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+enum HostMessage {
+    Ping,
+    Send {
+        session_key: String,
+        #[serde(default)]
+        message: String,
+        #[serde(default)]
+        page: Option<PageInfo>,
+        #[serde(default)]
+        selection: Option<String>,
+    },
+}
+
+/// Browsers pass the caller's origin (Chromium) or the manifest path plus
+/// extension id (Firefox) as arguments when starting a native host.
+pub fn is_host_invocation() -> bool {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    args.iter().any(|a| a.starts_with("chrome-extension://"))
+        || args.iter().any(|a| a == FIREFOX_EXTENSION_ID)
+}
+
+/// Serve the extension until the browser closes the pipe.
+pub fn run_host() {
+    let mut stdin = std::io::stdin().lock();
+    let mut stdout = std::io::stdout().lock();
+    loop {
+        let message = match read_message(&mut stdin) {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(e) => {
+                let _ = write_message(&mut stdout, &json!({ "type": "error", "error": e }));
+                break;
+            }
+        };
+        let id = message.get("id").cloned().unwrap_or(Value::Null);
+        let mut reply = match serde_json::from_value::<HostMessage>(message) {
+            Ok(message) => {
+                handle(message).unwrap_or_else(|e| json!({ "type": "error", "error": e }))
+            }
+            Err(e) => json!({ "type": "error", "error": format!("Invalid message: {}", e) }),
+        };
+        reply["id"] = id;
+        if write_message(&mut stdout, &reply).is_err() {
+            break;
+        }
+    }
+}
+
+fn handle(message: HostMessage) -> Result<Value, String> {
+    match message {
+        HostMessage::Ping => Ok(json!({ "type": "pong", "version": env!("CARGO_PKG_VERSION") })),
+        HostMessage::Send {
+            session_key,
+            message,
+            page,
+            selection,
+        } => {
+            let mut prompt = message;
+            if let Some(page) = page {
+                prompt.push_str(&format!("\n\nPage: {} <{}>", page.title, page.url));
+            }
+            if let Some(selection) = selection.filter(|s| !s.trim().is_empty()) {
+                let quoted: Vec<String> = selection.lines().map(|l| format!("> {}", l)).collect();
+                prompt.push_str(&format!("\n\nSelection:\n{}", quoted.join("\n")));
+            }
+            let reply = backend::send_chat_message(&session_key, prompt.trim())?;
+            Ok(json!({ "type": "reply", "reply": reply }))
+        }
+    }
+}
+
+/// Read one message: 4-byte native-endian length, then UTF-8 JSON. `None` on EOF.
+fn read_message(input: &mut impl Read) -> Result<Option<Value>, String> {
+    let mut len = [0u8; 4];
+    match input.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    }
+    let len = u32::from_ne_bytes(len);
+    if len > MAX_INCOMING_BYTES {
+        return Err("Message too large".to_string());
+    }
+    let mut body = vec![0u8; len as usize];
+    input.read_exact(&mut body).map_err(|e| e.to_string())?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> std::io::Result<()> {
+    let mut body = message.to_string().into_bytes();
+    if body.len() > MAX_OUTGOING_BYTES {
+        body = json!({ "id": message["id"], "type": "error", "error": "Reply too large" })
+            .to_string()
+            .into_bytes();
+    }
+    output.write_all(&(body.len() as u32).to_ne_bytes())?;
+    output.write_all(&body)?;
+    output.flush()
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Browser {
+    Chrome,
+    Chromium,
+    Edge,
+    Brave,
+    Firefox,
+}
+
+impl Browser {
+    const ALL: [Browser; 5] = [
+        Browser::Chrome,
+        Browser::Chromium,
+        Browser::Edge,
+        Browser::Brave,
+        Browser::Firefox,
+    ];
+
+    /// Per-user manifest directory, relative to the home directory.
+    #[cfg(target_os = "macos")]
+    fn manifest_dir(self) -> &'static str {
+        match self {
+            Browser::Chrome => "Library/Application Support/Google/Chrome/NativeMessagingHosts",
+            Browser::Chromium => "Library/Application Support/Chromium/NativeMessagingHosts",
+            Browser::Edge => "Library/Application Support/Microsoft Edge/NativeMessagingHosts",
+            Browser::Brave => {
+                "Library/Application Support/BraveSoftware/Brave-Browser/NativeMessagingHosts"
+            }
+            Browser::Firefox => "Library/Application Support/Mozilla/NativeMessagingHosts",
+        }
+    }
+
+    #[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+    fn manifest_dir(self) -> &'static str {
+        match self {
+            Browser::Chrome => ".config/google-chrome/NativeMessagingHosts",
+            Browser::Chromium => ".config/chromium/NativeMessagingHosts",
+            Browser::Edge => ".config/microsoft-edge/NativeMessagingHosts",
+            Browser::Brave => ".config/BraveSoftware/Brave-Browser/NativeMessagingHosts",
+            Browser::Firefox => ".mozilla/native-messaging-hosts",
+        }
+    }
+
+    /// Registry key pointing at the manifest (Windows looks hosts up in the registry).
+    #[cfg(target_os = "windows")]
+    fn registry_key(self) -> &'static str {
+        match self {
+            Browser::Chrome => r"HKCU\Software\Google\Chrome\NativeMessagingHosts",
+            Browser::Chromium => r"HKCU\Software\Chromium\NativeMessagingHosts",
+            Browser::Edge => r"HKCU\Software\Microsoft\Edge\NativeMessagingHosts",
+            Browser::Brave => r"HKCU\Software\BraveSoftware\Brave-Browser\NativeMessagingHosts",
+            Browser::Firefox => r"HKCU\Software\Mozilla\NativeMessagingHosts",
+        }
+    }
+}
+
+fn manifest(browser: Browser, chrome_extension_id: &str) -> Result<Value, String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let mut manifest = json!({
+        "name": HOST_NAME,
+        "description": "CrewHub desktop app",
+        "path": exe.display().to_string(),
+        "type": "stdio",
+    });
+    match browser {
+        Browser::Firefox => manifest["allowed_extensions"] = json!([FIREFOX_EXTENSION_ID]),
+        _ => {
+            manifest["allowed_origins"] =
+                json!([format!("chrome-extension://{}/", chrome_extension_id)])
+        }
+    }
+    Ok(manifest)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn install(app: &AppHandle, browser: Browser, manifest: &Value) -> Result<PathBuf, String> {
+    use tauri::Manager;
+    let dir = app
+        .path()
+        .home_dir()
+        .map_err(|e| e.to_string())?
+        .join(browser.manifest_dir());
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.json", HOST_NAME));
+    let raw = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    std::fs::write(&path, raw).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+#[cfg(target_os = "windows")]
+fn install(app: &AppHandle, browser: Browser, manifest: &Value) -> Result<PathBuf, String> {
+    let file_name = format!(
+        "{}-{}.json",
+        HOST_NAME,
+        serde_json::to_value(browser)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default()
+    );
+    let path = crate::store::data_path(app, &file_name)?;
+    let raw = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    std::fs::write(&path, raw).map_err(|e| e.to_string())?;
+    let status = std::process::Command::new("reg")
+        .args([
+            "add",
+            &format!(r"{}\{}", browser.registry_key(), HOST_NAME),
+            "/ve",
+            "/t",
+            "REG_SZ",
+            "/d",
+            &path.display().to_string(),
+            "/f",
+        ])
+        .status()
+        .map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err("Failed to register the host in the registry".to_string());
+    }
+    Ok(path)
+}
+
+/// Tauri command: install the native messaging host manifest for the given
+/// browsers (all supported browsers if omitted). Returns the manifest paths.
+/// Called from the frontend via `invoke('install_native_messaging_host', { chromeExtensionId, browsers })`.
+#[tauri::command]
+pub fn install_native_messaging_host(
+    chrome_extension_id: String,
+    browsers: Option<Vec<Browser>>,
+    app: AppHandle,
+) -> Result<Vec<String>, String> {
+    if !chrome_extension_id.chars().all(|c| c.is_ascii_lowercase())
+        || chrome_extension_id.len() != 32
+    {
+        return Err("Invalid Chrome extension id".to_string());
+    }
+    browsers
+        .unwrap_or_else(|| Browser::ALL.to_vec())
+        .into_iter()
+        .map(|browser| {
+            let manifest = manifest(browser, &chrome_extension_id)?;
+            install(&app, browser, &manifest).map(|path| path.display().to_string())
+        })
+        .collect()
+}
@@ -0,0 +1,573 @@
+//! Native notification service.
+//!
+//! Every CrewHub notification goes through here. Notices that belong to a
+//! group (a conversation, crew or schedule) are rate limited per group; with
+//! digest mode on, a burst over the limit is held and coalesced into a single
+//! "12 new updates from Research Crew" notification once the group has been
+//! quiet for a while. Notices from muted conversations or projects are dropped
+//! before any of this ([`crate::mute_rules`]). What gets through is then
+//! subject to the screen lock
+//! ([`crate::session_events`]) and the OS Do Not Disturb state
+//! ([`crate::dnd`]).
+//!
+//! Each notice is tagged with the [`Event`] that caused it, and the user picks
+//! a [`Priority`] per event. Critical notices skip rate limiting and in-app Do
+//! Not Disturb, and play a distinct sound.
+//!
+//! How much a notification reveals is a [`Preview`] level, set globally and
+//! per event: the full content, only who or what it is from, or a generic
+//! "New activity". While the screen is locked or probably shared
+//! ([`crate::screen_share`]) every preview is generic unless turned off.
+//!
+//! A notice may carry a [`NoticeAction`]: a button that, like clicking the
+//! notification itself, hands launch arguments to [`crate::launch`]. The
+//! notification plugin has no actions on desktop, so these notices go to the
+//! platform notifier directly.
+
+use crate::federation::BackendProfile;
+use crate::mute_rules::{self, MuteContext};
+use crate::{
+    announcements, dnd, ephemeral, federation, i18n, launch, quiet_hours, screen_share,
+    session_events, store,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime, State, WebviewWindow};
+use tauri_plugin_notification::NotificationExt;
+
+/// Backend event announcing that a crew run failed.
+pub const CREW_FAILED_EVENT: &str = "crew-failed";
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "notifications.json";
+
+/// Platform sound for critical notifications.
+#[cfg(target_os = "macos")]
+const DEFAULT_CRITICAL_SOUND: &str = "Sosumi";
+#[cfg(target_os = "windows")]
+const DEFAULT_CRITICAL_SOUND: &str = "Alarm";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const DEFAULT_CRITICAL_SOUND: &str = "alarm-clock-elapsed";
+
+/// Window the per-group rate limit applies to.
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// How often pending digests are checked.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What a notification is about; priorities are configured per event.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Event {
+    AgentBlocked,
+    BudgetExceeded,
+    CrewFailed,
+    DevicePaired,
+    QuickAction,
+    ScheduleCompleted,
+    ScheduleFailed,
+    Reminder,
+    /// Low disk space or memory pressure.
+    ResourcePressure,
+    /// Work interrupted by a restart was recovered.
+    TasksRecovered,
+    /// Chat and other notifications sent by the frontend.
+    #[default]
+    Message,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Priority {
+    Normal,
+    Critical,
+}
+
+/// How much of a notification is shown.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Preview {
+    /// Title and body.
+    #[default]
+    Full,
+    /// Title (sender, conversation or crew) only.
+    Sender,
+    /// "New activity".
+    Generic,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notice {
+    pub title: String,
+    pub body: String,
+    /// Conversation / crew name; rate limiting and digests are per group.
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub event: Event,
+    /// Resolved from the event's priority when the notice is sent.
+    #[serde(skip_deserializing)]
+    pub critical: bool,
+    #[serde(default)]
+    pub action: Option<NoticeAction>,
+    /// Conversation and project the notice is about, matched against mute rules.
+    #[serde(default)]
+    pub context: MuteContext,
+}
+
+/// Button on a notification; it and a click on the notification perform `args`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoticeAction {
+    pub label: String,
+    /// Launch arguments, e.g. from [`launch::action_args`].
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NotificationConfig {
+    /// Notifications per group per minute before the limit kicks in.
+    pub max_per_minute: u32,
+    /// Coalesce notifications over the limit into a digest (otherwise drop them).
+    pub digest: bool,
+    /// Seconds a group must be quiet before its digest is shown.
+    pub quiet_seconds: u64,
+    /// In-app Do Not Disturb: only critical notifications are shown.
+    pub do_not_disturb: bool,
+    /// Priority per event; events not listed are normal.
+    pub priorities: BTreeMap<Event, Priority>,
+    /// Sound played for critical notifications (platform sound name, empty for none).
+    pub critical_sound: String,
+    /// Preview level of events not listed in `previews`.
+    pub preview: Preview,
+    /// Preview level per event.
+    pub previews: BTreeMap<Event, Preview>,
+    /// Generic previews while the screen is locked or shared.
+    pub private_when_exposed: bool,
+    /// More process names that mean the screen is shared (see [`crate::screen_share`]).
+    pub sharing_apps: Vec<String>,
+}
+
+impl NotificationConfig {
+    fn priority(&self, event: Event) -> Priority {
+        self.priorities
+            .get(&event)
+            .copied()
+            .unwrap_or(Priority::Normal)
+    }
+
+    fn preview(&self, event: Event) -> Preview {
+        let preview = self.previews.get(&event).copied().unwrap_or(self.preview);
+        let exposed = self.private_when_exposed
+            && (session_events::is_locked() || screen_share::is_sharing(&self.sharing_apps));
+        if exposed {
+            Preview::Generic
+        } else {
+            preview
+        }
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            max_per_minute: 3,
+            digest: true,
+            quiet_seconds: 20,
+            do_not_disturb: false,
+            priorities: [
+                (Event::AgentBlocked, Priority::Critical),
+                (Event::BudgetExceeded, Priority::Critical),
+                (Event::CrewFailed, Priority::Critical),
+            ]
+            .into(),
+            critical_sound: DEFAULT_CRITICAL_SOUND.to_string(),
+            preview: Preview::Full,
+            previews: BTreeMap::new(),
+            private_when_exposed: true,
+            sharing_apps: Vec::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Group {
+    /// When recent notifications for the group were shown.
+    shown: Vec<Instant>,
+    /// Held for the digest.
+    pending: Vec<Notice>,
+    last_pending: Option<Instant>,
+}
+
+/// App state: config and per-group rate limiting.
+pub struct NotificationState {
+    config: Mutex<NotificationConfig>,
+    groups: Mutex<HashMap<String, Group>>,
+}
+
+/// Register state and start the digest flusher.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(NotificationState {
+        config: Mutex::new(store::load_json(app, CONFIG_FILE)),
+        groups: Mutex::new(HashMap::new()),
+    });
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(FLUSH_INTERVAL);
+        flush(&app);
+    });
+}
+
+fn config<R: Runtime>(app: &AppHandle<R>) -> NotificationConfig {
+    app.try_state::<NotificationState>()
+        .and_then(|state| state.config.lock().ok().map(|c| c.clone()))
+        .unwrap_or_default()
+}
+
+/// Notify without a group (no rate limiting).
+pub fn notify<R: Runtime>(
+    app: &AppHandle<R>,
+    event: Event,
+    title: impl Into<String>,
+    body: impl Into<String>,
+) {
+    send(
+        app,
+        Notice {
+            title: title.into(),
+            body: body.into(),
+            group: None,
+            event,
+            critical: false,
+            action: None,
+            context: MuteContext::default(),
+        },
+    );
+}
+
+/// Notify as part of a group (conversation, crew, schedule).
+pub fn notify_in<R: Runtime>(
+    app: &AppHandle<R>,
+    event: Event,
+    group: &str,
+    title: impl Into<String>,
+    body: impl Into<String>,
+) {
+    send(
+        app,
+        Notice {
+            title: title.into(),
+            body: body.into(),
+            group: Some(group.to_string()),
+            event,
+            critical: false,
+            action: None,
+            context: MuteContext::default(),
+        },
+    );
+}
+
+/// Handle a `crew-failed` event from the backend bridge.
+pub fn handle_crew_failed<R: Runtime>(
+    app: &AppHandle<R>,
+    backend: &BackendProfile,
+    payload: Value,
+) {
+    let crew = payload["crew"].as_str().unwrap_or("Crew");
+    let crew = match federation::label(app, &backend.id) {
+        Some(label) => format!("{} ({})", crew, label),
+        None => crew.to_string(),
+    };
+    let body = match payload["error"].as_str() {
+        Some(error) => error.to_string(),
+        None => i18n::t("notify.crewFailedBody"),
+    };
+    notify_in(
+        app,
+        Event::CrewFailed,
+        &crew,
+        i18n::tf("notify.crewFailedTitle", &[("crew", &crew)]),
+        body,
+    );
+}
+
+/// Drop muted notices, rate limit (or hold for the digest), then deliver.
+pub fn send<R: Runtime>(app: &AppHandle<R>, mut notice: Notice) {
+    if !mute_rules::allows(app, &notice.context) {
+        return;
+    }
+    let config = config(app);
+    notice.critical = config.priority(notice.event) == Priority::Critical;
+    if config.do_not_disturb && !notice.critical {
+        return;
+    }
+    let Some(group) = notice.group.clone().filter(|_| !notice.critical) else {
+        return deliver(app, notice);
+    };
+    let Some(state) = app.try_state::<NotificationState>() else {
+        return deliver(app, notice);
+    };
+    let Ok(mut groups) = state.groups.lock() else {
+        return deliver(app, notice);
+    };
+    let now = Instant::now();
+    let entry = groups.entry(group).or_default();
+    entry
+        .shown
+        .retain(|at| now.duration_since(*at) < RATE_WINDOW);
+    if entry.pending.is_empty() && entry.shown.len() < config.max_per_minute as usize {
+        entry.shown.push(now);
+        drop(groups);
+        deliver(app, notice);
+    } else if config.digest {
+        entry.pending.push(notice);
+        entry.last_pending = Some(now);
+    }
+}
+
+/// Show digests for groups that have gone quiet.
+fn flush<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<NotificationState>() else {
+        return;
+    };
+    let quiet = Duration::from_secs(config(app).quiet_seconds);
+    let now = Instant::now();
+    let due: Vec<(String, Vec<Notice>)> = match state.groups.lock() {
+        Ok(mut groups) => groups
+            .iter_mut()
+            .filter(|(_, g)| {
+                g.last_pending
+                    .is_some_and(|at| now.duration_since(at) >= quiet)
+            })
+            .map(|(name, g)| {
+                g.last_pending = None;
+                g.shown.push(now);
+                (name.clone(), std::mem::take(&mut g.pending))
+            })
+            .collect(),
+        Err(_) => return,
+    };
+    for (group, mut pending) in due {
+        if pending.len() == 1 {
+            deliver(app, pending.remove(0));
+            continue;
+        }
+        let latest = pending.last().map(|n| n.title.clone()).unwrap_or_default();
+        deliver(
+            app,
+            Notice {
+                title: i18n::tf(
+                    "notify.digestTitle",
+                    &[("count", &pending.len().to_string()), ("group", &group)],
+                ),
+                body: i18n::tf("notify.digestBody", &[("title", &latest)]),
+                group: Some(group),
+                event: Event::Message,
+                critical: false,
+                action: None,
+                context: MuteContext::default(),
+            },
+        );
+    }
+}
+
+/// Hand to the quiet hours or screen lock hold, or show.
+pub fn deliver<R: Runtime>(app: &AppHandle<R>, notice: Notice) {
+    if !quiet_hours::hold(app, &notice) && !session_events::hold(app, &notice) {
+        show(app, &notice);
+    }
+}
+
+/// Reduce a notice to what its preview level allows.
+fn redact(notice: &Notice, preview: Preview) -> Notice {
+    let mut notice = notice.clone();
+    match preview {
+        Preview::Full => {}
+        Preview::Sender => notice.body = i18n::t("notify.previewHidden"),
+        Preview::Generic => {
+            notice.title = "CrewHub".to_string();
+            notice.body = i18n::t("notify.previewGeneric");
+        }
+    }
+    notice
+}
+
+/// Show a native notification unless Do Not Disturb suppresses it.
+pub fn show<R: Runtime>(app: &AppHandle<R>, notice: &Notice) {
+    if dnd::suppresses(app, notice.critical) {
+        return;
+    }
+    let config = config(app);
+    let notice = &redact(notice, config.preview(notice.event));
+    announcements::notice(app, notice);
+    let sound = Some(config.critical_sound).filter(|s| notice.critical && !s.is_empty());
+    if let Some(action) = &notice.action {
+        let (app, notice, action) = (app.clone(), notice.clone(), action.clone());
+        // Platform notifiers block until the notification is acted on.
+        std::thread::spawn(move || {
+            if let Err(e) = show_with_action(&app, &notice, &action, sound) {
+                eprintln!("[CrewHub] Failed to show notification: {}", e);
+            }
+        });
+        return;
+    }
+    let mut builder = app
+        .notification()
+        .builder()
+        .title(&notice.title)
+        .body(&notice.body);
+    if let Some(sound) = sound {
+        builder = builder.sound(sound);
+    }
+    if let Err(e) = builder.show() {
+        eprintln!("[CrewHub] Failed to show notification: {}", e);
+    }
+}
+
+fn perform<R: Runtime>(app: &AppHandle<R>, action: &NoticeAction) {
+    let (handle, args) = (app.clone(), action.args.clone());
+    let _ = app.run_on_main_thread(move || {
+        launch::handle(&handle, &args, std::path::Path::new(""));
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn show_with_action<R: Runtime>(
+    app: &AppHandle<R>,
+    notice: &Notice,
+    action: &NoticeAction,
+    sound: Option<String>,
+) -> Result<(), String> {
+    use mac_notification_sys::{MainButton, Notification, NotificationResponse};
+    // Same bundle the notification plugin posts as.
+    let bundle = if tauri::is_dev() {
+        "com.apple.Terminal".to_string()
+    } else {
+        app.config().identifier.clone()
+    };
+    let _ = mac_notification_sys::set_application(&bundle);
+    let response = Notification::new()
+        .title(&notice.title)
+        .message(&notice.body)
+        .main_button(MainButton::SingleAction(&action.label))
+        .maybe_sound(sound.as_deref())
+        .wait_for_click(true)
+        .send()
+        .map_err(|e| e.to_string())?;
+    if matches!(
+        response,
+        NotificationResponse::ActionButton(_) | NotificationResponse::Click
+    ) {
+        perform(app, action);
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn show_with_action<R: Runtime>(
+    app: &AppHandle<R>,
+    notice: &Notice,
+    action: &NoticeAction,
+    _sound: Option<String>,
+) -> Result<(), String> {
+    use tauri_winrt_notification::Toast;
+    if crate::toast_activator::is_registered() {
+        return crate::toast_activator::show(app, notice, action);
+    }
+    // Like the notification plugin: the app's own id only once installed.
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let dev = exe
+        .parent()
+        .is_some_and(|dir| dir.ends_with(r"target\debug") || dir.ends_with(r"target\release"));
+    let identifier = app.config().identifier.clone();
+    let app_id = if dev {
+        Toast::POWERSHELL_APP_ID
+    } else {
+        identifier.as_str()
+    };
+    let (handle, on_click) = (app.clone(), action.clone());
+    Toast::new(app_id)
+        .title(&notice.title)
+        .text1(&notice.body)
+        .add_button(&action.label, "open")
+        .on_activated(move |_| {
+            perform(&handle, &on_click);
+            Ok(())
+        })
+        .show()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+fn show_with_action<R: Runtime>(
+    app: &AppHandle<R>,
+    notice: &Notice,
+    action: &NoticeAction,
+    sound: Option<String>,
+) -> Result<(), String> {
+    let mut notification = notify_rust::Notification::new();
+    notification
+        .summary(&notice.title)
+        .body(&notice.body)
+        .auto_icon()
+        // "default" is what notification servers invoke on a click.
+        .action("default", &action.label)
+        .action("open", &action.label);
+    if let Some(sound) = &sound {
+        notification.sound_name(sound);
+    }
+    let handle = notification.show().map_err(|e| e.to_string())?;
+    handle.wait_for_action(|id| {
+        if id == "default" || id == "open" {
+            perform(app, action);
+        }
+    });
+    Ok(())
+}
+
+/// Tauri command: send a notification through the rate limiter. Notices from
+/// ephemeral chat windows are shown right away or not at all (never held).
+/// Called from the frontend via `invoke('send_notification', { notice })`.
+#[tauri::command]
+pub fn send_notification(mut notice: Notice, app: AppHandle, window: WebviewWindow) {
+    if !ephemeral::is_ephemeral_window(window.label()) {
+        return send(&app, notice);
+    }
+    if !mute_rules::allows(&app, &notice.context) {
+        return;
+    }
+    let config = config(&app);
+    notice.critical = config.priority(notice.event) == Priority::Critical;
+    if (config.do_not_disturb && !notice.critical) || session_events::is_locked() {
+        return;
+    }
+    show(&app, &notice);
+}
+
+/// Tauri command: notification rate limit / digest config.
+/// Called from the frontend via `invoke('get_notification_config')`.
+#[tauri::command]
+pub fn get_notification_config(
+    state: State<NotificationState>,
+) -> Result<NotificationConfig, String> {
+    Ok(state.config.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: replace the notification rate limit / digest config.
+/// Called from the settings window via `invoke('configure_notifications', { config })`.
+#[tauri::command]
+pub fn configure_notifications(
+    config: NotificationConfig,
+    app: AppHandle,
+    state: State<NotificationState>,
+) -> Result<NotificationConfig, String> {
+    let mut current = state.config.lock().map_err(|e| e.to_string())?;
+    *current = config;
+    store::save_json(&app, CONFIG_FILE, &*current)?;
+    Ok(current.clone())
+}
@@ -0,0 +1,152 @@
+//! Text recognition (OCR) on screenshots and other images.
+//!
+//! Uses the OS's own engine where there is one — Vision on macOS and
+//! `Windows.Media.Ocr` on Windows, both offline and using the user's
+//! languages — and the `tesseract` CLI otherwise (or when the platform
+//! engine fails). Agents reach it through the `recognize_text` local tool
+//! and the `ocr` option of `capture_screenshot`, so they can work with the
+//! text of a screen instead of the raw image.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Recognize the text in an image file, one line per recognized line.
+pub fn recognize(path: &Path) -> Result<String, String> {
+    if !path.is_file() {
+        return Err(format!("Image not found: {}", path.display()));
+    }
+    match recognize_native(path) {
+        Ok(text) => Ok(text),
+        Err(native) => recognize_tesseract(path).map_err(|e| {
+            if native.is_empty() {
+                e
+            } else {
+                format!("{} ({})", native, e)
+            }
+        }),
+    }
+}
+
+/// `tesseract <image> stdout`, with the languages given in `CREWHUB_OCR_LANGUAGES`
+/// (tesseract's `eng+deu` syntax) when set.
+fn recognize_tesseract(path: &Path) -> Result<String, String> {
+    let mut cmd = Command::new("tesseract");
+    cmd.arg(path).arg("stdout");
+    if let Ok(languages) = std::env::var("CREWHUB_OCR_LANGUAGES") {
+        cmd.args(["-l", &languages]);
+    }
+    let output = cmd
+        .output()
+        .map_err(|_| "No OCR engine found (install tesseract)".to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "tesseract failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn recognize_native(path: &Path) -> Result<String, String> {
+    use objc2::AnyThread;
+    use objc2_foundation::{NSArray, NSDictionary, NSString, NSURL};
+    use objc2_vision::{
+        VNImageRequestHandler, VNRecognizeTextRequest, VNRequest, VNRequestTextRecognitionLevel,
+    };
+
+    let url = NSURL::fileURLWithPath(&NSString::from_str(&path.to_string_lossy()));
+    // SAFETY: an empty options dictionary is valid for any image.
+    let handler = unsafe {
+        VNImageRequestHandler::initWithURL_options(
+            VNImageRequestHandler::alloc(),
+            &url,
+            &NSDictionary::new(),
+        )
+    };
+    let request = VNRecognizeTextRequest::new();
+    request.setRecognitionLevel(VNRequestTextRecognitionLevel::Accurate);
+    request.setUsesLanguageCorrection(true);
+    let requests = NSArray::from_slice(&[&**request as &VNRequest]);
+    handler.performRequests_error(&requests).map_err(|e| {
+        format!(
+            "Vision text recognition failed: {}",
+            e.localizedDescription()
+        )
+    })?;
+
+    let lines: Vec<String> = request
+        .results()
+        .map(|observations| {
+            observations
+                .iter()
+                .filter_map(|observation| observation.topCandidates(1).firstObject())
+                .map(|candidate| candidate.string().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(lines.join("\n"))
+}
+
+#[cfg(target_os = "windows")]
+fn recognize_native(path: &Path) -> Result<String, String> {
+    use windows::core::HSTRING;
+    use windows::Graphics::Imaging::BitmapDecoder;
+    use windows::Media::Ocr::OcrEngine;
+    use windows::Storage::{FileAccessMode, StorageFile};
+
+    let err = |e: windows::core::Error| format!("Windows OCR failed: {}", e.message());
+    let engine = OcrEngine::TryCreateFromUserProfileLanguages()
+        .map_err(|_| "No Windows OCR language pack is installed".to_string())?;
+
+    // The engine refuses images larger than MaxImageDimension on either side.
+    let max = OcrEngine::MaxImageDimension().map_err(err)?;
+    let image = image::open(path).map_err(|e| e.to_string())?;
+    let scaled = if image.width() > max || image.height() > max {
+        let scaled = std::env::temp_dir().join(format!("crewhub-ocr-{}.png", uuid::Uuid::new_v4()));
+        image
+            .resize(max, max, image::imageops::FilterType::Triangle)
+            .save_with_format(&scaled, image::ImageFormat::Png)
+            .map_err(|e| e.to_string())?;
+        Some(scaled)
+    } else {
+        None
+    };
+    let absolute =
+        std::path::absolute(scaled.as_deref().unwrap_or(path)).map_err(|e| e.to_string())?;
+
+    let result = (|| {
+        let file =
+            StorageFile::GetFileFromPathAsync(&HSTRING::from(absolute.as_os_str()))?.get()?;
+        let stream = file.OpenAsync(FileAccessMode::Read)?.get()?;
+        let bitmap = BitmapDecoder::CreateAsync(&stream)?
+            .get()?
+            .GetSoftwareBitmapAsync()?
+            .get()?;
+        let result = engine.RecognizeAsync(&bitmap)?.get()?;
+        let mut lines = Vec::new();
+        for line in result.Lines()? {
+            lines.push(line.Text()?.to_string());
+        }
+        Ok(lines.join("\n"))
+    })()
+    .map_err(err);
+    if let Some(scaled) = scaled {
+        let _ = std::fs::remove_file(scaled);
+    }
+    result
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn recognize_native(_path: &Path) -> Result<String, String> {
+    Err(String::new())
+}
+
+/// Tauri command: recognize the text in an image (e.g. a path from `capture_screenshot`).
+/// Called from the frontend via `invoke('recognize_text', { path })`.
+#[tauri::command]
+pub async fn recognize_text(path: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || recognize(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+}
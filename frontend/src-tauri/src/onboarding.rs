@@ -0,0 +1,224 @@
+//! First-run onboarding.
+//!
+//! Until onboarding is completed, the app opens a dedicated onboarding window
+//! at launch. It walks the user through finding and testing a backend and
+//! explains where the tray icon lives on their OS. Completion is persisted in
+//! `onboarding.json` and mirrored into every window's `crewhub-onboarded`
+//! localStorage flag via [`init_script`], so the setting has one source of
+//! truth; `reset_onboarding` starts the flow again.
+
+use crate::{federation, store};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+
+/// Label for the onboarding window.
+pub const ONBOARDING_WINDOW_LABEL: &str = "onboarding";
+
+/// Persisted completion state (app data dir).
+const STATE_FILE: &str = "onboarding.json";
+
+/// Timeout for one backend health probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Mirrors the persisted state for [`init_script`], which has no app handle.
+static COMPLETED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OnboardingState {
+    /// Unix seconds when onboarding was completed.
+    pub completed_at: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStatus {
+    completed_at: Option<i64>,
+    platform: &'static str,
+    /// Where to find the tray icon on this OS and how to keep it visible.
+    tray_hint: &'static str,
+    backend_url: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendProbe {
+    url: String,
+    reachable: bool,
+    latency_ms: Option<u64>,
+    error: Option<String>,
+}
+
+/// Load the completion state and open the onboarding window on first run.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let state: OnboardingState = store::load_json(app, STATE_FILE);
+    COMPLETED.store(state.completed_at.is_some(), Ordering::Relaxed);
+    if state.completed_at.is_none() {
+        open_or_focus_onboarding(app);
+    }
+}
+
+/// JS fragment syncing the frontend's `crewhub-onboarded` flag with the persisted state.
+pub fn init_script() -> &'static str {
+    if COMPLETED.load(Ordering::Relaxed) {
+        "localStorage.setItem('crewhub-onboarded', 'true');"
+    } else {
+        "localStorage.removeItem('crewhub-onboarded');"
+    }
+}
+
+fn onboarding_init_script() -> String {
+    format!(
+        "window.__TAURI_VIEW__ = 'onboarding'; {}",
+        crate::base_init(ONBOARDING_WINDOW_LABEL)
+    )
+}
+
+/// Open or focus the onboarding window (620×560).
+pub fn open_or_focus_onboarding<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window(ONBOARDING_WINDOW_LABEL) {
+        crate::show_and_focus(&window);
+        return;
+    }
+
+    let result = WebviewWindowBuilder::new(
+        app,
+        ONBOARDING_WINDOW_LABEL,
+        crate::view_url("view=onboarding"),
+    )
+    .title("Welcome to CrewHub")
+    .inner_size(620.0, 560.0)
+    .resizable(false)
+    .fullscreen(false)
+    .decorations(true)
+    .always_on_top(false)
+    .center()
+    .user_agent(&crate::request_headers::user_agent())
+    .on_new_window(crate::external_links::new_window_handler(app))
+    .initialization_script(onboarding_init_script())
+    .build();
+
+    match result {
+        Ok(window) => crate::show_and_focus(&window),
+        Err(e) => eprintln!("[CrewHub] Failed to create onboarding window: {}", e),
+    }
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, state: &OnboardingState) -> Result<(), String> {
+    store::save_json(app, STATE_FILE, state)?;
+    COMPLETED.store(state.completed_at.is_some(), Ordering::Relaxed);
+    Ok(())
+}
+
+fn tray_hint() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "CrewHub lives in the menu bar at the top right of the screen. If you can't see it, \
+         the menu bar may be full: quit a few menu bar apps or use a menu bar manager."
+    } else if cfg!(target_os = "windows") {
+        "CrewHub lives in the notification area. Click the ^ arrow on the taskbar and drag \
+         the CrewHub icon onto the taskbar, or enable it under Settings → Personalization → \
+         Taskbar → Other system tray icons."
+    } else {
+        "CrewHub shows a StatusNotifier (AppIndicator) tray icon. On GNOME, install the \
+         \"AppIndicator and KStatusNotifierItem Support\" extension to see it."
+    }
+}
+
+/// Health-check a backend via `GET /api/health`.
+pub fn probe(url: &str) -> BackendProbe {
+    let started = Instant::now();
+    let result = ureq::AgentBuilder::new()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .get(&format!("{}/api/health", url.trim_end_matches('/')))
+        .call();
+    let (reachable, error) = match result {
+        Ok(_) => (true, None),
+        Err(ureq::Error::Status(code, _)) => (false, Some(format!("Backend returned {}", code))),
+        Err(e) => (false, Some(e.to_string())),
+    };
+    BackendProbe {
+        url: url.to_string(),
+        reachable,
+        latency_ms: reachable.then(|| started.elapsed().as_millis() as u64),
+        error,
+    }
+}
+
+/// Tauri command: onboarding completion state plus per-OS hints.
+/// Called from the frontend via `invoke('get_onboarding_status')`.
+#[tauri::command]
+pub fn get_onboarding_status(app: AppHandle) -> OnboardingStatus {
+    let state: OnboardingState = store::load_json(&app, STATE_FILE);
+    OnboardingStatus {
+        completed_at: state.completed_at,
+        platform: std::env::consts::OS,
+        tray_hint: tray_hint(),
+        backend_url: crate::backend_url(),
+    }
+}
+
+/// Tauri command: probe the configured backends and the usual local addresses.
+/// Called from the onboarding window via `invoke('detect_backends')`.
+#[tauri::command]
+pub async fn detect_backends(app: AppHandle) -> Result<Vec<BackendProbe>, String> {
+    let mut candidates: Vec<String> = federation::enabled(&app)
+        .into_iter()
+        .map(|backend| backend.url)
+        .collect();
+    candidates.push(crate::backend_url());
+    candidates.push("http://localhost:8091".to_string());
+    candidates.push("http://127.0.0.1:8091".to_string());
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|url| seen.insert(url.trim_end_matches('/').to_string()));
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let handles: Vec<_> = candidates
+            .into_iter()
+            .map(|url| std::thread::spawn(move || probe(&url)))
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok())
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Tauri command: test the connection to one backend URL.
+/// Called from the onboarding window via `invoke('test_backend_connection', { url })`.
+#[tauri::command]
+pub async fn test_backend_connection(url: String) -> Result<BackendProbe, String> {
+    tauri::async_runtime::spawn_blocking(move || probe(&url))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command: mark onboarding done, close the wizard and open the chat.
+/// Called from the onboarding window via `invoke('complete_onboarding')`.
+#[tauri::command]
+pub fn complete_onboarding(app: AppHandle) -> Result<(), String> {
+    save(
+        &app,
+        &OnboardingState {
+            completed_at: Some(chrono::Local::now().timestamp()),
+        },
+    )?;
+    if let Some(window) = app.get_webview_window(ONBOARDING_WINDOW_LABEL) {
+        let _ = window.close();
+    }
+    crate::open_or_focus_chat(&app);
+    Ok(())
+}
+
+/// Tauri command: forget onboarding completion and show the wizard again.
+/// Called from the settings window via `invoke('reset_onboarding')`.
+#[tauri::command]
+pub fn reset_onboarding(app: AppHandle) -> Result<(), String> {
+    save(&app, &OnboardingState::default())?;
+    open_or_focus_onboarding(&app);
+    Ok(())
+}
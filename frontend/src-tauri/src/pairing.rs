@@ -0,0 +1,279 @@
+//! QR-code pairing for mobile companions.
+//!
+//! `start_pairing` mints a short-lived pairing token, registers it with the
+//! backend (`POST /api/pairing/tokens`), and opens a small window showing a
+//! QR code for the pairing URL. The phone scans it and completes the
+//! handshake with the backend, which announces it with a `pairing-completed`
+//! event on its stream; the bridge hands that to this module, which closes
+//! the window and tells the user. Closing the window early revokes the token.
+
+use crate::federation::{self, BackendProfile};
+use crate::notifications::{self, Event};
+use crate::{backend, i18n, local_http};
+use qrcode::render::svg;
+use qrcode::QrCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, WebviewWindowBuilder};
+
+/// Backend event sent when a phone finished pairing.
+pub const PAIRING_COMPLETED_EVENT: &str = "pairing-completed";
+
+/// Label for the pairing window.
+pub const PAIRING_WINDOW_LABEL: &str = "pairing";
+
+/// How long a pairing code stays valid.
+const TOKEN_TTL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingSession {
+    token: String,
+    backend_id: String,
+    /// URL encoded in the QR code.
+    url: String,
+    /// QR code as an SVG document.
+    svg: String,
+    /// Unix seconds.
+    expires_at: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PairingCompleted {
+    token: String,
+    #[serde(default)]
+    device_name: Option<String>,
+}
+
+/// App state: the pairing in progress (one at a time).
+#[derive(Default)]
+pub struct PairingState {
+    session: Mutex<Option<PairingSession>>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(PairingState::default());
+}
+
+/// This machine's LAN address, for pairing URLs when the backend runs on localhost.
+/// Connecting a UDP socket sends nothing; it only selects the outgoing interface.
+fn lan_ip() -> Option<std::net::IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:9").ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+/// Pairing URL when the backend doesn't supply one: its own URL, reachable from the LAN.
+fn fallback_url(backend: &BackendProfile, token: &str) -> String {
+    let mut url = url::Url::parse(&backend.url)
+        .unwrap_or_else(|_| url::Url::parse("http://localhost:8091").expect("valid URL"));
+    if matches!(url.host_str(), Some("localhost" | "127.0.0.1")) {
+        if let Some(ip) = lan_ip() {
+            let _ = url.set_host(Some(&ip.to_string()));
+        }
+    }
+    url.set_path("/pair");
+    url.query_pairs_mut().clear().append_pair("token", token);
+    url.to_string()
+}
+
+fn create_session(backend: &BackendProfile) -> Result<PairingSession, String> {
+    let token = local_http::new_token();
+    let expires_at = chrono::Local::now().timestamp() + TOKEN_TTL.as_secs() as i64;
+    let desktop_name = std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "CrewHub desktop".to_string());
+    let reply = backend::post_json_to(
+        &backend.url,
+        "/api/pairing/tokens",
+        &json!({ "token": token, "expiresAt": expires_at, "desktopName": desktop_name }),
+    )?;
+    let url = reply
+        .get("pairUrl")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| fallback_url(backend, &token));
+
+    let svg = QrCode::new(url.as_bytes())
+        .map_err(|e| e.to_string())?
+        .render::<svg::Color>()
+        .min_dimensions(240, 240)
+        .quiet_zone(true)
+        .build();
+    Ok(PairingSession {
+        token,
+        backend_id: backend.id.clone(),
+        url,
+        svg,
+        expires_at,
+    })
+}
+
+fn revoke(backend_url: &str, token: &str) {
+    let path = format!("/api/pairing/tokens/{}/revoke", backend::urlencode(token));
+    if let Err(e) = backend::post_json_to(backend_url, &path, &Value::Null) {
+        eprintln!("[CrewHub] Failed to revoke pairing token: {}", e);
+    }
+}
+
+/// Forget the pending session and revoke its token (window closed or expired).
+fn cancel<R: Runtime>(app: &AppHandle<R>, token: &str) {
+    let state = app.state::<PairingState>();
+    let session = match state.session.lock() {
+        Ok(mut slot) if slot.as_ref().is_some_and(|s| s.token == token) => slot.take(),
+        _ => None,
+    };
+    if let Some(session) = session {
+        if let Some(backend) = federation::enabled(app)
+            .into_iter()
+            .find(|b| b.id == session.backend_id)
+        {
+            std::thread::spawn(move || revoke(&backend.url, &session.token));
+        }
+    }
+}
+
+fn open_window<R: Runtime>(app: &AppHandle<R>, token: &str) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(PAIRING_WINDOW_LABEL) {
+        let _ = window.emit("pairing-updated", ());
+        crate::show_and_focus(&window);
+        return Ok(());
+    }
+    let window =
+        WebviewWindowBuilder::new(app, PAIRING_WINDOW_LABEL, crate::view_url("view=pairing"))
+            .title("Pair a Phone")
+            .inner_size(360.0, 480.0)
+            .resizable(false)
+            .fullscreen(false)
+            .decorations(true)
+            .always_on_top(true)
+            .user_agent(&crate::request_headers::user_agent())
+            .on_new_window(crate::external_links::new_window_handler(app))
+            .initialization_script(format!(
+                "window.__TAURI_VIEW__ = 'pairing'; {}",
+                crate::base_init(PAIRING_WINDOW_LABEL)
+            ))
+            .build()
+            .map_err(|e| format!("Failed to create pairing window: {}", e))?;
+
+    let handle = app.clone();
+    let token = token.to_string();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Destroyed = event {
+            cancel(&handle, &token);
+        }
+    });
+    crate::show_and_focus(&window);
+    Ok(())
+}
+
+/// Handle a `pairing-completed` event from the backend bridge.
+pub fn handle_completed<R: Runtime>(app: &AppHandle<R>, payload: Value) {
+    let Ok(event) = serde_json::from_value::<PairingCompleted>(payload) else {
+        eprintln!("[CrewHub] Ignoring malformed pairing event");
+        return;
+    };
+    let state = app.state::<PairingState>();
+    let matched = match state.session.lock() {
+        Ok(mut slot) if slot.as_ref().is_some_and(|s| s.token == event.token) => {
+            slot.take().is_some()
+        }
+        _ => false,
+    };
+    if !matched {
+        return;
+    }
+    if let Some(window) = app.get_webview_window(PAIRING_WINDOW_LABEL) {
+        let _ = window.close();
+    }
+    let device = event
+        .device_name
+        .unwrap_or_else(|| i18n::t("notify.pairedDevice"));
+    notifications::notify(
+        app,
+        Event::DevicePaired,
+        i18n::t("notify.pairedTitle"),
+        i18n::tf("notify.pairedBody", &[("device", &device)]),
+    );
+    let _ = app.emit("pairing-completed", json!({ "deviceName": device }));
+}
+
+/// Tauri command: start pairing a phone with a backend (primary if omitted)
+/// and open the QR code window.
+/// Called from the frontend via `invoke('start_pairing', { backendId })`.
+#[tauri::command]
+pub async fn start_pairing(
+    backend_id: Option<String>,
+    app: AppHandle,
+) -> Result<PairingSession, String> {
+    let backend_id = backend_id.unwrap_or_else(|| federation::DEFAULT_BACKEND_ID.to_string());
+    let backend = federation::enabled(&app)
+        .into_iter()
+        .find(|b| b.id == backend_id)
+        .ok_or_else(|| format!("Backend not connected: {}", backend_id))?;
+    let session = tauri::async_runtime::spawn_blocking(move || create_session(&backend))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let state = app.state::<PairingState>();
+    let previous = state
+        .session
+        .lock()
+        .map_err(|e| e.to_string())?
+        .replace(session.clone());
+    if let Some(previous) = previous {
+        cancel_token_only(&app, previous);
+    }
+    open_window(&app, &session.token)?;
+
+    // Revoke automatically once the code expires.
+    let handle = app.clone();
+    let token = session.token.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(TOKEN_TTL);
+        cancel(&handle, &token);
+        if let Some(window) = handle.get_webview_window(PAIRING_WINDOW_LABEL) {
+            let _ = window.emit("pairing-expired", ());
+        }
+    });
+    Ok(session)
+}
+
+/// Revoke a replaced session's token without touching the current one.
+fn cancel_token_only(app: &AppHandle, session: PairingSession) {
+    if let Some(backend) = federation::enabled(app)
+        .into_iter()
+        .find(|b| b.id == session.backend_id)
+    {
+        std::thread::spawn(move || revoke(&backend.url, &session.token));
+    }
+}
+
+/// Tauri command: the pairing in progress (QR code, URL, expiry), if any.
+/// Called from the pairing window via `invoke('get_pairing_session')`.
+#[tauri::command]
+pub fn get_pairing_session(state: State<PairingState>) -> Result<Option<PairingSession>, String> {
+    Ok(state.session.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: cancel the pairing in progress and close its window.
+/// Called from the frontend via `invoke('cancel_pairing')`.
+#[tauri::command]
+pub fn cancel_pairing(app: AppHandle, state: State<PairingState>) -> Result<(), String> {
+    let token = state
+        .session
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_ref()
+        .map(|s| s.token.clone());
+    if let Some(token) = token {
+        cancel(&app, &token);
+    }
+    if let Some(window) = app.get_webview_window(PAIRING_WINDOW_LABEL) {
+        let _ = window.close();
+    }
+    Ok(())
+}
@@ -0,0 +1,198 @@
+//! Pinned conversations.
+//!
+//! The user's pinned conversations, in their chosen order. Pins are mirrored
+//! into the tray ("📌 Pinned" submenu) and platform shell menus, and
+//! `open_pinned(index)` jumps straight to one (bound to hotkeys).
+
+use crate::{federation, store};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Persisted pins (app data dir).
+const PINS_FILE: &str = "pinned-conversations.json";
+
+/// Prefix of tray menu item ids that open a pin (followed by its index).
+pub const MENU_ID_PREFIX: &str = "pinned:";
+
+/// Event sent to the chat window to switch conversation.
+const OPEN_CONVERSATION_EVENT: &str = "open-conversation";
+
+/// A conversation on one of the connected backends.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationRef {
+    pub session_key: String,
+    pub title: String,
+    #[serde(default = "default_backend")]
+    pub backend_id: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedConversation {
+    #[serde(flatten)]
+    pub conversation: ConversationRef,
+    /// Unix seconds.
+    pub pinned_at: i64,
+}
+
+pub fn default_backend() -> String {
+    federation::DEFAULT_BACKEND_ID.to_string()
+}
+
+/// App state: pins in display order, plus a conversation waiting for a
+/// chat window that is still loading.
+pub struct PinState {
+    pins: Mutex<Vec<PinnedConversation>>,
+    pending: Mutex<Option<ConversationRef>>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let pins: Vec<PinnedConversation> = store::load_json(app, PINS_FILE);
+    app.manage(PinState {
+        pins: Mutex::new(pins),
+        pending: Mutex::new(None),
+    });
+}
+
+/// Current pins in display order.
+pub fn pinned<R: Runtime>(app: &AppHandle<R>) -> Vec<PinnedConversation> {
+    app.try_state::<PinState>()
+        .and_then(|state| state.pins.lock().ok().map(|p| p.clone()))
+        .unwrap_or_default()
+}
+
+/// Open the chat window on the pin at `index` (zero-based).
+pub fn open<R: Runtime>(app: &AppHandle<R>, index: usize) -> Result<(), String> {
+    let pin = pinned(app)
+        .get(index)
+        .cloned()
+        .ok_or_else(|| format!("No pinned conversation at position {}", index + 1))?;
+    open_conversation(app, pin.conversation);
+    Ok(())
+}
+
+/// Show the chat window on a conversation. A window that is still being
+/// created picks it up via `take_pending_conversation` once loaded.
+pub fn open_conversation<R: Runtime>(app: &AppHandle<R>, conversation: ConversationRef) {
+    let already_open = app.get_webview_window(crate::CHAT_WINDOW_LABEL).is_some();
+    if !already_open {
+        if let Ok(mut pending) = app.state::<PinState>().pending.lock() {
+            *pending = Some(conversation.clone());
+        }
+    }
+    crate::open_or_focus_chat(app);
+    if already_open {
+        let _ = app.emit_to(
+            crate::CHAT_WINDOW_LABEL,
+            OPEN_CONVERSATION_EVENT,
+            &conversation,
+        );
+    }
+}
+
+fn save_and_apply(
+    app: &AppHandle,
+    pins: &[PinnedConversation],
+) -> Result<Vec<PinnedConversation>, String> {
+    store::save_json(app, PINS_FILE, &pins)?;
+    crate::refresh_tray_menu(app);
+    #[cfg(target_os = "windows")]
+    crate::jump_list::refresh(app);
+    crate::search_index::refresh(app);
+    let _ = app.emit("pins-changed", pins);
+    Ok(pins.to_vec())
+}
+
+/// Tauri command: pinned conversations in display order.
+/// Called from the frontend via `invoke('list_pinned_conversations')`.
+#[tauri::command]
+pub fn list_pinned_conversations(
+    state: State<PinState>,
+) -> Result<Vec<PinnedConversation>, String> {
+    Ok(state.pins.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: pin a conversation (appended; re-pinning updates the title).
+/// Called from the frontend via `invoke('pin_conversation', { sessionKey, title, backendId })`.
+#[tauri::command]
+pub fn pin_conversation(
+    session_key: String,
+    title: String,
+    backend_id: Option<String>,
+    app: AppHandle,
+    state: State<PinState>,
+) -> Result<Vec<PinnedConversation>, String> {
+    if crate::ephemeral::is_ephemeral(&session_key) {
+        return Err("Ephemeral conversations can't be pinned".to_string());
+    }
+    let backend_id = backend_id.unwrap_or_else(default_backend);
+    let mut pins = state.pins.lock().map_err(|e| e.to_string())?;
+    match pins.iter_mut().find(|p| {
+        p.conversation.session_key == session_key && p.conversation.backend_id == backend_id
+    }) {
+        Some(pin) => pin.conversation.title = title,
+        None => pins.push(PinnedConversation {
+            conversation: ConversationRef {
+                session_key,
+                title,
+                backend_id,
+            },
+            pinned_at: chrono::Local::now().timestamp(),
+        }),
+    }
+    save_and_apply(&app, &pins)
+}
+
+/// Tauri command: unpin a conversation.
+/// Called from the frontend via `invoke('unpin_conversation', { sessionKey, backendId })`.
+#[tauri::command]
+pub fn unpin_conversation(
+    session_key: String,
+    backend_id: Option<String>,
+    app: AppHandle,
+    state: State<PinState>,
+) -> Result<Vec<PinnedConversation>, String> {
+    let backend_id = backend_id.unwrap_or_else(default_backend);
+    let mut pins = state.pins.lock().map_err(|e| e.to_string())?;
+    pins.retain(|p| {
+        !(p.conversation.session_key == session_key && p.conversation.backend_id == backend_id)
+    });
+    save_and_apply(&app, &pins)
+}
+
+/// Tauri command: reorder pins; `sessionKeys` lists them in the new order
+/// (pins not mentioned keep their relative order at the end).
+/// Called from the frontend via `invoke('reorder_pinned_conversations', { sessionKeys })`.
+#[tauri::command]
+pub fn reorder_pinned_conversations(
+    session_keys: Vec<String>,
+    app: AppHandle,
+    state: State<PinState>,
+) -> Result<Vec<PinnedConversation>, String> {
+    let mut pins = state.pins.lock().map_err(|e| e.to_string())?;
+    pins.sort_by_key(|p| {
+        session_keys
+            .iter()
+            .position(|k| *k == p.conversation.session_key)
+            .unwrap_or(usize::MAX)
+    });
+    save_and_apply(&app, &pins)
+}
+
+/// Tauri command: open the pin at `index` (zero-based) in the chat window.
+/// Called from the frontend (and hotkeys) via `invoke('open_pinned', { index })`.
+#[tauri::command]
+pub fn open_pinned(index: usize, app: AppHandle) -> Result<(), String> {
+    open(&app, index)
+}
+
+/// Tauri command: conversation the chat window should show after loading, if any.
+/// Called from the frontend via `invoke('take_pending_conversation')`.
+#[tauri::command]
+pub fn take_pending_conversation(
+    state: State<PinState>,
+) -> Result<Option<ConversationRef>, String> {
+    Ok(state.pending.lock().map_err(|e| e.to_string())?.take())
+}
@@ -0,0 +1,456 @@
+//! xdg-desktop-portal integration (Linux).
+//!
+//! Inside Flatpak or Snap the host's tools and files are out of reach:
+//! `grim` isn't there, `~/.config/autostart` is the sandbox's own copy, and
+//! key grabs don't work under Wayland. Those features go through portals
+//! instead:
+//! - file dialogs: GTK uses the FileChooser portal once `GTK_USE_PORTAL` is
+//!   set ([`prepare`]);
+//! - autostart: Background portal ([`request_background`]);
+//! - screenshots: Screenshot portal ([`screenshot`]);
+//! - global hotkeys: GlobalShortcuts portal ([`bind_shortcuts`]), also used
+//!   outside a sandbox on Wayland.
+//!
+//! Which portals the desktop provides is detected once ([`status`]) and sent
+//! to the frontend with a note for each feature that degrades
+//! (`portal-status` event, `get_portal_status`).
+
+use crate::capabilities::{self, Capability};
+use futures_util::StreamExt;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Runtime};
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Type, Value};
+use zbus::{Connection, Proxy};
+
+const DESTINATION: &str = "org.freedesktop.portal.Desktop";
+const OBJECT_PATH: &str = "/org/freedesktop/portal/desktop";
+
+const FILE_CHOOSER: &str = "org.freedesktop.portal.FileChooser";
+const BACKGROUND: &str = "org.freedesktop.portal.Background";
+const SCREENSHOT: &str = "org.freedesktop.portal.Screenshot";
+const GLOBAL_SHORTCUTS: &str = "org.freedesktop.portal.GlobalShortcuts";
+
+type Results = HashMap<String, OwnedValue>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Sandbox {
+    Flatpak,
+    Snap,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortalStatus {
+    pub sandbox: Option<Sandbox>,
+    pub file_chooser: bool,
+    pub background: bool,
+    pub screenshot: bool,
+    pub global_shortcuts: bool,
+    /// What doesn't work on this desktop and why, for the settings window.
+    pub notes: Vec<String>,
+}
+
+static STATUS: OnceLock<PortalStatus> = OnceLock::new();
+static CONNECTION: OnceLock<Connection> = OnceLock::new();
+
+/// Current GlobalShortcuts session (replaced on every bind).
+static SHORTCUT_SESSION: Mutex<Option<OwnedObjectPath>> = Mutex::new(None);
+
+pub fn sandbox() -> Option<Sandbox> {
+    if Path::new("/.flatpak-info").exists() {
+        Some(Sandbox::Flatpak)
+    } else if std::env::var_os("SNAP_NAME").is_some() {
+        Some(Sandbox::Snap)
+    } else {
+        None
+    }
+}
+
+/// Environment for portal-backed dialogs; call before any GTK setup.
+pub fn prepare() {
+    if sandbox().is_some() && std::env::var_os("GTK_USE_PORTAL").is_none() {
+        std::env::set_var("GTK_USE_PORTAL", "1");
+    }
+}
+
+/// Detect the available portals in the background and tell the frontend
+/// (and [`crate::capabilities`], which logs what degrades).
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let status = status();
+        let sandboxed = status.sandbox.is_some();
+        // Outside a sandbox these work without portals.
+        let portal = |present: bool, reason: &str| {
+            if present || !sandboxed {
+                Ok(())
+            } else {
+                Err(reason.to_string())
+            }
+        };
+        capabilities::record(
+            &app,
+            Capability::FileDialogs,
+            portal(
+                status.file_chooser,
+                "The desktop provides no FileChooser portal; install xdg-desktop-portal-gtk \
+                 or xdg-desktop-portal-kde",
+            ),
+        );
+        capabilities::record(
+            &app,
+            Capability::Autostart,
+            portal(
+                status.background,
+                "The desktop provides no Background portal",
+            ),
+        );
+        if sandboxed {
+            capabilities::record(
+                &app,
+                Capability::Screenshots,
+                portal(
+                    status.screenshot,
+                    "The desktop provides no Screenshot portal",
+                ),
+            );
+        }
+        let _ = app.emit("portal-status", status);
+    });
+}
+
+/// Portal availability (detected on first use).
+pub fn status() -> &'static PortalStatus {
+    STATUS.get_or_init(|| tauri::async_runtime::block_on(detect()))
+}
+
+async fn detect() -> PortalStatus {
+    let sandbox = sandbox();
+    let mut versions = HashMap::new();
+    if let Ok(connection) = connection().await {
+        for interface in [FILE_CHOOSER, BACKGROUND, SCREENSHOT, GLOBAL_SHORTCUTS] {
+            let version = async {
+                let proxy = Proxy::new(&connection, DESTINATION, OBJECT_PATH, interface).await?;
+                proxy.get_property::<u32>("version").await
+            };
+            if let Ok(version) = version.await {
+                versions.insert(interface, version);
+            }
+        }
+    }
+    let has = |interface| versions.contains_key(interface);
+
+    let mut notes = Vec::new();
+    if sandbox.is_some() {
+        if !has(FILE_CHOOSER) {
+            notes.push(
+                "File dialogs need the FileChooser portal; install xdg-desktop-portal-gtk or \
+                 xdg-desktop-portal-kde."
+                    .to_string(),
+            );
+        }
+        if !has(BACKGROUND) {
+            notes.push(
+                "Start at login is unavailable: the desktop provides no Background portal."
+                    .to_string(),
+            );
+        }
+        if !has(SCREENSHOT) {
+            notes.push(
+                "Screenshots are unavailable: the desktop provides no Screenshot portal."
+                    .to_string(),
+            );
+        }
+    }
+    if (sandbox.is_some() || crate::wayland::is_wayland()) && !has(GLOBAL_SHORTCUTS) {
+        notes.push(
+            "Global hotkeys need the GlobalShortcuts portal (GNOME 48, KDE Plasma 5.27 or \
+             newer); add them as custom shortcuts in the desktop's keyboard settings instead."
+                .to_string(),
+        );
+    }
+
+    PortalStatus {
+        sandbox,
+        file_chooser: has(FILE_CHOOSER),
+        background: has(BACKGROUND),
+        screenshot: has(SCREENSHOT),
+        global_shortcuts: has(GLOBAL_SHORTCUTS),
+        notes,
+    }
+}
+
+async fn connection() -> Result<Connection, String> {
+    if let Some(connection) = CONNECTION.get() {
+        return Ok(connection.clone());
+    }
+    let connection = Connection::session().await.map_err(|e| e.to_string())?;
+    Ok(CONNECTION.get_or_init(|| connection).clone())
+}
+
+/// A fresh `handle_token` (object path element).
+fn token() -> String {
+    format!("crewhub_{}", uuid::Uuid::new_v4().simple())
+}
+
+/// Call a portal method that answers through a `Request` object and wait
+/// for its `Response`. `body` must carry `token` as its `handle_token`.
+async fn request<B>(
+    connection: &Connection,
+    interface: &'static str,
+    method: &'static str,
+    token: &str,
+    body: &B,
+) -> Result<Results, String>
+where
+    B: serde::Serialize + Type,
+{
+    let err = |e: zbus::Error| e.to_string();
+    let sender = connection
+        .unique_name()
+        .ok_or("Not connected to the session bus")?
+        .trim_start_matches(':')
+        .replace('.', "_");
+    // Subscribe before calling so a fast response isn't missed.
+    let path = format!("{}/request/{}/{}", OBJECT_PATH, sender, token);
+    let request = Proxy::new(
+        connection,
+        DESTINATION,
+        path,
+        "org.freedesktop.portal.Request",
+    )
+    .await
+    .map_err(err)?;
+    let mut responses = request.receive_signal("Response").await.map_err(err)?;
+
+    let portal = Proxy::new(connection, DESTINATION, OBJECT_PATH, interface)
+        .await
+        .map_err(err)?;
+    let _: OwnedObjectPath = portal.call(method, body).await.map_err(err)?;
+
+    let response = responses
+        .next()
+        .await
+        .ok_or("The portal closed the request")?;
+    let (code, results): (u32, Results) = response.body().deserialize().map_err(err)?;
+    match code {
+        0 => Ok(results),
+        1 => Err("Cancelled".to_string()),
+        _ => Err(format!("The {} portal request failed", method)),
+    }
+}
+
+fn unavailable(feature: &str, interface: &str) -> String {
+    format!(
+        "{} is unavailable: the desktop provides no {} portal",
+        feature,
+        interface.rsplit('.').next().unwrap_or(interface)
+    )
+}
+
+/// Ask the Background portal to (not) start CrewHub at login. Returns whether
+/// autostart is now on (the user or desktop may refuse).
+pub fn request_background(autostart: bool) -> Result<bool, String> {
+    if !status().background {
+        return Err(unavailable("Start at login", BACKGROUND));
+    }
+    let commandline = vec![std::env::current_exe()
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "crewhub".to_string())];
+    tauri::async_runtime::block_on(async move {
+        let connection = connection().await?;
+        let token = token();
+        let options = HashMap::from([
+            ("handle_token", Value::from(token.as_str())),
+            ("reason", Value::from("Start CrewHub when you log in")),
+            ("autostart", Value::from(autostart)),
+            ("commandline", Value::from(commandline)),
+        ]);
+        let results = request(
+            &connection,
+            BACKGROUND,
+            "RequestBackground",
+            &token,
+            &("", options),
+        )
+        .await?;
+        Ok(results
+            .get("autostart")
+            .and_then(|v| bool::try_from(v).ok())
+            .unwrap_or(false))
+    })
+}
+
+/// Take a screenshot through the Screenshot portal and copy it to `path`.
+pub fn screenshot(path: &Path) -> Result<(), String> {
+    if !status().screenshot {
+        return Err(unavailable("Screenshots", SCREENSHOT));
+    }
+    let uri = tauri::async_runtime::block_on(async {
+        let connection = connection().await?;
+        let token = token();
+        let options = HashMap::from([
+            ("handle_token", Value::from(token.as_str())),
+            ("interactive", Value::from(false)),
+        ]);
+        let results = request(
+            &connection,
+            SCREENSHOT,
+            "Screenshot",
+            &token,
+            &("", options),
+        )
+        .await?;
+        results
+            .get("uri")
+            .and_then(|v| String::try_from(v.clone()).ok())
+            .ok_or_else(|| "The Screenshot portal returned no image".to_string())
+    })?;
+    let source = url::Url::parse(&uri)
+        .ok()
+        .and_then(|url| url.to_file_path().ok())
+        .ok_or_else(|| format!("Unexpected screenshot location: {}", uri))?;
+    std::fs::copy(&source, path).map_err(|e| e.to_string())?;
+    let _ = std::fs::remove_file(&source);
+    Ok(())
+}
+
+/// A shortcut to bind through the GlobalShortcuts portal.
+pub struct Shortcut {
+    pub id: String,
+    pub description: String,
+    /// Trigger in the portal's format (`CTRL+SHIFT+a`); the desktop may pick another.
+    pub preferred_trigger: String,
+}
+
+/// Replace the bound shortcuts. `on_activated` gets the id of a shortcut when
+/// it fires. Returns id → trigger for each shortcut the desktop bound.
+pub fn bind_shortcuts<F>(
+    shortcuts: Vec<Shortcut>,
+    on_activated: F,
+) -> Result<HashMap<String, String>, String>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    if !status().global_shortcuts {
+        return Err(unavailable("Global hotkeys", GLOBAL_SHORTCUTS));
+    }
+    tauri::async_runtime::block_on(async move {
+        let connection = connection().await?;
+        let err = |e: zbus::Error| e.to_string();
+
+        let previous = SHORTCUT_SESSION.lock().ok().and_then(|mut s| s.take());
+        if let Some(previous) = previous {
+            let session = Proxy::new(
+                &connection,
+                DESTINATION,
+                previous,
+                "org.freedesktop.portal.Session",
+            )
+            .await
+            .map_err(err)?;
+            let _: Result<(), _> = session.call("Close", &()).await;
+        }
+        if shortcuts.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let token = token();
+        let options = HashMap::from([
+            ("handle_token", Value::from(token.as_str())),
+            ("session_handle_token", Value::from(token.as_str())),
+        ]);
+        let results = request(
+            &connection,
+            GLOBAL_SHORTCUTS,
+            "CreateSession",
+            &token,
+            &(options,),
+        )
+        .await?;
+        let session = results
+            .get("session_handle")
+            .and_then(|v| String::try_from(v.clone()).ok())
+            .and_then(|path| OwnedObjectPath::try_from(path).ok())
+            .ok_or("The GlobalShortcuts portal returned no session")?;
+        if let Ok(mut current) = SHORTCUT_SESSION.lock() {
+            *current = Some(session.clone());
+        }
+
+        let portal = Proxy::new(&connection, DESTINATION, OBJECT_PATH, GLOBAL_SHORTCUTS)
+            .await
+            .map_err(err)?;
+        let mut activations = portal.receive_signal("Activated").await.map_err(err)?;
+
+        let list: Vec<(String, HashMap<&str, Value>)> = shortcuts
+            .iter()
+            .map(|s| {
+                let properties = HashMap::from([
+                    ("description", Value::from(s.description.as_str())),
+                    (
+                        "preferred_trigger",
+                        Value::from(s.preferred_trigger.as_str()),
+                    ),
+                ]);
+                (s.id.clone(), properties)
+            })
+            .collect();
+        let token = self::token();
+        let options = HashMap::from([("handle_token", Value::from(token.as_str()))]);
+        let results = request(
+            &connection,
+            GLOBAL_SHORTCUTS,
+            "BindShortcuts",
+            &token,
+            &(&session, list, "", options),
+        )
+        .await?;
+        let bound: Vec<(String, Results)> = results
+            .get("shortcuts")
+            .and_then(|v| v.try_clone().ok())
+            .and_then(|v| v.try_into().ok())
+            .unwrap_or_default();
+        let bound = bound
+            .into_iter()
+            .map(|(id, properties)| {
+                let trigger = properties
+                    .get("trigger_description")
+                    .and_then(|v| String::try_from(v.clone()).ok())
+                    .unwrap_or_default();
+                (id, trigger)
+            })
+            .collect();
+
+        tauri::async_runtime::spawn(async move {
+            while let Some(message) = activations.next().await {
+                let current = SHORTCUT_SESSION.lock().ok().and_then(|s| s.clone());
+                if current.as_ref() != Some(&session) {
+                    break;
+                }
+                let body = message.body();
+                let Ok((from, id, _, _)) =
+                    body.deserialize::<(OwnedObjectPath, String, u64, Results)>()
+                else {
+                    continue;
+                };
+                if from == session {
+                    on_activated(&id);
+                }
+            }
+        });
+        Ok(bound)
+    })
+}
+
+/// Tauri command: which portals are available and which features degrade.
+/// Called from the settings window via `invoke('get_portal_status')`.
+#[tauri::command]
+pub async fn get_portal_status() -> Result<PortalStatus, String> {
+    tauri::async_runtime::spawn_blocking(|| status().clone())
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,389 @@
+//! System sleep/wake and battery handling.
+//!
+//! Suspend and resume are picked up from the OS where it announces them
+//! (logind's `PrepareForSleep` on Linux, `Win32_PowerManagementEvent` on
+//! Windows, powerd's log messages on macOS) and, on every platform, from
+//! jumps in the wall clock — a loop that wakes every few seconds notices when
+//! much more time has passed than it slept. On suspend the backend bridge is
+//! paused and timers hold off; on resume the bridge reconnects at once
+//! (instead of sitting out a backoff on a dead socket) and every backend is
+//! health-checked. The frontend gets `system-suspend` / `system-resume`
+//! events.
+//!
+//! The power source (battery or AC) is polled as well. On battery, the
+//! configurable [`BatteryPolicy`] applies: background polling slows down
+//! ([`interval`]), the tray stops pulsing, and windows are told via
+//! `power-policy-changed` so the world view can stop rendering while hidden.
+
+use crate::child_processes::{self, Kind};
+use crate::{bridge, federation, onboarding, reminders, store};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Battery policy config (app data dir).
+const CONFIG_FILE: &str = "power.json";
+
+/// Wall-clock check interval.
+const CLOCK_INTERVAL: Duration = Duration::from_secs(5);
+/// Extra wall-clock time beyond the interval that counts as having slept.
+const CLOCK_JUMP: Duration = Duration::from_secs(30);
+/// Resume signals this close together are one wake-up.
+const RESUME_DEBOUNCE: Duration = Duration::from_secs(30);
+/// How often the power source is checked.
+const POWER_SOURCE_INTERVAL: Duration = Duration::from_secs(30);
+/// Polling intervals are multiplied by this while throttled.
+const SLOW_POLLING_FACTOR: u32 = 4;
+
+static SUSPENDED: AtomicBool = AtomicBool::new(false);
+static LAST_RESUME: Mutex<Option<Instant>> = Mutex::new(None);
+static ON_BATTERY: AtomicBool = AtomicBool::new(false);
+
+/// What to scale back while running on battery.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BatteryPolicy {
+    /// Apply the policy at all.
+    pub enabled: bool,
+    /// Stop rendering the 3D world while its window is hidden.
+    pub pause_hidden_world: bool,
+    /// Poll clipboard, theme and local control state less often.
+    pub slow_polling: bool,
+    /// Show a steady tray icon instead of pulsing while agents work.
+    pub calm_tray: bool,
+}
+
+impl Default for BatteryPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            pause_hidden_world: true,
+            slow_polling: true,
+            calm_tray: true,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerStatus {
+    on_battery: bool,
+    /// Whether the battery policy is in effect right now.
+    throttled: bool,
+    policy: BatteryPolicy,
+}
+
+/// App state: battery policy.
+pub struct PowerState {
+    policy: Mutex<BatteryPolicy>,
+}
+
+/// Start watching for suspend/resume and power source changes.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(PowerState {
+        policy: Mutex::new(store::load_json(app, CONFIG_FILE)),
+    });
+    ON_BATTERY.store(on_battery().unwrap_or(false), Ordering::Relaxed);
+    {
+        let app = app.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(POWER_SOURCE_INTERVAL);
+            if let Some(battery) = on_battery() {
+                if ON_BATTERY.swap(battery, Ordering::Relaxed) != battery {
+                    policy_changed(&app);
+                }
+            }
+        });
+    }
+    {
+        let app = app.clone();
+        std::thread::spawn(move || watch_clock(&app));
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = watch_os(&app) {
+            eprintln!("[CrewHub] OS power events unavailable: {}", e);
+        }
+    });
+}
+
+/// Whether the system is about to sleep (timers should hold off).
+pub fn is_suspended() -> bool {
+    SUSPENDED.load(Ordering::Relaxed)
+}
+
+fn policy<R: Runtime>(app: &AppHandle<R>) -> BatteryPolicy {
+    app.try_state::<PowerState>()
+        .and_then(|state| state.policy.lock().ok().map(|p| p.clone()))
+        .unwrap_or_default()
+}
+
+/// The battery policy if it currently applies.
+fn active_policy<R: Runtime>(app: &AppHandle<R>) -> Option<BatteryPolicy> {
+    let policy = policy(app);
+    (ON_BATTERY.load(Ordering::Relaxed) && policy.enabled).then_some(policy)
+}
+
+/// Polling interval to use for background work with the given normal interval.
+pub fn interval<R: Runtime>(app: &AppHandle<R>, normal: Duration) -> Duration {
+    match active_policy(app) {
+        Some(policy) if policy.slow_polling => normal * SLOW_POLLING_FACTOR,
+        _ => normal,
+    }
+}
+
+/// Whether tray animations should be skipped.
+pub fn calm_tray<R: Runtime>(app: &AppHandle<R>) -> bool {
+    active_policy(app).is_some_and(|policy| policy.calm_tray)
+}
+
+fn status<R: Runtime>(app: &AppHandle<R>) -> PowerStatus {
+    PowerStatus {
+        on_battery: ON_BATTERY.load(Ordering::Relaxed),
+        throttled: active_policy(app).is_some(),
+        policy: policy(app),
+    }
+}
+
+fn policy_changed<R: Runtime>(app: &AppHandle<R>) {
+    let status = status(app);
+    println!(
+        "[CrewHub] Running on {}",
+        if status.on_battery {
+            "battery"
+        } else {
+            "AC power"
+        }
+    );
+    // Drop a pulse frame that may be showing when the tray calms down.
+    crate::refresh_tray_icon(app);
+    let _ = app.emit("power-policy-changed", &status);
+}
+
+fn on_suspend<R: Runtime>(app: &AppHandle<R>) {
+    if SUSPENDED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    println!("[CrewHub] System going to sleep");
+    bridge::pause(app);
+    let _ = app.emit("system-suspend", ());
+}
+
+fn on_resume<R: Runtime>(app: &AppHandle<R>, slept: Option<Duration>) {
+    let was_suspended = SUSPENDED.swap(false, Ordering::Relaxed);
+    if let Ok(mut last) = LAST_RESUME.lock() {
+        // The OS signal and the clock jump usually both report the same wake-up.
+        if !was_suspended && last.is_some_and(|at| at.elapsed() < RESUME_DEBOUNCE) {
+            return;
+        }
+        *last = Some(Instant::now());
+    }
+    println!("[CrewHub] System woke up");
+    bridge::resume(app);
+    let _ = app.emit(
+        "system-resume",
+        serde_json::json!({ "sleptSeconds": slept.map(|d| d.as_secs()) }),
+    );
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        reminders::check(&app);
+        let probes: Vec<_> = federation::enabled(&app)
+            .iter()
+            .map(|backend| onboarding::probe(&backend.url))
+            .collect();
+        let _ = app.emit("backend-health", &probes);
+    });
+}
+
+fn watch_clock<R: Runtime>(app: &AppHandle<R>) {
+    let mut last = SystemTime::now();
+    loop {
+        std::thread::sleep(CLOCK_INTERVAL);
+        let now = SystemTime::now();
+        if let Ok(elapsed) = now.duration_since(last) {
+            if elapsed > CLOCK_INTERVAL + CLOCK_JUMP {
+                on_resume(app, Some(elapsed));
+            }
+        }
+        last = now;
+    }
+}
+
+/// Run `program` and call `on_line` for each line it prints until it exits.
+pub fn follow(program: &str, args: &[&str], mut on_line: impl FnMut(&str)) -> Result<(), String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args).stdout(Stdio::piped()).stderr(Stdio::null());
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW
+        cmd.creation_flags(0x0800_0000);
+    }
+    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+    child_processes::register(child.id(), Kind::Watcher, program, program);
+    let stdout = child.stdout.take().ok_or("No stdout")?;
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        on_line(&line);
+    }
+    let _ = child.wait();
+    child_processes::unregister(child.id());
+    Ok(())
+}
+
+/// logind announces `PrepareForSleep(true)` before and `(false)` after sleeping.
+#[cfg(target_os = "linux")]
+fn watch_os<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let mut in_signal = false;
+    follow(
+        "dbus-monitor",
+        &[
+            "--system",
+            "type='signal',interface='org.freedesktop.login1.Manager',member='PrepareForSleep'",
+        ],
+        |line| {
+            let line = line.trim();
+            if line.contains("member=PrepareForSleep") {
+                in_signal = true;
+            } else if in_signal && line.starts_with("boolean") {
+                in_signal = false;
+                if line.ends_with("true") {
+                    on_suspend(app);
+                } else {
+                    on_resume(app, None);
+                }
+            }
+        },
+    )
+}
+
+/// `Win32_PowerManagementEvent`: 4 = entering suspend, 7 / 18 = resumed.
+#[cfg(target_os = "windows")]
+fn watch_os<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    const SCRIPT: &str =
+        "Register-WmiEvent -Class Win32_PowerManagementEvent -SourceIdentifier power; \
+        while ($true) { $e = Wait-Event -SourceIdentifier power; \
+        [Console]::Out.WriteLine($e.SourceEventArgs.NewEvent.EventType); \
+        [Console]::Out.Flush(); Remove-Event -EventIdentifier $e.EventIdentifier }";
+    follow(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", SCRIPT],
+        |line| match line.trim() {
+            "4" => on_suspend(app),
+            "7" | "18" => on_resume(app, None),
+            _ => {}
+        },
+    )
+}
+
+/// powerd logs "Entering Sleep" before and "Wake from" after sleeping.
+#[cfg(target_os = "macos")]
+fn watch_os<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    follow(
+        "log",
+        &[
+            "stream",
+            "--style",
+            "compact",
+            "--predicate",
+            "process == \"powerd\" AND (eventMessage BEGINSWITH \"Entering Sleep\" \
+             OR eventMessage BEGINSWITH \"Wake from\")",
+        ],
+        |line| {
+            if line.contains("Entering Sleep") {
+                on_suspend(app);
+            } else if line.contains("Wake from") {
+                on_resume(app, None);
+            }
+        },
+    )
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn watch_os<R: Runtime>(_app: &AppHandle<R>) -> Result<(), String> {
+    Ok(())
+}
+
+/// `Some(true)` on battery, `Some(false)` on AC, `None` if unknown (desktops).
+#[cfg(target_os = "linux")]
+fn on_battery() -> Option<bool> {
+    let mut has_battery = false;
+    for entry in std::fs::read_dir("/sys/class/power_supply").ok()?.flatten() {
+        let path = entry.path();
+        let kind = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+        match kind.trim() {
+            "Mains" | "USB" => {
+                let online = std::fs::read_to_string(path.join("online")).unwrap_or_default();
+                if online.trim() == "1" {
+                    return Some(false);
+                }
+            }
+            "Battery" => has_battery = true,
+            _ => {}
+        }
+    }
+    has_battery.then_some(true)
+}
+
+#[cfg(target_os = "macos")]
+fn on_battery() -> Option<bool> {
+    let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let first = text.lines().next()?;
+    Some(first.contains("Battery Power"))
+}
+
+/// `Win32_Battery.BatteryStatus` 1 means discharging.
+#[cfg(target_os = "windows")]
+fn on_battery() -> Option<bool> {
+    use std::os::windows::process::CommandExt;
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "(Get-CimInstance Win32_Battery | Select-Object -First 1).BatteryStatus",
+        ])
+        // CREATE_NO_WINDOW
+        .creation_flags(0x0800_0000)
+        .output()
+        .ok()?;
+    let status = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if status.is_empty() {
+        return None;
+    }
+    Some(status == "1")
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn on_battery() -> Option<bool> {
+    None
+}
+
+/// Tauri command: power source, whether throttling applies, and the battery policy.
+/// Called from the frontend via `invoke('get_power_status')`.
+#[tauri::command]
+pub fn get_power_status(app: AppHandle) -> PowerStatus {
+    status(&app)
+}
+
+/// Tauri command: replace the battery policy.
+/// Called from the settings window via `invoke('configure_battery_policy', { policy })`.
+#[tauri::command]
+pub fn configure_battery_policy(
+    policy: BatteryPolicy,
+    app: AppHandle,
+    state: State<PowerState>,
+) -> Result<PowerStatus, String> {
+    {
+        let mut current = state.policy.lock().map_err(|e| e.to_string())?;
+        *current = policy;
+        store::save_json(&app, CONFIG_FILE, &*current)?;
+    }
+    policy_changed(&app);
+    Ok(status(&app))
+}
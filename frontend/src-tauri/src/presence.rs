@@ -0,0 +1,261 @@
+//! Agent presence: a live map of what every agent is doing.
+//!
+//! Fed by `agent-status` events from the backend bridge. Drives the tray
+//! (pulsing icon while any agent is working, badge count includes agents
+//! blocked on the user) and notifies when an agent has been waiting on the
+//! user for longer than `BLOCKED_ALERT_AFTER`. Becoming blocked also asks for
+//! the user's attention right away (see [`crate::attention`]).
+
+use crate::notifications::{self, Event};
+use crate::usage_stats::{self, Metric};
+use crate::{accessibility, attention, bridge, dnd, federation, i18n, power, reminders};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{image::Image, AppHandle, Manager, Runtime, State};
+
+/// Backend event carrying one agent's new state.
+pub const AGENT_STATUS_EVENT: &str = "agent-status";
+
+/// Emitted with every agent's state after a change (replayed, see [`bridge::emit`]).
+pub const AGENT_STATES_CHANGED_EVENT: &str = "agent-states-changed";
+
+/// How long an agent may sit in `blocked` before the user is notified.
+const BLOCKED_ALERT_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// Tray pulse frame interval while any agent is working.
+const PULSE_INTERVAL: Duration = Duration::from_millis(600);
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AgentState {
+    Idle,
+    Working,
+    Blocked,
+    Error,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentPresence {
+    /// Namespaced by backend when several backends are connected.
+    pub agent_id: String,
+    pub backend_id: String,
+    pub name: String,
+    pub state: AgentState,
+    pub detail: Option<String>,
+    /// Unix seconds when the agent entered `state`.
+    since: i64,
+    #[serde(skip)]
+    alerted: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusEvent {
+    agent_id: String,
+    #[serde(default)]
+    name: Option<String>,
+    state: AgentState,
+    #[serde(default)]
+    detail: Option<String>,
+}
+
+/// App state: current presence per agent id.
+#[derive(Default)]
+pub struct PresenceState {
+    agents: Mutex<HashMap<String, AgentPresence>>,
+}
+
+/// Register state and start the tray pulse / blocked-agent watcher.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(PresenceState::default());
+    let app = app.clone();
+    std::thread::spawn(move || watch(&app));
+}
+
+fn now() -> i64 {
+    chrono::Local::now().timestamp()
+}
+
+/// Handle an `agent-status` event from the backend bridge.
+pub fn handle_status<R: Runtime>(app: &AppHandle<R>, backend_id: &str, payload: Value) {
+    let event: StatusEvent = match serde_json::from_value(payload) {
+        Ok(event) => event,
+        Err(e) => {
+            eprintln!("[CrewHub] Ignoring malformed agent status: {}", e);
+            return;
+        }
+    };
+    let state = app.state::<PresenceState>();
+    let mut run_started = None;
+    let mut run_finished = None;
+    let mut became_blocked = false;
+    let snapshot = {
+        let Ok(mut agents) = state.agents.lock() else {
+            return;
+        };
+        let key = federation::namespaced(backend_id, &event.agent_id);
+        let entry = agents.entry(key.clone()).or_insert_with(|| AgentPresence {
+            agent_id: key,
+            backend_id: backend_id.to_string(),
+            name: event.agent_id.clone(),
+            state: AgentState::Idle,
+            detail: None,
+            since: now(),
+            alerted: false,
+        });
+        if let Some(name) = event.name {
+            entry.name = name;
+        }
+        if entry.state != event.state {
+            // Resuming after being blocked is the same run.
+            if event.state == AgentState::Working && entry.state != AgentState::Blocked {
+                run_started = Some(entry.name.clone());
+            }
+            if matches!(entry.state, AgentState::Working | AgentState::Blocked)
+                && matches!(event.state, AgentState::Idle | AgentState::Error)
+            {
+                run_finished = Some(entry.agent_id.clone());
+            }
+            became_blocked = event.state == AgentState::Blocked;
+            entry.state = event.state;
+            entry.since = now();
+            entry.alerted = false;
+        }
+        entry.detail = event.detail;
+        sorted(&agents)
+    };
+    bridge::emit(app, AGENT_STATES_CHANGED_EVENT, &snapshot);
+    crate::refresh_tray_icon(app);
+    if let Some(name) = run_started {
+        usage_stats::record(app, Metric::AgentRun, &name, 1.0);
+    }
+    if let Some(agent_id) = run_finished {
+        reminders::agent_finished(app, &agent_id);
+    }
+    if became_blocked {
+        attention::agent_blocked(app);
+    }
+}
+
+fn sorted(agents: &HashMap<String, AgentPresence>) -> Vec<AgentPresence> {
+    let mut list: Vec<AgentPresence> = agents.values().cloned().collect();
+    list.sort_by(|a, b| a.name.cmp(&b.name));
+    list
+}
+
+/// All known agents, sorted by name.
+pub fn agents<R: Runtime>(app: &AppHandle<R>) -> Vec<AgentPresence> {
+    app.try_state::<PresenceState>()
+        .and_then(|state| state.agents.lock().ok().map(|agents| sorted(&agents)))
+        .unwrap_or_default()
+}
+
+fn count_in<R: Runtime>(app: &AppHandle<R>, wanted: AgentState) -> u32 {
+    app.try_state::<PresenceState>()
+        .and_then(|state| {
+            state
+                .agents
+                .lock()
+                .ok()
+                .map(|agents| agents.values().filter(|a| a.state == wanted).count() as u32)
+        })
+        .unwrap_or(0)
+}
+
+/// Agents currently waiting on the user (counted into the tray badge).
+pub fn blocked_count<R: Runtime>(app: &AppHandle<R>) -> u32 {
+    count_in(app, AgentState::Blocked)
+}
+
+/// Agents currently working (shown in the tray tooltip).
+pub fn working_count<R: Runtime>(app: &AppHandle<R>) -> u32 {
+    count_in(app, AgentState::Working)
+}
+
+/// Same icon with alpha halved, used as the "off" frame of the pulse.
+pub fn dimmed(icon: &Image<'_>) -> Image<'static> {
+    let rgba = icon
+        .rgba()
+        .chunks_exact(4)
+        .flat_map(|px| [px[0], px[1], px[2], px[3] / 2])
+        .collect();
+    Image::new_owned(rgba, icon.width(), icon.height())
+}
+
+fn watch<R: Runtime>(app: &AppHandle<R>) {
+    let mut dim_frame = false;
+    loop {
+        std::thread::sleep(PULSE_INTERVAL);
+
+        if working_count(app) > 0
+            && !power::calm_tray(app)
+            && !dnd::quiet(app)
+            && !accessibility::reduce_motion()
+        {
+            dim_frame = !dim_frame;
+            if let (Some(tray), Ok(icon)) = (app.tray_by_id(crate::TRAY_ID), crate::tray_icon(app))
+            {
+                let frame = if dim_frame { dimmed(&icon) } else { icon };
+                let _ = tray.set_icon(Some(frame));
+            }
+        } else if dim_frame {
+            // Work finished mid-pulse: restore the solid icon.
+            dim_frame = false;
+            crate::refresh_tray_icon(app);
+        }
+
+        alert_blocked(app);
+    }
+}
+
+/// Notify once per blocked episode for agents waiting longer than the threshold.
+fn alert_blocked<R: Runtime>(app: &AppHandle<R>) {
+    let due: Vec<AgentPresence> = {
+        let state = app.state::<PresenceState>();
+        let Ok(mut agents) = state.agents.lock() else {
+            return;
+        };
+        let cutoff = now() - BLOCKED_ALERT_AFTER.as_secs() as i64;
+        agents
+            .values_mut()
+            .filter(|a| a.state == AgentState::Blocked && !a.alerted && a.since <= cutoff)
+            .map(|a| {
+                a.alerted = true;
+                a.clone()
+            })
+            .collect()
+    };
+    for agent in due {
+        let minutes = (now() - agent.since) / 60;
+        let minutes = minutes.to_string();
+        let body = match &agent.detail {
+            Some(detail) => i18n::tf(
+                "notify.blockedBodyDetail",
+                &[("minutes", &minutes), ("detail", detail)],
+            ),
+            None => i18n::tf("notify.blockedBody", &[("minutes", &minutes)]),
+        };
+        let name = match federation::label(app, &agent.backend_id) {
+            Some(backend) => format!("{} ({})", agent.name, backend),
+            None => agent.name.clone(),
+        };
+        notifications::notify(
+            app,
+            Event::AgentBlocked,
+            i18n::tf("notify.blockedTitle", &[("agent", &name)]),
+            body,
+        );
+    }
+}
+
+/// Tauri command: current state of every known agent.
+/// Called from the frontend via `invoke('get_agent_states')`.
+#[tauri::command]
+pub fn get_agent_states(state: State<PresenceState>) -> Result<Vec<AgentPresence>, String> {
+    let agents = state.agents.lock().map_err(|e| e.to_string())?;
+    Ok(sorted(&agents))
+}
@@ -0,0 +1,186 @@
+//! Printing conversations.
+//!
+//! Printing from the chat webview is unreliable (windows are hidden rather
+//! than closed, and their layout is built for a 390 px column), so the
+//! transcript is fetched and rendered to a plain HTML document here, loaded
+//! into a dedicated window, and handed to the platform's native print dialog
+//! once the page has finished loading.
+
+use crate::pins::ConversationRef;
+use crate::{backend, federation, i18n};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindowBuilder};
+
+/// Label for the print window.
+pub const PRINT_WINDOW_LABEL: &str = "print";
+
+/// Rendered documents (app cache dir); replaced on every print.
+const PRINT_DIR: &str = "print";
+
+/// Messages fetched per history request (the backend's maximum).
+const PAGE_SIZE: usize = 100;
+
+/// Stop paging back after this many messages.
+const MAX_MESSAGES: usize = 5000;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryPage {
+    #[serde(default)]
+    messages: Vec<HistoryMessage>,
+    #[serde(default)]
+    has_more: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: String,
+    /// Unix milliseconds.
+    #[serde(default)]
+    pub timestamp: i64,
+}
+
+/// Fetch the whole transcript, oldest message first (also used by [`crate::archive`]).
+pub fn fetch_history(base: &str, session_key: &str) -> Result<Vec<HistoryMessage>, String> {
+    let mut messages: Vec<HistoryMessage> = Vec::new();
+    let mut before: Option<i64> = None;
+    loop {
+        let mut path = format!(
+            "/api/chat/{}/history?limit={}",
+            backend::urlencode(session_key),
+            PAGE_SIZE
+        );
+        if let Some(before) = before {
+            path.push_str(&format!("&before={}", before));
+        }
+        let page: HistoryPage = serde_json::from_value(backend::get_json_from(base, &path)?)
+            .map_err(|e| format!("Invalid history response: {}", e))?;
+        let oldest = page.messages.first().map(|m| m.timestamp);
+        messages.splice(0..0, page.messages);
+        match oldest {
+            Some(oldest) if page.has_more && messages.len() < MAX_MESSAGES => {
+                // No progress possible without a usable timestamp.
+                if oldest <= 0 || before == Some(oldest) {
+                    break;
+                }
+                before = Some(oldest);
+            }
+            _ => break,
+        }
+    }
+    Ok(messages)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render(title: &str, messages: &[HistoryMessage]) -> String {
+    let mut body = String::new();
+    for message in messages {
+        let who = match message.role.as_str() {
+            "user" => i18n::t("print.you"),
+            "assistant" => i18n::t("print.agent"),
+            _ => i18n::t("print.system"),
+        };
+        let when = chrono::DateTime::from_timestamp_millis(message.timestamp)
+            .filter(|_| message.timestamp > 0)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        body.push_str(&format!(
+            "<section class=\"{}\"><header><strong>{}</strong> <time>{}</time></header><div>{}</div></section>\n",
+            escape(&message.role),
+            escape(&who),
+            when,
+            escape(&message.content)
+        ));
+    }
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title><style>\
+body{{font:11pt/1.45 -apple-system,'Segoe UI',sans-serif;color:#000;margin:2em;}}\
+h1{{font-size:16pt;}}\
+section{{break-inside:avoid;border-top:1px solid #ccc;padding:.5em 0;}}\
+header{{margin-bottom:.25em;}}\
+time{{color:#666;font-size:9pt;}}\
+section>div{{white-space:pre-wrap;}}\
+section.user>div{{font-style:italic;}}\
+</style></head><body><h1>{title}</h1>\n{body}</body></html>\n",
+        title = escape(title),
+        body = body
+    )
+}
+
+fn write_document<R: Runtime>(app: &AppHandle<R>, html: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join(PRINT_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join("conversation.html");
+    std::fs::write(&path, html).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Open the print window on `path`; the print dialog shows once it has loaded.
+fn open_print_window<R: Runtime>(app: &AppHandle<R>, path: PathBuf) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(PRINT_WINDOW_LABEL) {
+        let _ = window.destroy();
+    }
+    let url = url::Url::from_file_path(&path)
+        .map_err(|_| format!("Invalid print path: {}", path.display()))?;
+    let window = WebviewWindowBuilder::new(app, PRINT_WINDOW_LABEL, WebviewUrl::External(url))
+        .title(i18n::t("print.windowTitle"))
+        .inner_size(720.0, 900.0)
+        .center()
+        .on_new_window(crate::external_links::new_window_handler(app))
+        .on_page_load(|window, payload| {
+            if payload.event() == PageLoadEvent::Finished {
+                if let Err(e) = window.print() {
+                    eprintln!("[CrewHub] Failed to open print dialog: {}", e);
+                }
+            }
+        })
+        .build()
+        .map_err(|e| e.to_string())?;
+    crate::show_and_focus(&window);
+    Ok(())
+}
+
+/// Tauri command: render a conversation for printing and open the native print dialog.
+/// Called from the frontend via `invoke('print_conversation', { conversation })`.
+#[tauri::command]
+pub async fn print_conversation(
+    conversation: ConversationRef,
+    app: AppHandle,
+) -> Result<(), String> {
+    let base = federation::enabled(&app)
+        .into_iter()
+        .find(|backend| backend.id == conversation.backend_id)
+        .map(|backend| backend.url)
+        .ok_or_else(|| format!("Unknown backend: {}", conversation.backend_id))?;
+    let session_key = conversation.session_key.clone();
+    let messages = tauri::async_runtime::spawn_blocking(move || fetch_history(&base, &session_key))
+        .await
+        .map_err(|e| e.to_string())??;
+    let path = write_document(&app, &render(&conversation.title, &messages))?;
+    let handle = app.clone();
+    app.run_on_main_thread(move || {
+        if let Err(e) = open_print_window(&handle, path) {
+            eprintln!("[CrewHub] Failed to open print window: {}", e);
+        }
+    })
+    .map_err(|e| e.to_string())
+}
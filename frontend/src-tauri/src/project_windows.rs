@@ -0,0 +1,315 @@
+//! Per-project chat and world windows, each in its project's color.
+//!
+//! `open_project_window` opens `chat-<projectId>` or `world-<projectId>` next
+//! to the main windows, with `?project=<id>` and the project's theme in the
+//! bootstrap config (`window.__CREWHUB_PROJECT__ = { id, color, icon }`).
+//! Themes are set per project in `project-themes.json`: a `#rrggbb` color
+//! and an optional icon (emoji or short text) the frontend shows in the
+//! window. The titlebar is tinted where the app draws it: DWM's caption color
+//! on Windows 11, a transparent titlebar over the window background on macOS.
+//! Linux titlebars belong to the window manager and stay as they are.
+//!
+//! While a project window was the last one focused, the tray icon carries a
+//! dot in the project's color (see [`crate::badges::project_dot`]), so with
+//! several projects open the tray shows which one was in front. Changing a
+//! theme re-tints open windows and emits `project-theme-changed`.
+
+use crate::{badges, external_links, request_headers, store};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::image::Image;
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow, WebviewWindowBuilder};
+
+/// Persisted themes (app data dir).
+const THEMES_FILE: &str = "project-themes.json";
+
+static THEMES: Mutex<Option<BTreeMap<String, ProjectTheme>>> = Mutex::new(None);
+
+/// Project of the project window focused last (`None` after a main window).
+static FOCUSED: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTheme {
+    /// `#rrggbb`.
+    pub color: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum View {
+    Chat,
+    World,
+}
+
+impl View {
+    fn prefix(self) -> &'static str {
+        match self {
+            View::Chat => "chat-",
+            View::World => "world-",
+        }
+    }
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let loaded: BTreeMap<String, ProjectTheme> = store::load_json(app, THEMES_FILE);
+    if let Ok(mut themes) = THEMES.lock() {
+        *themes = Some(loaded);
+    }
+}
+
+fn themes() -> BTreeMap<String, ProjectTheme> {
+    THEMES
+        .lock()
+        .ok()
+        .and_then(|themes| themes.clone())
+        .unwrap_or_default()
+}
+
+fn theme(project_id: &str) -> Option<ProjectTheme> {
+    themes().remove(project_id)
+}
+
+/// Project ids become part of window labels.
+fn valid_id(project_id: &str) -> bool {
+    !project_id.is_empty()
+        && project_id.len() <= 64
+        && project_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Project of a `chat-<id>` / `world-<id>` window label.
+pub fn project_of(label: &str) -> Option<&str> {
+    [View::Chat, View::World]
+        .iter()
+        .find_map(|view| label.strip_prefix(view.prefix()))
+        .filter(|id| valid_id(id))
+}
+
+fn parse_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+}
+
+fn project_script(project_id: &str, theme: Option<&ProjectTheme>) -> String {
+    let project = json!({
+        "id": project_id,
+        "color": theme.map(|theme| &theme.color),
+        "icon": theme.and_then(|theme| theme.icon.as_ref()),
+    });
+    format!("window.__CREWHUB_PROJECT__ = {};", project)
+}
+
+/// Tint the titlebar of a project window (Windows, macOS).
+#[cfg_attr(
+    not(any(target_os = "windows", target_os = "macos")),
+    allow(unused_variables)
+)]
+fn tint<R: Runtime>(window: &WebviewWindow<R>, color: Option<[u8; 3]>) {
+    #[cfg(target_os = "windows")]
+    platform::set_caption_color(window, color);
+    #[cfg(target_os = "macos")]
+    let _ = window.set_background_color(color.map(|[r, g, b]| tauri::webview::Color(r, g, b, 255)));
+}
+
+/// Open (or focus) the `view` window of a project.
+pub fn open<R: Runtime>(app: &AppHandle<R>, project_id: &str, view: View) -> Result<(), String> {
+    if !valid_id(project_id) {
+        return Err(format!("Invalid project id: {}", project_id));
+    }
+    let label = format!("{}{}", view.prefix(), project_id);
+    if let Some(window) = app.get_webview_window(&label) {
+        crate::show_and_focus(&window);
+        return Ok(());
+    }
+    let theme = theme(project_id);
+    let color = theme.as_ref().and_then(|theme| parse_color(&theme.color));
+    let (title, base_script, (width, height), (min_width, min_height)) = match view {
+        View::Chat => (
+            "CrewHub Chat",
+            crate::chat_init_script(),
+            (390.0, 700.0),
+            (320.0, 500.0),
+        ),
+        View::World => (
+            "CrewHub 3D World",
+            crate::world_init_script(),
+            (1280.0, 900.0),
+            (900.0, 600.0),
+        ),
+    };
+    let title = match theme.as_ref().and_then(|theme| theme.icon.as_deref()) {
+        Some(icon) => format!("{} {} — {}", icon, title, project_id),
+        None => format!("{} — {}", title, project_id),
+    };
+    let builder = WebviewWindowBuilder::new(
+        app,
+        &label,
+        crate::view_url(&format!("project={}", project_id)),
+    )
+    .title(title)
+    .inner_size(width, height)
+    .min_inner_size(min_width, min_height)
+    .resizable(true)
+    .decorations(true)
+    .user_agent(&request_headers::user_agent())
+    .on_new_window(external_links::new_window_handler(app))
+    .initialization_script(format!(
+        "{} {}",
+        base_script,
+        project_script(project_id, theme.as_ref())
+    ));
+    #[cfg(target_os = "macos")]
+    let builder = match color {
+        Some([r, g, b]) => builder
+            .title_bar_style(tauri::TitleBarStyle::Transparent)
+            .background_color(tauri::webview::Color(r, g, b, 255)),
+        None => builder,
+    };
+    let window = builder.build().map_err(|e| e.to_string())?;
+    tint(&window, color);
+    crate::show_and_focus(&window);
+    Ok(())
+}
+
+/// Remember which project window was focused last (main windows clear it).
+pub fn track_focus<R: Runtime>(app: &AppHandle<R>, label: &str) {
+    let project = project_of(label).map(str::to_string);
+    let is_main = [
+        crate::CHAT_WINDOW_LABEL,
+        crate::WORLD_WINDOW_LABEL,
+        crate::ZEN_WINDOW_LABEL,
+    ]
+    .contains(&label);
+    if project.is_none() && !is_main {
+        return;
+    }
+    let changed = match FOCUSED.lock() {
+        Ok(mut focused) if *focused != project => {
+            *focused = project;
+            true
+        }
+        _ => false,
+    };
+    if changed {
+        crate::refresh_tray_icon(app);
+    }
+}
+
+/// The tray icon with the focused project's dot, or `icon` as it is.
+pub fn tray_variant<R: Runtime>(
+    app: &AppHandle<R>,
+    icon: Image<'static>,
+) -> Result<Image<'static>, String> {
+    let color = FOCUSED
+        .lock()
+        .ok()
+        .and_then(|focused| focused.clone())
+        .and_then(|project_id| theme(&project_id))
+        .and_then(|theme| parse_color(&theme.color));
+    match color {
+        Some(color) => badges::project_dot(app, &icon, color),
+        None => Ok(icon),
+    }
+}
+
+/// Tauri command: all project themes by project id.
+/// Called from the settings window via `invoke('get_project_themes')`.
+#[tauri::command]
+pub fn get_project_themes() -> BTreeMap<String, ProjectTheme> {
+    themes()
+}
+
+/// Tauri command: set (or with `null` clear) a project's color and icon.
+/// Called from the frontend via `invoke('set_project_theme', { projectId, theme })`.
+#[tauri::command]
+pub fn set_project_theme(
+    project_id: String,
+    theme: Option<ProjectTheme>,
+    app: AppHandle,
+) -> Result<BTreeMap<String, ProjectTheme>, String> {
+    if !valid_id(&project_id) {
+        return Err(format!("Invalid project id: {}", project_id));
+    }
+    if let Some(theme) = &theme {
+        parse_color(&theme.color).ok_or_else(|| format!("Invalid color: {}", theme.color))?;
+    }
+    let mut themes = themes();
+    match theme.clone() {
+        Some(theme) => themes.insert(project_id.clone(), theme),
+        None => themes.remove(&project_id),
+    };
+    store::save_json(&app, THEMES_FILE, &themes)?;
+    *THEMES.lock().map_err(|e| e.to_string())? = Some(themes.clone());
+
+    let color = theme.as_ref().and_then(|theme| parse_color(&theme.color));
+    for view in [View::Chat, View::World] {
+        let label = format!("{}{}", view.prefix(), project_id);
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = window.eval(project_script(&project_id, theme.as_ref()));
+            tint(&window, color);
+            let _ = app.emit_to(
+                label.as_str(),
+                "project-theme-changed",
+                json!({ "id": project_id, "theme": theme }),
+            );
+        }
+    }
+    crate::refresh_tray_icon(&app);
+    Ok(themes)
+}
+
+/// Tauri command: open (or focus) a project's chat or world window.
+/// Called from the frontend via `invoke('open_project_window', { projectId, view })`.
+#[tauri::command]
+pub fn open_project_window(project_id: String, view: View, app: AppHandle) -> Result<(), String> {
+    open(&app, &project_id, view)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use tauri::{Runtime, WebviewWindow};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Dwm::{
+        DwmSetWindowAttribute, DWMWA_CAPTION_COLOR, DWMWA_TEXT_COLOR, DWMWINDOWATTRIBUTE,
+    };
+
+    /// `DWMWA_COLOR_DEFAULT`: back to the system caption color.
+    const COLOR_DEFAULT: u32 = 0xFFFF_FFFF;
+
+    /// Caption and caption text color (Windows 11; ignored before).
+    pub fn set_caption_color<R: Runtime>(window: &WebviewWindow<R>, color: Option<[u8; 3]>) {
+        let Ok(hwnd) = window.hwnd() else {
+            return;
+        };
+        let hwnd = HWND(hwnd.0);
+        let (caption, text) = match color {
+            Some([r, g, b]) => {
+                // Dark text on light colors (perceived luminance).
+                let light = 299 * r as u32 + 587 * g as u32 + 114 * b as u32 > 150_000;
+                let text = if light { 0x0000_0000 } else { 0x00FF_FFFF };
+                (r as u32 | (g as u32) << 8 | (b as u32) << 16, text)
+            }
+            None => (COLOR_DEFAULT, COLOR_DEFAULT),
+        };
+        let set = |attribute: DWMWINDOWATTRIBUTE, value: u32| unsafe {
+            DwmSetWindowAttribute(
+                hwnd,
+                attribute,
+                (&value as *const u32).cast(),
+                std::mem::size_of::<u32>() as u32,
+            )
+        };
+        let _ = set(DWMWA_CAPTION_COLOR, caption);
+        let _ = set(DWMWA_TEXT_COLOR, text);
+    }
+}
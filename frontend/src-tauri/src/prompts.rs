@@ -0,0 +1,396 @@
+//! Prompt/snippet library.
+//!
+//! Reusable prompt templates stored in the shared SQLite database, with tags,
+//! search, and `{{variable}}` substitution. Used by the command palette and
+//! the quick-prompt window.
+
+use crate::db;
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime, State};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplate {
+    id: String,
+    name: String,
+    body: String,
+    tags: Vec<String>,
+    /// `{{name}}` placeholders found in the body, in order of first use.
+    variables: Vec<String>,
+    created_at: i64,
+    updated_at: i64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplateInput {
+    pub name: String,
+    pub body: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// A template row as exchanged with other desktops by [`crate::lan_sync`].
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncedTemplate {
+    id: String,
+    name: String,
+    body: String,
+    tags: String,
+    created_at: i64,
+    updated_at: i64,
+}
+
+/// Library contents for syncing: live rows plus deletions (id, deleted_at).
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncedLibrary {
+    templates: Vec<SyncedTemplate>,
+    deleted: Vec<(String, i64)>,
+}
+
+/// App state: database connection for the library.
+pub struct PromptState {
+    conn: Mutex<Connection>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let conn = db::open(app)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS prompt_templates (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            body TEXT NOT NULL,
+            tags TEXT NOT NULL DEFAULT '[]',
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS prompt_template_tombstones (
+            id TEXT PRIMARY KEY,
+            deleted_at INTEGER NOT NULL
+        );",
+    )
+    .map_err(|e| e.to_string())?;
+    app.manage(PromptState {
+        conn: Mutex::new(conn),
+    });
+    Ok(())
+}
+
+/// Placeholder names in `{{name}}` syntax (whitespace around the name is allowed).
+fn variables(body: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + end].trim();
+        if !name.is_empty() && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = &rest[start + 2 + end + 2..];
+    }
+    names
+}
+
+/// Replace `{{name}}` placeholders; unknown names are left as-is.
+fn substitute(body: &str, values: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let raw = &rest[start..start + 2 + end + 2];
+        out.push_str(&rest[..start]);
+        match values.get(rest[start + 2..start + 2 + end].trim()) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(raw),
+        }
+        rest = &rest[start + raw.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .into_iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+fn from_row(row: &Row) -> rusqlite::Result<PromptTemplate> {
+    let body: String = row.get(2)?;
+    let tags: String = row.get(3)?;
+    Ok(PromptTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        variables: variables(&body),
+        body,
+        tags: serde_json::from_str(&tags).unwrap_or_default(),
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+const COLUMNS: &str = "id, name, body, tags, created_at, updated_at";
+
+fn get(conn: &Connection, id: &str) -> Result<PromptTemplate, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM prompt_templates WHERE id = ?1", COLUMNS),
+        params![id],
+        from_row,
+    )
+    .map_err(|_| format!("Template not found: {}", id))
+}
+
+fn now() -> i64 {
+    chrono::Local::now().timestamp()
+}
+
+/// Snapshot of the library for syncing.
+pub fn export_library<R: Runtime>(app: &AppHandle<R>) -> Result<SyncedLibrary, String> {
+    let state = app
+        .try_state::<PromptState>()
+        .ok_or("Prompt library unavailable")?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM prompt_templates", COLUMNS))
+        .map_err(|e| e.to_string())?;
+    let templates = stmt
+        .query_map([], |row| {
+            Ok(SyncedTemplate {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                body: row.get(2)?,
+                tags: row.get(3)?,
+                created_at: row.get(4)?,
+                updated_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT id, deleted_at FROM prompt_template_tombstones")
+        .map_err(|e| e.to_string())?;
+    let deleted = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(SyncedLibrary { templates, deleted })
+}
+
+/// Merge a peer's library (last writer wins per template). Returns whether anything changed.
+pub fn merge_library<R: Runtime>(app: &AppHandle<R>, peer: SyncedLibrary) -> Result<bool, String> {
+    let state = app
+        .try_state::<PromptState>()
+        .ok_or("Prompt library unavailable")?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut changed = 0;
+    for (id, deleted_at) in peer.deleted {
+        changed += conn
+            .execute(
+                "DELETE FROM prompt_templates WHERE id = ?1 AND updated_at <= ?2",
+                params![id, deleted_at],
+            )
+            .map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO prompt_template_tombstones (id, deleted_at) VALUES (?1, ?2)
+             ON CONFLICT(id) DO UPDATE SET deleted_at = MAX(deleted_at, excluded.deleted_at)",
+            params![id, deleted_at],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    for t in peer.templates {
+        changed += conn
+            .execute(
+                "INSERT INTO prompt_templates (id, name, body, tags, created_at, updated_at)
+                 SELECT ?1, ?2, ?3, ?4, ?5, ?6
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM prompt_template_tombstones WHERE id = ?1 AND deleted_at >= ?6
+                 )
+                 ON CONFLICT(id) DO UPDATE SET
+                     name = excluded.name, body = excluded.body, tags = excluded.tags,
+                     updated_at = excluded.updated_at
+                 WHERE excluded.updated_at > prompt_templates.updated_at",
+                params![t.id, t.name, t.body, t.tags, t.created_at, t.updated_at],
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(changed > 0)
+}
+
+/// Templates by id, as inputs (for [`crate::bundle`] exports). Unknown ids are skipped.
+pub fn export_templates<R: Runtime>(
+    app: &AppHandle<R>,
+    ids: &[String],
+) -> Result<Vec<PromptTemplateInput>, String> {
+    let state = app.state::<PromptState>();
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    Ok(ids
+        .iter()
+        .filter_map(|id| get(&conn, id).ok())
+        .map(|template| PromptTemplateInput {
+            name: template.name,
+            body: template.body,
+            tags: template.tags,
+        })
+        .collect())
+}
+
+/// Add templates in one transaction (for [`crate::bundle`] imports).
+pub fn import_templates<R: Runtime>(
+    app: &AppHandle<R>,
+    inputs: &[PromptTemplateInput],
+) -> Result<(), String> {
+    let state = app.state::<PromptState>();
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for input in inputs {
+        let tags = serde_json::to_string(&normalize_tags(input.tags.clone()))
+            .map_err(|e| e.to_string())?;
+        tx.execute(
+            "INSERT INTO prompt_templates (id, name, body, tags, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+            params![
+                uuid::Uuid::new_v4().to_string(),
+                input.name.trim(),
+                input.body,
+                tags,
+                now()
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Tauri command: templates matching an optional text query and/or tag, by name.
+/// Called from the frontend via `invoke('list_prompt_templates', { query, tag })`.
+#[tauri::command]
+pub fn list_prompt_templates(
+    query: Option<String>,
+    tag: Option<String>,
+    state: State<PromptState>,
+) -> Result<Vec<PromptTemplate>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let pattern = format!("%{}%", query.unwrap_or_default().trim());
+    let tag_pattern = match tag {
+        Some(tag) => format!(
+            "%{}%",
+            serde_json::to_string(&tag.trim().to_lowercase()).unwrap_or_default()
+        ),
+        None => "%".to_string(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM prompt_templates
+             WHERE (name LIKE ?1 OR body LIKE ?1 OR tags LIKE ?1) AND tags LIKE ?2
+             ORDER BY name COLLATE NOCASE",
+            COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![pattern, tag_pattern], from_row)
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+/// Tauri command: add a template.
+/// Called from the frontend via `invoke('create_prompt_template', { input })`.
+#[tauri::command]
+pub fn create_prompt_template(
+    input: PromptTemplateInput,
+    state: State<PromptState>,
+) -> Result<PromptTemplate, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let tags = serde_json::to_string(&normalize_tags(input.tags)).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO prompt_templates (id, name, body, tags, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+        params![id, input.name.trim(), input.body, tags, now()],
+    )
+    .map_err(|e| e.to_string())?;
+    get(&conn, &id)
+}
+
+/// Tauri command: replace a template's name, body, and tags.
+/// Called from the frontend via `invoke('update_prompt_template', { id, input })`.
+#[tauri::command]
+pub fn update_prompt_template(
+    id: String,
+    input: PromptTemplateInput,
+    state: State<PromptState>,
+) -> Result<PromptTemplate, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tags = serde_json::to_string(&normalize_tags(input.tags)).map_err(|e| e.to_string())?;
+    let changed = conn
+        .execute(
+            "UPDATE prompt_templates SET name = ?2, body = ?3, tags = ?4, updated_at = ?5 WHERE id = ?1",
+            params![id, input.name.trim(), input.body, tags, now()],
+        )
+        .map_err(|e| e.to_string())?;
+    if changed == 0 {
+        return Err(format!("Template not found: {}", id));
+    }
+    get(&conn, &id)
+}
+
+/// Tauri command: set a template's tags only.
+/// Called from the frontend via `invoke('tag_prompt_template', { id, tags })`.
+#[tauri::command]
+pub fn tag_prompt_template(
+    id: String,
+    tags: Vec<String>,
+    state: State<PromptState>,
+) -> Result<PromptTemplate, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tags = serde_json::to_string(&normalize_tags(tags)).map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE prompt_templates SET tags = ?2, updated_at = ?3 WHERE id = ?1",
+        params![id, tags, now()],
+    )
+    .map_err(|e| e.to_string())?;
+    get(&conn, &id)
+}
+
+/// Tauri command: delete a template.
+/// Called from the frontend via `invoke('delete_prompt_template', { id })`.
+#[tauri::command]
+pub fn delete_prompt_template(id: String, state: State<PromptState>) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM prompt_templates WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    // Remembered so synced desktops delete it too.
+    conn.execute(
+        "INSERT OR REPLACE INTO prompt_template_tombstones (id, deleted_at) VALUES (?1, ?2)",
+        params![id, now()],
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// Tauri command: template body with `{{variable}}` placeholders filled in.
+/// Called from the frontend via `invoke('render_prompt_template', { id, values })`.
+#[tauri::command]
+pub fn render_prompt_template(
+    id: String,
+    values: HashMap<String, String>,
+    state: State<PromptState>,
+) -> Result<String, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    Ok(substitute(&get(&conn, &id)?.body, &values))
+}
@@ -0,0 +1,153 @@
+//! Quick actions: named one-click backend calls ("Summarize inbox",
+//! "Run tests crew").
+//!
+//! Actions are defined by the frontend or by editing `quick-actions.json` in
+//! the app data dir. Rust exposes them as a tray submenu, as command palette
+//! entries (`list_quick_actions`), and as targets for global hotkeys. Each
+//! action resolves to a [`RunTarget`].
+
+use crate::backend::RunTarget;
+use crate::notifications::{self, Event};
+use crate::{i18n, store};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Persisted actions (app data dir).
+const ACTIONS_FILE: &str = "quick-actions.json";
+
+/// Prefix of tray menu item ids that trigger an action.
+pub const MENU_ID_PREFIX: &str = "quick-action:";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickAction {
+    pub id: String,
+    pub name: String,
+    pub target: RunTarget,
+    /// Accelerator such as `CmdOrCtrl+Shift+1`, bound by [`crate::hotkeys`].
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    /// Show in the tray submenu.
+    #[serde(default = "default_true")]
+    pub in_tray: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// App state: registered actions in display order.
+pub struct QuickActionState {
+    actions: Mutex<Vec<QuickAction>>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let actions: Vec<QuickAction> = store::load_json(app, ACTIONS_FILE);
+    app.manage(QuickActionState {
+        actions: Mutex::new(actions),
+    });
+}
+
+/// All actions in display order.
+pub fn all<R: Runtime>(app: &AppHandle<R>) -> Vec<QuickAction> {
+    app.try_state::<QuickActionState>()
+        .and_then(|state| state.actions.lock().ok().map(|a| a.clone()))
+        .unwrap_or_default()
+}
+
+/// Actions to show in the tray submenu.
+pub fn tray_actions<R: Runtime>(app: &AppHandle<R>) -> Vec<QuickAction> {
+    all(app).into_iter().filter(|a| a.in_tray).collect()
+}
+
+/// Run an action in the background and report the outcome as a notification.
+pub fn trigger<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<(), String> {
+    let action = app
+        .state::<QuickActionState>()
+        .actions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .find(|a| a.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Quick action not found: {}", id))?;
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let result = action.target.run();
+        let (title, body) = match &result {
+            Ok(_) => (
+                i18n::t("notify.quickActionStarted"),
+                i18n::tf("notify.quickActionSent", &[("name", &action.name)]),
+            ),
+            Err(e) => (
+                i18n::t("notify.quickActionFailed"),
+                i18n::tf("notify.runError", &[("name", &action.name), ("error", e)]),
+            ),
+        };
+        notifications::notify(&app, Event::QuickAction, title, body);
+        let _ = app.emit(
+            "quick-action-run",
+            serde_json::json!({ "id": action.id, "ok": result.is_ok(), "error": result.err() }),
+        );
+    });
+    Ok(())
+}
+
+fn save_and_apply(app: &AppHandle, actions: &[QuickAction]) -> Result<Vec<QuickAction>, String> {
+    store::save_json(app, ACTIONS_FILE, &actions)?;
+    crate::refresh_tray_menu(app);
+    crate::hotkeys::sync(app, actions);
+    let _ = app.emit("quick-actions-changed", actions);
+    Ok(actions.to_vec())
+}
+
+/// Tauri command: all quick actions (for the command palette and settings).
+/// Called from the frontend via `invoke('list_quick_actions')`.
+#[tauri::command]
+pub fn list_quick_actions(state: State<QuickActionState>) -> Result<Vec<QuickAction>, String> {
+    Ok(state.actions.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: create or replace an action (matched by `id`; a new id is assigned if empty).
+/// Called from the frontend via `invoke('save_quick_action', { action })`.
+#[tauri::command]
+pub fn save_quick_action(
+    mut action: QuickAction,
+    app: AppHandle,
+    state: State<QuickActionState>,
+) -> Result<Vec<QuickAction>, String> {
+    if action.name.trim().is_empty() {
+        return Err("Quick action needs a name".to_string());
+    }
+    if action.id.is_empty() {
+        action.id = uuid::Uuid::new_v4().to_string();
+    }
+    let mut actions = state.actions.lock().map_err(|e| e.to_string())?;
+    match actions.iter_mut().find(|a| a.id == action.id) {
+        Some(existing) => *existing = action,
+        None => actions.push(action),
+    }
+    save_and_apply(&app, &actions)
+}
+
+/// Tauri command: delete an action.
+/// Called from the frontend via `invoke('delete_quick_action', { id })`.
+#[tauri::command]
+pub fn delete_quick_action(
+    id: String,
+    app: AppHandle,
+    state: State<QuickActionState>,
+) -> Result<Vec<QuickAction>, String> {
+    let mut actions = state.actions.lock().map_err(|e| e.to_string())?;
+    actions.retain(|a| a.id != id);
+    save_and_apply(&app, &actions)
+}
+
+/// Tauri command: run an action now (palette entry or hotkey).
+/// Called from the frontend via `invoke('run_quick_action', { id })`.
+#[tauri::command]
+pub fn run_quick_action(id: String, app: AppHandle) -> Result<(), String> {
+    trigger(&app, &id)
+}
@@ -0,0 +1,53 @@
+//! Quick-prompt window: a small always-on-top box for firing off a prompt
+//! without bringing up the full chat window. Content shared from other apps
+//! lands here too (see [`crate::share`]).
+
+use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+
+/// Label for the quick-prompt window.
+pub const QUICK_PROMPT_WINDOW_LABEL: &str = "quick-prompt";
+
+fn quick_prompt_init_script() -> String {
+    format!(
+        "window.__TAURI_VIEW__ = 'quick-prompt'; {}",
+        crate::base_init(QUICK_PROMPT_WINDOW_LABEL)
+    )
+}
+
+/// Open or focus the quick-prompt window (640×360, frameless, always on top).
+pub fn open<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window(QUICK_PROMPT_WINDOW_LABEL) {
+        crate::show_and_focus(&window);
+        return;
+    }
+
+    let result = WebviewWindowBuilder::new(
+        app,
+        QUICK_PROMPT_WINDOW_LABEL,
+        crate::view_url("view=quick-prompt"),
+    )
+    .title("CrewHub Quick Prompt")
+    .inner_size(640.0, 360.0)
+    .resizable(false)
+    .fullscreen(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .user_agent(&crate::request_headers::user_agent())
+    .on_new_window(crate::external_links::new_window_handler(app))
+    .initialization_script(quick_prompt_init_script())
+    .build();
+
+    match result {
+        Ok(window) => crate::show_and_focus(&window),
+        Err(e) => eprintln!("[CrewHub] Failed to create quick-prompt window: {}", e),
+    }
+}
+
+/// Tauri command: open or focus the quick-prompt window.
+/// Called from the frontend via `invoke('open_quick_prompt')`.
+#[tauri::command]
+pub fn open_quick_prompt(app: AppHandle) {
+    open(&app);
+}
@@ -0,0 +1,226 @@
+//! Scheduled quiet hours (e.g. 19:00–08:00) with a catch-up at the end.
+//!
+//! Off by default; configured in `quiet-hours.json`. While quiet hours are
+//! on, CrewHub's Do Not Disturb is on (reason `schedule`, see
+//! [`crate::dnd`]), so the tray stops pulsing — but notifications are held
+//! instead of dropped, and the tray badge keeps the count it had when quiet
+//! hours began. When they end, the held notifications become one summary
+//! notification and the badge jumps to the accurate count in a single tray
+//! update. Critical alerts follow [`crate::dnd::DndConfig::critical_override`].
+//!
+//! A period that crosses midnight belongs to the day it starts on, so
+//! `days: [1, 2, 3, 4, 5]` with 19:00–08:00 covers Monday evening through
+//! Saturday morning.
+
+use crate::badges::BadgeStyle;
+use crate::notifications::{self, Event, Notice};
+use crate::{dnd, i18n, power, store};
+use chrono::{Datelike, Duration as ChronoDuration, Local, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "quiet-hours.json";
+
+/// How often the schedule is checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+static CONFIG: Mutex<Option<QuietHoursConfig>> = Mutex::new(None);
+
+/// Notifications held until quiet hours end.
+static HELD: Mutex<Vec<Notice>> = Mutex::new(Vec::new());
+
+/// Badge shown while quiet hours are on.
+static FROZEN: Mutex<Option<(u32, BadgeStyle)>> = Mutex::new(None);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct QuietHoursConfig {
+    pub enabled: bool,
+    /// `HH:MM`, local time.
+    pub start: String,
+    /// `HH:MM`, local time; before `start` for a period that crosses midnight.
+    pub end: String,
+    /// ISO weekdays (1 = Monday … 7 = Sunday) a period may start on; empty for every day.
+    pub days: Vec<u32>,
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: "19:00".to_string(),
+            end: "08:00".to_string(),
+            days: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHoursStatus {
+    config: QuietHoursConfig,
+    active: bool,
+    /// Notifications waiting for the end of quiet hours.
+    held: usize,
+}
+
+/// Load the config and start following the schedule.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: QuietHoursConfig = store::load_json(app, CONFIG_FILE);
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config);
+    }
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        check(&app);
+        std::thread::sleep(power::interval(&app, POLL_INTERVAL));
+    });
+}
+
+fn config() -> QuietHoursConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M").ok()
+}
+
+/// Whether `now` falls in a quiet period.
+fn within(config: &QuietHoursConfig, now: NaiveDateTime) -> bool {
+    let (Some(start), Some(end)) = (parse_time(&config.start), parse_time(&config.end)) else {
+        return false;
+    };
+    let time = now.time();
+    let started_on = if start < end {
+        if time < start || time >= end {
+            return false;
+        }
+        now.date()
+    } else if start > end {
+        if time >= start {
+            now.date()
+        } else if time < end {
+            now.date() - ChronoDuration::days(1)
+        } else {
+            return false;
+        }
+    } else {
+        return false;
+    };
+    config.days.is_empty()
+        || config
+            .days
+            .contains(&started_on.weekday().number_from_monday())
+}
+
+/// Start or end quiet hours when the schedule says so.
+fn check<R: Runtime>(app: &AppHandle<R>) {
+    let config = config();
+    let on = config.enabled && within(&config, Local::now().naive_local());
+    if on == dnd::scheduled() {
+        return;
+    }
+    if on {
+        if let Ok(mut frozen) = FROZEN.lock() {
+            *frozen = Some(crate::live_badge(app));
+        }
+        println!("[CrewHub] Quiet hours started");
+        dnd::set_schedule(app, true);
+    } else {
+        println!("[CrewHub] Quiet hours ended");
+        dnd::set_schedule(app, false);
+        catch_up(app);
+    }
+}
+
+/// Show what was held as one notification and the accurate badge.
+fn catch_up<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(mut frozen) = FROZEN.lock() {
+        *frozen = None;
+    }
+    crate::refresh_tray_icon(app);
+    let held: Vec<Notice> = HELD
+        .lock()
+        .map(|mut held| held.drain(..).collect())
+        .unwrap_or_default();
+    if held.is_empty() {
+        return;
+    }
+    let latest = held
+        .last()
+        .map(|notice| notice.title.clone())
+        .unwrap_or_default();
+    notifications::deliver(
+        app,
+        Notice {
+            title: i18n::t("notify.quietHoursTitle"),
+            body: i18n::tf(
+                "notify.quietHoursBody",
+                &[("count", &held.len().to_string()), ("title", &latest)],
+            ),
+            group: None,
+            event: Event::Message,
+            critical: held.iter().any(|notice| notice.critical),
+            action: None,
+            context: Default::default(),
+        },
+    );
+}
+
+/// Hold a notification until quiet hours end. Returns whether it was held.
+pub fn hold<R: Runtime>(app: &AppHandle<R>, notice: &Notice) -> bool {
+    if !dnd::scheduled() || !dnd::suppresses(app, notice.critical) {
+        return false;
+    }
+    HELD.lock()
+        .map(|mut held| held.push(notice.clone()))
+        .is_ok()
+}
+
+/// The badge to show instead of the live one while quiet hours are on.
+pub fn frozen_badge() -> Option<(u32, BadgeStyle)> {
+    FROZEN.lock().ok().and_then(|frozen| *frozen)
+}
+
+fn status() -> QuietHoursStatus {
+    QuietHoursStatus {
+        config: config(),
+        active: dnd::scheduled(),
+        held: HELD.lock().map(|held| held.len()).unwrap_or(0),
+    }
+}
+
+/// Tauri command: quiet hours config, whether they are on, and how much is held.
+/// Called from the settings window via `invoke('get_quiet_hours')`.
+#[tauri::command]
+pub fn get_quiet_hours() -> QuietHoursStatus {
+    status()
+}
+
+/// Tauri command: replace the quiet hours config and apply it right away.
+/// Called from the settings window via `invoke('configure_quiet_hours', { config })`.
+#[tauri::command]
+pub fn configure_quiet_hours(
+    config: QuietHoursConfig,
+    app: AppHandle,
+) -> Result<QuietHoursStatus, String> {
+    for time in [&config.start, &config.end] {
+        parse_time(time).ok_or_else(|| format!("Invalid time (expected HH:MM): {}", time))?;
+    }
+    if let Some(day) = config.days.iter().find(|day| !(1..=7).contains(*day)) {
+        return Err(format!("Invalid weekday: {}", day));
+    }
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config);
+    }
+    check(&app);
+    Ok(status())
+}
@@ -0,0 +1,121 @@
+//! Recently opened conversations.
+//!
+//! The chat view reports each conversation it opens; the most recent ones
+//! are kept across launches for launchers (see [`crate::control`]) and
+//! platform shell menus.
+
+use crate::pins::{self, ConversationRef};
+use crate::{ephemeral, store};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime, State, Window};
+
+/// Persisted list (app data dir).
+const RECENTS_FILE: &str = "recent-conversations.json";
+
+/// How many conversations are remembered.
+const MAX_RECENTS: usize = 25;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentConversation {
+    #[serde(flatten)]
+    pub conversation: ConversationRef,
+    /// Unix seconds.
+    pub opened_at: i64,
+}
+
+/// App state: recent conversations, most recent first.
+pub struct RecentState {
+    recents: Mutex<Vec<RecentConversation>>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let recents: Vec<RecentConversation> = store::load_json(app, RECENTS_FILE);
+    app.manage(RecentState {
+        recents: Mutex::new(recents),
+    });
+}
+
+/// Recent conversations, most recent first.
+pub fn recent<R: Runtime>(app: &AppHandle<R>) -> Vec<RecentConversation> {
+    app.try_state::<RecentState>()
+        .and_then(|state| state.recents.lock().ok().map(|r| r.clone()))
+        .unwrap_or_default()
+}
+
+/// Drop a conversation from the list (it was archived, see [`crate::archive`]).
+pub fn forget<R: Runtime>(app: &AppHandle<R>, conversation: &ConversationRef) {
+    let Some(state) = app.try_state::<RecentState>() else {
+        return;
+    };
+    let Ok(mut recents) = state.recents.lock() else {
+        return;
+    };
+    let before = recents.len();
+    recents.retain(|r| {
+        !(r.conversation.session_key == conversation.session_key
+            && r.conversation.backend_id == conversation.backend_id)
+    });
+    if recents.len() != before {
+        if let Err(e) = store::save_json(app, RECENTS_FILE, &*recents) {
+            eprintln!("[CrewHub] Failed to save recent conversations: {}", e);
+        }
+    }
+}
+
+/// Tauri command: recent conversations, most recent first.
+/// Called from the frontend via `invoke('list_recent_conversations')`.
+#[tauri::command]
+pub fn list_recent_conversations(
+    state: State<RecentState>,
+) -> Result<Vec<RecentConversation>, String> {
+    Ok(state.recents.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: record that a conversation was opened (moves it to the front).
+/// Called from the frontend via `invoke('record_recent_conversation', { sessionKey, title, backendId })`.
+#[tauri::command]
+pub fn record_recent_conversation(
+    session_key: String,
+    title: String,
+    backend_id: Option<String>,
+    app: AppHandle,
+    window: Window,
+    state: State<RecentState>,
+) -> Result<(), String> {
+    if ephemeral::is_ephemeral(&session_key) {
+        return Ok(());
+    }
+    let backend_id = backend_id.unwrap_or_else(pins::default_backend);
+    let conversation = ConversationRef {
+        session_key,
+        title,
+        backend_id,
+    };
+    let mut recents = state.recents.lock().map_err(|e| e.to_string())?;
+    recents.retain(|r| {
+        !(r.conversation.session_key == conversation.session_key
+            && r.conversation.backend_id == conversation.backend_id)
+    });
+    recents.insert(
+        0,
+        RecentConversation {
+            conversation: conversation.clone(),
+            opened_at: chrono::Local::now().timestamp(),
+        },
+    );
+    recents.truncate(MAX_RECENTS);
+    store::save_json(&app, RECENTS_FILE, &*recents)?;
+    drop(recents);
+    #[cfg(target_os = "windows")]
+    crate::jump_list::refresh(&app);
+    #[cfg(target_os = "macos")]
+    {
+        crate::dock_menu::refresh(&app);
+        crate::handoff::set_current(&app, &conversation);
+    }
+    crate::archive::rehydrate(&app, &conversation, window.label());
+    crate::search_index::refresh(&app);
+    Ok(())
+}
@@ -0,0 +1,428 @@
+//! Local reminders ("ping me about this thread at 4pm", "remind me when the
+//! build crew finishes").
+//!
+//! Reminders live in the shared SQLite database, so they survive restarts. A
+//! reminder fires either at a time or when an agent finishes its run (reported
+//! by [`crate::presence`]), as a native notification whose Open action jumps
+//! to the attached conversation (or the chat). Timed reminders are checked
+//! every few seconds, holding off while the machine sleeps and again right
+//! after it wakes; ones that came due while the app was closed or the machine
+//! was asleep are shown as late, or summed up in one notification when there
+//! are many. When the user moves to another time zone, pending timed
+//! reminders keep their local wall-clock time (see [`crate::clock`]).
+
+use crate::notifications::{self, Event, Notice, NoticeAction};
+use crate::pins::ConversationRef;
+use crate::{db, i18n, launch, power};
+use chrono::{Local, TimeZone};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// How often due reminders are checked.
+const TICK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A reminder noticed later than this after its time counts as missed.
+const GRACE_SECONDS: i64 = 120;
+
+/// More missed reminders than this are summed up in one notification.
+const MAX_CATCH_UP: usize = 3;
+
+/// When a reminder fires.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Trigger {
+    /// At a point in time (Unix seconds).
+    At { at: i64 },
+    /// When an agent goes from working (or blocked) to idle or error.
+    AgentFinished {
+        /// Namespaced agent id, as in [`crate::presence::AgentPresence`].
+        agent_id: String,
+        #[serde(default)]
+        agent_name: Option<String>,
+    },
+}
+
+impl Trigger {
+    /// Column value used to find due timed reminders.
+    fn due_at(&self) -> Option<i64> {
+        match self {
+            Trigger::At { at } => Some(*at),
+            Trigger::AgentFinished { .. } => None,
+        }
+    }
+
+    /// Column value used to find reminders waiting on an agent.
+    fn agent_id(&self) -> Option<&str> {
+        match self {
+            Trigger::At { .. } => None,
+            Trigger::AgentFinished { agent_id, .. } => Some(agent_id),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reminder {
+    pub id: String,
+    pub title: String,
+    pub note: String,
+    pub trigger: Trigger,
+    /// Opened by the notification's Open action.
+    pub conversation: Option<ConversationRef>,
+    /// Unix seconds.
+    pub created_at: i64,
+    /// Unix seconds; `None` while pending.
+    pub fired_at: Option<i64>,
+}
+
+/// Fields the frontend sets when creating a reminder.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderInput {
+    pub title: String,
+    #[serde(default)]
+    pub note: String,
+    pub trigger: Trigger,
+    #[serde(default)]
+    pub conversation: Option<ConversationRef>,
+}
+
+/// App state: database connection for the reminders table.
+pub struct ReminderState {
+    conn: Mutex<Connection>,
+}
+
+/// Open the reminders table, register state, and start checking for due reminders.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let conn = db::open(app)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS reminders (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            note TEXT NOT NULL DEFAULT '',
+            trigger TEXT NOT NULL,
+            due_at INTEGER,
+            agent_id TEXT,
+            conversation TEXT,
+            created_at INTEGER NOT NULL,
+            fired_at INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS reminders_due ON reminders (fired_at, due_at);",
+    )
+    .map_err(|e| e.to_string())?;
+    app.manage(ReminderState {
+        conn: Mutex::new(conn),
+    });
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        if !power::is_suspended() {
+            check(&app);
+        }
+        std::thread::sleep(TICK_INTERVAL);
+    });
+    Ok(())
+}
+
+fn now() -> i64 {
+    Local::now().timestamp()
+}
+
+const COLUMNS: &str = "id, title, note, trigger, conversation, created_at, fired_at";
+
+fn from_row(row: &Row) -> rusqlite::Result<Reminder> {
+    let trigger: String = row.get(3)?;
+    let conversation: Option<String> = row.get(4)?;
+    Ok(Reminder {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        note: row.get(2)?,
+        trigger: serde_json::from_str(&trigger).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, e.into())
+        })?,
+        conversation: conversation.and_then(|c| serde_json::from_str(&c).ok()),
+        created_at: row.get(5)?,
+        fired_at: row.get(6)?,
+    })
+}
+
+/// Mark the pending reminders matching `condition` as fired and return them.
+fn take_pending(
+    conn: &Connection,
+    condition: &str,
+    param: &dyn rusqlite::ToSql,
+) -> Result<Vec<Reminder>, String> {
+    let sql = format!(
+        "SELECT {} FROM reminders WHERE fired_at IS NULL AND {} ORDER BY created_at",
+        COLUMNS, condition
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let mut reminders = stmt
+        .query_map([param], from_row)
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| e.to_string())?;
+    let fired_at = now();
+    for reminder in &mut reminders {
+        conn.execute(
+            "UPDATE reminders SET fired_at = ?1 WHERE id = ?2",
+            params![fired_at, reminder.id],
+        )
+        .map_err(|e| e.to_string())?;
+        reminder.fired_at = Some(fired_at);
+    }
+    Ok(reminders)
+}
+
+/// Fire timed reminders that are due (also called right after wake-up).
+pub fn check<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<ReminderState>() else {
+        return;
+    };
+    let due = match state.conn.lock() {
+        Ok(conn) => take_pending(&conn, "due_at IS NOT NULL AND due_at <= ?1", &now()),
+        Err(e) => Err(e.to_string()),
+    };
+    match due {
+        Ok(due) if !due.is_empty() => fire(app, due),
+        Ok(_) => {}
+        Err(e) => eprintln!("[CrewHub] Failed to check reminders: {}", e),
+    }
+}
+
+/// Shift pending timed reminders after the UTC offset moved by `offset_delta`
+/// seconds, so each still fires at the same local wall-clock time.
+pub fn keep_local_times<R: Runtime>(app: &AppHandle<R>, offset_delta: i64) {
+    let Some(state) = app.try_state::<ReminderState>() else {
+        return;
+    };
+    let result = match state.conn.lock() {
+        Ok(conn) => conn
+            .execute(
+                "UPDATE reminders SET due_at = due_at - ?1,
+                 trigger = json_set(trigger, '$.at', due_at - ?1)
+                 WHERE fired_at IS NULL AND due_at IS NOT NULL",
+                [offset_delta],
+            )
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    match result {
+        Ok(0) => {}
+        Ok(_) => {
+            let _ = app.emit("reminders-changed", ());
+        }
+        Err(e) => eprintln!(
+            "[CrewHub] Failed to move reminders to the new time zone: {}",
+            e
+        ),
+    }
+}
+
+/// Fire reminders waiting on `agent_id` (called by presence when an agent finishes a run).
+pub fn agent_finished<R: Runtime>(app: &AppHandle<R>, agent_id: &str) {
+    let Some(state) = app.try_state::<ReminderState>() else {
+        return;
+    };
+    let due = match state.conn.lock() {
+        Ok(conn) => take_pending(&conn, "agent_id = ?1", &agent_id),
+        Err(e) => Err(e.to_string()),
+    };
+    match due {
+        Ok(due) if !due.is_empty() => fire(app, due),
+        Ok(_) => {}
+        Err(e) => eprintln!("[CrewHub] Failed to check reminders: {}", e),
+    }
+}
+
+fn open_action(conversation: Option<&ConversationRef>) -> NoticeAction {
+    let args = match conversation {
+        Some(conversation) => launch::action_args(
+            "open-conversation",
+            &[
+                ("sessionKey", &conversation.session_key),
+                ("backendId", &conversation.backend_id),
+                ("title", &conversation.title),
+            ],
+        ),
+        None => launch::action_args("open-chat", &[]),
+    };
+    NoticeAction {
+        label: i18n::t("reminders.open"),
+        args,
+    }
+}
+
+fn fire<R: Runtime>(app: &AppHandle<R>, reminders: Vec<Reminder>) {
+    let now = now();
+    let missed = |r: &Reminder| {
+        r.trigger
+            .due_at()
+            .is_some_and(|at| now - at > GRACE_SECONDS)
+    };
+    let (late, on_time): (Vec<&Reminder>, Vec<&Reminder>) =
+        reminders.iter().partition(|r| missed(r));
+
+    let mut notices: Vec<Notice> = on_time
+        .iter()
+        .map(|reminder| notice(reminder, false))
+        .collect();
+    if late.len() > MAX_CATCH_UP {
+        notices.push(Notice {
+            title: i18n::tf(
+                "reminders.missedTitle",
+                &[("count", &late.len().to_string())],
+            ),
+            body: i18n::t("reminders.missedBody"),
+            group: None,
+            event: Event::Reminder,
+            critical: false,
+            action: Some(open_action(None)),
+            context: Default::default(),
+        });
+    } else {
+        notices.extend(late.iter().map(|reminder| notice(reminder, true)));
+    }
+    for notice in notices {
+        notifications::send(app, notice);
+    }
+    let _ = app.emit("reminders-fired", &reminders);
+}
+
+fn notice(reminder: &Reminder, late: bool) -> Notice {
+    let body = if late {
+        let due = reminder
+            .trigger
+            .due_at()
+            .and_then(|at| Local.timestamp_opt(at, 0).single())
+            .map(|at| at.format("%H:%M").to_string())
+            .unwrap_or_default();
+        i18n::tf("reminders.late", &[("time", &due)])
+    } else if !reminder.note.is_empty() {
+        reminder.note.clone()
+    } else {
+        match &reminder.trigger {
+            Trigger::AgentFinished {
+                agent_id,
+                agent_name,
+            } => i18n::tf(
+                "reminders.agentFinished",
+                &[("agent", agent_name.as_deref().unwrap_or(agent_id))],
+            ),
+            Trigger::At { .. } => String::new(),
+        }
+    };
+    Notice {
+        title: reminder.title.clone(),
+        body,
+        group: None,
+        event: Event::Reminder,
+        critical: false,
+        action: Some(open_action(reminder.conversation.as_ref())),
+        context: Default::default(),
+    }
+}
+
+/// Tauri command: all reminders, pending first, then by creation time.
+/// Called from the frontend via `invoke('list_reminders')`.
+#[tauri::command]
+pub fn list_reminders(state: State<ReminderState>) -> Result<Vec<Reminder>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let sql = format!(
+        "SELECT {} FROM reminders ORDER BY fired_at IS NOT NULL, created_at",
+        COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let reminders = stmt
+        .query_map([], from_row)
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| e.to_string())?;
+    Ok(reminders)
+}
+
+/// Tauri command: create a reminder.
+/// Called from the frontend via `invoke('create_reminder', { input })`.
+#[tauri::command]
+pub fn create_reminder(
+    input: ReminderInput,
+    state: State<ReminderState>,
+) -> Result<Reminder, String> {
+    let reminder = Reminder {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: input.title,
+        note: input.note,
+        trigger: input.trigger,
+        conversation: input.conversation,
+        created_at: now(),
+        fired_at: None,
+    };
+    let trigger = serde_json::to_string(&reminder.trigger).map_err(|e| e.to_string())?;
+    let conversation = reminder
+        .conversation
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO reminders (id, title, note, trigger, due_at, agent_id, conversation, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            reminder.id,
+            reminder.title,
+            reminder.note,
+            trigger,
+            reminder.trigger.due_at(),
+            reminder.trigger.agent_id(),
+            conversation,
+            reminder.created_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(reminder)
+}
+
+/// Tauri command: move a timed reminder to a new time (also re-arms a fired one).
+/// Called from the frontend via `invoke('snooze_reminder', { id, at })`.
+#[tauri::command]
+pub fn snooze_reminder(
+    id: String,
+    at: i64,
+    state: State<ReminderState>,
+) -> Result<Reminder, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let trigger = serde_json::to_string(&Trigger::At { at }).map_err(|e| e.to_string())?;
+    let updated = conn
+        .execute(
+            "UPDATE reminders SET trigger = ?1, due_at = ?2, agent_id = NULL, fired_at = NULL
+             WHERE id = ?3",
+            params![trigger, at, id],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Reminder not found: {}", id));
+    }
+    conn.query_row(
+        &format!("SELECT {} FROM reminders WHERE id = ?1", COLUMNS),
+        [&id],
+        from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Reminder not found: {}", id))
+}
+
+/// Tauri command: delete a reminder.
+/// Called from the frontend via `invoke('delete_reminder', { id })`.
+#[tauri::command]
+pub fn delete_reminder(id: String, state: State<ReminderState>) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM reminders WHERE id = ?1", [&id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
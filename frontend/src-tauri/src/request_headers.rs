@@ -0,0 +1,158 @@
+//! Extra request headers and the user agent of webview traffic.
+//!
+//! Every CrewHub window identifies itself with a user agent ending in
+//! `CrewHub/<version>`, set when the window is created (the configured
+//! `userAgent` replaces it). Requests the frontend makes to a backend with
+//! `fetch` or `XMLHttpRequest` additionally carry `X-CrewHub-Client`, an
+//! `X-CrewHub-Desktop-Session` id that is new for every launch, and the
+//! configured headers (auth tokens, tracing ids); the wrapper in
+//! `assets/request-headers.js` adds them, as webviews offer no native hook
+//! for other origins. `EventSource` and WebSocket connections can't carry
+//! headers and only get the user agent. Custom headers make cross-origin
+//! requests preflighted, so the backend has to allow them in CORS.
+//!
+//! Header changes reach open windows at once; a new user agent only applies
+//! to windows created afterwards.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "request-headers.json";
+
+const SCRIPT: &str = include_str!("../assets/request-headers.js");
+
+static CONFIG: Mutex<Option<RequestHeadersConfig>> = Mutex::new(None);
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RequestHeadersConfig {
+    /// Added to every backend request (name → value).
+    pub headers: BTreeMap<String, String>,
+    /// Replaces the default user agent.
+    pub user_agent: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestHeadersStatus {
+    config: RequestHeadersConfig,
+    /// User agent of new windows.
+    user_agent: String,
+    /// Headers sent with backend requests, including CrewHub's own.
+    effective_headers: BTreeMap<String, String>,
+}
+
+fn config() -> RequestHeadersConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+/// Id of this launch, sent as `X-CrewHub-Desktop-Session`.
+fn session_id() -> &'static str {
+    static ID: OnceLock<String> = OnceLock::new();
+    ID.get_or_init(|| uuid::Uuid::new_v4().to_string())
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let loaded: RequestHeadersConfig = store::load_json(app, CONFIG_FILE);
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = Some(loaded);
+    }
+}
+
+/// User agent for new windows.
+pub fn user_agent() -> String {
+    config().user_agent.unwrap_or_else(|| {
+        let platform = match std::env::consts::OS {
+            "macos" => "Macintosh; Mac OS X",
+            "windows" => "Windows NT 10.0; Win64; x64",
+            _ => "X11; Linux x86_64",
+        };
+        format!(
+            "Mozilla/5.0 ({}) AppleWebKit/605.1.15 (KHTML, like Gecko) CrewHub/{}",
+            platform,
+            env!("CARGO_PKG_VERSION")
+        )
+    })
+}
+
+pub fn effective_headers() -> BTreeMap<String, String> {
+    let mut headers = BTreeMap::from([
+        (
+            "X-CrewHub-Client".to_string(),
+            format!("desktop/{}", env!("CARGO_PKG_VERSION")),
+        ),
+        (
+            "X-CrewHub-Desktop-Session".to_string(),
+            session_id().to_string(),
+        ),
+    ]);
+    headers.extend(config().headers);
+    headers
+}
+
+fn headers_script() -> String {
+    let headers = serde_json::to_string(&effective_headers()).unwrap_or_else(|_| "{}".to_string());
+    format!("window.__CREWHUB_REQUEST_HEADERS__ = {};", headers)
+}
+
+/// Init script part: the headers and the request wrapper.
+pub fn init_script() -> String {
+    format!("{} {}", headers_script(), SCRIPT)
+}
+
+fn valid_header(name: &str, value: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+        && !value.contains(['\r', '\n'])
+}
+
+fn status() -> RequestHeadersStatus {
+    RequestHeadersStatus {
+        config: config(),
+        user_agent: user_agent(),
+        effective_headers: effective_headers(),
+    }
+}
+
+/// Tauri command: configured headers and user agent, and what is sent.
+/// Called from the settings window via `invoke('get_request_headers')`.
+#[tauri::command]
+pub fn get_request_headers() -> RequestHeadersStatus {
+    status()
+}
+
+/// Tauri command: replace the extra headers and user agent; headers apply to
+/// open windows right away.
+/// Called from the settings window via `invoke('set_request_headers', { config })`.
+#[tauri::command]
+pub fn set_request_headers(
+    config: RequestHeadersConfig,
+    app: AppHandle,
+) -> Result<RequestHeadersStatus, String> {
+    if let Some((name, _)) = config
+        .headers
+        .iter()
+        .find(|(name, value)| !valid_header(name, value))
+    {
+        return Err(format!("Invalid header: {}", name));
+    }
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config);
+    }
+    let script = headers_script();
+    for window in app.webview_windows().values() {
+        let _ = window.eval(&script);
+    }
+    Ok(status())
+}
@@ -0,0 +1,382 @@
+//! Low disk space and memory pressure warnings.
+//!
+//! Free space on the volumes holding the app data and cache directories (where
+//! attachments, screenshots and reports live) and the system memory load are
+//! checked once a minute. Crossing a threshold emits `resource-pressure`,
+//! shows a native notification and tightens retention until the situation
+//! recovers: with low disk space, cached files older than
+//! [`PRESSURE_CACHE_AGE`] and all but the newest benchmark reports are
+//! deleted on every check; under memory pressure the clipboard history keeps
+//! fewer entries ([`crate::clipboard_history`]). Recovery needs some headroom
+//! past the threshold so the state doesn't flap.
+
+use crate::notifications::{self, Event};
+use crate::{i18n, power, store};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "resources.json";
+
+/// How often disk space and memory are checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Cache subdirectories swept while disk space is low.
+const CACHE_DIRS: &[&str] = &["attachments", "drag-out", "screenshots", "print"];
+
+/// Cached files older than this are deleted while disk space is low.
+const PRESSURE_CACHE_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Benchmark reports kept while disk space is low.
+const PRESSURE_REPORTS: usize = 5;
+
+/// Extra free space (percent of the threshold) needed to leave low-disk mode.
+const DISK_RECOVERY_PERCENT: u64 = 20;
+
+/// Memory load (percentage points below the threshold) needed to recover.
+const MEMORY_RECOVERY_POINTS: u8 = 5;
+
+static LOW_DISK: AtomicBool = AtomicBool::new(false);
+static MEMORY_PRESSURE: AtomicBool = AtomicBool::new(false);
+
+/// Last measurement, for `get_resource_status`.
+static LAST: Mutex<Option<Measurement>> = Mutex::new(None);
+
+static CONFIG: Mutex<Option<ResourceConfig>> = Mutex::new(None);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ResourceConfig {
+    pub enabled: bool,
+    /// Low disk space below this many MiB free on any watched volume.
+    pub min_free_disk_mb: u64,
+    /// Memory pressure at or above this percentage of physical memory in use.
+    pub max_memory_percent: u8,
+    /// Show a native notification when a threshold is crossed.
+    pub notify: bool,
+}
+
+impl Default for ResourceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_free_disk_mb: 1024,
+            max_memory_percent: 92,
+            notify: true,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Measurement {
+    /// Smallest free space across the watched directories, in MiB.
+    free_disk_mb: Option<u64>,
+    /// Physical memory in use, in percent.
+    memory_percent: Option<u8>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceStatus {
+    low_disk: bool,
+    memory_pressure: bool,
+    free_disk_mb: Option<u64>,
+    memory_percent: Option<u8>,
+    config: ResourceConfig,
+}
+
+/// Load the config and start monitoring.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: ResourceConfig = store::load_json(app, CONFIG_FILE);
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config);
+    }
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        check(&app);
+        std::thread::sleep(power::interval(&app, POLL_INTERVAL));
+    });
+}
+
+fn config() -> ResourceConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+/// Whether memory is under pressure (caches should stay small).
+pub fn memory_pressure() -> bool {
+    MEMORY_PRESSURE.load(Ordering::Relaxed)
+}
+
+fn status() -> ResourceStatus {
+    let last = LAST.lock().ok().and_then(|last| last.clone());
+    ResourceStatus {
+        low_disk: LOW_DISK.load(Ordering::Relaxed),
+        memory_pressure: memory_pressure(),
+        free_disk_mb: last.as_ref().and_then(|last| last.free_disk_mb),
+        memory_percent: last.and_then(|last| last.memory_percent),
+        config: config(),
+    }
+}
+
+fn watched_dirs<R: Runtime>(app: &AppHandle<R>) -> Vec<PathBuf> {
+    let paths = app.path();
+    [paths.app_data_dir(), paths.app_cache_dir()]
+        .into_iter()
+        .flatten()
+        .map(|dir| {
+            // Free space is per volume, so measure the nearest existing ancestor.
+            dir.ancestors()
+                .find(|dir| dir.exists())
+                .map_or_else(|| dir.clone(), Path::to_path_buf)
+        })
+        .collect()
+}
+
+fn check<R: Runtime>(app: &AppHandle<R>) {
+    let config = config();
+    if !config.enabled {
+        set(app, &LOW_DISK, false, None);
+        set(app, &MEMORY_PRESSURE, false, None);
+        return;
+    }
+    let measurement = Measurement {
+        free_disk_mb: watched_dirs(app)
+            .iter()
+            .filter_map(|dir| free_disk_bytes(dir))
+            .min()
+            .map(|bytes| bytes / (1024 * 1024)),
+        memory_percent: memory_load(),
+    };
+    if let Ok(mut last) = LAST.lock() {
+        *last = Some(measurement.clone());
+    }
+
+    if let Some(free) = measurement.free_disk_mb {
+        let low = if LOW_DISK.load(Ordering::Relaxed) {
+            free < config.min_free_disk_mb * (100 + DISK_RECOVERY_PERCENT) / 100
+        } else {
+            free < config.min_free_disk_mb
+        };
+        let notice = (
+            "notify.lowDiskTitle",
+            "notify.lowDiskBody",
+            free.to_string(),
+        );
+        set(app, &LOW_DISK, low, config.notify.then_some(notice));
+    }
+    if let Some(load) = measurement.memory_percent {
+        let pressure = if memory_pressure() {
+            load + MEMORY_RECOVERY_POINTS > config.max_memory_percent
+        } else {
+            load >= config.max_memory_percent
+        };
+        let notice = ("notify.memoryTitle", "notify.memoryBody", load.to_string());
+        set(
+            app,
+            &MEMORY_PRESSURE,
+            pressure,
+            config.notify.then_some(notice),
+        );
+    }
+
+    if LOW_DISK.load(Ordering::Relaxed) {
+        free_space(app);
+    }
+    if memory_pressure() {
+        crate::clipboard_history::trim(app);
+    }
+}
+
+/// Update one pressure flag; entering it emits and optionally notifies
+/// (`(title key, body key, value)`), leaving it only emits.
+fn set<R: Runtime>(
+    app: &AppHandle<R>,
+    flag: &AtomicBool,
+    on: bool,
+    notice: Option<(&str, &str, String)>,
+) {
+    if flag.swap(on, Ordering::Relaxed) == on {
+        return;
+    }
+    let _ = app.emit("resource-pressure", status());
+    if let Some((title, body, value)) = notice.filter(|_| on) {
+        notifications::notify(
+            app,
+            Event::ResourcePressure,
+            i18n::t(title),
+            i18n::tf(body, &[("value", &value)]),
+        );
+    }
+}
+
+/// Delete cached files past [`PRESSURE_CACHE_AGE`] and old benchmark reports.
+fn free_space<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(cache) = app.path().app_cache_dir() {
+        for dir in CACHE_DIRS {
+            remove_older_than(&cache.join(dir), PRESSURE_CACHE_AGE);
+        }
+    }
+    if let Ok(reports) = store::data_path(app, crate::benchmark::REPORTS_DIR) {
+        let mut files: Vec<(SystemTime, PathBuf)> = std::fs::read_dir(reports)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+            .collect();
+        files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+        for (_, path) in files.into_iter().skip(PRESSURE_REPORTS) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Remove files under `dir` not modified for `age`, then empty directories.
+fn remove_older_than(dir: &Path, age: Duration) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            remove_older_than(&path, age);
+            let _ = std::fs::remove_dir(&path);
+        } else if metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|elapsed| elapsed > age)
+        {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+}
+
+/// Free bytes available to the user on the volume holding `path`.
+#[cfg(unix)]
+fn free_disk_bytes(path: &Path) -> Option<u64> {
+    // POSIX output: header, then "<fs> <blocks> <used> <available> <capacity> <mount>".
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let available: u64 = text
+        .lines()
+        .nth(1)?
+        .split_whitespace()
+        .nth(3)?
+        .parse()
+        .ok()?;
+    Some(available * 1024)
+}
+
+#[cfg(windows)]
+fn free_disk_bytes(path: &Path) -> Option<u64> {
+    use windows::core::HSTRING;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let mut available = 0u64;
+    unsafe {
+        GetDiskFreeSpaceExW(
+            &HSTRING::from(path.as_os_str()),
+            Some(&mut available),
+            None,
+            None,
+        )
+        .ok()?;
+    }
+    Some(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn free_disk_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Share of physical memory in use: `MemAvailable` against `MemTotal`.
+#[cfg(target_os = "linux")]
+fn memory_load() -> Option<u8> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let field = |name: &str| -> Option<u64> {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name))?
+            .trim_start_matches(':')
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    };
+    let (total, available) = (field("MemTotal")?, field("MemAvailable")?);
+    (total > 0).then(|| (100 - available.min(total) * 100 / total) as u8)
+}
+
+/// The kernel's memory status level is the percentage of memory still free.
+#[cfg(target_os = "macos")]
+fn memory_load() -> Option<u8> {
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", "kern.memorystatus_level"])
+        .output()
+        .ok()?;
+    let free: u8 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()?;
+    Some(100u8.saturating_sub(free))
+}
+
+#[cfg(windows)]
+fn memory_load() -> Option<u8> {
+    use windows::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    let mut status = MEMORYSTATUSEX {
+        dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32,
+        ..Default::default()
+    };
+    unsafe { GlobalMemoryStatusEx(&mut status) }.ok()?;
+    Some(status.dwMemoryLoad as u8)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn memory_load() -> Option<u8> {
+    None
+}
+
+/// Tauri command: current pressure state, last measurement and config.
+/// Called from the frontend via `invoke('get_resource_status')`.
+#[tauri::command]
+pub fn get_resource_status() -> ResourceStatus {
+    status()
+}
+
+/// Tauri command: replace the thresholds and re-check right away.
+/// Called from the settings window via `invoke('configure_resource_monitor', { config })`.
+#[tauri::command]
+pub async fn configure_resource_monitor(
+    config: ResourceConfig,
+    app: AppHandle,
+) -> Result<ResourceStatus, String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config);
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        check(&app);
+        status()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
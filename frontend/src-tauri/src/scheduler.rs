@@ -0,0 +1,444 @@
+//! Recurring agent runs ("run the standup-summary crew weekdays at 9:00").
+//!
+//! Schedules are cron expressions in local time, persisted to the app data
+//! dir. A background thread checks them every few seconds; occurrences missed
+//! while the machine slept or the app was closed are handled according to
+//! each schedule's catch-up policy. A clock set back behind the last check
+//! rewinds it, so the occurrences in between still fire (see
+//! [`crate::clock`]). Results are reported as native notifications and
+//! `schedule-run` events.
+
+use crate::backend::RunTarget;
+use crate::cron::CronExpr;
+use crate::notifications::{self, Event};
+use crate::task_journal::{self, TaskKind};
+use crate::{i18n, power, store};
+use chrono::{DateTime, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Persisted schedules (app data dir).
+const SCHEDULES_FILE: &str = "schedules.json";
+
+/// How often the scheduler thread wakes up.
+const TICK_INTERVAL: Duration = Duration::from_secs(20);
+
+/// An occurrence older than this when noticed counts as missed, not late.
+const GRACE_SECONDS: i64 = 120;
+
+/// Upper bound when counting missed occurrences after a long sleep.
+const MAX_MISSED_SCAN: usize = 1000;
+
+/// What to do with occurrences that passed while the app wasn't running or the machine slept.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CatchUpPolicy {
+    /// Drop missed occurrences and wait for the next one.
+    #[default]
+    Skip,
+    /// Run once to catch up, however many occurrences were missed.
+    RunOnce,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRecord {
+    /// Unix seconds.
+    pub at: i64,
+    pub ok: bool,
+    pub message: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    pub id: String,
+    pub name: String,
+    pub cron: String,
+    pub target: RunTarget,
+    pub enabled: bool,
+    #[serde(default)]
+    pub catch_up: CatchUpPolicy,
+    /// Unix seconds up to which occurrences have been handled.
+    #[serde(default)]
+    pub last_checked: Option<i64>,
+    #[serde(default)]
+    pub last_run: Option<RunRecord>,
+}
+
+/// Fields the frontend may set when creating or updating a schedule.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleInput {
+    pub name: String,
+    pub cron: String,
+    pub target: RunTarget,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub catch_up: CatchUpPolicy,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleView {
+    #[serde(flatten)]
+    schedule: Schedule,
+    /// RFC 3339 local time of the next occurrence, if any.
+    next_run: Option<String>,
+}
+
+/// App state: all schedules.
+pub struct SchedulerState {
+    schedules: Mutex<Vec<Schedule>>,
+}
+
+/// Load schedules, register state, and start the scheduler thread.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let schedules: Vec<Schedule> = store::load_json(app, SCHEDULES_FILE);
+    app.manage(SchedulerState {
+        schedules: Mutex::new(schedules),
+    });
+
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        // Runs due while the system falls asleep are caught up after wake.
+        if !power::is_suspended() {
+            tick(&app);
+        }
+        std::thread::sleep(TICK_INTERVAL);
+    });
+}
+
+fn tick<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<SchedulerState>();
+    let now = Local::now();
+    let mut to_run = Vec::new();
+    {
+        let Ok(mut schedules) = state.schedules.lock() else {
+            return;
+        };
+        for schedule in schedules.iter_mut().filter(|s| s.enabled) {
+            let Ok(expr) = CronExpr::parse(&schedule.cron) else {
+                continue;
+            };
+            // Checked "in the future" means the clock was set back since.
+            let since = schedule
+                .last_checked
+                .and_then(|ts| Local.timestamp_opt(ts, 0).single())
+                .filter(|since| *since <= now)
+                .unwrap_or(now);
+            if fires(&expr, since, now, schedule.catch_up) {
+                to_run.push(schedule.clone());
+            }
+            schedule.last_checked = Some(now.timestamp());
+        }
+        // Only persist when something fires: if nothing was due, replaying the
+        // same window after a restart finds nothing either.
+        if !to_run.is_empty() {
+            if let Err(e) = store::save_json(app, SCHEDULES_FILE, &*schedules) {
+                eprintln!("[CrewHub] {}", e);
+            }
+        }
+    }
+
+    for schedule in to_run {
+        let app = app.clone();
+        std::thread::spawn(move || execute(&app, schedule));
+    }
+}
+
+/// Whether a schedule should run for the window `(since, now]`.
+fn fires(
+    expr: &CronExpr,
+    since: DateTime<Local>,
+    now: DateTime<Local>,
+    policy: CatchUpPolicy,
+) -> bool {
+    let mut missed = false;
+    let mut next = expr.next_after(since);
+    for _ in 0..MAX_MISSED_SCAN {
+        match next {
+            Some(at) if at <= now => {
+                if (now - at).num_seconds() <= GRACE_SECONDS {
+                    return true;
+                }
+                missed = true;
+                next = expr.next_after(at);
+            }
+            _ => break,
+        }
+    }
+    missed && policy == CatchUpPolicy::RunOnce
+}
+
+fn execute<R: Runtime>(app: &AppHandle<R>, schedule: Schedule) {
+    let running = task_journal::begin(
+        app,
+        TaskKind::ScheduledRun {
+            schedule_id: schedule.id.clone(),
+            name: schedule.name.clone(),
+        },
+    );
+    let result = schedule.target.run();
+    drop(running);
+    let record = RunRecord {
+        at: Local::now().timestamp(),
+        ok: result.is_ok(),
+        message: match &result {
+            Ok(_) => "Completed".to_string(),
+            Err(e) => e.clone(),
+        },
+    };
+
+    let state = app.state::<SchedulerState>();
+    if let Ok(mut schedules) = state.schedules.lock() {
+        if let Some(s) = schedules.iter_mut().find(|s| s.id == schedule.id) {
+            s.last_run = Some(record.clone());
+        }
+        if let Err(e) = store::save_json(app, SCHEDULES_FILE, &*schedules) {
+            eprintln!("[CrewHub] {}", e);
+        }
+    }
+
+    let (event, title, body) = if record.ok {
+        (
+            Event::ScheduleCompleted,
+            i18n::t("notify.scheduleCompleted"),
+            i18n::tf("notify.scheduleFinished", &[("name", &schedule.name)]),
+        )
+    } else {
+        (
+            Event::ScheduleFailed,
+            i18n::t("notify.scheduleFailed"),
+            i18n::tf(
+                "notify.runError",
+                &[("name", &schedule.name), ("error", &record.message)],
+            ),
+        )
+    };
+    notifications::notify_in(app, event, &schedule.name, title, body);
+    let _ = app.emit(
+        "schedule-run",
+        serde_json::json!({ "id": schedule.id, "name": schedule.name, "run": record }),
+    );
+}
+
+/// Run a schedule again whose run was interrupted by a restart (see [`crate::task_journal`]).
+pub fn resume<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<(), String> {
+    let schedule = app
+        .state::<SchedulerState>()
+        .schedules
+        .lock()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .find(|s| s.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Schedule not found: {}", id))?;
+    if !schedule.enabled {
+        return Err(format!("Schedule '{}' is disabled", schedule.name));
+    }
+    let app = app.clone();
+    std::thread::spawn(move || execute(&app, schedule));
+    Ok(())
+}
+
+/// Rewind schedules checked past the current time after the clock was set back.
+pub fn rewind<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<SchedulerState>() else {
+        return;
+    };
+    let Ok(mut schedules) = state.schedules.lock() else {
+        return;
+    };
+    let now = Local::now().timestamp();
+    let mut rewound = false;
+    for schedule in schedules.iter_mut() {
+        if schedule.last_checked.is_some_and(|checked| checked > now) {
+            schedule.last_checked = Some(now);
+            rewound = true;
+        }
+    }
+    if rewound {
+        if let Err(e) = store::save_json(app, SCHEDULES_FILE, &*schedules) {
+            eprintln!("[CrewHub] {}", e);
+        }
+    }
+}
+
+/// All schedules with their next run time, as of now.
+pub fn views<R: Runtime>(app: &AppHandle<R>) -> Vec<ScheduleView> {
+    app.try_state::<SchedulerState>()
+        .and_then(|state| {
+            state
+                .schedules
+                .lock()
+                .ok()
+                .map(|s| s.iter().map(view).collect())
+        })
+        .unwrap_or_default()
+}
+
+fn view(schedule: &Schedule) -> ScheduleView {
+    let next_run = CronExpr::parse(&schedule.cron)
+        .ok()
+        .filter(|_| schedule.enabled)
+        .and_then(|expr| expr.next_after(Local::now()))
+        .map(|t| t.to_rfc3339());
+    ScheduleView {
+        schedule: schedule.clone(),
+        next_run,
+    }
+}
+
+fn save(app: &AppHandle, schedules: &[Schedule]) -> Result<(), String> {
+    store::save_json(app, SCHEDULES_FILE, &schedules)
+}
+
+/// Tauri command: list all schedules with their next run time.
+/// Called from the frontend via `invoke('list_schedules')`.
+#[tauri::command]
+pub fn list_schedules(state: State<SchedulerState>) -> Result<Vec<ScheduleView>, String> {
+    let schedules = state.schedules.lock().map_err(|e| e.to_string())?;
+    Ok(schedules.iter().map(view).collect())
+}
+
+/// Tauri command: create a schedule.
+/// Called from the frontend via `invoke('create_schedule', { input })`.
+#[tauri::command]
+pub fn create_schedule(
+    input: ScheduleInput,
+    app: AppHandle,
+    state: State<SchedulerState>,
+) -> Result<ScheduleView, String> {
+    CronExpr::parse(&input.cron)?;
+    let schedule = Schedule {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: input.name,
+        cron: input.cron,
+        target: input.target,
+        enabled: input.enabled,
+        catch_up: input.catch_up,
+        last_checked: Some(Local::now().timestamp()),
+        last_run: None,
+    };
+    let mut schedules = state.schedules.lock().map_err(|e| e.to_string())?;
+    schedules.push(schedule.clone());
+    save(&app, &schedules)?;
+    Ok(view(&schedule))
+}
+
+/// Tauri command: replace a schedule's definition (run history is kept).
+/// Called from the frontend via `invoke('update_schedule', { id, input })`.
+#[tauri::command]
+pub fn update_schedule(
+    id: String,
+    input: ScheduleInput,
+    app: AppHandle,
+    state: State<SchedulerState>,
+) -> Result<ScheduleView, String> {
+    CronExpr::parse(&input.cron)?;
+    let mut schedules = state.schedules.lock().map_err(|e| e.to_string())?;
+    let schedule = schedules
+        .iter_mut()
+        .find(|s| s.id == id)
+        .ok_or_else(|| format!("Schedule not found: {}", id))?;
+    schedule.name = input.name;
+    schedule.cron = input.cron;
+    schedule.target = input.target;
+    schedule.enabled = input.enabled;
+    schedule.catch_up = input.catch_up;
+    // Don't retroactively fire occurrences of the new expression.
+    schedule.last_checked = Some(Local::now().timestamp());
+    let updated = view(schedule);
+    save(&app, &schedules)?;
+    Ok(updated)
+}
+
+/// Tauri command: delete a schedule.
+/// Called from the frontend via `invoke('delete_schedule', { id })`.
+#[tauri::command]
+pub fn delete_schedule(
+    id: String,
+    app: AppHandle,
+    state: State<SchedulerState>,
+) -> Result<(), String> {
+    let mut schedules = state.schedules.lock().map_err(|e| e.to_string())?;
+    schedules.retain(|s| s.id != id);
+    save(&app, &schedules)
+}
+
+/// Tauri command: run a schedule immediately, outside its cron timing.
+/// Called from the frontend via `invoke('run_schedule_now', { id })`.
+#[tauri::command]
+pub fn run_schedule_now(
+    id: String,
+    app: AppHandle,
+    state: State<SchedulerState>,
+) -> Result<(), String> {
+    let schedule = state
+        .schedules
+        .lock()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .find(|s| s.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Schedule not found: {}", id))?;
+    std::thread::spawn(move || execute(&app, schedule));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(h: u32, mi: u32, s: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 1, 14, h, mi, s).unwrap()
+    }
+
+    #[test]
+    fn fires_on_time() {
+        let expr = CronExpr::parse("*/5 * * * *").unwrap();
+        assert!(fires(
+            &expr,
+            local(10, 0, 0),
+            local(10, 5, 30),
+            CatchUpPolicy::Skip
+        ));
+    }
+
+    #[test]
+    fn nothing_due() {
+        let expr = CronExpr::parse("0 * * * *").unwrap();
+        for policy in [CatchUpPolicy::Skip, CatchUpPolicy::RunOnce] {
+            assert!(!fires(&expr, local(10, 0, 0), local(10, 59, 0), policy));
+        }
+    }
+
+    #[test]
+    fn missed_runs_follow_the_policy() {
+        // 09:00 and 10:00 passed while asleep; 10:00 is past the grace period.
+        let expr = CronExpr::parse("0 * * * *").unwrap();
+        let (since, now) = (local(8, 30, 0), local(10, 30, 0));
+        assert!(!fires(&expr, since, now, CatchUpPolicy::Skip));
+        assert!(fires(&expr, since, now, CatchUpPolicy::RunOnce));
+    }
+
+    #[test]
+    fn late_run_within_grace_still_fires() {
+        // The 10:00 run is noticed 90 s late after missing 09:00.
+        let expr = CronExpr::parse("0 * * * *").unwrap();
+        assert!(fires(
+            &expr,
+            local(8, 30, 0),
+            local(10, 1, 30),
+            CatchUpPolicy::Skip
+        ));
+    }
+}
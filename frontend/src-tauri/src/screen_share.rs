@@ -0,0 +1,116 @@
+//! Whether the screen is probably being shared or recorded.
+//!
+//! No platform reports this to other apps, so it is inferred from running
+//! processes that only exist while a share or recording is live: Zoom's
+//! share host, macOS Screen Sharing while someone is connected, OBS. The
+//! user can add more process names (see
+//! [`crate::notifications::NotificationConfig`]). Checked on demand and
+//! cached briefly, as listing processes isn't free.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Processes that indicate a live share or recording (lowercase, without `.exe`).
+const SHARING_PROCESSES: &[&str] = &["cpthost", "screensharingd", "obs", "obs64", "obs32"];
+
+/// How long a check is reused.
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+static CACHE: Mutex<Option<(Instant, Vec<String>)>> = Mutex::new(None);
+
+fn normalize(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    name.strip_suffix(".exe")
+        .map(str::to_string)
+        .unwrap_or(name)
+}
+
+/// Names of running processes (normalized), cached for [`CACHE_TTL`].
+fn processes() -> Vec<String> {
+    if let Ok(cache) = CACHE.lock() {
+        if let Some((_, names)) = cache.as_ref().filter(|(at, _)| at.elapsed() < CACHE_TTL) {
+            return names.clone();
+        }
+    }
+    let names: Vec<String> = platform::process_names()
+        .iter()
+        .map(|name| normalize(name))
+        .collect();
+    if let Ok(mut cache) = CACHE.lock() {
+        *cache = Some((Instant::now(), names.clone()));
+    }
+    names
+}
+
+/// Whether a known sharing process, or one of `extra`, is running.
+pub fn is_sharing(extra: &[String]) -> bool {
+    let extra: Vec<String> = extra.iter().map(|name| normalize(name)).collect();
+    processes()
+        .iter()
+        .any(|name| SHARING_PROCESSES.contains(&name.as_str()) || extra.iter().any(|e| e == name))
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    pub fn process_names() -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .bytes()
+                    .all(|b| b.is_ascii_digit())
+            })
+            .filter_map(|entry| std::fs::read_to_string(entry.path().join("comm")).ok())
+            .collect()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    pub fn process_names() -> Vec<String> {
+        // `-c`: executable names only, not full command lines.
+        std::process::Command::new("ps")
+            .args(["-Ac", "-o", "comm="])
+            .output()
+            .map(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::os::windows::process::CommandExt;
+
+    pub fn process_names() -> Vec<String> {
+        // CSV rows: "image name","pid",…
+        std::process::Command::new("tasklist")
+            .args(["/fo", "csv", "/nh"])
+            .creation_flags(0x0800_0000) // CREATE_NO_WINDOW
+            .output()
+            .map(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .filter_map(|line| line.split(',').next())
+                    .map(|name| name.trim_matches('"').to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+mod platform {
+    pub fn process_names() -> Vec<String> {
+        Vec::new()
+    }
+}
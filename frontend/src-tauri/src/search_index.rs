@@ -0,0 +1,475 @@
+//! Conversations in the OS search (Spotlight, Windows Search).
+//!
+//! Pinned, recent and archived conversations (see [`crate::archive`]) are
+//! published with their title and, when allowed, a snippet of the latest
+//! message:
+//! - macOS: Core Spotlight items; opening one delivers an `NSUserActivity`,
+//!   picked up by the app delegate method installed in [`init`];
+//! - Windows: shortcuts in a Start Menu folder, which the Windows Search
+//!   indexer lists by title; they start CrewHub with `--action` arguments;
+//! - Linux desktops have no common search index, so nothing is published.
+//!
+//! Either way the activation is handled by [`crate::launch`]. The list is
+//! republished when pins or recents change; turning the integration off
+//! removes everything that was published.
+
+use crate::pins::{self, ConversationRef};
+use crate::{archive, backend, federation, launch, recents, store};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime, State};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "search-index.json";
+
+/// Longest snippet published per conversation (characters).
+const MAX_SNIPPET: usize = 200;
+
+/// Rebuilds run on their own threads; one at a time.
+static PUBLISHING: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SearchIndexConfig {
+    /// Publish conversations to the OS search index at all.
+    pub enabled: bool,
+    /// Include a snippet of the latest message (otherwise titles only).
+    pub snippets: bool,
+}
+
+impl Default for SearchIndexConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            snippets: true,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchIndexStatus {
+    /// This platform has a search index CrewHub can publish to.
+    pub supported: bool,
+    /// Conversations published by the last update.
+    pub published: usize,
+    pub config: SearchIndexConfig,
+}
+
+/// App state: config and the size of the last published list.
+pub struct SearchIndexState {
+    config: Mutex<SearchIndexConfig>,
+    published: Mutex<usize>,
+}
+
+#[cfg_attr(
+    all(not(target_os = "macos"), not(target_os = "windows")),
+    allow(dead_code)
+)]
+struct Entry {
+    conversation: ConversationRef,
+    snippet: Option<String>,
+}
+
+/// Launch arguments that open `conversation` (see [`crate::launch`]).
+#[cfg_attr(
+    all(not(target_os = "macos"), not(target_os = "windows")),
+    allow(dead_code)
+)]
+fn launch_args(conversation: &ConversationRef) -> Vec<String> {
+    launch::action_args(
+        "open-conversation",
+        &[
+            ("sessionKey", &conversation.session_key),
+            ("backendId", &conversation.backend_id),
+            ("title", &conversation.title),
+        ],
+    )
+}
+
+/// Register state, hook up activation, and publish the current list.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(SearchIndexState {
+        config: Mutex::new(store::load_json(app, CONFIG_FILE)),
+        published: Mutex::new(0),
+    });
+    #[cfg(target_os = "macos")]
+    spotlight::install_activation(app);
+    refresh(app);
+}
+
+fn config<R: Runtime>(app: &AppHandle<R>) -> SearchIndexConfig {
+    app.try_state::<SearchIndexState>()
+        .and_then(|state| state.config.lock().ok().map(|c| c.clone()))
+        .unwrap_or_default()
+}
+
+/// Pinned conversations first, then recents and archived ones not already listed.
+fn conversations<R: Runtime>(app: &AppHandle<R>) -> Vec<ConversationRef> {
+    let mut list: Vec<ConversationRef> = pins::pinned(app)
+        .into_iter()
+        .map(|pin| pin.conversation)
+        .collect();
+    let recent = recents::recent(app).into_iter().map(|r| r.conversation);
+    let archived = archive::list(app).into_iter().map(|a| a.conversation);
+    for conversation in recent.chain(archived) {
+        if !list.iter().any(|c| {
+            c.session_key == conversation.session_key && c.backend_id == conversation.backend_id
+        }) {
+            list.push(conversation);
+        }
+    }
+    list
+}
+
+/// Latest message of a conversation, shortened to [`MAX_SNIPPET`].
+fn snippet(base: &str, session_key: &str) -> Option<String> {
+    let path = format!(
+        "/api/chat/{}/history?limit=1",
+        backend::urlencode(session_key)
+    );
+    let reply = backend::get_json_from(base, &path).ok()?;
+    let content = reply
+        .get("messages")?
+        .as_array()?
+        .last()?
+        .get("content")
+        .and_then(Value::as_str)?;
+    let text = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(MAX_SNIPPET) {
+        Some((end, _)) => Some(format!("{}…", &text[..end])),
+        None if text.is_empty() => None,
+        None => Some(text),
+    }
+}
+
+/// Republish the conversation list (or clear it when disabled).
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let config = config(app);
+    let conversations = if config.enabled && SUPPORTED {
+        conversations(app)
+    } else {
+        Vec::new()
+    };
+    let backends = federation::enabled(app);
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let _one_at_a_time = PUBLISHING.lock();
+        let entries: Vec<Entry> = conversations
+            .into_iter()
+            .map(|conversation| {
+                let snippet = config
+                    .snippets
+                    .then(|| backends.iter().find(|b| b.id == conversation.backend_id))
+                    .flatten()
+                    .and_then(|backend| snippet(&backend.url, &conversation.session_key))
+                    .or_else(|| {
+                        config
+                            .snippets
+                            .then(|| archive::snippet(&app, &conversation))
+                            .flatten()
+                    });
+                Entry {
+                    conversation,
+                    snippet,
+                }
+            })
+            .collect();
+        match publish(&entries) {
+            Ok(()) => {
+                if let Some(state) = app.try_state::<SearchIndexState>() {
+                    if let Ok(mut published) = state.published.lock() {
+                        *published = entries.len();
+                    }
+                }
+            }
+            Err(e) => eprintln!("[CrewHub] Failed to update the search index: {}", e),
+        }
+    });
+}
+
+fn status<R: Runtime>(app: &AppHandle<R>) -> SearchIndexStatus {
+    SearchIndexStatus {
+        supported: SUPPORTED,
+        published: app
+            .try_state::<SearchIndexState>()
+            .and_then(|state| state.published.lock().ok().map(|p| *p))
+            .unwrap_or(0),
+        config: config(app),
+    }
+}
+
+#[cfg(target_os = "macos")]
+const SUPPORTED: bool = true;
+
+#[cfg(target_os = "macos")]
+fn publish(entries: &[Entry]) -> Result<(), String> {
+    spotlight::publish(entries);
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+mod spotlight {
+    use super::Entry;
+    use crate::launch;
+    use crate::pins::ConversationRef;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject, Bool, Imp, Sel};
+    use objc2::{sel, AllocAnyThread, MainThreadMarker};
+    use objc2_app_kit::NSApplication;
+    use objc2_core_spotlight::{
+        CSSearchableIndex, CSSearchableItem, CSSearchableItemActionType,
+        CSSearchableItemActivityIdentifier, CSSearchableItemAttributeSet,
+    };
+    use objc2_foundation::{NSArray, NSString, NSUserActivity};
+    use std::ffi::c_void;
+    use std::sync::OnceLock;
+    use tauri::{AppHandle, Runtime};
+
+    /// Spotlight domain of all CrewHub items; cleared before each publish.
+    const DOMAIN: &str = "dev.crewhub.conversations";
+
+    type ContinueUserActivity =
+        extern "C-unwind" fn(&AnyObject, Sel, &AnyObject, &NSUserActivity, *mut c_void) -> Bool;
+
+    /// Opens a conversation from its Spotlight identifier; set once in [`install_activation`].
+    type Handler = Box<dyn Fn(&str) + Send + Sync>;
+    static HANDLER: OnceLock<Handler> = OnceLock::new();
+
+    /// The delegate's own implementation (Tauri handles web links there).
+    static ORIGINAL: OnceLock<Imp> = OnceLock::new();
+
+    /// `-[NSApplicationDelegate application:continueUserActivity:restorationHandler:]`:
+    /// Spotlight items are handled here, everything else goes to the original.
+    extern "C-unwind" fn continue_user_activity(
+        this: &AnyObject,
+        cmd: Sel,
+        application: &AnyObject,
+        activity: &NSUserActivity,
+        restoration_handler: *mut c_void,
+    ) -> Bool {
+        if activity
+            .activityType()
+            .isEqualToString(unsafe { CSSearchableItemActionType })
+        {
+            let identifier = activity
+                .userInfo()
+                .and_then(|info| info.objectForKey(unsafe { CSSearchableItemActivityIdentifier }))
+                .and_then(|value| value.downcast::<NSString>().ok());
+            if let (Some(identifier), Some(handler)) = (identifier, HANDLER.get()) {
+                handler(&identifier.to_string());
+                return Bool::YES;
+            }
+            return Bool::NO;
+        }
+        match ORIGINAL.get() {
+            Some(original) => {
+                let original: ContinueUserActivity = unsafe { std::mem::transmute(*original) };
+                original(this, cmd, application, activity, restoration_handler)
+            }
+            None => Bool::NO,
+        }
+    }
+
+    /// Route Spotlight activations to [`launch::handle`]. Runs on the main thread (setup).
+    pub fn install_activation<R: Runtime>(app: &AppHandle<R>) {
+        let Some(mtm) = MainThreadMarker::new() else {
+            return;
+        };
+        let handle = app.clone();
+        let _ = HANDLER.set(Box::new(move |identifier| {
+            match serde_json::from_str::<ConversationRef>(identifier) {
+                Ok(conversation) => {
+                    let args = super::launch_args(&conversation);
+                    launch::handle(&handle, &args, std::path::Path::new(""));
+                }
+                Err(e) => eprintln!("[CrewHub] Unknown Spotlight item: {}", e),
+            }
+        }));
+
+        let Some(delegate) = NSApplication::sharedApplication(mtm).delegate() else {
+            eprintln!("[CrewHub] No app delegate; Spotlight results won't open conversations");
+            return;
+        };
+        let class: *const AnyClass = AsRef::<AnyObject>::as_ref(&*delegate).class();
+        let selector = sel!(application:continueUserActivity:restorationHandler:);
+        let imp: Imp =
+            unsafe { std::mem::transmute::<ContinueUserActivity, Imp>(continue_user_activity) };
+        unsafe {
+            let method = objc2::ffi::class_getInstanceMethod(class, selector);
+            if method.is_null() {
+                objc2::ffi::class_addMethod(class.cast_mut(), selector, imp, c"c@:@@@?".as_ptr());
+            } else if let Some(original) =
+                objc2::ffi::method_setImplementation(method.cast_mut(), imp)
+            {
+                let _ = ORIGINAL.set(original);
+            }
+        }
+    }
+
+    /// Replace CrewHub's Spotlight items with `entries`.
+    pub fn publish(entries: &[Entry]) {
+        let domain = NSString::from_str(DOMAIN);
+        let items: Vec<Retained<CSSearchableItem>> = entries
+            .iter()
+            .map(|entry| unsafe {
+                // `initWithContentType:` needs UTType; the string form is equivalent.
+                #[allow(deprecated)]
+                let attributes = CSSearchableItemAttributeSet::initWithItemContentType(
+                    CSSearchableItemAttributeSet::alloc(),
+                    &NSString::from_str("public.text"),
+                );
+                attributes.setTitle(Some(&NSString::from_str(&entry.conversation.title)));
+                attributes.setDisplayName(Some(&NSString::from_str(&entry.conversation.title)));
+                if let Some(snippet) = &entry.snippet {
+                    attributes.setContentDescription(Some(&NSString::from_str(snippet)));
+                }
+                let identifier = serde_json::to_string(&entry.conversation).unwrap_or_default();
+                CSSearchableItem::initWithUniqueIdentifier_domainIdentifier_attributeSet(
+                    CSSearchableItem::alloc(),
+                    Some(&NSString::from_str(&identifier)),
+                    Some(&domain),
+                    &attributes,
+                )
+            })
+            .collect();
+        unsafe {
+            let index = CSSearchableIndex::defaultSearchableIndex();
+            index.deleteSearchableItemsWithDomainIdentifiers_completionHandler(
+                &NSArray::from_retained_slice(std::slice::from_ref(&domain)),
+                None,
+            );
+            if !items.is_empty() {
+                index.indexSearchableItems_completionHandler(
+                    &NSArray::from_retained_slice(&items),
+                    None,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+const SUPPORTED: bool = true;
+
+/// Start Menu folder holding one shortcut per conversation.
+#[cfg(target_os = "windows")]
+fn start_menu_folder() -> Result<std::path::PathBuf, String> {
+    std::env::var_os("APPDATA")
+        .map(|dir| {
+            std::path::PathBuf::from(dir)
+                .join(r"Microsoft\Windows\Start Menu\Programs\CrewHub Conversations")
+        })
+        .ok_or_else(|| "APPDATA is not set".to_string())
+}
+
+/// File name for a shortcut: the title without characters Windows forbids.
+#[cfg(target_os = "windows")]
+fn shortcut_name(title: &str, taken: &[String]) -> String {
+    let clean: String = title
+        .chars()
+        .map(|c| {
+            if r#"<>:"/\|?*"#.contains(c) || c.is_control() {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    let clean = clean.trim().trim_end_matches('.').to_string();
+    let base = if clean.is_empty() {
+        "Conversation".to_string()
+    } else {
+        clean
+    };
+    let mut name = base.clone();
+    let mut n = 2;
+    while taken.iter().any(|t| t.eq_ignore_ascii_case(&name)) {
+        name = format!("{} ({})", base, n);
+        n += 1;
+    }
+    name
+}
+
+#[cfg(target_os = "windows")]
+fn publish(entries: &[Entry]) -> Result<(), String> {
+    use windows::core::{Interface, HSTRING};
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, IPersistFile, CLSCTX_INPROC_SERVER,
+        COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
+
+    let folder = start_menu_folder()?;
+    if folder.exists() {
+        std::fs::remove_dir_all(&folder).map_err(|e| e.to_string())?;
+    }
+    if entries.is_empty() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(&folder).map_err(|e| e.to_string())?;
+    let exe = HSTRING::from(std::env::current_exe().unwrap_or_default().as_os_str());
+    let mut taken = Vec::new();
+    unsafe {
+        // Fails harmlessly if COM is already initialised on this thread.
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        for entry in entries {
+            let name = shortcut_name(&entry.conversation.title, &taken);
+            let args: Vec<String> = launch_args(&entry.conversation)
+                .iter()
+                .map(|arg| crate::jump_list::quote(arg))
+                .collect();
+            let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
+                .map_err(|e| e.to_string())?;
+            link.SetPath(&exe).map_err(|e| e.to_string())?;
+            link.SetArguments(&HSTRING::from(args.join(" ")))
+                .map_err(|e| e.to_string())?;
+            link.SetIconLocation(&exe, 0).map_err(|e| e.to_string())?;
+            // Shown as the tooltip and matched by Windows Search.
+            if let Some(snippet) = &entry.snippet {
+                link.SetDescription(&HSTRING::from(snippet.as_str()))
+                    .map_err(|e| e.to_string())?;
+            }
+            let file: IPersistFile = link.cast().map_err(|e| e.to_string())?;
+            let path = folder.join(format!("{}.lnk", name));
+            file.Save(&HSTRING::from(path.as_os_str()), true)
+                .map_err(|e| e.to_string())?;
+            taken.push(name);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+const SUPPORTED: bool = false;
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+fn publish(_entries: &[Entry]) -> Result<(), String> {
+    Ok(())
+}
+
+/// Tauri command: OS search integration status and config.
+/// Called from the settings window via `invoke('get_search_index_status')`.
+#[tauri::command]
+pub fn get_search_index_status(app: AppHandle) -> SearchIndexStatus {
+    status(&app)
+}
+
+/// Tauri command: replace the OS search integration config and republish
+/// (with `enabled: false`, everything published so far is removed).
+/// Called from the settings window via `invoke('configure_search_index', { config })`.
+#[tauri::command]
+pub fn configure_search_index(
+    config: SearchIndexConfig,
+    app: AppHandle,
+    state: State<SearchIndexState>,
+) -> Result<SearchIndexStatus, String> {
+    {
+        let mut current = state.config.lock().map_err(|e| e.to_string())?;
+        *current = config;
+        store::save_json(&app, CONFIG_FILE, &*current)?;
+    }
+    refresh(&app);
+    Ok(status(&app))
+}
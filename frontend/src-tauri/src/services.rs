@@ -0,0 +1,74 @@
+//! macOS Services menu provider.
+//!
+//! `Info.plist` declares an `NSServices` entry, "CrewHub: Ask about
+//! selection", offered for selected text in any app. AppKit calls
+//! `askAboutSelection:userData:error:` on the provider object registered
+//! here; the text goes to the quick-prompt window like `--share-text` from a
+//! second launch (see [`crate::launch`]).
+
+use crate::{launch, share};
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, NSObject};
+use objc2::{define_class, msg_send, MainThreadMarker, MainThreadOnly};
+use objc2_app_kit::{NSApplication, NSPasteboard, NSPasteboardTypeString, NSUpdateDynamicServices};
+use objc2_foundation::NSString;
+use std::cell::RefCell;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Runtime};
+
+/// Hands selected text to the launch router; set once in [`init`].
+type Handler = Box<dyn Fn(String) + Send + Sync>;
+static HANDLER: OnceLock<Handler> = OnceLock::new();
+
+thread_local! {
+    // NSApplication doesn't retain its services provider.
+    static PROVIDER: RefCell<Option<Retained<ServicesProvider>>> = const { RefCell::new(None) };
+}
+
+define_class!(
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "CrewHubServicesProvider"]
+    struct ServicesProvider;
+
+    impl ServicesProvider {
+        /// `NSMessage` of the service in `Info.plist`.
+        #[unsafe(method(askAboutSelection:userData:error:))]
+        fn ask_about_selection(
+            &self,
+            pasteboard: &NSPasteboard,
+            _user_data: Option<&NSString>,
+            _error: *mut *mut NSString,
+        ) {
+            let text = pasteboard
+                .stringForType(unsafe { NSPasteboardTypeString })
+                .map(|text| text.to_string())
+                .filter(|text| !text.trim().is_empty());
+            if let (Some(text), Some(handler)) = (text, HANDLER.get()) {
+                handler(text);
+            }
+        }
+    }
+);
+
+/// Register the services provider. Must run on the main thread (setup).
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        eprintln!("[CrewHub] Services provider must be installed on the main thread");
+        return;
+    };
+    let handle = app.clone();
+    let _ = HANDLER.set(Box::new(move |text| {
+        let args = [share::SHARE_TEXT_FLAG.to_string(), text];
+        launch::handle(&handle, &args, std::path::Path::new(""));
+    }));
+    let provider: Retained<ServicesProvider> =
+        unsafe { msg_send![ServicesProvider::alloc(mtm), init] };
+    unsafe {
+        NSApplication::sharedApplication(mtm)
+            .setServicesProvider(Some(AsRef::<AnyObject>::as_ref(&*provider)));
+    }
+    PROVIDER.with(|slot| *slot.borrow_mut() = Some(provider));
+    // Picks up the Info.plist entry after an update without logging out.
+    NSUpdateDynamicServices();
+}
@@ -0,0 +1,258 @@
+//! Session lock/unlock events.
+//!
+//! Watches for the user locking the screen (ScreenSaver `ActiveChanged` on
+//! Linux, `SystemEvents.SessionSwitch` on Windows, the console lock flag on
+//! macOS) and reacts from one place:
+//! - native notifications are held back while locked, so their previews never
+//!   appear on the lock screen, and delivered on unlock (see [`hold`]);
+//! - every connected backend is told the user is away / back;
+//! - optionally, all CrewHub windows are hidden (`app-locked` event) so the
+//!   frontend can require unlocking the app again.
+//!
+//! The frontend gets `session-locked` / `session-unlocked` events.
+
+use crate::notifications::{self, Event, Notice};
+use crate::{backend, federation, i18n, power, store};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "session.json";
+
+/// Held notifications beyond this many are delivered as one summary.
+const MAX_REPLAYED: usize = 3;
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SessionConfig {
+    /// Hold notifications while the screen is locked.
+    pub hold_notifications: bool,
+    /// Report away/back to the backends.
+    pub report_away: bool,
+    /// Hide all windows when the screen locks.
+    pub lock_app: bool,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            hold_notifications: true,
+            report_away: true,
+            lock_app: false,
+        }
+    }
+}
+
+/// App state: config and notifications held while locked.
+pub struct SessionState {
+    config: Mutex<SessionConfig>,
+    held: Mutex<Vec<Notice>>,
+}
+
+/// Register state and start watching for lock/unlock.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(SessionState {
+        config: Mutex::new(store::load_json(app, CONFIG_FILE)),
+        held: Mutex::new(Vec::new()),
+    });
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = watch(&app) {
+            eprintln!("[CrewHub] Session lock events unavailable: {}", e);
+        }
+    });
+}
+
+/// Whether the screen is currently locked.
+pub fn is_locked() -> bool {
+    LOCKED.load(Ordering::Relaxed)
+}
+
+fn config<R: Runtime>(app: &AppHandle<R>) -> SessionConfig {
+    app.try_state::<SessionState>()
+        .and_then(|state| state.config.lock().ok().map(|c| c.clone()))
+        .unwrap_or_default()
+}
+
+/// Hold a notification until unlock if the screen is locked. Returns whether it was held.
+pub fn hold<R: Runtime>(app: &AppHandle<R>, notice: &Notice) -> bool {
+    if !is_locked() || !config(app).hold_notifications {
+        return false;
+    }
+    app.try_state::<SessionState>()
+        .and_then(|state| {
+            state
+                .held
+                .lock()
+                .ok()
+                .map(|mut held| held.push(notice.clone()))
+        })
+        .is_some()
+}
+
+/// Tell every backend whether the user is at the machine.
+fn report_presence<R: Runtime>(app: &AppHandle<R>, status: &'static str) {
+    let backends = federation::enabled(app);
+    std::thread::spawn(move || {
+        for backend in backends {
+            let body = json!({ "status": status });
+            if let Err(e) = backend::post_json_to(&backend.url, "/api/presence/user", &body) {
+                eprintln!(
+                    "[CrewHub] Failed to report {} to {}: {}",
+                    status, backend.name, e
+                );
+            }
+        }
+    });
+}
+
+fn on_lock<R: Runtime>(app: &AppHandle<R>) {
+    if LOCKED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    let config = config(app);
+    if config.report_away {
+        report_presence(app, "away");
+    }
+    if config.lock_app {
+        for window in app.webview_windows().values() {
+            let _ = window.hide();
+        }
+        let _ = app.emit("app-locked", ());
+    }
+    let _ = app.emit("session-locked", ());
+}
+
+fn on_unlock<R: Runtime>(app: &AppHandle<R>) {
+    if !LOCKED.swap(false, Ordering::Relaxed) {
+        return;
+    }
+    if config(app).report_away {
+        report_presence(app, "active");
+    }
+    let held: Vec<Notice> = app
+        .try_state::<SessionState>()
+        .and_then(|state| {
+            state
+                .held
+                .lock()
+                .ok()
+                .map(|mut held| held.drain(..).collect())
+        })
+        .unwrap_or_default();
+    if held.len() > MAX_REPLAYED {
+        let summary = Notice {
+            title: i18n::t("notify.whileAwayTitle"),
+            body: i18n::tf(
+                "notify.whileAwayBody",
+                &[("count", &held.len().to_string())],
+            ),
+            group: None,
+            event: Event::Message,
+            critical: held.iter().any(|notice| notice.critical),
+            action: None,
+            context: Default::default(),
+        };
+        notifications::show(app, &summary);
+    } else {
+        for notice in &held {
+            notifications::show(app, notice);
+        }
+    }
+    let _ = app.emit("session-unlocked", ());
+}
+
+/// The freedesktop / GNOME screensaver reports `ActiveChanged(true)` on lock.
+#[cfg(target_os = "linux")]
+fn watch<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let mut in_signal = false;
+    power::follow(
+        "dbus-monitor",
+        &[
+            "--session",
+            "type='signal',member='ActiveChanged',interface='org.gnome.ScreenSaver'",
+            "type='signal',member='ActiveChanged',interface='org.freedesktop.ScreenSaver'",
+        ],
+        |line| {
+            let line = line.trim();
+            if line.contains("member=ActiveChanged") {
+                in_signal = true;
+            } else if in_signal && line.starts_with("boolean") {
+                in_signal = false;
+                if line.ends_with("true") {
+                    on_lock(app);
+                } else {
+                    on_unlock(app);
+                }
+            }
+        },
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn watch<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    const SCRIPT: &str = "Register-ObjectEvent -InputObject ([Microsoft.Win32.SystemEvents]) \
+        -EventName SessionSwitch -SourceIdentifier session | Out-Null; \
+        while ($true) { $e = Wait-Event -SourceIdentifier session; \
+        [Console]::Out.WriteLine($e.SourceEventArgs.Reason); [Console]::Out.Flush(); \
+        Remove-Event -EventIdentifier $e.EventIdentifier }";
+    power::follow(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", SCRIPT],
+        |line| match line.trim() {
+            "SessionLock" => on_lock(app),
+            "SessionUnlock" => on_unlock(app),
+            _ => {}
+        },
+    )
+}
+
+/// No lock notifications from the command line on macOS; poll the console session flag.
+#[cfg(target_os = "macos")]
+fn watch<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    loop {
+        std::thread::sleep(power::interval(app, std::time::Duration::from_secs(3)));
+        let output = std::process::Command::new("ioreg")
+            .args(["-n", "Root", "-d1"])
+            .output()
+            .map_err(|e| e.to_string())?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        if text.contains("\"CGSSessionScreenIsLocked\"=Yes") {
+            on_lock(app);
+        } else {
+            on_unlock(app);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn watch<R: Runtime>(_app: &AppHandle<R>) -> Result<(), String> {
+    Ok(())
+}
+
+/// Tauri command: session lock state and config.
+/// Called from the frontend via `invoke('get_session_config')`.
+#[tauri::command]
+pub fn get_session_config(state: State<SessionState>) -> Result<serde_json::Value, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    Ok(json!({ "locked": is_locked(), "config": config }))
+}
+
+/// Tauri command: replace the lock behaviour config.
+/// Called from the settings window via `invoke('configure_session', { config })`.
+#[tauri::command]
+pub fn configure_session(
+    config: SessionConfig,
+    app: AppHandle,
+    state: State<SessionState>,
+) -> Result<SessionConfig, String> {
+    let mut current = state.config.lock().map_err(|e| e.to_string())?;
+    *current = config;
+    store::save_json(&app, CONFIG_FILE, &*current)?;
+    Ok(current.clone())
+}
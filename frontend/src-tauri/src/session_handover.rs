@@ -0,0 +1,163 @@
+//! Session handover between the desktop app and the backend's web UI.
+//!
+//! "Continue in browser" asks the backend for a one-time handover token for
+//! a conversation (`POST /api/auth/handover`) and opens the web UI in the
+//! default browser with it, already signed in and on the same conversation.
+//! The other way round, the web UI opens `crewhub://handover/<token>` (with
+//! `?backend=<id>` for other backends). Any page can open such a link, so a
+//! native dialog naming the backend asks first; only then is the token
+//! redeemed (`POST /api/auth/handover/redeem`) and the conversation opened in
+//! the chat window, whose `deep-link` event carries the redeemed session so
+//! the frontend can adopt it.
+//!
+//! Sessions stay in the frontend (see [`crate::accounts`]): the API key the
+//! token is issued for is passed in by the caller and never stored.
+
+use crate::{backend, external_links, federation, i18n, pins, url_scheme};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+use url::Url;
+
+/// Host of handover links.
+pub const HOST: &str = "handover";
+
+/// Base URL of the backend `backend_id`, falling back to the primary backend.
+fn base_url<R: Runtime>(app: &AppHandle<R>, backend_id: &str) -> String {
+    federation::enabled(app)
+        .into_iter()
+        .find(|p| p.id == backend_id)
+        .map(|p| p.url)
+        .unwrap_or_else(crate::backend_url)
+}
+
+/// Name and URL of the backend `backend_id`, for the confirmation dialog.
+fn describe<R: Runtime>(app: &AppHandle<R>, backend_id: &str) -> String {
+    match federation::enabled(app)
+        .into_iter()
+        .find(|p| p.id == backend_id)
+    {
+        Some(profile) => format!("{} ({})", profile.name, profile.url),
+        None => crate::backend_url(),
+    }
+}
+
+/// Ask before signing the chat window in with a session from a link. Blocks.
+fn confirm<R: Runtime>(app: &AppHandle<R>, backend_id: &str) -> bool {
+    let result = app
+        .dialog()
+        .message(i18n::tf(
+            "dialog.handover",
+            &[("backend", &describe(app, backend_id))],
+        ))
+        .title(i18n::t("dialog.handoverTitle"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            i18n::t("dialog.open"),
+            i18n::t("dialog.cancel"),
+        ))
+        .blocking_show_with_result();
+    match result {
+        MessageDialogResult::Custom(label) => label == i18n::t("dialog.open"),
+        MessageDialogResult::Ok | MessageDialogResult::Yes => true,
+        _ => false,
+    }
+}
+
+/// Web UI URL to open: the one the backend returns, else its own origin.
+fn web_url(base: &str, session_key: &str, reply: &Value) -> Result<Url, String> {
+    let token = reply
+        .get("token")
+        .and_then(Value::as_str)
+        .ok_or("Backend returned no handover token")?;
+    let url = match reply.get("url").and_then(Value::as_str) {
+        Some(url) => Url::parse(url).map_err(|e| format!("Invalid handover URL: {}", e))?,
+        None => {
+            let mut url = Url::parse(&format!(
+                "{}/chat/{}",
+                base.trim_end_matches('/'),
+                backend::urlencode(session_key)
+            ))
+            .map_err(|e| format!("Invalid backend URL: {}", e))?;
+            url.query_pairs_mut().append_pair("handover", token);
+            url
+        }
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("Refusing to open handover URL {}", url));
+    }
+    Ok(url)
+}
+
+/// Handle a `crewhub://handover/<token>` link (redeems off the main thread).
+pub fn handle<R: Runtime>(app: &AppHandle<R>, url: &Url) {
+    let app = app.clone();
+    let url = url.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = redeem(&app, &url) {
+            eprintln!("[CrewHub] Handover failed: {}", e);
+        }
+    });
+}
+
+fn redeem<R: Runtime>(app: &AppHandle<R>, url: &Url) -> Result<(), String> {
+    let token = url
+        .path_segments()
+        .and_then(|mut segments| segments.find(|segment| !segment.is_empty()))
+        .ok_or("Handover link without a token")?;
+    let backend_id = url
+        .query_pairs()
+        .find(|(key, _)| key == "backend")
+        .map(|(_, id)| id.to_string())
+        .unwrap_or_else(pins::default_backend);
+    if !confirm(app, &backend_id) {
+        return Ok(());
+    }
+    let session = backend::post_json_to(
+        &base_url(app, &backend_id),
+        "/api/auth/handover/redeem",
+        &json!({ "token": token, "target": "desktop" }),
+    )?;
+    let session_key = session
+        .get("sessionKey")
+        .and_then(Value::as_str)
+        .ok_or("Backend returned no conversation for the handover")?
+        .to_string();
+    url_scheme::deliver(
+        app,
+        url,
+        crate::CHAT_WINDOW_LABEL,
+        "open-conversation",
+        json!({ "sessionKey": session_key, "backendId": backend_id, "handover": session }),
+    );
+    Ok(())
+}
+
+/// Tauri command: open a conversation in the web UI, signed in as the caller.
+/// Resolves to the URL that was opened.
+/// Called from the chat window via `invoke('continue_in_browser', { sessionKey, backendId, apiKey })`.
+#[tauri::command]
+pub async fn continue_in_browser(
+    session_key: String,
+    backend_id: Option<String>,
+    api_key: Option<String>,
+    app: AppHandle,
+) -> Result<String, String> {
+    let backend_id = backend_id.unwrap_or_else(pins::default_backend);
+    let base = base_url(&app, &backend_id);
+    tauri::async_runtime::spawn_blocking(move || {
+        let reply = backend::post_json_as(
+            &base,
+            "/api/auth/handover",
+            &json!({ "sessionKey": session_key, "target": "web" }),
+            api_key.as_deref(),
+        )?;
+        let url = web_url(&base, &session_key, &reply)?;
+        external_links::open_in_browser(&url)?;
+        Ok(url.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,286 @@
+//! "Share → CrewHub" from other apps.
+//!
+//! Shared content arrives as command-line arguments — `--share <file|url>…`
+//! or `--share-text <text>` — either at startup or from a second launch
+//! (see [`crate::launch`]).
+//! On macOS, files opened with CrewHub (`CFBundleDocumentTypes`) arrive as
+//! `RunEvent::Opened` instead. Files are staged like dropped attachments and
+//! the content is handed to the quick-prompt window: it is kept as pending
+//! (fetched with `take_pending_share` once a freshly opened window loads) and
+//! also sent as a `share-received` event to a window that is already open.
+//!
+//! How the app is registered as a destination depends on the platform:
+//! - Linux: a hidden desktop entry, so CrewHub is offered under "Open With" /
+//!   "Share" in file managers (xdg-desktop-portal has no share-target portal);
+//! - Windows: a "Send to" shortcut (Share Targets need an MSIX package identity,
+//!   which the installer builds don't have);
+//! - macOS: the document types in `Info.plist` put CrewHub under "Open With";
+//!   a Share Extension needs a separate app extension bundle, which this build
+//!   doesn't produce.
+
+use crate::attachments::{self, SkippedFile, StagedFile};
+use crate::quick_prompt;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+const SHARE_FLAG: &str = "--share";
+pub const SHARE_TEXT_FLAG: &str = "--share-text";
+
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedContent {
+    pub text: Option<String>,
+    pub urls: Vec<String>,
+    pub files: Vec<StagedFile>,
+    pub skipped: Vec<SkippedFile>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareTargetStatus {
+    pub registered: bool,
+    /// `desktopEntry`, `sendTo` or `openWith`.
+    pub method: &'static str,
+    pub path: Option<String>,
+}
+
+impl SharedContent {
+    fn merge(&mut self, other: SharedContent) {
+        self.text = match (self.text.take(), other.text) {
+            (Some(a), Some(b)) => Some(format!("{}\n\n{}", a, b)),
+            (a, b) => a.or(b),
+        };
+        self.urls.extend(other.urls);
+        self.files.extend(other.files);
+        self.skipped.extend(other.skipped);
+    }
+}
+
+/// App state: shared content not yet picked up by the quick-prompt window.
+#[derive(Default)]
+pub struct ShareState {
+    pending: Mutex<Option<SharedContent>>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(ShareState::default());
+}
+
+/// Handle share arguments (without the program name). Returns whether they were a share.
+pub fn handle_args<R: Runtime>(app: &AppHandle<R>, args: &[String], cwd: &Path) -> bool {
+    let mut text = None;
+    let mut items = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            SHARE_TEXT_FLAG => text = iter.next().cloned(),
+            SHARE_FLAG => items.extend(iter.by_ref().cloned()),
+            _ => {}
+        }
+    }
+    if text.is_none() && items.is_empty() {
+        return false;
+    }
+
+    let mut urls = Vec::new();
+    let mut paths = Vec::new();
+    for item in items {
+        match url::Url::parse(&item) {
+            Ok(url) if url.scheme() == "file" => paths.extend(url.to_file_path().ok()),
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => urls.push(item),
+            _ => paths.push(cwd.join(item)),
+        }
+    }
+    receive(app, text, urls, paths);
+    true
+}
+
+/// Handle files opened with CrewHub on macOS.
+#[cfg(target_os = "macos")]
+pub fn handle_opened<R: Runtime>(app: &AppHandle<R>, urls: &[url::Url]) {
+    let paths = urls
+        .iter()
+        .filter_map(|url| url.to_file_path().ok())
+        .collect();
+    let links = urls
+        .iter()
+        .filter(|url| url.scheme() != "file")
+        .map(|url| url.to_string())
+        .collect();
+    receive(app, None, links, paths);
+}
+
+/// Stage files (which copies them, so off the main thread) and hand everything to the quick prompt.
+fn receive<R: Runtime>(
+    app: &AppHandle<R>,
+    text: Option<String>,
+    urls: Vec<String>,
+    paths: Vec<PathBuf>,
+) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut content = SharedContent {
+            text,
+            urls,
+            ..Default::default()
+        };
+        if !paths.is_empty() {
+            match attachments::stage(&app, &paths) {
+                Ok(staged) => {
+                    content.files = staged.files;
+                    content.skipped = staged.skipped;
+                }
+                Err(e) => eprintln!("[CrewHub] Failed to stage shared files: {}", e),
+            }
+        }
+        // File managers start one process per selected file for some verbs;
+        // shares that arrive before the window picks them up accumulate.
+        if let Ok(mut pending) = app.state::<ShareState>().pending.lock() {
+            match pending.as_mut() {
+                Some(existing) => existing.merge(content.clone()),
+                None => *pending = Some(content.clone()),
+            }
+        }
+        let _ = app.emit_to(
+            quick_prompt::QUICK_PROMPT_WINDOW_LABEL,
+            "share-received",
+            content,
+        );
+        let handle = app.clone();
+        let _ = app.run_on_main_thread(move || quick_prompt::open(&handle));
+    });
+}
+
+/// Hidden desktop entry that offers CrewHub for any file.
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+fn registration_path() -> Result<PathBuf, String> {
+    let data = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+        .ok_or("No home directory")?;
+    Ok(data.join("applications/dev.crewhub.share.desktop"))
+}
+
+#[cfg(target_os = "windows")]
+fn registration_path() -> Result<PathBuf, String> {
+    let appdata = std::env::var_os("APPDATA").ok_or("APPDATA is not set")?;
+    Ok(Path::new(&appdata).join(r"Microsoft\Windows\SendTo\CrewHub.lnk"))
+}
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+fn register() -> Result<ShareTargetStatus, String> {
+    let path = registration_path()?;
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=Share with CrewHub\nIcon=crewhub\n\
+         Exec=\"{}\" {} %U\nMimeType=application/octet-stream;text/plain;image/png;image/jpeg;application/pdf;\n\
+         NoDisplay=true\nTerminal=false\n",
+        exe.display(),
+        SHARE_FLAG
+    );
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&path, entry).map_err(|e| e.to_string())?;
+    // Refresh the MIME cache so file managers pick the entry up; optional.
+    if let Some(parent) = path.parent() {
+        let _ = std::process::Command::new("update-desktop-database")
+            .arg(parent)
+            .status();
+    }
+    status()
+}
+
+#[cfg(target_os = "windows")]
+fn register() -> Result<ShareTargetStatus, String> {
+    use std::os::windows::process::CommandExt;
+    let path = registration_path()?;
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let script = format!(
+        "$s = (New-Object -ComObject WScript.Shell).CreateShortcut('{}'); \
+         $s.TargetPath = '{}'; $s.Arguments = '{}'; $s.Save()",
+        path.display().to_string().replace('\'', "''"),
+        exe.display().to_string().replace('\'', "''"),
+        SHARE_FLAG
+    );
+    let created = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        // CREATE_NO_WINDOW
+        .creation_flags(0x0800_0000)
+        .status()
+        .map_err(|e| e.to_string())?;
+    if !created.success() {
+        return Err("Failed to create the Send to shortcut".to_string());
+    }
+    status()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn unregister() -> Result<ShareTargetStatus, String> {
+    let path = registration_path()?;
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+    }
+    status()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn status() -> Result<ShareTargetStatus, String> {
+    let path = registration_path()?;
+    Ok(ShareTargetStatus {
+        registered: path.exists(),
+        method: if cfg!(target_os = "windows") {
+            "sendTo"
+        } else {
+            "desktopEntry"
+        },
+        path: Some(path.to_string_lossy().to_string()),
+    })
+}
+
+/// The bundle's document types are fixed at build time.
+#[cfg(target_os = "macos")]
+fn status() -> Result<ShareTargetStatus, String> {
+    Ok(ShareTargetStatus {
+        registered: true,
+        method: "openWith",
+        path: None,
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn register() -> Result<ShareTargetStatus, String> {
+    status()
+}
+
+#[cfg(target_os = "macos")]
+fn unregister() -> Result<ShareTargetStatus, String> {
+    Err("CrewHub is registered through its app bundle on macOS".to_string())
+}
+
+/// Tauri command: shared content waiting for the quick-prompt window (cleared on read).
+/// Called from the quick-prompt window via `invoke('take_pending_share')`.
+#[tauri::command]
+pub fn take_pending_share(state: State<ShareState>) -> Result<Option<SharedContent>, String> {
+    Ok(state.pending.lock().map_err(|e| e.to_string())?.take())
+}
+
+/// Tauri command: whether CrewHub is registered as a share destination.
+/// Called from the settings window via `invoke('get_share_target_status')`.
+#[tauri::command]
+pub fn get_share_target_status() -> Result<ShareTargetStatus, String> {
+    status()
+}
+
+/// Tauri command: register (or with `enabled: false`, remove) CrewHub as a share destination.
+/// Called from the settings window via `invoke('set_share_target', { enabled })`.
+#[tauri::command]
+pub fn set_share_target(enabled: bool) -> Result<ShareTargetStatus, String> {
+    if enabled {
+        register()
+    } else {
+        unregister()
+    }
+}
@@ -0,0 +1,107 @@
+//! Simulated events for frontend development.
+//!
+//! Lets frontend developers exercise badge logic, notification routing and
+//! window choreography without scripting a backend:
+//! - `simulate_backend_event` runs an event through the backend event bridge
+//!   ([`crate::bridge`]) as if a backend had sent it, and emits it to the
+//!   webviews as `simulated-backend-event` for the frontend's own handlers;
+//! - `simulate_notification` sends a notice through the full routing (mute
+//!   rules, priorities, rate limits, Do Not Disturb, quiet hours);
+//! - `simulate_tray_click` acts as if the tray icon was clicked or a tray
+//!   menu item chosen.
+//!
+//! Only compiled into debug builds, or release builds with the `simulation`
+//! feature; production builds don't have the commands at all. See
+//! [`crate::automation`] for driving a release build in CI.
+
+use crate::automation::TrayButton;
+use crate::federation::{self, BackendProfile};
+use crate::notifications::{self, Notice};
+use crate::{bridge, debug};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+/// Id of the made-up backend events are attributed to when none is enabled.
+const SIMULATED_BACKEND_ID: &str = "simulated";
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulatedEvent {
+    backend_id: String,
+    event: String,
+    payload: Value,
+}
+
+/// The enabled backend `backend_id` (default: the first one), or a made-up one.
+fn backend(app: &AppHandle, backend_id: Option<&str>) -> Result<BackendProfile, String> {
+    let enabled = federation::enabled(app);
+    match backend_id {
+        Some(id) if id != SIMULATED_BACKEND_ID => enabled
+            .into_iter()
+            .find(|backend| backend.id == id)
+            .ok_or_else(|| format!("No enabled backend '{}'", id)),
+        _ => Ok(enabled.into_iter().next().unwrap_or(BackendProfile {
+            id: SIMULATED_BACKEND_ID.to_string(),
+            name: "Simulated backend".to_string(),
+            url: crate::backend_url(),
+            enabled: true,
+        })),
+    }
+}
+
+/// Tauri command: handle a backend event as if a backend's event stream had delivered it.
+/// Called from the frontend via `invoke('simulate_backend_event', { event, payload, backendId })`.
+#[tauri::command]
+pub fn simulate_backend_event(
+    event: String,
+    payload: Value,
+    backend_id: Option<String>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let backend = backend(&app, backend_id.as_deref())?;
+    debug::record("simulated", &event, Some(backend.name.clone()));
+    bridge::dispatch(&app, &backend, &event, payload.clone());
+    let _ = app.emit(
+        "simulated-backend-event",
+        SimulatedEvent {
+            backend_id: backend.id,
+            event,
+            payload,
+        },
+    );
+    Ok(())
+}
+
+/// Tauri command: send a notice through the notification routing as if a subsystem had raised it.
+/// Called from the frontend via `invoke('simulate_notification', { notice })`.
+#[tauri::command]
+pub fn simulate_notification(notice: Notice, app: AppHandle) {
+    debug::record("simulated", "notification", Some(notice.title.clone()));
+    notifications::send(&app, notice);
+}
+
+/// Tauri command: act as if the tray icon was clicked (`button`) or a tray menu item chosen (`item`).
+/// Called from the frontend via `invoke('simulate_tray_click', { button, item })`.
+#[tauri::command]
+pub fn simulate_tray_click(
+    button: Option<TrayButton>,
+    item: Option<String>,
+    app: AppHandle,
+) -> Result<(), String> {
+    match (button, item) {
+        (_, Some(item)) => {
+            debug::record("simulated", "tray-menu", Some(item.clone()));
+            crate::handle_tray_menu(&app, &item);
+        }
+        (Some(TrayButton::Middle), None) => {
+            debug::record("simulated", "tray-click", Some("middle".to_string()));
+            crate::tray_click::handle_middle(&app);
+        }
+        (Some(TrayButton::Left) | None, None) => {
+            debug::record("simulated", "tray-click", Some("left".to_string()));
+            crate::tray_click::handle(&app);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Keyboard-driven window snapping.
+//!
+//! `snap_window` places a window in a region of its monitor's work area —
+//! halves, quarters and thirds as in Windows 11 Snap Layouts — so the
+//! frontend can bind them to shortcuts on every platform. The Snap Layouts
+//! flyout itself comes with the native title bar's maximize button; windows
+//! with custom (frameless) title bars would have to report that button's
+//! region to Windows, and CrewHub has none yet. Wayland doesn't let clients
+//! position their windows, so there only `maximize` and `restore` work.
+
+use crate::wayland;
+use serde::Deserialize;
+use tauri::{Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow, Window};
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Layout {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    LeftThird,
+    CenterThird,
+    RightThird,
+    LeftTwoThirds,
+    RightTwoThirds,
+    Center,
+    Maximize,
+    Restore,
+}
+
+impl Layout {
+    /// Region as fractions of the work area: x, y, width, height.
+    fn region(self) -> Option<(f64, f64, f64, f64)> {
+        let third = 1.0 / 3.0;
+        Some(match self {
+            Layout::Left => (0.0, 0.0, 0.5, 1.0),
+            Layout::Right => (0.5, 0.0, 0.5, 1.0),
+            Layout::Top => (0.0, 0.0, 1.0, 0.5),
+            Layout::Bottom => (0.0, 0.5, 1.0, 0.5),
+            Layout::TopLeft => (0.0, 0.0, 0.5, 0.5),
+            Layout::TopRight => (0.5, 0.0, 0.5, 0.5),
+            Layout::BottomLeft => (0.0, 0.5, 0.5, 0.5),
+            Layout::BottomRight => (0.5, 0.5, 0.5, 0.5),
+            Layout::LeftThird => (0.0, 0.0, third, 1.0),
+            Layout::CenterThird => (third, 0.0, third, 1.0),
+            Layout::RightThird => (2.0 * third, 0.0, third, 1.0),
+            Layout::LeftTwoThirds => (0.0, 0.0, 2.0 * third, 1.0),
+            Layout::RightTwoThirds => (third, 0.0, 2.0 * third, 1.0),
+            Layout::Center => (0.15, 0.1, 0.7, 0.8),
+            Layout::Maximize | Layout::Restore => return None,
+        })
+    }
+}
+
+/// Invisible resize borders around the visible frame (left, top, right, bottom).
+/// Windows 10/11 draw them outside the window's visible edge, so without this
+/// snapped windows would show gaps.
+#[cfg(target_os = "windows")]
+pub fn invisible_borders<R: Runtime>(window: &Window<R>) -> (i32, i32, i32, i32) {
+    use windows::Win32::Foundation::{HWND, RECT};
+    use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS};
+    use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+
+    let Ok(hwnd) = window.hwnd() else {
+        return (0, 0, 0, 0);
+    };
+    let hwnd = HWND(hwnd.0);
+    let mut outer = RECT::default();
+    let mut visible = RECT::default();
+    let ok = unsafe {
+        GetWindowRect(hwnd, &mut outer).is_ok()
+            && DwmGetWindowAttribute(
+                hwnd,
+                DWMWA_EXTENDED_FRAME_BOUNDS,
+                (&mut visible as *mut RECT).cast(),
+                std::mem::size_of::<RECT>() as u32,
+            )
+            .is_ok()
+    };
+    if !ok {
+        return (0, 0, 0, 0);
+    }
+    (
+        visible.left - outer.left,
+        visible.top - outer.top,
+        outer.right - visible.right,
+        outer.bottom - visible.bottom,
+    )
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn invisible_borders<R: Runtime>(_window: &Window<R>) -> (i32, i32, i32, i32) {
+    (0, 0, 0, 0)
+}
+
+fn snap(window: &WebviewWindow, layout: Layout) -> Result<(), String> {
+    if window.is_fullscreen().unwrap_or(false) {
+        window.set_fullscreen(false).map_err(|e| e.to_string())?;
+    }
+    let Some((x, y, width, height)) = layout.region() else {
+        return match layout {
+            Layout::Maximize => window.maximize(),
+            _ => window.unmaximize(),
+        }
+        .map_err(|e| e.to_string());
+    };
+    if wayland::is_wayland() {
+        return Err("Use the compositor's shortcuts to snap windows on Wayland".to_string());
+    }
+    if window.is_maximized().unwrap_or(false) {
+        window.unmaximize().map_err(|e| e.to_string())?;
+    }
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .ok_or("The window is not on a monitor")?;
+    let area = monitor.work_area();
+    let (left, top, right, bottom) = invisible_borders(&window.as_ref().window());
+    let (area_width, area_height) = (area.size.width as f64, area.size.height as f64);
+    let position = PhysicalPosition::new(
+        area.position.x + (area_width * x).round() as i32 - left,
+        area.position.y + (area_height * y).round() as i32 - top,
+    );
+    let size = PhysicalSize::new(
+        ((area_width * width).round() as i32 + left + right).max(1) as u32,
+        ((area_height * height).round() as i32 + top + bottom).max(1) as u32,
+    );
+    window.set_size(size).map_err(|e| e.to_string())?;
+    window.set_position(position).map_err(|e| e.to_string())
+}
+
+/// Tauri command: snap a window (the calling one unless `label` is given) to a layout,
+/// e.g. `left`, `top-right`, `center-third`, `maximize`.
+/// Called from the frontend via `invoke('snap_window', { layout, label })`.
+#[tauri::command]
+pub fn snap_window(
+    layout: Layout,
+    label: Option<String>,
+    window: WebviewWindow,
+) -> Result<(), String> {
+    let target = match label {
+        Some(label) => window
+            .app_handle()
+            .get_webview_window(&label)
+            .ok_or_else(|| format!("No window '{}'", label))?,
+        None => window,
+    };
+    snap(&target, layout)
+}
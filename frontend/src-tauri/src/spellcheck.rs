@@ -0,0 +1,223 @@
+//! Spellcheck settings for webviews.
+//!
+//! By default spellchecking follows the OS. The user can turn it off or pick
+//! languages, globally and per window label, persisted in `spellcheck.json`.
+//! Every window gets the settings up front via [`init_script`] (as
+//! `window.__CREWHUB_SPELLCHECK__`) and applies them to its editable fields
+//! (`spellcheck` / `lang` attributes); changes are broadcast as
+//! `spellcheck-changed`.
+//!
+//! The native checker is shared by all webviews of the process, so the
+//! global settings are also applied there ([`plugin`]): WebKitGTK's spell
+//! checking languages on Linux, `NSSpellChecker` and WebKit's continuous
+//! spellchecking default on macOS. WebView2 always checks in the Windows
+//! input languages; there only the per-field attributes take effect.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "spellcheck.json";
+
+/// Current config, readable without an app handle (for [`init_script`]).
+static CONFIG: Mutex<Option<SpellcheckConfig>> = Mutex::new(None);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SpellcheckConfig {
+    pub enabled: bool,
+    /// BCP 47 tags (`en-US`, `de`); empty follows the OS.
+    pub languages: Vec<String>,
+    /// Overrides by window label.
+    pub windows: BTreeMap<String, WindowSpellcheck>,
+}
+
+impl Default for SpellcheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            languages: Vec::new(),
+            windows: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WindowSpellcheck {
+    pub enabled: Option<bool>,
+    pub languages: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpellcheckStatus {
+    config: SpellcheckConfig,
+    /// Whether the chosen languages reach the native checker (not on Windows).
+    native_languages: bool,
+    /// Languages the native checker has dictionaries for, where it says (macOS).
+    available: Vec<String>,
+}
+
+fn current() -> SpellcheckConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+/// Whether any window checks spelling (the native checker is shared).
+#[cfg_attr(not(any(target_os = "linux", target_os = "macos")), allow(dead_code))]
+fn any_enabled(config: &SpellcheckConfig) -> bool {
+    config.enabled || config.windows.values().any(|w| w.enabled == Some(true))
+}
+
+/// Plugin that loads the settings before any window exists and applies them
+/// to every new webview's native checker.
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("spellcheck")
+        .setup(|app, _api| {
+            let config: SpellcheckConfig = store::load_json(app, CONFIG_FILE);
+            if let Ok(mut current) = CONFIG.lock() {
+                *current = Some(config.clone());
+            }
+            platform::apply_global(app, &config);
+            Ok(())
+        })
+        .on_webview_ready(|webview| platform::apply(&webview, &current()))
+        .build()
+}
+
+/// JS fragment exposing the settings as `window.__CREWHUB_SPELLCHECK__`.
+pub fn init_script() -> String {
+    let config = serde_json::to_string(&current()).unwrap_or_else(|_| "null".to_string());
+    format!("window.__CREWHUB_SPELLCHECK__ = {};", config)
+}
+
+/// Tauri command: spellcheck settings and what the platform supports.
+/// Called from the settings window via `invoke('get_spellcheck_config')`.
+#[tauri::command]
+pub fn get_spellcheck_config() -> SpellcheckStatus {
+    SpellcheckStatus {
+        config: current(),
+        native_languages: cfg!(any(target_os = "linux", target_os = "macos")),
+        available: platform::available(),
+    }
+}
+
+/// Tauri command: save spellcheck settings and apply them to open windows.
+/// Called from the settings window via `invoke('set_spellcheck_config', { config })`.
+#[tauri::command]
+pub fn set_spellcheck_config(
+    config: SpellcheckConfig,
+    app: AppHandle,
+) -> Result<SpellcheckStatus, String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config.clone());
+    }
+    platform::apply_global(&app, &config);
+    for window in app.webview_windows().values() {
+        platform::apply(window.as_ref(), &config);
+    }
+    let _ = app.emit("spellcheck-changed", &config);
+    Ok(get_spellcheck_config())
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{any_enabled, SpellcheckConfig};
+    use tauri::{AppHandle, Runtime, Webview};
+    use webkit2gtk::{WebContextExt, WebViewExt};
+
+    pub fn apply_global<R: Runtime>(_app: &AppHandle<R>, _config: &SpellcheckConfig) {}
+
+    /// Set the spell checking state of the webview's (shared) web context.
+    pub fn apply<R: Runtime>(webview: &Webview<R>, config: &SpellcheckConfig) {
+        let enabled = any_enabled(config);
+        // WebKit wants `en_US`; with none set it checks nothing.
+        let languages: Vec<String> = if config.languages.is_empty() {
+            sys_locale::get_locales().collect()
+        } else {
+            config.languages.clone()
+        }
+        .iter()
+        .map(|tag| tag.split('.').next().unwrap_or(tag).replace('-', "_"))
+        .collect();
+        let result = webview.with_webview(move |platform| {
+            let Some(context) = platform.inner().context() else {
+                return;
+            };
+            context.set_spell_checking_enabled(enabled);
+            let languages: Vec<&str> = languages.iter().map(String::as_str).collect();
+            context.set_spell_checking_languages(&languages);
+        });
+        if let Err(e) = result {
+            eprintln!("[CrewHub] Failed to set spellcheck: {}", e);
+        }
+    }
+
+    pub fn available() -> Vec<String> {
+        Vec::new()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{any_enabled, SpellcheckConfig};
+    use objc2_app_kit::NSSpellChecker;
+    use objc2_foundation::{NSString, NSUserDefaults};
+    use tauri::{AppHandle, Runtime, Webview};
+
+    /// WebKit reads this default for every new web content process.
+    const CONTINUOUS_KEY: &str = "WebContinuousSpellCheckingEnabled";
+
+    /// Pick the checker's language and WebKit's spellchecking default.
+    pub fn apply_global<R: Runtime>(app: &AppHandle<R>, config: &SpellcheckConfig) {
+        let enabled = any_enabled(config);
+        let language = config.languages.first().map(|tag| tag.replace('-', "_"));
+        let _ = app.run_on_main_thread(move || {
+            let defaults = NSUserDefaults::standardUserDefaults();
+            defaults.setBool_forKey(enabled, &NSString::from_str(CONTINUOUS_KEY));
+            let checker = NSSpellChecker::sharedSpellChecker();
+            match language {
+                Some(language) => {
+                    checker.setAutomaticallyIdentifiesLanguages(false);
+                    if !checker.setLanguage(&NSString::from_str(&language)) {
+                        eprintln!("[CrewHub] No spellcheck dictionary for {}", language);
+                    }
+                }
+                None => checker.setAutomaticallyIdentifiesLanguages(true),
+            }
+        });
+    }
+
+    pub fn apply<R: Runtime>(_webview: &Webview<R>, _config: &SpellcheckConfig) {}
+
+    pub fn available() -> Vec<String> {
+        NSSpellChecker::sharedSpellChecker()
+            .availableLanguages()
+            .iter()
+            .map(|language| language.to_string().replace('_', "-"))
+            .collect()
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod platform {
+    use super::SpellcheckConfig;
+    use tauri::{AppHandle, Runtime, Webview};
+
+    pub fn apply_global<R: Runtime>(_app: &AppHandle<R>, _config: &SpellcheckConfig) {}
+
+    pub fn apply<R: Runtime>(_webview: &Webview<R>, _config: &SpellcheckConfig) {}
+
+    pub fn available() -> Vec<String> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,233 @@
+//! JSON persistence for desktop-side state.
+//!
+//! Every Rust subsystem that needs to remember something between launches
+//! (configs, registries, queues) stores a single JSON file in the app data
+//! directory through these helpers, so there is one place that decides where
+//! and how files are written.
+//!
+//! Writes are crash-safe: the new contents go to a temporary file that is
+//! flushed to disk and then renamed over the old one, next to a SHA-256
+//! checksum. The previous good copy is kept as `<file>.bak`, and reads fall
+//! back to it when the current file is missing, fails its checksum or does
+//! not parse, so a crash mid-write never loses user data.
+
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Resolve `file_name` inside the app data directory (its demo subfolder in
+/// demo mode, see [`crate::demo`]), creating the directory if needed.
+pub fn data_path<R: Runtime>(app: &AppHandle<R>, file_name: &str) -> Result<PathBuf, String> {
+    let mut dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    if crate::demo::enabled() {
+        dir.push(crate::demo::DATA_DIR);
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(file_name))
+}
+
+/// `path` with `suffix` appended to its file name (`settings.json` -> `settings.json.bak`).
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+fn checksum(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Write `bytes` to a temporary file next to `path`, flush it to disk and
+/// rename it into place, so readers only ever see the old or the new contents.
+fn replace(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let tmp = sibling(path, ".tmp");
+    {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+    }
+    std::fs::rename(&tmp, path)
+}
+
+/// Read `path` if it is intact: present and matching its checksum file.
+///
+/// Files written before checksums existed (no `.sha256` next to them) are
+/// accepted as-is.
+fn read_intact(path: &Path) -> Option<Vec<u8>> {
+    let bytes = std::fs::read(path).ok()?;
+    match std::fs::read_to_string(sibling(path, ".sha256")) {
+        Ok(expected) if expected.trim() != checksum(&bytes) => None,
+        _ => Some(bytes),
+    }
+}
+
+/// Atomically replace `path` with `bytes`, keeping the previous good copy as a backup.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let backup = sibling(path, ".bak");
+    if let Some(previous) = read_intact(path) {
+        replace(&backup, &previous)?;
+        replace(&sibling(&backup, ".sha256"), checksum(&previous).as_bytes())?;
+    }
+    // A crash between these two renames leaves a complete file with a stale
+    // checksum; reads then fall back to the backup written above.
+    replace(path, bytes)?;
+    replace(&sibling(path, ".sha256"), checksum(bytes).as_bytes())
+}
+
+/// Read `path`, falling back to its backup when it is missing or corrupt.
+///
+/// `accept` lets callers reject contents that pass the checksum but cannot be
+/// used (e.g. JSON that no longer parses). A recovered backup is written back
+/// over the broken file.
+fn read_recovering<T>(path: &Path, accept: impl Fn(&[u8]) -> Option<T>) -> Option<T> {
+    if let Some(value) = read_intact(path).and_then(|bytes| accept(&bytes)) {
+        return Some(value);
+    }
+    let backup = read_intact(&sibling(path, ".bak"))?;
+    let value = accept(&backup)?;
+    if path.exists() {
+        eprintln!(
+            "[CrewHub] Recovered {} from its last good copy",
+            path.display()
+        );
+    }
+    if let Err(e) = replace(path, &backup)
+        .and_then(|_| replace(&sibling(path, ".sha256"), checksum(&backup).as_bytes()))
+    {
+        eprintln!("[CrewHub] Failed to restore {}: {}", path.display(), e);
+    }
+    Some(value)
+}
+
+/// Load a JSON file from the app data directory.
+///
+/// A missing, truncated or unparsable file falls back to its last good copy,
+/// and then to `T::default()`, so a corrupt file never prevents the app from
+/// starting.
+pub fn load_json<R: Runtime, T: DeserializeOwned + Default>(
+    app: &AppHandle<R>,
+    file_name: &str,
+) -> T {
+    let path = match data_path(app, file_name) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("[CrewHub] Cannot resolve {}: {}", file_name, e);
+            return T::default();
+        }
+    };
+
+    read_recovering(&path, |bytes| {
+        serde_json::from_slice(bytes)
+            .map_err(|e| eprintln!("[CrewHub] Ignoring invalid {}: {}", file_name, e))
+            .ok()
+    })
+    .unwrap_or_default()
+}
+
+/// Write `value` as pretty-printed JSON to the app data directory.
+pub fn save_json<R: Runtime, T: Serialize>(
+    app: &AppHandle<R>,
+    file_name: &str,
+    value: &T,
+) -> Result<(), String> {
+    let path = data_path(app, file_name)?;
+    let raw = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    write_atomic(&path, raw.as_bytes()).map_err(|e| format!("Failed to write {}: {}", file_name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    /// A fresh directory under the system temp dir, removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("crewhub-store-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn parse(bytes: &[u8]) -> Option<Value> {
+        serde_json::from_slice(bytes).ok()
+    }
+
+    #[test]
+    fn writes_a_checksum_and_keeps_the_previous_copy() {
+        let dir = TempDir::new();
+        let path = dir.0.join("settings.json");
+        write_atomic(&path, b"{\"v\":1}").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(sibling(&path, ".sha256")).unwrap(),
+            checksum(b"{\"v\":1}")
+        );
+        assert!(!sibling(&path, ".bak").exists());
+
+        write_atomic(&path, b"{\"v\":2}").unwrap();
+        assert_eq!(read_intact(&path).unwrap(), b"{\"v\":2}");
+        assert_eq!(read_intact(&sibling(&path, ".bak")).unwrap(), b"{\"v\":1}");
+        assert!(!sibling(&path, ".tmp").exists());
+    }
+
+    #[test]
+    fn checksum_mismatch_falls_back_to_backup_and_restores_it() {
+        let dir = TempDir::new();
+        let path = dir.0.join("settings.json");
+        write_atomic(&path, b"{\"v\":1}").unwrap();
+        write_atomic(&path, b"{\"v\":2}").unwrap();
+        // A torn write: new bytes, old checksum.
+        std::fs::write(&path, b"{\"v\":3").unwrap();
+
+        assert_eq!(read_intact(&path), None);
+        assert_eq!(read_recovering(&path, parse), Some(json!({ "v": 1 })));
+        assert_eq!(read_intact(&path).unwrap(), b"{\"v\":1}");
+    }
+
+    #[test]
+    fn unparsable_file_falls_back_to_backup() {
+        let dir = TempDir::new();
+        let path = dir.0.join("settings.json");
+        write_atomic(&path, b"{\"v\":1}").unwrap();
+        write_atomic(&path, b"not json").unwrap();
+        assert_eq!(read_recovering(&path, parse), Some(json!({ "v": 1 })));
+    }
+
+    #[test]
+    fn missing_file_recovers_from_backup() {
+        let dir = TempDir::new();
+        let path = dir.0.join("settings.json");
+        write_atomic(&path, b"{\"v\":1}").unwrap();
+        write_atomic(&path, b"{\"v\":2}").unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read_recovering(&path, parse), Some(json!({ "v": 1 })));
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn files_without_checksum_are_accepted() {
+        let dir = TempDir::new();
+        let path = dir.0.join("legacy.json");
+        std::fs::write(&path, b"{\"v\":0}").unwrap();
+        assert_eq!(read_recovering(&path, parse), Some(json!({ "v": 0 })));
+    }
+
+    #[test]
+    fn nothing_usable_yields_none() {
+        let dir = TempDir::new();
+        let path = dir.0.join("settings.json");
+        assert_eq!(read_recovering(&path, parse), None);
+        write_atomic(&path, b"not json").unwrap();
+        assert_eq!(read_recovering(&path, parse), None);
+    }
+}
@@ -0,0 +1,242 @@
+//! Keyboard window switcher.
+//!
+//! A small frameless window listing CrewHub's windows and pinned
+//! conversations, opened by a global hotkey (bound through
+//! [`crate::hotkeys`], `CmdOrCtrl+Alt+O` by default) or the `open-switcher`
+//! control action. It is the keyboard route to everything the tray menu
+//! offers: the frontend handles arrow keys, typeahead and Escape, reads the
+//! list from `get_switcher_entries` and activates an entry with `switch_to`.
+//! The window hides itself when it loses focus and is re-populated every
+//! time it opens (`switcher-opened`).
+
+use crate::{hud, i18n, pins, store, thumbnails};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindowBuilder, WindowEvent};
+
+/// Label for the switcher window.
+pub const SWITCHER_WINDOW_LABEL: &str = "switcher";
+
+/// Binding id of the switcher hotkey (next to quick action ids).
+pub const HOTKEY_ID: &str = "crewhub:switcher";
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "switcher.json";
+
+const DEFAULT_HOTKEY: &str = "CmdOrCtrl+Alt+O";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SwitcherConfig {
+    /// Global hotkey; `None` leaves the switcher to the control surface.
+    pub hotkey: Option<String>,
+}
+
+impl Default for SwitcherConfig {
+    fn default() -> Self {
+        Self {
+            hotkey: Some(DEFAULT_HOTKEY.to_string()),
+        }
+    }
+}
+
+/// Something the switcher can bring to the front.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Target {
+    Window { label: String },
+    Pinned { index: usize },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitcherEntry {
+    #[serde(flatten)]
+    target: Target,
+    title: String,
+    /// Window exists and is shown (always `false` for pins).
+    open: bool,
+    /// Window had focus before the switcher opened.
+    focused: bool,
+    /// Last preview of the window (`data:` URL); fresh ones follow as
+    /// `window-thumbnail` events.
+    thumbnail: Option<String>,
+}
+
+/// Main windows, listed even while closed (label, title key).
+const MAIN_WINDOWS: &[(&str, &str)] = &[
+    (crate::CHAT_WINDOW_LABEL, "menu.chat"),
+    (crate::WORLD_WINDOW_LABEL, "menu.world"),
+    (crate::ZEN_WINDOW_LABEL, "menu.zen"),
+    (crate::SETTINGS_WINDOW_LABEL, "switcher.settings"),
+];
+
+pub fn config<R: Runtime>(app: &AppHandle<R>) -> SwitcherConfig {
+    store::load_json(app, CONFIG_FILE)
+}
+
+fn switcher_init_script() -> String {
+    format!(
+        "window.__TAURI_VIEW__ = 'switcher'; {}",
+        crate::base_init(SWITCHER_WINDOW_LABEL)
+    )
+}
+
+/// Open the switcher, or close it if it is already in front (hotkey pressed twice).
+pub fn toggle<R: Runtime>(app: &AppHandle<R>) {
+    match app.get_webview_window(SWITCHER_WINDOW_LABEL) {
+        Some(window)
+            if window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false) =>
+        {
+            let _ = window.hide();
+        }
+        _ => open(app),
+    }
+}
+
+/// Open or focus the switcher window (420×360, frameless, always on top).
+pub fn open<R: Runtime>(app: &AppHandle<R>) {
+    thumbnails::refresh(app);
+    if let Some(window) = app.get_webview_window(SWITCHER_WINDOW_LABEL) {
+        let _ = window.center();
+        crate::show_and_focus(&window);
+        let _ = window.emit("switcher-opened", ());
+        return;
+    }
+
+    let result =
+        WebviewWindowBuilder::new(app, SWITCHER_WINDOW_LABEL, crate::view_url("view=switcher"))
+            .title("CrewHub Switcher")
+            .inner_size(420.0, 360.0)
+            .resizable(false)
+            .fullscreen(false)
+            .decorations(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .center()
+            .user_agent(&crate::request_headers::user_agent())
+            .on_new_window(crate::external_links::new_window_handler(app))
+            .initialization_script(switcher_init_script())
+            .build();
+
+    match result {
+        Ok(window) => {
+            let target = window.clone();
+            window.on_window_event(move |event| {
+                if let WindowEvent::Focused(false) = event {
+                    let _ = target.hide();
+                }
+            });
+            crate::show_and_focus(&window);
+        }
+        Err(e) => eprintln!("[CrewHub] Failed to create switcher window: {}", e),
+    }
+}
+
+/// Windows (main ones first, then any other open window) and pins, in list order.
+fn entries<R: Runtime>(app: &AppHandle<R>) -> Vec<SwitcherEntry> {
+    let windows = app.webview_windows();
+    let mut entries: Vec<SwitcherEntry> = MAIN_WINDOWS
+        .iter()
+        .map(|(label, key)| {
+            let window = windows.get(*label);
+            SwitcherEntry {
+                target: Target::Window {
+                    label: label.to_string(),
+                },
+                title: i18n::t(key),
+                open: window.is_some_and(|w| w.is_visible().unwrap_or(false)),
+                focused: window.is_some_and(|w| w.is_focused().unwrap_or(false)),
+                thumbnail: thumbnails::cached(label),
+            }
+        })
+        .collect();
+
+    let mut others: Vec<_> = windows
+        .iter()
+        .filter(|(label, _)| {
+            !MAIN_WINDOWS.iter().any(|(main, _)| main == label)
+                && label.as_str() != SWITCHER_WINDOW_LABEL
+                && label.as_str() != hud::HUD_WINDOW_LABEL
+        })
+        .filter(|(_, window)| window.is_visible().unwrap_or(false))
+        .collect();
+    others.sort_by(|a, b| a.0.cmp(b.0));
+    entries.extend(others.into_iter().map(|(label, window)| SwitcherEntry {
+        target: Target::Window {
+            label: label.clone(),
+        },
+        title: window.title().unwrap_or_else(|_| label.clone()),
+        open: true,
+        focused: window.is_focused().unwrap_or(false),
+        thumbnail: thumbnails::cached(label),
+    }));
+
+    entries.extend(
+        pins::pinned(app)
+            .into_iter()
+            .enumerate()
+            .map(|(index, pin)| SwitcherEntry {
+                target: Target::Pinned { index },
+                title: pin.conversation.title,
+                open: false,
+                focused: false,
+                thumbnail: None,
+            }),
+    );
+    entries
+}
+
+/// Bring a target to the front (opening main windows that aren't created yet).
+fn activate<R: Runtime>(app: &AppHandle<R>, target: &Target) -> Result<(), String> {
+    match target {
+        Target::Window { label } => {
+            match label.as_str() {
+                crate::CHAT_WINDOW_LABEL => crate::open_or_focus_chat(app),
+                crate::WORLD_WINDOW_LABEL => crate::open_or_focus_world(app),
+                crate::ZEN_WINDOW_LABEL => crate::open_or_focus_zen(app),
+                crate::SETTINGS_WINDOW_LABEL => crate::open_or_focus_settings(app),
+                _ => {
+                    let window = app
+                        .get_webview_window(label)
+                        .ok_or_else(|| format!("No window '{}'", label))?;
+                    crate::show_and_focus(&window);
+                }
+            }
+            Ok(())
+        }
+        Target::Pinned { index } => pins::open(app, *index),
+    }
+}
+
+/// Tauri command: the switcher's list.
+/// Called from the switcher window via `invoke('get_switcher_entries')`.
+#[tauri::command]
+pub fn get_switcher_entries(app: AppHandle) -> Vec<SwitcherEntry> {
+    entries(&app)
+}
+
+/// Tauri command: close the switcher and bring `target` to the front.
+/// Called from the switcher window via `invoke('switch_to', { target })`.
+#[tauri::command]
+pub fn switch_to(target: Target, app: AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(SWITCHER_WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+    activate(&app, &target)
+}
+
+/// Tauri command: the switcher hotkey.
+/// Called from the settings window via `invoke('get_switcher_config')`.
+#[tauri::command]
+pub fn get_switcher_config(app: AppHandle) -> SwitcherConfig {
+    config(&app)
+}
+
+/// Tauri command: change (or clear) the switcher hotkey and re-register hotkeys.
+/// Called from the settings window via `invoke('set_switcher_config', { config })`.
+#[tauri::command]
+pub fn set_switcher_config(config: SwitcherConfig, app: AppHandle) -> Result<(), String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    crate::hotkeys::sync(&app, &crate::quick_actions::all(&app));
+    Ok(())
+}
@@ -0,0 +1,341 @@
+//! Long-running work that survives a restart.
+//!
+//! Scheduled crew runs, bundle and config exports, and attachment uploads
+//! (tracked by the frontend with `track_upload` / `finish_upload`) are
+//! recorded in `tasks.json` while in flight. If the app quits or crashes
+//! before they finish, the next launch reconciles them a few seconds after
+//! startup:
+//! - scheduled runs are run again if the schedule still exists and is enabled;
+//! - exports are written again (they are rebuilt from the same inputs);
+//! - uploads whose staged files are still there (see [`crate::attachments`])
+//!   are handed back to the chat window, which resumes them
+//!   (`take_recovered_uploads`, or the `uploads-recovered` event while open).
+//!
+//! A notification sums up what was recovered; `get_task_recovery` lists it.
+
+use crate::bundle::{self, Manifest};
+use crate::notifications::{self, Event};
+use crate::{config_template, i18n, scheduler, store};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Journal of in-flight tasks (app data dir).
+const JOURNAL_FILE: &str = "tasks.json";
+
+/// Delay before interrupted tasks are reconciled, so startup isn't slowed down.
+const RECOVERY_DELAY: Duration = Duration::from_secs(5);
+
+/// Tasks in flight in this run.
+static RUNNING: Mutex<Vec<Task>> = Mutex::new(Vec::new());
+/// Tasks left over from the last run, until reconciled.
+static INTERRUPTED: Mutex<Vec<Task>> = Mutex::new(Vec::new());
+/// Recovered uploads waiting for the chat window.
+static UPLOADS: Mutex<Vec<RecoveredUpload>> = Mutex::new(Vec::new());
+/// Outcome of the last reconciliation.
+static REPORT: Mutex<Vec<RecoveredTask>> = Mutex::new(Vec::new());
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum TaskKind {
+    ScheduledRun {
+        schedule_id: String,
+        name: String,
+    },
+    BundleExport {
+        path: String,
+        manifest: Manifest,
+        prompt_ids: Vec<String>,
+        layout_id: Option<String>,
+    },
+    ConfigExport {
+        path: String,
+    },
+    /// Owned by the frontend; `data` is whatever it needs to resume.
+    Upload {
+        conversation_id: Option<String>,
+        files: Vec<String>,
+        #[serde(default)]
+        data: Value,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Task {
+    id: String,
+    #[serde(flatten)]
+    kind: TaskKind,
+    /// Unix seconds.
+    started_at: i64,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    /// Run again.
+    Resumed,
+    /// Handed back to the window that owns it.
+    HandedBack,
+    /// Couldn't be resumed (details in `error`).
+    Lost,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredTask {
+    #[serde(flatten)]
+    kind: TaskKind,
+    started_at: i64,
+    outcome: Outcome,
+    error: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredUpload {
+    conversation_id: Option<String>,
+    files: Vec<String>,
+    data: Value,
+}
+
+/// A recorded task; dropping it (finished, failed or panicked) removes the record.
+pub struct Running<R: Runtime> {
+    app: AppHandle<R>,
+    id: String,
+}
+
+impl<R: Runtime> Drop for Running<R> {
+    fn drop(&mut self) {
+        finish(&self.app, &self.id);
+    }
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, running: &[Task]) {
+    if let Err(e) = store::save_json(app, JOURNAL_FILE, &running) {
+        eprintln!("[CrewHub] {}", e);
+    }
+}
+
+fn record<R: Runtime>(app: &AppHandle<R>, kind: TaskKind) -> String {
+    let task = Task {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind,
+        started_at: chrono::Utc::now().timestamp(),
+    };
+    let id = task.id.clone();
+    if let Ok(mut running) = RUNNING.lock() {
+        running.push(task);
+        save(app, &running);
+    }
+    id
+}
+
+fn finish<R: Runtime>(app: &AppHandle<R>, id: &str) -> bool {
+    let Ok(mut running) = RUNNING.lock() else {
+        return false;
+    };
+    let before = running.len();
+    running.retain(|task| task.id != id);
+    let found = running.len() != before;
+    if found {
+        save(app, &running);
+    }
+    found
+}
+
+/// Record a task until the returned guard is dropped.
+pub fn begin<R: Runtime>(app: &AppHandle<R>, kind: TaskKind) -> Running<R> {
+    Running {
+        app: app.clone(),
+        id: record(app, kind),
+    }
+}
+
+/// Load the tasks the last run left unfinished and schedule their reconciliation.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let interrupted: Vec<Task> = store::load_json(app, JOURNAL_FILE);
+    if interrupted.is_empty() {
+        return;
+    }
+    println!(
+        "[CrewHub] {} task(s) interrupted in the last run",
+        interrupted.len()
+    );
+    if let Ok(mut tasks) = INTERRUPTED.lock() {
+        *tasks = interrupted;
+    }
+    save(app, &[]);
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(RECOVERY_DELAY);
+        recover(&app);
+    });
+}
+
+/// Staging directories of interrupted uploads, kept when the staging area is cleared.
+pub fn kept_staging_dirs(staging_root: &Path) -> Vec<PathBuf> {
+    let Ok(tasks) = INTERRUPTED.lock() else {
+        return Vec::new();
+    };
+    tasks
+        .iter()
+        .filter_map(|task| match &task.kind {
+            TaskKind::Upload { files, .. } => Some(files),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|file| {
+            let relative = Path::new(file).strip_prefix(staging_root).ok()?;
+            relative
+                .components()
+                .next()
+                .map(|dir| staging_root.join(dir))
+        })
+        .collect()
+}
+
+fn resume<R: Runtime>(app: &AppHandle<R>, kind: &TaskKind) -> Result<Outcome, String> {
+    match kind.clone() {
+        TaskKind::ScheduledRun { schedule_id, .. } => {
+            scheduler::resume(app, &schedule_id)?;
+            Ok(Outcome::Resumed)
+        }
+        TaskKind::BundleExport {
+            path,
+            manifest,
+            prompt_ids,
+            layout_id,
+        } => {
+            bundle::export(app, &path, manifest, prompt_ids, layout_id)?;
+            Ok(Outcome::Resumed)
+        }
+        TaskKind::ConfigExport { path } => {
+            config_template::export(app, &path)?;
+            Ok(Outcome::Resumed)
+        }
+        TaskKind::Upload {
+            conversation_id,
+            files,
+            data,
+        } => {
+            if let Some(missing) = files.iter().find(|file| !Path::new(file).is_file()) {
+                return Err(format!("Staged file is gone: {}", missing));
+            }
+            let upload = RecoveredUpload {
+                conversation_id,
+                files,
+                data,
+            };
+            if app.get_webview_window(crate::CHAT_WINDOW_LABEL).is_some() {
+                let _ = app.emit_to(crate::CHAT_WINDOW_LABEL, "uploads-recovered", [upload]);
+            } else if let Ok(mut uploads) = UPLOADS.lock() {
+                uploads.push(upload);
+            }
+            Ok(Outcome::HandedBack)
+        }
+    }
+}
+
+fn recover<R: Runtime>(app: &AppHandle<R>) {
+    let interrupted = INTERRUPTED
+        .lock()
+        .map(|mut tasks| std::mem::take(&mut *tasks))
+        .unwrap_or_default();
+    let report: Vec<RecoveredTask> = interrupted
+        .into_iter()
+        .map(|task| {
+            let (outcome, error) = match resume(app, &task.kind) {
+                Ok(outcome) => (outcome, None),
+                Err(e) => {
+                    eprintln!("[CrewHub] Couldn't recover interrupted task: {}", e);
+                    (Outcome::Lost, Some(e))
+                }
+            };
+            RecoveredTask {
+                kind: task.kind,
+                started_at: task.started_at,
+                outcome,
+                error,
+            }
+        })
+        .collect();
+    let recovered = report
+        .iter()
+        .filter(|task| task.outcome != Outcome::Lost)
+        .count();
+    notifications::notify(
+        app,
+        Event::TasksRecovered,
+        i18n::t("notify.tasksRecovered"),
+        i18n::tf(
+            "notify.tasksRecoveredBody",
+            &[
+                ("recovered", &recovered.to_string()),
+                ("total", &report.len().to_string()),
+            ],
+        ),
+    );
+    let _ = app.emit("tasks-recovered", &report);
+    if let Ok(mut last) = REPORT.lock() {
+        *last = report;
+    }
+}
+
+/// Tauri command: record an attachment upload so it can be resumed after a restart.
+/// Resolves to the id to pass to `finish_upload`.
+/// Called from the chat window via `invoke('track_upload', { conversationId, files, data })`.
+#[tauri::command]
+pub fn track_upload(
+    conversation_id: Option<String>,
+    files: Vec<String>,
+    data: Option<Value>,
+    app: AppHandle,
+) -> String {
+    record(
+        &app,
+        TaskKind::Upload {
+            conversation_id,
+            files,
+            data: data.unwrap_or_default(),
+        },
+    )
+}
+
+/// Tauri command: an upload finished, failed or was cancelled.
+/// Called from the chat window via `invoke('finish_upload', { id })`.
+#[tauri::command]
+pub fn finish_upload(id: String, app: AppHandle) -> Result<(), String> {
+    if finish(&app, &id) {
+        Ok(())
+    } else {
+        Err(format!("Unknown upload: {}", id))
+    }
+}
+
+/// Tauri command: uploads interrupted in the last run, for the chat window to resume.
+/// Called from the chat window via `invoke('take_recovered_uploads')`.
+#[tauri::command]
+pub fn take_recovered_uploads() -> Vec<RecoveredUpload> {
+    UPLOADS
+        .lock()
+        .map(|mut uploads| std::mem::take(&mut *uploads))
+        .unwrap_or_default()
+}
+
+/// Tauri command: what was recovered from the last run.
+/// Called from the settings window via `invoke('get_task_recovery')`.
+#[tauri::command]
+pub fn get_task_recovery() -> Vec<RecoveredTask> {
+    REPORT
+        .lock()
+        .map(|report| report.clone())
+        .unwrap_or_default()
+}
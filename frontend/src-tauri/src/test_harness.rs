@@ -0,0 +1,397 @@
+//! Mock-backend test harness (`--test-harness[=<fixture.json>]`).
+//!
+//! Started with the flag, CrewHub serves an in-process mock backend on a
+//! free loopback port (a fixed one in [`crate::automation`] mode) and points [`crate::backend_url`] (and so every window
+//! and background service) at it, so E2E tests can drive the real Tauri
+//! layer without a live backend. The mock answers from a fixture (the
+//! bundled `fixtures/test-harness.json` unless a path is given): agents,
+//! sessions, chat history, canned `routes` keyed `"METHOD /path"`, and named
+//! `scripts` of backend events.
+//!
+//! Nothing happens on its own: events reach `/api/events` subscribers only
+//! when a test asks, and each hook returns once they are delivered. Hooks are
+//! both Tauri commands (`harness_*`) and HTTP endpoints on the mock:
+//! - `POST /__harness/emit`           — `{"event": "…", "data": …}`
+//! - `POST /__harness/scripts/<name>` — play a fixture script
+//! - `GET  /__harness/requests`       — requests the mock received, in order
+//! - `POST /__harness/reset`          — restore the fixture, clear the log
+//!
+//! Windows see the harness as `window.__CREWHUB_TEST_HARNESS__` (its URL).
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Command-line flag; `--test-harness=<path>` uses another fixture.
+const FLAG: &str = "--test-harness";
+
+const DEFAULT_FIXTURE: &str = include_str!("../fixtures/test-harness.json");
+
+/// Mock backend URL once started.
+static URL: OnceLock<String> = OnceLock::new();
+
+static STATE: Mutex<Option<Harness>> = Mutex::new(None);
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+struct Fixture {
+    agents: Vec<Value>,
+    sessions: Vec<Value>,
+    /// Chat history by session key, oldest first.
+    history: HashMap<String, Vec<Value>>,
+    /// Canned responses by `"METHOD /path"`.
+    routes: HashMap<String, Value>,
+    scripts: HashMap<String, Vec<ScriptedEvent>>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScriptedEvent {
+    event: String,
+    #[serde(default)]
+    data: Value,
+    /// Pause before this event.
+    #[serde(default)]
+    delay_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedRequest {
+    method: String,
+    path: String,
+    body: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarnessStatus {
+    url: String,
+    scripts: Vec<String>,
+    subscribers: usize,
+}
+
+struct Harness {
+    fixture: Fixture,
+    history: HashMap<String, Vec<Value>>,
+    requests: Vec<RecordedRequest>,
+    subscribers: Vec<Sender<Vec<u8>>>,
+}
+
+/// Start the mock backend if the flag was passed. Call before anything reads
+/// the backend URL.
+pub fn start_if_requested() {
+    let Some(arg) = std::env::args().find(|arg| arg.starts_with(FLAG)) else {
+        return;
+    };
+    let fixture = match arg
+        .strip_prefix(FLAG)
+        .and_then(|rest| rest.strip_prefix('='))
+    {
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read fixture {}: {}", path, e)),
+        None if arg == FLAG => Ok(DEFAULT_FIXTURE.to_string()),
+        None => return,
+    };
+    // Automation mode (CI) needs a URL known in advance.
+    let port = if crate::automation::enabled() {
+        crate::automation::HARNESS_PORT
+    } else {
+        0
+    };
+    match fixture.and_then(|raw| serve(&raw, port)) {
+        Ok(url) => {
+            println!("[CrewHub] Test harness: mock backend at {}", url);
+            let _ = URL.set(url);
+        }
+        Err(e) => eprintln!("[CrewHub] Test harness not started: {}", e),
+    }
+}
+
+/// Serve `fixture` (JSON) as a mock backend on loopback `port` (0 picks a
+/// free one) and return its URL. Also used by [`crate::demo`].
+pub fn serve(fixture: &str, port: u16) -> Result<String, String> {
+    let fixture: Fixture = serde_json::from_str(fixture).map_err(|e| e.to_string())?;
+    let server = Server::http(("127.0.0.1", port)).map_err(|e| e.to_string())?;
+    let port = server
+        .server_addr()
+        .to_ip()
+        .map(|addr| addr.port())
+        .ok_or("Mock backend has no IP address")?;
+    if let Ok(mut state) = STATE.lock() {
+        *state = Some(Harness {
+            history: fixture.history.clone(),
+            fixture,
+            requests: Vec::new(),
+            subscribers: Vec::new(),
+        });
+    }
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            std::thread::spawn(move || handle(request));
+        }
+    });
+    Ok(format!("http://127.0.0.1:{}", port))
+}
+
+/// Mock backend URL, when running as a test harness.
+pub fn url() -> Option<&'static str> {
+    URL.get().map(String::as_str)
+}
+
+/// JS fragment exposing the harness as `window.__CREWHUB_TEST_HARNESS__`
+/// (its URL, or `null` in normal runs).
+pub fn init_script() -> String {
+    let url = serde_json::to_string(&url()).unwrap_or_else(|_| "null".to_string());
+    format!("window.__CREWHUB_TEST_HARNESS__ = {};", url)
+}
+
+fn with_state<T>(f: impl FnOnce(&mut Harness) -> T) -> Result<T, String> {
+    let mut state = STATE.lock().map_err(|e| e.to_string())?;
+    state
+        .as_mut()
+        .map(f)
+        .ok_or_else(|| "Test harness is not running".to_string())
+}
+
+/// Send one event to every open `/api/events` stream; drops closed ones.
+fn emit(event: &str, data: &Value) -> Result<usize, String> {
+    let frame = format!("event: {}\ndata: {}\n\n", event, data).into_bytes();
+    with_state(|state| {
+        state
+            .subscribers
+            .retain(|subscriber| subscriber.send(frame.clone()).is_ok());
+        state.subscribers.len()
+    })
+}
+
+/// Play a fixture script in order (blocking through its delays).
+pub fn run_script(name: &str) -> Result<usize, String> {
+    let script = with_state(|state| state.fixture.scripts.get(name).cloned())?
+        .ok_or_else(|| format!("Unknown script: {}", name))?;
+    for step in &script {
+        if step.delay_ms > 0 {
+            std::thread::sleep(Duration::from_millis(step.delay_ms));
+        }
+        emit(&step.event, &step.data)?;
+    }
+    Ok(script.len())
+}
+
+fn reset() -> Result<(), String> {
+    with_state(|state| {
+        state.history = state.fixture.history.clone();
+        state.requests.clear();
+    })
+}
+
+fn cors_headers() -> Vec<Header> {
+    [
+        ("Access-Control-Allow-Origin", "*"),
+        ("Access-Control-Allow-Headers", "*"),
+        (
+            "Access-Control-Allow-Methods",
+            "GET, POST, PUT, PATCH, DELETE, OPTIONS",
+        ),
+    ]
+    .iter()
+    .filter_map(|(name, value)| Header::from_bytes(name.as_bytes(), value.as_bytes()).ok())
+    .collect()
+}
+
+fn respond(request: Request, status: u16, body: &Value) {
+    let mut response = Response::from_string(body.to_string()).with_status_code(status);
+    for header in cors_headers() {
+        response.add_header(header);
+    }
+    if let Ok(header) = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]) {
+        response.add_header(header);
+    }
+    let _ = request.respond(response);
+}
+
+/// SSE body fed by [`emit`].
+struct EventStream {
+    events: Receiver<Vec<u8>>,
+    buffer: Vec<u8>,
+}
+
+impl Read for EventStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // Empty frames only probe whether the stream is still open.
+        while self.buffer.is_empty() {
+            match self.events.recv() {
+                Ok(frame) => self.buffer = frame,
+                Err(_) => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.buffer.len());
+        buf[..n].copy_from_slice(&self.buffer[..n]);
+        self.buffer.drain(..n);
+        Ok(n)
+    }
+}
+
+fn stream_events(request: Request) {
+    let (sender, events) = mpsc::channel();
+    // A comment line so the client sees the stream open right away.
+    let _ = sender.send(b": connected\n\n".to_vec());
+    if with_state(|state| state.subscribers.push(sender)).is_err() {
+        return respond(
+            request,
+            503,
+            &json!({ "error": "Test harness is not running" }),
+        );
+    }
+    let mut headers = cors_headers();
+    headers.extend(Header::from_bytes(&b"Content-Type"[..], &b"text/event-stream"[..]).ok());
+    let body = EventStream {
+        events,
+        buffer: Vec::new(),
+    };
+    // Ends with an error once the client disconnects.
+    let _ = request.respond(Response::new(200.into(), headers, body, None, None));
+}
+
+fn handle(mut request: Request) {
+    if *request.method() == Method::Options {
+        return respond(request, 204, &Value::Null);
+    }
+    let method = request.method().to_string().to_uppercase();
+    let url = request.url().to_string();
+    let path = url.split('?').next().unwrap_or_default().to_string();
+    if method == "GET" && path == "/api/events" {
+        return stream_events(request);
+    }
+
+    let body = crate::local_http::read_json_body(&mut request).unwrap_or(Value::Null);
+    if let Some(hook) = path.strip_prefix("/__harness/") {
+        let result = match (method.as_str(), hook) {
+            ("POST", "emit") => match body.get("event").and_then(Value::as_str) {
+                Some(event) => emit(event, body.get("data").unwrap_or(&Value::Null))
+                    .map(|delivered| json!({ "delivered": delivered })),
+                None => Err("Missing 'event'".to_string()),
+            },
+            ("GET", "requests") => with_state(|state| json!({ "requests": state.requests })),
+            ("POST", "reset") => reset().map(|()| json!({ "ok": true })),
+            ("POST", hook) => match hook.strip_prefix("scripts/") {
+                Some(name) => run_script(name).map(|events| json!({ "events": events })),
+                None => Err(format!("Unknown hook: {}", hook)),
+            },
+            _ => Err(format!("Unknown hook: {}", hook)),
+        };
+        return match result {
+            Ok(value) => respond(request, 200, &value),
+            Err(e) => respond(request, 400, &json!({ "error": e })),
+        };
+    }
+
+    let reply = with_state(|state| {
+        state.requests.push(RecordedRequest {
+            method: method.clone(),
+            path: path.clone(),
+            body: body.clone(),
+        });
+        route(state, &method, &path, &body)
+    });
+    match reply {
+        Ok(Some(value)) => respond(request, 200, &value),
+        Ok(None) => respond(request, 404, &json!({ "error": "Not mocked" })),
+        Err(e) => respond(request, 503, &json!({ "error": e })),
+    }
+}
+
+/// Mocked backend API: built-in endpoints first, then the fixture's routes.
+fn route(state: &mut Harness, method: &str, path: &str, body: &Value) -> Option<Value> {
+    match (method, path) {
+        ("GET", "/api/agents") => return Some(json!({ "agents": state.fixture.agents })),
+        ("GET", "/api/sessions") => return Some(json!({ "sessions": state.fixture.sessions })),
+        _ => {}
+    }
+    if let Some(rest) = path.strip_prefix("/api/chat/") {
+        let (key, action) = rest.rsplit_once('/')?;
+        let key = percent_encoding::percent_decode_str(key)
+            .decode_utf8_lossy()
+            .into_owned();
+        match (method, action) {
+            ("GET", "history") => {
+                let messages = state.history.get(&key).cloned().unwrap_or_default();
+                return Some(json!({ "messages": messages, "hasMore": false }));
+            }
+            ("POST", "send") => {
+                let message = body.get("message").and_then(Value::as_str).unwrap_or("");
+                let history = state.history.entry(key).or_default();
+                // Deterministic timestamps: one second per message.
+                let timestamp = 1_700_000_000_000i64 + history.len() as i64 * 1000;
+                let reply = format!("Echo: {}", message);
+                history.push(json!({ "role": "user", "content": message, "timestamp": timestamp }));
+                history.push(json!({
+                    "role": "assistant",
+                    "content": reply,
+                    "timestamp": timestamp + 1000,
+                }));
+                return Some(json!({ "reply": reply }));
+            }
+            _ => {}
+        }
+    }
+    state
+        .fixture
+        .routes
+        .get(&format!("{} {}", method, path))
+        .cloned()
+}
+
+/// Tauri command: the mock backend's URL and scripts.
+/// Called from E2E tests via `invoke('get_test_harness')`.
+#[tauri::command]
+pub fn get_test_harness() -> Result<HarnessStatus, String> {
+    let url = url().ok_or("Test harness is not running")?.to_string();
+    with_state(|state| {
+        let mut scripts: Vec<String> = state.fixture.scripts.keys().cloned().collect();
+        scripts.sort();
+        state
+            .subscribers
+            .retain(|subscriber| subscriber.send(Vec::new()).is_ok());
+        HarnessStatus {
+            url,
+            scripts,
+            subscribers: state.subscribers.len(),
+        }
+    })
+}
+
+/// Tauri command: send a backend event to all event streams; returns how many got it.
+/// Called from E2E tests via `invoke('harness_emit', { event, data })`.
+#[tauri::command]
+pub fn harness_emit(event: String, data: Value) -> Result<usize, String> {
+    emit(&event, &data)
+}
+
+/// Tauri command: play a fixture script; returns once every event is sent.
+/// Called from E2E tests via `invoke('harness_run_script', { name })`.
+#[tauri::command]
+pub async fn harness_run_script(name: String) -> Result<usize, String> {
+    tauri::async_runtime::spawn_blocking(move || run_script(&name))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: requests the mock backend received, in order.
+/// Called from E2E tests via `invoke('harness_requests')`.
+#[tauri::command]
+pub fn harness_requests() -> Result<Vec<RecordedRequest>, String> {
+    with_state(|state| state.requests.clone())
+}
+
+/// Tauri command: restore the fixture's chat history and clear the request log.
+/// Called from E2E tests via `invoke('harness_reset')`.
+#[tauri::command]
+pub fn harness_reset() -> Result<(), String> {
+    reset()
+}
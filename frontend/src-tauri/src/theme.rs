@@ -0,0 +1,329 @@
+//! OS dark/light theme and accent color tracking.
+//!
+//! The theme is read from the OS at startup and then kept current two ways:
+//! windows report `ThemeChanged` as soon as the user switches, and a slow poll
+//! catches switches while no window is open (the tray still needs to know).
+//! On every change the theme is emitted to all windows as `theme-changed`,
+//! applied to native titlebars, and the tray icon is re-picked so themed
+//! variants (`<icon>-dark.png` / `<icon>-light.png`, when bundled) are used.
+//! New windows get the current theme up front via [`init_script`], and their
+//! frame via [`plugin`] as soon as they are created. On Windows the frame is
+//! switched with DWM's immersive dark mode attribute, so titlebars and borders
+//! match a dark app instead of staying white.
+//!
+//! The accent color is polled the same way (there is no window event for it)
+//! and broadcast as `accent-color-changed` with a `#rrggbb` value.
+
+use crate::power;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Manager, Runtime, Window};
+
+/// How often the OS setting is re-read.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Current theme, readable without an app handle (for [`init_script`]).
+static DARK: AtomicBool = AtomicBool::new(false);
+
+/// Current accent color as `#rrggbb`, if the OS exposes one.
+static ACCENT: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl Theme {
+    fn as_str(self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+}
+
+impl From<tauri::Theme> for Theme {
+    fn from(theme: tauri::Theme) -> Self {
+        match theme {
+            tauri::Theme::Dark => Theme::Dark,
+            _ => Theme::Light,
+        }
+    }
+}
+
+/// Read the initial theme and start watching for changes.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(theme) = detect() {
+        DARK.store(theme == Theme::Dark, Ordering::Relaxed);
+    }
+    if let Ok(mut accent) = ACCENT.lock() {
+        *accent = detect_accent();
+    }
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(power::interval(&app, POLL_INTERVAL));
+        if let Some(theme) = detect() {
+            set(&app, theme);
+        }
+        set_accent(&app, detect_accent());
+    });
+}
+
+/// Plugin that gives every new window a frame matching the current theme.
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("theme-frame")
+        .on_window_ready(|window| apply_frame(&window, current()))
+        .build()
+}
+
+/// Switch a window's titlebar and frame to `theme`.
+fn apply_frame<R: Runtime>(window: &Window<R>, theme: Theme) {
+    let native = match theme {
+        Theme::Light => tauri::Theme::Light,
+        Theme::Dark => tauri::Theme::Dark,
+    };
+    let _ = window.set_theme(Some(native));
+    #[cfg(target_os = "windows")]
+    set_immersive_dark_mode(window, theme == Theme::Dark);
+}
+
+/// Set DWM's immersive dark mode on the window frame (Windows 10 20H1+;
+/// earlier builds only know the undocumented attribute 19).
+#[cfg(target_os = "windows")]
+fn set_immersive_dark_mode<R: Runtime>(window: &Window<R>, dark: bool) {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Dwm::{
+        DwmSetWindowAttribute, DWMWA_USE_IMMERSIVE_DARK_MODE, DWMWINDOWATTRIBUTE,
+    };
+
+    let Ok(hwnd) = window.hwnd() else {
+        return;
+    };
+    let hwnd = HWND(hwnd.0);
+    let value: i32 = dark.into();
+    let set = |attribute: DWMWINDOWATTRIBUTE| unsafe {
+        DwmSetWindowAttribute(
+            hwnd,
+            attribute,
+            (&value as *const i32).cast(),
+            std::mem::size_of::<i32>() as u32,
+        )
+    };
+    if set(DWMWA_USE_IMMERSIVE_DARK_MODE).is_err() {
+        let _ = set(DWMWINDOWATTRIBUTE(19));
+    }
+}
+
+/// The current OS theme.
+pub fn current() -> Theme {
+    if DARK.load(Ordering::Relaxed) {
+        Theme::Dark
+    } else {
+        Theme::Light
+    }
+}
+
+/// The current accent color (`#rrggbb`), if known.
+pub fn accent_color() -> Option<String> {
+    ACCENT.lock().ok().and_then(|accent| accent.clone())
+}
+
+/// JS fragment exposing the theme and accent color as
+/// `window.__CREWHUB_THEME__` / `window.__CREWHUB_ACCENT__`.
+pub fn init_script() -> String {
+    let accent = serde_json::to_string(&accent_color()).unwrap_or_else(|_| "null".to_string());
+    format!(
+        "window.__CREWHUB_THEME__ = '{}'; window.__CREWHUB_ACCENT__ = {};",
+        current().as_str(),
+        accent
+    )
+}
+
+/// Record a theme reported by a window or the poll; broadcasts only real changes.
+pub fn set<R: Runtime>(app: &AppHandle<R>, theme: Theme) {
+    if DARK.swap(theme == Theme::Dark, Ordering::Relaxed) == (theme == Theme::Dark) {
+        return;
+    }
+    for window in app.webview_windows().values() {
+        apply_frame(&window.as_ref().window(), theme);
+    }
+    crate::refresh_tray_icon(app);
+    let _ = app.emit("theme-changed", serde_json::json!({ "theme": theme }));
+}
+
+fn set_accent<R: Runtime>(app: &AppHandle<R>, color: Option<String>) {
+    let Ok(mut accent) = ACCENT.lock() else {
+        return;
+    };
+    if *accent == color {
+        return;
+    }
+    *accent = color.clone();
+    drop(accent);
+    let _ = app.emit(
+        "accent-color-changed",
+        serde_json::json!({ "accentColor": color }),
+    );
+}
+
+/// Variant of bundled icon `name` for the current theme, if one is bundled.
+pub fn icon_variant<R: Runtime>(app: &AppHandle<R>, name: &str) -> Option<PathBuf> {
+    let stem = name.strip_suffix(".png")?;
+    let path = app.path().resource_dir().ok()?.join("icons").join(format!(
+        "{}-{}.png",
+        stem,
+        current().as_str()
+    ));
+    path.exists().then_some(path)
+}
+
+/// Stdout of a settings query (`defaults`, `reg`, `gsettings`), without a console window.
+pub fn output(program: &str, args: &[&str]) -> Option<String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW: don't flash a console on every poll.
+        cmd.creation_flags(0x0800_0000);
+    }
+    let output = cmd.output().ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Ask the OS for the current theme (`None` if it can't be determined).
+#[cfg(target_os = "macos")]
+fn detect() -> Option<Theme> {
+    // The key only exists in dark mode.
+    let style = output("defaults", &["read", "-g", "AppleInterfaceStyle"])?;
+    Some(if style.trim() == "Dark" {
+        Theme::Dark
+    } else {
+        Theme::Light
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn detect_accent() -> Option<String> {
+    // Missing key means the default (multicolor, shown as blue).
+    let value = output("defaults", &["read", "-g", "AppleAccentColor"]).unwrap_or_default();
+    let color = match value.trim() {
+        "-1" => "#8c8c8c",
+        "0" => "#ff5257",
+        "1" => "#f7821b",
+        "2" => "#ffc600",
+        "3" => "#62ba46",
+        "5" => "#a550a7",
+        "6" => "#f74f9e",
+        _ => "#007aff",
+    };
+    Some(color.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn detect() -> Option<Theme> {
+    let value = output(
+        "reg",
+        &[
+            "query",
+            r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+            "/v",
+            "AppsUseLightTheme",
+        ],
+    )?;
+    let light = value
+        .lines()
+        .find(|line| line.contains("AppsUseLightTheme"))?;
+    Some(if light.trim_end().ends_with("0x0") {
+        Theme::Dark
+    } else {
+        Theme::Light
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn detect_accent() -> Option<String> {
+    let value = output(
+        "reg",
+        &[
+            "query",
+            r"HKCU\Software\Microsoft\Windows\DWM",
+            "/v",
+            "AccentColor",
+        ],
+    )?;
+    // REG_DWORD 0xAABBGGRR
+    let line = value.lines().find(|line| line.contains("AccentColor"))?;
+    let hex = line.split_whitespace().last()?.trim_start_matches("0x");
+    let abgr = u32::from_str_radix(hex, 16).ok()?;
+    Some(format!(
+        "#{:02x}{:02x}{:02x}",
+        abgr & 0xff,
+        (abgr >> 8) & 0xff,
+        (abgr >> 16) & 0xff
+    ))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn detect() -> Option<Theme> {
+    let scheme = output(
+        "gsettings",
+        &["get", "org.gnome.desktop.interface", "color-scheme"],
+    )
+    .unwrap_or_default();
+    if scheme.contains("dark") {
+        return Some(Theme::Dark);
+    }
+    let gtk_theme = output(
+        "gsettings",
+        &["get", "org.gnome.desktop.interface", "gtk-theme"],
+    )?;
+    Some(if gtk_theme.to_lowercase().contains("dark") {
+        Theme::Dark
+    } else {
+        Theme::Light
+    })
+}
+
+/// GNOME 47+ accent setting, mapped to the libadwaita palette.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn detect_accent() -> Option<String> {
+    let name = output(
+        "gsettings",
+        &["get", "org.gnome.desktop.interface", "accent-color"],
+    )?;
+    let color = match name.trim().trim_matches('\'') {
+        "blue" => "#3584e4",
+        "teal" => "#2190a4",
+        "green" => "#3a944a",
+        "yellow" => "#c88800",
+        "orange" => "#ed5b00",
+        "red" => "#e62d42",
+        "pink" => "#d56199",
+        "purple" => "#9141ac",
+        "slate" => "#6f8396",
+        _ => return None,
+    };
+    Some(color.to_string())
+}
+
+/// Tauri command: the current OS theme (`light` or `dark`).
+/// Called from the frontend via `invoke('get_system_theme')`.
+#[tauri::command]
+pub fn get_system_theme() -> Theme {
+    current()
+}
+
+/// Tauri command: the OS accent color as `#rrggbb` (`null` if unavailable).
+/// Called from the frontend via `invoke('get_accent_color')`.
+#[tauri::command]
+pub fn get_accent_color() -> Option<String> {
+    accent_color()
+}
@@ -0,0 +1,175 @@
+//! Windows taskbar thumbnail toolbar.
+//!
+//! Buttons under the chat window's taskbar preview: mark all read, toggle
+//! CrewHub's Do Not Disturb, and open Zen Mode. They perform the matching
+//! [`crate::control`] actions. The window is subclassed to receive the
+//! clicks (`WM_COMMAND` / `THBN_CLICKED`) and Explorer's
+//! `TaskbarButtonCreated`, after which the buttons have to be added again.
+
+use crate::{control, dnd, i18n};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+use windows::core::w;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+};
+use windows::Win32::UI::Shell::{
+    DefSubclassProc, ITaskbarList3, SetWindowSubclass, TaskbarList, THBF_ENABLED, THBN_CLICKED,
+    THB_FLAGS, THB_ICON, THB_TOOLTIP, THUMBBUTTON,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateIconFromResourceEx, GetSystemMetrics, RegisterWindowMessageW, HICON, LR_DEFAULTCOLOR,
+    SM_CXSMICON, SM_CYSMICON, WM_COMMAND,
+};
+
+/// Button ids, and the control action each performs.
+const BUTTONS: [(u32, &str); 3] = [(1, "mark-all-read"), (2, "toggle-dnd"), (3, "start-focus")];
+
+const ICONS: [&[u8]; 3] = [
+    include_bytes!("../icons/thumbbar-read.png"),
+    include_bytes!("../icons/thumbbar-dnd.png"),
+    include_bytes!("../icons/thumbbar-zen.png"),
+];
+
+/// Subclass id (any value unique to this module).
+const SUBCLASS_ID: usize = 0x7468_756d;
+
+/// The chat window's handle, once attached.
+static HWND_VALUE: AtomicIsize = AtomicIsize::new(0);
+
+/// Whether the buttons exist (they can only be added once per taskbar button).
+static ADDED: AtomicBool = AtomicBool::new(false);
+
+/// Performs a control action; set once in [`attach`].
+type Handler = Box<dyn Fn(&str) + Send + Sync>;
+static HANDLER: OnceLock<Handler> = OnceLock::new();
+
+/// `HICON`s (as `isize`), created once.
+static ICON_HANDLES: OnceLock<Vec<isize>> = OnceLock::new();
+
+fn icons() -> &'static [isize] {
+    ICON_HANDLES.get_or_init(|| {
+        let (cx, cy) = unsafe { (GetSystemMetrics(SM_CXSMICON), GetSystemMetrics(SM_CYSMICON)) };
+        ICONS
+            .iter()
+            .map(|png| {
+                // PNG data is a valid icon resource since Windows Vista.
+                unsafe { CreateIconFromResourceEx(png, true, 0x0003_0000, cx, cy, LR_DEFAULTCOLOR) }
+                    .map(|icon| icon.0 as isize)
+                    .unwrap_or_default()
+            })
+            .collect()
+    })
+}
+
+fn buttons() -> Vec<THUMBBUTTON> {
+    let tips = [
+        i18n::t("thumbBar.markRead"),
+        if dnd::manual() {
+            i18n::t("thumbBar.dndOff")
+        } else {
+            i18n::t("thumbBar.dndOn")
+        },
+        i18n::t("thumbBar.zen"),
+    ];
+    BUTTONS
+        .iter()
+        .zip(tips)
+        .zip(icons())
+        .map(|((&(id, _), tip), &icon)| {
+            let mut button = THUMBBUTTON {
+                dwMask: THB_ICON | THB_TOOLTIP | THB_FLAGS,
+                iId: id,
+                hIcon: HICON(icon as _),
+                dwFlags: THBF_ENABLED,
+                ..Default::default()
+            };
+            for (slot, unit) in button.szTip.iter_mut().zip(tip.encode_utf16().take(259)) {
+                *slot = unit;
+            }
+            button
+        })
+        .collect()
+}
+
+/// Add (or update) the buttons. Main thread only.
+fn apply(hwnd: HWND) {
+    let result = unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        CoCreateInstance::<_, ITaskbarList3>(&TaskbarList, None, CLSCTX_INPROC_SERVER).and_then(
+            |taskbar| {
+                let buttons = buttons();
+                if ADDED.load(Ordering::SeqCst) {
+                    taskbar.ThumbBarUpdateButtons(hwnd, &buttons)
+                } else {
+                    taskbar.ThumbBarAddButtons(hwnd, &buttons).map(|_| {
+                        ADDED.store(true, Ordering::SeqCst);
+                    })
+                }
+            },
+        )
+    };
+    if let Err(e) = result {
+        eprintln!("[CrewHub] Failed to set thumbnail buttons: {}", e);
+    }
+}
+
+unsafe extern "system" fn subclass_proc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: usize,
+    _data: usize,
+) -> LRESULT {
+    if message == WM_COMMAND && (wparam.0 >> 16) as u32 & 0xffff == THBN_CLICKED {
+        let id = (wparam.0 & 0xffff) as u32;
+        if let (Some((_, action)), Some(handler)) = (
+            BUTTONS.iter().find(|(button, _)| *button == id),
+            HANDLER.get(),
+        ) {
+            handler(action);
+        }
+        return LRESULT(0);
+    }
+    if message == unsafe { RegisterWindowMessageW(w!("TaskbarButtonCreated")) } {
+        // Explorer (re)created the taskbar button: the old buttons are gone.
+        ADDED.store(false, Ordering::SeqCst);
+        apply(hwnd);
+    }
+    unsafe { DefSubclassProc(hwnd, message, wparam, lparam) }
+}
+
+/// Add the buttons to the chat window (called once it is created).
+pub fn attach<R: Runtime>(window: &WebviewWindow<R>) {
+    let Ok(hwnd) = window.hwnd() else {
+        return;
+    };
+    let app = window.app_handle().clone();
+    let _ = HANDLER.set(Box::new(move |action| {
+        if let Err(e) = control::perform(&app, action, &Value::Null) {
+            eprintln!("[CrewHub] Thumbnail button '{}' failed: {}", action, e);
+        }
+    }));
+    let raw = hwnd.0 as isize;
+    HWND_VALUE.store(raw, Ordering::SeqCst);
+    let _ = window.run_on_main_thread(move || unsafe {
+        let hwnd = HWND(raw as _);
+        let _ = SetWindowSubclass(hwnd, Some(subclass_proc), SUBCLASS_ID, 0);
+        // The taskbar button usually exists already; otherwise
+        // TaskbarButtonCreated adds them.
+        apply(hwnd);
+    });
+}
+
+/// Refresh tooltips (Do Not Disturb state, language).
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let hwnd = HWND_VALUE.load(Ordering::SeqCst);
+    if hwnd == 0 || !ADDED.load(Ordering::SeqCst) {
+        return;
+    }
+    let _ = app.run_on_main_thread(move || apply(HWND(hwnd as _)));
+}
@@ -0,0 +1,337 @@
+//! Window thumbnails for the switcher and recent-window lists.
+//!
+//! `capture_window_thumbnail` snapshots a CrewHub window's webview with the
+//! webview's own API (WebKitGTK `get_snapshot`, `Page.captureScreenshot`
+//! over WebView2's DevTools protocol, `WKWebView takeSnapshot`), so only
+//! CrewHub's own content is ever captured — never other apps or whatever
+//! overlaps the window — and no screen recording permission is involved.
+//! Thumbnails are scaled down to [`THUMBNAIL_WIDTH`] and kept in memory only.
+//!
+//! Captures are throttled to one per window every [`MIN_INTERVAL`]; hidden
+//! or minimized windows keep their last thumbnail. Ephemeral chat windows,
+//! windows with content protection ([`crate::content_protection`]), the
+//! switcher and the HUD are never captured. When the switcher opens, the
+//! open windows are refreshed in the background and each new thumbnail is
+//! sent as `window-thumbnail`.
+
+use crate::{content_protection, ephemeral, hud, switcher};
+use base64::Engine;
+use image::RgbaImage;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+
+/// Width of thumbnails in pixels (the height keeps the window's aspect ratio).
+pub const THUMBNAIL_WIDTH: u32 = 320;
+
+/// A window's thumbnail is taken at most this often.
+const MIN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long to wait for the webview to deliver a snapshot.
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(3);
+
+type Snapshot = Result<RgbaImage, String>;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowThumbnail {
+    label: String,
+    /// PNG as a `data:` URL.
+    data_url: String,
+    width: u32,
+    height: u32,
+    /// Capture time (ms since the epoch).
+    captured_at: i64,
+    #[serde(skip)]
+    taken: Option<Instant>,
+}
+
+static CACHE: Mutex<Option<HashMap<String, WindowThumbnail>>> = Mutex::new(None);
+
+fn with_cache<T>(f: impl FnOnce(&mut HashMap<String, WindowThumbnail>) -> T) -> T {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    f(cache.get_or_insert_with(HashMap::new))
+}
+
+/// Whether a window may be captured at all.
+fn capturable<R: Runtime>(app: &AppHandle<R>, label: &str) -> bool {
+    label != switcher::SWITCHER_WINDOW_LABEL
+        && label != hud::HUD_WINDOW_LABEL
+        && !ephemeral::is_ephemeral_window(label)
+        && !content_protection::is_protected(app, label)
+}
+
+/// Drop a window's thumbnail (window closed or protected).
+pub fn forget(label: &str) {
+    with_cache(|cache| cache.remove(label));
+}
+
+/// Last thumbnail of a window, without capturing.
+pub fn cached(label: &str) -> Option<String> {
+    with_cache(|cache| cache.get(label).map(|thumbnail| thumbnail.data_url.clone()))
+}
+
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+fn decode_png(bytes: &[u8]) -> Snapshot {
+    image::load_from_memory_with_format(bytes, image::ImageFormat::Png)
+        .map(|image| image.to_rgba8())
+        .map_err(|e| e.to_string())
+}
+
+fn snapshot<R: Runtime>(window: &WebviewWindow<R>) -> Snapshot {
+    let (tx, rx) = mpsc::channel();
+    platform::snapshot(window, tx)?;
+    rx.recv_timeout(CAPTURE_TIMEOUT)
+        .map_err(|_| "The window did not deliver a snapshot".to_string())?
+}
+
+fn encode(label: &str, image: &RgbaImage) -> Result<WindowThumbnail, String> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Err("The window is empty".to_string());
+    }
+    let scaled_height = (u64::from(height) * u64::from(THUMBNAIL_WIDTH) / u64::from(width)).max(1);
+    let scaled = if width > THUMBNAIL_WIDTH {
+        image::imageops::thumbnail(image, THUMBNAIL_WIDTH, scaled_height as u32)
+    } else {
+        image.clone()
+    };
+    let mut png = Vec::new();
+    scaled
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(WindowThumbnail {
+        label: label.to_string(),
+        data_url: format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(png)
+        ),
+        width: scaled.width(),
+        height: scaled.height(),
+        captured_at: chrono::Utc::now().timestamp_millis(),
+        taken: Some(Instant::now()),
+    })
+}
+
+/// The window's thumbnail, captured now unless the last one is recent or
+/// the window isn't showing. `None` for windows that are never captured.
+/// Blocks until the webview delivers, so never call it on the main thread.
+pub fn capture<R: Runtime>(
+    app: &AppHandle<R>,
+    label: &str,
+) -> Result<Option<WindowThumbnail>, String> {
+    with_cache(|cache| cache.retain(|label, _| app.get_webview_window(label).is_some()));
+    let window = app
+        .get_webview_window(label)
+        .ok_or_else(|| format!("No window '{}'", label))?;
+    if !capturable(app, label) {
+        forget(label);
+        return Ok(None);
+    }
+    let last = with_cache(|cache| cache.get(label).cloned());
+    let fresh = last
+        .as_ref()
+        .and_then(|thumbnail| thumbnail.taken)
+        .is_some_and(|taken| taken.elapsed() < MIN_INTERVAL);
+    let showing = window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false);
+    if fresh || !showing {
+        return Ok(last);
+    }
+    let thumbnail = encode(label, &snapshot(&window)?)?;
+    with_cache(|cache| cache.insert(label.to_string(), thumbnail.clone()));
+    Ok(Some(thumbnail))
+}
+
+/// Refresh the thumbnails of all open windows in the background, emitting
+/// `window-thumbnail` for each new one (called when the switcher opens).
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let labels: Vec<String> = app.webview_windows().into_keys().collect();
+        for label in labels {
+            let before = with_cache(|cache| cache.get(&label).and_then(|t| t.taken));
+            match capture(&app, &label) {
+                Ok(Some(thumbnail)) if thumbnail.taken != before => {
+                    let _ = app.emit("window-thumbnail", thumbnail);
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("[CrewHub] No thumbnail of '{}': {}", label, e),
+            }
+        }
+    });
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::Snapshot;
+    use image::RgbaImage;
+    use std::sync::mpsc::Sender;
+    use tauri::{Runtime, WebviewWindow};
+    use webkit2gtk::{gio, SnapshotOptions, SnapshotRegion, WebViewExt};
+
+    /// Cairo ARGB32 (premultiplied, native byte order) to straight RGBA.
+    fn to_rgba(surface: cairo::Surface) -> Snapshot {
+        let surface = cairo::ImageSurface::try_from(surface)
+            .map_err(|_| "Unexpected snapshot surface".to_string())?;
+        let (width, height) = (surface.width() as u32, surface.height() as u32);
+        let stride = surface.stride() as usize;
+        let mut image = RgbaImage::new(width, height);
+        surface
+            .with_data(|data| {
+                for (x, y, pixel) in image.enumerate_pixels_mut() {
+                    let offset = y as usize * stride + x as usize * 4;
+                    let Some(bytes) = data.get(offset..offset + 4) else {
+                        continue;
+                    };
+                    let argb = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                    let [a, r, g, b] = argb.to_be_bytes();
+                    let straight = |c: u8| match a {
+                        0 => 0,
+                        a => (u32::from(c) * 255 / u32::from(a)).min(255) as u8,
+                    };
+                    *pixel = image::Rgba([straight(r), straight(g), straight(b), a]);
+                }
+            })
+            .map_err(|e| e.to_string())?;
+        Ok(image)
+    }
+
+    pub fn snapshot<R: Runtime>(
+        window: &WebviewWindow<R>,
+        tx: Sender<Snapshot>,
+    ) -> Result<(), String> {
+        window
+            .with_webview(move |platform| {
+                platform.inner().snapshot(
+                    SnapshotRegion::Visible,
+                    SnapshotOptions::NONE,
+                    None::<&gio::Cancellable>,
+                    move |result| {
+                        let _ = tx.send(result.map_err(|e| e.to_string()).and_then(to_rgba));
+                    },
+                );
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::Snapshot;
+    use base64::Engine;
+    use std::sync::mpsc::Sender;
+    use tauri::{Runtime, WebviewWindow};
+    use webview2_com::{CallDevToolsProtocolMethodCompletedHandler, CoTaskMemPWSTR};
+
+    /// `Page.captureScreenshot` reply (`{"data": "<base64 PNG>"}`).
+    fn decode_reply(reply: &str) -> Snapshot {
+        let reply: serde_json::Value = serde_json::from_str(reply).map_err(|e| e.to_string())?;
+        let data = reply
+            .get("data")
+            .and_then(|data| data.as_str())
+            .ok_or("Empty snapshot")?;
+        let png = base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| e.to_string())?;
+        super::decode_png(&png)
+    }
+
+    /// Through the DevTools protocol, like clearing the cache (see
+    /// [`crate::webview_data`]); `CapturePreview` would need an `IStream`
+    /// from another `windows` version than WebView2's.
+    pub fn snapshot<R: Runtime>(
+        window: &WebviewWindow<R>,
+        tx: Sender<Snapshot>,
+    ) -> Result<(), String> {
+        window
+            .with_webview(move |platform| unsafe {
+                let webview = match platform.controller().CoreWebView2() {
+                    Ok(webview) => webview,
+                    Err(e) => {
+                        let _ = tx.send(Err(e.to_string()));
+                        return;
+                    }
+                };
+                let method = CoTaskMemPWSTR::from("Page.captureScreenshot");
+                let params = CoTaskMemPWSTR::from(r#"{"format":"png"}"#);
+                let done = tx.clone();
+                let handler = CallDevToolsProtocolMethodCompletedHandler::create(Box::new(
+                    move |result, reply| {
+                        let snapshot = match &result {
+                            Ok(()) => decode_reply(&reply),
+                            Err(e) => Err(e.to_string()),
+                        };
+                        let _ = done.send(snapshot);
+                        result
+                    },
+                ));
+                if let Err(e) = webview.CallDevToolsProtocolMethod(
+                    *method.as_ref().as_pcwstr(),
+                    *params.as_ref().as_pcwstr(),
+                    &handler,
+                ) {
+                    let _ = tx.send(Err(e.to_string()));
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::Snapshot;
+    use block2::RcBlock;
+    use objc2_app_kit::{NSBitmapImageFileType, NSBitmapImageRep, NSImage};
+    use objc2_foundation::{NSDictionary, NSError};
+    use objc2_web_kit::WKWebView;
+    use std::sync::mpsc::Sender;
+    use tauri::{Runtime, WebviewWindow};
+
+    fn to_png(image: &NSImage) -> Option<Vec<u8>> {
+        let tiff = image.TIFFRepresentation()?;
+        let rep = NSBitmapImageRep::imageRepWithData(&tiff)?;
+        let png = unsafe {
+            rep.representationUsingType_properties(NSBitmapImageFileType::PNG, &NSDictionary::new())
+        }?;
+        Some(png.to_vec())
+    }
+
+    pub fn snapshot<R: Runtime>(
+        window: &WebviewWindow<R>,
+        tx: Sender<Snapshot>,
+    ) -> Result<(), String> {
+        window
+            .with_webview(move |platform| {
+                // SAFETY: Tauri's WKWebView, used on the main thread inside `with_webview`.
+                let webview = unsafe { &*(platform.inner() as *const WKWebView) };
+                let handler = RcBlock::new(move |image: *mut NSImage, error: *mut NSError| {
+                    // SAFETY: WebKit passes a valid image, or null and an error.
+                    let snapshot = match unsafe { (image.as_ref(), error.as_ref()) } {
+                        (Some(image), _) => to_png(image)
+                            .ok_or_else(|| "Unreadable snapshot".to_string())
+                            .and_then(|png| super::decode_png(&png)),
+                        (None, Some(error)) => Err(error.localizedDescription().to_string()),
+                        (None, None) => Err("No snapshot".to_string()),
+                    };
+                    let _ = tx.send(snapshot);
+                });
+                unsafe { webview.takeSnapshotWithConfiguration_completionHandler(None, &handler) };
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Tauri command: a small preview of a CrewHub window (`null` for windows
+/// that are never captured); throttled, see the module docs.
+/// Called from the switcher via `invoke('capture_window_thumbnail', { label })`.
+#[tauri::command]
+pub async fn capture_window_thumbnail(
+    label: String,
+    app: AppHandle,
+) -> Result<Option<WindowThumbnail>, String> {
+    tauri::async_runtime::spawn_blocking(move || capture(&app, &label))
+        .await
+        .map_err(|e| e.to_string())?
+}
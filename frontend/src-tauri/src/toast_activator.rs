@@ -0,0 +1,197 @@
+//! Windows toast activation while CrewHub isn't running.
+//!
+//! Toasts of an unpackaged app only call back into the process that showed
+//! them, so clicking one after CrewHub quit did nothing. CrewHub therefore
+//! registers (per user, under `HKCU\Software\Classes`) its AppUserModelID
+//! with a `CustomActivator` and a COM local server for that class. Windows
+//! starts `CrewHub.exe -Embedding` when needed and calls
+//! `INotificationActivationCallback::Activate` with the toast's arguments —
+//! the JSON-encoded launch arguments of its [`NoticeAction`], which go
+//! through [`crate::launch`] like any other activation.
+
+use crate::launch;
+use crate::notifications::{Notice, NoticeAction};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Runtime};
+use windows::core::{implement, Interface, Ref, BOOL, GUID, HSTRING, PCWSTR};
+use windows::Data::Xml::Dom::XmlDocument;
+use windows::Win32::Foundation::CLASS_E_NOAGGREGATION;
+use windows::Win32::System::Com::{
+    CoInitializeEx, CoRegisterClassObject, IClassFactory, IClassFactory_Impl, CLSCTX_LOCAL_SERVER,
+    COINIT_MULTITHREADED, REGCLS_MULTIPLEUSE,
+};
+use windows::Win32::System::Registry::{RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ};
+use windows::Win32::UI::Notifications::{
+    INotificationActivationCallback, INotificationActivationCallback_Impl,
+    NOTIFICATION_USER_INPUT_DATA,
+};
+use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+
+/// COM class Windows activates for CrewHub's toasts.
+const CLSID: GUID = GUID::from_u128(0x8a04382f_7313_4f62_b657_71da8542f7ed);
+
+/// Routes a toast's arguments; set once in [`init`].
+type Handler = Box<dyn Fn(Vec<String>) + Send + Sync>;
+static HANDLER: OnceLock<Handler> = OnceLock::new();
+
+/// Whether the AppUserModelID and COM server are registered.
+static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+#[implement(INotificationActivationCallback)]
+struct Activator;
+
+impl INotificationActivationCallback_Impl for Activator_Impl {
+    fn Activate(
+        &self,
+        _app_user_model_id: &PCWSTR,
+        invoked_args: &PCWSTR,
+        _data: *const NOTIFICATION_USER_INPUT_DATA,
+        _count: u32,
+    ) -> windows::core::Result<()> {
+        let invoked = unsafe { invoked_args.to_string() }.unwrap_or_default();
+        // Empty for a toast without an action: just open CrewHub.
+        let args = serde_json::from_str(&invoked).unwrap_or_default();
+        if let Some(handler) = HANDLER.get() {
+            handler(args);
+        }
+        Ok(())
+    }
+}
+
+#[implement(IClassFactory)]
+struct ActivatorFactory;
+
+impl IClassFactory_Impl for ActivatorFactory_Impl {
+    fn CreateInstance(
+        &self,
+        outer: Ref<'_, windows::core::IUnknown>,
+        iid: *const GUID,
+        object: *mut *mut std::ffi::c_void,
+    ) -> windows::core::Result<()> {
+        unsafe { *object = std::ptr::null_mut() };
+        if outer.is_some() {
+            return Err(CLASS_E_NOAGGREGATION.into());
+        }
+        let activator: INotificationActivationCallback = Activator.into();
+        unsafe { activator.query(iid, object).ok() }
+    }
+
+    fn LockServer(&self, _lock: BOOL) -> windows::core::Result<()> {
+        Ok(())
+    }
+}
+
+fn set_value(key: &str, name: Option<&str>, value: &str) -> Result<(), String> {
+    let data: Vec<u16> = value.encode_utf16().chain(Some(0)).collect();
+    let name = name.map(HSTRING::from);
+    let result = unsafe {
+        RegSetKeyValueW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(key),
+            name.as_ref()
+                .map_or(PCWSTR::null(), |name| PCWSTR(name.as_ptr())),
+            REG_SZ.0,
+            Some(data.as_ptr().cast()),
+            (data.len() * 2) as u32,
+        )
+    };
+    result
+        .ok()
+        .map_err(|e| format!("Failed to write HKCU\\{}: {}", key, e))
+}
+
+/// Register the AppUserModelID and its activator (refreshed at every start,
+/// so moving the app keeps the path current).
+fn register(app_id: &str) -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let clsid = format!("{{{:?}}}", CLSID);
+    let aumid_key = format!(r"Software\Classes\AppUserModelId\{}", app_id);
+    set_value(&aumid_key, Some("DisplayName"), "CrewHub")?;
+    set_value(&aumid_key, Some("CustomActivator"), &clsid)?;
+    set_value(
+        &format!(r"Software\Classes\CLSID\{}\LocalServer32", clsid),
+        None,
+        &format!("\"{}\"", exe.display()),
+    )
+}
+
+/// Register the activator. Runs early in setup: when Windows started CrewHub
+/// for a toast click, it waits for the class object.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let handle = app.clone();
+    let _ = HANDLER.set(Box::new(move |args| {
+        let app = handle.clone();
+        let _ = handle.run_on_main_thread(move || {
+            if !launch::handle(&app, &args, std::path::Path::new("")) {
+                crate::open_or_focus_chat(&app);
+            }
+        });
+    }));
+    let app_id = app.config().identifier.clone();
+    // The class object lives in a multithreaded apartment, so calls don't
+    // depend on the main thread's message loop.
+    std::thread::spawn(move || {
+        if let Err(e) = register(&app_id) {
+            eprintln!("[CrewHub] {}", e);
+            return;
+        }
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let factory: IClassFactory = ActivatorFactory.into();
+            match CoRegisterClassObject(&CLSID, &factory, CLSCTX_LOCAL_SERVER, REGCLS_MULTIPLEUSE) {
+                Ok(_) => REGISTERED.store(true, Ordering::SeqCst),
+                Err(e) => {
+                    eprintln!("[CrewHub] Failed to register the toast activator: {}", e);
+                    return;
+                }
+            }
+        }
+        // Keep the apartment (and the registration) for the life of the process.
+        loop {
+            std::thread::park();
+        }
+    });
+}
+
+/// Whether toasts can be shown through the registered activator.
+pub fn is_registered() -> bool {
+    REGISTERED.load(Ordering::SeqCst)
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Show a toast whose click (or button) performs `action`, even after CrewHub quit.
+pub fn show<R: Runtime>(
+    app: &AppHandle<R>,
+    notice: &Notice,
+    action: &NoticeAction,
+) -> Result<(), String> {
+    let args = xml_escape(&serde_json::to_string(&action.args).map_err(|e| e.to_string())?);
+    let xml = format!(
+        "<toast launch=\"{args}\" activationType=\"foreground\">\
+         <visual><binding template=\"ToastGeneric\"><text>{title}</text><text>{body}</text></binding></visual>\
+         <actions><action content=\"{label}\" arguments=\"{args}\" activationType=\"foreground\"/></actions>\
+         </toast>",
+        args = args,
+        title = xml_escape(&notice.title),
+        body = xml_escape(&notice.body),
+        label = xml_escape(&action.label),
+    );
+    let show = || -> windows::core::Result<()> {
+        let document = XmlDocument::new()?;
+        document.LoadXml(&HSTRING::from(xml))?;
+        let toast = ToastNotification::CreateToastNotification(&document)?;
+        ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(
+            app.config().identifier.as_str(),
+        ))?
+        .Show(&toast)
+    };
+    show().map_err(|e| e.to_string())
+}
@@ -0,0 +1,322 @@
+//! What clicks and scrolls on the tray icon do.
+//!
+//! A left click opens the chat window by default; the setting can instead
+//! open the world window, toggle whichever main window was used last, open
+//! the quick prompt, or show the tray menu (as a right click does). The last
+//! used main window is tracked from focus events. On Linux a left click is
+//! the StatusNotifierItem's "activate" (see [`crate::tray_support`]).
+//!
+//! A middle click toggles Do Not Disturb by default, and the scroll wheel
+//! cycles through the open main windows. Windows and Linux don't report the
+//! wheel over tray icons; on macOS it is picked up from the status item's
+//! button (see `mod scroll`).
+
+use crate::{dnd, store};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "tray-click.json";
+
+/// Windows that "toggle last window" can bring back.
+const MAIN_WINDOWS: [&str; 3] = [
+    crate::CHAT_WINDOW_LABEL,
+    crate::WORLD_WINDOW_LABEL,
+    crate::ZEN_WINDOW_LABEL,
+];
+
+/// Minimum time between two window switches from the scroll wheel, so one
+/// trackpad swipe doesn't race through every window.
+const SCROLL_DEBOUNCE: Duration = Duration::from_millis(350);
+
+/// Saved actions, readable from the tray event handler.
+static CONFIG: Mutex<TrayClickConfig> = Mutex::new(TrayClickConfig {
+    left_click: LeftClick::OpenChat,
+    middle_click: MiddleClick::ToggleDnd,
+    scroll: Scroll::CycleWindows,
+});
+
+/// When the scroll wheel last switched windows.
+static LAST_SCROLL: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Label of the main window focused most recently.
+static LAST_WINDOW: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LeftClick {
+    #[default]
+    OpenChat,
+    OpenWorld,
+    /// Hide the last used main window if it is showing, otherwise bring it back.
+    ToggleLastWindow,
+    /// Open the quick prompt.
+    OpenPalette,
+    ShowMenu,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MiddleClick {
+    #[default]
+    ToggleDnd,
+    MarkAllRead,
+    Nothing,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Scroll {
+    /// Bring up the next (wheel down) or previous (wheel up) main window.
+    #[default]
+    CycleWindows,
+    Nothing,
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TrayClickConfig {
+    left_click: LeftClick,
+    middle_click: MiddleClick,
+    scroll: Scroll,
+}
+
+/// Load the saved actions (called before the tray is built).
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let saved: TrayClickConfig = store::load_json(app, CONFIG_FILE);
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = saved;
+    }
+    #[cfg(target_os = "macos")]
+    scroll::init(app);
+}
+
+fn config() -> TrayClickConfig {
+    CONFIG.lock().map(|config| *config).unwrap_or_default()
+}
+
+fn action() -> LeftClick {
+    config().left_click
+}
+
+/// Whether a left click should open the tray menu (for the tray builder).
+pub fn shows_menu() -> bool {
+    action() == LeftClick::ShowMenu
+}
+
+/// Remember a main window as the last used one (called on focus).
+pub fn track_focus(label: &str) {
+    if MAIN_WINDOWS.contains(&label) {
+        if let Ok(mut last) = LAST_WINDOW.lock() {
+            *last = Some(label.to_string());
+        }
+    }
+}
+
+fn open<R: Runtime>(app: &AppHandle<R>, label: &str) {
+    match label {
+        crate::WORLD_WINDOW_LABEL => crate::open_or_focus_world(app),
+        crate::ZEN_WINDOW_LABEL => crate::open_or_focus_zen(app),
+        _ => crate::open_or_focus_chat(app),
+    }
+}
+
+fn last_window() -> String {
+    LAST_WINDOW
+        .lock()
+        .ok()
+        .and_then(|last| last.clone())
+        .unwrap_or_else(|| crate::CHAT_WINDOW_LABEL.to_string())
+}
+
+fn toggle_last_window<R: Runtime>(app: &AppHandle<R>) {
+    let label = last_window();
+    let showing = app.get_webview_window(&label).is_some_and(|window| {
+        window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false)
+    });
+    match app.get_webview_window(&label) {
+        Some(window) if showing => {
+            let _ = window.hide();
+        }
+        _ => open(app, &label),
+    }
+}
+
+/// Bring up the main window after (or before) the last used one. Windows that
+/// are open come first; if none is, this cycles through all of them.
+fn cycle_windows<R: Runtime>(app: &AppHandle<R>, forward: bool) {
+    let showing: Vec<&str> = MAIN_WINDOWS
+        .into_iter()
+        .filter(|label| {
+            app.get_webview_window(label)
+                .is_some_and(|window| window.is_visible().unwrap_or(false))
+        })
+        .collect();
+    let labels = if showing.len() > 1 {
+        showing
+    } else {
+        MAIN_WINDOWS.to_vec()
+    };
+    let current = last_window();
+    let index = labels.iter().position(|label| *label == current);
+    let next = match (index, forward) {
+        (Some(i), true) => (i + 1) % labels.len(),
+        (Some(i), false) => (i + labels.len() - 1) % labels.len(),
+        (None, _) => 0,
+    };
+    open(app, labels[next]);
+}
+
+/// Run the configured left-click action (the menu itself is shown by the OS).
+pub fn handle<R: Runtime>(app: &AppHandle<R>) {
+    match action() {
+        LeftClick::OpenChat => crate::open_or_focus_chat(app),
+        LeftClick::OpenWorld => crate::open_or_focus_world(app),
+        LeftClick::ToggleLastWindow => toggle_last_window(app),
+        LeftClick::OpenPalette => crate::quick_prompt::open(app),
+        LeftClick::ShowMenu => {}
+    }
+}
+
+/// Run the configured middle-click action.
+pub fn handle_middle<R: Runtime>(app: &AppHandle<R>) {
+    match config().middle_click {
+        MiddleClick::ToggleDnd => dnd::set_manual(app, !dnd::manual()),
+        MiddleClick::MarkAllRead => crate::mark_all_read(app),
+        MiddleClick::Nothing => {}
+    }
+}
+
+/// Run the configured scroll action (`forward` for wheel down).
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn handle_scroll<R: Runtime>(app: &AppHandle<R>, forward: bool) {
+    if config().scroll == Scroll::Nothing {
+        return;
+    }
+    if let Ok(mut last) = LAST_SCROLL.lock() {
+        if last.is_some_and(|at| at.elapsed() < SCROLL_DEBOUNCE) {
+            return;
+        }
+        *last = Some(Instant::now());
+    }
+    cycle_windows(app, forward);
+}
+
+fn save(app: &AppHandle, config: TrayClickConfig) -> Result<(), String> {
+    store::save_json(app, CONFIG_FILE, &config)?;
+    if let Ok(mut saved) = CONFIG.lock() {
+        *saved = config;
+    }
+    let _ = app.emit("tray-click-changed", config);
+    Ok(())
+}
+
+/// Tauri command: the saved left-click action.
+/// Called from the settings window via `invoke('get_tray_left_click')`.
+#[tauri::command]
+pub fn get_tray_left_click() -> LeftClick {
+    action()
+}
+
+/// Tauri command: change what a left click on the tray icon does.
+/// Called from the settings window via `invoke('set_tray_left_click', { action })`.
+#[tauri::command]
+pub fn set_tray_left_click(action: LeftClick, app: AppHandle) -> Result<LeftClick, String> {
+    save(
+        &app,
+        TrayClickConfig {
+            left_click: action,
+            ..config()
+        },
+    )?;
+    if let Some(tray) = app.tray_by_id(crate::TRAY_ID) {
+        let _ = tray.set_show_menu_on_left_click(action == LeftClick::ShowMenu);
+    }
+    let _ = app.emit("tray-left-click-changed", action);
+    Ok(action)
+}
+
+/// Tauri command: all tray click and scroll actions.
+/// Called from the settings window via `invoke('get_tray_click_config')`.
+#[tauri::command]
+pub fn get_tray_click_config() -> TrayClickConfig {
+    config()
+}
+
+/// Tauri command: change what a middle click on the tray icon does.
+/// Called from the settings window via `invoke('set_tray_middle_click', { action })`.
+#[tauri::command]
+pub fn set_tray_middle_click(
+    action: MiddleClick,
+    app: AppHandle,
+) -> Result<TrayClickConfig, String> {
+    let config = TrayClickConfig {
+        middle_click: action,
+        ..config()
+    };
+    save(&app, config)?;
+    Ok(config)
+}
+
+/// Tauri command: change what the scroll wheel over the tray icon does (macOS only).
+/// Called from the settings window via `invoke('set_tray_scroll', { action })`.
+#[tauri::command]
+pub fn set_tray_scroll(action: Scroll, app: AppHandle) -> Result<TrayClickConfig, String> {
+    let config = TrayClickConfig {
+        scroll: action,
+        ..config()
+    };
+    save(&app, config)?;
+    Ok(config)
+}
+
+/// The status item's button doesn't handle `scrollWheel:`, so wheel events
+/// over it travel up the responder chain and are lost. Adding the method to
+/// `NSStatusBarButton` catches them (CrewHub has only one status item).
+#[cfg(target_os = "macos")]
+mod scroll {
+    use objc2::runtime::{AnyClass, AnyObject, Imp, Sel};
+    use objc2::{msg_send, sel};
+    use std::sync::OnceLock;
+    use tauri::{AppHandle, Runtime};
+
+    type ScrollWheel = extern "C-unwind" fn(&AnyObject, Sel, &AnyObject);
+
+    /// Runs the scroll action; set once in [`init`].
+    type Handler = Box<dyn Fn(bool) + Send + Sync>;
+    static HANDLER: OnceLock<Handler> = OnceLock::new();
+
+    extern "C-unwind" fn scroll_wheel(_this: &AnyObject, _cmd: Sel, event: &AnyObject) {
+        let delta: f64 = unsafe { msg_send![event, scrollingDeltaY] };
+        if delta != 0.0 {
+            if let Some(handler) = HANDLER.get() {
+                // Positive deltas scroll up (towards the previous window).
+                handler(delta < 0.0);
+            }
+        }
+    }
+
+    pub fn init<R: Runtime>(app: &AppHandle<R>) {
+        let app = app.clone();
+        if HANDLER
+            .set(Box::new(move |forward| super::handle_scroll(&app, forward)))
+            .is_err()
+        {
+            return;
+        }
+        let Some(class) = AnyClass::get(c"NSStatusBarButton") else {
+            return;
+        };
+        let imp: Imp = unsafe { std::mem::transmute::<ScrollWheel, Imp>(scroll_wheel) };
+        unsafe {
+            objc2::ffi::class_addMethod(
+                (class as *const AnyClass).cast_mut(),
+                sel!(scrollWheel:),
+                imp,
+                c"v@:@".as_ptr(),
+            );
+        }
+    }
+}
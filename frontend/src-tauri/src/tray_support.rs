@@ -0,0 +1,233 @@
+//! Whether the tray icon can actually be seen.
+//!
+//! On Linux the tray is a StatusNotifierItem served over D-Bus (the `ksni`
+//! backend of `tray-icon`, see `Cargo.toml`) rather than libappindicator,
+//! which never reports primary clicks; with it a left click "activates" the
+//! item and runs the configured action (see [`crate::tray_click`]). The icon
+//! only shows up when a StatusNotifierWatcher is on the session bus (KDE,
+//! XFCE, Cinnamon, GNOME with the AppIndicator extension, …). Shortly after
+//! startup the watcher is looked up. Windows and macOS always have a tray,
+//! but creating the icon can still fail.
+//!
+//! Without a tray CrewHub would be running but unreachable, since closing a
+//! window only hides it. [`fallback`] switches to trayless mode instead: the
+//! chat window is opened and closing it minimizes it to the taskbar/dock,
+//! and `tray-unavailable` is emitted for the frontend to explain why (and to
+//! offer Quit, which otherwise lives in the tray menu).
+//!
+//! Trayless operation can also be chosen (`tray.json`, for desktops that hide
+//! or forbid tray icons). It is one mode rather than a set of toggles: no
+//! tray icon is created, CrewHub lives in the Dock/taskbar (the `Regular`
+//! activation policy on macOS, whatever [`crate::activation_policy`] says;
+//! the settings window gets a taskbar entry), clicking the Dock icon opens
+//! the chat window, and closing the last window quits. Switching takes effect
+//! immediately.
+
+use crate::capabilities::{self, Capability};
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "tray.json";
+
+/// Set once there is no tray to bring hidden windows back.
+static TRAYLESS: AtomicBool = AtomicBool::new(false);
+
+/// Set while trayless operation is chosen in settings.
+static CHOSEN: AtomicBool = AtomicBool::new(false);
+
+const CHOSEN_REASON: &str = "Turned off in settings (run without a tray icon)";
+
+/// Windows that don't keep a trayless CrewHub running (overlays and pickers).
+const OVERLAY_LABELS: &[&str] = &[
+    crate::hud::HUD_WINDOW_LABEL,
+    crate::quick_prompt::QUICK_PROMPT_WINDOW_LABEL,
+    crate::switcher::SWITCHER_WINDOW_LABEL,
+];
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TrayConfig {
+    /// Run without a tray icon.
+    trayless: bool,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraySupport {
+    /// `statusNotifierItem` on Linux, `native` elsewhere.
+    backend: &'static str,
+    /// Whether a tray host is there to show the icon (`None` if unknown).
+    available: Option<bool>,
+    /// Whether left clicks reach CrewHub (otherwise they only open the menu).
+    primary_click: bool,
+    /// Whether CrewHub runs without a tray icon.
+    trayless: bool,
+    /// Whether that was chosen in settings (closing the last window quits)
+    /// rather than forced by a missing tray (closing the chat window minimizes it).
+    chosen: bool,
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::process::Command;
+
+    const WATCHER: &str = "org.kde.StatusNotifierWatcher";
+
+    /// Whether a StatusNotifierWatcher owns its bus name (`None` without `dbus-send`).
+    pub fn watcher_present() -> Option<bool> {
+        let output = Command::new("dbus-send")
+            .args([
+                "--session",
+                "--dest=org.freedesktop.DBus",
+                "--type=method_call",
+                "--print-reply",
+                "/org/freedesktop/DBus",
+                "org.freedesktop.DBus.NameHasOwner",
+                &format!("string:{}", WATCHER),
+            ])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).contains("boolean true"))
+    }
+}
+
+/// What the tray can do on this desktop.
+pub fn support() -> TraySupport {
+    let trayless = trayless();
+    #[cfg(target_os = "linux")]
+    return TraySupport {
+        backend: "statusNotifierItem",
+        available: if trayless {
+            Some(false)
+        } else {
+            platform::watcher_present()
+        },
+        primary_click: true,
+        trayless,
+        chosen: chosen(),
+    };
+    #[cfg(not(target_os = "linux"))]
+    TraySupport {
+        backend: "native",
+        available: Some(!trayless),
+        primary_click: true,
+        trayless,
+        chosen: chosen(),
+    }
+}
+
+/// Whether CrewHub runs without a tray (see [`fallback`] and [`chosen`]).
+pub fn trayless() -> bool {
+    TRAYLESS.load(Ordering::SeqCst) || chosen()
+}
+
+/// Whether trayless operation is chosen in settings.
+pub fn chosen() -> bool {
+    CHOSEN.load(Ordering::SeqCst)
+}
+
+/// Read the saved mode before the tray is set up (called from setup).
+pub fn load<R: Runtime>(app: &AppHandle<R>) {
+    let config: TrayConfig = store::load_json(app, CONFIG_FILE);
+    CHOSEN.store(config.trayless, Ordering::SeqCst);
+    if config.trayless {
+        capabilities::record(app, Capability::Tray, Err(CHOSEN_REASON.to_string()));
+    }
+}
+
+/// The user closed a window: with trayless operation chosen, quit once no
+/// other CrewHub window is visible.
+pub fn window_closed<R: Runtime>(app: &AppHandle<R>, label: &str) {
+    if !chosen() {
+        return;
+    }
+    let visible = app.webview_windows().into_iter().any(|(other, window)| {
+        other != label
+            && !OVERLAY_LABELS.contains(&other.as_str())
+            && !other.starts_with(crate::widgets::LABEL_PREFIX)
+            && window.is_visible().unwrap_or(false)
+    });
+    if !visible {
+        println!("[CrewHub] Last window closed; quitting (trayless mode)");
+        app.exit(0);
+    }
+}
+
+/// The tray icon was created.
+pub fn created<R: Runtime>(app: &AppHandle<R>) {
+    TRAYLESS.store(false, Ordering::SeqCst);
+    capabilities::record(app, Capability::Tray, Ok(()));
+}
+
+/// Keep CrewHub reachable without a tray (`reason`: why there is none).
+pub fn fallback<R: Runtime>(app: &AppHandle<R>, reason: String) {
+    capabilities::record(app, Capability::Tray, Err(reason));
+    if TRAYLESS.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    crate::open_or_focus_chat(app);
+    let _ = app.emit("tray-unavailable", support());
+}
+
+/// Check for a tray host once the desktop session had a moment to start
+/// (CrewHub may be autostarted before the panel), and fall back to a window.
+#[cfg(target_os = "linux")]
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_secs(5));
+        if !trayless() && platform::watcher_present() == Some(false) {
+            let reason = "No StatusNotifierWatcher on the session bus, so the tray icon won't \
+                          show; on GNOME install the AppIndicator extension";
+            fallback(&app, reason.to_string());
+        }
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn init<R: Runtime>(_app: &AppHandle<R>) {}
+
+/// Tauri command: tray backend and whether the icon can be seen.
+/// Called from the settings window via `invoke('get_tray_support')`.
+#[tauri::command]
+pub fn get_tray_support() -> TraySupport {
+    support()
+}
+
+/// Tauri command: run with or without a tray icon, switching now and remembering it.
+/// Called from the settings window via `invoke('set_trayless', { enabled })`.
+#[tauri::command]
+pub fn set_trayless(enabled: bool, app: AppHandle) -> Result<TraySupport, String> {
+    store::save_json(&app, CONFIG_FILE, &TrayConfig { trayless: enabled })?;
+    CHOSEN.store(enabled, Ordering::SeqCst);
+    if enabled {
+        crate::open_or_focus_chat(&app);
+        let _ = app.remove_tray_by_id(crate::TRAY_ID);
+        capabilities::record(&app, Capability::Tray, Err(CHOSEN_REASON.to_string()));
+    } else if app.tray_by_id(crate::TRAY_ID).is_none() {
+        match crate::setup_tray(&app) {
+            Ok(()) => {
+                created(&app);
+                crate::refresh_tray_icon(&app);
+            }
+            Err(e) => fallback(&app, format!("The tray icon could not be created: {}", e)),
+        }
+    }
+    crate::activation_policy::init(&app);
+    let support = support();
+    let _ = app.emit("tray-mode-changed", support);
+    Ok(support)
+}
+
+/// Tauri command: quit CrewHub (offered by the frontend when there is no tray menu).
+/// Called from the chat window via `invoke('quit_app')`.
+#[tauri::command]
+pub fn quit_app(app: AppHandle) {
+    app.exit(0);
+}
@@ -0,0 +1,95 @@
+//! Background worker for tray icon, tooltip and menu updates.
+//!
+//! Badge changes can arrive in storms (a backend replaying unread counts,
+//! agents flipping state), and each used to load a PNG from disk and rebuild
+//! the menu on the caller's thread — often the IPC thread. Callers now only
+//! mark what is stale; the worker waits a moment so a burst collapses into
+//! one update, then applies it. Decoded icons are cached by path.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::{AppHandle, Runtime};
+
+/// How long the worker lets further requests pile up before applying them.
+const COALESCE_DELAY: Duration = Duration::from_millis(50);
+
+static ICON_STALE: AtomicBool = AtomicBool::new(false);
+static MENU_STALE: AtomicBool = AtomicBool::new(false);
+
+/// Wakes the worker; unset until [`init`], when updates are applied inline.
+static WAKE: OnceLock<Mutex<Sender<()>>> = OnceLock::new();
+
+/// Decoded icons by path.
+static ICONS: Mutex<Option<HashMap<PathBuf, Image<'static>>>> = Mutex::new(None);
+
+/// Start the worker (called once the tray exists).
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let (sender, receiver) = mpsc::channel::<()>();
+    if WAKE.set(Mutex::new(sender)).is_err() {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        while receiver.recv().is_ok() {
+            std::thread::sleep(COALESCE_DELAY);
+            while receiver.try_recv().is_ok() {}
+            apply(&app);
+        }
+    });
+}
+
+fn apply<R: Runtime>(app: &AppHandle<R>) {
+    if MENU_STALE.swap(false, Ordering::SeqCst) {
+        crate::apply_tray_menu(app);
+    }
+    if ICON_STALE.swap(false, Ordering::SeqCst) {
+        crate::apply_tray_icon(app);
+        #[cfg(target_os = "linux")]
+        crate::dbus::state_changed(app);
+    }
+}
+
+fn wake<R: Runtime>(app: &AppHandle<R>) {
+    let sent = WAKE
+        .get()
+        .and_then(|wake| wake.lock().ok().map(|sender| sender.send(()).is_ok()))
+        .unwrap_or(false);
+    if !sent {
+        apply(app);
+    }
+}
+
+/// Schedule a tray icon and tooltip update.
+pub fn icon<R: Runtime>(app: &AppHandle<R>) {
+    ICON_STALE.store(true, Ordering::SeqCst);
+    wake(app);
+}
+
+/// Schedule a tray menu rebuild.
+pub fn menu<R: Runtime>(app: &AppHandle<R>) {
+    MENU_STALE.store(true, Ordering::SeqCst);
+    wake(app);
+}
+
+/// Load an icon, decoding each file only once.
+pub fn load_icon(path: &Path) -> Result<Image<'static>, String> {
+    if let Some(icon) = ICONS
+        .lock()
+        .ok()
+        .and_then(|icons| icons.as_ref()?.get(path).cloned())
+    {
+        return Ok(icon);
+    }
+    let icon = Image::from_path(path).map_err(|e| e.to_string())?;
+    if let Ok(mut icons) = ICONS.lock() {
+        icons
+            .get_or_insert_with(HashMap::new)
+            .insert(path.to_path_buf(), icon.clone());
+    }
+    Ok(icon)
+}
@@ -0,0 +1,71 @@
+//! Per-window UI scale override.
+//!
+//! On mixed-DPI setups a window can end up rendering tiny (or huge) on one
+//! monitor no matter what the OS scale says. The user can pin a scale factor
+//! per window label; it is applied as the webview's zoom as soon as the
+//! webview is created ([`plugin`]) and whenever it is changed, and persisted
+//! in `ui-scale.json`. This is separate from any zoom the frontend applies
+//! itself, which multiplies on top.
+
+use crate::store;
+use std::collections::BTreeMap;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Persisted overrides by window label (app data dir).
+const CONFIG_FILE: &str = "ui-scale.json";
+
+const MIN_SCALE: f64 = 0.5;
+const MAX_SCALE: f64 = 3.0;
+
+fn overrides<R: Runtime>(app: &AppHandle<R>) -> BTreeMap<String, f64> {
+    store::load_json(app, CONFIG_FILE)
+}
+
+/// Plugin that applies the saved scale to every new webview.
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("ui-scale")
+        .on_webview_ready(|webview| {
+            let scale = overrides(webview.app_handle())
+                .get(webview.label())
+                .copied();
+            if let Some(scale) = scale {
+                if let Err(e) = webview.set_zoom(scale) {
+                    eprintln!("[CrewHub] Failed to scale '{}': {}", webview.label(), e);
+                }
+            }
+        })
+        .build()
+}
+
+/// Tauri command: scale a window's UI by `factor` (1.0 removes the override).
+/// Called from the settings window via `invoke('set_ui_scale', { label, factor })`.
+#[tauri::command]
+pub fn set_ui_scale(
+    label: String,
+    factor: f64,
+    app: AppHandle,
+) -> Result<BTreeMap<String, f64>, String> {
+    if !factor.is_finite() {
+        return Err("Invalid scale factor".to_string());
+    }
+    let factor = factor.clamp(MIN_SCALE, MAX_SCALE);
+    let mut overrides = overrides(&app);
+    if (factor - 1.0).abs() < f64::EPSILON {
+        overrides.remove(&label);
+    } else {
+        overrides.insert(label.clone(), factor);
+    }
+    store::save_json(&app, CONFIG_FILE, &overrides)?;
+    if let Some(webview) = app.get_webview_window(&label) {
+        webview.set_zoom(factor).map_err(|e| e.to_string())?;
+    }
+    Ok(overrides)
+}
+
+/// Tauri command: the scale overrides by window label.
+/// Called from the settings window via `invoke('get_ui_scales')`.
+#[tauri::command]
+pub fn get_ui_scales(app: AppHandle) -> BTreeMap<String, f64> {
+    overrides(&app)
+}
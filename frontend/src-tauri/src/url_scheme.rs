@@ -0,0 +1,496 @@
+//! `crewhub://` links.
+//!
+//! Links such as `crewhub://chat/<sessionKey>` and `crewhub://agent/<agentId>`
+//! (the `url`s returned by the control API's search) start CrewHub with the
+//! link as its argument — handed to the running instance by the
+//! single-instance plugin, see [`crate::launch`] — or, on macOS, arrive as
+//! `RunEvent::Opened`. They map onto control actions; `?backend=<id>` picks
+//! the backend of a conversation; `crewhub://x-callback-url/…` links are
+//! automation requests (see [`crate::x_callback`]); `crewhub://handover/…`
+//! links continue a web UI session (see [`crate::session_handover`]); and on
+//! macOS `https://go.crewhub.app/<path>` universal links route like
+//! `crewhub://<path>` (see [`crate::handoff`]).
+//!
+//! | Link                          | Window   | Action              |
+//! |-------------------------------|----------|---------------------|
+//! | `crewhub://chat`              | chat     | `open-chat`         |
+//! | `crewhub://chat/<sessionKey>` | chat     | `open-conversation` |
+//! | `crewhub://agent/<agentId>`   | chat     | `open-agent`        |
+//! | `crewhub://world`             | world    | `open-world`        |
+//! | `crewhub://world/room/<id>`   | world    | `open-room`         |
+//! | `crewhub://zen`               | zen-mode | `start-focus`       |
+//! | `crewhub://settings`          | settings | `open-settings`     |
+//! | `crewhub://handover/<token>`  | chat     | `open-conversation` |
+//!
+//! After the action the window also gets a `deep-link` event ([`DeepLink`])
+//! to navigate by; a window the link had to create fetches it with
+//! `take_pending_deep_link` once loaded.
+//!
+//! How the scheme is registered depends on the platform:
+//! - Linux: a hidden desktop entry set as the `x-scheme-handler/crewhub` default;
+//! - Windows: `HKCU\Software\Classes\crewhub`;
+//! - macOS: `CFBundleURLTypes` in the bundle's `Info.plist`, registered with
+//!   Launch Services.
+//!
+//! Reinstalling or moving the app can leave the registration (and the share
+//! target's file associations, see [`crate::share`]) pointing at a stale
+//! executable; the settings window can check and repair both.
+
+#[cfg(target_os = "macos")]
+use crate::{
+    backend,
+    pins::{self, ConversationRef},
+};
+use crate::{control, session_handover, share, x_callback};
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+use url::Url;
+
+pub const SCHEME: &str = "crewhub";
+
+/// Host of universal links; they show the website where CrewHub isn't installed.
+#[cfg(target_os = "macos")]
+pub const UNIVERSAL_LINK_HOST: &str = "go.crewhub.app";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandlerStatus {
+    pub scheme: &'static str,
+    /// Whether `crewhub://` links open this copy of CrewHub.
+    pub registered: bool,
+    /// What currently handles the scheme: desktop entry, command line or bundle id.
+    pub handler: Option<String>,
+    pub file_associations: share::ShareTargetStatus,
+}
+
+/// Deep links waiting for the window they open to load (label → link).
+static PENDING: Mutex<Option<HashMap<String, DeepLink>>> = Mutex::new(None);
+
+/// Payload of the `deep-link` event.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLink {
+    url: String,
+    /// Label of the window the link was routed to.
+    window: &'static str,
+    /// Control action performed for it.
+    action: &'static str,
+    args: Value,
+}
+
+/// Where a link goes: `crewhub://<kind>/<path…>` or `https://<host>/<kind>/<path…>`.
+fn route(url: &Url) -> Option<(&'static str, &'static str, Value)> {
+    let mut segments = url.path_segments()?;
+    let kind = if url.scheme() == SCHEME {
+        url.host_str()?
+    } else {
+        segments.next()?
+    };
+    // Ids are form-encoded (see `backend::urlencode`).
+    let path = segments
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            percent_decode_str(&segment.replace('+', " "))
+                .decode_utf8()
+                .ok()
+                .map(|segment| segment.to_string())
+        })
+        .collect::<Option<Vec<String>>>()?;
+    let path: Vec<&str> = path.iter().map(String::as_str).collect();
+    match (kind, path.as_slice()) {
+        ("chat", []) => Some((crate::CHAT_WINDOW_LABEL, "open-chat", json!({}))),
+        ("chat", [id]) => {
+            let mut args = json!({ "sessionKey": id });
+            if let Some((_, backend)) = url.query_pairs().find(|(key, _)| key == "backend") {
+                args["backendId"] = json!(backend);
+            }
+            Some((crate::CHAT_WINDOW_LABEL, "open-conversation", args))
+        }
+        ("agent", [id]) => Some((
+            crate::CHAT_WINDOW_LABEL,
+            "open-agent",
+            json!({ "agentId": id }),
+        )),
+        ("world", []) => Some((crate::WORLD_WINDOW_LABEL, "open-world", json!({}))),
+        ("world", ["room", id]) => Some((
+            crate::WORLD_WINDOW_LABEL,
+            "open-room",
+            json!({ "roomId": id }),
+        )),
+        ("zen", []) => Some((crate::ZEN_WINDOW_LABEL, "start-focus", json!({}))),
+        ("settings", []) => Some((crate::SETTINGS_WINDOW_LABEL, "open-settings", json!({}))),
+        _ => None,
+    }
+}
+
+/// Universal link to a conversation.
+#[cfg(target_os = "macos")]
+pub fn universal_link(conversation: &ConversationRef) -> String {
+    let mut link = format!(
+        "https://{}/chat/{}",
+        UNIVERSAL_LINK_HOST,
+        backend::urlencode(&conversation.session_key)
+    );
+    if conversation.backend_id != pins::default_backend() {
+        link.push_str(&format!(
+            "?backend={}",
+            backend::urlencode(&conversation.backend_id)
+        ));
+    }
+    link
+}
+
+/// Open a universal link in the app. Returns false for links it doesn't handle.
+#[cfg(target_os = "macos")]
+pub fn open_universal<R: Runtime>(app: &AppHandle<R>, url: &Url) -> bool {
+    if url.host_str() != Some(UNIVERSAL_LINK_HOST) || route(url).is_none() {
+        return false;
+    }
+    open(app, url);
+    true
+}
+
+/// Handle `crewhub://` links among launch arguments. Returns whether there were any.
+pub fn handle_args<R: Runtime>(app: &AppHandle<R>, args: &[String]) -> bool {
+    let urls: Vec<Url> = args
+        .iter()
+        .filter_map(|arg| Url::parse(arg).ok())
+        .filter(|url| url.scheme() == SCHEME)
+        .collect();
+    for url in &urls {
+        open(app, url);
+    }
+    !urls.is_empty()
+}
+
+fn open<R: Runtime>(app: &AppHandle<R>, url: &Url) {
+    if url.scheme() == SCHEME && url.host_str() == Some(x_callback::HOST) {
+        x_callback::handle(app, url);
+        return;
+    }
+    if url.scheme() == SCHEME && url.host_str() == Some(session_handover::HOST) {
+        session_handover::handle(app, url);
+        return;
+    }
+    let Some((window, action, args)) = route(url) else {
+        eprintln!("[CrewHub] Unsupported link: {}", url);
+        return;
+    };
+    deliver(app, url, window, action, args);
+}
+
+/// Perform `action` for `url` and send the window its `deep-link` event.
+pub fn deliver<R: Runtime>(
+    app: &AppHandle<R>,
+    url: &Url,
+    window: &'static str,
+    action: &'static str,
+    args: Value,
+) {
+    let already_open = app.get_webview_window(window).is_some();
+    if let Err(e) = control::perform(app, action, &args) {
+        eprintln!("[CrewHub] Link '{}' failed: {}", url, e);
+        return;
+    }
+    let link = DeepLink {
+        url: url.to_string(),
+        window,
+        action,
+        args,
+    };
+    if already_open {
+        let _ = app.emit_to(window, "deep-link", &link);
+    } else if let Ok(mut pending) = PENDING.lock() {
+        pending
+            .get_or_insert_with(HashMap::new)
+            .insert(window.to_string(), link);
+    }
+}
+
+/// Handle `crewhub://` links opened on macOS; returns the other URLs (shared files).
+#[cfg(target_os = "macos")]
+pub fn handle_opened<R: Runtime>(app: &AppHandle<R>, urls: &[Url]) -> Vec<Url> {
+    let (links, rest): (Vec<_>, Vec<_>) =
+        urls.iter().cloned().partition(|url| url.scheme() == SCHEME);
+    for url in &links {
+        open(app, url);
+    }
+    rest
+}
+
+#[cfg(not(target_os = "macos"))]
+fn exe() -> Result<String, String> {
+    std::env::current_exe()
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+mod platform {
+    use super::{exe, SCHEME};
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    const DESKTOP_ENTRY: &str = "dev.crewhub.url-handler.desktop";
+
+    fn entry_path() -> Result<PathBuf, String> {
+        let data = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+            .ok_or("No home directory")?;
+        Ok(data.join("applications").join(DESKTOP_ENTRY))
+    }
+
+    fn mime_type() -> String {
+        format!("x-scheme-handler/{}", SCHEME)
+    }
+
+    /// The default handler's desktop entry and whether it is ours, pointing at this executable.
+    pub fn status() -> Result<(Option<String>, bool), String> {
+        let output = Command::new("xdg-mime")
+            .args(["query", "default", &mime_type()])
+            .output()
+            .map_err(|e| format!("xdg-mime: {}", e))?;
+        let handler = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if handler.is_empty() {
+            return Ok((None, false));
+        }
+        let current = handler == DESKTOP_ENTRY
+            && std::fs::read_to_string(entry_path()?)
+                .map(|entry| entry.contains(&format!("Exec=\"{}\"", exe().unwrap_or_default())))
+                .unwrap_or(false);
+        Ok((Some(handler), current))
+    }
+
+    pub fn register() -> Result<(), String> {
+        let path = entry_path()?;
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName=CrewHub\nIcon=crewhub\n\
+             Exec=\"{}\" %u\nMimeType={};\nNoDisplay=true\nTerminal=false\n",
+            exe()?,
+            mime_type()
+        );
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, entry).map_err(|e| e.to_string())?;
+        let set = Command::new("xdg-mime")
+            .args(["default", DESKTOP_ENTRY, &mime_type()])
+            .status()
+            .map_err(|e| format!("xdg-mime: {}", e))?;
+        if !set.success() {
+            return Err("xdg-mime failed to set the default handler".to_string());
+        }
+        // Refresh the MIME cache; optional.
+        if let Some(parent) = path.parent() {
+            let _ = Command::new("update-desktop-database").arg(parent).status();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{exe, SCHEME};
+    use std::os::windows::process::CommandExt;
+    use std::process::{Command, Output};
+
+    fn reg(args: &[&str]) -> Result<Output, String> {
+        Command::new("reg")
+            .args(args)
+            // CREATE_NO_WINDOW
+            .creation_flags(0x0800_0000)
+            .output()
+            .map_err(|e| e.to_string())
+    }
+
+    fn command() -> Result<String, String> {
+        Ok(format!("\"{}\" \"%1\"", exe()?))
+    }
+
+    /// The registered command line and whether it starts this executable.
+    pub fn status() -> Result<(Option<String>, bool), String> {
+        // HKCR merges the per-user and machine-wide (installer) registrations.
+        let key = format!(r"HKCR\{}\shell\open\command", SCHEME);
+        let output = reg(&["query", &key, "/ve"])?;
+        if !output.status.success() {
+            return Ok((None, false));
+        }
+        let handler = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.split_once("REG_SZ").map(|(_, v)| v.trim().to_string()));
+        let current = handler.as_deref() == Some(command()?.as_str());
+        Ok((handler, current))
+    }
+
+    pub fn register() -> Result<(), String> {
+        let exe = exe()?;
+        let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+        let icon_key = format!(r"{}\DefaultIcon", key);
+        let command_key = format!(r"{}\shell\open\command", key);
+        let command = command()?;
+        for args in [
+            vec!["add", &key, "/ve", "/d", "URL:CrewHub", "/f"],
+            vec!["add", &key, "/v", "URL Protocol", "/d", "", "/f"],
+            vec!["add", &icon_key, "/ve", "/d", &exe, "/f"],
+            vec!["add", &command_key, "/ve", "/d", &command, "/f"],
+        ] {
+            if !reg(&args)?.status.success() {
+                return Err(format!("Failed to write {}", key));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::SCHEME;
+    use objc2::rc::Retained;
+    use objc2_foundation::NSString;
+    use std::path::PathBuf;
+
+    const LSREGISTER: &str = "/System/Library/Frameworks/CoreServices.framework\
+        /Frameworks/LaunchServices.framework/Support/lsregister";
+
+    #[link(name = "CoreServices", kind = "framework")]
+    extern "C" {
+        // CFStringRef is toll-free bridged with NSString.
+        fn LSCopyDefaultHandlerForURLScheme(scheme: &NSString) -> *mut NSString;
+        fn LSSetDefaultHandlerForURLScheme(scheme: &NSString, handler: &NSString) -> i32;
+    }
+
+    fn bundle() -> Result<PathBuf, String> {
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        exe.ancestors()
+            .find(|dir| dir.extension().is_some_and(|ext| ext == "app"))
+            .map(PathBuf::from)
+            .ok_or_else(|| "Not running from an app bundle".to_string())
+    }
+
+    /// The default handler's bundle id and whether it is this app.
+    pub fn status(identifier: &str) -> Result<(Option<String>, bool), String> {
+        let scheme = NSString::from_str(SCHEME);
+        // SAFETY: "Copy" function, so we own the returned string (or null).
+        let handler = unsafe { Retained::from_raw(LSCopyDefaultHandlerForURLScheme(&scheme)) }
+            .map(|handler| handler.to_string());
+        let current = handler
+            .as_deref()
+            .is_some_and(|handler| handler.eq_ignore_ascii_case(identifier));
+        Ok((handler, current))
+    }
+
+    pub fn register(identifier: &str) -> Result<(), String> {
+        let bundle = bundle()?;
+        // Re-read the bundle's Info.plist (URL types, document types).
+        let registered = std::process::Command::new(LSREGISTER)
+            .arg("-f")
+            .arg(&bundle)
+            .status()
+            .map_err(|e| format!("lsregister: {}", e))?;
+        if !registered.success() {
+            return Err(format!("lsregister failed for {}", bundle.display()));
+        }
+        let scheme = NSString::from_str(SCHEME);
+        let handler = NSString::from_str(identifier);
+        // SAFETY: both arguments are valid strings.
+        let status = unsafe { LSSetDefaultHandlerForURLScheme(&scheme, &handler) };
+        if status != 0 {
+            return Err(format!("Launch Services error {}", status));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn platform_status<R: Runtime>(_app: &AppHandle<R>) -> Result<(Option<String>, bool), String> {
+    platform::status()
+}
+
+#[cfg(target_os = "macos")]
+fn platform_status<R: Runtime>(app: &AppHandle<R>) -> Result<(Option<String>, bool), String> {
+    platform::status(&app.config().identifier)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn platform_register<R: Runtime>(_app: &AppHandle<R>) -> Result<(), String> {
+    platform::register()
+}
+
+#[cfg(target_os = "macos")]
+fn platform_register<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    platform::register(&app.config().identifier)
+}
+
+fn status<R: Runtime>(app: &AppHandle<R>) -> Result<HandlerStatus, String> {
+    let (handler, registered) = platform_status(app)?;
+    Ok(HandlerStatus {
+        scheme: SCHEME,
+        registered,
+        handler,
+        file_associations: share::get_share_target_status()?,
+    })
+}
+
+/// Register the scheme at startup unless something already handles it.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    // The bundle registers it on macOS.
+    if cfg!(target_os = "macos") {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let result = match platform_status(&app) {
+            Ok((None, _)) => platform_register(&app),
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            eprintln!("[CrewHub] Failed to register {}:// links: {}", SCHEME, e);
+        }
+    });
+}
+
+/// Whether `crewhub://` links open this copy of CrewHub.
+pub fn is_registered<R: Runtime>(app: &AppHandle<R>) -> Result<bool, String> {
+    platform_status(app).map(|(_, registered)| registered)
+}
+
+/// (Re)register the scheme, and refresh the file associations if they are enabled.
+pub fn repair<R: Runtime>(app: &AppHandle<R>) -> Result<HandlerStatus, String> {
+    platform_register(app)?;
+    if share::get_share_target_status()?.registered {
+        share::set_share_target(true)?;
+    }
+    status(app)
+}
+
+/// Tauri command: the deep link that opened the calling window, if any.
+/// Called from the frontend after loading via `invoke('take_pending_deep_link')`.
+#[tauri::command]
+pub fn take_pending_deep_link(window: WebviewWindow) -> Option<DeepLink> {
+    PENDING.lock().ok()?.as_mut()?.remove(window.label())
+}
+
+/// Tauri command: whether `crewhub://` links and file associations point at this copy of CrewHub.
+/// Called from the settings window via `invoke('get_link_handler_status')`.
+#[tauri::command]
+pub async fn get_link_handler_status(app: AppHandle) -> Result<HandlerStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || status(&app))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: (re)register CrewHub for `crewhub://` links, and refresh the
+/// file associations if they are enabled.
+/// Called from the settings window via `invoke('repair_link_handler')`.
+#[tauri::command]
+pub async fn repair_link_handler(app: AppHandle) -> Result<HandlerStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || repair(&app))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,247 @@
+//! Local usage statistics for the weekly review.
+//!
+//! Counts time spent in each window, messages sent, focus sessions completed
+//! and agent runs, aggregated per day in SQLite. Window time is sampled here
+//! (the focused window accrues time); agent runs come from presence changes;
+//! messages and focus sessions are reported by the frontend. Nothing in this
+//! store ever leaves the machine.
+
+use crate::db;
+use chrono::{Duration, Local};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime, State};
+
+/// How often the focused window is sampled.
+const SAMPLE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Metric {
+    /// Seconds with a window focused; key is the window.
+    WindowSeconds,
+    /// Chat messages sent by the user; key is the session.
+    MessageSent,
+    /// Focus (Zen) sessions completed.
+    FocusSession,
+    /// Agent runs started; key is the agent name.
+    AgentRun,
+}
+
+impl Metric {
+    fn as_str(self) -> &'static str {
+        match self {
+            Metric::WindowSeconds => "windowSeconds",
+            Metric::MessageSent => "messageSent",
+            Metric::FocusSession => "focusSession",
+            Metric::AgentRun => "agentRun",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StatsRange {
+    Today,
+    Week,
+    Month,
+}
+
+impl StatsRange {
+    fn days(self) -> i64 {
+        match self {
+            StatsRange::Today => 1,
+            StatsRange::Week => 7,
+            StatsRange::Month => 30,
+        }
+    }
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayStats {
+    day: String,
+    active_seconds: f64,
+    messages_sent: f64,
+    focus_sessions: f64,
+    agent_runs: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStats {
+    since: String,
+    until: String,
+    window_seconds: BTreeMap<String, f64>,
+    agent_runs_by_agent: BTreeMap<String, f64>,
+    totals: DayStats,
+    /// One entry per day in the range, oldest first (days without activity included).
+    daily: Vec<DayStats>,
+}
+
+/// App state: database connection for the stats table.
+pub struct UsageStatsState {
+    conn: Mutex<Connection>,
+}
+
+/// Open the stats table, register state, and start sampling window focus.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let conn = db::open(app)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS usage_stats (
+            day TEXT NOT NULL,
+            metric TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value REAL NOT NULL,
+            PRIMARY KEY (day, metric, key)
+        );",
+    )
+    .map_err(|e| e.to_string())?;
+    app.manage(UsageStatsState {
+        conn: Mutex::new(conn),
+    });
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SAMPLE_INTERVAL);
+        sample_focus(&app);
+    });
+    Ok(())
+}
+
+fn day(offset_days: i64) -> String {
+    (Local::now() - Duration::days(offset_days))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// Add `amount` to today's value of a metric (no-op if stats are unavailable).
+pub fn record<R: Runtime>(app: &AppHandle<R>, metric: Metric, key: &str, amount: f64) {
+    let Some(state) = app.try_state::<UsageStatsState>() else {
+        return;
+    };
+    let Ok(conn) = state.conn.lock() else {
+        return;
+    };
+    if let Err(e) = conn.execute(
+        "INSERT INTO usage_stats (day, metric, key, value) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(day, metric, key) DO UPDATE SET value = value + excluded.value",
+        params![day(0), metric.as_str(), key, amount],
+    ) {
+        eprintln!("[CrewHub] Failed to record usage stat: {}", e);
+    }
+}
+
+/// Credit the sample interval to whichever window has focus.
+fn sample_focus<R: Runtime>(app: &AppHandle<R>) {
+    let focused = app
+        .webview_windows()
+        .into_iter()
+        .find(|(_, window)| window.is_focused().unwrap_or(false));
+    if let Some((label, _)) = focused {
+        // All widget windows count as one.
+        let key = if label.starts_with("widget-") {
+            "widget"
+        } else {
+            label.as_str()
+        };
+        record(
+            app,
+            Metric::WindowSeconds,
+            key,
+            SAMPLE_INTERVAL.as_secs_f64(),
+        );
+    }
+}
+
+/// Tauri command: record a usage event reported by the frontend
+/// (`messageSent` with the session key, or `focusSession`).
+/// Called from the frontend via `invoke('record_usage_event', { metric, key })`.
+#[tauri::command]
+pub fn record_usage_event(
+    metric: Metric,
+    key: Option<String>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if matches!(metric, Metric::WindowSeconds | Metric::AgentRun) {
+        return Err("This metric is tracked by the app itself".to_string());
+    }
+    record(&app, metric, &key.unwrap_or_default(), 1.0);
+    Ok(())
+}
+
+/// Tauri command: usage statistics for today, the last 7 days or the last 30 days.
+/// Called from the frontend via `invoke('get_usage_stats', { range })`.
+#[tauri::command]
+pub fn get_usage_stats(
+    range: StatsRange,
+    state: State<UsageStatsState>,
+) -> Result<UsageStats, String> {
+    let since = day(range.days() - 1);
+    let mut daily: BTreeMap<String, DayStats> = (0..range.days())
+        .map(|offset| {
+            let day = day(offset);
+            (
+                day.clone(),
+                DayStats {
+                    day,
+                    ..Default::default()
+                },
+            )
+        })
+        .collect();
+    let mut window_seconds = BTreeMap::new();
+    let mut agent_runs_by_agent = BTreeMap::new();
+
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT day, metric, key, value FROM usage_stats WHERE day >= ?1")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![since], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, f64>(3)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    for row in rows {
+        let (day, metric, key, value) = row.map_err(|e| e.to_string())?;
+        let Some(stats) = daily.get_mut(&day) else {
+            continue;
+        };
+        match metric.as_str() {
+            "windowSeconds" => {
+                stats.active_seconds += value;
+                *window_seconds.entry(key).or_insert(0.0) += value;
+            }
+            "messageSent" => stats.messages_sent += value,
+            "focusSession" => stats.focus_sessions += value,
+            "agentRun" => {
+                stats.agent_runs += value;
+                *agent_runs_by_agent.entry(key).or_insert(0.0) += value;
+            }
+            _ => {}
+        }
+    }
+
+    let daily: Vec<DayStats> = daily.into_values().collect();
+    let totals = daily.iter().fold(DayStats::default(), |mut acc, d| {
+        acc.active_seconds += d.active_seconds;
+        acc.messages_sent += d.messages_sent;
+        acc.focus_sessions += d.focus_sessions;
+        acc.agent_runs += d.agent_runs;
+        acc
+    });
+    Ok(UsageStats {
+        since,
+        until: day(0),
+        window_seconds,
+        agent_runs_by_agent,
+        totals,
+        daily,
+    })
+}
@@ -0,0 +1,106 @@
+//! Wayland-aware placement for windows pinned to screen edges.
+//!
+//! Wayland clients can't position their own toplevel windows: the HUD's
+//! corner placement and snapping ([`crate::snap`]) are silently ignored by
+//! the compositor. [`placement`] tells callers what they can rely on:
+//! - `absolute` — X11, macOS, Windows: move the window yourself;
+//! - `layerShell` — a wlroots or KDE compositor with wlr-layer-shell, and a
+//!   build with the `layer-shell` feature: [`anchor`] pins the window to
+//!   screen edges as an overlay surface;
+//! - `compositor` — any other Wayland session: the compositor decides, so
+//!   windows are left where it puts them and the frontend offers to drag them.
+
+use serde::Serialize;
+use std::sync::OnceLock;
+use tauri::{Runtime, WebviewWindow};
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Placement {
+    Absolute,
+    LayerShell,
+    Compositor,
+}
+
+/// Whether the app talks to a Wayland compositor directly (not through XWayland).
+pub fn is_wayland() -> bool {
+    static WAYLAND: OnceLock<bool> = OnceLock::new();
+    *WAYLAND.get_or_init(|| {
+        if !cfg!(target_os = "linux") {
+            return false;
+        }
+        let x11_forced = std::env::var("GDK_BACKEND").is_ok_and(|b| b.starts_with("x11"));
+        let session = std::env::var_os("WAYLAND_DISPLAY").is_some()
+            || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland");
+        session && !x11_forced
+    })
+}
+
+/// How windows pinned to screen edges can be placed in this session.
+pub fn placement() -> Placement {
+    if !is_wayland() {
+        Placement::Absolute
+    } else if layer_shell_supported() {
+        Placement::LayerShell
+    } else {
+        Placement::Compositor
+    }
+}
+
+/// Whether the compositor offers wlr-layer-shell (GTK must be initialized).
+#[cfg(all(target_os = "linux", feature = "layer-shell"))]
+fn layer_shell_supported() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(gtk_layer_shell::is_supported)
+}
+
+#[cfg(not(all(target_os = "linux", feature = "layer-shell")))]
+fn layer_shell_supported() -> bool {
+    false
+}
+
+/// Turn `window` into an overlay layer surface anchored to the top or bottom
+/// and left or right screen edge, `margin` logical pixels in. Must run on the
+/// main thread; the window is unrealized and shown again in the process.
+#[cfg(all(target_os = "linux", feature = "layer-shell"))]
+pub fn anchor<R: Runtime>(
+    window: &WebviewWindow<R>,
+    top: bool,
+    left: bool,
+    margin: i32,
+) -> Result<(), String> {
+    use gtk::prelude::*;
+    use gtk_layer_shell::{Edge, Layer, LayerShell};
+
+    let gtk_window = window.gtk_window().map_err(|e| e.to_string())?;
+    if !gtk_window.is_layer_window() {
+        // Layer surfaces have to be set up before the window is realized.
+        gtk_window.hide();
+        gtk_window.unrealize();
+        gtk_window.init_layer_shell();
+        gtk_window.set_namespace("crewhub");
+        gtk_window.set_layer(Layer::Overlay);
+        gtk_window.set_keyboard_interactivity(false);
+    }
+    for (edge, anchored) in [
+        (Edge::Top, top),
+        (Edge::Bottom, !top),
+        (Edge::Left, left),
+        (Edge::Right, !left),
+    ] {
+        gtk_window.set_anchor(edge, anchored);
+        gtk_window.set_layer_shell_margin(edge, if anchored { margin } else { 0 });
+    }
+    gtk_window.show_all();
+    Ok(())
+}
+
+#[cfg(not(all(target_os = "linux", feature = "layer-shell")))]
+pub fn anchor<R: Runtime>(
+    _window: &WebviewWindow<R>,
+    _top: bool,
+    _left: bool,
+    _margin: i32,
+) -> Result<(), String> {
+    Err("Layer-shell support is not built in".to_string())
+}
@@ -0,0 +1,223 @@
+//! Opt-in local webhook listener for external tools (git hooks, CI, scripts).
+//!
+//! Endpoints (all on 127.0.0.1, bearer-token protected):
+//! - `POST /hooks/run`     — body is a [`RunTarget`], e.g. `{"kind":"pipeline","pipelineId":"…"}`
+//! - `POST /hooks/message` — body `{"sessionKey":"…","message":"…"}`
+//! - `GET  /hooks/health`  — liveness check
+//!
+//! Requests are accepted immediately (202) and forwarded to the backend on a
+//! background thread, so a slow agent never blocks the caller's hook.
+
+use crate::backend::RunTarget;
+use crate::{local_http, store};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "webhook.json";
+
+const DEFAULT_PORT: u16 = 8094;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WebhookConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: local_http::new_token(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageBody {
+    session_key: String,
+    message: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookStatus {
+    #[serde(flatten)]
+    config: WebhookConfig,
+    running: bool,
+    url: String,
+}
+
+/// App state: webhook config plus the running listener (if any).
+pub struct WebhookState {
+    config: Mutex<WebhookConfig>,
+    server: Mutex<Option<local_http::Listener>>,
+}
+
+/// Load the config, register state, and start the listener if the user opted in.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let config: WebhookConfig = store::load_json(app, CONFIG_FILE);
+    // Persist a token generated for a missing one, so senders keep working
+    // across restarts.
+    if let Err(e) = store::save_json(app, CONFIG_FILE, &config) {
+        eprintln!("[CrewHub] {}", e);
+    }
+    let enabled = config.enabled;
+    let port = config.port;
+    app.manage(WebhookState {
+        config: Mutex::new(config),
+        server: Mutex::new(None),
+    });
+    if enabled {
+        if let Err(e) = start(app, port) {
+            eprintln!("[CrewHub] Failed to start webhook listener: {}", e);
+        }
+    }
+}
+
+/// Listen on `port`; a listener already running on another port is
+/// replaced once the new one is bound, one on the same port is kept.
+fn start<R: Runtime>(app: &AppHandle<R>, port: u16) -> Result<(), String> {
+    let state = app.state::<WebhookState>();
+    let mut slot = state.server.lock().map_err(|e| e.to_string())?;
+    if slot.as_ref().is_some_and(|listener| listener.port == port) {
+        return Ok(());
+    }
+    let handler = app.clone();
+    let listener =
+        local_http::Listener::start(port, move |request| handle_request(&handler, request))?;
+    if let Some(previous) = slot.replace(listener) {
+        previous.stop();
+    }
+    println!(
+        "[CrewHub] Webhook listener on http://127.0.0.1:{}/hooks",
+        port
+    );
+    Ok(())
+}
+
+fn stop(state: &WebhookState) -> Result<(), String> {
+    let listener = state.server.lock().map_err(|e| e.to_string())?.take();
+    if let Some(listener) = listener {
+        listener.stop();
+    }
+    Ok(())
+}
+
+fn handle_request<R: Runtime>(app: &AppHandle<R>, mut request: tiny_http::Request) {
+    let token = match app.state::<WebhookState>().config.lock() {
+        Ok(config) => config.token.clone(),
+        Err(_) => return local_http::respond_empty(request, 500),
+    };
+    if !local_http::is_authorized(&request, &token) {
+        return local_http::respond_empty(request, 401);
+    }
+
+    let method = request.method().clone();
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let target = match (method, path.as_str()) {
+        (tiny_http::Method::Get, "/hooks/health") => {
+            return local_http::respond_json(request, 200, &json!({ "ok": true }));
+        }
+        (tiny_http::Method::Post, "/hooks/run") => local_http::read_json_body(&mut request)
+            .and_then(|body| serde_json::from_value::<RunTarget>(body).map_err(|e| e.to_string())),
+        (tiny_http::Method::Post, "/hooks/message") => local_http::read_json_body(&mut request)
+            .and_then(|body| serde_json::from_value::<MessageBody>(body).map_err(|e| e.to_string()))
+            .map(|body| RunTarget::Chat {
+                session_key: body.session_key,
+                message: body.message,
+            }),
+        _ => return local_http::respond_empty(request, 404),
+    };
+
+    match target {
+        Ok(target) => {
+            local_http::respond_json(request, 202, &json!({ "accepted": true }));
+            let app = app.clone();
+            std::thread::spawn(move || forward(&app, target));
+        }
+        Err(e) => local_http::respond_json(request, 400, &json!({ "error": e })),
+    }
+}
+
+fn forward<R: Runtime>(app: &AppHandle<R>, target: RunTarget) {
+    let result = target.run();
+    if let Err(e) = &result {
+        eprintln!("[CrewHub] Webhook run failed: {}", e);
+    }
+    let _ = app.emit(
+        "webhook-triggered",
+        json!({ "target": target, "ok": result.is_ok(), "error": result.err() }),
+    );
+}
+
+fn status(state: &WebhookState) -> Result<WebhookStatus, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let running = state.server.lock().map_err(|e| e.to_string())?.is_some();
+    Ok(WebhookStatus {
+        url: format!("http://127.0.0.1:{}/hooks", config.port),
+        config,
+        running,
+    })
+}
+
+/// Tauri command: webhook listener config, URL, and running state.
+/// Called from the frontend via `invoke('get_webhook_status')`.
+#[tauri::command]
+pub fn get_webhook_status(state: State<WebhookState>) -> Result<WebhookStatus, String> {
+    status(&state)
+}
+
+/// Tauri command: enable/disable the listener and/or change its port.
+/// Called from the frontend via `invoke('configure_webhook', { enabled, port })`.
+#[tauri::command]
+pub fn configure_webhook(
+    enabled: bool,
+    port: Option<u16>,
+    app: AppHandle,
+    state: State<WebhookState>,
+) -> Result<WebhookStatus, String> {
+    let mut config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    config.enabled = enabled;
+    if let Some(port) = port {
+        config.port = port;
+    }
+    // Only saved once the listener runs as configured.
+    if enabled {
+        start(&app, config.port)?;
+    } else {
+        stop(&state)?;
+    }
+    {
+        let mut current = state.config.lock().map_err(|e| e.to_string())?;
+        *current = config;
+        store::save_json(&app, CONFIG_FILE, &*current)?;
+    }
+    status(&state)
+}
+
+/// Tauri command: issue a new token, invalidating the old one immediately.
+/// Called from the frontend via `invoke('regenerate_webhook_token')`.
+#[tauri::command]
+pub fn regenerate_webhook_token(
+    app: AppHandle,
+    state: State<WebhookState>,
+) -> Result<WebhookStatus, String> {
+    {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.token = local_http::new_token();
+        store::save_json(&app, CONFIG_FILE, &*config)?;
+    }
+    status(&state)
+}
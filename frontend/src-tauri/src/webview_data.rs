@@ -0,0 +1,201 @@
+//! Clearing the webviews' HTTP cache and site data.
+//!
+//! `clear_webview_cache` drops cached responses (stale frontend assets after
+//! an update gone wrong); `clear_webview_storage` drops everything the
+//! webviews keep: local storage, IndexedDB, cookies and the cache ("log out
+//! and wipe local data"). All windows share one data store (ephemeral chat
+//! windows excepted), so the data is cleared for all of them; `label` limits
+//! which windows are recreated afterwards.
+//!
+//! Recreating makes the frontend start from the cleared state instead of
+//! writing its in-memory copy back. Main windows are destroyed and reopened
+//! where they were (hidden ones are only destroyed and come back fresh the
+//! next time they open); other windows are reloaded. The frontend gets
+//! `webview-data-cleared` once that is done.
+
+use crate::{ephemeral, layout_profiles};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+
+/// How long to wait for a destroyed window to go away before reopening it.
+const DESTROY_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Cleared {
+    Cache,
+    Storage,
+}
+
+fn clear<R: Runtime>(
+    app: &AppHandle<R>,
+    what: Cleared,
+    label: Option<String>,
+) -> Result<(), String> {
+    let targets: Vec<WebviewWindow<R>> = match &label {
+        Some(label) => vec![app
+            .get_webview_window(label)
+            .ok_or_else(|| format!("No window '{}'", label))?],
+        None => app.webview_windows().into_values().collect(),
+    };
+    let targets: Vec<WebviewWindow<R>> = targets
+        .into_iter()
+        .filter(|window| !ephemeral::is_ephemeral_window(window.label()))
+        .collect();
+    let Some(webview) = targets.first() else {
+        return Err("No window to clear".to_string());
+    };
+    match what {
+        Cleared::Cache => platform::clear_cache(webview)?,
+        Cleared::Storage => webview
+            .clear_all_browsing_data()
+            .map_err(|e| e.to_string())?,
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        for window in targets {
+            if let Err(e) = recreate(&app, window) {
+                eprintln!("[CrewHub] {}", e);
+            }
+        }
+        let _ = app.emit("webview-data-cleared", what);
+    });
+    Ok(())
+}
+
+/// Reopen a main window from scratch where it was, or reload any other window.
+fn recreate<R: Runtime>(app: &AppHandle<R>, window: WebviewWindow<R>) -> Result<(), String> {
+    let label = window.label().to_string();
+    if !layout_profiles::is_managed(&label) {
+        return window.reload().map_err(|e| e.to_string());
+    }
+    let placement = window
+        .is_visible()
+        .unwrap_or(false)
+        .then(|| layout_profiles::placement(&window))
+        .transpose()?;
+    window.destroy().map_err(|e| e.to_string())?;
+    let started = std::time::Instant::now();
+    while app.get_webview_window(&label).is_some() {
+        if started.elapsed() > DESTROY_TIMEOUT {
+            return Err(format!("Window '{}' did not close", label));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    match placement {
+        Some(placement) => layout_profiles::restore(app, &placement),
+        None => Ok(()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use tauri::{Runtime, WebviewWindow};
+    use webkit2gtk::{glib, WebViewExt, WebsiteDataManagerExtManual, WebsiteDataTypes};
+
+    pub fn clear_cache<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+        window
+            .with_webview(|platform| {
+                let Some(manager) = platform.inner().website_data_manager() else {
+                    return;
+                };
+                manager.clear(
+                    WebsiteDataTypes::MEMORY_CACHE | WebsiteDataTypes::DISK_CACHE,
+                    glib::TimeSpan::from_seconds(0),
+                    None::<&webkit2gtk::gio::Cancellable>,
+                    |result| {
+                        if let Err(e) = result {
+                            eprintln!("[CrewHub] Failed to clear the web cache: {}", e);
+                        }
+                    },
+                );
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use tauri::{Runtime, WebviewWindow};
+    use webview2_com::{CallDevToolsProtocolMethodCompletedHandler, CoTaskMemPWSTR};
+
+    /// WebView2 has no cache-only clear before `ClearBrowsingData` (SDK 1.0.1245);
+    /// the DevTools protocol command works on every runtime.
+    pub fn clear_cache<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+        window
+            .with_webview(|platform| unsafe {
+                let Ok(webview) = platform.controller().CoreWebView2() else {
+                    return;
+                };
+                let method = CoTaskMemPWSTR::from("Network.clearBrowserCache");
+                let params = CoTaskMemPWSTR::from("{}");
+                let handler =
+                    CallDevToolsProtocolMethodCompletedHandler::create(Box::new(|result, _| {
+                        if let Err(e) = &result {
+                            eprintln!("[CrewHub] Failed to clear the web cache: {}", e);
+                        }
+                        result
+                    }));
+                if let Err(e) = webview.CallDevToolsProtocolMethod(
+                    *method.as_ref().as_pcwstr(),
+                    *params.as_ref().as_pcwstr(),
+                    &handler,
+                ) {
+                    eprintln!("[CrewHub] Failed to clear the web cache: {}", e);
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use block2::RcBlock;
+    use objc2::MainThreadMarker;
+    use objc2_foundation::{NSDate, NSSet};
+    use objc2_web_kit::{
+        WKWebsiteDataStore, WKWebsiteDataTypeDiskCache, WKWebsiteDataTypeMemoryCache,
+    };
+    use tauri::{Runtime, WebviewWindow};
+
+    pub fn clear_cache<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+        window
+            .with_webview(|_| {
+                let Some(mtm) = MainThreadMarker::new() else {
+                    return;
+                };
+                unsafe {
+                    let store = WKWebsiteDataStore::defaultDataStore(mtm);
+                    let types = NSSet::from_slice(&[
+                        WKWebsiteDataTypeDiskCache,
+                        WKWebsiteDataTypeMemoryCache,
+                    ]);
+                    let handler = RcBlock::new(|| {});
+                    store.removeDataOfTypes_modifiedSince_completionHandler(
+                        &types,
+                        &NSDate::distantPast(),
+                        &handler,
+                    );
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Tauri command: clear the webviews' HTTP cache and recreate the windows
+/// (all, or only `label`).
+/// Called from the settings window via `invoke('clear_webview_cache', { label })`.
+#[tauri::command]
+pub fn clear_webview_cache(label: Option<String>, app: AppHandle) -> Result<(), String> {
+    clear(&app, Cleared::Cache, label)
+}
+
+/// Tauri command: clear all site data (storage, cookies, cache) and recreate
+/// the windows (all, or only `label`).
+/// Called from the settings window via `invoke('clear_webview_storage', { label })`.
+#[tauri::command]
+pub fn clear_webview_storage(label: Option<String>, app: AppHandle) -> Result<(), String> {
+    clear(&app, Cleared::Storage, label)
+}
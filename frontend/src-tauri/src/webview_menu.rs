@@ -0,0 +1,302 @@
+//! Native context menus in webviews.
+//!
+//! The webview's own context menu (Back, Reload, Inspect Element, …) is
+//! replaced by a menu built here: Copy and Select All, plus CrewHub items —
+//! "Ask agent about selection", "Copy link" and "Save attachment as…" for
+//! elements marked `data-crewhub-attachment`. `assets/webview-menu.js`
+//! intercepts `contextmenu` and calls `show_context_menu` with what was
+//! clicked. Editable fields keep the webview's menu, because spelling
+//! suggestions and input method entries only exist there.
+//!
+//! The menu can be turned off, or its items chosen, globally and per window
+//! label in `webview-menu.json`. Item clicks arrive through the tray's menu
+//! handler (ids prefixed [`MENU_ID_PREFIX`]) and act on the request of the
+//! last menu shown.
+
+use crate::{attachments, i18n, store};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::{AppHandle, Emitter, LogicalPosition, Manager, Runtime, WebviewWindow};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::DialogExt;
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "webview-menu.json";
+
+const SCRIPT: &str = include_str!("../assets/webview-menu.js");
+
+/// Prefix of context menu item ids (dispatched from the tray's menu handler).
+pub const MENU_ID_PREFIX: &str = "webview-menu:";
+
+static CONFIG: Mutex<Option<WebviewMenuConfig>> = Mutex::new(None);
+
+/// Window and request of the menu shown last.
+static PENDING: Mutex<Option<(String, ContextRequest)>> = Mutex::new(None);
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContextItem {
+    AskAgent,
+    CopyLink,
+    SaveAttachment,
+}
+
+impl ContextItem {
+    const ALL: [ContextItem; 3] = [Self::AskAgent, Self::CopyLink, Self::SaveAttachment];
+
+    fn id(self) -> &'static str {
+        match self {
+            Self::AskAgent => "ask-agent",
+            Self::CopyLink => "copy-link",
+            Self::SaveAttachment => "save-attachment",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|item| item.id() == id)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WebviewMenuConfig {
+    /// Replace the webview's menu (otherwise it is left alone).
+    pub enabled: bool,
+    pub items: Vec<ContextItem>,
+    /// Overrides by window label.
+    pub windows: BTreeMap<String, WindowWebviewMenu>,
+}
+
+impl Default for WebviewMenuConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            items: ContextItem::ALL.to_vec(),
+            windows: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WindowWebviewMenu {
+    pub enabled: Option<bool>,
+    pub items: Option<Vec<ContextItem>>,
+}
+
+/// What was right-clicked, as reported by the webview.
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextRequest {
+    /// Pointer position in the webview (CSS pixels).
+    x: f64,
+    y: f64,
+    #[serde(default)]
+    selection: String,
+    link: Option<String>,
+    attachment: Option<Attachment>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Attachment {
+    /// http(s) or `file:` URL, or a local path.
+    url: String,
+    name: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AskAgentPayload {
+    text: String,
+}
+
+fn config() -> WebviewMenuConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let loaded: WebviewMenuConfig = store::load_json(app, CONFIG_FILE);
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = Some(loaded);
+    }
+}
+
+fn config_script() -> String {
+    let config = serde_json::to_string(&config()).unwrap_or_else(|_| "null".to_string());
+    format!("window.__CREWHUB_WEBVIEW_MENU__ = {};", config)
+}
+
+/// Init script part: the config (as `window.__CREWHUB_WEBVIEW_MENU__`) and the listener.
+pub fn init_script() -> String {
+    format!("{} {}", config_script(), SCRIPT)
+}
+
+/// Items enabled for a window label.
+fn items_for(label: &str) -> Vec<ContextItem> {
+    let config = config();
+    let window = config.windows.get(label).cloned().unwrap_or_default();
+    if !window.enabled.unwrap_or(config.enabled) {
+        return Vec::new();
+    }
+    window.items.unwrap_or(config.items)
+}
+
+fn build<R: Runtime>(
+    app: &AppHandle<R>,
+    label: &str,
+    request: &ContextRequest,
+) -> tauri::Result<Menu<R>> {
+    let item = |id: &str, key: &str| {
+        MenuItem::with_id(
+            app,
+            format!("{}{}", MENU_ID_PREFIX, id),
+            i18n::t(key),
+            true,
+            None::<&str>,
+        )
+    };
+    let has_selection = !request.selection.trim().is_empty();
+    let menu = Menu::new(app)?;
+    if has_selection {
+        menu.append(&item("copy", "webviewMenu.copy")?)?;
+    }
+    menu.append(&item("select-all", "webviewMenu.selectAll")?)?;
+
+    let mut extra = Vec::new();
+    for entry in items_for(label) {
+        let available = match entry {
+            ContextItem::AskAgent => has_selection,
+            ContextItem::CopyLink => request.link.is_some(),
+            ContextItem::SaveAttachment => request.attachment.is_some(),
+        };
+        if available {
+            let key = match entry {
+                ContextItem::AskAgent => "webviewMenu.askAgent",
+                ContextItem::CopyLink => "webviewMenu.copyLink",
+                ContextItem::SaveAttachment => "webviewMenu.saveAttachment",
+            };
+            extra.push(item(entry.id(), key)?);
+        }
+    }
+    if !extra.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+        for entry in &extra {
+            menu.append(entry)?;
+        }
+    }
+    Ok(menu)
+}
+
+/// Handle a click on a context menu item (`id` without [`MENU_ID_PREFIX`]).
+pub fn handle<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<(), String> {
+    let (label, request) = PENDING
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .ok_or("No context menu is open")?;
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' is gone", label))?;
+    match id {
+        "copy" => app
+            .clipboard()
+            .write_text(request.selection)
+            .map_err(|e| e.to_string()),
+        "select-all" => window
+            .eval("document.execCommand('selectAll')")
+            .map_err(|e| e.to_string()),
+        other => match ContextItem::from_id(other) {
+            Some(ContextItem::AskAgent) => window
+                .emit(
+                    "ask-agent-about-selection",
+                    AskAgentPayload {
+                        text: request.selection,
+                    },
+                )
+                .map_err(|e| e.to_string()),
+            Some(ContextItem::CopyLink) => app
+                .clipboard()
+                .write_text(request.link.unwrap_or_default())
+                .map_err(|e| e.to_string()),
+            Some(ContextItem::SaveAttachment) => {
+                let attachment = request.attachment.ok_or("Nothing to save")?;
+                save_attachment(&window, attachment);
+                Ok(())
+            }
+            None => Err(format!("Unknown context menu item: {}", other)),
+        },
+    }
+}
+
+/// Ask where to save, then download or copy the attachment there.
+fn save_attachment<R: Runtime>(window: &WebviewWindow<R>, attachment: Attachment) {
+    let app = window.app_handle().clone();
+    let name = attachment.name.clone().unwrap_or_else(|| {
+        attachment
+            .url
+            .rsplit(['/', '\\'])
+            .next()
+            .and_then(|name| name.split(['?', '#']).next())
+            .filter(|name| !name.is_empty())
+            .unwrap_or("attachment")
+            .to_string()
+    });
+    app.dialog()
+        .file()
+        .set_parent(window)
+        .set_file_name(name)
+        .save_file(move |target| {
+            let Some(target) = target.and_then(|target| target.into_path().ok()) else {
+                return;
+            };
+            std::thread::spawn(move || {
+                if let Err(e) = attachments::fetch(&app, &attachment.url, &target) {
+                    eprintln!("[CrewHub] Failed to save attachment: {}", e);
+                }
+            });
+        });
+}
+
+/// Tauri command: show the context menu for what was right-clicked.
+/// Called from every window's init script via `invoke('show_context_menu', { request })`.
+#[tauri::command]
+pub fn show_context_menu(
+    app: AppHandle,
+    request: ContextRequest,
+    window: WebviewWindow,
+) -> Result<(), String> {
+    let label = window.label().to_string();
+    let menu = build(&app, &label, &request).map_err(|e| e.to_string())?;
+    let position = LogicalPosition::new(request.x, request.y);
+    *PENDING.lock().map_err(|e| e.to_string())? = Some((label, request));
+    window
+        .popup_menu_at(&menu, position)
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command: current context menu config.
+/// Called from the settings window via `invoke('get_webview_menu_config')`.
+#[tauri::command]
+pub fn get_webview_menu_config() -> WebviewMenuConfig {
+    config()
+}
+
+/// Tauri command: save the context menu config (applies to open windows at once).
+/// Called from the settings window via `invoke('set_webview_menu_config', { config })`.
+#[tauri::command]
+pub fn set_webview_menu_config(app: AppHandle, config: WebviewMenuConfig) -> Result<(), String> {
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    *CONFIG.lock().map_err(|e| e.to_string())? = Some(config);
+    let script = config_script();
+    for window in app.webview_windows().values() {
+        let _ = window.eval(&script);
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! "What's new" window shown once after an update.
+//!
+//! The last version the user has seen is kept in `whats-new.json`. When the
+//! app starts with a different version, the release-notes window opens once
+//! and the new version is recorded. Fresh installs only record the version
+//! (onboarding greets them instead). The window can be reopened from the
+//! tray's Help submenu, the macOS Help menu or `open_whats_new`.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+
+/// Label for the release-notes window.
+pub const WHATS_NEW_WINDOW_LABEL: &str = "whats-new";
+
+/// Tray and menu bar id of the "What's New" item.
+pub const MENU_ID: &str = "whats-new";
+
+/// Persisted last-seen version (app data dir).
+const STATE_FILE: &str = "whats-new.json";
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct WhatsNewState {
+    last_seen_version: Option<String>,
+}
+
+/// Record the running version and open the release notes if it changed since the last launch.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let version = app.package_info().version.to_string();
+    let state: WhatsNewState = store::load_json(app, STATE_FILE);
+    if state.last_seen_version.as_deref() == Some(version.as_str()) {
+        return;
+    }
+    let previous = state.last_seen_version;
+    let state = WhatsNewState {
+        last_seen_version: Some(version),
+    };
+    if let Err(e) = store::save_json(app, STATE_FILE, &state) {
+        eprintln!("[CrewHub] {}", e);
+    }
+    if let Some(previous) = previous {
+        open_or_focus(app, Some(&previous));
+    }
+}
+
+fn whats_new_init_script() -> String {
+    format!(
+        "window.__TAURI_VIEW__ = 'whats-new'; {}",
+        crate::base_init(WHATS_NEW_WINDOW_LABEL)
+    )
+}
+
+/// Open or focus the release-notes window (560×640). `previous` is the
+/// version updated from, so the notes can list every release since then.
+pub fn open_or_focus<R: Runtime>(app: &AppHandle<R>, previous: Option<&str>) {
+    if let Some(window) = app.get_webview_window(WHATS_NEW_WINDOW_LABEL) {
+        crate::show_and_focus(&window);
+        return;
+    }
+
+    let mut query = format!("view=whats-new&version={}", app.package_info().version);
+    if let Some(previous) = previous {
+        query.push_str(&format!(
+            "&from={}",
+            percent_encoding::utf8_percent_encode(previous, percent_encoding::NON_ALPHANUMERIC)
+        ));
+    }
+    let result = WebviewWindowBuilder::new(app, WHATS_NEW_WINDOW_LABEL, crate::view_url(&query))
+        .title("What's New in CrewHub")
+        .inner_size(560.0, 640.0)
+        .min_inner_size(420.0, 400.0)
+        .resizable(true)
+        .fullscreen(false)
+        .decorations(true)
+        .always_on_top(false)
+        .center()
+        .user_agent(&crate::request_headers::user_agent())
+        .on_new_window(crate::external_links::new_window_handler(app))
+        .initialization_script(whats_new_init_script())
+        .build();
+
+    match result {
+        Ok(window) => crate::show_and_focus(&window),
+        Err(e) => eprintln!("[CrewHub] Failed to create what's new window: {}", e),
+    }
+}
+
+/// Tauri command: reopen the release notes for the running version.
+/// Called from the frontend via `invoke('open_whats_new')`.
+#[tauri::command]
+pub fn open_whats_new(app: AppHandle) {
+    open_or_focus(&app, None);
+}
@@ -0,0 +1,201 @@
+//! Desktop widgets: small frameless windows showing crew status.
+//!
+//! Each widget is its own window (`widget-<id>`) rendering one of a few
+//! views — a single agent's status card, today's spend, or a focus timer —
+//! from the same live events the main windows use. Widgets sit below normal
+//! windows like desktop gadgets; their kind and position are remembered in
+//! `widgets.json` and restored on launch.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime, State, WebviewWindowBuilder, WindowEvent};
+
+/// Persisted widget list (app data dir).
+const WIDGETS_FILE: &str = "widgets.json";
+
+/// Window labels are this prefix plus the widget id.
+pub const LABEL_PREFIX: &str = "widget-";
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WidgetKind {
+    /// Status card for one agent (needs `agentId`).
+    AgentStatus,
+    /// Today's spend against the daily budget.
+    TodayCost,
+    /// Focus timer.
+    FocusTimer,
+}
+
+impl WidgetKind {
+    /// Default inner size (logical pixels).
+    fn size(self) -> (f64, f64) {
+        match self {
+            WidgetKind::AgentStatus => (240.0, 110.0),
+            WidgetKind::TodayCost => (200.0, 90.0),
+            WidgetKind::FocusTimer => (200.0, 120.0),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Widget {
+    pub id: String,
+    pub kind: WidgetKind,
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    /// Logical position of the window; `None` until first placed.
+    #[serde(default)]
+    pub position: Option<(f64, f64)>,
+}
+
+/// App state: open widgets in creation order.
+pub struct WidgetState {
+    widgets: Mutex<Vec<Widget>>,
+}
+
+/// Load the widget list, register state, and reopen the widgets from last session.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let widgets: Vec<Widget> = store::load_json(app, WIDGETS_FILE);
+    app.manage(WidgetState {
+        widgets: Mutex::new(widgets.clone()),
+    });
+    for widget in &widgets {
+        if let Err(e) = open(app, widget) {
+            eprintln!("[CrewHub] Failed to restore widget {}: {}", widget.id, e);
+        }
+    }
+}
+
+fn widget_init_script(widget: &Widget, label: &str) -> String {
+    let widget_json = serde_json::to_string(widget).unwrap_or_else(|_| "null".to_string());
+    format!(
+        "window.__TAURI_VIEW__ = 'widget'; window.__CREWHUB_WIDGET__ = {}; {}",
+        widget_json,
+        crate::base_init(label)
+    )
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, widgets: &[Widget]) {
+    if let Err(e) = store::save_json(app, WIDGETS_FILE, &widgets) {
+        eprintln!("[CrewHub] {}", e);
+    }
+}
+
+/// Remember where a widget was dragged to.
+fn moved<R: Runtime>(app: &AppHandle<R>, id: &str, position: (f64, f64)) {
+    let state = app.state::<WidgetState>();
+    let Ok(mut widgets) = state.widgets.lock() else {
+        return;
+    };
+    if let Some(widget) = widgets.iter_mut().find(|w| w.id == id) {
+        widget.position = Some(position);
+        save(app, &widgets);
+    }
+}
+
+/// Drop a widget from the remembered list.
+fn forget<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<(), String> {
+    let state = app.state::<WidgetState>();
+    let mut widgets = state.widgets.lock().map_err(|e| e.to_string())?;
+    let before = widgets.len();
+    widgets.retain(|w| w.id != id);
+    if widgets.len() == before {
+        return Err(format!("Widget not found: {}", id));
+    }
+    save(app, &widgets);
+    Ok(())
+}
+
+/// Forget a widget and close its window.
+fn remove<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<(), String> {
+    forget(app, id)?;
+    if let Some(window) = app.get_webview_window(&format!("{}{}", LABEL_PREFIX, id)) {
+        window.destroy().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Create the window for a widget (frameless, below other windows, not in the taskbar).
+fn open<R: Runtime>(app: &AppHandle<R>, widget: &Widget) -> Result<(), String> {
+    let label = format!("{}{}", LABEL_PREFIX, widget.id);
+    if let Some(window) = app.get_webview_window(&label) {
+        return window.show().map_err(|e| e.to_string());
+    }
+    let (width, height) = widget.kind.size();
+    let mut builder = WebviewWindowBuilder::new(app, &label, crate::view_url("view=widget"))
+        .title("CrewHub Widget")
+        .inner_size(width, height)
+        .resizable(false)
+        .fullscreen(false)
+        .decorations(false)
+        .always_on_bottom(true)
+        .skip_taskbar(true)
+        .focused(false)
+        .user_agent(&crate::request_headers::user_agent())
+        .on_new_window(crate::external_links::new_window_handler(app))
+        .initialization_script(widget_init_script(widget, &label));
+    if let Some((x, y)) = widget.position {
+        builder = builder.position(x, y);
+    }
+    let window = builder.build().map_err(|e| e.to_string())?;
+
+    let (app, id) = (app.clone(), widget.id.clone());
+    let handle = window.clone();
+    window.on_window_event(move |event| match event {
+        WindowEvent::Moved(position) => {
+            let scale = handle.scale_factor().unwrap_or(1.0);
+            let logical = position.to_logical::<f64>(scale);
+            moved(&app, &id, (logical.x, logical.y));
+        }
+        // Closed by the OS (e.g. Alt+F4) rather than from the widget itself.
+        WindowEvent::CloseRequested { .. } => {
+            let _ = forget(&app, &id);
+        }
+        _ => {}
+    });
+    Ok(())
+}
+
+/// Tauri command: widgets currently on the desktop.
+/// Called from the frontend via `invoke('list_widgets')`.
+#[tauri::command]
+pub fn list_widgets(state: State<WidgetState>) -> Result<Vec<Widget>, String> {
+    Ok(state.widgets.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Tauri command: place a new widget on the desktop.
+/// Called from the frontend via `invoke('open_widget', { kind, agentId })`.
+#[tauri::command]
+pub fn open_widget(
+    kind: WidgetKind,
+    agent_id: Option<String>,
+    app: AppHandle,
+    state: State<WidgetState>,
+) -> Result<Widget, String> {
+    if kind == WidgetKind::AgentStatus && agent_id.is_none() {
+        return Err("An agent status widget needs an agentId".to_string());
+    }
+    let widget = Widget {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind,
+        agent_id,
+        position: None,
+    };
+    {
+        let mut widgets = state.widgets.lock().map_err(|e| e.to_string())?;
+        widgets.push(widget.clone());
+        save(&app, &widgets);
+    }
+    open(&app, &widget)?;
+    Ok(widget)
+}
+
+/// Tauri command: remove a widget from the desktop.
+/// Called from the widget via `invoke('close_widget', { id })`.
+#[tauri::command]
+pub fn close_widget(id: String, app: AppHandle) -> Result<(), String> {
+    remove(&app, &id)
+}
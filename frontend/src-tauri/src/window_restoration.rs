@@ -0,0 +1,148 @@
+//! macOS window restoration and Spaces / Stage Manager behavior.
+//!
+//! Every window gets its label as `NSWindow` identifier (so Mission Control,
+//! Stage Manager and accessibility tools can tell them apart) and a
+//! collection behavior for its role:
+//! - main windows (chat, world, zen, settings) are primary: they get their own
+//!   Stage Manager set, and the world window can be tiled full screen next to
+//!   another app;
+//! - panels (HUD, quick prompt, pairing, desktop widgets) are auxiliary: they
+//!   join the current set and full-screen Space instead of forming their own.
+//!
+//! The windows open at quit (and whether the world window was full screen)
+//! are saved to `window-restoration.json` and reopened at launch when the
+//! system setting "Close windows when quitting an application" is off, as
+//! AppKit's own restoration does. AppKit additionally returns restored windows
+//! to their previous Space, which needs restoration classes that recreate the
+//! windows itself; Tauri creates them, so they reopen on the current Space.
+
+use crate::{hud, pairing, quick_prompt, store, widgets};
+use objc2_app_kit::{NSUserInterfaceItemIdentification, NSWindow, NSWindowCollectionBehavior};
+use objc2_foundation::{NSString, NSUserDefaults};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, RunEvent, Runtime, Window};
+
+/// Windows open at the last quit (app data dir).
+const STATE_FILE: &str = "window-restoration.json";
+
+/// Main windows, in the order they are reopened (the last one ends up focused).
+const RESTORED_WINDOWS: [&str; 4] = [
+    crate::SETTINGS_WINDOW_LABEL,
+    crate::ZEN_WINDOW_LABEL,
+    crate::WORLD_WINDOW_LABEL,
+    crate::CHAT_WINDOW_LABEL,
+];
+
+/// Set once the window set was saved, so a later pass (after the windows are gone) keeps it.
+static SAVED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct RestorationState {
+    /// Labels of the main windows that were visible.
+    windows: Vec<String>,
+    world_full_screen: bool,
+}
+
+fn is_panel(label: &str) -> bool {
+    label == hud::HUD_WINDOW_LABEL
+        || label == quick_prompt::QUICK_PROMPT_WINDOW_LABEL
+        || label == pairing::PAIRING_WINDOW_LABEL
+        || label.starts_with(widgets::LABEL_PREFIX)
+}
+
+/// Plugin that sets up every window as it is created and saves the open windows at quit.
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("window-restoration")
+        .on_window_ready(|window| configure(&window))
+        .on_event(|app, event| {
+            if matches!(event, RunEvent::ExitRequested { .. } | RunEvent::Exit) {
+                save(app);
+            }
+        })
+        .build()
+}
+
+fn configure<R: Runtime>(window: &Window<R>) {
+    let Ok(ns_window) = window.ns_window() else {
+        return;
+    };
+    let label = window.label().to_string();
+    let ns_window = ns_window as usize;
+    let _ = window.run_on_main_thread(move || {
+        // SAFETY: Tauri's NSWindow pointer, used on the main thread while the window exists.
+        let ns_window = unsafe { &*(ns_window as *const NSWindow) };
+        ns_window.setIdentifier(Some(&NSString::from_str(&label)));
+        let mut behavior = ns_window.collectionBehavior();
+        if is_panel(&label) {
+            behavior.remove(NSWindowCollectionBehavior::Primary);
+            behavior |= NSWindowCollectionBehavior::Auxiliary
+                | NSWindowCollectionBehavior::FullScreenAuxiliary;
+        } else {
+            behavior |= NSWindowCollectionBehavior::Primary;
+            if label == crate::WORLD_WINDOW_LABEL {
+                behavior |= NSWindowCollectionBehavior::FullScreenPrimary
+                    | NSWindowCollectionBehavior::FullScreenAllowsTiling;
+            }
+        }
+        ns_window.setCollectionBehavior(behavior);
+    });
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>) {
+    if SAVED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let visible = |label: &str| {
+        app.get_webview_window(label)
+            .filter(|window| window.is_visible().unwrap_or(false))
+    };
+    let state = RestorationState {
+        windows: RESTORED_WINDOWS
+            .iter()
+            .filter(|label| visible(label).is_some())
+            .map(|label| label.to_string())
+            .collect(),
+        world_full_screen: visible(crate::WORLD_WINDOW_LABEL)
+            .and_then(|window| window.is_fullscreen().ok())
+            .unwrap_or(false),
+    };
+    if let Err(e) = store::save_json(app, STATE_FILE, &state) {
+        eprintln!("[CrewHub] Failed to save open windows: {}", e);
+    }
+}
+
+/// Whether "Close windows when quitting an application" is off.
+fn keeps_windows() -> bool {
+    NSUserDefaults::standardUserDefaults()
+        .boolForKey(&NSString::from_str("NSQuitAlwaysKeepsWindows"))
+}
+
+/// Reopen the windows that were open at the last quit (if the system setting asks for it).
+pub fn restore<R: Runtime>(app: &AppHandle<R>) {
+    let state: RestorationState = store::load_json(app, STATE_FILE);
+    if !keeps_windows() {
+        return;
+    }
+    for label in RESTORED_WINDOWS {
+        if !state.windows.iter().any(|open| open == label) {
+            continue;
+        }
+        match label {
+            crate::CHAT_WINDOW_LABEL => crate::open_or_focus_chat(app),
+            crate::WORLD_WINDOW_LABEL => {
+                crate::open_or_focus_world(app);
+                if state.world_full_screen {
+                    if let Some(window) = app.get_webview_window(label) {
+                        let _ = window.set_fullscreen(true);
+                    }
+                }
+            }
+            crate::ZEN_WINDOW_LABEL => crate::open_or_focus_zen(app),
+            crate::SETTINGS_WINDOW_LABEL => crate::open_or_focus_settings(app),
+            _ => {}
+        }
+    }
+}
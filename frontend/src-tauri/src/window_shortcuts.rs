@@ -0,0 +1,137 @@
+//! Global shortcuts that toggle CrewHub's main windows.
+//!
+//! Makes the app usable keyboard-first, in particular as a menu-bar-only
+//! accessory on macOS. Each shortcut toggles its window: hidden if it is in
+//! front, shown and focused otherwise. The bindings are registered through
+//! [`crate::hotkeys`] next to quick actions and the switcher, so conflicts
+//! and the Wayland fallbacks are reported the same way. Defaults use
+//! Alt rather than Shift: a global `Cmd+Shift+Z` would take Redo away from
+//! every other app. Bindings are kept in `window-shortcuts.json`; a window
+//! missing there has its default, `null` means unbound.
+
+use crate::{hotkeys, i18n, quick_actions, store};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Persisted config file (app data dir).
+const CONFIG_FILE: &str = "window-shortcuts.json";
+
+/// Prefix of hotkey binding ids (next to quick action ids).
+pub const HOTKEY_PREFIX: &str = "crewhub:window:";
+
+/// Windows with a shortcut: (action, window label, title key, default).
+const WINDOWS: &[(&str, &str, &str, Option<&str>)] = &[
+    (
+        "chat",
+        crate::CHAT_WINDOW_LABEL,
+        "menu.chat",
+        Some("CmdOrCtrl+Alt+C"),
+    ),
+    (
+        "zen",
+        crate::ZEN_WINDOW_LABEL,
+        "menu.zen",
+        Some("CmdOrCtrl+Alt+Z"),
+    ),
+    ("world", crate::WORLD_WINDOW_LABEL, "menu.world", None),
+    (
+        "settings",
+        crate::SETTINGS_WINDOW_LABEL,
+        "switcher.settings",
+        None,
+    ),
+];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowShortcut {
+    action: String,
+    name: String,
+    accelerator: Option<String>,
+    default: Option<String>,
+}
+
+fn configured<R: Runtime>(app: &AppHandle<R>) -> BTreeMap<String, Option<String>> {
+    store::load_json(app, CONFIG_FILE)
+}
+
+/// Every window shortcut with its current binding.
+pub fn all<R: Runtime>(app: &AppHandle<R>) -> Vec<WindowShortcut> {
+    let configured = configured(app);
+    WINDOWS
+        .iter()
+        .map(|(action, _, key, default)| WindowShortcut {
+            action: action.to_string(),
+            name: i18n::t(key),
+            accelerator: match configured.get(*action) {
+                Some(accelerator) => accelerator.clone(),
+                None => default.map(str::to_string),
+            },
+            default: default.map(str::to_string),
+        })
+        .collect()
+}
+
+/// Hotkey bindings: (binding id, name, accelerator).
+pub fn bindings<R: Runtime>(app: &AppHandle<R>) -> Vec<(String, String, Option<String>)> {
+    all(app)
+        .into_iter()
+        .map(|shortcut| {
+            (
+                format!("{}{}", HOTKEY_PREFIX, shortcut.action),
+                shortcut.name,
+                shortcut.accelerator,
+            )
+        })
+        .collect()
+}
+
+/// Hide the window if it is in front, otherwise show and focus it.
+pub fn toggle<R: Runtime>(app: &AppHandle<R>, action: &str) -> Result<(), String> {
+    let (_, label, _, _) = WINDOWS
+        .iter()
+        .find(|(id, ..)| *id == action)
+        .ok_or_else(|| format!("Unknown window: {}", action))?;
+    if let Some(window) = app.get_webview_window(label) {
+        if window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false) {
+            return window.hide().map_err(|e| e.to_string());
+        }
+    }
+    match *label {
+        crate::CHAT_WINDOW_LABEL => crate::open_or_focus_chat(app),
+        crate::ZEN_WINDOW_LABEL => crate::open_or_focus_zen(app),
+        crate::WORLD_WINDOW_LABEL => crate::open_or_focus_world(app),
+        _ => crate::open_or_focus_settings(app),
+    }
+    Ok(())
+}
+
+/// Tauri command: the window shortcuts and their bindings.
+/// Called from the settings window via `invoke('get_shortcuts')`.
+#[tauri::command]
+pub fn get_shortcuts(app: AppHandle) -> Vec<WindowShortcut> {
+    all(&app)
+}
+
+/// Tauri command: bind a window shortcut (`null` unbinds it) and re-register
+/// all hotkeys; the outcome follows as `hotkeys-changed`.
+/// Called from the settings window via `invoke('set_shortcut', { action, accelerator })`.
+#[tauri::command]
+pub fn set_shortcut(
+    action: String,
+    accelerator: Option<String>,
+    app: AppHandle,
+) -> Result<Vec<WindowShortcut>, String> {
+    if !WINDOWS.iter().any(|(id, ..)| *id == action) {
+        return Err(format!("Unknown window: {}", action));
+    }
+    let accelerator = accelerator
+        .map(|accelerator| accelerator.trim().to_string())
+        .filter(|accelerator| !accelerator.is_empty());
+    let mut configured = configured(&app);
+    configured.insert(action, accelerator);
+    store::save_json(&app, CONFIG_FILE, &configured)?;
+    hotkeys::sync(&app, &quick_actions::all(&app));
+    Ok(all(&app))
+}
@@ -0,0 +1,266 @@
+//! Window geometry remembered across launches.
+//!
+//! The chat, world, Zen Mode and settings windows are hidden rather than
+//! closed, but each launch used to create them at their default size,
+//! centered on the primary monitor. Their last size, position, maximized
+//! and full-screen state and monitor are now kept per label in
+//! `window-state.json`, written shortly after the last move or resize (and
+//! on hide and at quit), and applied by the `open_or_focus_*` builders.
+//!
+//! A saved position is only used while the window's title bar would land on
+//! a connected monitor ([`displays::REACHABLE`]), and the size is shrunk to
+//! fit that monitor's work area, so unplugging a display doesn't leave
+//! windows out of reach. Full screen is only restored on the same monitor.
+//! Wayland doesn't report or accept positions, so there only the size and
+//! state are restored. The settings window keeps its fixed size.
+
+use crate::{displays, store, wayland};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::plugin::TauriPlugin;
+use tauri::{
+    AppHandle, LogicalSize, Manager, Monitor, RunEvent, Runtime, WebviewWindowBuilder, Window,
+    WindowEvent,
+};
+
+/// Saved geometry (app data dir).
+const STATE_FILE: &str = "window-state.json";
+
+/// Quiet time after the last move or resize before the file is written.
+const SAVE_DELAY: Duration = Duration::from_millis(750);
+
+/// Tracked windows and their default inner size (`None`: not resizable).
+const TRACKED: [(&str, Option<(f64, f64)>); 4] = [
+    (crate::CHAT_WINDOW_LABEL, Some((390.0, 700.0))),
+    (crate::WORLD_WINDOW_LABEL, Some((1280.0, 900.0))),
+    (crate::ZEN_WINDOW_LABEL, Some((820.0, 920.0))),
+    (crate::SETTINGS_WINDOW_LABEL, None),
+];
+
+static STATE: Mutex<Option<BTreeMap<String, WindowGeometry>>> = Mutex::new(None);
+
+/// When the geometry last changed, while a save is pending.
+static LAST_CHANGE: Mutex<Option<Instant>> = Mutex::new(None);
+static SAVE_PENDING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WindowGeometry {
+    /// Outer position and inner size in logical pixels, as last shown
+    /// normally (zero size: never seen outside maximized or full screen).
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub maximized: bool,
+    pub fullscreen: bool,
+    /// Name of the monitor the window was on.
+    pub monitor: Option<String>,
+}
+
+fn is_tracked(label: &str) -> bool {
+    TRACKED.iter().any(|(tracked, _)| *tracked == label)
+}
+
+fn saved(label: &str) -> Option<WindowGeometry> {
+    STATE.lock().ok()?.as_ref()?.get(label).cloned()
+}
+
+/// Plugin that records the geometry of the tracked windows.
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri::plugin::Builder::new("window-state")
+        .setup(|app, _api| {
+            let loaded: BTreeMap<String, WindowGeometry> = store::load_json(app, STATE_FILE);
+            if let Ok(mut state) = STATE.lock() {
+                *state = Some(loaded);
+            }
+            Ok(())
+        })
+        .on_window_ready(|window| {
+            if !is_tracked(window.label()) {
+                return;
+            }
+            let target = window.clone();
+            window.on_window_event(move |event| {
+                if matches!(
+                    event,
+                    WindowEvent::Moved(_)
+                        | WindowEvent::Resized(_)
+                        | WindowEvent::CloseRequested { .. }
+                ) {
+                    record(&target);
+                    schedule_save(target.app_handle());
+                }
+            });
+        })
+        .on_event(|app, event| {
+            if matches!(event, RunEvent::Exit) && SAVE_PENDING.load(Ordering::Relaxed) {
+                save(app);
+            }
+        })
+        .build()
+}
+
+/// Remember how `window` is shown right now (nothing while it is hidden or minimized).
+fn record<R: Runtime>(window: &Window<R>) {
+    if !window.is_visible().unwrap_or(false) || window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    let fullscreen = window.is_fullscreen().unwrap_or(false);
+    let normal = if maximized || fullscreen {
+        None
+    } else {
+        let scale = window.scale_factor().unwrap_or(1.0);
+        match (window.outer_position(), window.inner_size()) {
+            (Ok(position), Ok(size)) => Some((
+                position.to_logical::<f64>(scale),
+                size.to_logical::<f64>(scale),
+            )),
+            _ => None,
+        }
+    };
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned());
+    let Ok(mut state) = STATE.lock() else {
+        return;
+    };
+    let geometry = state
+        .get_or_insert_with(BTreeMap::new)
+        .entry(window.label().to_string())
+        .or_default();
+    geometry.maximized = maximized;
+    geometry.fullscreen = fullscreen;
+    geometry.monitor = monitor;
+    if let Some((position, size)) = normal {
+        geometry.x = position.x;
+        geometry.y = position.y;
+        geometry.width = size.width;
+        geometry.height = size.height;
+    }
+}
+
+/// Write the state once the windows have been still for [`SAVE_DELAY`].
+fn schedule_save<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(mut last) = LAST_CHANGE.lock() {
+        *last = Some(Instant::now());
+    }
+    if SAVE_PENDING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        loop {
+            let quiet = LAST_CHANGE
+                .lock()
+                .ok()
+                .and_then(|last| *last)
+                .map_or(SAVE_DELAY, |last| last.elapsed());
+            if quiet >= SAVE_DELAY {
+                break;
+            }
+            std::thread::sleep(SAVE_DELAY - quiet);
+        }
+        save(&app);
+    });
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>) {
+    SAVE_PENDING.store(false, Ordering::SeqCst);
+    let state = STATE
+        .lock()
+        .ok()
+        .and_then(|state| state.clone())
+        .unwrap_or_default();
+    if let Err(e) = store::save_json(app, STATE_FILE, &state) {
+        eprintln!("[CrewHub] Failed to save window state: {}", e);
+    }
+}
+
+/// Work area of a monitor in logical pixels: (x, y, width, height).
+fn work_area(monitor: &Monitor) -> (f64, f64, f64, f64) {
+    let scale = monitor.scale_factor();
+    let area = monitor.work_area();
+    let position = area.position.to_logical::<f64>(scale);
+    let size = area.size.to_logical::<f64>(scale);
+    (position.x, position.y, size.width, size.height)
+}
+
+/// Whether enough of the saved title bar lies on `monitor` to grab it.
+fn reachable_on(geometry: &WindowGeometry, monitor: &Monitor) -> bool {
+    let (x, y, width, height) = work_area(monitor);
+    let strip = displays::REACHABLE;
+    let overlap_x = (geometry.x + geometry.width).min(x + width) - geometry.x.max(x);
+    let overlap_y = (geometry.y + strip).min(y + height) - geometry.y.max(y);
+    overlap_x >= strip && overlap_y > 0.0
+}
+
+/// Apply the saved geometry of `label` to the builder of its window.
+pub fn apply<'a, R: Runtime, M: Manager<R>>(
+    app: &AppHandle<R>,
+    label: &str,
+    mut builder: WebviewWindowBuilder<'a, R, M>,
+) -> WebviewWindowBuilder<'a, R, M> {
+    let Some((_, default_size)) = TRACKED.iter().find(|(tracked, _)| *tracked == label) else {
+        return builder;
+    };
+    let Some(geometry) = saved(label) else {
+        return builder;
+    };
+    let monitors = app.available_monitors().unwrap_or_default();
+    let target = monitors
+        .iter()
+        .find(|monitor| reachable_on(&geometry, monitor))
+        .cloned();
+    if geometry.width > 0.0 && geometry.height > 0.0 {
+        if let Some(monitor) = target
+            .as_ref()
+            .or(app.primary_monitor().ok().flatten().as_ref())
+        {
+            if default_size.is_some() {
+                let (_, _, width, height) = work_area(monitor);
+                builder =
+                    builder.inner_size(geometry.width.min(width), geometry.height.min(height));
+            }
+        }
+        if target.is_some() && !wayland::is_wayland() {
+            builder = builder.position(geometry.x, geometry.y);
+        }
+    }
+    let same_monitor = geometry.monitor.is_some()
+        && target.as_ref().and_then(|monitor| monitor.name()) == geometry.monitor.as_ref();
+    builder
+        .maximized(geometry.maximized)
+        .fullscreen(geometry.fullscreen && same_monitor)
+}
+
+/// Tauri command: forget the saved geometry and put open windows back at
+/// their default size, centered.
+/// Called from the settings window via `invoke('reset_window_layout')`.
+#[tauri::command]
+pub fn reset_window_layout(app: AppHandle) -> Result<(), String> {
+    if let Ok(mut state) = STATE.lock() {
+        *state = Some(BTreeMap::new());
+    }
+    store::save_json(&app, STATE_FILE, &BTreeMap::<String, WindowGeometry>::new())?;
+    for (label, default_size) in TRACKED {
+        let Some(window) = app.get_webview_window(label) else {
+            continue;
+        };
+        window.set_fullscreen(false).map_err(|e| e.to_string())?;
+        window.unmaximize().map_err(|e| e.to_string())?;
+        if let Some((width, height)) = default_size {
+            window
+                .set_size(LogicalSize::new(width, height))
+                .map_err(|e| e.to_string())?;
+        }
+        window.center().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
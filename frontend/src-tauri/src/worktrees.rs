@@ -0,0 +1,287 @@
+//! Dedicated git worktrees per agent task.
+//!
+//! Agent-generated code changes land in an isolated branch
+//! (`crewhub/<agent>/<task>`) checked out under the app data dir, so the user's
+//! own checkout is never touched and every task can be reviewed on its own.
+//! Changes to the set of worktrees are pushed to the world window as
+//! `worktrees-changed` events.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Persisted registry of worktrees we created (app data dir).
+const REGISTRY_FILE: &str = "worktrees.json";
+
+/// Directory (inside app data) holding all agent worktrees.
+const WORKTREES_DIR: &str = "worktrees";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentWorktree {
+    pub id: String,
+    pub repo_path: String,
+    pub agent_id: String,
+    pub task_id: String,
+    pub branch: String,
+    pub base_ref: String,
+    pub path: String,
+    /// Unix seconds.
+    pub created_at: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeStatus {
+    #[serde(flatten)]
+    worktree: AgentWorktree,
+    exists: bool,
+    /// Uncommitted changed files.
+    changed_files: usize,
+    /// Commits on the task branch that are not on the base.
+    commits_ahead: usize,
+}
+
+/// App state: registry of agent worktrees.
+pub struct WorktreeState {
+    worktrees: Mutex<Vec<AgentWorktree>>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let worktrees: Vec<AgentWorktree> = store::load_json(app, REGISTRY_FILE);
+    app.manage(WorktreeState {
+        worktrees: Mutex::new(worktrees),
+    });
+}
+
+/// Look up a registered worktree by id.
+pub fn find<R: Runtime>(app: &AppHandle<R>, id: &str) -> Option<AgentWorktree> {
+    let state = app.try_state::<WorktreeState>()?;
+    let worktrees = state.worktrees.lock().ok()?;
+    worktrees.iter().find(|w| w.id == id).cloned()
+}
+
+/// Run `git` in `dir` and return trimmed stdout, or stderr as the error.
+pub fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Lowercase, `[a-z0-9-]` only — safe for branch names and directory names.
+fn slug(raw: &str) -> String {
+    let slug: String = raw
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    slug.split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn status(worktree: &AgentWorktree) -> WorktreeStatus {
+    let path = PathBuf::from(&worktree.path);
+    let exists = path.exists();
+    let changed_files = if exists {
+        git(&path, &["status", "--porcelain"])
+            .map(|out| out.lines().filter(|l| !l.is_empty()).count())
+            .unwrap_or(0)
+    } else {
+        0
+    };
+    let commits_ahead = git(
+        Path::new(&worktree.repo_path),
+        &[
+            "rev-list",
+            "--count",
+            &format!("{}..{}", worktree.base_ref, worktree.branch),
+        ],
+    )
+    .ok()
+    .and_then(|n| n.parse().ok())
+    .unwrap_or(0);
+    WorktreeStatus {
+        worktree: worktree.clone(),
+        exists,
+        changed_files,
+        commits_ahead,
+    }
+}
+
+fn save_and_notify(
+    app: &AppHandle,
+    worktrees: &[AgentWorktree],
+) -> Result<Vec<WorktreeStatus>, String> {
+    store::save_json(app, REGISTRY_FILE, &worktrees)?;
+    let statuses: Vec<WorktreeStatus> = worktrees.iter().map(status).collect();
+    let _ = app.emit_to(crate::WORLD_WINDOW_LABEL, "worktrees-changed", &statuses);
+    Ok(statuses)
+}
+
+/// Tauri command: list agent worktrees with their git status.
+/// Called from the frontend via `invoke('list_agent_worktrees')`.
+#[tauri::command]
+pub async fn list_agent_worktrees(app: AppHandle) -> Result<Vec<WorktreeStatus>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<WorktreeState>();
+        let worktrees = state.worktrees.lock().map_err(|e| e.to_string())?;
+        Ok(worktrees.iter().map(status).collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: create a worktree + branch for an agent task in a linked repo.
+/// Called from the frontend via `invoke('create_agent_worktree', { repoPath, agentId, taskId, baseRef })`.
+#[tauri::command]
+pub async fn create_agent_worktree(
+    repo_path: String,
+    agent_id: String,
+    task_id: String,
+    base_ref: Option<String>,
+    app: AppHandle,
+) -> Result<WorktreeStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = PathBuf::from(
+            git(Path::new(&repo_path), &["rev-parse", "--show-toplevel"])
+                .map_err(|e| format!("Not a git repository: {}", e))?,
+        );
+        let base_ref = match base_ref {
+            Some(base) => base,
+            None => git(&repo, &["rev-parse", "--abbrev-ref", "HEAD"])?,
+        };
+        // Later passed to git as a plain argument (`worktree add`, `rev-list`,
+        // `diff`), so it must name a commit and can't be read as an option.
+        if base_ref.starts_with('-')
+            || git(
+                &repo,
+                &[
+                    "rev-parse",
+                    "--verify",
+                    "--quiet",
+                    "--end-of-options",
+                    &format!("{}^{{commit}}", base_ref),
+                ],
+            )
+            .is_err()
+        {
+            return Err(format!("Not a commit: {}", base_ref));
+        }
+        let (agent, task) = (slug(&agent_id), slug(&task_id));
+        if agent.is_empty() || task.is_empty() {
+            return Err("Agent and task ids must contain letters or digits".to_string());
+        }
+        let branch = format!("crewhub/{}/{}", agent, task);
+        let repo_name = repo
+            .file_name()
+            .map(|n| slug(&n.to_string_lossy()))
+            .unwrap_or_default();
+        let path = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join(WORKTREES_DIR)
+            .join(format!("{}-{}-{}", repo_name, agent, task));
+        let path_str = path.display().to_string();
+
+        git(
+            &repo,
+            &["worktree", "add", "-b", &branch, &path_str, &base_ref],
+        )?;
+
+        let worktree = AgentWorktree {
+            id: uuid::Uuid::new_v4().to_string(),
+            repo_path: repo.display().to_string(),
+            agent_id,
+            task_id,
+            branch,
+            base_ref,
+            path: path_str,
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        let state = app.state::<WorktreeState>();
+        let mut worktrees = state.worktrees.lock().map_err(|e| e.to_string())?;
+        worktrees.push(worktree.clone());
+        save_and_notify(&app, &worktrees)?;
+        Ok(status(&worktree))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: remove an agent worktree, optionally deleting its branch.
+/// `force` discards uncommitted changes in the worktree.
+/// Called from the frontend via `invoke('remove_agent_worktree', { id, deleteBranch, force })`.
+#[tauri::command]
+pub async fn remove_agent_worktree(
+    id: String,
+    delete_branch: bool,
+    force: bool,
+    app: AppHandle,
+) -> Result<Vec<WorktreeStatus>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<WorktreeState>();
+        let mut worktrees = state.worktrees.lock().map_err(|e| e.to_string())?;
+        let worktree = worktrees
+            .iter()
+            .find(|w| w.id == id)
+            .cloned()
+            .ok_or_else(|| format!("Worktree not found: {}", id))?;
+        let repo = PathBuf::from(&worktree.repo_path);
+
+        if Path::new(&worktree.path).exists() {
+            let mut args = vec!["worktree", "remove"];
+            if force {
+                args.push("--force");
+            }
+            args.push(&worktree.path);
+            git(&repo, &args)?;
+        }
+        if delete_branch {
+            git(
+                &repo,
+                &["branch", if force { "-D" } else { "-d" }, &worktree.branch],
+            )?;
+        }
+
+        worktrees.retain(|w| w.id != id);
+        save_and_notify(&app, &worktrees)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Tauri command: forget worktrees whose directory disappeared and prune git metadata.
+/// Called from the frontend via `invoke('cleanup_agent_worktrees')`.
+#[tauri::command]
+pub async fn cleanup_agent_worktrees(app: AppHandle) -> Result<Vec<WorktreeStatus>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<WorktreeState>();
+        let mut worktrees = state.worktrees.lock().map_err(|e| e.to_string())?;
+        let mut repos: Vec<String> = worktrees.iter().map(|w| w.repo_path.clone()).collect();
+        repos.sort();
+        repos.dedup();
+        for repo in repos {
+            if let Err(e) = git(Path::new(&repo), &["worktree", "prune"]) {
+                eprintln!("[CrewHub] git worktree prune failed in {}: {}", repo, e);
+            }
+        }
+        worktrees.retain(|w| Path::new(&w.path).exists());
+        save_and_notify(&app, &worktrees)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,391 @@
+//! Quality tier of the 3D world, picked from the hardware.
+//!
+//! At startup the GPU, CPU count and installed memory are detected off the
+//! main thread and mapped to a tier (`low`, `medium`, `high`), which the
+//! world window gets in its init script (`window.__CREWHUB_WORLD_QUALITY__`)
+//! and uses as its default render quality: shadows, post-processing, pixel
+//! ratio and draw distance. The settings window can pin a tier instead;
+//! changes reach an open world window with a `world-quality-changed` event.
+//!
+//! Detection is deliberately coarse:
+//! - Linux: PCI vendors of `/sys/class/drm/card*`, AMD parts with little
+//!   dedicated VRAM count as integrated;
+//! - Windows: the names of the display adapters;
+//! - macOS: Apple silicon, or the chipset names from `system_profiler`.
+
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Persisted config file (app data dir). Machine-specific, so not part of
+/// config templates.
+const CONFIG_FILE: &str = "world-quality.json";
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+static CONFIG: Mutex<Option<WorldQualityConfig>> = Mutex::new(None);
+static HARDWARE: OnceLock<Hardware> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tier {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WorldQualityConfig {
+    /// Tier to use instead of the detected one.
+    pub r#override: Option<Tier>,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GpuClass {
+    Discrete,
+    Integrated,
+    /// Apple silicon.
+    Apple,
+    /// Software rasterizer or virtual machine adapter.
+    Software,
+    Unknown,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hardware {
+    pub cpus: usize,
+    /// Installed memory in bytes.
+    pub memory_bytes: Option<u64>,
+    pub gpu: Option<String>,
+    pub gpu_class: GpuClass,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldQualityStatus {
+    /// Tier the world renders at.
+    tier: Tier,
+    detected: Tier,
+    r#override: Option<Tier>,
+    hardware: Hardware,
+}
+
+fn config() -> WorldQualityConfig {
+    CONFIG
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default()
+}
+
+/// Class of a GPU from its marketing name.
+fn classify(name: &str) -> GpuClass {
+    let name = name.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|needle| name.contains(needle));
+    if has(&[
+        "llvmpipe",
+        "softpipe",
+        "swiftshader",
+        "basic render",
+        "virtio",
+        "vmware",
+        "virtualbox",
+    ]) {
+        GpuClass::Software
+    } else if has(&[
+        "nvidia",
+        "geforce",
+        "quadro",
+        "radeon rx",
+        "radeon pro",
+        "intel arc",
+        "arc a",
+    ]) {
+        GpuClass::Discrete
+    } else if name.contains("apple") {
+        GpuClass::Apple
+    } else if has(&["intel", "radeon", "amd", "adreno", "mali"]) {
+        GpuClass::Integrated
+    } else {
+        GpuClass::Unknown
+    }
+}
+
+/// The most capable of several adapters (laptops with switchable graphics).
+fn best(gpus: impl IntoIterator<Item = (String, GpuClass)>) -> (Option<String>, GpuClass) {
+    let rank = |class: GpuClass| match class {
+        GpuClass::Discrete => 4,
+        GpuClass::Apple => 3,
+        GpuClass::Integrated => 2,
+        GpuClass::Unknown => 1,
+        GpuClass::Software => 0,
+    };
+    gpus.into_iter()
+        .max_by_key(|(_, class)| rank(*class))
+        .map_or((None, GpuClass::Unknown), |(name, class)| {
+            (Some(name), class)
+        })
+}
+
+fn detect() -> Hardware {
+    let (gpu, gpu_class) = best(platform::gpus());
+    Hardware {
+        cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        memory_bytes: platform::memory_bytes(),
+        gpu,
+        gpu_class,
+    }
+}
+
+/// Detected hardware; detection runs once (on a background thread from [`init`]).
+pub fn hardware() -> &'static Hardware {
+    HARDWARE.get_or_init(detect)
+}
+
+/// Tier for `hardware`: the GPU sets the ceiling, little memory or few cores
+/// pull it down. Unknown GPUs get the benefit of the doubt on strong machines.
+fn tier_for(hardware: &Hardware) -> Tier {
+    let memory = hardware.memory_bytes.unwrap_or(8 * GIB);
+    if hardware.gpu_class == GpuClass::Software || memory < 6 * GIB || hardware.cpus <= 2 {
+        return Tier::Low;
+    }
+    let strong = memory >= 12 * GIB && hardware.cpus >= 8;
+    match hardware.gpu_class {
+        GpuClass::Discrete | GpuClass::Apple if memory >= 8 * GIB => Tier::High,
+        GpuClass::Discrete | GpuClass::Apple => Tier::Medium,
+        GpuClass::Unknown if strong => Tier::High,
+        GpuClass::Integrated | GpuClass::Unknown if memory >= 8 * GIB && hardware.cpus >= 4 => {
+            Tier::Medium
+        }
+        _ => Tier::Low,
+    }
+}
+
+fn status() -> WorldQualityStatus {
+    let hardware = hardware().clone();
+    let detected = tier_for(&hardware);
+    let r#override = config().r#override;
+    WorldQualityStatus {
+        tier: r#override.unwrap_or(detected),
+        detected,
+        r#override,
+        hardware,
+    }
+}
+
+fn quality_script(status: &WorldQualityStatus) -> String {
+    let quality = serde_json::json!({
+        "tier": status.tier,
+        "detected": status.detected,
+        "override": status.r#override,
+    });
+    format!("window.__CREWHUB_WORLD_QUALITY__ = {};", quality)
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let loaded: WorldQualityConfig = store::load_json(app, CONFIG_FILE);
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = Some(loaded);
+    }
+    std::thread::spawn(|| {
+        let hardware = hardware();
+        println!(
+            "[CrewHub] World quality: {:?} ({} CPUs, {} GiB, GPU {})",
+            tier_for(hardware),
+            hardware.cpus,
+            hardware.memory_bytes.map_or(0, |bytes| bytes / GIB),
+            hardware.gpu.as_deref().unwrap_or("unknown")
+        );
+    });
+}
+
+/// Init script part of the world window.
+pub fn init_script() -> String {
+    quality_script(&status())
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{classify, GpuClass, GIB};
+    use std::fs;
+
+    pub fn gpus() -> Vec<(String, GpuClass)> {
+        let Ok(entries) = fs::read_dir("/sys/class/drm") else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                name.starts_with("card") && !name.contains('-')
+            })
+            .filter_map(|entry| {
+                let device = entry.path().join("device");
+                let read = |file: &str| fs::read_to_string(device.join(file)).ok();
+                let vendor = read("vendor")?;
+                let id = read("device").unwrap_or_default();
+                let (name, class) = match vendor.trim() {
+                    "0x10de" => ("NVIDIA", GpuClass::Discrete),
+                    "0x1002" => {
+                        let vram: u64 = read("mem_info_vram_total")
+                            .and_then(|vram| vram.trim().parse().ok())
+                            .unwrap_or(0);
+                        let class = if vram >= 2 * GIB {
+                            GpuClass::Discrete
+                        } else {
+                            GpuClass::Integrated
+                        };
+                        ("AMD", class)
+                    }
+                    "0x8086" => ("Intel", GpuClass::Integrated),
+                    vendor => (vendor, classify(vendor)),
+                };
+                let class = match class {
+                    GpuClass::Unknown
+                        if ["0x1af4", "0x15ad", "0x80ee", "0x1234"].contains(&name) =>
+                    {
+                        GpuClass::Software
+                    }
+                    class => class,
+                };
+                Some((format!("{} ({})", name, id.trim()), class))
+            })
+            .collect()
+    }
+
+    pub fn memory_bytes() -> Option<u64> {
+        let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+        let line = meminfo.lines().find(|line| line.starts_with("MemTotal:"))?;
+        let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kb * 1024)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{classify, GpuClass};
+    use windows::core::PCWSTR;
+    use windows::Win32::Graphics::Gdi::{EnumDisplayDevicesW, DISPLAY_DEVICEW};
+    use windows::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    pub fn gpus() -> Vec<(String, GpuClass)> {
+        let mut gpus: Vec<(String, GpuClass)> = Vec::new();
+        for index in 0.. {
+            let mut device = DISPLAY_DEVICEW {
+                cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32,
+                ..Default::default()
+            };
+            // SAFETY: `device` is sized by `cb`; a null device enumerates adapters.
+            if !unsafe { EnumDisplayDevicesW(PCWSTR::null(), index, &mut device, 0) }.as_bool() {
+                break;
+            }
+            let len = device
+                .DeviceString
+                .iter()
+                .position(|&c| c == 0)
+                .unwrap_or(device.DeviceString.len());
+            let name = String::from_utf16_lossy(&device.DeviceString[..len]);
+            // Every output of an adapter is listed separately.
+            if !name.is_empty() && !gpus.iter().any(|(known, _)| *known == name) {
+                let class = classify(&name);
+                gpus.push((name, class));
+            }
+        }
+        gpus
+    }
+
+    pub fn memory_bytes() -> Option<u64> {
+        let mut status = MEMORYSTATUSEX {
+            dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32,
+            ..Default::default()
+        };
+        // SAFETY: `status` is sized by `dwLength`.
+        unsafe { GlobalMemoryStatusEx(&mut status) }.ok()?;
+        Some(status.ullTotalPhys)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{classify, GpuClass};
+    use std::process::Command;
+
+    fn output(program: &str, args: &[&str]) -> Option<String> {
+        let output = Command::new(program).args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    pub fn gpus() -> Vec<(String, GpuClass)> {
+        if cfg!(target_arch = "aarch64") {
+            let chip = output("sysctl", &["-n", "machdep.cpu.brand_string"])
+                .map(|brand| brand.trim().to_string())
+                .unwrap_or_else(|| "Apple silicon".to_string());
+            return vec![(chip, GpuClass::Apple)];
+        }
+        output("system_profiler", &["SPDisplaysDataType"])
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("Chipset Model:"))
+            .map(|name| {
+                let name = name.trim().to_string();
+                let class = classify(&name);
+                (name, class)
+            })
+            .collect()
+    }
+
+    pub fn memory_bytes() -> Option<u64> {
+        output("sysctl", &["-n", "hw.memsize"])?.trim().parse().ok()
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::GpuClass;
+
+    pub fn gpus() -> Vec<(String, GpuClass)> {
+        Vec::new()
+    }
+
+    pub fn memory_bytes() -> Option<u64> {
+        None
+    }
+}
+
+/// Tauri command: the world's quality tier, how it was picked and the detected hardware.
+/// Called from the settings window via `invoke('get_world_quality')`.
+#[tauri::command]
+pub async fn get_world_quality() -> Result<WorldQualityStatus, String> {
+    tauri::async_runtime::spawn_blocking(status)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command: pin the world's quality tier (`null` goes back to the detected one).
+/// Called from the settings window via `invoke('set_world_quality_override', { tier })`.
+#[tauri::command]
+pub async fn set_world_quality_override(
+    tier: Option<Tier>,
+    app: AppHandle,
+) -> Result<WorldQualityStatus, String> {
+    let config = WorldQualityConfig { r#override: tier };
+    store::save_json(&app, CONFIG_FILE, &config)?;
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config);
+    }
+    let status = tauri::async_runtime::spawn_blocking(status)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(window) = app.get_webview_window(crate::WORLD_WINDOW_LABEL) {
+        let _ = window.eval(quality_script(&status));
+        let _ = app.emit_to(window.label(), "world-quality-changed", &status);
+    }
+    Ok(status)
+}
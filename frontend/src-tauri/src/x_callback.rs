@@ -0,0 +1,165 @@
+//! x-callback-url automation (Apple Shortcuts, launchers, scripts).
+//!
+//! `crewhub://x-callback-url/<action>?<args>&x-success=…&x-error=…&x-cancel=…`
+//! performs a control action (see [`crate::control::perform`]), e.g.
+//! `send-prompt?sessionKey=…&message=…`, `toggle-focus` or `get-state`, then
+//! opens the matching callback URL. `x-success` gets the action's result as
+//! query parameters (`get-state` → `unread`, `agentsWorking`, …), `x-error`
+//! gets `errorMessage`.
+//!
+//! Shortcuts runs these with "Open X-Callback URL". Native App Intents would
+//! need a Swift app extension, which this build doesn't produce.
+//!
+//! Any web page can open a `crewhub://` link, so only plain navigation
+//! ([`NAVIGATION_ACTIONS`]) runs without asking, and without opening any
+//! callback. Everything else needs the user's OK each time, and only a
+//! confirmed call reports back to its callbacks. The `x-source` name is the
+//! caller's own claim and is shown as unverified.
+
+use crate::{control, external_links, i18n};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+use url::Url;
+
+/// Host of x-callback-url links.
+pub const HOST: &str = "x-callback-url";
+
+/// Actions that only bring up a window or conversation; they run unasked.
+const NAVIGATION_ACTIONS: &[&str] = &[
+    "open-chat",
+    "open-world",
+    "open-conversation",
+    "open-agent",
+    "open-room",
+];
+
+// Dialog buttons (i18n keys).
+const RUN: &str = "dialog.run";
+const CANCEL: &str = "dialog.cancel";
+
+/// App state: a lock so only one dialog is shown at a time.
+pub struct XCallbackState {
+    dialog: Mutex<()>,
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(XCallbackState {
+        dialog: Mutex::new(()),
+    });
+}
+
+/// Perform an x-callback-url link off the calling thread.
+pub fn handle<R: Runtime>(app: &AppHandle<R>, url: &Url) {
+    let app = app.clone();
+    let url = url.clone();
+    std::thread::spawn(move || run(&app, &url));
+}
+
+fn run<R: Runtime>(app: &AppHandle<R>, url: &Url) {
+    let action = url.path().trim_start_matches('/').to_string();
+    let mut args = Map::new();
+    let mut callbacks = HashMap::new();
+    let mut source = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "x-success" | "x-error" | "x-cancel" => {
+                callbacks.insert(key.to_string(), value.to_string());
+            }
+            "x-source" => source = Some(value.to_string()),
+            _ => {
+                args.insert(key.to_string(), Value::String(value.to_string()));
+            }
+        }
+    }
+    let args = Value::Object(args);
+
+    if NAVIGATION_ACTIONS.contains(&action.as_str()) {
+        if let Err(e) = control::perform(app, &action, &args) {
+            eprintln!("[CrewHub] x-callback '{}' failed: {}", action, e);
+        }
+        return;
+    }
+    // Unconfirmed calls get no callback, so a page learns nothing from them.
+    if !confirm(app, &action, &args, source.as_deref()) {
+        return;
+    }
+    let (callback, params) = match control::perform(app, &action, &args) {
+        Ok(Value::Object(result)) => ("x-success", result),
+        Ok(_) => ("x-success", Map::new()),
+        Err(e) => {
+            eprintln!("[CrewHub] x-callback '{}' failed: {}", action, e);
+            let mut params = Map::new();
+            params.insert("errorMessage".to_string(), Value::String(e));
+            ("x-error", params)
+        }
+    };
+    let Some(target) = callbacks.get(callback) else {
+        return;
+    };
+    match Url::parse(target) {
+        Ok(mut target) => {
+            for (key, value) in &params {
+                let value = match value {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                target.query_pairs_mut().append_pair(key, &value);
+            }
+            if let Err(e) = external_links::open_in_browser(&target) {
+                eprintln!("[CrewHub] {}", e);
+            }
+        }
+        Err(e) => eprintln!("[CrewHub] Invalid {} URL: {}", callback, e),
+    }
+}
+
+/// Ask before running an action. Blocks.
+fn confirm<R: Runtime>(
+    app: &AppHandle<R>,
+    action: &str,
+    args: &Value,
+    source: Option<&str>,
+) -> bool {
+    let state = app.state::<XCallbackState>();
+    let _one_at_a_time = state.dialog.lock();
+    let details = args
+        .as_object()
+        .map(|args| {
+            args.iter()
+                .map(|(key, value)| format!("{}: {}", key, value.as_str().unwrap_or_default()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default();
+    let source = match source {
+        Some(source) => i18n::tf("dialog.unverifiedSource", &[("source", source)]),
+        None => i18n::t("dialog.anotherApp"),
+    };
+    let result = app
+        .dialog()
+        .message(i18n::tf(
+            "dialog.automation",
+            &[
+                ("source", &source),
+                ("action", action),
+                ("details", &details),
+            ],
+        ))
+        .title(i18n::t("dialog.automationTitle"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            i18n::t(RUN),
+            i18n::t(CANCEL),
+        ))
+        .blocking_show_with_result();
+    match result {
+        MessageDialogResult::Custom(label) => label == i18n::t(RUN),
+        MessageDialogResult::Ok | MessageDialogResult::Yes => true,
+        _ => false,
+    }
+}